@@ -0,0 +1,109 @@
+use core::fmt::{Display, Formatter};
+
+/// A parsed Game Boy cheat code.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum CheatCode {
+    /// GameShark code (`TTVVLLHH`), which writes a value to RAM every frame.
+    GameShark {
+        /// Type of code, the low nibble of which is the RAM bank to write to (unless this is `01`,
+        /// which writes to whichever bank is selected)
+        code_type: u8,
+
+        /// Address to write to
+        address: u16,
+
+        /// Value to write
+        value: u8
+    },
+
+    /// Game Genie code (`VVA-AAA` or `VVA-AAA-CCC`), which replaces a value in ROM.
+    GameGenie {
+        /// Address to replace, which is always in ROM
+        address: u16,
+
+        /// Value to replace it with
+        value: u8,
+
+        /// If set, only replace the value in banks where it is currently this
+        compare: Option<u8>
+    }
+}
+
+/// Reason a cheat code can't be parsed.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum CheatParseError {
+    /// The code is not formatted like a GameShark or Game Genie code.
+    InvalidFormat,
+
+    /// The code is a Game Genie code for an address outside of ROM.
+    InvalidAddress
+}
+
+impl Display for CheatParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::InvalidFormat => f.write_str("not a valid GameShark or Game Genie code"),
+            Self::InvalidAddress => f.write_str("Game Genie code is not for a ROM address")
+        }
+    }
+}
+
+impl CheatCode {
+    /// Parse a GameShark or Game Genie code.
+    ///
+    /// Codes are case-insensitive, and surrounding whitespace is ignored. Like Game Genie codes,
+    /// GameShark codes are told apart by length, so a GameShark code can't contain dashes.
+    pub fn parse(code: &str) -> Result<Self, CheatParseError> {
+        let code = code.trim();
+        if code.len() == 8 {
+            let code = u32::from_str_radix(code, 16).map_err(|_| CheatParseError::InvalidFormat)?;
+            let [code_type, value, address_low, address_high] = code.to_be_bytes();
+            return Ok(Self::GameShark { code_type, address: u16::from_le_bytes([address_low, address_high]), value })
+        }
+
+        // Game Genie codes are grouped in threes by dashes, but the dashes can be left out
+        let dashed = code.contains('-');
+        let mut digits = [0u8; 9];
+        let mut length = 0;
+        for group in code.split('-') {
+            if dashed && group.len() != 3 {
+                return Err(CheatParseError::InvalidFormat)
+            }
+            for digit in group.chars() {
+                if length == digits.len() {
+                    return Err(CheatParseError::InvalidFormat)
+                }
+                digits[length] = digit.to_digit(16).ok_or(CheatParseError::InvalidFormat)? as u8;
+                length += 1;
+            }
+        }
+
+        if length != 6 && length != 9 {
+            return Err(CheatParseError::InvalidFormat)
+        }
+
+        let value = digits[0] << 4 | digits[1];
+        let address = u16::from_be_bytes([digits[2] << 4 | digits[3], digits[4] << 4 | digits[5]]).rotate_right(4) ^ 0xF000;
+        if address > 0x7FFF {
+            return Err(CheatParseError::InvalidAddress)
+        }
+
+        // The eighth digit is only a checksum
+        let compare = (length == 9).then(|| (digits[6] << 4 | digits[8]).rotate_right(2) ^ 0xBA);
+        Ok(Self::GameGenie { address, value, compare })
+    }
+}
+
+/// A single write made by a cheat (see [`EmulatorCore::resolve_cheat`](crate::emulator::EmulatorCore::resolve_cheat)).
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct CheatPatch {
+    /// Address to write to, interpreted like [`EmulatorCore::write_ram`](crate::emulator::EmulatorCore::write_ram)
+    pub address: u32,
+
+    /// Value to write
+    pub value: u8,
+
+    /// Value to write back when the cheat is removed, if the game would never overwrite it itself
+    /// (e.g. ROM)
+    pub restore: Option<u8>
+}
@@ -0,0 +1,259 @@
+use crate::emulator::EmulatorCore;
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+use core::num::NonZeroU64;
+use supershuckie_replay_recorder::replay_file::record::{ReplayFileRecorderFns, ReplayFileWriteError};
+use supershuckie_replay_recorder::replay_file::{blake3_hash_to_ascii, ReplayHeaderBlake3Hash};
+use supershuckie_replay_recorder::{blake3_hash, ByteVec, InputBuffer, MemoryWrite, Packet, Speed, TimestampMillis, UnsignedInteger};
+
+/// Defines how replays are checked for determinism while recording (see
+/// [`SuperShuckieCore::set_determinism_check`](crate::SuperShuckieCore::set_determinism_check)).
+pub struct DeterminismCheckSettings {
+    /// Core used to re-simulate recorded frames.
+    ///
+    /// This must be loaded with the same ROM, BIOS, and core config as the live core, or every check
+    /// will fail.
+    pub shadow_core: Box<dyn EmulatorCore>,
+
+    /// Number of keyframes per check.
+    ///
+    /// Only one keyframe interval out of this many is re-simulated, since each check runs all of the
+    /// interval's frames again.
+    pub keyframes_per_check: NonZeroU64
+}
+
+/// Describes a keyframe interval that did not play back the same as it was recorded.
+#[derive(Clone, PartialEq, Debug)]
+pub struct DeterminismMismatch {
+    /// Frame of the keyframe the shadow core started from
+    pub start_frame: u64,
+
+    /// Frame of the keyframe the shadow core was compared against
+    pub end_frame: u64,
+
+    /// Hash of the live core's state
+    pub expected_hash: ReplayHeaderBlake3Hash,
+
+    /// Hash of the shadow core's state
+    pub actual_hash: ReplayHeaderBlake3Hash
+}
+
+impl Display for DeterminismMismatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "Replaying frames {} to {} did not reproduce the recorded state (expected {}, got {})",
+            self.start_frame,
+            self.end_frame,
+            blake3_hash_to_ascii(self.expected_hash),
+            blake3_hash_to_ascii(self.actual_hash)
+        )
+    }
+}
+
+/// Everything passed to the recorder since a keyframe.
+struct CheckedInterval {
+    state: Vec<u8>,
+    input: InputBuffer,
+    frame: u64,
+    packets: Vec<Packet>
+}
+
+/// Recorder wrapper that re-simulates keyframe intervals on a shadow core.
+///
+/// Since this sits in front of the actual recorder, it checks what the core asked to be recorded,
+/// not how it was encoded. Anything the recorder rejected is left out, so write errors are caught
+/// as well.
+pub(crate) struct DeterminismCheckRecorder {
+    recorder: Box<dyn ReplayFileRecorderFns>,
+    settings: DeterminismCheckSettings,
+
+    /// Input last passed to the recorder.
+    input: InputBuffer,
+
+    /// Frames recorded so far.
+    frame: u64,
+
+    keyframes_until_check: u64,
+    interval: Option<CheckedInterval>,
+    mismatch: Option<DeterminismMismatch>
+}
+
+impl DeterminismCheckRecorder {
+    /// Wrap `recorder`, where `input` is the input last recorded and `frame` is the number of frames
+    /// recorded so far.
+    ///
+    /// If `initial_state` is set, the recorder was just started with it, and it is checked like a
+    /// keyframe. Otherwise, checking starts at the next keyframe.
+    pub fn new(recorder: Box<dyn ReplayFileRecorderFns>, settings: DeterminismCheckSettings, initial_state: Option<&[u8]>, input: InputBuffer, frame: u64) -> Self {
+        let mut result = Self {
+            recorder,
+            settings,
+            input,
+            frame,
+            keyframes_until_check: 0,
+            interval: None,
+            mismatch: None
+        };
+        if let Some(initial_state) = initial_state {
+            result.on_keyframe(initial_state);
+        }
+        result
+    }
+
+    /// Unwrap the recorder, also returning the settings so they can be used again.
+    pub fn into_parts(self) -> (Box<dyn ReplayFileRecorderFns>, DeterminismCheckSettings) {
+        (self.recorder, self.settings)
+    }
+
+    pub fn take_mismatch(&mut self) -> Option<DeterminismMismatch> {
+        self.mismatch.take()
+    }
+
+    /// Copy a packet for the current interval, if any.
+    fn copy_packet(&self, packet: impl FnOnce() -> Packet) -> Option<Packet> {
+        self.interval.as_ref().map(|_| packet())
+    }
+
+    fn log(&mut self, result: Result<(), ReplayFileWriteError>, packet: Option<Packet>) -> Result<(), ReplayFileWriteError> {
+        if result.is_ok() && let Some(interval) = self.interval.as_mut() && let Some(packet) = packet {
+            interval.packets.push(packet);
+        }
+        result
+    }
+
+    fn on_keyframe(&mut self, state: &[u8]) {
+        if let Some(interval) = self.interval.take() {
+            self.check(interval, state);
+        }
+
+        if self.keyframes_until_check == 0 {
+            self.interval = Some(CheckedInterval {
+                state: state.to_vec(),
+                input: self.input.clone(),
+                frame: self.frame,
+                packets: Vec::new()
+            });
+            self.keyframes_until_check = self.settings.keyframes_per_check.get() - 1;
+        }
+        else {
+            self.keyframes_until_check -= 1;
+        }
+    }
+
+    fn check(&mut self, interval: CheckedInterval, expected_state: &[u8]) {
+        let shadow = self.settings.shadow_core.as_mut();
+        if shadow.load_save_state(&interval.state).is_err() {
+            return
+        }
+        shadow.set_input_encoded(interval.input.as_slice());
+
+        for packet in interval.packets {
+            match packet {
+                Packet::NextFrame { .. } => while shadow.run_unlocked().frames == 0 {},
+                Packet::WriteMemory { address, data } => { let _ = shadow.write_ram(address as u32, data.as_slice()); },
+                Packet::WriteMemoryMulti { writes } => {
+                    for MemoryWrite { address, data } in writes {
+                        let _ = shadow.write_ram(address as u32, data.as_slice());
+                    }
+                },
+                Packet::ChangeInput { data } => shadow.set_input_encoded(data.as_slice()),
+                Packet::ChangeSpeed { speed } => shadow.set_speed(speed.into_multiplier_float()),
+                Packet::ResetConsole => shadow.hard_reset(),
+                Packet::SoftResetConsole => { shadow.soft_reset(); },
+                Packet::LoadSaveState { state } => { let _ = shadow.load_save_state(state.as_slice()); },
+                _ => unreachable!("packet {packet:?} is never logged")
+            }
+        }
+
+        let expected_hash = blake3_hash(expected_state);
+        let actual_hash = blake3_hash(&shadow.create_save_state());
+        if expected_hash != actual_hash && self.mismatch.is_none() {
+            self.mismatch = Some(DeterminismMismatch {
+                start_frame: interval.frame,
+                end_frame: self.frame,
+                expected_hash,
+                actual_hash
+            });
+        }
+    }
+}
+
+impl ReplayFileRecorderFns for DeterminismCheckRecorder {
+    fn is_closed(&self) -> bool {
+        self.recorder.is_closed()
+    }
+
+    fn close(&mut self) -> Result<(), ReplayFileWriteError> {
+        self.recorder.close()
+    }
+
+    fn next_frame(&mut self, timestamp_millis: TimestampMillis) -> Result<(), ReplayFileWriteError> {
+        let result = self.recorder.next_frame(timestamp_millis);
+        self.frame += 1;
+        self.log(result, Some(Packet::NextFrame { timestamp_delta: 0 }))
+    }
+
+    fn add_bookmark(&mut self, name: String) -> Result<(), ReplayFileWriteError> {
+        self.recorder.add_bookmark(name)
+    }
+
+    fn insert_keyframe(&mut self, state: ByteVec, timestamp_millis: TimestampMillis) -> Result<(), ReplayFileWriteError> {
+        self.on_keyframe(state.as_slice());
+        self.recorder.insert_keyframe(state, timestamp_millis)
+    }
+
+    fn set_input(&mut self, input_buffer: InputBuffer) -> Result<(), ReplayFileWriteError> {
+        let packet = self.copy_packet(|| Packet::ChangeInput { data: input_buffer.clone() });
+        let result = self.recorder.set_input(input_buffer.clone());
+        if result.is_ok() {
+            self.input = input_buffer;
+        }
+        self.log(result, packet)
+    }
+
+    fn reset_console(&mut self) -> Result<(), ReplayFileWriteError> {
+        let result = self.recorder.reset_console();
+        self.log(result, Some(Packet::ResetConsole))
+    }
+
+    fn soft_reset_console(&mut self) -> Result<(), ReplayFileWriteError> {
+        let result = self.recorder.soft_reset_console();
+        self.log(result, Some(Packet::SoftResetConsole))
+    }
+
+    fn write_memory(&mut self, address: UnsignedInteger, data: ByteVec) -> Result<(), ReplayFileWriteError> {
+        let packet = self.copy_packet(|| Packet::WriteMemory { address, data: data.clone() });
+        let result = self.recorder.write_memory(address, data);
+        self.log(result, packet)
+    }
+
+    fn write_memory_multi(&mut self, writes: Vec<MemoryWrite>) -> Result<(), ReplayFileWriteError> {
+        let packet = self.copy_packet(|| Packet::WriteMemoryMulti { writes: writes.clone() });
+        let result = self.recorder.write_memory_multi(writes);
+        self.log(result, packet)
+    }
+
+    fn set_speed(&mut self, speed: Speed) -> Result<(), ReplayFileWriteError> {
+        let result = self.recorder.set_speed(speed);
+        self.log(result, Some(Packet::ChangeSpeed { speed }))
+    }
+
+    fn load_save_state(&mut self, state: ByteVec) -> Result<(), ReplayFileWriteError> {
+        let packet = self.copy_packet(|| Packet::LoadSaveState { state: state.clone() });
+        let result = self.recorder.load_save_state(state);
+        self.log(result, packet)
+    }
+
+    fn restore_state(&mut self, keyframe_index: UnsignedInteger) -> Result<(), ReplayFileWriteError> {
+        // Keyframe states aren't kept, so this interval can't be re-simulated
+        self.interval = None;
+        self.recorder.restore_state(keyframe_index)
+    }
+
+    fn flush(&mut self) -> Result<(), ReplayFileWriteError> {
+        self.recorder.flush()
+    }
+}
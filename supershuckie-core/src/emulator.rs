@@ -3,14 +3,18 @@
 mod game_boy_color;
 mod null;
 
+use alloc::borrow::ToOwned;
 use alloc::string::String;
 pub use game_boy_color::*;
 pub use null::*;
 
 use alloc::vec::Vec;
+use core::ops::Range;
+use crate::{CheatCode, CheatPatch};
 use std::num::NonZeroU64;
 use supershuckie_replay_recorder::ByteVec;
 use supershuckie_replay_recorder::replay_file::{ReplayConsoleType, ReplayHeaderBlake3Hash, ReplayPatchFormat};
+use supershuckie_replay_recorder::replay_file::import::MovieFrameInput;
 use supershuckie_replay_recorder::replay_file::record::{ReplayFileRecorderSettings, ReplayFileSink};
 
 /// Emulator core functionality.
@@ -31,6 +35,13 @@ pub trait EmulatorCore: Send + 'static {
     /// Note: The way `address` is interpreted is core-specific.
     fn write_ram(&mut self, address: u32, from: &[u8]) -> Result<(), &'static str>;
 
+    /// Copy all RAM readable with [`EmulatorCore::read_ram`] into `snapshot`, reusing its
+    /// allocations.
+    ///
+    /// This is meant to be called at frame boundaries so the snapshot can be read on another thread
+    /// without holding up emulation.
+    fn snapshot_ram(&self, snapshot: &mut RamSnapshot);
+
     /// Set the game speed multiplier.
     fn set_speed(&mut self, speed: f64);
 
@@ -56,6 +67,20 @@ pub trait EmulatorCore: Send + 'static {
     /// It must be encoded by `encode_input`.
     fn set_input_encoded(&mut self, input: &[u8]);
 
+    /// Decode input encoded by `encode_input`.
+    ///
+    /// This is the inverse of `encode_input`, and it can be used to interpret the data of recorded
+    /// `ChangeInput` packets and keyframes (e.g. for input displays or converting to other formats
+    /// with `Into<MovieFrameInput>`).
+    ///
+    /// Returns `None` if the input is not valid for this core.
+    fn decode_input(&self, input: &[u8]) -> Option<Input>;
+
+    /// Describe which parts of [`Input`] this core uses.
+    ///
+    /// Anything not described here is ignored by `encode_input`.
+    fn input_descriptor(&self) -> InputDescriptor;
+
     /// Get the screen(s).
     fn get_screens(&self) -> &[ScreenData];
 
@@ -65,11 +90,99 @@ pub trait EmulatorCore: Send + 'static {
     /// implementation may copy, instead.
     fn swap_screen_data(&mut self, screens: &mut [ScreenData]);
 
+    /// Set whether or not the screen(s) should be rendered.
+    ///
+    /// Disabling rendering can speed up emulation when the screen is not going to be displayed.
+    fn set_rendering_enabled(&mut self, enabled: bool);
+
     /// Hard reset the console.
     ///
     /// This simulates instantly turning it off and on.
     fn hard_reset(&mut self);
 
+    /// Soft reset the console, if supported.
+    ///
+    /// Unlike [`EmulatorCore::hard_reset`], some state may be retained, depending on the console.
+    ///
+    /// Returns `false` if the console does not support soft resetting, in which case nothing happens.
+    fn soft_reset(&mut self) -> bool;
+
+    /// Connect or disconnect a printer, if supported.
+    ///
+    /// Returns `false` if the console does not support a printer, in which case nothing happens.
+    fn set_printer_connected(&mut self, connected: bool) -> bool;
+
+    /// Take all pages printed since the last call.
+    ///
+    /// Each page is returned as [`ScreenData`] with its own dimensions.
+    fn take_printed_pages(&mut self) -> Vec<ScreenData>;
+
+    /// Start or stop capturing bytes sent over the serial port, if supported.
+    ///
+    /// Many test ROMs report their results this way. Returns `false` if the console does not
+    /// support it or the serial port is in use (e.g. by a printer), in which case nothing happens.
+    fn set_serial_capture_enabled(&mut self, enabled: bool) -> bool;
+
+    /// Take all bytes sent over the serial port since the last call while capture was enabled.
+    fn take_serial_output(&mut self) -> Vec<u8>;
+
+    /// Set the rate audio samples are generated at, in Hz, or 0 to stop generating them.
+    ///
+    /// Returns `false` if the console does not support audio, in which case nothing happens.
+    fn set_audio_sample_rate(&mut self, sample_rate: u32) -> bool;
+
+    /// Get the rate audio samples are generated at, in Hz, or 0 if they are not being generated.
+    fn get_audio_sample_rate(&self) -> u32;
+
+    /// Move all audio samples generated since the last call to the end of `into`.
+    ///
+    /// Samples are signed 16-bit stereo, interleaved left then right. If they aren't drained, only
+    /// about half a second's worth is kept.
+    fn drain_audio_samples(&mut self, into: &mut Vec<i16>);
+
+    /// Get the current state of any peripherals that output to the user (e.g. rumble).
+    fn get_peripheral_output(&self) -> PeripheralOutput;
+
+    /// Set how colors are corrected to look closer to the original hardware's display, if supported.
+    ///
+    /// Returns `false` if the console does not support color correction, in which case nothing happens.
+    fn set_color_correction(&mut self, color_correction: ColorCorrection) -> bool;
+
+    /// Get all core-specific options (e.g. RTC mode) and their current values.
+    fn list_options(&self) -> Vec<CoreOption>;
+
+    /// Set a core-specific option listed by [`EmulatorCore::list_options`].
+    ///
+    /// Returns `Err` if the key is unknown or the value is not one of the option's allowed values,
+    /// in which case nothing happens.
+    fn set_option(&mut self, key: &str, value: &str) -> Result<(), String>;
+
+    /// Get the writes needed to apply the cheat to the loaded game.
+    ///
+    /// Returns `Err` if the console does not support the cheat or it does not apply to the loaded
+    /// game.
+    fn resolve_cheat(&self, cheat: &CheatCode) -> Result<Vec<CheatPatch>, &'static str>;
+
+    /// Serialize any core-specific configuration that affects determinism so it can be stored in
+    /// replays.
+    ///
+    /// The blob is opaque to everything but the core and must fit in 1024 bytes. By default, this
+    /// is empty.
+    fn core_config_blob(&self) -> ByteVec {
+        ByteVec::new()
+    }
+
+    /// Apply a blob previously returned by [`EmulatorCore::core_config_blob`].
+    ///
+    /// Returns `Err` if the blob can't be applied, in which case nothing happens. By default, only
+    /// an empty blob is accepted.
+    fn apply_core_config_blob(&mut self, blob: &[u8]) -> Result<(), String> {
+        match blob.is_empty() {
+            true => Ok(()),
+            false => Err("this core does not support core config".to_owned())
+        }
+    }
+
     /// Get the replay type.
     fn replay_console_type(&self) -> Option<ReplayConsoleType>;
 
@@ -81,13 +194,86 @@ pub trait EmulatorCore: Send + 'static {
 
     /// Get the current core name.
     fn core_name(&self) -> &'static str;
+
+    /// Get the number of ticks (as returned in [`RunTime::ticks`]) in one second of emulated time.
+    fn ticks_per_second(&self) -> u64;
 }
 
 /// Amount of time passed when running the emulator core.
 #[derive(Copy, Clone, PartialEq, Debug)]
 pub struct RunTime {
     /// Frames passed.
-    pub frames: u64
+    pub frames: u64,
+
+    /// Ticks passed (see [`EmulatorCore::ticks_per_second`]).
+    pub ticks: u64
+}
+
+/// Describes the current state of peripherals that output to the user.
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct PeripheralOutput {
+    /// Rumble amplitude, from 0.0 (off) to 1.0 (full).
+    pub rumble_amplitude: f64,
+
+    /// Whether the infrared LED is on.
+    pub infrared_led: bool
+}
+
+/// Describes how colors are corrected to look closer to the original hardware's display.
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct ColorCorrection {
+    /// Color correction curve to use.
+    pub mode: ColorCorrectionMode,
+
+    /// Ambient light temperature, from -1.0 (warm red) to 1.0 (cool blue), with 0.0 being neutral.
+    ///
+    /// This simulates an external light source shining on a screen that is not front or backlit.
+    pub light_temperature: f64
+}
+
+/// Describes a core-specific option.
+///
+/// Options are adjusted with [`EmulatorCore::set_option`] so that each core's knobs can be exposed
+/// without needing a dedicated method for each one.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct CoreOption {
+    /// Unique key used to set the option and to store it in settings.
+    pub key: &'static str,
+
+    /// Human-readable name of the option.
+    pub name: &'static str,
+
+    /// All values the option can be set to.
+    pub values: &'static [&'static str],
+
+    /// Current value, which is one of `values`.
+    pub value: &'static str
+}
+
+/// Color correction curves, matching those supported by SameBoy.
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub enum ColorCorrectionMode {
+    /// Colors are mapped directly to sRGB.
+    #[default]
+    Disabled,
+
+    /// Brightness is corrected, but hues are not.
+    CorrectCurves,
+
+    /// Brightness and hues are corrected for a modern display.
+    ModernAccurate,
+
+    /// Brightness and hues are corrected for a modern display, and blue contrast is boosted.
+    ModernBalanced,
+
+    /// Contrast is boosted beyond [`ColorCorrectionMode::ModernBalanced`].
+    ModernBoostContrast,
+
+    /// Contrast is reduced to better match the original display.
+    ReduceContrast,
+
+    /// Contrast is heavily reduced.
+    LowContrast
 }
 
 /// Describes a current input state.
@@ -109,7 +295,11 @@ pub struct Input {
     pub x: bool,
     pub y: bool,
 
-    pub touch: Option<(u16, u16)>
+    pub touch: Option<(u16, u16)>,
+
+    /// Accelerometer tilt as (x, y), where `i16::MAX` is fully tilted right/down and `-i16::MAX`
+    /// is fully tilted left/up.
+    pub tilt: Option<(i16, i16)>
 }
 
 impl Default for Input {
@@ -137,6 +327,7 @@ impl Input {
             x: false,
             y: false,
             touch: None,
+            tilt: None,
         }
     }
 
@@ -156,6 +347,190 @@ impl Input {
         && !self.x
         && !self.y
         && self.touch.is_none()
+        && self.tilt.is_none()
+    }
+}
+
+/// A single part of [`Input`].
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[repr(u32)]
+#[allow(missing_docs)]
+pub enum InputKind {
+    A,
+    B,
+    Start,
+    Select,
+
+    DUp,
+    DDown,
+    DLeft,
+    DRight,
+
+    L,
+    R,
+    X,
+    Y,
+
+    Touch,
+    Tilt
+}
+
+impl InputKind {
+    /// All input kinds, in bit order.
+    pub const ALL: [InputKind; 14] = [
+        InputKind::A,
+        InputKind::B,
+        InputKind::Start,
+        InputKind::Select,
+        InputKind::DUp,
+        InputKind::DDown,
+        InputKind::DLeft,
+        InputKind::DRight,
+        InputKind::L,
+        InputKind::R,
+        InputKind::X,
+        InputKind::Y,
+        InputKind::Touch,
+        InputKind::Tilt
+    ];
+
+    /// Get the name of the input.
+    pub const fn name(self) -> &'static str {
+        match self {
+            InputKind::A => "A",
+            InputKind::B => "B",
+            InputKind::Start => "Start",
+            InputKind::Select => "Select",
+            InputKind::DUp => "D-Up",
+            InputKind::DDown => "D-Down",
+            InputKind::DLeft => "D-Left",
+            InputKind::DRight => "D-Right",
+            InputKind::L => "L",
+            InputKind::R => "R",
+            InputKind::X => "X",
+            InputKind::Y => "Y",
+            InputKind::Touch => "Touch",
+            InputKind::Tilt => "Tilt"
+        }
+    }
+
+    /// Return true if this part of the input is pressed/active.
+    pub const fn is_active(self, input: &Input) -> bool {
+        match self {
+            InputKind::A => input.a,
+            InputKind::B => input.b,
+            InputKind::Start => input.start,
+            InputKind::Select => input.select,
+            InputKind::DUp => input.d_up,
+            InputKind::DDown => input.d_down,
+            InputKind::DLeft => input.d_left,
+            InputKind::DRight => input.d_right,
+            InputKind::L => input.l,
+            InputKind::R => input.r,
+            InputKind::X => input.x,
+            InputKind::Y => input.y,
+            InputKind::Touch => input.touch.is_some(),
+            InputKind::Tilt => input.tilt.is_some()
+        }
+    }
+
+    #[inline]
+    const fn bit(self) -> u32 {
+        1 << (self as u32)
+    }
+}
+
+/// A set of [`InputKind`]s stored as a bitfield.
+///
+/// This is used both to describe the capabilities of a core and the parts of an input in use.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub struct InputDescriptor {
+    mask: u32
+}
+
+impl InputDescriptor {
+    /// Instantiate an empty descriptor.
+    #[inline]
+    pub const fn new() -> Self {
+        Self { mask: 0 }
+    }
+
+    /// Instantiate a descriptor containing the given input kinds.
+    pub const fn from_kinds(kinds: &[InputKind]) -> Self {
+        let mut descriptor = Self::new();
+        let mut i = 0;
+        while i < kinds.len() {
+            descriptor = descriptor.with(kinds[i]);
+            i += 1;
+        }
+        descriptor
+    }
+
+    /// Get a descriptor of all parts of the input that are active.
+    pub fn of_input(input: &Input) -> Self {
+        let mut descriptor = Self::new();
+        for kind in InputKind::ALL {
+            if kind.is_active(input) {
+                descriptor = descriptor.with(kind);
+            }
+        }
+        descriptor
+    }
+
+    /// Instantiate a descriptor from a raw bitfield, discarding unknown bits.
+    pub const fn from_mask(mask: u32) -> Self {
+        Self { mask: mask & ((1 << InputKind::ALL.len()) - 1) }
+    }
+
+    /// Get the raw bitfield, where each bit is `1 << InputKind as u32`.
+    #[inline]
+    pub const fn mask(self) -> u32 {
+        self.mask
+    }
+
+    /// Return a copy with the given input kind added.
+    #[inline]
+    pub const fn with(self, kind: InputKind) -> Self {
+        Self { mask: self.mask | kind.bit() }
+    }
+
+    /// Return true if the given input kind is contained.
+    #[inline]
+    pub const fn contains(self, kind: InputKind) -> bool {
+        self.mask & kind.bit() != 0
+    }
+
+    /// Return true if everything in `other` is contained.
+    #[inline]
+    pub const fn contains_all(self, other: InputDescriptor) -> bool {
+        self.mask & other.mask == other.mask
+    }
+
+    /// Iterate through all contained input kinds.
+    pub fn iter(self) -> impl Iterator<Item = InputKind> {
+        InputKind::ALL.into_iter().filter(move |i| self.contains(*i))
+    }
+
+    /// Clear any part of the input not contained.
+    pub const fn mask_input(self, input: &mut Input) {
+        input.a &= self.contains(InputKind::A);
+        input.b &= self.contains(InputKind::B);
+        input.start &= self.contains(InputKind::Start);
+        input.select &= self.contains(InputKind::Select);
+        input.d_up &= self.contains(InputKind::DUp);
+        input.d_down &= self.contains(InputKind::DDown);
+        input.d_left &= self.contains(InputKind::DLeft);
+        input.d_right &= self.contains(InputKind::DRight);
+        input.l &= self.contains(InputKind::L);
+        input.r &= self.contains(InputKind::R);
+        input.x &= self.contains(InputKind::X);
+        input.y &= self.contains(InputKind::Y);
+        if !self.contains(InputKind::Touch) {
+            input.touch = None;
+        }
+        if !self.contains(InputKind::Tilt) {
+            input.tilt = None;
+        }
     }
 }
 
@@ -176,6 +551,7 @@ impl core::ops::BitOr<Input> for Input {
             x: self.x | rhs.x,
             y: self.y | rhs.y,
             touch: self.touch.or(rhs.touch),
+            tilt: self.tilt.or(rhs.tilt),
         }
     }
 }
@@ -197,6 +573,7 @@ impl core::ops::BitAnd<Input> for Input {
             x: self.x & rhs.x,
             y: self.y & rhs.y,
             touch: self.touch.and(rhs.touch),
+            tilt: self.tilt.and(rhs.tilt),
         }
     }
 }
@@ -218,7 +595,8 @@ impl core::ops::Not for Input {
             r: !self.r,
             x: !self.x,
             y: !self.y,
-            touch: None
+            touch: None,
+            tilt: None
         }
     }
 }
@@ -235,6 +613,44 @@ impl core::ops::BitOrAssign for Input {
     }
 }
 
+/// Convert to a frame of an imported or exported movie (see [`supershuckie_replay_recorder::replay_file::script`]).
+///
+/// Inputs that movies can't represent are dropped.
+impl From<Input> for MovieFrameInput {
+    fn from(value: Input) -> Self {
+        Self {
+            a: value.a,
+            b: value.b,
+            start: value.start,
+            select: value.select,
+            d_up: value.d_up,
+            d_down: value.d_down,
+            d_left: value.d_left,
+            d_right: value.d_right,
+            reset: false
+        }
+    }
+}
+
+/// Convert from a frame of an imported or exported movie.
+///
+/// [`MovieFrameInput::reset`] is not an input and is ignored.
+impl From<&MovieFrameInput> for Input {
+    fn from(value: &MovieFrameInput) -> Self {
+        Self {
+            a: value.a,
+            b: value.b,
+            start: value.start,
+            select: value.select,
+            d_up: value.d_up,
+            d_down: value.d_down,
+            d_left: value.d_left,
+            d_right: value.d_right,
+            ..Self::new()
+        }
+    }
+}
+
 /// Describes screen data.
 #[derive(Clone, PartialEq)]
 pub struct ScreenData {
@@ -268,11 +684,178 @@ impl Default for ScreenData {
     }
 }
 
+impl ScreenData {
+    /// Iterate over the color of each pixel as `[r, g, b]`, regardless of encoding.
+    pub fn rgb_pixels(&self) -> impl Iterator<Item = [u8; 3]> + Clone + '_ {
+        let count = self.width * self.height;
+        let encoding = self.encoding;
+        (0..count).map(move |i| encoding.to_rgb(self.read_pixel(i)))
+    }
+
+    /// Convert the screen to another encoding, reusing the buffer of `into`.
+    pub fn convert_into(&self, encoding: ScreenDataEncoding, into: &mut ScreenData) {
+        let count = self.width * self.height;
+        into.width = self.width;
+        into.height = self.height;
+        into.encoding = encoding;
+        into.pixels.clear();
+        into.pixels.resize(encoding.buffer_len(count), 0);
+
+        for (i, rgb) in self.rgb_pixels().enumerate() {
+            into.write_pixel(i, encoding.from_rgb(rgb));
+        }
+    }
+
+    fn read_pixel(&self, index: usize) -> u32 {
+        match self.encoding.bits_per_pixel() {
+            32 => self.pixels[index],
+            // Packed as a u16 array, so go by memory order rather than the bit order of each u32
+            _ => {
+                let word = self.pixels[index / 2].to_ne_bytes();
+                let half = &word[(index % 2) * 2..][..2];
+                u16::from_ne_bytes([half[0], half[1]]) as u32
+            }
+        }
+    }
+
+    fn write_pixel(&mut self, index: usize, value: u32) {
+        match self.encoding.bits_per_pixel() {
+            32 => self.pixels[index] = value,
+            _ => {
+                let mut word = self.pixels[index / 2].to_ne_bytes();
+                word[(index % 2) * 2..][..2].copy_from_slice(&(value as u16).to_ne_bytes());
+                self.pixels[index / 2] = u32::from_ne_bytes(word);
+            }
+        }
+    }
+}
+
 /// Describes the color encoding.
-#[derive(Copy, Clone, PartialEq, Debug)]
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+#[repr(u32)]
 pub enum ScreenDataEncoding {
-    /// 0xAARRGGBB
-    A8R8G8B8
+    /// 0xAARRGGBB (B, G, R, A in memory on little-endian platforms)
+    #[default]
+    A8R8G8B8 = 0,
+
+    /// 0xAABBGGRR (R, G, B, A in memory on little-endian platforms)
+    A8B8G8R8 = 1,
+
+    /// 16-bit 0bRRRRRGGGGGGBBBBB, packed two pixels per `u32` so the pixels can be read as a `u16`
+    /// array
+    R5G6B5 = 2
+}
+
+impl ScreenDataEncoding {
+    /// All encodings.
+    pub const ALL: [ScreenDataEncoding; 3] = [ScreenDataEncoding::A8R8G8B8, ScreenDataEncoding::A8B8G8R8, ScreenDataEncoding::R5G6B5];
+
+    /// Get the number of bits each pixel takes up.
+    pub const fn bits_per_pixel(self) -> usize {
+        match self {
+            Self::A8R8G8B8 => 32,
+            Self::A8B8G8R8 => 32,
+            Self::R5G6B5 => 16
+        }
+    }
+
+    /// Get the number of `u32`s needed to hold the given number of pixels.
+    pub const fn buffer_len(self, pixel_count: usize) -> usize {
+        (pixel_count * self.bits_per_pixel()).div_ceil(32)
+    }
+
+    /// Get the color of a pixel as `[r, g, b]`.
+    pub const fn to_rgb(self, pixel: u32) -> [u8; 3] {
+        match self {
+            Self::A8R8G8B8 => {
+                let [_, r, g, b] = pixel.to_be_bytes();
+                [r, g, b]
+            },
+            Self::A8B8G8R8 => {
+                let [_, b, g, r] = pixel.to_be_bytes();
+                [r, g, b]
+            },
+            Self::R5G6B5 => {
+                let r = ((pixel >> 11) & 0x1F) as u8;
+                let g = ((pixel >> 5) & 0x3F) as u8;
+                let b = (pixel & 0x1F) as u8;
+                [(r << 3) | (r >> 2), (g << 2) | (g >> 4), (b << 3) | (b >> 2)]
+            }
+        }
+    }
+
+    /// Encode an opaque `[r, g, b]` color as a pixel.
+    pub const fn from_rgb(self, [r, g, b]: [u8; 3]) -> u32 {
+        match self {
+            Self::A8R8G8B8 => u32::from_be_bytes([0xFF, r, g, b]),
+            Self::A8B8G8R8 => u32::from_be_bytes([0xFF, b, g, r]),
+            Self::R5G6B5 => ((r as u32 >> 3) << 11) | ((g as u32 >> 2) << 5) | (b as u32 >> 3)
+        }
+    }
+}
+
+/// A copy of a core's RAM, made with [`EmulatorCore::snapshot_ram`].
+///
+/// This is read with the same addresses as [`EmulatorCore::read_ram`], and it can be reused for
+/// later snapshots to avoid reallocating.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct RamSnapshot {
+    buffers: Vec<Vec<u8>>,
+    mappings: Vec<RamSnapshotMapping>
+}
+
+#[derive(Clone, PartialEq, Debug)]
+struct RamSnapshotMapping {
+    /// Addresses that reads can start at
+    addresses: Range<u32>,
+
+    /// Index of the buffer in [`RamSnapshot::buffers`]
+    buffer: usize,
+
+    /// Offset into the buffer of the first address
+    offset: usize
+}
+
+impl RamSnapshot {
+    /// Forget all mappings, keeping allocations for the next snapshot.
+    pub fn clear(&mut self) {
+        self.buffers.iter_mut().for_each(Vec::clear);
+        self.mappings.clear();
+    }
+
+    /// Copy `data` into the buffer at `index`, replacing its contents.
+    pub fn set_buffer(&mut self, index: usize, data: &[u8]) {
+        if self.buffers.len() <= index {
+            self.buffers.resize_with(index + 1, Vec::new);
+        }
+        let buffer = &mut self.buffers[index];
+        buffer.clear();
+        buffer.extend_from_slice(data);
+    }
+
+    /// Make reads starting in `addresses` read from the buffer at `buffer`, with the first address
+    /// being at `offset`.
+    ///
+    /// Reads can extend past the end of `addresses`, but not past the end of the buffer.
+    pub fn map(&mut self, addresses: Range<u32>, buffer: usize, offset: usize) {
+        self.mappings.push(RamSnapshotMapping { addresses, buffer, offset });
+    }
+
+    /// Read RAM at the given address to the given data buffer.
+    pub fn read(&self, address: u32, into: &mut [u8]) -> Result<(), &'static str> {
+        let Some(mapping) = self.mappings.iter().find(|m| m.addresses.contains(&address)) else {
+            return Err("invalid or unknown address");
+        };
+        let start = mapping.offset + (address - mapping.addresses.start) as usize;
+        let Some(end) = start.checked_add(into.len()) else {
+            return Err("invalid length");
+        };
+        let Some(data) = self.buffers.get(mapping.buffer).and_then(|b| b.get(start..end)) else {
+            return Err("address+length overflows");
+        };
+        into.copy_from_slice(data);
+        Ok(())
+    }
 }
 
 fn _ensure_emulator_core_is_dyn_compatible(_core: &dyn EmulatorCore) {}
@@ -305,6 +888,12 @@ pub struct PartialReplayRecordMetadata<
     /// Lower numbers will improve seeking performance but increase file and memory size.
     pub frames_per_keyframe: NonZeroU64,
 
+    /// Also insert a keyframe whenever a save state is loaded, the console is reset, or a bookmark
+    /// is added, regardless of [`frames_per_keyframe`](Self::frames_per_keyframe).
+    ///
+    /// This makes seeking to these moments instant.
+    pub keyframe_on_events: bool,
+
     /// Final file to write to
     pub final_file: FS,
 
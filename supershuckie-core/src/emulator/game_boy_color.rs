@@ -1,18 +1,58 @@
-use crate::emulator::{EmulatorCore, Input, RunTime, ScreenData, ScreenDataEncoding};
+use crate::{CheatCode, CheatPatch};
+use crate::emulator::{ColorCorrection, ColorCorrectionMode, CoreOption, EmulatorCore, Input, InputDescriptor, InputKind, PeripheralOutput, RamSnapshot, RunTime, ScreenData, ScreenDataEncoding};
 use alloc::borrow::ToOwned;
 use alloc::boxed::Box;
 use alloc::string::String;
 use alloc::sync::Arc;
 use alloc::vec::Vec;
 use core::cell::UnsafeCell;
-use core::sync::atomic::{AtomicU32, Ordering};
+use core::ops::Range;
+use core::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicUsize, Ordering};
 use safeboy::rgb_encoder::encode_a8r8g8b8;
-use safeboy::{BorderMode, DirectAccessRegion, Gameboy, GameboyCallbacks, InputButton, RtcMode, RunnableInstanceFunctions, RunningGameboy, TurboMode, VBlankType};
+use safeboy::{BorderMode, DirectAccessRegion, Gameboy, GameboyCallbacks, InputButton, PrinterPage, RtcMode, RunnableInstanceFunctions, RunningGameboy, TurboMode, VBlankType};
 pub use safeboy::Model;
+use sameboy_sys::{GB_gameboy_internal_s, GB_gameboy_t, GB_set_accelerometer_values};
 use spin::Lazy;
-use supershuckie_replay_recorder::blake3_hash;
+use supershuckie_replay_recorder::{blake3_hash, ByteVec};
 use supershuckie_replay_recorder::replay_file::{ReplayConsoleType, ReplayHeaderBlake3Hash};
 
+const CARTRIDGE_TYPE_ADDRESS: usize = 0x147;
+
+/// MBC7 cartridges (e.g. Kirby Tilt 'n' Tumble) have an accelerometer.
+const CARTRIDGE_TYPE_MBC7: u8 = 0x22;
+
+/// Inputs used by all Game Boy games.
+const GAME_BOY_INPUTS: InputDescriptor = InputDescriptor::from_kinds(&[
+    InputKind::A,
+    InputKind::B,
+    InputKind::Start,
+    InputKind::Select,
+    InputKind::DUp,
+    InputKind::DDown,
+    InputKind::DLeft,
+    InputKind::DRight
+]);
+
+const OPTION_RTC_MODE: &str = "rtc_mode";
+const OPTION_ALLOW_ILLEGAL_INPUTS: &str = "allow_illegal_inputs";
+
+/// Options supported by the core, with their default values.
+const GAME_BOY_OPTIONS: [CoreOption; 2] = [
+    // Syncing to the host makes the RTC nondeterministic, so it defaults to accurate for replays.
+    CoreOption {
+        key: OPTION_RTC_MODE,
+        name: "Real-time clock",
+        values: &["accurate", "sync_to_host"],
+        value: "accurate"
+    },
+    CoreOption {
+        key: OPTION_ALLOW_ILLEGAL_INPUTS,
+        name: "Allow opposing directions",
+        values: &["false", "true"],
+        value: "false"
+    }
+];
+
 /// Game Boy and Game Boy Color emulator.
 ///
 /// Uses [SameBoy](https://sameboy.github.io) as the underlying core.
@@ -20,6 +60,13 @@ pub struct GameBoyColor {
     core: Gameboy,
     turbo_mode: TurboMode,
     callback_data: Arc<GameBoyCallbackData>,
+    has_accelerometer: bool,
+    printer_connected: bool,
+    serial_capture: bool,
+
+    /// Kept here since SameBoy doesn't report it back reliably.
+    audio_sample_rate: u32,
+    options: [CoreOption; GAME_BOY_OPTIONS.len()],
 
     rom_checksum: ReplayHeaderBlake3Hash,
     bios_checksum: ReplayHeaderBlake3Hash,
@@ -27,18 +74,36 @@ pub struct GameBoyColor {
 
 struct GameBoyCallbackData {
     run_frames: AtomicU32,
-    screen: UnsafeCell<ScreenData>
+    screen: UnsafeCell<ScreenData>,
+    printed_pages: UnsafeCell<Vec<ScreenData>>,
+    serial_output: UnsafeCell<Vec<u8>>,
+    audio_samples: UnsafeCell<Vec<i16>>,
+
+    /// Samples are dropped once this many are buffered.
+    max_audio_samples: AtomicUsize,
+    rumble_amplitude: AtomicU64,
+    infrared_led: AtomicBool
 }
 
 unsafe impl Send for GameBoyCallbackData {}
 unsafe impl Sync for GameBoyCallbackData {}
 
 impl GameBoyColor {
+    /// Get the SameBoy instance, for functions safeboy doesn't wrap.
+    fn sameboy_instance(&mut self) -> *mut GB_gameboy_t {
+        // safeboy only hands out pointers into the instance, so work back from IE, which is a field
+        // of it. The layout is checked by sameboy-sys's bindings.
+        let ie_offset = core::mem::offset_of!(GB_gameboy_internal_s, __bindgen_anon_2.__bindgen_anon_1.interrupt_enable);
+        let ie = self.core.direct_access_mut(DirectAccessRegion::IE).data.as_mut_ptr();
+        ie.wrapping_sub(ie_offset).cast()
+    }
+
     /// Instantiate a `GameBoyColor` emulator from the given ROM.
     pub fn new_from_rom(
         rom: &[u8],
         bios: &[u8],
-        model: Model
+        model: Model,
+        color_correction: ColorCorrection
     ) -> Self {
         let mut core = Gameboy::new(model);
         core.set_rtc_mode(RtcMode::Accurate);
@@ -58,25 +123,54 @@ impl GameBoyColor {
 
         let callback_data = Arc::new(GameBoyCallbackData {
             run_frames: AtomicU32::new(0),
-            screen: UnsafeCell::new(screen_data)
+            screen: UnsafeCell::new(screen_data),
+            printed_pages: UnsafeCell::new(Vec::new()),
+            serial_output: UnsafeCell::new(Vec::new()),
+            audio_samples: UnsafeCell::new(Vec::new()),
+            max_audio_samples: AtomicUsize::new(0),
+            rumble_amplitude: AtomicU64::new(0.0f64.to_bits()),
+            infrared_led: AtomicBool::new(false)
         });
 
-        core.set_callbacks(Some(Box::new(CallbackHandler { callback_data: callback_data.clone() })));
+        core.set_callbacks(Some(Box::new(CallbackHandler { callback_data: callback_data.clone(), serial_byte: 0, serial_bits: 0 })));
 
         let mut r = Self {
             turbo_mode: TurboMode::Disabled,
             callback_data,
             core,
+            has_accelerometer: rom.get(CARTRIDGE_TYPE_ADDRESS) == Some(&CARTRIDGE_TYPE_MBC7),
+            printer_connected: false,
+            serial_capture: false,
+            audio_sample_rate: 0,
+            options: GAME_BOY_OPTIONS,
             rom_checksum: blake3_hash(rom),
             bios_checksum: blake3_hash(bios),
         };
+        r.set_color_correction(color_correction);
         r.hard_reset();
         r
     }
+
+    /// Connect whatever should be on the serial port. The printer takes priority over capture.
+    fn update_serial_connection(&mut self) {
+        if self.printer_connected {
+            self.core.connect_printer();
+        }
+        else if self.serial_capture {
+            self.core.connect_serial();
+        }
+        else {
+            self.core.disconnect_serial();
+        }
+    }
 }
 
 struct CallbackHandler {
-    callback_data: Arc<GameBoyCallbackData>
+    callback_data: Arc<GameBoyCallbackData>,
+
+    /// Bits of the serial byte being sent so far, most significant first
+    serial_byte: u8,
+    serial_bits: u8
 }
 
 impl GameboyCallbacks for CallbackHandler {
@@ -88,22 +182,126 @@ impl GameboyCallbacks for CallbackHandler {
         screen.pixels.copy_from_slice(instance.get_pixel_buffer_pixels());
         self.callback_data.run_frames.fetch_add(1, Ordering::Relaxed);
     }
+
+    fn printer_page(&mut self, _instance: &mut RunningGameboy, page: PrinterPage) {
+        // SAFETY: Same as above.
+        let printed_pages = unsafe { &mut *self.callback_data.printed_pages.get() };
+
+        let (width, height) = page.get_dimensions();
+        printed_pages.push(ScreenData {
+            pixels: page.data,
+            width: width as usize,
+            height: height as usize,
+            encoding: ScreenDataEncoding::A8R8G8B8
+        });
+    }
+
+    fn serial_transfer_bit_start(&mut self, _instance: &mut RunningGameboy, bit: bool) {
+        self.serial_byte = (self.serial_byte << 1) | bit as u8;
+        self.serial_bits += 1;
+        if self.serial_bits < 8 {
+            return
+        }
+
+        // SAFETY: Same as above.
+        let serial_output = unsafe { &mut *self.callback_data.serial_output.get() };
+        serial_output.push(self.serial_byte);
+        self.serial_byte = 0;
+        self.serial_bits = 0;
+    }
+
+    fn apu_sample(&mut self, _instance: &mut RunningGameboy, left: i16, right: i16) {
+        // SAFETY: Same as above.
+        let audio_samples = unsafe { &mut *self.callback_data.audio_samples.get() };
+        if audio_samples.len() < self.callback_data.max_audio_samples.load(Ordering::Relaxed) {
+            audio_samples.extend_from_slice(&[left, right]);
+        }
+    }
+
+    fn rumble(&mut self, _instance: &mut RunningGameboy, amplitude: f64) {
+        self.callback_data.rumble_amplitude.store(amplitude.to_bits(), Ordering::Relaxed);
+    }
+
+    fn infrared(&mut self, _instance: &mut RunningGameboy, on: bool) {
+        self.callback_data.infrared_led.store(on, Ordering::Relaxed);
+    }
 }
 
 /// Returns the region and offset.
+/// Addresses Poke-A-Byte reads and writes can start at, and the region and offset they map to.
+const POKEABYTE_PROTOCOL_REGIONS: [(Range<u32>, DirectAccessRegion, usize); 4] = [
+    // VRAM
+    (0x8000..0xA000, DirectAccessRegion::VRAM, 0),
+
+    // WRAM bank #0
+    (0xC000..0xE000, DirectAccessRegion::RAM, 0),
+
+    // WRAM bank #1 (not the actual address)
+    (0x10000..0x12000, DirectAccessRegion::RAM, 0x2000),
+
+    // HRAM
+    (0xFF80..0xFFFF, DirectAccessRegion::HRAM, 0)
+];
+
+/// Regions copied by [`EmulatorCore::snapshot_ram`], in buffer order.
+const SNAPSHOT_REGIONS: [DirectAccessRegion; 3] = [DirectAccessRegion::VRAM, DirectAccessRegion::RAM, DirectAccessRegion::HRAM];
+
+/// Start of all WRAM banks, in order.
+const FULL_WRAM_ADDRESS: u32 = 0x100_0000;
+
+/// Start of all cartridge RAM banks, in order.
+const FULL_CART_RAM_ADDRESS: u32 = 0x200_0000;
+
+/// Start of the whole ROM.
+const FULL_ROM_ADDRESS: u32 = 0x300_0000;
+
+/// Maximum size of each full region.
+const FULL_REGION_SIZE: u32 = 0x100_0000;
+
+/// Regions that can also be accessed in full, past what Poke-A-Byte can access (e.g. for cheats).
+///
+/// Only WRAM is included in RAM snapshots, since the others can be too big to copy every frame.
+const FULL_REGIONS: [(u32, DirectAccessRegion); 3] = [
+    (FULL_WRAM_ADDRESS, DirectAccessRegion::RAM),
+    (FULL_CART_RAM_ADDRESS, DirectAccessRegion::CartRAM),
+    (FULL_ROM_ADDRESS, DirectAccessRegion::ROM)
+];
+
+/// Size of a switchable ROM bank.
+const ROM_BANK_SIZE: usize = 0x4000;
+
 fn pokeabyte_protocol_region_from_address(address: u32) -> Option<(DirectAccessRegion, usize)> {
-    match address {
-        // VRAM
-        0x8000..=0x9FFF => Some((DirectAccessRegion::VRAM, address as usize - 0x8000)),
+    POKEABYTE_PROTOCOL_REGIONS
+        .iter()
+        .find(|(addresses, _, _)| addresses.contains(&address))
+        .map(|(addresses, region, offset)| (*region, offset + (address - addresses.start) as usize))
+}
+
+fn region_from_address(address: u32) -> Option<(DirectAccessRegion, usize)> {
+    pokeabyte_protocol_region_from_address(address).or_else(|| {
+        FULL_REGIONS
+            .iter()
+            .find(|(start, _)| (*start..*start + FULL_REGION_SIZE).contains(&address))
+            .map(|(start, region)| (*region, (address - start) as usize))
+    })
+}
 
-        // WRAM bank #0
-        0xC000..=0xDFFF => Some((DirectAccessRegion::RAM, address as usize - 0xC000)),
+/// Get the address of a GameShark write to `address` on the bus in `bank`, or `None` for the
+/// selected bank.
+///
+/// Since the selected bank can change at any time, the bank that is selected by default is used.
+fn game_shark_address(address: u16, bank: Option<u8>) -> Option<u32> {
+    let bank = bank.map(u32::from);
+    let address = address as u32;
+    match address {
+        0x8000..0xA000 | 0xC000..0xD000 | 0xFF80..0xFFFF => Some(address),
+        0xA000..0xC000 => Some(FULL_CART_RAM_ADDRESS + bank.unwrap_or(0) * 0x2000 + (address - 0xA000)),
 
-        // WRAM bank #1 (not the actual address)
-        0x10000..=0x11FFF => Some((DirectAccessRegion::RAM, address as usize - 0x10000 + 0x2000)),
+        // Bank 0 can't be selected here on the Game Boy Color, so it means bank 1
+        0xD000..0xE000 => Some(FULL_WRAM_ADDRESS + bank.unwrap_or(1).max(1) * 0x1000 + (address - 0xD000)),
 
-        // HRAM
-        0xFF80..=0xFFFE => Some((DirectAccessRegion::HRAM, address as usize - 0xFF80)),
+        // Echo RAM
+        0xE000..0xFE00 => game_shark_address((address - 0x2000) as u16, bank.map(|b| b as u8)),
 
         _ => None
     }
@@ -111,9 +309,9 @@ fn pokeabyte_protocol_region_from_address(address: u32) -> Option<(DirectAccessR
 
 impl EmulatorCore for GameBoyColor {
     fn run(&mut self) -> RunTime {
-        self.core.run();
+        let ticks = self.core.run() as u64;
         let frames = self.callback_data.run_frames.swap(0, Ordering::Relaxed) as u64;
-        RunTime { frames }
+        RunTime { frames, ticks }
     }
 
     fn run_unlocked(&mut self) -> RunTime {
@@ -124,7 +322,7 @@ impl EmulatorCore for GameBoyColor {
     }
 
     fn read_ram(&self, address: u32, into: &mut [u8]) -> Result<(), &'static str> {
-        let Some((region, offset)) = pokeabyte_protocol_region_from_address(address) else {
+        let Some((region, offset)) = region_from_address(address) else {
             return Err("invalid or unknown address");
         };
         let Some(offset_end) = offset.checked_add(into.len()) else {
@@ -140,7 +338,7 @@ impl EmulatorCore for GameBoyColor {
     }
 
     fn write_ram(&mut self, address: u32, from: &[u8]) -> Result<(), &'static str> {
-        let Some((region, offset)) = pokeabyte_protocol_region_from_address(address) else {
+        let Some((region, offset)) = region_from_address(address) else {
             return Err("invalid or unknown address");
         };
         let Some(offset_end) = offset.checked_add(from.len()) else {
@@ -154,6 +352,20 @@ impl EmulatorCore for GameBoyColor {
         Ok(())
     }
 
+    fn snapshot_ram(&self, snapshot: &mut RamSnapshot) {
+        snapshot.clear();
+        for (index, region) in SNAPSHOT_REGIONS.into_iter().enumerate() {
+            snapshot.set_buffer(index, self.core.direct_access(region).data);
+        }
+        for (addresses, region, offset) in POKEABYTE_PROTOCOL_REGIONS {
+            let buffer = SNAPSHOT_REGIONS.iter().position(|r| *r == region).expect("region not snapshotted");
+            snapshot.map(addresses, buffer, offset);
+        }
+
+        let wram = SNAPSHOT_REGIONS.iter().position(|r| *r == DirectAccessRegion::RAM).expect("WRAM not snapshotted");
+        snapshot.map(FULL_WRAM_ADDRESS..FULL_WRAM_ADDRESS + FULL_REGION_SIZE, wram, 0);
+    }
+
     #[inline]
     fn set_speed(&mut self, speed: f64) {
         self.core.set_clock_multiplier(speed);
@@ -186,12 +398,73 @@ impl EmulatorCore for GameBoyColor {
             | (input.d_left as u8) << InputButton::Left
             | (input.d_right as u8) << InputButton::Right;
         into.push(mask);
+
+        // Only encode tilt for cartridges that can use it so other replays don't grow.
+        if self.has_accelerometer {
+            let (x, y) = input.tilt.unwrap_or_default();
+            into.extend_from_slice(&x.to_le_bytes());
+            into.extend_from_slice(&y.to_le_bytes());
+        }
     }
 
     #[inline]
     fn set_input_encoded(&mut self, input: &[u8]) {
-        debug_assert!(input.len() == 1, "set_input_encoded with wrong number of bytes {}", input.len());
+        debug_assert!(matches!(input.len(), 1 | 5), "set_input_encoded with wrong number of bytes {}", input.len());
         self.core.set_input_button_mask(input[0]);
+
+        if self.has_accelerometer {
+            let (x, y) = match input {
+                [_, x0, x1, y0, y1] => (i16::from_le_bytes([*x0, *x1]), i16::from_le_bytes([*y0, *y1])),
+                _ => (0, 0)
+            };
+            let gb = self.sameboy_instance();
+
+            // SAFETY: `gb` is the instance owned by `self.core`, which is not running.
+            unsafe { GB_set_accelerometer_values(gb, x as f64 / i16::MAX as f64, y as f64 / i16::MAX as f64) };
+        }
+    }
+
+    fn decode_input(&self, input: &[u8]) -> Option<Input> {
+        // Replays recorded before tilt was encoded only have buttons, even for MBC7 cartridges.
+        let valid_len = match input.len() {
+            1 => true,
+            5 => self.has_accelerometer,
+            _ => false
+        };
+        if !valid_len {
+            return None
+        }
+
+        let mask = input[0];
+        let pressed = |button: InputButton| mask & (1u8 << button) != 0;
+        let mut decoded = Input {
+            a: pressed(InputButton::A),
+            b: pressed(InputButton::B),
+            start: pressed(InputButton::Start),
+            select: pressed(InputButton::Select),
+            d_up: pressed(InputButton::Up),
+            d_down: pressed(InputButton::Down),
+            d_left: pressed(InputButton::Left),
+            d_right: pressed(InputButton::Right),
+            ..Input::new()
+        };
+
+        if let [_, x0, x1, y0, y1] = *input {
+            let x = i16::from_le_bytes([x0, x1]);
+            let y = i16::from_le_bytes([y0, y1]);
+            decoded.tilt = (x != 0 || y != 0).then_some((x, y));
+        }
+
+        Some(decoded)
+    }
+
+    fn input_descriptor(&self) -> InputDescriptor {
+        if self.has_accelerometer {
+            GAME_BOY_INPUTS.with(InputKind::Tilt)
+        }
+        else {
+            GAME_BOY_INPUTS
+        }
     }
 
     #[inline]
@@ -214,6 +487,11 @@ impl EmulatorCore for GameBoyColor {
         core::mem::swap(&mut first_screen.pixels, &mut screen_data.pixels);
     }
 
+    #[inline]
+    fn set_rendering_enabled(&mut self, enabled: bool) {
+        self.core.set_rendering_enabled(enabled);
+    }
+
     #[inline]
     fn hard_reset(&mut self) {
         self.core.reset();
@@ -227,6 +505,186 @@ impl EmulatorCore for GameBoyColor {
         }
     }
 
+    #[inline]
+    fn soft_reset(&mut self) -> bool {
+        // Retains HRAM, VRAM, OAM, palettes, and DMA state, like quickly switching off and on
+        self.core.partial_reset();
+        true
+    }
+
+    fn set_printer_connected(&mut self, connected: bool) -> bool {
+        self.printer_connected = connected;
+        self.update_serial_connection();
+        true
+    }
+
+    fn take_printed_pages(&mut self) -> Vec<ScreenData> {
+        // SAFETY: This won't leave this function.
+        let printed_pages = unsafe { &mut *self.callback_data.printed_pages.get() };
+        core::mem::take(printed_pages)
+    }
+
+    fn set_serial_capture_enabled(&mut self, enabled: bool) -> bool {
+        if enabled && self.printer_connected {
+            return false
+        }
+        self.serial_capture = enabled;
+        self.update_serial_connection();
+        true
+    }
+
+    fn take_serial_output(&mut self) -> Vec<u8> {
+        // SAFETY: This won't leave this function.
+        let serial_output = unsafe { &mut *self.callback_data.serial_output.get() };
+        core::mem::take(serial_output)
+    }
+
+    fn set_audio_sample_rate(&mut self, sample_rate: u32) -> bool {
+        self.core.set_sample_rate(sample_rate);
+        self.audio_sample_rate = sample_rate;
+
+        // Half a second of stereo samples
+        self.callback_data.max_audio_samples.store(sample_rate as usize, Ordering::Relaxed);
+        if sample_rate == 0 {
+            // SAFETY: This won't leave this function.
+            unsafe { &mut *self.callback_data.audio_samples.get() }.clear();
+        }
+        true
+    }
+
+    fn get_audio_sample_rate(&self) -> u32 {
+        self.audio_sample_rate
+    }
+
+    fn drain_audio_samples(&mut self, into: &mut Vec<i16>) {
+        // SAFETY: This won't leave this function.
+        let audio_samples = unsafe { &mut *self.callback_data.audio_samples.get() };
+        into.append(audio_samples);
+    }
+
+    fn get_peripheral_output(&self) -> PeripheralOutput {
+        PeripheralOutput {
+            rumble_amplitude: f64::from_bits(self.callback_data.rumble_amplitude.load(Ordering::Relaxed)),
+            infrared_led: self.callback_data.infrared_led.load(Ordering::Relaxed)
+        }
+    }
+
+    fn set_color_correction(&mut self, color_correction: ColorCorrection) -> bool {
+        let mode = match color_correction.mode {
+            ColorCorrectionMode::Disabled => safeboy::ColorCorrectionMode::Disabled,
+            ColorCorrectionMode::CorrectCurves => safeboy::ColorCorrectionMode::CorrectCurves,
+            ColorCorrectionMode::ModernAccurate => safeboy::ColorCorrectionMode::ModernAccurate,
+            ColorCorrectionMode::ModernBalanced => safeboy::ColorCorrectionMode::ModernBalanced,
+            ColorCorrectionMode::ModernBoostContrast => safeboy::ColorCorrectionMode::ModernBoostContrast,
+            ColorCorrectionMode::ReduceContrast => safeboy::ColorCorrectionMode::ReduceContrast,
+            ColorCorrectionMode::LowContrast => safeboy::ColorCorrectionMode::LowContrast
+        };
+        self.core.set_color_correction_mode(mode);
+        self.core.set_light_temperature(color_correction.light_temperature.clamp(-1.0, 1.0));
+        true
+    }
+
+    fn resolve_cheat(&self, cheat: &CheatCode) -> Result<Vec<CheatPatch>, &'static str> {
+        match *cheat {
+            CheatCode::GameShark { code_type, address, value } => {
+                let bank = (code_type != 0x01).then_some(code_type & 0xF);
+                let address = game_shark_address(address, bank).ok_or("GameShark code is for an unsupported address")?;
+                if self.read_ram(address, &mut [0]).is_err() {
+                    return Err("GameShark code is for a RAM bank this game does not have")
+                }
+                Ok(alloc::vec![CheatPatch { address, value, restore: None }])
+            },
+            CheatCode::GameGenie { address, value, compare } => {
+                // Addresses past the first bank can be in any bank but the first
+                let rom = self.core.direct_access(DirectAccessRegion::ROM).data;
+                let address = address as usize;
+                let offsets = match address {
+                    ..ROM_BANK_SIZE => address..address + 1,
+                    _ => address..rom.len()
+                };
+
+                let patches: Vec<CheatPatch> = offsets
+                    .step_by(ROM_BANK_SIZE)
+                    .filter_map(|offset| {
+                        let original = *rom.get(offset)?;
+                        compare.is_none_or(|c| c == original).then_some(CheatPatch {
+                            address: FULL_ROM_ADDRESS + offset as u32,
+                            value,
+                            restore: Some(original)
+                        })
+                    })
+                    .collect();
+
+                match patches.is_empty() {
+                    true => Err("Game Genie code does not match anything in this game's ROM"),
+                    false => Ok(patches)
+                }
+            }
+        }
+    }
+
+    fn list_options(&self) -> Vec<CoreOption> {
+        self.options.to_vec()
+    }
+
+    fn set_option(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let Some(option) = self.options.iter_mut().find(|o| o.key == key) else {
+            return Err(alloc::format!("unknown option {key}"))
+        };
+        let Some(&value) = option.values.iter().find(|v| **v == value) else {
+            return Err(alloc::format!("invalid value {value} for option {key}"))
+        };
+
+        match key {
+            OPTION_RTC_MODE => self.core.set_rtc_mode(match value {
+                "sync_to_host" => RtcMode::SyncToHost,
+                _ => RtcMode::Accurate
+            }),
+            OPTION_ALLOW_ILLEGAL_INPUTS => self.core.set_allow_illegal_inputs(value == "true"),
+            _ => unreachable!("option {key} is listed but not handled")
+        }
+
+        option.value = value;
+        Ok(())
+    }
+
+    fn core_config_blob(&self) -> ByteVec {
+        // Every option currently affects determinism, so store all of them as key=value lines.
+        let mut blob = String::new();
+        for option in &self.options {
+            blob.push_str(option.key);
+            blob.push('=');
+            blob.push_str(option.value);
+            blob.push('\n');
+        }
+        ByteVec::from(blob.as_bytes())
+    }
+
+    fn apply_core_config_blob(&mut self, blob: &[u8]) -> Result<(), String> {
+        let blob = core::str::from_utf8(blob).map_err(|_| "core config is non-UTF-8".to_owned())?;
+        let mut values = Vec::new();
+
+        // Validate everything first so nothing is applied if any of it is bad.
+        for line in blob.lines() {
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(alloc::format!("invalid core config line {line}"))
+            };
+            let Some(option) = self.options.iter().find(|o| o.key == key) else {
+                return Err(alloc::format!("unknown option {key}"))
+            };
+            if !option.values.contains(&value) {
+                return Err(alloc::format!("invalid value {value} for option {key}"))
+            }
+            values.push((key, value));
+        }
+
+        for (key, value) in values {
+            self.set_option(key, value)?;
+        }
+
+        Ok(())
+    }
+
     fn replay_console_type(&self) -> Option<ReplayConsoleType> {
         match self.core.is_cgb() {
             true => Some(ReplayConsoleType::GameBoyColor),
@@ -256,6 +714,12 @@ impl EmulatorCore for GameBoyColor {
             safeboy::GB_VERSION
         }
     }
+
+    #[inline]
+    fn ticks_per_second(&self) -> u64 {
+        // SameBoy always reports time in 8 MiHz ticks, regardless of the CPU speed
+        8 * 1024 * 1024
+    }
 }
 
 static GB_VERSION_WITH_HACKS: Lazy<String> = Lazy::new(|| {
@@ -1,6 +1,7 @@
 use alloc::vec::Vec;
 use spin::Lazy;
-use crate::emulator::{EmulatorCore, Input, RunTime, ScreenData, ScreenDataEncoding};
+use crate::{CheatCode, CheatPatch};
+use crate::emulator::{ColorCorrection, CoreOption, EmulatorCore, Input, InputDescriptor, PeripheralOutput, RamSnapshot, RunTime, ScreenData, ScreenDataEncoding};
 use alloc::string::String;
 use supershuckie_replay_recorder::replay_file::{ReplayConsoleType, ReplayHeaderBlake3Hash};
 
@@ -25,12 +26,17 @@ static NULL_EMULATOR_SCREEN: Lazy<ScreenData> = Lazy::new(|| {
 impl EmulatorCore for NullEmulatorCore {
     fn run(&mut self) -> RunTime {
         RunTime {
-            frames: 0
+            frames: 0,
+            ticks: 0
         }
     }
 
     fn run_unlocked(&mut self) -> RunTime {
-        self.run()
+        // Finish a frame so that anything running until the next frame doesn't spin forever
+        RunTime {
+            frames: 1,
+            ticks: 0
+        }
     }
 
     fn read_ram(&self, address: u32, into: &mut [u8]) -> Result<(), &'static str> {
@@ -41,6 +47,10 @@ impl EmulatorCore for NullEmulatorCore {
         Err("unsupported")
     }
 
+    fn snapshot_ram(&self, snapshot: &mut RamSnapshot) {
+        snapshot.clear();
+    }
+
     fn set_speed(&mut self, speed: f64) {
 
     }
@@ -69,6 +79,14 @@ impl EmulatorCore for NullEmulatorCore {
 
     }
 
+    fn decode_input(&self, input: &[u8]) -> Option<Input> {
+        input.is_empty().then(Input::new)
+    }
+
+    fn input_descriptor(&self) -> InputDescriptor {
+        InputDescriptor::new()
+    }
+
     fn get_screens(&self) -> &[ScreenData] {
         core::slice::from_ref(&*NULL_EMULATOR_SCREEN)
     }
@@ -77,10 +95,64 @@ impl EmulatorCore for NullEmulatorCore {
         screens.fill(NULL_EMULATOR_SCREEN.clone())
     }
 
+    fn set_rendering_enabled(&mut self, enabled: bool) {
+
+    }
+
     fn hard_reset(&mut self) {
         
     }
 
+    fn soft_reset(&mut self) -> bool {
+        false
+    }
+
+    fn set_printer_connected(&mut self, connected: bool) -> bool {
+        false
+    }
+
+    fn take_printed_pages(&mut self) -> Vec<ScreenData> {
+        Vec::new()
+    }
+
+    fn set_serial_capture_enabled(&mut self, enabled: bool) -> bool {
+        false
+    }
+
+    fn take_serial_output(&mut self) -> Vec<u8> {
+        Vec::new()
+    }
+
+    fn set_audio_sample_rate(&mut self, sample_rate: u32) -> bool {
+        false
+    }
+
+    fn get_audio_sample_rate(&self) -> u32 {
+        0
+    }
+
+    fn drain_audio_samples(&mut self, into: &mut Vec<i16>) {}
+
+    fn get_peripheral_output(&self) -> PeripheralOutput {
+        PeripheralOutput::default()
+    }
+
+    fn set_color_correction(&mut self, _color_correction: ColorCorrection) -> bool {
+        false
+    }
+
+    fn resolve_cheat(&self, cheat: &CheatCode) -> Result<Vec<CheatPatch>, &'static str> {
+        Err("unsupported")
+    }
+
+    fn list_options(&self) -> Vec<CoreOption> {
+        Vec::new()
+    }
+
+    fn set_option(&mut self, key: &str, value: &str) -> Result<(), String> {
+        Err(alloc::format!("unknown option {key}"))
+    }
+
     fn replay_console_type(&self) -> Option<ReplayConsoleType> {
         None
     }
@@ -96,4 +168,8 @@ impl EmulatorCore for NullEmulatorCore {
     fn core_name(&self) -> &'static str {
         "Null"
     }
+
+    fn ticks_per_second(&self) -> u64 {
+        1
+    }
 }
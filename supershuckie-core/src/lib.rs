@@ -6,22 +6,43 @@ extern crate alloc;
 #[cfg(feature = "std")]
 extern crate std;
 
-use crate::emulator::{EmulatorCore, Input, PartialReplayRecordMetadata, RunTime};
+use crate::emulator::{ColorCorrection, CoreOption, EmulatorCore, Input, InputDescriptor, PartialReplayRecordMetadata, PeripheralOutput, RunTime, ScreenData};
 use alloc::borrow::ToOwned;
 use alloc::boxed::Box;
+use alloc::collections::VecDeque;
 use alloc::format;
 use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec;
 use alloc::vec::Vec;
+use core::any::Any;
 use core::fmt::{Display, Formatter};
 use core::num::NonZeroU64;
-use supershuckie_replay_recorder::replay_file::playback::{ReplayFilePlayer, ReplaySeekError};
-use supershuckie_replay_recorder::replay_file::record::{NonBlockingReplayFileRecorder, ReplayFileRecorder, ReplayFileRecorderFns, ReplayFileSink, ReplayFileWriteError};
+use core::ops::Range;
+use core::sync::atomic::{AtomicBool, Ordering};
+use core::time::Duration;
+use supershuckie_replay_recorder::replay_file::playback::{ReplayFilePlayer, ReplayPlayerPosition, ReplaySeekError};
+use supershuckie_replay_recorder::replay_file::record::{LoadSaveStateMode, NonBlockingReplayFileRecorder, ReplayFileRecorder, ReplayFileRecorderFns, ReplayFileSink, ReplayFileWriteError};
 use supershuckie_replay_recorder::replay_file::{blake3_hash_to_ascii, ReplayFileMetadata, ReplayHeaderBlake3Hash, ReplayPatchFormat};
-use supershuckie_replay_recorder::{ByteVec, Packet, TimestampMillis, UnsignedInteger};
+use crate::rewind::RewindBuffer;
+use crate::determinism::DeterminismCheckRecorder;
+use supershuckie_replay_recorder::{apply_state_diff, blake3_hash, ByteVec, InputBuffer, MemoryWrite, Packet, TimestampMillis, UnsignedInteger};
 
 pub mod emulator;
 
-pub use supershuckie_replay_recorder::Speed;
+pub use supershuckie_replay_recorder::{Speed, SpeedBounds, SpeedError};
+
+mod rewind;
+
+pub use rewind::RewindSettings;
+
+mod determinism;
+
+pub use determinism::{DeterminismCheckSettings, DeterminismMismatch};
+
+mod cheat;
+
+pub use cheat::{CheatCode, CheatParseError, CheatPatch};
 
 #[cfg(feature = "std")]
 mod thread;
@@ -29,6 +50,21 @@ mod thread;
 #[cfg(feature = "std")]
 pub use thread::*;
 
+#[cfg(feature = "std")]
+mod profile;
+
+#[cfg(feature = "std")]
+pub use profile::*;
+
+#[cfg(feature = "std")]
+mod netplay;
+
+#[cfg(feature = "std")]
+mod pokeabyte_mirror;
+
+#[cfg(feature = "std")]
+pub use netplay::*;
+
 /// Wrapper for [`EmulatorCore`] that provides useful desktop emulator functionality.
 pub struct SuperShuckieCore {
     core: Box<dyn EmulatorCore>,
@@ -37,6 +73,26 @@ pub struct SuperShuckieCore {
 
     replay_player: Option<ReplayFilePlayer>,
 
+    /// The encoded input last applied by the replay player.
+    replay_input: InputBuffer,
+
+    /// Recently played frames, used for stepping backwards through a replay.
+    reverse_step_cache: Vec<ReverseStepCacheEntry>,
+
+    /// The core config from before the replay player's config was applied, restored on detach.
+    pre_replay_core_config: Option<ByteVec>,
+
+    /// Captured states for rewinding, if enabled (see [`SuperShuckieCore::set_rewind_settings`]).
+    rewind: Option<RewindBuffer>,
+
+    /// If true, each frame rewinds instead of capturing a state.
+    rewinding: bool,
+
+    /// Determinism check to use when not recording (see [`SuperShuckieCore::set_determinism_check`]).
+    ///
+    /// While recording, this is moved into the recorder.
+    determinism_check: Option<DeterminismCheckSettings>,
+
     /// The current user-defined input.
     base_input: Input,
 
@@ -49,7 +105,20 @@ pub struct SuperShuckieCore {
     rapid_fire_input: Option<SuperShuckieRapidFire>,
 
     /// Queued writes, if any
-    writes: Vec<QueuedWrite>,
+    ///
+    /// Each batch is applied (and recorded) together.
+    writes: Vec<Vec<QueuedWrite>>,
+
+    /// Cheats applied every frame (see [`SuperShuckieCore::set_cheats`]).
+    cheats: Vec<ActiveCheat>,
+
+    /// Writes flushed on the previous frame, used to skip recording redundant writes.
+    previous_frame_writes: Vec<QueuedWrite>,
+
+    /// Writes flushed on the current frame ([`SuperShuckieCore::current_frame_writes_frame`]).
+    current_frame_writes: Vec<QueuedWrite>,
+    current_frame_writes_frame: u64,
+    ram_scratch_buffer: Vec<u8>,
 
     /// Toggled input, if any.
     ///
@@ -59,21 +128,106 @@ pub struct SuperShuckieCore {
     /// The "total" input that was actually applied.
     current_input: Input,
 
+    /// Inputs waiting to be applied, oldest first (see [`SuperShuckieCore::set_input_delay`]).
+    delayed_inputs: VecDeque<Input>,
+
+    /// Input to apply next frame instead of the user's input, bypassing the input delay.
+    input_override: Option<Input>,
+
     mid_frame: bool,
     replay_stalled: bool,
 
+    /// If true, failed RAM accesses are reported in [`SuperShuckieCore::ram_access_error`] and stall
+    /// replay playback instead of being ignored.
+    strict_ram_access: bool,
+
+    /// The first RAM access that failed in strict mode since this was last taken.
+    ram_access_error: Option<RamAccessError>,
+
     input_scratch_buffer: Vec<u8>,
     starting_milliseconds: TimestampMillis,
     total_milliseconds: TimestampMillis,
     paused_timer_at: Option<TimestampMillis>,
     game_speed: Speed,
 
+    /// Speed last written to the replay being recorded (see [`SuperShuckieCore::record_speed_change_if_needed`]).
+    recorded_speed: Speed,
+    frames_since_speed_change: u64,
+
     frames_since_last_keyframe: u64,
     frames_per_keyframe: u64,
+    keyframe_on_events: bool,
+    load_save_state_mode: LoadSaveStateMode,
     total_frames: u64,
+
+    /// Ticks emulated in the current frame.
+    frame_ticks: u64,
+
+    /// Ticks emulated since the timer was restarted.
+    total_ticks: u64,
+
+    /// Wall clock time, in milliseconds, used for display purposes only.
+    wall_clock_milliseconds: TimestampMillis,
+
+    /// Wall clock time, in milliseconds, before rounding (see [`SuperShuckieCore::smooth_timestamp`]).
+    smoothed_milliseconds: f64,
+
+    /// Time spent on the replay recorder (see [`SuperShuckieCore::take_recorder_time`]).
+    recorder_time: Duration,
 }
 
-#[derive(Clone, Debug)]
+/// Remove the determinism check from `recorder`, if any, returning the unwrapped recorder and the
+/// check's settings.
+fn unwrap_determinism_check(recorder: Box<dyn ReplayFileRecorderFns>) -> (Box<dyn ReplayFileRecorderFns>, Option<DeterminismCheckSettings>) {
+    if !(Box::as_ref(&recorder) as &dyn Any).is::<DeterminismCheckRecorder>() {
+        return (recorder, None)
+    }
+
+    let recorder: Box<dyn Any> = recorder;
+    let (recorder, settings) = recorder
+        .downcast::<DeterminismCheckRecorder>()
+        .expect("should be a determinism check recorder")
+        .into_parts();
+    (recorder, Some(settings))
+}
+
+/// Maximum difference between the smoothed timestamp and the wall clock before snapping to the wall
+/// clock.
+const MAX_TIMESTAMP_DRIFT_MILLISECONDS: f64 = 250.0;
+
+/// Fraction of the difference between the smoothed timestamp and the wall clock corrected per frame.
+const TIMESTAMP_CORRECTION_RATE: f64 = 1.0 / 32.0;
+
+/// Minimum relative change in speed that is recorded after [`MIN_FRAMES_BETWEEN_SPEED_CHANGES`].
+///
+/// Smaller changes are only recorded after [`SPEED_CHANGE_SETTLE_FRAMES`].
+const SIGNIFICANT_SPEED_CHANGE: f64 = 0.05;
+
+/// Minimum number of frames between recorded speed changes.
+const MIN_FRAMES_BETWEEN_SPEED_CHANGES: u64 = 4;
+
+/// Number of frames after which an insignificant speed change is recorded anyway.
+const SPEED_CHANGE_SETTLE_FRAMES: u64 = 30;
+
+/// Maximum number of frames kept in [`SuperShuckieCore::reverse_step_cache`].
+const REVERSE_STEP_CACHE_SIZE: u64 = 60;
+
+struct ReverseStepCacheEntry {
+    state: Vec<u8>,
+    position: ReplayPlayerPosition,
+    input: InputBuffer,
+    speed: Speed,
+    total_frames: u64,
+    total_milliseconds: TimestampMillis
+}
+
+#[derive(Clone, PartialEq, Debug)]
+struct ActiveCheat {
+    code: String,
+    patches: Vec<CheatPatch>
+}
+
+#[derive(Clone, PartialEq, Debug)]
 struct QueuedWrite {
     address: u32,
     data: ByteVec
@@ -122,18 +276,42 @@ impl SuperShuckieCore {
             next_input: None,
             rapid_fire_input: None,
             writes: Vec::new(),
+            cheats: Vec::new(),
+            previous_frame_writes: Vec::new(),
+            current_frame_writes: Vec::new(),
+            current_frame_writes_frame: 0,
+            ram_scratch_buffer: Vec::new(),
             toggled_input: None,
             current_input: Default::default(),
+            delayed_inputs: VecDeque::new(),
+            input_override: None,
             mid_frame: false,
             input_scratch_buffer: Vec::new(),
             total_milliseconds: 0,
             starting_milliseconds: timestamp_provider.get_timestamp(),
             game_speed: Default::default(),
+            recorded_speed: Default::default(),
+            frames_since_speed_change: 0,
             frames_since_last_keyframe: 0,
             frames_per_keyframe: 0,
+            keyframe_on_events: false,
+            load_save_state_mode: LoadSaveStateMode::default(),
             total_frames: 0,
+            frame_ticks: 0,
+            total_ticks: 0,
+            wall_clock_milliseconds: 0,
+            smoothed_milliseconds: 0.0,
+            recorder_time: Duration::ZERO,
             replay_player: None,
+            replay_input: InputBuffer::new(),
+            reverse_step_cache: Vec::new(),
+            pre_replay_core_config: None,
+            rewind: None,
+            rewinding: false,
+            determinism_check: None,
             replay_stalled: false,
+            strict_ram_access: false,
+            ram_access_error: None,
             paused_timer_at: None,
             core: emulator_core,
             timestamp_provider
@@ -168,15 +346,37 @@ impl SuperShuckieCore {
         }
     }
 
+    /// Run unlocked until exactly one more frame is finished (e.g. for frame advance).
+    ///
+    /// If the current frame was only partially run, this just finishes it.
+    pub fn run_single_frame(&mut self) {
+        if !self.mid_frame {
+            self.run_unlocked();
+        }
+        self.finish_current_frame();
+    }
+
     /// Enqueue a write for the next frame.
     pub fn enqueue_write(&mut self, address: u32, data: ByteVec) {
-        self.writes.push(QueuedWrite { address, data });
+        self.writes.push(vec![QueuedWrite { address, data }]);
+        self.flush_writes();
+    }
+
+    /// Enqueue multiple writes for the next frame.
+    ///
+    /// All writes are applied together (in order) before the next frame, and they are recorded as a
+    /// single packet, so they can never be split across frames.
+    pub fn enqueue_writes(&mut self, writes: &[(u32, ByteVec)]) {
+        if writes.is_empty() {
+            return
+        }
+        self.writes.push(writes.iter().map(|(address, data)| QueuedWrite { address: *address, data: data.clone() }).collect());
         self.flush_writes();
     }
 
     /// Pause the current timer.
     pub fn pause_timer(&mut self) {
-        self.paused_timer_at = Some(self.total_milliseconds + self.starting_milliseconds);
+        self.paused_timer_at = Some(self.wall_clock_milliseconds + self.starting_milliseconds);
     }
 
     /// Unpause the current timer if it is currently paused.
@@ -189,11 +389,28 @@ impl SuperShuckieCore {
         self.starting_milliseconds = self.starting_milliseconds.wrapping_add(unpaused_time.wrapping_sub(paused_time));
     }
 
+    /// Replace the timestamp provider, keeping the timer (and whether it is paused) where it is.
+    pub fn set_timestamp_provider(&mut self, mut timestamp_provider: Box<dyn MonotonicTimestampProvider>) {
+        let old_now = self.timestamp_provider.get_timestamp();
+        let new_now = timestamp_provider.get_timestamp();
+        let elapsed = self.paused_timer_at.unwrap_or(old_now).wrapping_sub(self.starting_milliseconds);
+
+        self.starting_milliseconds = new_now.wrapping_sub(elapsed);
+        if self.paused_timer_at.is_some() {
+            self.paused_timer_at = Some(new_now);
+        }
+        self.timestamp_provider = timestamp_provider;
+    }
+
     fn restart_timer(&mut self) {
         self.paused_timer_at = None;
         self.starting_milliseconds = self.timestamp_provider.get_timestamp();
         self.total_milliseconds = 0;
         self.total_frames = 0;
+        self.frame_ticks = 0;
+        self.total_ticks = 0;
+        self.wall_clock_milliseconds = 0;
+        self.smoothed_milliseconds = 0.0;
     }
 
     /// Get an immutable reference to the underlying core.
@@ -201,10 +418,81 @@ impl SuperShuckieCore {
         self.core.as_ref()
     }
 
+    /// Get the number of frames elapsed in the replay being recorded or played back.
+    pub fn get_elapsed_frames(&self) -> UnsignedInteger {
+        self.total_frames
+    }
+
+    /// Returns `true` if the replay being played back can't go any further (e.g. it ended).
+    pub fn is_replay_stalled(&self) -> bool {
+        self.replay_stalled
+    }
+
+    /// Set whether failed RAM reads and writes are reported instead of being ignored.
+    ///
+    /// In strict mode, a failed write during replay playback stalls the replay, and a failed write
+    /// while recording is not recorded. Either way, the failure can be taken with
+    /// [`SuperShuckieCore::take_ram_access_error`].
+    pub fn set_strict_ram_access(&mut self, strict: bool) {
+        self.strict_ram_access = strict;
+        if !strict {
+            self.ram_access_error = None;
+        }
+    }
+
+    /// Take the first RAM access that failed in strict mode since the last call, if any.
+    pub fn take_ram_access_error(&mut self) -> Option<RamAccessError> {
+        self.ram_access_error.take()
+    }
+
+    /// Report a failed RAM access if in strict mode, returning `true` if it was reported.
+    pub(crate) fn report_ram_access_error(&mut self, kind: RamAccessKind, address: u32, length: usize, error: &'static str) -> bool {
+        if !self.strict_ram_access {
+            return false
+        }
+
+        if self.ram_access_error.is_none() {
+            self.ram_access_error = Some(RamAccessError { kind, address, length, frame: self.total_frames, error });
+        }
+        true
+    }
+
     /// Set the speed multiplier of the game.
+    ///
+    /// When recording, small or rapid changes (e.g. from analog turbo) are not recorded right away
+    /// to avoid bloating the replay; see [`SuperShuckieCore::record_speed_change_if_needed`].
     pub fn set_speed(&mut self, speed: Speed) {
         self.game_speed = Speed::from_multiplier_float(speed.into_multiplier_float());
         self.core.set_speed(speed.into_multiplier_float());
+        self.record_speed_change_if_needed();
+    }
+
+    /// Record the current speed if it differs from the recorded speed and enough frames passed.
+    ///
+    /// Changes of more than [`SIGNIFICANT_SPEED_CHANGE`] are recorded at most once every
+    /// [`MIN_FRAMES_BETWEEN_SPEED_CHANGES`] frames. Anything smaller waits for
+    /// [`SPEED_CHANGE_SETTLE_FRAMES`] frames so the speed the user settles on still ends up in the
+    /// replay.
+    fn record_speed_change_if_needed(&mut self) {
+        if self.replay_file_recorder.is_none() || self.game_speed == self.recorded_speed {
+            return
+        }
+
+        let change = (self.game_speed.into_multiplier_float() / self.recorded_speed.into_multiplier_float() - 1.0).abs();
+        let frames_needed = if change > SIGNIFICANT_SPEED_CHANGE {
+            MIN_FRAMES_BETWEEN_SPEED_CHANGES
+        }
+        else {
+            SPEED_CHANGE_SETTLE_FRAMES
+        };
+
+        if self.frames_since_speed_change < frames_needed {
+            return
+        }
+
+        let speed = self.game_speed;
+        self.recorded_speed = speed;
+        self.frames_since_speed_change = 0;
         self.with_recorder(|r| r.set_speed(speed));
     }
 
@@ -235,10 +523,32 @@ impl SuperShuckieCore {
                             break;
                         }
                         Packet::WriteMemory { address, data } => {
-                            self.core.write_ram(*address as u32, data.as_slice()).expect("failed to write RAM (and this was not handled)");
+                            if let Err(e) = self.core.write_ram(*address as u32, data.as_slice())
+                                && self.report_ram_access_error(RamAccessKind::Write, *address as u32, data.len(), e) {
+                                self.replay_stalled = true;
+                                break
+                            }
+                        }
+                        Packet::WriteMemoryMulti { writes } => {
+                            let mut failed = None;
+                            for MemoryWrite { address, data } in writes {
+                                if let Err(e) = self.core.write_ram(*address as u32, data.as_slice()) {
+                                    failed.get_or_insert((*address as u32, data.len(), e));
+                                }
+                            }
+                            if let Some((address, length, e)) = failed
+                                && self.report_ram_access_error(RamAccessKind::Write, address, length, e) {
+                                self.replay_stalled = true;
+                                break
+                            }
                         }
                         Packet::ChangeInput { data } => {
+                            if !self.is_valid_encoded_input(data.as_slice()) {
+                                self.replay_stalled = true;
+                                break
+                            }
                             self.core.set_input_encoded(data.as_slice());
+                            self.replay_input = data.clone();
                         }
                         Packet::ChangeSpeed { speed } => {
                             self.set_speed(*speed);
@@ -246,9 +556,34 @@ impl SuperShuckieCore {
                         Packet::ResetConsole => {
                             self.core.hard_reset();
                         }
+                        Packet::SoftResetConsole => {
+                            self.core.soft_reset();
+                        }
                         Packet::LoadSaveState { state } => {
                             let _ = self.core.load_save_state(state.as_slice());
                         },
+                        Packet::RestoreState { keyframe_index } => {
+                            let keyframe_index = *keyframe_index;
+                            match player.get_keyframe_state(keyframe_index) {
+                                Ok(state) => { let _ = self.core.load_save_state(state.as_slice()); },
+                                Err(_) => {
+                                    self.replay_stalled = true;
+                                    break
+                                }
+                            }
+                        },
+                        Packet::LoadSaveStateDiff { keyframe_index, diff } => {
+                            let (keyframe_index, diff) = (*keyframe_index, diff.clone());
+                            let state = player
+                                .get_keyframe_state(keyframe_index)
+                                .ok()
+                                .and_then(|base| apply_state_diff(base.as_slice(), diff.as_slice()).ok());
+                            let Some(state) = state else {
+                                self.replay_stalled = true;
+                                break
+                            };
+                            let _ = self.core.load_save_state(state.as_slice());
+                        },
                         Packet::Bookmark { .. } => {}
                         Packet::Keyframe { .. } => {}
                         Packet::CompressedBlob { .. } => unreachable!("compressed blob")
@@ -264,6 +599,13 @@ impl SuperShuckieCore {
         self.replay_player = Some(player);
     }
 
+    /// Return true if the encoded input can be decoded and only uses inputs the core supports.
+    fn is_valid_encoded_input(&self, input: &[u8]) -> bool {
+        self.core
+            .decode_input(input)
+            .is_some_and(|i| self.core.input_descriptor().contains_all(InputDescriptor::of_input(&i)))
+    }
+
     fn before_run(&mut self) {
         self.handle_replay();
         self.update_input();
@@ -273,6 +615,7 @@ impl SuperShuckieCore {
     fn after_run(&mut self, time: &RunTime) {
         self.do_frame_timekeeping(&time);
         self.push_keyframe_if_needed();
+        self.update_rewind(time);
     }
 
     fn flush_writes(&mut self) {
@@ -284,12 +627,48 @@ impl SuperShuckieCore {
             return
         }
 
+        if self.current_frame_writes_frame != self.total_frames {
+            if self.current_frame_writes_frame.wrapping_add(1) == self.total_frames {
+                core::mem::swap(&mut self.previous_frame_writes, &mut self.current_frame_writes);
+            }
+            else {
+                self.previous_frame_writes.clear();
+            }
+            self.current_frame_writes.clear();
+            self.current_frame_writes_frame = self.total_frames;
+            self.queue_cheat_writes();
+        }
+
         let mut writes = core::mem::take(&mut self.writes);
 
-        for write in writes.drain(..) {
-            let _ = self.core.write_ram(write.address, write.data.as_slice());
+        for batch in writes.drain(..) {
+            let mut recorded = Vec::with_capacity(batch.len());
+
+            for write in batch {
+                let redundant = self.is_redundant_write(&write);
+
+                // In strict mode, don't record writes that never happened
+                if let Err(e) = self.core.write_ram(write.address, write.data.as_slice())
+                    && self.report_ram_access_error(RamAccessKind::Write, write.address, write.data.len(), e) {
+                    continue
+                }
+
+                if !redundant && self.replay_file_recorder.is_some() {
+                    recorded.push(MemoryWrite { address: write.address as UnsignedInteger, data: write.data.clone() });
+                }
+
+                self.current_frame_writes.push(write);
+            }
+
             self.with_recorder(|recorder| {
-                let _ = recorder.write_memory(write.address as UnsignedInteger, write.data);
+                let _ = match recorded.len() {
+                    0 => Ok(()),
+                    1 => {
+                        let MemoryWrite { address, data } = recorded.pop().expect("one write");
+                        recorder.write_memory(address, data)
+                    },
+                    _ => recorder.write_memory_multi(recorded)
+                };
             });
         }
 
@@ -297,6 +676,75 @@ impl SuperShuckieCore {
         self.writes = writes;
     }
 
+    /// Queue each cheat's writes as its own batch, so cheats that make a single write are recorded
+    /// as a single `WriteMemory` packet.
+    fn queue_cheat_writes(&mut self) {
+        for cheat in &self.cheats {
+            self.writes.push(cheat.patches.iter().map(|p| QueuedWrite { address: p.address, data: ByteVec::from([p.value].as_slice()) }).collect());
+        }
+    }
+
+    /// Apply the given GameShark and Game Genie codes every frame, replacing any applied before.
+    ///
+    /// Cheats are applied like any other write, so they are recorded in the replay being recorded,
+    /// and they are not applied while a replay is being played back. Patches the game would never
+    /// undo itself (e.g. Game Genie codes, which patch the ROM) are undone when their cheat is
+    /// removed.
+    ///
+    /// Returns `Err` if any code is invalid or does not apply to the loaded game, in which case
+    /// nothing changes.
+    pub fn set_cheats(&mut self, codes: &[String]) -> Result<(), String> {
+        let mut cheats = Vec::with_capacity(codes.len());
+        for code in codes {
+            // Cheats that are already applied can't be resolved again once they have patched the ROM
+            if let Some(cheat) = self.cheats.iter().find(|c| &c.code == code) {
+                cheats.push(cheat.clone());
+                continue
+            }
+
+            let cheat = CheatCode::parse(code).map_err(|e| format!("{code}: {e}"))?;
+            let patches = self.core.resolve_cheat(&cheat).map_err(|e| format!("{code}: {e}"))?;
+            cheats.push(ActiveCheat { code: code.to_owned(), patches });
+        }
+
+        let restores: Vec<(u32, ByteVec)> = self.cheats
+            .iter()
+            .filter(|c| !codes.contains(&c.code))
+            .flat_map(|c| c.patches.iter())
+            .filter_map(|p| Some((p.address, ByteVec::from([p.restore?].as_slice()))))
+            .collect();
+
+        self.cheats = cheats;
+        self.enqueue_writes(&restores);
+        Ok(())
+    }
+
+    /// Write back everything cheats patched that the game would never undo itself.
+    ///
+    /// The cheats stay active, so they are patched again on the next frame writes are made.
+    fn undo_cheat_patches(&mut self) {
+        for patch in self.cheats.iter().flat_map(|c| c.patches.iter()) {
+            if let Some(restore) = patch.restore {
+                let _ = self.core.write_ram(patch.address, &[restore]);
+            }
+        }
+    }
+
+    /// Returns true if `write` is identical to one made on the previous frame and RAM still holds
+    /// that data, in which case the write does nothing and does not need to be recorded.
+    ///
+    /// This keeps frozen values (e.g. from Poke-A-Byte) from adding a packet every frame.
+    fn is_redundant_write(&mut self, write: &QueuedWrite) -> bool {
+        if !self.previous_frame_writes.contains(write) {
+            return false
+        }
+
+        self.ram_scratch_buffer.clear();
+        self.ram_scratch_buffer.resize(write.data.len(), 0);
+        self.core.read_ram(write.address, &mut self.ram_scratch_buffer).is_ok()
+            && self.ram_scratch_buffer.as_slice() == write.data.as_slice()
+    }
+
     /// Enqueue an input for the next frame.
     pub fn enqueue_input(&mut self, input: Input) {
         self.next_input = Some(input);
@@ -310,6 +758,185 @@ impl SuperShuckieCore {
         self.finish_current_frame();
         self.core.hard_reset();
         self.with_recorder(|r| r.reset_console());
+        self.push_event_keyframe();
+    }
+
+    /// Do a soft reset, if supported by the console.
+    ///
+    /// Returns `false` if not supported or if a replay is being played back.
+    pub fn soft_reset(&mut self) -> bool {
+        if self.replay_player.is_some() {
+            return false;
+        }
+        self.finish_current_frame();
+        if !self.core.soft_reset() {
+            return false;
+        }
+        self.with_recorder(|r| r.soft_reset_console());
+        self.push_event_keyframe();
+        true
+    }
+
+    /// Enable rewinding with the given settings, or disable it if `None`.
+    ///
+    /// Previously captured states are discarded.
+    pub fn set_rewind_settings(&mut self, settings: Option<RewindSettings>) {
+        self.rewind = settings.map(RewindBuffer::new);
+        self.rewinding &= self.rewind.is_some();
+    }
+
+    /// Get the current rewind settings, or `None` if rewinding is disabled.
+    pub fn get_rewind_settings(&self) -> Option<RewindSettings> {
+        self.rewind.as_ref().map(RewindBuffer::settings)
+    }
+
+    /// Go back to the newest captured state that is at least `frames` frames old.
+    ///
+    /// If no captured state is that old, the oldest one is used. The state is loaded as if it was a
+    /// save state, so this is recorded if a replay is being recorded.
+    ///
+    /// Returns `false` if rewinding is disabled, nothing was captured yet, or a replay is being
+    /// played back.
+    pub fn rewind_frames(&mut self, frames: u64) -> bool {
+        if self.replay_player.is_some() {
+            return false
+        }
+
+        let Some(mut rewind) = self.rewind.take() else {
+            return false
+        };
+
+        // Keep the buffer out of the way while loading, since loading may run a frame
+        let result = match rewind.rewind(frames) {
+            Some(state) => self.load_save_state(state).is_ok(),
+            None => false
+        };
+        self.rewind = Some(rewind);
+        result
+    }
+
+    /// Rewind every frame until [`SuperShuckieCore::stop_rewinding`] is called.
+    ///
+    /// Each frame goes back by the rewind interval, and no states are captured in the meantime.
+    /// This does nothing if rewinding is disabled.
+    pub fn start_rewinding(&mut self) {
+        self.rewinding = self.rewind.is_some();
+    }
+
+    /// Stop rewinding (see [`SuperShuckieCore::start_rewinding`]).
+    pub fn stop_rewinding(&mut self) {
+        self.rewinding = false;
+    }
+
+    /// Returns `true` if [`SuperShuckieCore::start_rewinding`] is in effect.
+    pub fn is_rewinding(&self) -> bool {
+        self.rewinding
+    }
+
+    fn update_rewind(&mut self, time: &RunTime) {
+        if self.replay_player.is_some() || time.frames == 0 {
+            return
+        }
+
+        let Some(rewind) = self.rewind.as_mut() else {
+            return
+        };
+
+        if self.rewinding {
+            let interval = rewind.settings().interval.get();
+            self.rewind_frames(interval);
+        }
+        else if rewind.advance(time.frames) {
+            rewind.push(self.core.create_save_state());
+        }
+    }
+
+    /// Connect or disconnect a printer, if supported by the console.
+    ///
+    /// Returns `false` if not supported.
+    pub fn set_printer_connected(&mut self, connected: bool) -> bool {
+        self.core.set_printer_connected(connected)
+    }
+
+    /// Set how colors are corrected to look closer to the original hardware's display, if supported.
+    ///
+    /// Returns `false` if not supported.
+    pub fn set_color_correction(&mut self, color_correction: ColorCorrection) -> bool {
+        self.core.set_color_correction(color_correction)
+    }
+
+    /// Get all core-specific options and their current values.
+    pub fn list_core_options(&self) -> Vec<CoreOption> {
+        self.core.list_options()
+    }
+
+    /// Set a core-specific option.
+    ///
+    /// Returns `Err` if the key or value is not valid for the core.
+    pub fn set_core_option(&mut self, key: &str, value: &str) -> Result<(), String> {
+        self.core.set_option(key, value)
+    }
+
+    /// Get the current state of any peripherals that output to the user.
+    pub fn get_peripheral_output(&self) -> PeripheralOutput {
+        self.core.get_peripheral_output()
+    }
+
+    /// Get the input applied to the current frame.
+    ///
+    /// During replay playback, this is the replay's input, decoded with
+    /// [`EmulatorCore::decode_input`]. If it can't be decoded, an empty input is returned.
+    pub fn get_applied_input(&self) -> Input {
+        if self.replay_player.is_none() {
+            return self.current_input
+        }
+        self.core.decode_input(self.replay_input.as_slice()).unwrap_or_default()
+    }
+
+    /// Take all pages printed since the last call.
+    pub fn take_printed_pages(&mut self) -> Vec<ScreenData> {
+        self.core.take_printed_pages()
+    }
+
+    /// Start or stop capturing bytes sent over the serial port, if supported by the console.
+    ///
+    /// Returns `false` if not supported or the serial port is in use (e.g. by a printer).
+    pub fn set_serial_capture_enabled(&mut self, enabled: bool) -> bool {
+        self.core.set_serial_capture_enabled(enabled)
+    }
+
+    /// Take all bytes sent over the serial port since the last call while capture was enabled.
+    pub fn take_serial_output(&mut self) -> Vec<u8> {
+        self.core.take_serial_output()
+    }
+
+    /// Set the rate audio samples are generated at, in Hz, or 0 to stop generating them.
+    ///
+    /// Returns `false` if the console does not support audio.
+    pub fn set_audio_sample_rate(&mut self, sample_rate: u32) -> bool {
+        self.core.set_audio_sample_rate(sample_rate)
+    }
+
+    /// Get the rate audio samples are generated at, in Hz, or 0 if they are not being generated.
+    pub fn get_audio_sample_rate(&self) -> u32 {
+        self.core.get_audio_sample_rate()
+    }
+
+    /// Move all audio samples generated since the last call to the end of `into`.
+    ///
+    /// Samples are signed 16-bit stereo, interleaved left then right.
+    pub fn drain_audio_samples(&mut self, into: &mut Vec<i16>) {
+        self.core.drain_audio_samples(into)
+    }
+
+    /// Add a bookmark to the replay being recorded, if any.
+    pub fn add_replay_bookmark(&mut self, name: String) {
+        if self.replay_file_recorder.is_none() {
+            return
+        }
+        self.finish_current_frame();
+        self.with_recorder(|r| r.add_bookmark(name));
+        self.push_event_keyframe();
     }
 
     /// Set the current rapid fire input.
@@ -348,23 +975,77 @@ impl SuperShuckieCore {
         self.core.save_sram()
     }
 
-    /// Load a save state.
-    pub fn load_save_state(&mut self, state: &[u8]) {
+    /// Load a save state, returning an error if the core rejected it.
+    ///
+    /// Nothing is loaded while playing back a replay.
+    pub fn load_save_state(&mut self, state: &[u8]) -> Result<(), String> {
         if self.replay_player.is_some() {
-            return
+            return Ok(())
         }
 
+        self.core.load_save_state(state)?;
         self.mid_frame = false;
-        let _ = self.core.load_save_state(state);
 
         if self.replay_file_recorder.is_some() {
             self.with_recorder(|r| r.load_save_state(state.into()));
+
+            // Otherwise, the recorder keyframes loaded save states itself
+            if self.load_save_state_mode == LoadSaveStateMode::Embed {
+                self.push_event_keyframe();
+            }
         }
         else {
             self.mid_frame = true;
             self.finish_current_frame();
             let _ = self.core.load_save_state(state);
         }
+
+        Ok(())
+    }
+
+    /// Load `state`, apply each input in `inputs` for exactly one frame, and return the resulting
+    /// save state along with a hash of the given memory regions.
+    ///
+    /// This runs unlocked with rendering disabled, so it is suitable for brute-force search loops.
+    /// Nothing is recorded, and the core is left in the resulting state.
+    ///
+    /// This cannot be used while recording or playing back a replay.
+    pub fn search_inputs(&mut self, state: &[u8], inputs: &[Input], hash_regions: &[Range<u32>]) -> Result<StateSearchResult, StateSearchError> {
+        if self.replay_player.is_some() || self.replay_file_recorder.is_some() {
+            return Err(StateSearchError::ReplayActive)
+        }
+
+        self.core.load_save_state(state).map_err(|description| StateSearchError::LoadStateFailed { description })?;
+        self.mid_frame = false;
+        self.core.set_rendering_enabled(false);
+
+        for input in inputs {
+            self.input_scratch_buffer.clear();
+            self.core.encode_input(*input, &mut self.input_scratch_buffer);
+            self.core.set_input_encoded(self.input_scratch_buffer.as_slice());
+
+            while self.core.run_unlocked().frames == 0 {}
+        }
+
+        self.core.set_rendering_enabled(true);
+
+        // restore the user's input so that it's applied once we resume
+        self.input_scratch_buffer.clear();
+        self.core.encode_input(self.current_input, &mut self.input_scratch_buffer);
+        self.core.set_input_encoded(self.input_scratch_buffer.as_slice());
+
+        let mut memory = Vec::new();
+        for region in hash_regions {
+            let start = memory.len();
+            memory.resize(start + region.len(), 0);
+            self.core.read_ram(region.start, &mut memory[start..])
+                .map_err(|description| StateSearchError::ReadFailed { address: region.start, description })?;
+        }
+
+        Ok(StateSearchResult {
+            state: self.core.create_save_state(),
+            memory_hash: blake3_hash(memory.as_slice())
+        })
     }
 
     /// Set the current toggled input.
@@ -374,6 +1055,24 @@ impl SuperShuckieCore {
         self.toggled_input = input;
     }
 
+    /// Set the number of frames to delay input by.
+    ///
+    /// Input is applied (and recorded) this many frames after it is polled. When growing the delay,
+    /// the currently applied input is held for the extra frames.
+    pub fn set_input_delay(&mut self, frames: usize) {
+        while self.delayed_inputs.len() > frames {
+            self.delayed_inputs.pop_front();
+        }
+        while self.delayed_inputs.len() < frames {
+            self.delayed_inputs.push_front(self.current_input);
+        }
+    }
+
+    /// Get the number of frames input is delayed by.
+    pub fn get_input_delay(&self) -> usize {
+        self.delayed_inputs.len()
+    }
+
     /// Start recording a replay.
     pub fn start_recording_replay<
         FS: ReplayFileSink + Send + Sync + 'static,
@@ -388,15 +1087,19 @@ impl SuperShuckieCore {
         let emulator_core_name = self.core.core_name().to_owned();
         let initial_input = self.current_input;
         let initial_speed = self.game_speed;
+        let load_save_state_mode = partial_replay_record_metadata.settings.load_save_state_mode;
 
         self.finish_current_frame();
 
+        let sram_checksum = sram_checksum(&self.core.save_sram());
         let initial_state = ByteVec::Heap(self.core.create_save_state());
         let mut initial_input_data = Vec::new();
         self.core.encode_input(initial_input, &mut initial_input_data);
         self.core.set_input_encoded(&initial_input_data);
         self.restart_timer();
 
+        let determinism_check = self.determinism_check.take().map(|settings| (settings, initial_state.as_slice().to_vec()));
+
         let recorder = NonBlockingReplayFileRecorder::new(ReplayFileRecorder::new_with_metadata(
             ReplayFileMetadata {
                 console_type,
@@ -407,6 +1110,8 @@ impl SuperShuckieCore {
                 emulator_core_name,
                 patch_format: ReplayPatchFormat::Unpatched,
                 patch_target_checksum: ReplayHeaderBlake3Hash::default(),
+                sram_checksum,
+                core_config: self.core.core_config_blob()
             },
 
             ByteVec::new(),
@@ -419,25 +1124,55 @@ impl SuperShuckieCore {
             partial_replay_record_metadata.final_file,
             partial_replay_record_metadata.temp_file
         )?);
+        let recorder: Box<dyn ReplayFileRecorderFns> = match determinism_check {
+            Some((settings, state)) => self.wrap_determinism_check(Box::new(recorder), settings, Some(state.as_slice())),
+            None => Box::new(recorder)
+        };
 
+        self.recorded_speed = initial_speed;
+        self.frames_since_speed_change = 0;
         self.frames_per_keyframe = partial_replay_record_metadata.frames_per_keyframe.get();
-        self.replay_file_recorder = Some(Box::new(recorder));
+        self.keyframe_on_events = partial_replay_record_metadata.keyframe_on_events;
+        self.load_save_state_mode = load_save_state_mode;
+        self.replay_file_recorder = Some(recorder);
+
+        // Record every cheat's writes again, since ROM patches aren't part of the initial state
+        self.previous_frame_writes.clear();
+        self.current_frame_writes.clear();
 
         Ok(())
     }
 
-    /// Get number of milliseconds
+    /// Get number of emulated milliseconds of the replay being recorded or played back.
     ///
     /// This will reset to 0 whenever a replay is started.
     pub fn get_recording_milliseconds(&self) -> TimestampMillis {
         self.total_milliseconds
     }
 
+    /// Get the number of milliseconds to display to the user.
+    ///
+    /// When playing back, this is the same as [`SuperShuckieCore::get_recording_milliseconds`].
+    /// Otherwise, this is the wall clock time since a replay was started, which may differ from the
+    /// emulated time if not running at 1x speed.
+    pub fn get_display_milliseconds(&self) -> TimestampMillis {
+        if self.replay_player.is_some() {
+            self.total_milliseconds
+        }
+        else {
+            self.wall_clock_milliseconds
+        }
+    }
+
     /// Stop recording the current replay.
     ///
     /// Returns None if no replay was being recorded. Otherwise, returns Some(true) if successfully closed, or Some(false) if not.
     pub fn stop_recording_replay(&mut self) -> Option<bool> {
-        if let Some(mut old_recorder) = self.replay_file_recorder.take() {
+        if let Some(old_recorder) = self.replay_file_recorder.take() {
+            let (mut old_recorder, determinism_check) = unwrap_determinism_check(old_recorder);
+            if determinism_check.is_some() {
+                self.determinism_check = determinism_check;
+            }
             return if !old_recorder.is_closed() {
                 Some(old_recorder.close().is_ok())
             }
@@ -449,15 +1184,78 @@ impl SuperShuckieCore {
         None
     }
 
+    /// Check that the replay being recorded plays back the same as it is being recorded.
+    ///
+    /// Every [`DeterminismCheckSettings::keyframes_per_check`] keyframes, the frames recorded since
+    /// the previous keyframe are re-simulated on the shadow core and the resulting state is compared
+    /// with the live core's state (see [`SuperShuckieCore::take_determinism_mismatch`]). This is slow,
+    /// so it is meant for catching recorder bugs rather than everyday use.
+    ///
+    /// If a replay is being recorded, this takes effect starting from its next keyframe.
+    pub fn set_determinism_check(&mut self, settings: Option<DeterminismCheckSettings>) {
+        let Some(recorder) = self.replay_file_recorder.take() else {
+            self.determinism_check = settings;
+            return
+        };
+
+        let (recorder, _) = unwrap_determinism_check(recorder);
+        self.replay_file_recorder = Some(match settings {
+            Some(settings) => self.wrap_determinism_check(recorder, settings, None),
+            None => recorder
+        });
+    }
+
+    fn wrap_determinism_check(&self, recorder: Box<dyn ReplayFileRecorderFns>, mut settings: DeterminismCheckSettings, initial_state: Option<&[u8]>) -> Box<dyn ReplayFileRecorderFns> {
+        // Options are part of the replay, so they need to match for the replay to play back the same
+        let _ = settings.shadow_core.apply_core_config_blob(&self.core.core_config_blob());
+
+        let mut input = Vec::new();
+        self.core.encode_input(self.current_input, &mut input);
+        Box::new(DeterminismCheckRecorder::new(recorder, settings, initial_state, ByteVec::Heap(input), self.total_frames))
+    }
+
+    /// Return true if the determinism check is enabled.
+    pub fn is_determinism_check_enabled(&self) -> bool {
+        self.determinism_check.is_some() || self.determinism_check_recorder().is_some()
+    }
+
+    /// Take the first mismatch found by the determinism check since this was last taken, if any.
+    ///
+    /// See [`SuperShuckieCore::set_determinism_check`].
+    pub fn take_determinism_mismatch(&mut self) -> Option<DeterminismMismatch> {
+        let recorder = self.replay_file_recorder.as_mut()?;
+        (Box::as_mut(recorder) as &mut dyn Any)
+            .downcast_mut::<DeterminismCheckRecorder>()?
+            .take_mismatch()
+    }
+
+    fn determinism_check_recorder(&self) -> Option<&DeterminismCheckRecorder> {
+        let recorder = self.replay_file_recorder.as_ref()?;
+        (Box::as_ref(recorder) as &dyn Any).downcast_ref::<DeterminismCheckRecorder>()
+    }
+
     fn with_recorder<T, F: FnOnce(&mut dyn ReplayFileRecorderFns) -> T>(&mut self, what: F) -> Option<T> {
-        if let Some(n) = self.replay_file_recorder.as_mut() {
-            Some(what(Box::as_mut(n)))
-        }
-        else {
-            None
+        let start = self.timestamp_provider.get_precise_timestamp();
+        let result = self.replay_file_recorder.as_mut().map(|n| what(Box::as_mut(n)));
+        self.add_recorder_time(start);
+        result
+    }
+
+    fn add_recorder_time(&mut self, start: Option<Duration>) {
+        if let Some(start) = start && let Some(end) = self.timestamp_provider.get_precise_timestamp() {
+            self.recorder_time = self.recorder_time.saturating_add(end.saturating_sub(start));
         }
     }
 
+    /// Get the time spent on recording a replay (including creating keyframes) since this was last
+    /// called, resetting it to zero.
+    ///
+    /// This is always zero if the timestamp provider does not support precise timestamps (see
+    /// [`MonotonicTimestampProvider::get_precise_timestamp`]).
+    pub fn take_recorder_time(&mut self) -> Duration {
+        core::mem::take(&mut self.recorder_time)
+    }
+
     fn update_input(&mut self) {
         if self.replay_player.is_some() {
             return
@@ -467,19 +1265,20 @@ impl SuperShuckieCore {
             return
         }
 
-        if let Some(pending_input) = self.next_input.take() {
-            self.base_input = pending_input;
+        let new_input = match self.input_override.take() {
+            Some(input) => input,
+            None => {
+                let new_input = self.poll_input();
+                if self.delayed_inputs.is_empty() {
+                    new_input
+                }
+                else {
+                    self.delayed_inputs.push_back(new_input);
+                    self.delayed_inputs.pop_front().expect("delayed inputs should not be empty")
+                }
+            }
         };
 
-        let mut new_input = self.base_input;
-        if let Some(rapid_fire_input) = self.rapid_fire_input && rapid_fire_input.current_frame < rapid_fire_input.hold_length.get() {
-            new_input |= rapid_fire_input.input;
-        }
-
-        if let Some(toggled_input) = self.toggled_input {
-            new_input |= toggled_input
-        }
-
         self.current_input = new_input;
         self.input_scratch_buffer.clear();
 
@@ -495,8 +1294,39 @@ impl SuperShuckieCore {
         }
     }
 
+    /// Take the user's input for the next frame (base, rapid fire, and toggled input).
+    fn poll_input(&mut self) -> Input {
+        if let Some(pending_input) = self.next_input.take() {
+            self.base_input = pending_input;
+        };
+
+        let mut new_input = self.base_input;
+        if let Some(rapid_fire_input) = self.rapid_fire_input && rapid_fire_input.current_frame < rapid_fire_input.hold_length.get() {
+            new_input |= rapid_fire_input.input;
+        }
+
+        if let Some(toggled_input) = self.toggled_input {
+            new_input |= toggled_input
+        }
+        self.core.input_descriptor().mask_input(&mut new_input);
+        new_input
+    }
+
+    /// Run exactly one frame with the given input, unlocked and without any timekeeping or recording.
+    ///
+    /// This must be called on a frame boundary.
+    fn run_frame_untracked(&mut self, input: Input) {
+        self.input_scratch_buffer.clear();
+        self.core.encode_input(input, &mut self.input_scratch_buffer);
+        self.core.set_input_encoded(self.input_scratch_buffer.as_slice());
+        while self.core.run_unlocked().frames == 0 {}
+    }
+
     fn do_frame_timekeeping(&mut self, time: &RunTime) {
+        self.timestamp_provider.advance_emulated_time(time, self.core.ticks_per_second());
         self.frames_since_last_keyframe += time.frames;
+        self.frame_ticks = self.frame_ticks.wrapping_add(time.ticks);
+        self.total_ticks = self.total_ticks.wrapping_add(time.ticks);
         self.total_frames = self.total_frames.wrapping_add(time.frames);
         self.mid_frame = time.frames == 0;
 
@@ -505,9 +1335,15 @@ impl SuperShuckieCore {
         }
 
         if self.replay_player.is_none() && !self.mid_frame {
-            let ms = self.timestamp_provider.get_timestamp() - self.starting_milliseconds;
+            let wall_clock = self.timestamp_provider.get_timestamp() - self.starting_milliseconds;
+            self.wall_clock_milliseconds = self.smooth_timestamp(wall_clock);
+
+            let ms = self.emulated_milliseconds();
             self.total_milliseconds = ms;
 
+            self.frames_since_speed_change = self.frames_since_speed_change.saturating_add(time.frames);
+            self.record_speed_change_if_needed();
+
             self.with_recorder(|f| {
                 // Add frames...
                 for _ in 0..time.frames {
@@ -518,6 +1354,40 @@ impl SuperShuckieCore {
 
     }
 
+    /// Get the emulated time since the timer was restarted, in milliseconds.
+    ///
+    /// Unlike the wall clock, this is unaffected by the game speed.
+    fn emulated_milliseconds(&self) -> TimestampMillis {
+        let ticks_per_second = self.core.ticks_per_second().max(1) as u128;
+        (self.total_ticks as u128 * 1000 / ticks_per_second) as TimestampMillis
+    }
+
+    /// Advance the displayed wall clock by the expected length of the frame that just finished,
+    /// slowly correcting towards the actual wall clock.
+    ///
+    /// This keeps OS scheduling jitter out of the displayed time while not drifting away from real
+    /// time.
+    fn smooth_timestamp(&mut self, wall_clock: TimestampMillis) -> TimestampMillis {
+        let ticks = core::mem::take(&mut self.frame_ticks);
+        let ticks_per_second = self.core.ticks_per_second().max(1);
+        let expected = ticks as f64 * 1000.0 / ticks_per_second as f64 / self.game_speed.into_multiplier_float();
+
+        let predicted = self.smoothed_milliseconds + expected;
+        let error = wall_clock as f64 - predicted;
+
+        self.smoothed_milliseconds = if error.abs() > MAX_TIMESTAMP_DRIFT_MILLISECONDS {
+            // Too far off (e.g. lag spike or uncapped speed); don't bother smoothing
+            wall_clock as f64
+        }
+        else {
+            predicted + error * TIMESTAMP_CORRECTION_RATE
+        };
+
+        // Timestamps must never go backwards
+        self.smoothed_milliseconds = self.smoothed_milliseconds.max(self.wall_clock_milliseconds as f64);
+        self.smoothed_milliseconds as TimestampMillis
+    }
+
     fn push_keyframe_if_needed(&mut self) {
         if self.mid_frame || self.replay_file_recorder.is_none() || self.frames_since_last_keyframe < self.frames_per_keyframe {
             return
@@ -525,12 +1395,36 @@ impl SuperShuckieCore {
 
         self.frames_since_last_keyframe = 0;
         let ms = self.total_milliseconds;
+        let start = self.timestamp_provider.get_precise_timestamp();
         let save_state = ByteVec::Heap(self.core.create_save_state());
+        self.add_recorder_time(start);
         self.with_recorder(|f| {
             let _ = f.insert_keyframe(save_state, ms);
         });
     }
 
+    /// Insert a keyframe for a significant event (see [`PartialReplayRecordMetadata::keyframe_on_events`]).
+    fn push_event_keyframe(&mut self) {
+        if !self.keyframe_on_events || self.mid_frame || self.replay_file_recorder.is_none() {
+            return
+        }
+
+        self.frames_since_last_keyframe = 0;
+        let ms = self.total_milliseconds;
+        let start = self.timestamp_provider.get_precise_timestamp();
+        let save_state = ByteVec::Heap(self.core.create_save_state());
+        self.add_recorder_time(start);
+        self.with_recorder(|f| {
+            let _ = f.insert_keyframe(save_state, ms);
+        });
+    }
+
+    /// Get all metadata mismatches between a replay and the loaded ROM, BIOS, SRAM, and core.
+    pub fn get_replay_metadata_mismatches(&self, metadata: &ReplayFileMetadata) -> Vec<ReplayPlayerMetadataMismatchKind> {
+        let sram_checksum = sram_checksum(&self.core.save_sram());
+        ReplayPlayerMetadataMismatchKind::find_all(metadata, self.core.rom_checksum(), self.core.bios_checksum(), &sram_checksum, self.core.core_name())
+    }
+
     /// Attach a replay file player to the core.
     pub fn attach_replay_player(&mut self, mut player: ReplayFilePlayer, allow_mismatched: bool) -> Result<(), ReplayPlayerAttachError> {
         let metadata = player.get_replay_metadata();
@@ -543,40 +1437,40 @@ impl SuperShuckieCore {
         }
 
         if !allow_mismatched {
-            let mut mismatched_list = Vec::new();
-
-            let rom_checksum = *self.core.rom_checksum();
-            let bios_checksum = *self.core.bios_checksum();
-            let core_name = self.core.core_name();
-
-            if metadata.rom_checksum != rom_checksum {
-                mismatched_list.push(ReplayPlayerMetadataMismatchKind::ROMChecksumMismatch { replay: metadata.rom_checksum, loaded: bios_checksum })
-            }
-
-            if metadata.bios_checksum != bios_checksum {
-                mismatched_list.push(ReplayPlayerMetadataMismatchKind::BIOSChecksumMismatch { replay: metadata.rom_checksum, loaded: bios_checksum })
-            }
-
-            if metadata.emulator_core_name != core_name {
-                mismatched_list.push(ReplayPlayerMetadataMismatchKind::CoreMismatch { replay: metadata.emulator_core_name.clone(), loaded: core_name.to_owned() })
-            }
-
+            let mismatched_list = self.get_replay_metadata_mismatches(metadata);
             if !mismatched_list.is_empty() {
                 return Err(ReplayPlayerAttachError::MismatchedMetadata { issues: mismatched_list })
             }
         }
 
+        let pre_replay_core_config = self.pre_replay_core_config.take().unwrap_or_else(|| self.core.core_config_blob());
+        if let Err(e) = self.core.apply_core_config_blob(&metadata.core_config) {
+            return Err(ReplayPlayerAttachError::Incompatible {
+                description: format!("Can't apply the replay's core config: {e}")
+            })
+        }
+        self.pre_replay_core_config = Some(pre_replay_core_config);
+
         if let Err(e) = player.go_to_keyframe(0) {
             todo!("can't go to 0th keyframe (and can't handle this error TODO): {e:?}")
         }
 
         self.current_input = Input::new();
         self.next_input = None;
+        self.delayed_inputs.iter_mut().for_each(|i| *i = Input::new());
         self.replay_player = Some(player);
         self.replay_stalled = false;
+        self.reverse_step_cache.clear();
+        if let Some(rewind) = self.rewind.as_mut() {
+            rewind.clear();
+        }
+        self.rewinding = false;
         self.restart_timer();
 
-        self.go_to_replay_frame_inner(0, 0);
+        // Any cheats used in the replay were recorded, so don't play back with a patched ROM
+        self.undo_cheat_patches();
+
+        self.go_to_replay_frame_inner(0, 0, None, None, &mut |_| ());
 
         Ok(())
     }
@@ -585,6 +1479,10 @@ impl SuperShuckieCore {
     pub fn detach_replay_player(&mut self) {
         self.replay_stalled = false;
         self.replay_player = None;
+        self.reverse_step_cache.clear();
+        if let Some(core_config) = self.pre_replay_core_config.take() {
+            let _ = self.core.apply_core_config_blob(&core_config);
+        }
         self.reset_input();
     }
 
@@ -595,12 +1493,74 @@ impl SuperShuckieCore {
 
     /// Seek to the given frame (if playing back).
     pub fn go_to_replay_frame(&mut self, frame: UnsignedInteger) {
+        self.go_to_replay_frame_with_progress(frame, None, &mut |_| ());
+    }
+
+    /// Go to the given frame (if playing back), calling `on_progress` with the percent complete as
+    /// frames are re-simulated from the nearest keyframe.
+    ///
+    /// If `cancel` is cancelled, re-simulation stops early, leaving playback at the frame reached.
+    pub fn go_to_replay_frame_with_progress(&mut self, frame: UnsignedInteger, cancel: Option<&CancellationToken>, on_progress: &mut dyn FnMut(u8)) {
         // go one frame before so that we play the actually desired frame (so it is rendered)
         let before_frame = frame.saturating_sub(1);
-        self.go_to_replay_frame_inner(before_frame, before_frame);
+        self.go_to_replay_frame_inner(before_frame, before_frame, None, cancel, on_progress);
+    }
+
+    /// Go back one frame (if playing back).
+    ///
+    /// Recently played frames are cached, so stepping back repeatedly only needs to re-simulate from
+    /// the previous keyframe once every [`REVERSE_STEP_CACHE_SIZE`] frames.
+    pub fn step_replay_backward(&mut self) {
+        if self.replay_player.is_none() || self.total_frames <= 1 {
+            return
+        }
+
+        let target = self.total_frames - 1;
+        if !self.reverse_step_cache.iter().any(|i| i.total_frames == target) {
+            let cache_from = target.saturating_sub(REVERSE_STEP_CACHE_SIZE - 1).max(1);
+            self.reverse_step_cache.clear();
+            self.go_to_replay_frame_inner(target - 1, target - 1, Some(cache_from), None, &mut |_| ());
+            return
+        }
+
+        let entry = self.reverse_step_cache.iter().find(|i| i.total_frames == target).expect("we just checked this");
+        let Some(player) = self.replay_player.as_mut() else {
+            unreachable!("we just checked this")
+        };
+
+        self.core.load_save_state(entry.state.as_slice()).expect("failed to load a save state we just made");
+        if !entry.input.is_empty() {
+            self.core.set_input_encoded(entry.input.as_slice());
+        }
+        player.set_position(entry.position);
+
+        self.replay_input = entry.input.clone();
+        self.total_frames = entry.total_frames;
+        self.total_milliseconds = entry.total_milliseconds;
+        self.mid_frame = false;
+        self.replay_stalled = false;
+
+        let speed = entry.speed;
+        self.set_speed(speed);
     }
 
-    fn go_to_replay_frame_inner(&mut self, frame: UnsignedInteger, desired: UnsignedInteger) {
+    fn cache_reverse_step_frame(&mut self) {
+        let Some(player) = self.replay_player.as_ref() else {
+            return
+        };
+
+        let entry = ReverseStepCacheEntry {
+            state: self.core.create_save_state(),
+            position: player.get_position(),
+            input: self.replay_input.clone(),
+            speed: self.game_speed,
+            total_frames: self.total_frames,
+            total_milliseconds: self.total_milliseconds
+        };
+        self.reverse_step_cache.push(entry);
+    }
+
+    fn go_to_replay_frame_inner(&mut self, frame: UnsignedInteger, desired: UnsignedInteger, cache_from: Option<UnsignedInteger>, cancel: Option<&CancellationToken>, on_progress: &mut dyn FnMut(u8)) {
         let Some(p) = self.replay_player.as_mut() else {
             return
         };
@@ -614,7 +1574,7 @@ impl SuperShuckieCore {
             match e {
                 ReplaySeekError::ReadError { error } => todo!("can't go to {frame}: {error:?} (can't handle this error TODO)"),
                 ReplaySeekError::NoSuchKeyframe { best, .. } => {
-                    return self.go_to_replay_frame_inner(best, desired);
+                    return self.go_to_replay_frame_inner(best, desired, cache_from, cancel, on_progress);
                 }
             }
         }
@@ -626,6 +1586,10 @@ impl SuperShuckieCore {
         let speed = metadata.speed;
 
         self.core.load_save_state(state.as_slice()).expect("replay file is broken (can't load save state) and error handling not yet implemented!");
+        if !metadata.input.is_empty() {
+            self.core.set_input_encoded(metadata.input.as_slice());
+        }
+        self.replay_input = metadata.input.clone();
 
         self.mid_frame = false;
         self.total_frames = metadata.elapsed_frames;
@@ -635,12 +1599,45 @@ impl SuperShuckieCore {
 
         self.set_speed(speed);
 
-        while self.total_frames <= desired && !self.replay_stalled {
+        let start_frame = self.total_frames;
+        let frames_to_run = desired.saturating_add(1).saturating_sub(start_frame).max(1);
+
+        while self.total_frames <= desired && !self.replay_stalled && !cancel.is_some_and(CancellationToken::is_cancelled) {
             self.run_unlocked();
+
+            if let Some(cache_from) = cache_from && !self.mid_frame && self.total_frames >= cache_from {
+                self.cache_reverse_step_frame();
+            }
+
+            let frames_run = self.total_frames.saturating_sub(start_frame).min(frames_to_run);
+            on_progress((frames_run * 100 / frames_to_run) as u8);
         }
     }
 }
 
+/// Shared flag for stopping a long operation early (e.g. a replay seek) from another thread.
+///
+/// Clones refer to the same flag. Once cancelled, a token stays cancelled.
+#[derive(Clone, Default, Debug)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Create a token that is not cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel any operation using this token.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns `true` if [`CancellationToken::cancel`] was called.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
 /// Returns when an error occurs.
 #[derive(Clone, Debug)]
 pub enum ReplayPlayerAttachError {
@@ -657,6 +1654,75 @@ pub enum ReplayPlayerAttachError {
     }
 }
 
+/// Result of [`SuperShuckieCore::search_inputs`].
+#[derive(Clone, Debug)]
+pub struct StateSearchResult {
+    /// Save state after all inputs were applied.
+    pub state: Vec<u8>,
+
+    /// Hash of the requested memory regions, concatenated in order.
+    pub memory_hash: ReplayHeaderBlake3Hash
+}
+
+/// Returns when [`SuperShuckieCore::search_inputs`] fails.
+#[derive(Clone, Debug)]
+pub enum StateSearchError {
+    /// A replay is being recorded or played back.
+    ReplayActive,
+
+    /// The starting save state could not be loaded.
+    #[allow(missing_docs)]
+    LoadStateFailed {
+        description: String
+    },
+
+    /// A memory region could not be read.
+    #[allow(missing_docs)]
+    ReadFailed {
+        address: u32,
+        description: &'static str
+    }
+}
+
+/// Whether a failed RAM access was a read or a write.
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[allow(missing_docs)]
+pub enum RamAccessKind {
+    Read,
+    Write
+}
+
+/// Describes a RAM access that failed in strict mode.
+///
+/// See [`SuperShuckieCore::set_strict_ram_access`].
+#[derive(Clone, PartialEq, Debug)]
+pub struct RamAccessError {
+    /// Whether it was a read or a write
+    pub kind: RamAccessKind,
+
+    /// Address that was accessed (interpreted the same way as [`EmulatorCore::read_ram`])
+    pub address: u32,
+
+    /// Number of bytes accessed
+    pub length: usize,
+
+    /// Frame the access was attempted on
+    pub frame: u64,
+
+    /// Error returned by the emulator core
+    pub error: &'static str
+}
+
+impl Display for RamAccessError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let verb = match self.kind {
+            RamAccessKind::Read => "read",
+            RamAccessKind::Write => "write"
+        };
+        write!(f, "Failed to {verb} {} byte(s) at 0x{:08X} on frame {}: {}", self.length, self.address, self.frame, self.error)
+    }
+}
+
 /// Describes a metadata mismatch.
 #[derive(Clone, Debug)]
 #[allow(missing_docs)]
@@ -674,7 +1740,82 @@ pub enum ReplayPlayerMetadataMismatchKind {
     CoreMismatch {
         replay: String,
         loaded: String
+    },
+
+    SRAMChecksumMismatch {
+        replay: ReplayHeaderBlake3Hash,
+        loaded: ReplayHeaderBlake3Hash
+    }
+}
+
+impl ReplayPlayerMetadataMismatchKind {
+    /// Find all metadata mismatches between a replay and the given ROM checksum, BIOS checksum,
+    /// SRAM checksum (see [`sram_checksum`]), and core name.
+    ///
+    /// The SRAM checksum is not checked if the replay doesn't have one.
+    pub fn find_all(metadata: &ReplayFileMetadata, rom_checksum: &ReplayHeaderBlake3Hash, bios_checksum: &ReplayHeaderBlake3Hash, sram_checksum: &ReplayHeaderBlake3Hash, core_name: &str) -> Vec<Self> {
+        let mut mismatched_list = Vec::new();
+
+        if metadata.rom_checksum != *rom_checksum {
+            mismatched_list.push(Self::ROMChecksumMismatch { replay: metadata.rom_checksum, loaded: *rom_checksum })
+        }
+
+        if metadata.bios_checksum != *bios_checksum {
+            mismatched_list.push(Self::BIOSChecksumMismatch { replay: metadata.bios_checksum, loaded: *bios_checksum })
+        }
+
+        if metadata.emulator_core_name != core_name && !is_compatible_core_upgrade(&metadata.emulator_core_name, core_name) {
+            mismatched_list.push(Self::CoreMismatch { replay: metadata.emulator_core_name.clone(), loaded: core_name.to_owned() })
+        }
+
+        if metadata.sram_checksum != ReplayHeaderBlake3Hash::default() && metadata.sram_checksum != *sram_checksum {
+            mismatched_list.push(Self::SRAMChecksumMismatch { replay: metadata.sram_checksum, loaded: *sram_checksum })
+        }
+
+        mismatched_list
+    }
+}
+
+/// Get the checksum of SRAM as stored in [`ReplayFileMetadata::sram_checksum`].
+///
+/// Empty SRAM is all zeroes, since there is nothing to check.
+pub fn sram_checksum(sram: &[u8]) -> ReplayHeaderBlake3Hash {
+    if sram.is_empty() {
+        ReplayHeaderBlake3Hash::default()
+    }
+    else {
+        blake3_hash(sram)
+    }
+}
+
+/// Core upgrades that are known to play back replays identically, as (replay core, loaded core)
+/// name patterns, where `*` matches any run of digits (e.g. `1.0.*`).
+///
+/// Only add a pair once replays recorded with the first core were verified to play back to the end
+/// with the second one (e.g. with `--headless-verify`), since any desyncs will go unwarned.
+const COMPATIBLE_CORE_UPGRADES: &[(&str, &str)] = &[];
+
+/// Returns `true` if replays recorded with the `replay` core are known to play back identically
+/// with the `loaded` core, even though they have different names.
+pub fn is_compatible_core_upgrade(replay: &str, loaded: &str) -> bool {
+    COMPATIBLE_CORE_UPGRADES.iter().any(|(replay_pattern, loaded_pattern)| {
+        core_name_matches(replay_pattern, replay) && core_name_matches(loaded_pattern, loaded)
+    })
+}
+
+fn core_name_matches(mut pattern: &str, mut name: &str) -> bool {
+    while let Some(wildcard) = pattern.find('*') {
+        let Some(rest) = name.strip_prefix(&pattern[..wildcard]) else {
+            return false
+        };
+        let digits = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+        if digits == 0 {
+            return false
+        }
+        name = &rest[digits..];
+        pattern = &pattern[wildcard + 1..];
     }
+    pattern == name
 }
 
 impl Display for ReplayPlayerMetadataMismatchKind {
@@ -698,6 +1839,12 @@ impl Display for ReplayPlayerMetadataMismatchKind {
                     replay, loaded
                 ))
             }
+            ReplayPlayerMetadataMismatchKind::SRAMChecksumMismatch { replay, loaded } => {
+                f.write_fmt(format_args!(
+                    "Save data mismatch! The loaded save data is different from when the replay was recorded.\n\n  Replay: {}\n  Loaded: {}\n\nThis can cause potential desyncs.",
+                    blake3_hash_to_ascii(*replay), blake3_hash_to_ascii(*loaded)
+                ))
+            }
         }
     }
 }
@@ -709,6 +1856,51 @@ impl Display for ReplayPlayerMetadataMismatchKind {
 pub trait MonotonicTimestampProvider {
     /// Get the timestamp.
     fn get_timestamp(&mut self) -> TimestampMillis;
+
+    /// Get a high resolution timestamp, if supported.
+    ///
+    /// This is only used for profiling. By default, this returns `None`.
+    fn get_precise_timestamp(&mut self) -> Option<Duration> {
+        None
+    }
+
+    /// Called whenever the core emulates some time, for providers that follow emulated time rather
+    /// than a real clock.
+    ///
+    /// By default, this does nothing.
+    fn advance_emulated_time(&mut self, _time: &RunTime, _ticks_per_second: u64) {}
+}
+
+/// Timestamp provider that advances by exactly how much time was emulated, ignoring the real clock.
+///
+/// Time passes as if the game always ran at 1x speed, no matter how fast it actually runs or how
+/// loaded the host is, so timestamps are perfectly regular (e.g. for headless or batch runs).
+#[derive(Copy, Clone, Default, Debug)]
+pub struct EmulatedTimestampProvider {
+    milliseconds: TimestampMillis,
+
+    /// Emulated time not yet counted in `milliseconds`, in thousandths of a tick
+    remainder: u128
+}
+
+impl EmulatedTimestampProvider {
+    /// Start at 0 milliseconds.
+    pub const fn new() -> Self {
+        Self { milliseconds: 0, remainder: 0 }
+    }
+}
+
+impl MonotonicTimestampProvider for EmulatedTimestampProvider {
+    fn get_timestamp(&mut self) -> TimestampMillis {
+        self.milliseconds
+    }
+
+    fn advance_emulated_time(&mut self, time: &RunTime, ticks_per_second: u64) {
+        let ticks_per_second = ticks_per_second.max(1) as u128;
+        let total = self.remainder + time.ticks as u128 * 1000;
+        self.milliseconds = self.milliseconds.wrapping_add((total / ticks_per_second) as TimestampMillis);
+        self.remainder = total % ticks_per_second;
+    }
 }
 
 #[cfg(feature = "std")]
@@ -719,7 +1911,7 @@ pub fn std_timestamp_provider() -> Box<dyn MonotonicTimestampProvider> {
 
 #[cfg(feature = "std")]
 mod std_timestamp_provider {
-    use std::time::Instant;
+    use std::time::{Duration, Instant};
     use supershuckie_replay_recorder::TimestampMillis;
     use crate::MonotonicTimestampProvider;
 
@@ -737,5 +1929,9 @@ mod std_timestamp_provider {
         fn get_timestamp(&mut self) -> TimestampMillis {
             (Instant::now() - self.reference_time).as_millis() as TimestampMillis
         }
+
+        fn get_precise_timestamp(&mut self) -> Option<Duration> {
+            Some(Instant::now() - self.reference_time)
+        }
     }
 }
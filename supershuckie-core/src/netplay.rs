@@ -0,0 +1,1152 @@
+use crate::emulator::Input;
+use crate::SuperShuckieCore;
+use std::collections::{BTreeMap, VecDeque};
+use std::io;
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::time::{Duration, Instant};
+use std::vec::Vec;
+use std::vec;
+use supershuckie_replay_recorder::replay_file::record::ReplayFileSink;
+use supershuckie_replay_recorder::{ByteVec, InputBuffer, KeyframeMetadata, Packet, PacketIO, PacketReadError};
+
+/// Identifies Super Shuckie netplay packets.
+const PACKET_MAGIC: [u8; 4] = *b"SSNP";
+
+/// Incremented whenever the packet format changes.
+const PROTOCOL_VERSION: u8 = 1;
+
+/// Size of the header on every packet (magic, version, and kind).
+const PACKET_HEADER_SIZE: usize = PACKET_MAGIC.len() + 2;
+
+/// Maximum number of frames emulated ahead of the last confirmed remote input before stalling.
+const MAX_ROLLBACK_FRAMES: usize = 8;
+
+/// Maximum number of inputs sent in a single packet.
+const MAX_INPUTS_PER_PACKET: usize = 64;
+
+/// Size of each piece of the save state sent to the joining instance.
+const STATE_CHUNK_SIZE: usize = 1024;
+
+/// Largest save state a joining instance accepts from the host.
+///
+/// Game Boy save states are a few hundred KiB at most, so anything larger is bogus.
+const MAX_STATE_SIZE: usize = 4 * 1024 * 1024;
+
+/// Time between resending anything that wasn't acknowledged, or sending a keepalive.
+const RESEND_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Time without hearing from the peer before the session is ended.
+const PEER_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Time a joining instance waits for the host to respond before giving up.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Maximum number of spectators connected to a host.
+const MAX_SPECTATORS: usize = 8;
+
+/// Number of frames between keyframes in the spectator stream.
+///
+/// Spectators that fall behind skip ahead to the next keyframe.
+const SPECTATOR_KEYFRAME_INTERVAL: u64 = 600;
+
+/// Maximum number of bytes of the spectator stream sent to a spectator that haven't been
+/// acknowledged.
+const MAX_SPECTATOR_BYTES_IN_FLIGHT: u64 = 64 * 1024;
+
+/// Number of frames a spectator buffers before playing.
+const SPECTATOR_DELAY_FRAMES: usize = 30;
+
+/// Number of buffered frames after which a spectator emulates frames as fast as possible to catch
+/// up.
+const MAX_SPECTATOR_BUFFERED_FRAMES: usize = SPECTATOR_DELAY_FRAMES * 4;
+
+/// State of a [`NetplaySession`].
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[repr(u32)]
+pub enum NetplayStatus {
+    /// No session is active
+    Inactive = 0,
+
+    /// Waiting for the other instance to connect (or, if joining, respond)
+    WaitingForPeer = 1,
+
+    /// The host's save state is being sent to the joining instance (or, if spectating, frames are
+    /// being buffered)
+    Synchronizing = 2,
+
+    /// Both instances are emulating
+    Connected = 3,
+
+    /// The session ended because the peer left, timed out, or the emulator was changed in a way
+    /// that can't be synchronized (e.g. a save state was loaded)
+    Disconnected = 4,
+
+    /// The peer has a different ROM loaded
+    Mismatched = 5
+}
+
+impl NetplayStatus {
+    pub(crate) const fn from_u32(value: u32) -> Self {
+        match value {
+            1 => Self::WaitingForPeer,
+            2 => Self::Synchronizing,
+            3 => Self::Connected,
+            4 => Self::Disconnected,
+            5 => Self::Mismatched,
+            _ => Self::Inactive
+        }
+    }
+}
+
+/// Which side of a [`NetplaySession`] this instance is.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum NetplayRole {
+    /// Listens for a peer and sends it the current state
+    Host,
+
+    /// Connects to a host and receives its state
+    Join,
+
+    /// Connects to a host and plays back the session without sending any input
+    Spectate
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[repr(u8)]
+enum PacketKind {
+    /// Sent by the joining instance until the host responds; followed by the ROM checksum
+    Hello = 0,
+
+    /// Part of the host's save state; followed by the total length (u32), offset (u32), and data
+    StateChunk = 1,
+
+    /// Sent by the host if the ROM checksum in [`PacketKind::Hello`] doesn't match
+    Mismatch = 2,
+
+    /// Sent by the joining instance once the host's save state is loaded
+    Ready = 3,
+
+    /// Followed by the next frame expected from the recipient (u64), the frame of the first input
+    /// (u64), the number of inputs (u8), and each encoded input prefixed by its length (u16)
+    Inputs = 4,
+
+    /// Sent when a session ends
+    Goodbye = 5,
+
+    /// Sent by a spectator until the host responds; followed by the ROM checksum
+    Spectate = 6,
+
+    /// Part of the spectator stream; followed by the offset of the latest keyframe (u64), the offset
+    /// of the data (u64), and the data
+    SpectatorData = 7,
+
+    /// Sent by a spectator when it receives data; followed by the next offset expected (u64)
+    SpectatorAck = 8
+}
+
+impl PacketKind {
+    const fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(Self::Hello),
+            1 => Some(Self::StateChunk),
+            2 => Some(Self::Mismatch),
+            3 => Some(Self::Ready),
+            4 => Some(Self::Inputs),
+            5 => Some(Self::Goodbye),
+            6 => Some(Self::Spectate),
+            7 => Some(Self::SpectatorData),
+            8 => Some(Self::SpectatorAck),
+            _ => None
+        }
+    }
+}
+
+/// A frame that was emulated but may need to be emulated again.
+struct NetplayFrame {
+    frame: u64,
+
+    /// Save state from the start of the frame
+    state: Vec<u8>,
+
+    local_input: Vec<u8>,
+    remote_input: Vec<u8>,
+
+    /// If false, `remote_input` is a prediction
+    remote_confirmed: bool
+}
+
+/// An instance watching a session hosted by this instance.
+struct Spectator {
+    address: SocketAddr,
+
+    /// Offset in the spectator stream the spectator expects next
+    acked: u64,
+
+    /// Offset in the spectator stream sent up to
+    sent: u64,
+
+    last_received: Instant,
+
+    /// Last time `acked` moved forward (or unacknowledged data was resent)
+    last_progress: Instant
+}
+
+/// Rollback netplay between two instances over UDP.
+///
+/// Both instances control the same console; the input applied each frame is the combination of
+/// both instances' input. Remote input that hasn't arrived yet is predicted to be the last input
+/// received, and if the prediction was wrong, the core is rolled back to the save state from that
+/// frame and the frames since are emulated again.
+///
+/// Once a frame's input is confirmed, the host sends it to any spectators as a stream of replay
+/// [`Packet`]s, starting with a keyframe. Spectators play the stream back slightly delayed.
+///
+/// Memory writes (e.g. from Poke-A-Byte) are not synchronized.
+pub struct NetplaySession {
+    socket: UdpSocket,
+    role: NetplayRole,
+    peer: Option<SocketAddr>,
+    status: NetplayStatus,
+    input_delay: u64,
+
+    /// Next frame to be emulated
+    frame: u64,
+
+    /// Frames since the oldest unconfirmed remote input
+    history: VecDeque<NetplayFrame>,
+
+    /// Local input by frame that hasn't been acknowledged by the peer or emulated yet
+    local_inputs: BTreeMap<u64, Vec<u8>>,
+
+    /// Remote input received for frames that haven't been emulated yet
+    remote_inputs: BTreeMap<u64, Vec<u8>>,
+
+    /// Last remote input received, used for predicting the remote input
+    last_remote_input: Vec<u8>,
+
+    /// Next frame expected from the peer
+    remote_next_frame: u64,
+
+    /// Next frame the peer expects from us
+    remote_ack_frame: u64,
+
+    /// Earliest frame the remote input was predicted incorrectly for
+    misprediction: Option<u64>,
+
+    /// Save state sent by the host or received by the joining instance
+    sync_state: Vec<u8>,
+    sync_chunks_received: Vec<bool>,
+
+    last_sent: Option<Instant>,
+    last_received: Instant,
+    receive_buffer: Vec<u8>,
+
+    spectators: Vec<Spectator>,
+
+    /// Spectator stream since the latest keyframe, starting at `spectator_stream_start`
+    spectator_stream: Vec<u8>,
+    spectator_stream_start: u64,
+
+    /// Frame of the latest keyframe in the spectator stream, or `None` if the next frame needs one
+    spectator_keyframe_frame: Option<u64>,
+
+    /// Last input written to (or, if spectating, read from) the spectator stream
+    spectator_input: Vec<u8>,
+
+    /// Offset in the spectator stream expected next, if spectating
+    spectator_offset: u64,
+
+    /// Data received from the spectator stream that isn't a complete packet yet
+    spectator_partial: Vec<u8>,
+
+    spectator_packets: VecDeque<Packet>,
+
+    /// Number of [`Packet::NextFrame`] packets in `spectator_packets`
+    spectator_buffered_frames: usize
+}
+
+impl NetplaySession {
+    /// Listen for a peer on the given UDP port over IPv4.
+    ///
+    /// `input_delay` is the number of frames local input is delayed by, which reduces how often
+    /// rollbacks happen.
+    pub fn host(port: u16, input_delay: u8) -> io::Result<Self> {
+        let socket = UdpSocket::bind(("0.0.0.0", port))?;
+        Self::new(socket, NetplayRole::Host, None, input_delay)
+    }
+
+    /// Connect to a host at the given address (e.g. `192.168.1.2:7845`).
+    ///
+    /// Hosts only listen on IPv4, so the address must resolve to an IPv4 address.
+    ///
+    /// `input_delay` is the number of frames local input is delayed by, which reduces how often
+    /// rollbacks happen.
+    pub fn join(address: &str, input_delay: u8) -> io::Result<Self> {
+        let mut addresses = address.to_socket_addrs()?.peekable();
+        if addresses.peek().is_none() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "address did not resolve"))
+        }
+        let peer = addresses
+            .find(SocketAddr::is_ipv4)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::Unsupported, "IPv6 is not supported; use the host's IPv4 address"))?;
+        let socket = UdpSocket::bind(("0.0.0.0", 0))?;
+        Self::new(socket, NetplayRole::Join, Some(peer), input_delay)
+    }
+
+    /// Watch a session hosted at the given address (e.g. `192.168.1.2:7845`).
+    pub fn spectate(address: &str) -> io::Result<Self> {
+        let mut session = Self::join(address, 0)?;
+        session.role = NetplayRole::Spectate;
+        Ok(session)
+    }
+
+    fn new(socket: UdpSocket, role: NetplayRole, peer: Option<SocketAddr>, input_delay: u8) -> io::Result<Self> {
+        socket.set_nonblocking(true)?;
+        Ok(Self {
+            socket,
+            role,
+            peer,
+            status: NetplayStatus::WaitingForPeer,
+            input_delay: input_delay as u64,
+            frame: 0,
+            history: VecDeque::new(),
+            local_inputs: BTreeMap::new(),
+            remote_inputs: BTreeMap::new(),
+            last_remote_input: Vec::new(),
+            remote_next_frame: 0,
+            remote_ack_frame: 0,
+            misprediction: None,
+            sync_state: Vec::new(),
+            sync_chunks_received: Vec::new(),
+            last_sent: None,
+            last_received: Instant::now(),
+            receive_buffer: vec![0u8; u16::MAX as usize],
+            spectators: Vec::new(),
+            spectator_stream: Vec::new(),
+            spectator_stream_start: 0,
+            spectator_keyframe_frame: None,
+            spectator_input: Vec::new(),
+            spectator_offset: 0,
+            spectator_partial: Vec::new(),
+            spectator_packets: VecDeque::new(),
+            spectator_buffered_frames: 0
+        })
+    }
+
+    /// Get the role of this instance.
+    pub fn role(&self) -> NetplayRole {
+        self.role
+    }
+
+    /// Get the current status.
+    pub fn status(&self) -> NetplayStatus {
+        self.status
+    }
+
+    /// Return true if the session ended and can be dropped.
+    pub(crate) fn is_finished(&self) -> bool {
+        matches!(self.status, NetplayStatus::Disconnected | NetplayStatus::Mismatched)
+    }
+
+    /// Handle incoming packets, roll back if needed, and prepare the input for the next frame.
+    ///
+    /// Returns false if the core should not run yet. `running` should be false if the core is
+    /// paused.
+    pub(crate) fn poll(&mut self, core: &mut SuperShuckieCore, running: bool) -> bool {
+        self.receive(core);
+
+        let now = Instant::now();
+        let resend = self.last_sent.is_none_or(|t| now.duration_since(t) >= RESEND_INTERVAL);
+
+        if self.role == NetplayRole::Host {
+            self.send_to_spectators(now);
+        }
+
+        match self.status {
+            NetplayStatus::WaitingForPeer => {
+                if self.role != NetplayRole::Host {
+                    if now.duration_since(self.last_received) >= CONNECT_TIMEOUT {
+                        self.status = NetplayStatus::Disconnected;
+                        return true
+                    }
+                    if resend {
+                        let kind = if self.role == NetplayRole::Spectate { PacketKind::Spectate } else { PacketKind::Hello };
+                        let mut packet = Self::begin_packet(kind);
+                        packet.extend_from_slice(core.get_core().rom_checksum());
+                        self.send(&packet);
+                    }
+                }
+                true
+            }
+            NetplayStatus::Synchronizing | NetplayStatus::Connected if self.role == NetplayRole::Spectate => {
+                if self.check_timeout(now) {
+                    return true
+                }
+                self.play_spectator_frame(core, running)
+            }
+            NetplayStatus::Synchronizing => {
+                if self.check_timeout(now) {
+                    return true
+                }
+                if self.role == NetplayRole::Host {
+                    if self.sync_state.is_empty() {
+                        core.finish_current_frame();
+                        self.sync_state = core.create_save_state();
+                    }
+                    if resend {
+                        self.send_state_chunks();
+                    }
+                }
+                false
+            }
+            NetplayStatus::Connected => {
+                if self.check_timeout(now) {
+                    return true
+                }
+
+                // a frame is already in progress
+                if core.mid_frame || core.input_override.is_some() {
+                    return true
+                }
+
+                if let Some(frame) = self.misprediction.take() {
+                    self.rollback(core, frame);
+                }
+                while self.history.front().is_some_and(|f| f.remote_confirmed) {
+                    let frame = self.history.pop_front().expect("history should not be empty");
+                    self.stream_to_spectators(core, frame);
+                }
+
+                if !running || self.history.len() >= MAX_ROLLBACK_FRAMES {
+                    if resend {
+                        self.send_inputs();
+                    }
+                    return false
+                }
+
+                self.advance(core);
+                self.send_inputs();
+                true
+            }
+            NetplayStatus::Inactive | NetplayStatus::Disconnected | NetplayStatus::Mismatched => true
+        }
+    }
+
+    /// Set up the input for the next frame and save the state in case it needs to be emulated
+    /// again.
+    fn advance(&mut self, core: &mut SuperShuckieCore) {
+        let input = core.poll_input();
+        self.local_inputs.insert(self.frame + self.input_delay, encode_input(core, input));
+
+        let local_input = self.local_inputs.get(&self.frame).cloned().unwrap_or_else(|| encode_input(core, Input::new()));
+        let (remote_input, remote_confirmed) = match self.remote_inputs.remove(&self.frame) {
+            Some(input) => (input, true),
+            None => (self.last_remote_input.clone(), false)
+        };
+
+        core.input_override = Some(combine_inputs(core, &local_input, &remote_input));
+        self.history.push_back(NetplayFrame {
+            frame: self.frame,
+            state: core.create_save_state(),
+            local_input,
+            remote_input,
+            remote_confirmed
+        });
+        self.frame += 1;
+
+        let oldest_needed = self.remote_ack_frame.min(self.frame);
+        self.local_inputs = self.local_inputs.split_off(&oldest_needed);
+    }
+
+    /// Load the state from `frame` and emulate every frame since with the corrected remote input.
+    fn rollback(&mut self, core: &mut SuperShuckieCore, frame: u64) {
+        let Some(start) = self.history.iter().position(|f| f.frame == frame) else {
+            return
+        };
+
+        if core.core.load_save_state(&self.history[start].state).is_err() {
+            self.status = NetplayStatus::Disconnected;
+            return
+        }
+
+        core.core.set_rendering_enabled(false);
+        let last = self.history.len() - 1;
+        for i in start..=last {
+            if i != start {
+                self.history[i].state = core.create_save_state();
+            }
+
+            let entry = &mut self.history[i];
+            if !entry.remote_confirmed {
+                entry.remote_input.clone_from(&self.last_remote_input);
+            }
+
+            // only the frame being displayed needs to be drawn
+            if i == last {
+                core.core.set_rendering_enabled(true);
+            }
+
+            let input = combine_inputs(core, &entry.local_input, &entry.remote_input);
+            core.run_frame_untracked(input);
+        }
+    }
+
+    /// Reset all per-session state once both instances have the same save state.
+    fn start_session(&mut self, core: &SuperShuckieCore) {
+        self.status = NetplayStatus::Connected;
+        self.frame = 0;
+        self.history.clear();
+        self.local_inputs.clear();
+        self.remote_inputs.clear();
+        self.last_remote_input = encode_input(core, Input::new());
+        self.remote_next_frame = 0;
+        self.remote_ack_frame = 0;
+        self.misprediction = None;
+        self.sync_state = Vec::new();
+        self.sync_chunks_received = Vec::new();
+
+        // nothing is pressed until the first delayed input comes in
+        for frame in 0..self.input_delay {
+            self.local_inputs.insert(frame, self.last_remote_input.clone());
+        }
+    }
+
+    fn check_timeout(&mut self, now: Instant) -> bool {
+        if now.duration_since(self.last_received) >= PEER_TIMEOUT {
+            self.status = NetplayStatus::Disconnected;
+            true
+        }
+        else {
+            false
+        }
+    }
+
+    fn receive(&mut self, core: &mut SuperShuckieCore) {
+        // stops when there's nothing left to read, or on an error (e.g. ICMP port unreachable because
+        // the peer isn't listening yet) that can wait until the next poll
+        while let Ok((length, from)) = self.socket.recv_from(&mut self.receive_buffer) {
+            let packet = &self.receive_buffer[..length];
+            let Some(body) = packet.strip_prefix(&PACKET_MAGIC) else {
+                continue
+            };
+            let [version, kind, body @ ..] = body else {
+                continue
+            };
+            if *version != PROTOCOL_VERSION {
+                continue
+            }
+            let Some(kind) = PacketKind::from_u8(*kind) else {
+                continue
+            };
+
+            // copied so that the receive buffer isn't borrowed while handling the packet
+            let body = body.to_vec();
+            self.handle_packet(core, kind, &body, from);
+            if self.is_finished() {
+                break
+            }
+        }
+    }
+
+    fn handle_packet(&mut self, core: &mut SuperShuckieCore, kind: PacketKind, body: &[u8], from: SocketAddr) {
+        // anyone can try to connect, but everything else has to come from the peer
+        match (self.role, kind) {
+            (NetplayRole::Host, PacketKind::Hello) => (),
+            (NetplayRole::Host, PacketKind::Spectate | PacketKind::SpectatorAck) => {
+                self.receive_from_spectator(core, kind, body, from);
+                return
+            }
+            _ if self.peer != Some(from) => return,
+            _ => ()
+        }
+
+        match (self.role, kind) {
+            (NetplayRole::Host, PacketKind::Hello) => {
+                if self.status != NetplayStatus::WaitingForPeer {
+                    return
+                }
+                if body != core.get_core().rom_checksum().as_slice() {
+                    self.send_to(&Self::begin_packet(PacketKind::Mismatch), from);
+                    return
+                }
+                self.peer = Some(from);
+                self.status = NetplayStatus::Synchronizing;
+                self.sync_state.clear();
+            }
+            (NetplayRole::Host, PacketKind::Ready) => {
+                if self.status == NetplayStatus::Synchronizing {
+                    self.start_session(core);
+                }
+            }
+            (NetplayRole::Join, PacketKind::StateChunk) => {
+                self.receive_state_chunk(core, body);
+            }
+            (NetplayRole::Join | NetplayRole::Spectate, PacketKind::Mismatch) => {
+                self.status = NetplayStatus::Mismatched;
+            }
+            (NetplayRole::Spectate, PacketKind::SpectatorData) => {
+                self.receive_spectator_data(body);
+            }
+            (NetplayRole::Host | NetplayRole::Join, PacketKind::Inputs) => {
+                // the peer only sends input once it has started, so the ready packet was lost
+                if self.role == NetplayRole::Host && self.status == NetplayStatus::Synchronizing {
+                    self.start_session(core);
+                }
+                if self.status == NetplayStatus::Connected {
+                    self.receive_inputs(core, body);
+                }
+            }
+            (_, PacketKind::Goodbye) => {
+                if self.status != NetplayStatus::WaitingForPeer {
+                    self.status = NetplayStatus::Disconnected;
+                }
+            }
+            _ => return
+        }
+
+        self.last_received = Instant::now();
+    }
+
+    fn receive_state_chunk(&mut self, core: &mut SuperShuckieCore, body: &[u8]) {
+        // the host didn't get our ready packet
+        if self.status == NetplayStatus::Connected {
+            self.send(&Self::begin_packet(PacketKind::Ready));
+            return
+        }
+
+        let (Some(total_length), Some(offset), Some(data)) = (read_u32(body, 0), read_u32(body, 4), body.get(8..)) else {
+            return
+        };
+        let (total_length, offset) = (total_length as usize, offset as usize);
+        if total_length > MAX_STATE_SIZE {
+            return
+        }
+        if offset % STATE_CHUNK_SIZE != 0 || data.len() > STATE_CHUNK_SIZE || offset + data.len() > total_length {
+            return
+        }
+
+        if self.sync_state.len() != total_length {
+            self.sync_state = vec![0u8; total_length];
+            self.sync_chunks_received = vec![false; total_length.div_ceil(STATE_CHUNK_SIZE)];
+        }
+        self.status = NetplayStatus::Synchronizing;
+        self.sync_state[offset..offset + data.len()].copy_from_slice(data);
+        self.sync_chunks_received[offset / STATE_CHUNK_SIZE] = true;
+
+        if self.sync_chunks_received.iter().all(|r| *r) {
+            let state = std::mem::take(&mut self.sync_state);
+            if core.load_save_state(&state).is_err() {
+                self.send(&Self::begin_packet(PacketKind::Goodbye));
+                self.status = NetplayStatus::Disconnected;
+                return
+            }
+            self.start_session(core);
+            self.send(&Self::begin_packet(PacketKind::Ready));
+        }
+    }
+
+    fn receive_inputs(&mut self, core: &SuperShuckieCore, body: &[u8]) {
+        let (Some(ack_frame), Some(first_frame), Some(&count)) = (read_u64(body, 0), read_u64(body, 8), body.get(16)) else {
+            return
+        };
+        self.remote_ack_frame = self.remote_ack_frame.max(ack_frame);
+
+        let Some(end_frame) = first_frame.checked_add(count as u64) else {
+            return
+        };
+
+        let mut offset = 17;
+        for frame in first_frame..end_frame {
+            let Some(length) = read_u16(body, offset) else {
+                return
+            };
+            let Some(input) = body.get(offset + 2..offset + 2 + length as usize) else {
+                return
+            };
+            offset += 2 + length as usize;
+
+            // inputs must be received in order
+            if frame != self.remote_next_frame {
+                continue
+            }
+            if core.get_core().decode_input(input).is_none() {
+                return
+            }
+
+            self.remote_next_frame += 1;
+            self.last_remote_input.clear();
+            self.last_remote_input.extend_from_slice(input);
+
+            if frame >= self.frame {
+                self.remote_inputs.insert(frame, input.to_vec());
+                continue
+            }
+
+            let Some(entry) = self.history.iter_mut().find(|f| f.frame == frame) else {
+                continue
+            };
+            if entry.remote_input != input {
+                entry.remote_input.clear();
+                entry.remote_input.extend_from_slice(input);
+                self.misprediction = Some(self.misprediction.map_or(frame, |f| f.min(frame)));
+            }
+            entry.remote_confirmed = true;
+        }
+    }
+
+    fn receive_from_spectator(&mut self, core: &SuperShuckieCore, kind: PacketKind, body: &[u8], from: SocketAddr) {
+        let now = Instant::now();
+        let stream_end = self.spectator_stream_start + self.spectator_stream.len() as u64;
+        let index = self.spectators.iter().position(|s| s.address == from);
+
+        match (kind, index) {
+            (PacketKind::Spectate, None) => {
+                if body != core.get_core().rom_checksum().as_slice() {
+                    self.send_to(&Self::begin_packet(PacketKind::Mismatch), from);
+                    return
+                }
+                if self.spectators.len() >= MAX_SPECTATORS {
+                    return
+                }
+
+                self.spectators.push(Spectator {
+                    address: from,
+                    acked: stream_end,
+                    sent: stream_end,
+                    last_received: now,
+                    last_progress: now
+                });
+
+                // start a new keyframe so the spectator doesn't have to wait for one
+                self.spectator_keyframe_frame = None;
+            }
+            (PacketKind::Spectate, Some(index)) => {
+                self.spectators[index].last_received = now;
+            }
+            (PacketKind::SpectatorAck, Some(index)) => {
+                let Some(acked) = read_u64(body, 0) else {
+                    return
+                };
+                let spectator = &mut self.spectators[index];
+                spectator.last_received = now;
+                if acked > spectator.acked {
+                    spectator.acked = acked.min(stream_end);
+                    spectator.last_progress = now;
+                }
+            }
+            _ => ()
+        }
+    }
+
+    /// Append a frame whose input is confirmed to the spectator stream.
+    fn stream_to_spectators(&mut self, core: &SuperShuckieCore, frame: NetplayFrame) {
+        if self.spectators.is_empty() {
+            return
+        }
+
+        let input = encode_input(core, combine_inputs(core, &frame.local_input, &frame.remote_input));
+        let mut input_buffer = InputBuffer::new();
+        input_buffer.extend_from_slice(&input);
+
+        if self.spectator_keyframe_frame.is_none_or(|f| frame.frame - f >= SPECTATOR_KEYFRAME_INTERVAL) {
+            // spectators that haven't received the old keyframe's data skip to this one
+            self.spectator_stream_start += self.spectator_stream.len() as u64;
+            self.spectator_stream.clear();
+            self.spectator_keyframe_frame = Some(frame.frame);
+
+            let mut state = ByteVec::new();
+            state.extend_from_slice(&frame.state);
+            self.write_spectator_packet(&Packet::Keyframe {
+                metadata: KeyframeMetadata {
+                    input: input_buffer,
+                    elapsed_frames: frame.frame,
+                    ..Default::default()
+                },
+                state
+            });
+        }
+        else if input != self.spectator_input {
+            self.write_spectator_packet(&Packet::ChangeInput { data: input_buffer });
+        }
+
+        // spectators play at their own pace
+        self.write_spectator_packet(&Packet::NextFrame { timestamp_delta: 0 });
+        self.spectator_input = input;
+    }
+
+    fn write_spectator_packet(&mut self, packet: &Packet) {
+        // writing to a Vec can only fail if out of memory
+        let _ = self.spectator_stream.write_packet_data(&packet.write_packet_instructions());
+    }
+
+    /// Send any unacknowledged part of the spectator stream to each spectator, and drop spectators
+    /// that timed out.
+    fn send_to_spectators(&mut self, now: Instant) {
+        self.spectators.retain(|s| now.duration_since(s.last_received) < PEER_TIMEOUT);
+
+        let stream_start = self.spectator_stream_start;
+        let stream_end = stream_start + self.spectator_stream.len() as u64;
+        for spectator in &mut self.spectators {
+            // skip anything from before the latest keyframe
+            spectator.acked = spectator.acked.max(stream_start);
+            spectator.sent = spectator.sent.max(spectator.acked);
+
+            let mut keepalive = false;
+            if now.duration_since(spectator.last_progress) >= RESEND_INTERVAL {
+                spectator.sent = spectator.acked;
+                spectator.last_progress = now;
+                keepalive = true;
+            }
+
+            while keepalive || (spectator.sent < stream_end && spectator.sent - spectator.acked < MAX_SPECTATOR_BYTES_IN_FLIGHT) {
+                let offset = (spectator.sent - stream_start) as usize;
+                let data = &self.spectator_stream[offset..(offset + STATE_CHUNK_SIZE).min(self.spectator_stream.len())];
+
+                let mut packet = Self::begin_packet(PacketKind::SpectatorData);
+                packet.extend_from_slice(&stream_start.to_le_bytes());
+                packet.extend_from_slice(&spectator.sent.to_le_bytes());
+                packet.extend_from_slice(data);
+
+                // packets are resent until acknowledged, so failures can be ignored
+                let _ = self.socket.send_to(&packet, spectator.address);
+                spectator.sent += data.len() as u64;
+                keepalive = false;
+            }
+        }
+    }
+
+    fn receive_spectator_data(&mut self, body: &[u8]) {
+        let (Some(keyframe_offset), Some(offset), Some(data)) = (read_u64(body, 0), read_u64(body, 8), body.get(16..)) else {
+            return
+        };
+
+        // anything before the latest keyframe was dropped by the host
+        if keyframe_offset > self.spectator_offset {
+            self.spectator_offset = keyframe_offset;
+            self.spectator_partial.clear();
+        }
+
+        if offset == self.spectator_offset && !data.is_empty() {
+            self.spectator_offset += data.len() as u64;
+            self.spectator_partial.extend_from_slice(data);
+
+            let mut remaining = self.spectator_partial.as_slice();
+            loop {
+                // read_all may advance past part of an incomplete packet
+                let mut cursor = remaining;
+                match Packet::read_all(&mut cursor) {
+                    Ok(packet) => {
+                        remaining = cursor;
+                        if matches!(packet, Packet::NextFrame { .. }) {
+                            self.spectator_buffered_frames += 1;
+                        }
+                        self.spectator_packets.push_back(packet);
+                    }
+                    Err(PacketReadError::NotEnoughData) => break,
+                    Err(PacketReadError::ParseFail { .. }) => {
+                        self.status = NetplayStatus::Disconnected;
+                        return
+                    }
+                }
+            }
+            let consumed = self.spectator_partial.len() - remaining.len();
+            self.spectator_partial.drain(..consumed);
+        }
+
+        if self.status == NetplayStatus::WaitingForPeer {
+            self.status = NetplayStatus::Synchronizing;
+        }
+
+        let mut packet = Self::begin_packet(PacketKind::SpectatorAck);
+        packet.extend_from_slice(&self.spectator_offset.to_le_bytes());
+        self.send(&packet);
+    }
+
+    /// Set up the next frame from the spectator stream.
+    ///
+    /// Returns false if the core should not run yet.
+    fn play_spectator_frame(&mut self, core: &mut SuperShuckieCore, running: bool) -> bool {
+        // a frame is already in progress
+        if core.mid_frame || core.input_override.is_some() {
+            return true
+        }
+
+        if self.status == NetplayStatus::Synchronizing {
+            if self.spectator_buffered_frames < SPECTATOR_DELAY_FRAMES {
+                return false
+            }
+            self.status = NetplayStatus::Connected;
+        }
+
+        if !running {
+            return false
+        }
+
+        // buffer again if the host is falling behind
+        if self.spectator_buffered_frames == 0 {
+            self.status = NetplayStatus::Synchronizing;
+            return false
+        }
+
+        if self.spectator_buffered_frames > MAX_SPECTATOR_BUFFERED_FRAMES {
+            core.core.set_rendering_enabled(false);
+            while self.spectator_buffered_frames > SPECTATOR_DELAY_FRAMES && let Some(input) = self.next_spectator_frame(core) {
+                core.run_frame_untracked(input);
+            }
+            core.core.set_rendering_enabled(true);
+        }
+
+        if let Some(input) = self.next_spectator_frame(core) {
+            core.input_override = Some(input);
+        }
+        true
+    }
+
+    /// Apply packets from the spectator stream up to the next frame, returning its input.
+    fn next_spectator_frame(&mut self, core: &mut SuperShuckieCore) -> Option<Input> {
+        while let Some(packet) = self.spectator_packets.pop_front() {
+            match packet {
+                Packet::Keyframe { metadata, state } => {
+                    if core.core.load_save_state(&state).is_err() {
+                        self.status = NetplayStatus::Disconnected;
+                        return None
+                    }
+                    self.spectator_input = metadata.input.to_vec();
+                }
+                Packet::ChangeInput { data } => {
+                    self.spectator_input = data.to_vec();
+                }
+                Packet::NextFrame { .. } => {
+                    self.spectator_buffered_frames -= 1;
+                    return Some(core.get_core().decode_input(&self.spectator_input).unwrap_or_default())
+                }
+                _ => ()
+            }
+        }
+        None
+    }
+
+    fn send_inputs(&mut self) {
+        let inputs: Vec<(&u64, &Vec<u8>)> = self.local_inputs.range(self.remote_ack_frame..).take(MAX_INPUTS_PER_PACKET).collect();
+        let first_frame = inputs.first().map_or(self.remote_ack_frame, |(frame, _)| **frame);
+
+        let mut packet = Self::begin_packet(PacketKind::Inputs);
+        packet.extend_from_slice(&self.remote_next_frame.to_le_bytes());
+        packet.extend_from_slice(&first_frame.to_le_bytes());
+        packet.push(inputs.len() as u8);
+        for (_, input) in inputs {
+            packet.extend_from_slice(&(input.len() as u16).to_le_bytes());
+            packet.extend_from_slice(input);
+        }
+
+        self.send(&packet);
+    }
+
+    fn send_state_chunks(&mut self) {
+        let total_length = self.sync_state.len() as u32;
+        let mut packets = Vec::with_capacity(self.sync_state.len().div_ceil(STATE_CHUNK_SIZE));
+        for (index, chunk) in self.sync_state.chunks(STATE_CHUNK_SIZE).enumerate() {
+            let mut packet = Self::begin_packet(PacketKind::StateChunk);
+            packet.extend_from_slice(&total_length.to_le_bytes());
+            packet.extend_from_slice(&((index * STATE_CHUNK_SIZE) as u32).to_le_bytes());
+            packet.extend_from_slice(chunk);
+            packets.push(packet);
+        }
+        for packet in packets {
+            self.send(&packet);
+        }
+    }
+
+    fn begin_packet(kind: PacketKind) -> Vec<u8> {
+        let mut packet = Vec::with_capacity(PACKET_HEADER_SIZE);
+        packet.extend_from_slice(&PACKET_MAGIC);
+        packet.push(PROTOCOL_VERSION);
+        packet.push(kind as u8);
+        packet
+    }
+
+    fn send(&mut self, packet: &[u8]) {
+        if let Some(peer) = self.peer {
+            self.send_to(packet, peer);
+        }
+    }
+
+    fn send_to(&mut self, packet: &[u8], to: SocketAddr) {
+        // packets are resent until acknowledged, so failures can be ignored
+        let _ = self.socket.send_to(packet, to);
+        self.last_sent = Some(Instant::now());
+    }
+}
+
+impl Drop for NetplaySession {
+    fn drop(&mut self) {
+        let goodbye = Self::begin_packet(PacketKind::Goodbye);
+        if matches!(self.status, NetplayStatus::Synchronizing | NetplayStatus::Connected) {
+            self.send(&goodbye);
+        }
+        for spectator in &self.spectators {
+            let _ = self.socket.send_to(&goodbye, spectator.address);
+        }
+    }
+}
+
+fn encode_input(core: &SuperShuckieCore, input: Input) -> Vec<u8> {
+    let mut encoded = Vec::new();
+    core.get_core().encode_input(input, &mut encoded);
+    encoded
+}
+
+fn combine_inputs(core: &SuperShuckieCore, local: &[u8], remote: &[u8]) -> Input {
+    let core = core.get_core();
+    let mut input = core.decode_input(local).unwrap_or_default() | core.decode_input(remote).unwrap_or_default();
+    core.input_descriptor().mask_input(&mut input);
+    input
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(data.get(offset..offset + 2)?.try_into().ok()?))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(data.get(offset..offset + 4)?.try_into().ok()?))
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Option<u64> {
+    Some(u64::from_le_bytes(data.get(offset..offset + 8)?.try_into().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::emulator::NullEmulatorCore;
+    use crate::std_timestamp_provider;
+    use std::boxed::Box;
+
+    fn null_core() -> SuperShuckieCore {
+        SuperShuckieCore::new(Box::new(NullEmulatorCore), std_timestamp_provider())
+    }
+
+    fn session(role: NetplayRole) -> NetplaySession {
+        let mut session = NetplaySession::host(0, 0).expect("failed to bind");
+        session.role = role;
+        session
+    }
+
+    fn inputs_body(ack_frame: u64, first_frame: u64, count: u8, inputs: &[&[u8]]) -> Vec<u8> {
+        let mut body = Vec::new();
+        body.extend_from_slice(&ack_frame.to_le_bytes());
+        body.extend_from_slice(&first_frame.to_le_bytes());
+        body.push(count);
+        for input in inputs {
+            body.extend_from_slice(&(input.len() as u16).to_le_bytes());
+            body.extend_from_slice(input);
+        }
+        body
+    }
+
+    fn state_chunk_body(total_length: u32, offset: u32, data: &[u8]) -> Vec<u8> {
+        let mut body = Vec::new();
+        body.extend_from_slice(&total_length.to_le_bytes());
+        body.extend_from_slice(&offset.to_le_bytes());
+        body.extend_from_slice(data);
+        body
+    }
+
+    #[test]
+    fn inputs_are_received_in_order() {
+        let core = null_core();
+        let mut session = session(NetplayRole::Host);
+        session.start_session(&core);
+
+        session.receive_inputs(&core, &inputs_body(4, 0, 3, &[&[], &[], &[]]));
+        assert_eq!(session.remote_ack_frame, 4);
+        assert_eq!(session.remote_next_frame, 3);
+        assert_eq!(session.remote_inputs.keys().copied().collect::<Vec<_>>(), [0, 1, 2]);
+
+        // frames 3 and 4 were lost
+        session.receive_inputs(&core, &inputs_body(2, 5, 1, &[&[]]));
+        assert_eq!(session.remote_ack_frame, 4);
+        assert_eq!(session.remote_next_frame, 3);
+    }
+
+    #[test]
+    fn invalid_inputs_are_dropped() {
+        let core = null_core();
+        let mut session = session(NetplayRole::Host);
+        session.start_session(&core);
+
+        // the frame range overflows
+        session.receive_inputs(&core, &inputs_body(0, u64::MAX - 1, 4, &[&[], &[], &[], &[]]));
+        assert_eq!(session.remote_next_frame, 0);
+
+        // only one of the two inputs is present
+        session.receive_inputs(&core, &inputs_body(0, 0, 2, &[&[]]));
+        assert_eq!(session.remote_next_frame, 1);
+
+        // the core can't decode the input
+        session.receive_inputs(&core, &inputs_body(0, 1, 1, &[&[1]]));
+        assert_eq!(session.remote_next_frame, 1);
+
+        // too short to have a header
+        session.receive_inputs(&core, &[0; 16]);
+        assert_eq!(session.remote_next_frame, 1);
+    }
+
+    #[test]
+    fn invalid_state_chunks_are_dropped() {
+        let mut core = null_core();
+        let mut session = session(NetplayRole::Join);
+
+        session.receive_state_chunk(&mut core, &state_chunk_body(MAX_STATE_SIZE as u32 + 1, 0, &[0; 16]));
+        session.receive_state_chunk(&mut core, &state_chunk_body(4096, 1, &[0; 16]));
+        session.receive_state_chunk(&mut core, &state_chunk_body(4096, 0, &[0; STATE_CHUNK_SIZE + 1]));
+        session.receive_state_chunk(&mut core, &state_chunk_body(16, 0, &[0; 32]));
+        session.receive_state_chunk(&mut core, &[0; 7]);
+        assert_eq!(session.status, NetplayStatus::WaitingForPeer);
+        assert!(session.sync_state.is_empty());
+    }
+
+    #[test]
+    fn state_is_loaded_once_every_chunk_is_received() {
+        let mut core = null_core();
+        let mut session = session(NetplayRole::Join);
+        let total_length = (STATE_CHUNK_SIZE + 16) as u32;
+
+        session.receive_state_chunk(&mut core, &state_chunk_body(total_length, STATE_CHUNK_SIZE as u32, &[0; 16]));
+        assert_eq!(session.status, NetplayStatus::Synchronizing);
+
+        // resent chunks don't complete the state
+        session.receive_state_chunk(&mut core, &state_chunk_body(total_length, STATE_CHUNK_SIZE as u32, &[0; 16]));
+        assert_eq!(session.status, NetplayStatus::Synchronizing);
+
+        session.receive_state_chunk(&mut core, &state_chunk_body(total_length, 0, &[0; STATE_CHUNK_SIZE]));
+        assert_eq!(session.status, NetplayStatus::Connected);
+    }
+
+    #[test]
+    fn mispredicted_frames_are_emulated_again() {
+        let mut core = null_core();
+        let mut session = session(NetplayRole::Host);
+        session.start_session(&core);
+
+        // predict that the remote input stays as something other than what was actually sent
+        let wrong_prediction = vec![1];
+        session.last_remote_input.clone_from(&wrong_prediction);
+        for _ in 0..3 {
+            session.advance(&mut core);
+            core.input_override = None;
+        }
+        assert!(session.history.iter().all(|f| f.remote_input == wrong_prediction && !f.remote_confirmed));
+
+        session.receive_inputs(&core, &inputs_body(0, 0, 1, &[&[]]));
+        assert_eq!(session.misprediction, Some(0));
+        assert!(session.history[0].remote_confirmed);
+        assert!(session.history[0].remote_input.is_empty());
+
+        let frame = session.misprediction.take().expect("a misprediction should be pending");
+        session.rollback(&mut core, frame);
+        assert_eq!(session.status, NetplayStatus::Connected);
+        assert!(session.history.iter().all(|f| f.remote_input.is_empty()));
+        assert!(!session.history[1].remote_confirmed && !session.history[2].remote_confirmed);
+    }
+}
@@ -0,0 +1,141 @@
+use crate::emulator::{EmulatorCore, RamSnapshot};
+use crate::{RamAccessError, RamAccessKind};
+use std::borrow::ToOwned;
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::JoinHandle;
+use std::vec::Vec;
+use supershuckie_pokeabyte_integration::PokeAByteIntegrationServer;
+
+/// Snapshot handed from the core thread to the mirror thread.
+#[derive(Default)]
+struct PendingSnapshot {
+    snapshot: RamSnapshot,
+
+    /// Frame the snapshot was taken on
+    frame: u64,
+
+    /// Set if the mirror thread has not taken the snapshot yet
+    fresh: bool,
+
+    /// Set when the mirror thread should exit
+    closed: bool
+}
+
+#[derive(Default)]
+struct MirrorShared {
+    pending: Mutex<PendingSnapshot>,
+    ready: Condvar,
+
+    /// The first read that failed since the core thread last checked
+    read_error: Mutex<Option<RamAccessError>>
+}
+
+/// Copies RAM snapshots into Poke-A-Byte's shared memory on its own thread, so mirroring doesn't
+/// take time away from emulation.
+///
+/// Writes from Poke-A-Byte still go through the core thread, since they have to be applied (and
+/// recorded) between frames.
+pub(crate) struct PokeAByteMirror {
+    shared: Arc<MirrorShared>,
+    thread: Option<JoinHandle<()>>
+}
+
+impl PokeAByteMirror {
+    /// Start mirroring to the session of `integration`, if any.
+    pub(crate) fn new(integration: Arc<PokeAByteIntegrationServer>) -> Self {
+        let shared = Arc::new(MirrorShared::default());
+        let thread = {
+            let shared = shared.clone();
+            std::thread::Builder::new()
+                .name("PokeAByteMirror".to_owned())
+                .spawn(move || Self::run_thread(&shared, &integration))
+                .ok()
+        };
+
+        Self { shared, thread }
+    }
+
+    /// Snapshot RAM for the mirror thread to copy, replacing any snapshot it hasn't gotten to yet.
+    pub(crate) fn submit(&self, core: &dyn EmulatorCore, frame: u64) {
+        let mut pending = self.shared.pending.lock().expect("can't get pending snapshot mutex submit");
+        core.snapshot_ram(&mut pending.snapshot);
+        pending.frame = frame;
+        pending.fresh = true;
+        drop(pending);
+
+        self.shared.ready.notify_one();
+    }
+
+    /// Take the first read that failed since the last call, if any.
+    pub(crate) fn take_read_error(&self) -> Option<RamAccessError> {
+        self.shared.read_error
+            .lock()
+            .expect("can't get read error mutex take_read_error")
+            .take()
+    }
+
+    fn run_thread(shared: &MirrorShared, integration: &PokeAByteIntegrationServer) {
+        let mut snapshot = RamSnapshot::default();
+        let mut read_buffer = Vec::new();
+
+        loop {
+            let frame = {
+                let mut pending = shared.pending.lock().expect("can't get pending snapshot mutex run_thread");
+                while !pending.fresh && !pending.closed {
+                    pending = shared.ready.wait(pending).expect("can't wait for pending snapshot run_thread");
+                }
+                if pending.closed {
+                    return
+                }
+                pending.fresh = false;
+                core::mem::swap(&mut pending.snapshot, &mut snapshot);
+                pending.frame
+            };
+
+            let mut session_lock = integration.get_session();
+            let Some(session) = session_lock.as_mut() else {
+                continue
+            };
+
+            // SAFETY: "Only one way to find out"
+            let ram = unsafe { session.shared_memory.get_memory_mut() };
+            for read in &session.config.reads {
+                let result = match read.direct_target() {
+                    Some(range) => {
+                        let into = ram.get_mut(range).expect("read range was wrong (this should have been checked!)");
+                        snapshot.read(read.game_address, into)
+                    },
+                    None => {
+                        read_buffer.resize(read.length, 0);
+                        let result = snapshot.read(read.game_address, &mut read_buffer);
+                        if result.is_ok() {
+                            read.scatter(&read_buffer, ram);
+                        }
+                        result
+                    }
+                };
+
+                if let Err(error) = result {
+                    shared.read_error
+                        .lock()
+                        .expect("can't get read error mutex run_thread")
+                        .get_or_insert(RamAccessError { kind: RamAccessKind::Read, address: read.game_address, length: read.length, frame, error });
+                }
+            }
+            session.shared_memory.publish();
+        }
+    }
+}
+
+impl Drop for PokeAByteMirror {
+    fn drop(&mut self) {
+        if let Ok(mut pending) = self.shared.pending.lock() {
+            pending.closed = true;
+        }
+        self.shared.ready.notify_one();
+
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
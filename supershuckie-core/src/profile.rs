@@ -0,0 +1,126 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// Number of frames averaged by [`FrameProfiler`].
+const PROFILED_FRAMES: usize = 60;
+
+/// Part of the core thread's frame loop.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum FrameSection {
+    /// Running the emulator core, not including replay recording
+    Emulation,
+
+    /// Copying screen data to the owning thread
+    ScreenCopy,
+
+    /// Applying writes from Poke-A-Byte and snapshotting RAM for it (the snapshot is mirrored on
+    /// its own thread)
+    PokeAByte,
+
+    /// Recording a replay, including creating keyframes
+    Recording
+}
+
+impl FrameSection {
+    /// All sections.
+    pub const ALL: [FrameSection; 4] = [Self::Emulation, Self::ScreenCopy, Self::PokeAByte, Self::Recording];
+}
+
+/// Average time spent per frame in each part of the core thread.
+///
+/// See [`ThreadedSuperShuckieCore::get_frame_timings`](crate::ThreadedSuperShuckieCore::get_frame_timings).
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct FrameTimings {
+    /// Time spent running the emulator core, not including replay recording
+    pub emulation: Duration,
+
+    /// Time spent copying screen data to the owning thread
+    pub screen_copy: Duration,
+
+    /// Time spent mirroring memory to and from Poke-A-Byte
+    pub pokeabyte: Duration,
+
+    /// Time spent recording a replay, including creating keyframes
+    pub recording: Duration,
+
+    /// Number of frames that were averaged
+    pub frames: u32
+}
+
+impl FrameTimings {
+    /// Get the total time spent per frame.
+    pub fn total(&self) -> Duration {
+        self.emulation + self.screen_copy + self.pokeabyte + self.recording
+    }
+
+    /// Get the time spent in the section.
+    pub fn get(&self, section: FrameSection) -> Duration {
+        match section {
+            FrameSection::Emulation => self.emulation,
+            FrameSection::ScreenCopy => self.screen_copy,
+            FrameSection::PokeAByte => self.pokeabyte,
+            FrameSection::Recording => self.recording
+        }
+    }
+
+    fn section_mut(&mut self, section: FrameSection) -> &mut Duration {
+        match section {
+            FrameSection::Emulation => &mut self.emulation,
+            FrameSection::ScreenCopy => &mut self.screen_copy,
+            FrameSection::PokeAByte => &mut self.pokeabyte,
+            FrameSection::Recording => &mut self.recording
+        }
+    }
+}
+
+/// Keeps a rolling average of [`FrameTimings`] over the last [`PROFILED_FRAMES`] frames.
+pub(crate) struct FrameProfiler {
+    current: FrameTimings,
+    history: VecDeque<FrameTimings>,
+    last_frame: u64
+}
+
+impl FrameProfiler {
+    pub(crate) fn new() -> Self {
+        Self {
+            current: FrameTimings::default(),
+            history: VecDeque::with_capacity(PROFILED_FRAMES),
+            last_frame: 0
+        }
+    }
+
+    /// Add time spent in a section of the current frame.
+    pub(crate) fn add(&mut self, section: FrameSection, time: Duration) {
+        let total = self.current.section_mut(section);
+        *total = total.saturating_add(time);
+    }
+
+    /// Finish the current frame if `total_frames` advanced, returning the new rolling average.
+    pub(crate) fn finish_frame(&mut self, total_frames: u64) -> Option<FrameTimings> {
+        if total_frames == self.last_frame {
+            return None
+        }
+        self.last_frame = total_frames;
+
+        if self.history.len() == PROFILED_FRAMES {
+            self.history.pop_front();
+        }
+        self.history.push_back(core::mem::take(&mut self.current));
+
+        let frames = self.history.len() as u32;
+        let mut average = FrameTimings { frames, ..FrameTimings::default() };
+        for section in FrameSection::ALL {
+            let total: Duration = self.history.iter().map(|i| i.get(section)).sum();
+            *average.section_mut(section) = total / frames;
+        }
+
+        Some(average)
+    }
+
+    /// Discard all timings, such as after pausing.
+    pub(crate) fn reset(&mut self, total_frames: u64) {
+        self.current = FrameTimings::default();
+        self.history.clear();
+        self.last_frame = total_frames;
+    }
+}
@@ -0,0 +1,130 @@
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+use core::num::NonZeroU64;
+use supershuckie_replay_recorder::{apply_state_diff, create_state_diff, ByteVec};
+
+/// Defines parameters for rewinding (see [`SuperShuckieCore::set_rewind_settings`](crate::SuperShuckieCore::set_rewind_settings)).
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct RewindSettings {
+    /// Number of frames between captured states.
+    ///
+    /// This is also how far back each rewound frame goes while rewinding is held.
+    pub interval: NonZeroU64,
+
+    /// Maximum number of bytes used by captured states.
+    ///
+    /// The oldest states are discarded first. The most recent state is always kept, even if it
+    /// alone exceeds this.
+    pub memory_budget: usize
+}
+
+impl Default for RewindSettings {
+    fn default() -> Self {
+        Self {
+            interval: NonZeroU64::new(4).unwrap(),
+            memory_budget: 64 * 1024 * 1024
+        }
+    }
+}
+
+// Only the newest state is stored in full. Every older state is stored as a diff against the state
+// captured after it, so the oldest state can be discarded without touching anything else, and
+// stepping back only needs to apply one diff at a time.
+
+struct RewindDiff {
+    diff: ByteVec,
+    frame: u64
+}
+
+/// Ring buffer of compact save states.
+pub(crate) struct RewindBuffer {
+    settings: RewindSettings,
+
+    /// Frames run since the buffer was created, not counting rewound frames.
+    frame: u64,
+    frames_since_capture: u64,
+
+    newest: Option<(Vec<u8>, u64)>,
+    older: VecDeque<RewindDiff>,
+    used_bytes: usize
+}
+
+impl RewindBuffer {
+    pub fn new(settings: RewindSettings) -> Self {
+        Self {
+            settings,
+            frame: 0,
+            frames_since_capture: 0,
+            newest: None,
+            older: VecDeque::new(),
+            used_bytes: 0
+        }
+    }
+
+    pub fn settings(&self) -> RewindSettings {
+        self.settings
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::new(self.settings);
+    }
+
+    /// Count `frames` as run, returning `true` if a state is due.
+    pub fn advance(&mut self, frames: u64) -> bool {
+        self.frame = self.frame.wrapping_add(frames);
+        self.frames_since_capture = self.frames_since_capture.saturating_add(frames);
+        self.newest.is_none() || self.frames_since_capture >= self.settings.interval.get()
+    }
+
+    pub fn push(&mut self, state: Vec<u8>) {
+        self.frames_since_capture = 0;
+        self.used_bytes += state.len();
+
+        if let Some((previous, frame)) = self.newest.take() {
+            let diff = create_state_diff(state.as_slice(), previous.as_slice());
+            self.used_bytes = self.used_bytes - previous.len() + diff.len();
+            self.older.push_back(RewindDiff { diff, frame });
+        }
+        self.newest = Some((state, self.frame));
+
+        while self.used_bytes > self.settings.memory_budget {
+            let Some(oldest) = self.older.pop_front() else {
+                break
+            };
+            self.used_bytes -= oldest.diff.len();
+        }
+    }
+
+    /// Step back to the newest state captured at least `frames` frames ago, or the oldest state
+    /// if none is that old, and return it.
+    ///
+    /// The returned state stays in the buffer, so it can be rewound to again.
+    pub fn rewind(&mut self, frames: u64) -> Option<&[u8]> {
+        let target = self.frame.saturating_sub(frames);
+
+        loop {
+            let (newest, frame) = self.newest.as_ref()?;
+            if *frame <= target {
+                break
+            }
+
+            let Some(older) = self.older.pop_back() else {
+                break
+            };
+
+            let Ok(state) = apply_state_diff(newest.as_slice(), older.diff.as_slice()) else {
+                // Should not happen since we made the diff, but don't keep anything we can't restore
+                self.clear();
+                return None
+            };
+
+            self.used_bytes = self.used_bytes - newest.len() - older.diff.len() + state.len();
+            self.newest = Some((state, older.frame));
+        }
+
+        let (state, frame) = self.newest.as_ref()?;
+        self.frame = *frame;
+        self.frames_since_capture = 0;
+        Some(state.as_slice())
+    }
+}
@@ -1,60 +1,254 @@
-use crate::emulator::{EmulatorCore, Input, PartialReplayRecordMetadata, ScreenData};
-use crate::{std_timestamp_provider, ReplayPlayerAttachError, Speed};
-use crate::{SuperShuckieCore, SuperShuckieRapidFire};
+use crate::emulator::{ColorCorrection, CoreOption, EmulatorCore, Input, InputDescriptor, PartialReplayRecordMetadata, PeripheralOutput, ScreenData};
+use crate::{std_timestamp_provider, CancellationToken, DeterminismCheckSettings, DeterminismMismatch, MonotonicTimestampProvider, RamAccessError, ReplayPlayerAttachError, ReplayPlayerMetadataMismatchKind, RewindSettings, Speed, sram_checksum};
+use crate::{FrameProfiler, FrameSection, FrameTimings, NetplaySession, NetplayStatus, SuperShuckieCore, SuperShuckieRapidFire};
+use crate::pokeabyte_mirror::PokeAByteMirror;
 use std::borrow::ToOwned;
 use std::boxed::Box;
-use std::fs::File;
 use std::string::String;
 use std::sync::atomic::{AtomicI32, AtomicU32, Ordering};
-use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender};
 use std::sync::{Arc, Mutex, TryLockError, Weak};
-use std::time::Duration;
+use std::any::Any;
+use std::collections::VecDeque;
+use std::fmt::Write;
+use std::panic::{catch_unwind, resume_unwind, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use std::vec::Vec;
 use std::format;
+use std::vec;
 #[cfg(feature = "pokeabyte")]
 use supershuckie_pokeabyte_integration::PokeAByteIntegrationServer;
+#[cfg(feature = "pokeabyte")]
+pub use supershuckie_pokeabyte_integration::{PokeAByteFrameSkipPolicy, PokeAByteTransport};
+use supershuckie_replay_recorder::replay_file::{blake3_hash_to_ascii, ReplayFileMetadata, ReplayHeaderBlake3Hash};
 use supershuckie_replay_recorder::replay_file::playback::ReplayFilePlayer;
-use supershuckie_replay_recorder::UnsignedInteger;
+use supershuckie_replay_recorder::replay_file::record::ReplayFileSink;
+use supershuckie_replay_recorder::{ByteVec, TimestampMillis, UnsignedInteger};
+
+/// Sink that replays recorded on the core thread are written to.
+pub type ThreadedReplayFileSink = Box<dyn ReplayFileSink + Send + Sync>;
+
+/// Describes what to do with Poke-A-Byte writes received while a replay is being played back.
+///
+/// Replays can't be written to, so writes can't be applied during playback.
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub enum PokeAByteWritePolicy {
+    /// Reject writes, sending an error response to the client.
+    #[default]
+    Reject,
+
+    /// Hold writes until playback ends, then apply them.
+    Buffer
+}
+
+/// Describes a core that stopped finishing frames.
+///
+/// See [`ThreadedSuperShuckieCore::set_watchdog_timeout`].
+#[derive(Clone, PartialEq, Debug)]
+pub struct CoreHangReport {
+    /// Name of the emulator core
+    pub core_name: &'static str,
+
+    /// Frame the core was stuck on
+    pub frame: u64,
+
+    /// Ticks emulated since the last frame finished
+    pub elapsed_ticks: u64,
+
+    /// Wall clock time since the last frame finished
+    pub elapsed_time: Duration
+}
+
+/// A point of interest in a replay being played back, such as a keyframe or bookmark.
+///
+/// See [`ThreadedSuperShuckieCore::get_playback_keyframes`] and
+/// [`ThreadedSuperShuckieCore::get_playback_bookmarks`].
+#[derive(Clone, PartialEq, Debug)]
+pub struct ReplayMarker {
+    /// Frame the marker is on
+    pub elapsed_frames: UnsignedInteger,
+
+    /// Time the marker is at, in milliseconds
+    pub elapsed_millis: TimestampMillis,
+
+    /// Name of the marker (empty for keyframes)
+    pub name: String
+}
+
+/// A range of memory read after every frame.
+///
+/// See [`ThreadedSuperShuckieCore::set_watched_memory`].
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct WatchedMemoryRange {
+    /// Address to read from (interpreted the same way as [`EmulatorCore::read_ram`])
+    pub address: u32,
+
+    /// Number of bytes to read
+    pub length: usize
+}
+
+/// A long operation that the core thread reports progress for.
+///
+/// See [`ThreadedSuperShuckieCore::take_progress_events`].
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[repr(u32)]
+pub enum CoreOperation {
+    /// Creating a save state
+    CreateSaveState = 0,
+
+    /// Seeking to a frame of a replay being played back
+    SeekReplay = 1,
+
+    /// Finishing a replay being recorded
+    FinishReplay = 2
+}
+
+/// How far along a [`CoreOperation`] is.
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[repr(u32)]
+pub enum CoreOperationStage {
+    /// The operation started
+    Started = 0,
+
+    /// The operation progressed
+    Progress = 1,
+
+    /// The operation finished
+    Finished = 2
+}
+
+/// Reports the progress of a long operation on the core thread.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct CoreProgressEvent {
+    /// Identifies the operation; every event for the same operation has the same ID
+    pub id: u32,
+
+    /// What is being done
+    pub operation: CoreOperation,
+
+    /// How far along the operation is
+    pub stage: CoreOperationStage,
+
+    /// Percent complete, from 0 to 100
+    pub percent: u8
+}
+
+/// Seeks are only reported if they take longer than this, since most are near-instant.
+const SEEK_PROGRESS_DELAY: Duration = Duration::from_millis(250);
+
+/// How often blocking calls check for progress events while waiting on the core thread.
+const PROGRESS_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Default time the core can go without finishing a frame before it is considered hung.
+pub const DEFAULT_WATCHDOG_TIMEOUT: Duration = Duration::from_secs(10);
 
 /// A (mostly) non-blocking, threaded wrapper for [`SuperShuckieCore`].
 pub struct ThreadedSuperShuckieCore {
     screens: Arc<Mutex<Vec<ScreenData>>>,
+    printed_pages: Arc<Mutex<Vec<ScreenData>>>,
+    serial_output: Arc<Mutex<Vec<u8>>>,
+    audio_samples: Arc<Mutex<Vec<i16>>>,
+    peripheral_output: Arc<Mutex<PeripheralOutput>>,
+    applied_input: Arc<Mutex<Input>>,
+    frame_timings: Arc<Mutex<FrameTimings>>,
+    hang_report: Arc<Mutex<Option<CoreHangReport>>>,
+    ram_access_error: Arc<Mutex<Option<RamAccessError>>>,
+    determinism_mismatch: Arc<Mutex<Option<DeterminismMismatch>>>,
+    watched_memory: Arc<Mutex<Vec<Vec<u8>>>>,
     sender: Sender<ThreadCommand>,
     receiver_close: Receiver<()>,
+    progress_events: Receiver<CoreProgressEvent>,
+    input_descriptor: InputDescriptor,
+    rom_checksum: ReplayHeaderBlake3Hash,
+    bios_checksum: ReplayHeaderBlake3Hash,
+    core_name: &'static str,
 
     frame_count: Arc<AtomicU32>,
     elapsed_milliseconds: Arc<AtomicU32>,
+    audio_sample_rate: Arc<AtomicU32>,
     desired_replay_frame: Arc<AtomicU32>,
     delta_replay_frames: Arc<AtomicI32>,
+    netplay_status: Arc<AtomicU32>,
 
     playback: bool,
     playback_total_frames: UnsignedInteger,
     playback_total_milliseconds: UnsignedInteger,
+    playback_keyframes: Vec<ReplayMarker>,
+    playback_bookmarks: Vec<ReplayMarker>,
 }
 
 impl ThreadedSuperShuckieCore {
     /// Wrap the given `core`.
     pub fn new(emulator_core: Box<dyn EmulatorCore>) -> Self {
         let frame_count = Arc::new(AtomicU32::new(0));
+        let input_descriptor = emulator_core.input_descriptor();
+        let rom_checksum = *emulator_core.rom_checksum();
+        let bios_checksum = *emulator_core.bios_checksum();
+        let core_name = emulator_core.core_name();
         let screens = Arc::new(Mutex::new(emulator_core.get_screens().to_vec()));
+        let printed_pages = Arc::new(Mutex::new(Vec::new()));
+        let serial_output = Arc::new(Mutex::new(Vec::new()));
+        let audio_samples = Arc::new(Mutex::new(Vec::new()));
+        let audio_sample_rate = Arc::new(AtomicU32::new(emulator_core.get_audio_sample_rate()));
+        let peripheral_output = Arc::new(Mutex::new(PeripheralOutput::default()));
+        let applied_input = Arc::new(Mutex::new(Input::default()));
+        let frame_timings = Arc::new(Mutex::new(FrameTimings::default()));
+        let hang_report = Arc::new(Mutex::new(None));
+        let ram_access_error = Arc::new(Mutex::new(None));
+        let determinism_mismatch = Arc::new(Mutex::new(None));
+        let watched_memory = Arc::new(Mutex::new(Vec::new()));
         let (sender, receiver) = channel();
         let (sender_close, receiver_close) = channel();
+        let (progress_sender, progress_events) = channel();
 
         let replay_milliseconds = Arc::new(AtomicU32::new(0));
         let playback_total_frames = 0;
         let playback_total_milliseconds = 0;
         let desired_replay_frame = Arc::new(AtomicU32::new(u32::MAX));
         let delta_replay_frames = Arc::new(AtomicI32::new(0));
+        let netplay_status = Arc::new(AtomicU32::new(NetplayStatus::Inactive as u32));
 
         {
             let frame_count = frame_count.clone();
             let screens = Arc::downgrade(&screens);
+            let printed_pages = Arc::downgrade(&printed_pages);
+            let serial_output = Arc::downgrade(&serial_output);
+            let audio_samples = Arc::downgrade(&audio_samples);
+            let audio_sample_rate = audio_sample_rate.clone();
+            let peripheral_output = Arc::downgrade(&peripheral_output);
+            let applied_input = Arc::downgrade(&applied_input);
+            let frame_timings = Arc::downgrade(&frame_timings);
+            let hang_report = Arc::downgrade(&hang_report);
+            let ram_access_error = Arc::downgrade(&ram_access_error);
+            let determinism_mismatch = Arc::downgrade(&determinism_mismatch);
+            let watched_memory = Arc::downgrade(&watched_memory);
             let replay_milliseconds = replay_milliseconds.clone();
             let desired_replay_frame = desired_replay_frame.clone();
             let delta_replay_frames = delta_replay_frames.clone();
+            let netplay_status = netplay_status.clone();
             let _ = std::thread::Builder::new().name("ThreadedSuperShuckieCore".to_owned()).spawn(move || {
-                ThreadedSuperShuckieCoreThread {
+                let mut thread = ThreadedSuperShuckieCoreThread {
                     screens,
+                    printed_pages,
+                    serial_output,
+                    audio_samples,
+                    audio_sample_rate,
+                    peripheral_output,
+                    last_peripheral_output: PeripheralOutput::default(),
+                    applied_input,
+                    last_applied_input: Input::default(),
+                    frame_timings,
+                    profiler: FrameProfiler::new(),
+                    hang_report,
+                    ram_access_error,
+                    determinism_mismatch,
+                    watched_memory,
+                    watched_memory_ranges: Vec::new(),
+                    watchdog_timeout: Some(DEFAULT_WATCHDOG_TIMEOUT),
+                    watchdog_frame: 0,
+                    watchdog_ticks: 0,
+                    watchdog_last_progress: Instant::now(),
                     screens_queued: emulator_core.get_screens().to_vec(),
                     screen_ready_for_copy: false,
                     is_running: false,
@@ -62,26 +256,63 @@ impl ThreadedSuperShuckieCore {
                     pokeabyte_integration: None,
                     receiver,
                     sender_close,
+                    progress_sender,
+                    next_operation_id: 0,
+                    seek_cancellation: None,
                     desired_replay_frame,
                     frame_count,
                     replay_milliseconds,
                     delta_replay_frames,
-                    playback_frozen: false
-                }.run_thread();
+                    playback_frozen: false,
+                    background: false,
+                    pokeabyte_write_policy: PokeAByteWritePolicy::default(),
+                    pokeabyte_frame_skip_policy: PokeAByteFrameSkipPolicy::default(),
+                    pokeabyte_transport: PokeAByteTransport::default(),
+                    pokeabyte_mirror: None,
+                    crash_dump_directory: None,
+                    recent_inputs: VecDeque::new(),
+                    netplay: None,
+                    netplay_status
+                };
+
+                // Try to save whatever we can before the thread goes down
+                if let Err(payload) = catch_unwind(AssertUnwindSafe(|| thread.run_thread())) {
+                    thread.write_crash_dump(payload.as_ref());
+                    resume_unwind(payload);
+                }
             });
         }
 
         Self {
             sender,
             screens,
+            printed_pages,
+            serial_output,
+            audio_samples,
+            peripheral_output,
+            applied_input,
+            frame_timings,
+            hang_report,
+            ram_access_error,
+            determinism_mismatch,
+            watched_memory,
             receiver_close,
+            progress_events,
+            input_descriptor,
+            rom_checksum,
+            bios_checksum,
+            core_name,
             frame_count,
             elapsed_milliseconds: replay_milliseconds,
+            audio_sample_rate,
             playback_total_frames,
             playback_total_milliseconds,
+            playback_keyframes: Vec::new(),
+            playback_bookmarks: Vec::new(),
             playback: false,
             desired_replay_frame,
-            delta_replay_frames
+            delta_replay_frames,
+            netplay_status
         }
     }
 
@@ -93,6 +324,107 @@ impl ThreadedSuperShuckieCore {
         self.frame_count.load(Ordering::Relaxed)
     }
 
+    /// Get the average time spent per frame in each part of the core thread over the last second or
+    /// so of emulation.
+    ///
+    /// This is useful for figuring out why emulation is slow.
+    pub fn get_frame_timings(&self) -> FrameTimings {
+        *self.frame_timings
+            .lock()
+            .expect("can't get frame timings mutex get_frame_timings")
+    }
+
+    /// Take the report of the core hanging, if it hung since the last call.
+    ///
+    /// Emulation is paused when this happens, and it can be resumed with [`start`](Self::start).
+    pub fn take_hang_report(&self) -> Option<CoreHangReport> {
+        self.hang_report
+            .lock()
+            .expect("can't get hang report mutex take_hang_report")
+            .take()
+    }
+
+    /// Take the first RAM access that failed in strict mode since the last call, if any.
+    ///
+    /// Emulation is paused when this happens, and it can be resumed with [`start`](Self::start).
+    /// See [`ThreadedSuperShuckieCore::set_strict_ram_access`].
+    pub fn take_ram_access_error(&self) -> Option<RamAccessError> {
+        self.ram_access_error
+            .lock()
+            .expect("can't get RAM access error mutex take_ram_access_error")
+            .take()
+    }
+
+    /// Take the first mismatch found by the determinism check since this was last called, if any.
+    ///
+    /// See [`ThreadedSuperShuckieCore::set_determinism_check`].
+    pub fn take_determinism_mismatch(&self) -> Option<DeterminismMismatch> {
+        self.determinism_mismatch
+            .lock()
+            .expect("can't get determinism mismatch mutex take_determinism_mismatch")
+            .take()
+    }
+
+    /// Replace the timestamp provider used for the timer.
+    ///
+    /// See [`SuperShuckieCore::set_timestamp_provider`].
+    pub fn set_timestamp_provider(&self, timestamp_provider: Box<dyn MonotonicTimestampProvider + Send>) {
+        self.sender.send(ThreadCommand::SetTimestampProvider(timestamp_provider))
+            .expect("SetTimestampProvider - the core thread has crashed");
+    }
+
+    /// Set whether failed RAM reads and writes (e.g. from Poke-A-Byte or replay playback) pause
+    /// emulation with a [`RamAccessError`] instead of being ignored.
+    ///
+    /// See [`SuperShuckieCore::set_strict_ram_access`].
+    pub fn set_strict_ram_access(&self, strict: bool) {
+        self.sender.send(ThreadCommand::SetStrictRamAccess(strict))
+            .expect("SetStrictRamAccess - the core thread has crashed");
+    }
+
+    /// Take all progress events reported since the last call.
+    ///
+    /// Blocking calls that have a `_with_progress` variant leave their events here unless that
+    /// variant is used.
+    pub fn take_progress_events(&self) -> Vec<CoreProgressEvent> {
+        self.progress_events.try_iter().collect()
+    }
+
+    /// Wait for the core thread to reply, passing any progress events to `on_progress` meanwhile.
+    ///
+    /// Returns `None` without waiting any longer if `cancel` is cancelled.
+    fn wait_with_progress<T>(&self, receiver: Receiver<T>, cancel: Option<&CancellationToken>, mut on_progress: impl FnMut(CoreProgressEvent)) -> Option<T> {
+        loop {
+            let result = receiver.recv_timeout(PROGRESS_POLL_INTERVAL);
+            self.progress_events.try_iter().for_each(&mut on_progress);
+
+            if result.is_err() && cancel.is_some_and(CancellationToken::is_cancelled) {
+                return None
+            }
+
+            match result {
+                Ok(n) => return Some(n),
+                Err(RecvTimeoutError::Timeout) => continue,
+                Err(RecvTimeoutError::Disconnected) => return None
+            }
+        }
+    }
+
+    /// Set how long the core can go without finishing a frame before emulation is paused and a
+    /// [`CoreHangReport`] is made.
+    ///
+    /// If `None`, the core will never be considered hung. The default is [`DEFAULT_WATCHDOG_TIMEOUT`].
+    pub fn set_watchdog_timeout(&self, timeout: Option<Duration>) {
+        self.sender.send(ThreadCommand::SetWatchdogTimeout(timeout))
+            .expect("SetWatchdogTimeout - the core thread has crashed");
+    }
+
+    /// Describe which parts of [`Input`] the core uses.
+    #[inline]
+    pub fn get_input_descriptor(&self) -> InputDescriptor {
+        self.input_descriptor
+    }
+
     /// Read the screens.
     ///
     /// Note that while this function is running, the screen buffer will be blocked from being
@@ -102,6 +434,62 @@ impl ThreadedSuperShuckieCore {
         reader(lock.as_slice())
     }
 
+    /// Take all pages printed since the last call.
+    pub fn take_printed_pages(&self) -> Vec<ScreenData> {
+        let mut lock = self.printed_pages.lock().expect("printed pages mutex is poisoned");
+        core::mem::take(&mut *lock)
+    }
+
+    /// Take all bytes sent over the serial port since the last call while capture was enabled.
+    pub fn take_serial_output(&self) -> Vec<u8> {
+        let mut lock = self.serial_output.lock().expect("serial output mutex is poisoned");
+        core::mem::take(&mut *lock)
+    }
+
+    /// Move all audio samples generated since the last call to the end of `into`.
+    ///
+    /// Samples are signed 16-bit stereo, interleaved left then right, at
+    /// [`get_audio_sample_rate`](Self::get_audio_sample_rate). If they aren't drained, only about a
+    /// second's worth is kept.
+    pub fn drain_audio_samples(&self, into: &mut Vec<i16>) {
+        let mut lock = self.audio_samples.lock().expect("audio samples mutex is poisoned");
+        into.append(&mut lock);
+    }
+
+    /// Get the rate audio samples are generated at, in Hz, or 0 if they are not being generated.
+    pub fn get_audio_sample_rate(&self) -> u32 {
+        self.audio_sample_rate.load(Ordering::Relaxed)
+    }
+
+    /// Set the memory to read after every frame, replacing any memory set before.
+    ///
+    /// Use [`get_watched_memory`](Self::get_watched_memory) to get what was read.
+    pub fn set_watched_memory(&self, ranges: Vec<WatchedMemoryRange>) {
+        self.sender.send(ThreadCommand::SetWatchedMemory(ranges))
+            .expect("SetWatchedMemory - the core thread has crashed");
+    }
+
+    /// Get the memory set with [`set_watched_memory`](Self::set_watched_memory) as of the frame
+    /// last copied to the screens, with one buffer per range, in order.
+    ///
+    /// Ranges that could not be read are zeroed. This is empty until the next frame after the
+    /// memory is set.
+    pub fn get_watched_memory(&self) -> Vec<Vec<u8>> {
+        self.watched_memory.lock().expect("watched memory mutex is poisoned").clone()
+    }
+
+    /// Get the current state of any peripherals that output to the user (e.g. rumble).
+    pub fn get_peripheral_output(&self) -> PeripheralOutput {
+        *self.peripheral_output.lock().expect("peripheral output mutex is poisoned")
+    }
+
+    /// Get the input applied to the current frame.
+    ///
+    /// During replay playback, this is the input the replay is pressing.
+    pub fn get_applied_input(&self) -> Input {
+        *self.applied_input.lock().expect("applied input mutex is poisoned")
+    }
+
     /// Start running continuously.
     pub fn start(&self) {
         self.sender.send(ThreadCommand::Start)
@@ -114,24 +502,80 @@ impl ThreadedSuperShuckieCore {
             .expect("Pause - the core thread has crashed");
     }
 
+    /// Run exactly one frame while paused (frame advance).
+    ///
+    /// This does nothing while running, playing back a replay (use
+    /// [`advance_playback_frames`](Self::advance_playback_frames) instead), or in a netplay session.
+    pub fn run_single_frame(&self) {
+        self.sender.send(ThreadCommand::RunSingleFrame)
+            .expect("RunSingleFrame - the core thread has crashed");
+    }
+
     /// Pause running temporarily.
     pub fn set_playback_frozen(&self, paused: bool) {
         self.sender.send(ThreadCommand::SetPlaybackFrozen(paused))
             .expect("SetPlaybackFrozen - the core thread has crashed");
     }
 
+    /// Set whether or not nobody is looking at the screens (e.g. the window is minimized).
+    ///
+    /// While in the background, the screens aren't copied, and the thread sleeps instead of
+    /// spinning while idle, but emulation and the Poke-A-Byte integration server keep running.
+    pub fn set_background(&self, background: bool) {
+        self.sender.send(ThreadCommand::SetBackground(background))
+            .expect("SetBackground - the core thread has crashed");
+    }
+
+    /// Set whether or not the core thread should be scheduled with a lower priority (e.g. to save
+    /// power).
+    ///
+    /// This currently only does anything on Linux.
+    pub fn set_low_priority(&self, low_priority: bool) {
+        self.sender.send(ThreadCommand::SetLowPriority(low_priority))
+            .expect("SetLowPriority - the core thread has crashed");
+    }
+
+    /// Set how the Poke-A-Byte integration server listens for clients.
+    ///
+    /// This takes effect the next time the server is enabled.
+    pub fn set_pokeabyte_transport(&self, transport: PokeAByteTransport) {
+        self.sender.send(ThreadCommand::SetPokeAByteTransport(transport))
+            .expect("SetPokeAByteTransport - the core thread has crashed");
+    }
+
     /// Attach/detach a Poke-A-Byte integration server.
-    pub fn set_pokeabyte_enabled(&self, enabled: bool) -> Result<(), String> {
+    ///
+    /// If enabled, returns the transport actually listened with, which may use a different port
+    /// than requested if the requested one was in use.
+    pub fn set_pokeabyte_enabled(&self, enabled: bool) -> Result<Option<PokeAByteTransport>, String> {
         let (sender, receiver) = channel();
 
         self.sender.send(ThreadCommand::SetPokeAByteEnabled(enabled, sender))
             .expect("SetPokeAByteEnabled - the core thread has crashed");
 
-        receiver.recv().ok().unwrap_or(Ok(()))
+        receiver.recv().ok().unwrap_or(Ok(None))
+    }
+
+    /// Set what to do with Poke-A-Byte writes received during replay playback.
+    pub fn set_pokeabyte_write_policy(&self, policy: PokeAByteWritePolicy) {
+        self.sender.send(ThreadCommand::SetPokeAByteWritePolicy(policy))
+            .expect("SetPokeAByteWritePolicy - the core thread has crashed");
+    }
+
+    /// Set how the number of frames Poke-A-Byte suggests skipping between reads is handled.
+    pub fn set_pokeabyte_frame_skip_policy(&self, policy: PokeAByteFrameSkipPolicy) {
+        self.sender.send(ThreadCommand::SetPokeAByteFrameSkipPolicy(policy))
+            .expect("SetPokeAByteFrameSkipPolicy - the core thread has crashed");
+    }
+
+    /// Disconnect the current Poke-A-Byte client, if any, so another client can connect.
+    pub fn disconnect_pokeabyte_client(&self) {
+        self.sender.send(ThreadCommand::DisconnectPokeAByteClient)
+            .expect("DisconnectPokeAByteClient - the core thread has crashed");
     }
 
     /// Stop recording replay.
-    pub fn start_recording_replay(&self, metadata: PartialReplayRecordMetadata<File, File>) {
+    pub fn start_recording_replay(&self, metadata: PartialReplayRecordMetadata<ThreadedReplayFileSink, ThreadedReplayFileSink>) {
         self.sender.send(ThreadCommand::StartRecordingReplay(metadata))
             .expect("StopRecordingReplay - the core thread has crashed");
     }
@@ -146,6 +590,24 @@ impl ThreadedSuperShuckieCore {
         receiver.recv().ok().unwrap_or(false)
     }
 
+    /// Stop recording replay, passing progress events to `on_progress` while the replay is finished.
+    pub fn stop_recording_replay_with_progress(&self, on_progress: impl FnMut(CoreProgressEvent)) -> bool {
+        let (sender, receiver) = channel();
+
+        self.sender.send(ThreadCommand::StopRecordingReplay(sender))
+            .expect("StopRecordingReplay - the core thread has crashed");
+
+        self.wait_with_progress(receiver, None, on_progress).unwrap_or(false)
+    }
+
+    /// Set the directory to write crash dumps to if the core thread panics.
+    ///
+    /// If `None`, no crash dumps will be written.
+    pub fn set_crash_dump_directory(&self, directory: Option<PathBuf>) {
+        self.sender.send(ThreadCommand::SetCrashDumpDirectory(directory))
+            .expect("SetCrashDumpDirectory - the core thread has crashed");
+    }
+
     /// Enqueue an input.
     pub fn enqueue_input(&self, input: Input) {
         self.sender.send(ThreadCommand::EnqueueInput(input))
@@ -164,6 +626,108 @@ impl ThreadedSuperShuckieCore {
             .expect("HardReset - the core thread has crashed");
     }
 
+    /// Enqueue multiple writes to be applied together before the next frame.
+    pub fn enqueue_writes(&self, writes: Vec<(u32, ByteVec)>) {
+        self.sender.send(ThreadCommand::EnqueueWrites(writes))
+            .expect("EnqueueWrites - the core thread has crashed");
+    }
+
+    /// Apply the given GameShark and Game Genie codes every frame, replacing any applied before.
+    ///
+    /// Returns `Err` if any code is invalid or does not apply to the loaded game, in which case
+    /// nothing changes.
+    ///
+    /// NOTE: This is blocking.
+    pub fn set_cheats(&self, codes: Vec<String>) -> Result<(), String> {
+        let (sender, receiver) = channel();
+        self.sender.send(ThreadCommand::SetCheats(codes, sender))
+            .expect("SetCheats - the core thread has crashed");
+        receiver.recv().unwrap_or_else(|_| Err("the core thread did not respond".to_owned()))
+    }
+
+    /// Soft reset the console.
+    ///
+    /// Returns `false` if the console does not support soft resetting.
+    ///
+    /// NOTE: This is blocking.
+    pub fn soft_reset(&self) -> bool {
+        let (sender, receiver) = channel();
+        self.sender.send(ThreadCommand::SoftReset(sender))
+            .expect("SoftReset - the core thread has crashed");
+        receiver.recv().unwrap_or(false)
+    }
+
+    /// Connect or disconnect a printer.
+    ///
+    /// Returns `false` if the console does not support a printer.
+    ///
+    /// NOTE: This is blocking.
+    pub fn set_printer_connected(&self, connected: bool) -> bool {
+        let (sender, receiver) = channel();
+        self.sender.send(ThreadCommand::SetPrinterConnected(connected, sender))
+            .expect("SetPrinterConnected - the core thread has crashed");
+        receiver.recv().unwrap_or(false)
+    }
+
+    /// Start or stop capturing bytes sent over the serial port.
+    ///
+    /// Returns `false` if the console does not support it or the serial port is in use (e.g. by a
+    /// printer).
+    ///
+    /// NOTE: This is blocking.
+    pub fn set_serial_capture_enabled(&self, enabled: bool) -> bool {
+        let (sender, receiver) = channel();
+        self.sender.send(ThreadCommand::SetSerialCaptureEnabled(enabled, sender))
+            .expect("SetSerialCaptureEnabled - the core thread has crashed");
+        receiver.recv().unwrap_or(false)
+    }
+
+    /// Set the rate audio samples are generated at, in Hz, or 0 to stop generating them.
+    ///
+    /// Returns `false` if the console does not support audio.
+    ///
+    /// NOTE: This is blocking.
+    pub fn set_audio_sample_rate(&self, sample_rate: u32) -> bool {
+        let (sender, receiver) = channel();
+        self.sender.send(ThreadCommand::SetAudioSampleRate(sample_rate, sender))
+            .expect("SetAudioSampleRate - the core thread has crashed");
+        receiver.recv().unwrap_or(false)
+    }
+
+    /// Set how colors are corrected to look closer to the original hardware's display, if supported.
+    pub fn set_color_correction(&self, color_correction: ColorCorrection) {
+        self.sender.send(ThreadCommand::SetColorCorrection(color_correction))
+            .expect("SetColorCorrection - the core thread has crashed");
+    }
+
+    /// Get all core-specific options and their current values.
+    ///
+    /// NOTE: This is blocking.
+    pub fn list_core_options(&self) -> Vec<CoreOption> {
+        let (sender, receiver) = channel();
+        self.sender.send(ThreadCommand::ListCoreOptions(sender))
+            .expect("ListCoreOptions - the core thread has crashed");
+        receiver.recv().unwrap_or_default()
+    }
+
+    /// Set a core-specific option.
+    ///
+    /// Returns `Err` if the key or value is not valid for the core.
+    ///
+    /// NOTE: This is blocking.
+    pub fn set_core_option(&self, key: String, value: String) -> Result<(), String> {
+        let (sender, receiver) = channel();
+        self.sender.send(ThreadCommand::SetCoreOption(key, value, sender))
+            .expect("SetCoreOption - the core thread has crashed");
+        receiver.recv().unwrap_or_else(|_| Err("the core thread did not respond".to_owned()))
+    }
+
+    /// Add a bookmark to the replay being recorded, if any.
+    pub fn add_replay_bookmark(&self, name: String) {
+        self.sender.send(ThreadCommand::AddReplayBookmark(name))
+            .expect("AddReplayBookmark - the core thread has crashed");
+    }
+
     /// Set the rapid fire input.
     pub fn set_rapid_fire_input(&self, input: Option<SuperShuckieRapidFire>) {
         self.sender.send(ThreadCommand::SetRapidFireInput(input))
@@ -176,6 +740,67 @@ impl ThreadedSuperShuckieCore {
             .expect("SetToggledInput - the core thread has crashed");
     }
 
+    /// Set the number of frames to delay input by.
+    pub fn set_input_delay(&self, frames: usize) {
+        self.sender.send(ThreadCommand::SetInputDelay(frames))
+            .expect("SetInputDelay - the core thread has crashed");
+    }
+
+    /// Enable rewinding with the given settings, or disable it if `None`.
+    pub fn set_rewind_settings(&self, settings: Option<RewindSettings>) {
+        self.sender.send(ThreadCommand::SetRewindSettings(settings))
+            .expect("SetRewindSettings - the core thread has crashed");
+    }
+
+    /// Go back to the newest captured state that is at least `frames` frames old.
+    ///
+    /// This ends any netplay session.
+    pub fn rewind_frames(&self, frames: u64) {
+        self.sender.send(ThreadCommand::Rewind(RewindCommand::Frames(frames)))
+            .expect("Rewind - the core thread has crashed");
+    }
+
+    /// Start or stop rewinding every frame, e.g. while a hotkey is held.
+    ///
+    /// Starting ends any netplay session.
+    pub fn set_rewinding(&self, rewinding: bool) {
+        let command = if rewinding { RewindCommand::Start } else { RewindCommand::Stop };
+        self.sender.send(ThreadCommand::Rewind(command))
+            .expect("Rewind - the core thread has crashed");
+    }
+
+    /// Check replays for determinism while recording, or stop checking if `None`.
+    ///
+    /// See [`SuperShuckieCore::set_determinism_check`].
+    pub fn set_determinism_check(&self, settings: Option<DeterminismCheckSettings>) {
+        self.sender.send(ThreadCommand::SetDeterminismCheck(settings))
+            .expect("SetDeterminismCheck - the core thread has crashed");
+    }
+
+    /// Start a netplay session, replacing the current one (if any).
+    ///
+    /// The session ends if a save state is loaded, the console is reset or rewound, or a replay is recorded
+    /// or played back.
+    pub fn start_netplay(&self, session: NetplaySession) {
+        self.netplay_status.store(session.status() as u32, Ordering::Relaxed);
+        self.sender.send(ThreadCommand::StartNetplay(Box::new(session)))
+            .expect("StartNetplay - the core thread has crashed");
+    }
+
+    /// End the current netplay session, if any.
+    pub fn stop_netplay(&self) {
+        self.netplay_status.store(NetplayStatus::Inactive as u32, Ordering::Relaxed);
+        self.sender.send(ThreadCommand::StopNetplay)
+            .expect("StopNetplay - the core thread has crashed");
+    }
+
+    /// Get the status of the current netplay session.
+    ///
+    /// Note that this may be slightly outdated.
+    pub fn get_netplay_status(&self) -> NetplayStatus {
+        NetplayStatus::from_u32(self.netplay_status.load(Ordering::Relaxed))
+    }
+
     /// Create a save state.
     ///
     /// Returns `None` if no save state could be created for some unknown reason.
@@ -188,10 +813,27 @@ impl ThreadedSuperShuckieCore {
         receiver.recv().ok()
     }
 
-    /// Load a save state.
-    pub fn load_save_state(&self, state: Vec<u8>) {
-        self.sender.send(ThreadCommand::LoadSaveState(state))
+    /// Create a save state, passing progress events to `on_progress` while it is created.
+    ///
+    /// Returns `None` if `cancel` is cancelled before it is done, or if no save state could be
+    /// created for some unknown reason.
+    ///
+    /// NOTE: This is blocking.
+    pub fn create_save_state_with_progress(&self, cancel: Option<&CancellationToken>, on_progress: impl FnMut(CoreProgressEvent)) -> Option<Vec<u8>> {
+        let (sender, receiver) = channel();
+        self.sender.send(ThreadCommand::CreateSaveState(sender))
+            .expect("CreateSaveState - the core thread has crashed");
+        self.wait_with_progress(receiver, cancel, on_progress)
+    }
+
+    /// Load a save state, returning an error if the core rejected it.
+    ///
+    /// NOTE: This is blocking.
+    pub fn load_save_state(&self, state: Vec<u8>) -> Result<(), String> {
+        let (sender, receiver) = channel();
+        self.sender.send(ThreadCommand::LoadSaveState(state, sender))
             .expect("LoadSaveState - the core thread has crashed");
+        receiver.recv().expect("LoadSaveState - the core thread has crashed")
     }
 
     /// Get SRAM.
@@ -230,6 +872,34 @@ impl ThreadedSuperShuckieCore {
         self.playback_total_milliseconds as u32
     }
 
+    /// Get all keyframes in the current playback, sorted by frame.
+    ///
+    /// Only the first keyframe on each frame is included.
+    #[inline]
+    pub fn get_playback_keyframes(&self) -> &[ReplayMarker] {
+        self.playback_keyframes.as_slice()
+    }
+
+    /// Get all bookmarks in the current playback, sorted by frame.
+    #[inline]
+    pub fn get_playback_bookmarks(&self) -> &[ReplayMarker] {
+        self.playback_bookmarks.as_slice()
+    }
+
+    /// Get the name of the emulator core, including its version.
+    #[inline]
+    pub fn get_core_name(&self) -> &'static str {
+        self.core_name
+    }
+
+    /// Get all metadata mismatches between a replay and the loaded ROM, BIOS, SRAM, and core.
+    ///
+    /// NOTE: This is blocking.
+    pub fn get_replay_metadata_mismatches(&self, metadata: &ReplayFileMetadata) -> Vec<ReplayPlayerMetadataMismatchKind> {
+        let sram_checksum = self.get_sram().map(|sram| sram_checksum(&sram)).unwrap_or_default();
+        ReplayPlayerMetadataMismatchKind::find_all(metadata, &self.rom_checksum, &self.bios_checksum, &sram_checksum, self.core_name)
+    }
+
     /// Load the replay.
     pub fn attach_replay_player(&mut self, mut player: ReplayFilePlayer, allow_mismatch: bool) -> Result<(), ReplayPlayerAttachError> {
         player.enable_threading();
@@ -237,10 +907,23 @@ impl ThreadedSuperShuckieCore {
         let total_ticks = player.get_total_milliseconds();
         let total_frames = player.get_total_frames();
 
+        // The player is moved to the core thread, so grab these now
+        let keyframes: Vec<ReplayMarker> = player.all_keyframes()
+            .values()
+            .filter_map(|i| i.first())
+            .map(|i| ReplayMarker { elapsed_frames: i.elapsed_frames, elapsed_millis: i.elapsed_millis, name: String::new() })
+            .collect();
+        let mut bookmarks: Vec<ReplayMarker> = player.all_bookmarks()
+            .values()
+            .flatten()
+            .map(|i| ReplayMarker { elapsed_frames: i.elapsed_frames, elapsed_millis: i.elapsed_millis, name: i.name.clone() })
+            .collect();
+        bookmarks.sort_by_key(|i| i.elapsed_frames);
+
         let (sender, receiver) = channel();
 
         self.sender.send(ThreadCommand::AttachReplayPlayer {
-            player,
+            player: Box::new(player),
             allow_mismatched: allow_mismatch,
             errors: sender
         }).expect("AttachReplayPlayer - the core thread has crashed");
@@ -249,6 +932,8 @@ impl ThreadedSuperShuckieCore {
             Err(_) => {
                 self.playback_total_frames = total_frames;
                 self.playback_total_milliseconds = total_ticks;
+                self.playback_keyframes = keyframes;
+                self.playback_bookmarks = bookmarks;
                 self.playback = true;
                 Ok(())
             },
@@ -260,6 +945,8 @@ impl ThreadedSuperShuckieCore {
     pub fn detach_replay_player(&mut self) {
         self.playback_total_frames = 0;
         self.playback_total_milliseconds = 0;
+        self.playback_keyframes.clear();
+        self.playback_bookmarks.clear();
         self.playback = false;
         self.sender.send(ThreadCommand::DetachReplayPlayer)
             .expect("DetachReplayPlayer - the core thread has crashed")
@@ -272,6 +959,14 @@ impl ThreadedSuperShuckieCore {
         self.desired_replay_frame.store(frame, Ordering::Relaxed);
     }
 
+    /// Skip to the given frame like [`go_to_replay_frame`](Self::go_to_replay_frame), stopping
+    /// early if `cancel` is cancelled.
+    pub fn go_to_replay_frame_cancellable(&self, frame: u32, cancel: CancellationToken) {
+        self.sender.send(ThreadCommand::SetSeekCancellation(cancel))
+            .expect("SetSeekCancellation - the core thread has crashed");
+        self.desired_replay_frame.store(frame, Ordering::Relaxed);
+    }
+
     /// Advance or go back some frames.
     pub fn advance_playback_frames(&self, amount: i32) {
         // similarly use AtomicI32 to avoid clogging the queue
@@ -288,17 +983,23 @@ impl Drop for ThreadedSuperShuckieCore {
     }
 }
 
-// TODO: Option to run just a single frame? Maybe also skip around a replay file to a given
-//       keyframe...
+// TODO: Maybe skip around a replay file to a given keyframe...
 enum ThreadCommand {
     Start,
     Pause,
+    RunSingleFrame,
     SetPlaybackFrozen(bool),
-    SetPokeAByteEnabled(bool, Sender<Result<(), String>>),
-    StartRecordingReplay(PartialReplayRecordMetadata<File, File>),
+    SetBackground(bool),
+    SetLowPriority(bool),
+    SetPokeAByteEnabled(bool, Sender<Result<Option<PokeAByteTransport>, String>>),
+    SetPokeAByteWritePolicy(PokeAByteWritePolicy),
+    SetPokeAByteTransport(PokeAByteTransport),
+    SetPokeAByteFrameSkipPolicy(PokeAByteFrameSkipPolicy),
+    DisconnectPokeAByteClient,
+    StartRecordingReplay(PartialReplayRecordMetadata<ThreadedReplayFileSink, ThreadedReplayFileSink>),
     StopRecordingReplay(Sender<bool>),
     AttachReplayPlayer {
-        player: ReplayFilePlayer,
+        player: Box<ReplayFilePlayer>,
         allow_mismatched: bool,
         errors: Sender<ReplayPlayerAttachError>
     },
@@ -306,16 +1007,108 @@ enum ThreadCommand {
     EnqueueInput(Input),
     SetRapidFireInput(Option<SuperShuckieRapidFire>),
     SetToggledInput(Option<Input>),
+    SetInputDelay(usize),
+    StartNetplay(Box<NetplaySession>),
+    StopNetplay,
     SetSpeed(Speed),
     HardReset,
+    SoftReset(Sender<bool>),
+    SetPrinterConnected(bool, Sender<bool>),
+    SetSerialCaptureEnabled(bool, Sender<bool>),
+    SetAudioSampleRate(u32, Sender<bool>),
+    SetColorCorrection(ColorCorrection),
+    ListCoreOptions(Sender<Vec<CoreOption>>),
+    SetCoreOption(String, String, Sender<Result<(), String>>),
+    AddReplayBookmark(String),
+    EnqueueWrites(Vec<(u32, ByteVec)>),
+    SetCheats(Vec<String>, Sender<Result<(), String>>),
     CreateSaveState(Sender<Vec<u8>>),
-    LoadSaveState(Vec<u8>),
+    LoadSaveState(Vec<u8>, Sender<Result<(), String>>),
     SaveSRAM(Sender<Vec<u8>>),
+    SetCrashDumpDirectory(Option<PathBuf>),
+    SetWatchdogTimeout(Option<Duration>),
+    SetStrictRamAccess(bool),
+    SetTimestampProvider(Box<dyn MonotonicTimestampProvider + Send>),
+    SetWatchedMemory(Vec<WatchedMemoryRange>),
+    SetSeekCancellation(CancellationToken),
+    SetRewindSettings(Option<RewindSettings>),
+    Rewind(RewindCommand),
+    SetDeterminismCheck(Option<DeterminismCheckSettings>),
     Close
 }
 
+enum RewindCommand {
+    Frames(u64),
+    Start,
+    Stop
+}
+
+/// Sends [`CoreProgressEvent`]s for a single operation.
+struct ProgressReporter {
+    sender: Sender<CoreProgressEvent>,
+    id: u32,
+    operation: CoreOperation,
+    report_after: Instant,
+    started: bool,
+    percent: u8
+}
+
+impl ProgressReporter {
+    /// Report that the operation is `percent` complete, starting it if it hasn't been started yet.
+    fn report(&mut self, percent: u8) {
+        if !self.started {
+            if Instant::now() < self.report_after {
+                return
+            }
+            self.started = true;
+            self.percent = percent;
+            self.send(CoreOperationStage::Started);
+        }
+        else if percent != self.percent {
+            self.percent = percent;
+            self.send(CoreOperationStage::Progress);
+        }
+    }
+
+    /// Report that the operation finished, if it was ever started.
+    fn finish(mut self) {
+        if self.started {
+            self.percent = 100;
+            self.send(CoreOperationStage::Finished);
+        }
+    }
+
+    fn send(&self, stage: CoreOperationStage) {
+        // Nobody is listening if the owning thread is gone
+        let _ = self.sender.send(CoreProgressEvent { id: self.id, operation: self.operation, stage, percent: self.percent });
+    }
+}
+
+/// Maximum number of inputs kept for crash dumps.
+const MAX_RECENT_INPUTS: usize = 600;
+
 struct ThreadedSuperShuckieCoreThread {
     screens: Weak<Mutex<Vec<ScreenData>>>,
+    printed_pages: Weak<Mutex<Vec<ScreenData>>>,
+    serial_output: Weak<Mutex<Vec<u8>>>,
+    audio_samples: Weak<Mutex<Vec<i16>>>,
+    audio_sample_rate: Arc<AtomicU32>,
+    peripheral_output: Weak<Mutex<PeripheralOutput>>,
+    last_peripheral_output: PeripheralOutput,
+    applied_input: Weak<Mutex<Input>>,
+    last_applied_input: Input,
+    frame_timings: Weak<Mutex<FrameTimings>>,
+    profiler: FrameProfiler,
+
+    hang_report: Weak<Mutex<Option<CoreHangReport>>>,
+    ram_access_error: Weak<Mutex<Option<RamAccessError>>>,
+    determinism_mismatch: Weak<Mutex<Option<DeterminismMismatch>>>,
+    watched_memory: Weak<Mutex<Vec<Vec<u8>>>>,
+    watched_memory_ranges: Vec<WatchedMemoryRange>,
+    watchdog_timeout: Option<Duration>,
+    watchdog_frame: u64,
+    watchdog_ticks: u64,
+    watchdog_last_progress: Instant,
 
     screens_queued: Vec<ScreenData>,
     screen_ready_for_copy: bool,
@@ -324,16 +1117,30 @@ struct ThreadedSuperShuckieCoreThread {
     desired_replay_frame: Arc<AtomicU32>,
     delta_replay_frames: Arc<AtomicI32>,
     playback_frozen: bool,
+    background: bool,
 
     core: SuperShuckieCore,
     receiver: Receiver<ThreadCommand>,
     is_running: bool,
-    pokeabyte_integration: Option<PokeAByteIntegrationServer>,
-    sender_close: Sender<()>
+    pokeabyte_integration: Option<Arc<PokeAByteIntegrationServer>>,
+    pokeabyte_write_policy: PokeAByteWritePolicy,
+    pokeabyte_frame_skip_policy: PokeAByteFrameSkipPolicy,
+    pokeabyte_transport: PokeAByteTransport,
+    pokeabyte_mirror: Option<PokeAByteMirror>,
+    sender_close: Sender<()>,
+    progress_sender: Sender<CoreProgressEvent>,
+    next_operation_id: u32,
+    seek_cancellation: Option<CancellationToken>,
+
+    crash_dump_directory: Option<PathBuf>,
+    recent_inputs: VecDeque<(u64, Input)>,
+
+    netplay: Option<Box<NetplaySession>>,
+    netplay_status: Arc<AtomicU32>
 }
 
 impl ThreadedSuperShuckieCoreThread {
-    fn run_thread(mut self) {
+    fn run_thread(&mut self) {
         loop {
             if let Ok(cmd) = self.receiver.try_recv() {
                 if matches!(cmd, ThreadCommand::Close) {
@@ -345,17 +1152,46 @@ impl ThreadedSuperShuckieCoreThread {
             }
 
             self.go_to_desired_frame();
-            self.refresh_screen_data();
-            self.update_queued_screens();
-            self.handle_pokeabyte_integration();
-            self.replay_milliseconds.store(self.core.get_recording_milliseconds() as u32, Ordering::Relaxed);
+            self.profile(FrameSection::ScreenCopy, |this| {
+                if this.background {
+                    // Nobody is looking at the screens, but the frame count is still used
+                    this.frame_count.store(this.core.total_frames as u32, Ordering::Relaxed);
+                }
+                else {
+                    this.refresh_screen_data();
+                    this.update_queued_screens();
+                }
+                this.update_watched_memory();
+            });
+            self.collect_printed_pages();
+            self.collect_serial_output();
+            self.collect_audio_samples();
+            self.update_peripheral_output();
+            self.update_applied_input();
+            self.profile(FrameSection::PokeAByte, Self::handle_pokeabyte_integration);
+            self.replay_milliseconds.store(self.core.get_display_milliseconds() as u32, Ordering::Relaxed);
+            let netplay_ready = self.step_netplay();
+            self.check_ram_access_error();
+            self.check_determinism_mismatch();
 
             if self.is_running {
-                if !self.playback_frozen {
+                if !netplay_ready {
+                    // waiting on the peer
+                    std::thread::sleep(Duration::from_millis(1));
+                }
+                else if !self.playback_frozen {
+                    let start = Instant::now();
                     self.core.run();
+
+                    // Recording happens as part of running, so split it into its own section
+                    let recording = self.core.take_recorder_time();
+                    self.profiler.add(FrameSection::Emulation, start.elapsed().saturating_sub(recording));
+                    self.profiler.add(FrameSection::Recording, recording);
+                    self.update_frame_timings();
+                    self.check_watchdog();
                 }
             }
-            else if self.core.replay_player.is_none() {
+            else if self.core.replay_player.is_none() || self.background {
                 // unfortunately we can't just block until we're running again because we still need
                 // to handle pokeabyte writes
                 std::thread::sleep(Duration::from_millis(100));
@@ -368,19 +1204,154 @@ impl ThreadedSuperShuckieCoreThread {
         }
 
         self.core.stop_recording_replay();
+        self.pokeabyte_mirror = None;
         self.pokeabyte_integration = None;
 
         let _ = self.sender_close.send(());
     }
 
+    /// Poll the netplay session, if any.
+    ///
+    /// Returns false if the core has to wait for the peer before running.
+    fn step_netplay(&mut self) -> bool {
+        let Some(netplay) = self.netplay.as_mut() else {
+            return true
+        };
+
+        let ready = netplay.poll(&mut self.core, self.is_running && !self.playback_frozen);
+        self.netplay_status.store(netplay.status() as u32, Ordering::Relaxed);
+        if netplay.is_finished() {
+            self.netplay = None;
+        }
+        ready
+    }
+
+    /// End the netplay session (if any) because the peer can't follow what is about to happen.
+    fn end_netplay(&mut self) {
+        if self.netplay.take().is_some() {
+            self.core.input_override = None;
+            self.netplay_status.store(NetplayStatus::Disconnected as u32, Ordering::Relaxed);
+        }
+    }
+
+    /// Run `what`, adding the time it took to the current frame's timings.
+    fn profile<T>(&mut self, section: FrameSection, what: impl FnOnce(&mut Self) -> T) -> T {
+        let start = Instant::now();
+        let result = what(self);
+        self.profiler.add(section, start.elapsed());
+        result
+    }
+
+    /// Publish the rolling average frame timings if a frame finished.
+    fn update_frame_timings(&mut self) {
+        let Some(timings) = self.profiler.finish_frame(self.core.total_frames) else {
+            return
+        };
+
+        let Some(frame_timings) = self.frame_timings.upgrade() else {
+            panic!("update_frame_timings Can't get frame_timings: owning thread must have crashed");
+        };
+
+        // Not worth blocking over; the next frame will publish it instead
+        if let Ok(mut n) = frame_timings.try_lock() {
+            *n = timings;
+        }
+    }
+
+    /// Restart the watchdog timer, such as after resuming emulation.
+    fn reset_watchdog(&mut self) {
+        self.watchdog_frame = self.core.total_frames;
+        self.watchdog_ticks = self.core.total_ticks;
+        self.watchdog_last_progress = Instant::now();
+    }
+
+    /// Pause emulation if the core has not finished a frame within the watchdog timeout.
+    fn check_watchdog(&mut self) {
+        // A stalled replay is waiting on the player, not the core
+        if !self.core.mid_frame || self.core.replay_stalled || self.core.total_frames != self.watchdog_frame {
+            self.reset_watchdog();
+            return
+        }
+
+        let Some(timeout) = self.watchdog_timeout else {
+            return
+        };
+
+        let elapsed_time = self.watchdog_last_progress.elapsed();
+        if elapsed_time < timeout {
+            return
+        }
+
+        let report = CoreHangReport {
+            core_name: self.core.get_core().core_name(),
+            frame: self.core.total_frames,
+            elapsed_ticks: self.core.total_ticks.wrapping_sub(self.watchdog_ticks),
+            elapsed_time
+        };
+
+        self.is_running = false;
+        self.core.pause_timer();
+        self.reset_watchdog();
+
+        let Some(hang_report) = self.hang_report.upgrade() else {
+            panic!("check_watchdog Can't get hang_report: owning thread must have crashed");
+        };
+
+        *hang_report
+            .lock()
+            .expect("can't get hang report mutex check_watchdog") = Some(report);
+    }
+
+    /// Pause emulation and publish the error if a RAM access failed in strict mode.
+    fn check_ram_access_error(&mut self) {
+        let Some(error) = self.core.take_ram_access_error() else {
+            return
+        };
+
+        if self.is_running {
+            self.is_running = false;
+            self.core.pause_timer();
+        }
+
+        let Some(ram_access_error) = self.ram_access_error.upgrade() else {
+            panic!("check_ram_access_error Can't get ram_access_error: owning thread must have crashed");
+        };
+
+        *ram_access_error
+            .lock()
+            .expect("can't get RAM access error mutex check_ram_access_error") = Some(error);
+    }
+
+    /// Publish the mismatch if the determinism check found one.
+    ///
+    /// Unlike RAM access errors, this doesn't pause emulation, since the replay can still be salvaged
+    /// up to the mismatch.
+    fn check_determinism_mismatch(&mut self) {
+        let Some(mismatch) = self.core.take_determinism_mismatch() else {
+            return
+        };
+
+        let Some(determinism_mismatch) = self.determinism_mismatch.upgrade() else {
+            panic!("check_determinism_mismatch Can't get determinism_mismatch: owning thread must have crashed");
+        };
+
+        determinism_mismatch
+            .lock()
+            .expect("can't get determinism mismatch mutex check_determinism_mismatch")
+            .get_or_insert(mismatch);
+    }
+
     fn go_to_desired_frame(&mut self) {
         let delta = self.delta_replay_frames.swap(0, Ordering::Relaxed);
         let frame = self.desired_replay_frame.swap(u32::MAX, Ordering::Relaxed);
         if frame != u32::MAX {
-            self.core.go_to_replay_frame(frame as UnsignedInteger);
+            self.seek_replay(frame as UnsignedInteger);
+        }
+        else if delta == -1 {
+            self.core.step_replay_backward();
         }
         else if delta != 0 {
-            self.core.go_to_replay_frame(self.core.total_frames.saturating_add_signed(delta as i64));
+            self.seek_replay(self.core.total_frames.saturating_add_signed(delta as i64));
         }
         else {
             return
@@ -390,6 +1361,14 @@ impl ThreadedSuperShuckieCoreThread {
         self.force_refresh_screen_data();
     }
 
+    /// Go to a replay frame, reporting progress if it takes a while.
+    fn seek_replay(&mut self, frame: UnsignedInteger) {
+        let mut progress = self.begin_operation(CoreOperation::SeekReplay, Some(SEEK_PROGRESS_DELAY));
+        let cancel = self.seek_cancellation.take();
+        self.core.go_to_replay_frame_with_progress(frame, cancel.as_ref(), &mut |percent| progress.report(percent));
+        progress.finish();
+    }
+
     /// If the mutex was blocked, we can copy it in when it's no longer blocked.
     fn update_queued_screens(&mut self) {
         if !self.screen_ready_for_copy {
@@ -461,8 +1440,147 @@ impl ThreadedSuperShuckieCoreThread {
         }
     }
 
-    /// Update RAM read/writes
+    /// Read watched memory, if any, at the same point the screens are copied.
+    fn update_watched_memory(&mut self) {
+        if self.watched_memory_ranges.is_empty() || (self.is_running && self.core.mid_frame) {
+            return
+        }
+
+        let core = self.core.get_core();
+        let memory: Vec<Vec<u8>> = self.watched_memory_ranges.iter().map(|range| {
+            let mut data = vec![0u8; range.length];
+            if core.read_ram(range.address, &mut data).is_err() {
+                data.fill(0);
+            }
+            data
+        }).collect();
+
+        let Some(watched_memory) = self.watched_memory.upgrade() else {
+            panic!("update_watched_memory Can't get watched_memory: owning thread must have crashed");
+        };
+
+        *watched_memory
+            .lock()
+            .expect("can't get watched memory mutex update_watched_memory") = memory;
+    }
+
+    /// Move any printed pages to where the owning thread can take them.
+    fn collect_printed_pages(&mut self) {
+        let pages = self.core.take_printed_pages();
+        if pages.is_empty() {
+            return
+        }
+
+        let Some(printed_pages) = self.printed_pages.upgrade() else {
+            panic!("collect_printed_pages Can't get printed_pages: owning thread must have crashed");
+        };
+
+        printed_pages
+            .lock()
+            .expect("can't get printed pages mutex collect_printed_pages")
+            .extend(pages);
+    }
+
+    /// Move any serial output to where the owning thread can take it.
+    fn collect_serial_output(&mut self) {
+        let output = self.core.take_serial_output();
+        if output.is_empty() {
+            return
+        }
+
+        let Some(serial_output) = self.serial_output.upgrade() else {
+            panic!("collect_serial_output Can't get serial_output: owning thread must have crashed");
+        };
+
+        serial_output
+            .lock()
+            .expect("can't get serial output mutex collect_serial_output")
+            .extend(output);
+    }
+
+    /// Move any audio samples to where the owning thread can take them.
+    fn collect_audio_samples(&mut self) {
+        let sample_rate = self.core.get_audio_sample_rate();
+        if sample_rate == 0 {
+            return
+        }
+
+        let Some(audio_samples) = self.audio_samples.upgrade() else {
+            panic!("collect_audio_samples Can't get audio_samples: owning thread must have crashed");
+        };
+
+        let mut audio_samples = audio_samples
+            .lock()
+            .expect("can't get audio samples mutex collect_audio_samples");
+        self.core.drain_audio_samples(&mut audio_samples);
+
+        // Nobody is taking them, so don't fall any further behind than a second
+        let max_samples = sample_rate as usize * 2;
+        if audio_samples.len() > max_samples {
+            let excess = audio_samples.len() - max_samples;
+            audio_samples.drain(..excess);
+        }
+    }
+
+    /// Publish peripheral output if it changed.
+    fn update_peripheral_output(&mut self) {
+        let output = self.core.get_peripheral_output();
+        if output == self.last_peripheral_output {
+            return
+        }
+        self.last_peripheral_output = output;
+
+        let Some(peripheral_output) = self.peripheral_output.upgrade() else {
+            panic!("update_peripheral_output Can't get peripheral_output: owning thread must have crashed");
+        };
+
+        *peripheral_output
+            .lock()
+            .expect("can't get peripheral output mutex update_peripheral_output") = output;
+    }
+
+    /// Publish the applied input if it changed.
+    fn update_applied_input(&mut self) {
+        let input = self.core.get_applied_input();
+        if input == self.last_applied_input {
+            return
+        }
+        self.last_applied_input = input;
+
+        let Some(applied_input) = self.applied_input.upgrade() else {
+            panic!("update_applied_input Can't get applied_input: owning thread must have crashed");
+        };
+
+        *applied_input
+            .lock()
+            .expect("can't get applied input mutex update_applied_input") = input;
+    }
+
+    /// Start reporting the progress of an operation.
+    ///
+    /// If `delay` is set, nothing is reported unless the operation is still going after that long.
+    fn begin_operation(&mut self, operation: CoreOperation, delay: Option<Duration>) -> ProgressReporter {
+        let id = self.next_operation_id;
+        self.next_operation_id = self.next_operation_id.wrapping_add(1);
+
+        let mut reporter = ProgressReporter {
+            sender: self.progress_sender.clone(),
+            id,
+            operation,
+            report_after: Instant::now() + delay.unwrap_or_default(),
+            started: false,
+            percent: 0
+        };
+        if delay.is_none() {
+            reporter.report(0);
+        }
+        reporter
+    }
+
+    /// Apply writes and hand a RAM snapshot to the mirror thread
     fn handle_pokeabyte_integration(&mut self) {
+        self.check_pokeabyte_read_errors();
+
         let Some(integration) = self.pokeabyte_integration.as_ref() else {
             return
         };
@@ -472,8 +1590,17 @@ impl ThreadedSuperShuckieCoreThread {
             return;
         };
 
-        for write in &mut session.writes {
-            self.core.enqueue_write(write.address as u32, write.data);
+        let playing_back = self.core.replay_player.is_some();
+        integration.set_reject_writes(playing_back && self.pokeabyte_write_policy == PokeAByteWritePolicy::Reject);
+
+        if !playing_back {
+            for write in &mut session.writes {
+                self.core.enqueue_write(write.address as u32, write.data);
+            }
+        }
+        else if self.pokeabyte_write_policy == PokeAByteWritePolicy::Reject {
+            // anything queued before playback started is rejected, too
+            integration.reject_queued_writes(session);
         }
 
         // don't update reads mid-frame; it's too slow
@@ -482,15 +1609,26 @@ impl ThreadedSuperShuckieCoreThread {
         }
 
         // handle frame skipping unless we're paused
-        if self.is_running && let Some(skipping) = session.config.frame_skip && self.core.total_frames % ((skipping as u64) + 1) != 0 {
+        if self.is_running && let Some(skipping) = session.config.effective_frame_skip && self.core.total_frames % ((skipping as u64) + 1) != 0 {
             return
         }
 
-        // SAFETY: "Only one way to find out"
-        let ram = unsafe { session.shared_memory.get_memory_mut() };
-        for read in &session.config.blocks {
-            let into = ram.get_mut(read.range.clone()).expect("read range was wrong (this should have been checked!)");
-            let _ = self.core.get_core().read_ram(read.game_address, into); // TODO: handle this?
+        // let the mirror thread have the session while it copies the snapshot
+        drop(session_lock);
+
+        if let Some(mirror) = self.pokeabyte_mirror.as_ref() {
+            mirror.submit(self.core.get_core(), self.core.total_frames);
+        }
+    }
+
+    /// Report reads that failed on the mirror thread if in strict mode.
+    fn check_pokeabyte_read_errors(&mut self) {
+        let Some(error) = self.pokeabyte_mirror.as_ref().and_then(PokeAByteMirror::take_read_error) else {
+            return
+        };
+
+        if self.core.strict_ram_access && self.core.ram_access_error.is_none() {
+            self.core.ram_access_error = Some(error);
         }
     }
 
@@ -500,6 +1638,8 @@ impl ThreadedSuperShuckieCoreThread {
                 if !self.is_running {
                     self.is_running = true;
                     self.core.unpause_timer();
+                    self.profiler.reset(self.core.total_frames);
+                    self.reset_watchdog();
                 }
             }
             ThreadCommand::Pause => {
@@ -508,15 +1648,26 @@ impl ThreadedSuperShuckieCoreThread {
                     self.core.pause_timer();
                 }
             }
+            ThreadCommand::RunSingleFrame => {
+                if !self.is_running && self.core.replay_player.is_none() && self.netplay.is_none() {
+                    self.core.run_single_frame();
+                }
+            }
             ThreadCommand::SetPokeAByteEnabled(enabled, err) => {
                 if !enabled && self.pokeabyte_integration.is_some() {
+                    self.pokeabyte_mirror = None;
                     self.pokeabyte_integration = None;
-                    let _ = err.send(Ok(()));
+                    let _ = err.send(Ok(None));
                 }
                 else if enabled {
-                    let integration = match PokeAByteIntegrationServer::begin_listen() {
+                    // Close the old server first so the new one can bind the same address. The
+                    // mirror goes first, since it holds a reference to the server.
+                    self.pokeabyte_mirror = None;
+                    self.pokeabyte_integration = None;
+                    let integration = match PokeAByteIntegrationServer::begin_listen_on(self.pokeabyte_transport.clone()) {
                         Ok(n) => {
-                            let _ = err.send(Ok(()));
+                            let _ = err.send(Ok(Some(n.transport().clone())));
+                            n.set_frame_skip_policy(self.pokeabyte_frame_skip_policy);
                             n
                         },
                         Err(e) => {
@@ -524,12 +1675,32 @@ impl ThreadedSuperShuckieCoreThread {
                             return
                         }
                     };
+                    let integration = Arc::new(integration);
+                    self.pokeabyte_mirror = Some(PokeAByteMirror::new(integration.clone()));
                     self.pokeabyte_integration = Some(integration)
                 } else {
-                    let _ = err.send(Ok(()));
+                    let _ = err.send(Ok(None));
+                }
+            }
+            ThreadCommand::SetPokeAByteWritePolicy(policy) => {
+                self.pokeabyte_write_policy = policy;
+            }
+            ThreadCommand::SetPokeAByteTransport(transport) => {
+                self.pokeabyte_transport = transport;
+            }
+            ThreadCommand::SetPokeAByteFrameSkipPolicy(policy) => {
+                self.pokeabyte_frame_skip_policy = policy;
+                if let Some(integration) = self.pokeabyte_integration.as_ref() {
+                    integration.set_frame_skip_policy(policy);
+                }
+            }
+            ThreadCommand::DisconnectPokeAByteClient => {
+                if let Some(integration) = self.pokeabyte_integration.as_ref() {
+                    integration.disconnect_client();
                 }
             }
             ThreadCommand::StartRecordingReplay(metadata) => {
+                self.end_netplay();
                 // FIXME: error if this fails
                 self.core.start_recording_replay(metadata).expect("FAILED TO START RECORDING REPLAY OH NO");
                 if !self.is_running {
@@ -537,9 +1708,18 @@ impl ThreadedSuperShuckieCoreThread {
                 }
             }
             ThreadCommand::StopRecordingReplay(sender) => {
-                let _ = sender.send(self.core.stop_recording_replay() == Some(true));
+                let progress = self.core.replay_file_recorder.is_some().then(|| self.begin_operation(CoreOperation::FinishReplay, None));
+                let result = self.core.stop_recording_replay() == Some(true);
+                if let Some(progress) = progress {
+                    progress.finish();
+                }
+                let _ = sender.send(result);
             }
             ThreadCommand::EnqueueInput(input) => {
+                if self.recent_inputs.len() >= MAX_RECENT_INPUTS {
+                    self.recent_inputs.pop_front();
+                }
+                self.recent_inputs.push_back((self.core.total_frames, input));
                 self.core.enqueue_input(input);
             }
             ThreadCommand::SetSpeed(speed) => {
@@ -551,27 +1731,132 @@ impl ThreadedSuperShuckieCoreThread {
             ThreadCommand::SetToggledInput(input) => {
                 self.core.set_toggled_input(input);
             }
+            ThreadCommand::SetInputDelay(frames) => {
+                self.core.set_input_delay(frames);
+            }
+            ThreadCommand::StartNetplay(session) => {
+                self.core.input_override = None;
+                self.netplay_status.store(session.status() as u32, Ordering::Relaxed);
+                self.netplay = Some(session);
+            }
+            ThreadCommand::StopNetplay => {
+                self.core.input_override = None;
+                self.netplay_status.store(NetplayStatus::Inactive as u32, Ordering::Relaxed);
+                self.netplay = None;
+            }
             ThreadCommand::HardReset => {
+                self.end_netplay();
                 self.core.hard_reset();
             }
+            ThreadCommand::SoftReset(sender) => {
+                self.end_netplay();
+                let _ = sender.send(self.core.soft_reset());
+            }
+            ThreadCommand::SetPrinterConnected(connected, sender) => {
+                let _ = sender.send(self.core.set_printer_connected(connected));
+            }
+            ThreadCommand::SetSerialCaptureEnabled(enabled, sender) => {
+                let _ = sender.send(self.core.set_serial_capture_enabled(enabled));
+            }
+            ThreadCommand::SetAudioSampleRate(sample_rate, sender) => {
+                let supported = self.core.set_audio_sample_rate(sample_rate);
+                self.audio_sample_rate.store(self.core.get_audio_sample_rate(), Ordering::Relaxed);
+                if let Some(audio_samples) = self.audio_samples.upgrade() {
+                    audio_samples.lock().expect("can't get audio samples mutex SetAudioSampleRate").clear();
+                }
+                let _ = sender.send(supported);
+            }
+            ThreadCommand::SetColorCorrection(color_correction) => {
+                self.core.set_color_correction(color_correction);
+            }
+            ThreadCommand::ListCoreOptions(sender) => {
+                let _ = sender.send(self.core.list_core_options());
+            }
+            ThreadCommand::SetCoreOption(key, value, sender) => {
+                let _ = sender.send(self.core.set_core_option(&key, &value));
+            }
+            ThreadCommand::AddReplayBookmark(name) => {
+                self.core.add_replay_bookmark(name);
+            }
+            ThreadCommand::EnqueueWrites(writes) => {
+                self.core.enqueue_writes(&writes);
+            }
+            ThreadCommand::SetCheats(codes, sender) => {
+                let _ = sender.send(self.core.set_cheats(&codes));
+            }
             ThreadCommand::CreateSaveState(sender) => {
+                let progress = self.begin_operation(CoreOperation::CreateSaveState, None);
                 self.core.finish_current_frame();
-                let _ = sender.send(self.core.create_save_state());
+                let state = self.core.create_save_state();
+                progress.finish();
+                let _ = sender.send(state);
+            }
+            ThreadCommand::LoadSaveState(state, result) => {
+                self.end_netplay();
+                let _ = result.send(self.core.load_save_state(&state));
+            }
+            ThreadCommand::SetRewindSettings(settings) => {
+                self.core.set_rewind_settings(settings);
             }
-            ThreadCommand::LoadSaveState(state) => {
-                self.core.load_save_state(&state);
+            ThreadCommand::Rewind(RewindCommand::Frames(frames)) => {
+                self.end_netplay();
+                self.core.rewind_frames(frames);
+            }
+            ThreadCommand::Rewind(RewindCommand::Start) => {
+                self.end_netplay();
+                self.core.start_rewinding();
+            }
+            ThreadCommand::Rewind(RewindCommand::Stop) => {
+                self.core.stop_rewinding();
+            }
+            ThreadCommand::SetDeterminismCheck(settings) => {
+                self.core.set_determinism_check(settings);
             }
             ThreadCommand::SetPlaybackFrozen(paused) => {
                 self.playback_frozen = paused;
+                self.reset_watchdog();
+            }
+            ThreadCommand::SetLowPriority(low_priority) => {
+                set_current_thread_low_priority(low_priority);
+            }
+            ThreadCommand::SetBackground(background) => {
+                if self.background && !background {
+                    // The screens are out of date
+                    self.force_refresh_screen_data();
+                }
+                self.background = background;
             }
             ThreadCommand::SaveSRAM(sender) => {
                 let _ = sender.send(self.core.save_sram());
             }
+            ThreadCommand::SetCrashDumpDirectory(directory) => {
+                self.crash_dump_directory = directory;
+            }
+            ThreadCommand::SetWatchdogTimeout(timeout) => {
+                self.watchdog_timeout = timeout;
+                self.reset_watchdog();
+            }
+            ThreadCommand::SetStrictRamAccess(strict) => {
+                self.core.set_strict_ram_access(strict);
+            }
+            ThreadCommand::SetTimestampProvider(timestamp_provider) => {
+                self.core.set_timestamp_provider(timestamp_provider);
+            }
+            ThreadCommand::SetSeekCancellation(cancel) => {
+                self.seek_cancellation = Some(cancel);
+            }
+            ThreadCommand::SetWatchedMemory(ranges) => {
+                self.watched_memory_ranges = ranges;
+                if self.watched_memory_ranges.is_empty() && let Some(watched_memory) = self.watched_memory.upgrade() {
+                    watched_memory.lock().expect("can't get watched memory mutex SetWatchedMemory").clear();
+                }
+            }
             ThreadCommand::Close => {
                 unreachable!("handle_command(ThreadCommand::Close) should not happen")
             },
             ThreadCommand::AttachReplayPlayer { player, allow_mismatched, errors } => {
-                if let Err(e) = self.core.attach_replay_player(player, allow_mismatched) {
+                self.end_netplay();
+                if let Err(e) = self.core.attach_replay_player(*player, allow_mismatched) {
                     let _ = errors.send(e);
                 }
                 if !self.is_running {
@@ -583,4 +1868,71 @@ impl ThreadedSuperShuckieCoreThread {
             }
         }
     }
+
+    /// Write the current save state, SRAM, recent inputs, and core metadata to a new directory in
+    /// the crash dump directory, and finish the current replay (if any).
+    ///
+    /// The core may be in an inconsistent state, so any of this may fail.
+    fn write_crash_dump(&mut self, payload: &(dyn Any + Send)) {
+        let Some(crash_dump_directory) = self.crash_dump_directory.clone() else {
+            return
+        };
+
+        let message = payload.downcast_ref::<&str>().copied()
+            .or_else(|| payload.downcast_ref::<String>().map(|i| i.as_str()))
+            .unwrap_or("(unknown panic payload)")
+            .to_owned();
+
+        let _ = catch_unwind(AssertUnwindSafe(|| {
+            let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map(|i| i.as_secs()).unwrap_or_default();
+            let dump_dir = crash_dump_directory.join(format!("crash-{timestamp}"));
+            if std::fs::create_dir_all(&dump_dir).is_err() {
+                return
+            }
+
+            let core = self.core.get_core();
+            let mut info = String::new();
+            let _ = writeln!(info, "panic: {message}");
+            let _ = writeln!(info, "core: {}", core.core_name());
+            let _ = writeln!(info, "console: {:?}", core.replay_console_type());
+            let _ = writeln!(info, "rom checksum: {}", blake3_hash_to_ascii(*core.rom_checksum()));
+            let _ = writeln!(info, "bios checksum: {}", blake3_hash_to_ascii(*core.bios_checksum()));
+            let _ = writeln!(info, "elapsed frames: {}", self.core.total_frames);
+            let _ = writeln!(info, "mid-frame: {}", self.core.mid_frame);
+            let _ = writeln!(info, "playing back: {}", self.core.replay_player.is_some());
+            let _ = writeln!(info);
+            let _ = writeln!(info, "recent inputs (frame: input):");
+            for (frame, input) in &self.recent_inputs {
+                let _ = writeln!(info, "{frame}: {input:?}");
+            }
+            let _ = std::fs::write(dump_dir.join("info.txt"), info);
+
+            // Each of these can panic independently, so try them separately
+            let _ = catch_unwind(AssertUnwindSafe(|| {
+                let _ = std::fs::write(dump_dir.join("state.bin"), self.core.create_save_state());
+            }));
+            let _ = catch_unwind(AssertUnwindSafe(|| {
+                let _ = std::fs::write(dump_dir.join("sram.bin"), self.core.save_sram());
+            }));
+            let _ = catch_unwind(AssertUnwindSafe(|| {
+                self.core.stop_recording_replay();
+            }));
+        }));
+    }
 }
+
+/// Switch the calling thread between the batch and normal scheduling policies.
+///
+/// Batch threads are slightly disfavored when waking up. Unlike raising the nice value, this can be
+/// undone without any special privileges.
+#[cfg(target_os = "linux")]
+fn set_current_thread_low_priority(low_priority: bool) {
+    let policy = if low_priority { libc::SCHED_BATCH } else { libc::SCHED_OTHER };
+    let param = libc::sched_param { sched_priority: 0 };
+
+    // SAFETY: pid 0 is the calling thread, and param is valid for the duration of the call.
+    unsafe { libc::sched_setscheduler(0, policy, &param) };
+}
+
+#[cfg(not(target_os = "linux"))]
+fn set_current_thread_low_priority(_low_priority: bool) {}
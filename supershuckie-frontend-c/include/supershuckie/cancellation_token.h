@@ -0,0 +1,50 @@
+#ifndef __SUPERSHUCKIE_CANCELLATION_TOKEN_H_
+#define __SUPERSHUCKIE_CANCELLATION_TOKEN_H_
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#include <stdbool.h>
+
+/**
+ * A flag for stopping long operations (e.g. loading a replay) early, created with supershuckie_cancellationtoken_new.
+ *
+ * Once cancelled, a token stays cancelled, so use a new token for each operation. Tokens can be cancelled from any
+ * thread while the operation is running on another.
+ *
+ * This can never be null EXCEPT in the supershuckie_cancellationtoken_free function, or where a function says so.
+ */
+struct SuperShuckieCancellationTokenRaw;
+
+/**
+ * Create a token that is not cancelled.
+ *
+ * This token must be freed with supershuckie_cancellationtoken_free
+ */
+struct SuperShuckieCancellationTokenRaw *supershuckie_cancellationtoken_new(void);
+
+/**
+ * Cancel any operation using this token.
+ */
+void supershuckie_cancellationtoken_cancel(const struct SuperShuckieCancellationTokenRaw *token);
+
+/**
+ * Return true if the token was cancelled.
+ */
+bool supershuckie_cancellationtoken_is_cancelled(const struct SuperShuckieCancellationTokenRaw *token);
+
+/**
+ * Free the token.
+ *
+ * Safety:
+ * - A pointer may only be freed once (unless the pointer is null)
+ * - The token must not be freed while an operation is still using it
+ */
+void supershuckie_cancellationtoken_free(struct SuperShuckieCancellationTokenRaw *token);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
@@ -43,6 +43,13 @@ const char *supershuckie_control_settings_modifier_name(SuperShuckieControlModif
  */
 bool supershuckie_control_settings_control_is_button(SuperShuckieControlType control);
 
+/**
+ * Return true if the control type can be bound to an input.
+ *
+ * Power cycling with cleared save data deletes save data with no way to confirm it first, so it can't be bound.
+ */
+bool supershuckie_control_settings_control_is_bindable(SuperShuckieControlType control);
+
 /**
  * Return true if the control type is a spoiler.
  */
@@ -99,6 +106,19 @@ void supershuckie_control_settings_set_control_for_device(
     uint32_t modifier
 );
 
+/**
+ * Check the settings for common mistakes (e.g. essential controls that are not bound), returning a human-readable
+ * description of each problem.
+ *
+ * rom_settings are the per-ROM settings used over these, or null if there are none.
+ *
+ * This array must be freed with supershuckie_stringarray_free
+ */
+struct SuperShuckieStringArrayRaw *supershuckie_control_settings_get_warnings(
+    const struct SuperShuckieControlSettingsRaw *settings,
+    const struct SuperShuckieControlSettingsRaw *rom_settings
+);
+
 /**
  * Free the settings map.
  *
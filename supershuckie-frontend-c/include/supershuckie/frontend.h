@@ -6,7 +6,10 @@ extern "C" {
 #endif
 
 struct SuperShuckieStringArrayRaw;
+struct SuperShuckieReplayMarkerArrayRaw;
+struct SuperShuckieReplayInfoArrayRaw;
 struct SuperShuckieControlSettingsRaw;
+struct SuperShuckieCancellationTokenRaw;
 
 #include <stdlib.h>
 #include <stdint.h>
@@ -21,6 +24,94 @@ struct SuperShuckieFrontendRaw;
 
 typedef uint32_t SuperShuckieConnectedControllerIndex;
 
+/**
+ * Kind of error recorded by the last function that failed (see supershuckie_get_last_error).
+ */
+enum SuperShuckieErrorCode {
+    /** No error was recorded */
+    SuperShuckieErrorCode__None = 0,
+
+    /** The function panicked, so it returned a fallback value (false, null, zero, etc.) instead */
+    SuperShuckieErrorCode__Panic = 1,
+
+    /** The frontend returned an error, which has a message key and arguments (see supershuckie_get_last_error_key) */
+    SuperShuckieErrorCode__Frontend = 2
+};
+
+/**
+ * What happens when a function panics (see supershuckie_set_panic_behavior).
+ */
+enum SuperShuckiePanicBehavior {
+    /** Record the error for supershuckie_get_last_error and return a fallback value (default) */
+    SuperShuckiePanicBehavior__Recover = 0,
+
+    /** Print the error and abort the process */
+    SuperShuckiePanicBehavior__Abort = 1
+};
+
+/**
+ * Set what happens when any function panics. This applies to all threads.
+ *
+ * Panics never unwind into the caller. Strings passed in that are not valid UTF-8 have invalid sequences replaced
+ * rather than being treated as an error.
+ */
+void supershuckie_set_panic_behavior(enum SuperShuckiePanicBehavior behavior);
+
+/**
+ * Get the last error recorded on the calling thread, writing its message to message.
+ *
+ * Errors are not cleared by successful calls; use supershuckie_clear_last_error to clear them.
+ *
+ * Safety:
+ * - message must be at least message_len bytes long
+ */
+enum SuperShuckieErrorCode supershuckie_get_last_error(char *message, size_t message_len);
+
+/**
+ * Get the message key of the last error recorded on the calling thread, returning false if it has none.
+ *
+ * Errors written to a function's error buffer are in the frontend's language; the key and arguments can be used to
+ * look the message up elsewhere (see supershuckie_frontend_get_message).
+ *
+ * Safety:
+ * - key must point to a buffer of at least `key_len` bytes (it can be null if key_len is 0)
+ */
+bool supershuckie_get_last_error_key(char *key, size_t key_len);
+
+/**
+ * Get the names of the arguments of the last error recorded on the calling thread, or null if it has no message key.
+ *
+ * This array must be freed with supershuckie_stringarray_free
+ */
+struct SuperShuckieStringArrayRaw *supershuckie_get_last_error_argument_names(void);
+
+/**
+ * Get the values of the arguments of the last error recorded on the calling thread, in the same order as
+ * supershuckie_get_last_error_argument_names, or null if it has no message key.
+ *
+ * This array must be freed with supershuckie_stringarray_free
+ */
+struct SuperShuckieStringArrayRaw *supershuckie_get_last_error_argument_values(void);
+
+/**
+ * Clear the last error recorded on the calling thread.
+ */
+void supershuckie_clear_last_error(void);
+
+/**
+ * Pixel encoding of screens (see supershuckie_frontend_set_preferred_screen_encodings).
+ */
+enum SuperShuckieScreenDataEncoding {
+    /** 0xAARRGGBB per uint32_t (B, G, R, A in memory on little-endian platforms) */
+    SuperShuckieScreenDataEncoding__A8R8G8B8 = 0,
+
+    /** 0xAABBGGRR per uint32_t (R, G, B, A in memory on little-endian platforms) */
+    SuperShuckieScreenDataEncoding__A8B8G8R8 = 1,
+
+    /** 16-bit RGB565 per uint16_t; the pixels passed to the refresh_screens callback can be read as a uint16_t array */
+    SuperShuckieScreenDataEncoding__R5G6B5 = 2
+};
+
 struct SuperShuckieScreenData {
     uint32_t width;
     uint32_t height;
@@ -36,11 +127,222 @@ enum SuperShuckieReplayState {
 typedef void (*SuperShuckieRefreshScreensCallback)(void *user_data, size_t screen_count, const uint32_t *const *pixels);
 typedef void (*SuperShuckieChangeVideoModeCallback)(void *user_data, size_t screen_count, const struct SuperShuckieScreenData *screen_data, uint8_t scaling);
 
+/**
+ * Physical key location on a keyboard, named after the key in that location on a US QWERTY keyboard.
+ */
+enum SuperShuckiePhysicalKey {
+    SuperShuckiePhysicalKey__ArrowUp,
+    SuperShuckiePhysicalKey__ArrowDown,
+    SuperShuckiePhysicalKey__ArrowLeft,
+    SuperShuckiePhysicalKey__ArrowRight,
+    SuperShuckiePhysicalKey__KeyA,
+    SuperShuckiePhysicalKey__KeyP,
+    SuperShuckiePhysicalKey__KeyS,
+    SuperShuckiePhysicalKey__KeyX,
+    SuperShuckiePhysicalKey__KeyZ,
+    SuperShuckiePhysicalKey__Enter,
+    SuperShuckiePhysicalKey__Backspace,
+    SuperShuckiePhysicalKey__Tab
+};
+
+/**
+ * Write the keycode (as passed into supershuckie_frontend_key_press) of the given physical key on the current keyboard
+ * layout to keycode, returning false if there is no such key.
+ */
+typedef bool (*SuperShuckieKeycodeForPhysicalKeyCallback)(void *user_data, uint32_t physical_key, int32_t *keycode);
+
+/**
+ * Briefly show a message to the user (e.g. as an on-screen notification).
+ */
+typedef void (*SuperShuckieShowMessageCallback)(void *user_data, const char *message);
+
+/**
+ * Set the rumble amplitude, from 0.0 (off) to 1.0 (full), e.g. for cartridges with a rumble motor.
+ */
+typedef void (*SuperShuckieSetRumbleCallback)(void *user_data, double amplitude);
+
+/**
+ * Set whether or not the console's infrared LED is on.
+ */
+typedef void (*SuperShuckieSetInfraredLEDCallback)(void *user_data, bool on);
+
+/**
+ * Long operation that progress is reported for.
+ */
+enum SuperShuckieCoreOperation {
+    SuperShuckieCoreOperation__CreateSaveState,
+    SuperShuckieCoreOperation__SeekReplay,
+    SuperShuckieCoreOperation__FinishReplay
+};
+
+/**
+ * How far along a SuperShuckieCoreOperation is.
+ */
+enum SuperShuckieCoreOperationStage {
+    SuperShuckieCoreOperationStage__Started,
+    SuperShuckieCoreOperationStage__Progress,
+    SuperShuckieCoreOperationStage__Finished
+};
+
+/**
+ * Report the progress of a long operation, with percent going from 0 to 100.
+ *
+ * Every call for the same operation has the same operation_id. Each operation gets exactly one Started and one Finished
+ * call, with any number of Progress calls in between. Seeks are only reported if they take a while.
+ *
+ * This may be called while a blocking function (e.g. supershuckie_frontend_create_save_state) is still running.
+ */
+typedef void (*SuperShuckieReportProgressCallback)(void *user_data, uint32_t operation_id, uint32_t operation, uint32_t stage, uint8_t percent);
+
+/**
+ * A controller button meant for navigating the UI was pressed or released.
+ *
+ * This is called for buttons not bound to any control, or for every button while UI navigation is captured (see
+ * supershuckie_frontend_set_ui_navigation_captured).
+ */
+typedef void (*SuperShuckieUINavigationCallback)(void *user_data, SuperShuckieConnectedControllerIndex controller, int32_t button, bool pressed);
+
+/**
+ * Play sample_count audio samples, which are signed 16-bit stereo interleaved left then right, at sample_rate Hz.
+ *
+ * This is only called if an audio sample rate was set with supershuckie_frontend_set_audio_sample_rate. samples is
+ * only valid for the duration of the call.
+ */
+typedef void (*SuperShuckiePushAudioCallback)(void *user_data, const int16_t *samples, size_t sample_count, uint32_t sample_rate);
+
 struct SuperShuckieFrontendCallbacks {
     void *user_data;
 
     SuperShuckieRefreshScreensCallback refresh_screens;
     SuperShuckieChangeVideoModeCallback change_video_mode;
+    SuperShuckieKeycodeForPhysicalKeyCallback keycode_for_physical_key;
+    SuperShuckieShowMessageCallback show_message;
+    SuperShuckieSetRumbleCallback set_rumble;
+    SuperShuckieSetInfraredLEDCallback set_infrared_led;
+    SuperShuckieReportProgressCallback report_progress;
+    SuperShuckieUINavigationCallback ui_navigation;
+    SuperShuckiePushAudioCallback push_audio;
+};
+
+/**
+ * Type of a SuperShuckieEvent.
+ */
+enum SuperShuckieEventType {
+    /**
+     * A new frame is ready to be read with supershuckie_frontend_read_screen. data.frame_ready is set.
+     */
+    SuperShuckieEventType__FrameReady,
+
+    /**
+     * Something failed. message and key are set.
+     */
+    SuperShuckieEventType__Error,
+
+    /**
+     * The replay being played back reached its last frame.
+     */
+    SuperShuckieEventType__PlaybackFinished,
+
+    /**
+     * The Poke-A-Byte integration server was enabled or disabled. data.pokeabyte_status is set, and message is set to
+     * the error if it failed to be enabled, the address it is listening on if it was enabled, or empty if it was
+     * disabled.
+     */
+    SuperShuckieEventType__PokeAByteStatus,
+
+    /**
+     * A message to briefly show to the user. message and key are set.
+     */
+    SuperShuckieEventType__Message,
+
+    /**
+     * A ROM was loaded. message is set to the ROM's filename.
+     */
+    SuperShuckieEventType__RomLoaded,
+
+    /**
+     * The console was reset. data.reset is set.
+     */
+    SuperShuckieEventType__Reset,
+
+    /**
+     * A save state was loaded. message is set to its name, or is empty if loading a save state was undone or redone.
+     */
+    SuperShuckieEventType__StateLoaded,
+
+    /**
+     * A replay started recording or playing back. data.replay is set, and message is set to the replay's name.
+     */
+    SuperShuckieEventType__ReplayStarted,
+
+    /**
+     * A replay stopped recording or playing back. data.replay is set.
+     */
+    SuperShuckieEventType__ReplayEnded,
+
+    /**
+     * The save data was written to disk.
+     */
+    SuperShuckieEventType__SramSaved,
+
+    /**
+     * A file was added to or removed from one of the current ROM's folders (see
+     * supershuckie_frontend_set_watch_storage_setting). data.storage_changed is set, and message is set to the file's
+     * name as listed by supershuckie_frontend_get_all_save_states_for_rom and similar.
+     */
+    SuperShuckieEventType__StorageChanged
+};
+
+#define SUPERSHUCKIE_EVENT_MESSAGE_LENGTH 256
+#define SUPERSHUCKIE_EVENT_KEY_LENGTH 64
+
+/**
+ * An event polled with supershuckie_frontend_poll_event or passed to a SuperShuckieEventHook.
+ */
+struct SuperShuckieEvent {
+    /**
+     * SuperShuckieEventType
+     */
+    uint32_t type;
+
+    union {
+        struct {
+            uint32_t frame;
+        } frame_ready;
+
+        struct {
+            bool enabled;
+        } pokeabyte_status;
+
+        struct {
+            bool hard;
+        } reset;
+
+        struct {
+            bool recording;
+        } replay;
+
+        struct {
+            /**
+             * SuperShuckieStorageCategory
+             */
+            uint32_t category;
+            bool added;
+        } storage_changed;
+    } data;
+
+    /**
+     * Null-terminated UTF-8 message, truncated if too long, or empty if the event has no message
+     */
+    char message[SUPERSHUCKIE_EVENT_MESSAGE_LENGTH];
+
+    /**
+     * Null-terminated key of the message in the message catalog (e.g. "autosnap_failed"), or empty if the event has
+     * no message from the catalog
+     *
+     * Frontends can use this to present the message themselves instead of using the formatted message.
+     */
+    char key[SUPERSHUCKIE_EVENT_KEY_LENGTH];
 };
 
 /**
@@ -54,6 +356,44 @@ struct SuperShuckieFrontendRaw *supershuckie_frontend_new(
     const struct SuperShuckieFrontendCallbacks *callbacks
 );
 
+/**
+ * Initialize a new frontend whose user data and ROMs are only stored in memory, for testing.
+ *
+ * ROMs can be added to it with supershuckie_frontend_write_file().
+ *
+ * Safety:
+ * - Both pointers must point to valid data.
+ */
+struct SuperShuckieFrontendRaw *supershuckie_frontend_new_in_memory(
+    const char *user_data_path,
+    const struct SuperShuckieFrontendCallbacks *callbacks
+);
+
+/**
+ * Forward a ROM path to an instance already running in single-instance mode with the same user data path.
+ *
+ * Call this before creating a frontend. Returns true if the running instance received it, in which case this
+ * instance should exit. Returns false if there is no such instance.
+ *
+ * Safety:
+ * - user_data_path must point to a valid null-terminated UTF-8 string.
+ * - rom_path must either point to a valid null-terminated UTF-8 string or be null if no ROM was given.
+ */
+bool supershuckie_frontend_forward_to_running_instance(const char *user_data_path, const char *rom_path);
+
+/**
+ * Set whether or not all controller buttons go to the ui_navigation callback instead of the game (e.g. while a
+ * controller-driven menu is open).
+ *
+ * Buttons not bound to any control always go to the callback.
+ */
+void supershuckie_frontend_set_ui_navigation_captured(struct SuperShuckieFrontendRaw *frontend, bool captured);
+
+/**
+ * Get whether or not all controller buttons go to the ui_navigation callback.
+ */
+bool supershuckie_frontend_is_ui_navigation_captured(const struct SuperShuckieFrontendRaw *frontend);
+
 /**
  * Set the current state for a keyboard key press, if any.
  */
@@ -93,353 +433,1976 @@ void supershuckie_frontend_set_sgb_enabled(struct SuperShuckieFrontendRaw *front
  */
 bool supershuckie_frontend_is_sgb_enabled(struct SuperShuckieFrontendRaw *frontend);
 
-enum SuperShuckieGBCMode {
-    SuperShuckieGBCMode__AlwaysGBC = 0,
-    SuperShuckieGBCMode__GBInGBMode = 1,
-    SuperShuckieGBCMode__AlwaysGB = 2
-};
-
 /**
- * Set the GBC mode.
+ * Get a bitfield of the inputs the current core uses.
+ *
+ * Bits are, from least significant: A, B, Start, Select, D-Up, D-Down, D-Left, D-Right, L, R, X, Y, touch, tilt.
  */
-void supershuckie_frontend_set_gbc_mode(struct SuperShuckieFrontendRaw *frontend, uint32_t mode);
+uint32_t supershuckie_frontend_get_input_descriptor(struct SuperShuckieFrontendRaw *frontend);
 
 /**
- * Get the GBC mode.
+ * Return true if the control (as a SuperShuckieControlType) does anything for the running game.
+ *
+ * If no game is running, all controls are considered supported.
  */
-uint32_t supershuckie_frontend_get_gbc_mode(struct SuperShuckieFrontendRaw *frontend);
+bool supershuckie_frontend_is_control_supported(struct SuperShuckieFrontendRaw *frontend, uint32_t control);
 
 /**
- * Get whether or not SGB is enabled.
+ * Set whether or not a Game Boy Printer is connected.
+ *
+ * Printed pages are saved as PNGs in the ROM's printer directory.
  */
-bool supershuckie_frontend_is_sgb_enabled(struct SuperShuckieFrontendRaw *frontend);
+void supershuckie_frontend_set_printer_connected(struct SuperShuckieFrontendRaw *frontend, bool connected);
 
 /**
- * Set whether or not the frontend is paused.
+ * Get whether or not a Game Boy Printer is connected.
  */
-void supershuckie_frontend_set_paused(struct SuperShuckieFrontendRaw *frontend, bool paused);
+bool supershuckie_frontend_is_printer_connected(struct SuperShuckieFrontendRaw *frontend);
 
 /**
- * Manually invoke the refresh screens callback even if no updates have occurred.
+ * Set the rate audio samples are generated at, in Hz, or 0 to stop generating them. Samples are passed to the
+ * push_audio callback every tick.
+ *
+ * This is not saved, but it stays set when another ROM is loaded, so it should be set once the audio output is ready.
+ * Returns false if the running console does not support audio.
  */
-void supershuckie_frontend_force_refresh_screens(struct SuperShuckieFrontendRaw *frontend);
+bool supershuckie_frontend_set_audio_sample_rate(struct SuperShuckieFrontendRaw *frontend, uint32_t sample_rate);
 
 /**
- * Set the video scale.
- *
- * If scale is 0, it will default to 1.
+ * Get the rate audio samples are generated at, in Hz, or 0 if disabled.
  */
-void supershuckie_frontend_set_video_scale(struct SuperShuckieFrontendRaw *frontend, uint8_t scale);
+uint32_t supershuckie_frontend_get_audio_sample_rate(struct SuperShuckieFrontendRaw *frontend);
 
 /**
- * Get the current speed settings.
+ * Start recording the game's audio to a WAV file with the given name in the ROM's screenshots directory, or null to use
+ * a default name.
+ *
+ * This records what is sent to push_audio, so supershuckie_frontend_set_audio_sample_rate has to be called first.
+ * Recording starts on the next frame, so starting a replay recording at the same time lines the two up. Recording stops
+ * when the ROM is closed or the sample rate is changed.
+ *
+ * If true is returned, the filename of the recording will be written to result (ensure it is long enough).
+ *
+ * If false is returned, an error will be written.
  *
  * Safety:
- * - base and/or turbo can be null
+ * - result must not be null and must be at least result_len bytes long.
  */
-void supershuckie_frontend_get_speed_settings(const struct SuperShuckieFrontendRaw *frontend, double *base, double *turbo);
+bool supershuckie_frontend_start_recording_audio(struct SuperShuckieFrontendRaw *frontend, const char *name, char *result, size_t result_len);
 
 /**
- * Set the current speed settings.
+ * Stop recording audio, if recording.
  */
-void supershuckie_frontend_set_speed_settings(struct SuperShuckieFrontendRaw *frontend, double base, double turbo);
+void supershuckie_frontend_stop_recording_audio(struct SuperShuckieFrontendRaw *frontend);
 
 /**
- * Get the setting, or null if no setting is set.
+ * Get whether or not audio is being recorded.
+ */
+bool supershuckie_frontend_is_recording_audio(const struct SuperShuckieFrontendRaw *frontend);
+
+/**
+ * Start or stop capturing bytes the running game sends over the serial port (e.g. test ROM results), which can then
+ * be taken with supershuckie_frontend_take_serial_output.
  *
- * Safety:
- * - setting must not be null
- * - The returned value may no longer be valid once any future call to this API is made.
+ * This is not saved, but it stays set when another ROM is loaded. Returns false if the running console does not
+ * support it or a printer is connected.
  */
-const char *supershuckie_frontend_get_custom_setting(const struct SuperShuckieFrontendRaw *frontend, const char *setting);
+bool supershuckie_frontend_set_serial_capture_enabled(struct SuperShuckieFrontendRaw *frontend, bool enabled);
 
 /**
- * Set the setting to the value, or null to unset.
+ * Take up to buffer_len bytes sent over the serial port while capture was enabled, returning how many were written to
+ * buffer. Any remaining bytes are kept for the next call.
  *
  * Safety:
- * - setting must not be null
+ * - buffer must point to a buffer of at least `buffer_len` bytes (it can be null if buffer_len is 0)
  */
-void supershuckie_frontend_set_custom_setting(const struct SuperShuckieFrontendRaw *frontend, const char *setting, const char *value);
+size_t supershuckie_frontend_take_serial_output(struct SuperShuckieFrontendRaw *frontend, uint8_t *buffer, size_t buffer_len);
 
 /**
- * Start recording a replay with the given name, or null to use a default name.
- *
- * If true is returned, the name of the replay (besides the extension) will be written to result (ensure it is long enough).
+ * Result of a test ROM run with supershuckie_frontend_run_serial_test.
+ */
+enum SuperShuckieSerialTestVerdict {
+    /** The ROM reported that it passed */
+    SuperShuckieSerialTestVerdict__Passed = 0,
+
+    /** The ROM reported that it failed */
+    SuperShuckieSerialTestVerdict__Failed = 1,
+
+    /** The ROM did not report a result before the frame limit */
+    SuperShuckieSerialTestVerdict__TimedOut = 2
+};
+
+/**
+ * Run the loaded ROM from power-on as fast as possible, without disturbing the running game, until it reports a test
+ * result over the serial port or max_frames frames have run. blargg and mooneye test ROMs are understood.
  *
- * If false is returned, an error will be written.
+ * On success, true is returned, and everything the ROM sent over the serial port is written to output as text.
+ * Otherwise, the error is written to output.
  *
  * Safety:
- * - result must not be null and must be at least result_len bytes long.
+ * - verdict can be null; if not, a SuperShuckieSerialTestVerdict is written to it on success
+ * - frames can be null; if not, the number of frames run is written to it on success
+ * - output must point to a buffer of at least `output_len` bytes (it can be null if output_len is 0)
  */
-bool supershuckie_frontend_start_recording_replay(struct SuperShuckieFrontendRaw *frontend, const char *name, char *result, size_t result_len);
+bool supershuckie_frontend_run_serial_test(
+    struct SuperShuckieFrontendRaw *frontend,
+    uint32_t max_frames,
+    uint32_t *verdict,
+    uint32_t *frames,
+    char *output,
+    size_t output_len
+);
 
 /**
- * Stop recording a replay.
+ * Run every .gb/.gbc test ROM in directory like supershuckie_frontend_run_serial_test, without loading any of them.
+ * No ROM needs to be loaded.
+ *
+ * If a test ROM has a .expected file next to it (e.g. cpu_instrs.expected), its serial output must match that file
+ * (ignoring trailing whitespace) for it to pass. Otherwise, it passes if it reports that it passed.
+ *
+ * If report_path is set, a JSON report is written to it. If previous_report_path is set, the report is compared
+ * against that one, and the number of test ROMs that passed before but don't now is written to regressions.
+ *
+ * Returns false and writes the error if the directory or a report could not be read or written.
+ *
+ * Safety:
+ * - directory must be a null-terminated UTF-8 string
+ * - report_path and previous_report_path can be null; if not, they must be null-terminated UTF-8 strings
+ * - passed, failed, and regressions can be null; if not, the respective count is written to each on success
+ * - error must point to a buffer of at least `error_len` bytes (it can be null if error_len is 0)
  */
-void supershuckie_frontend_stop_recording_replay(struct SuperShuckieFrontendRaw *frontend);
+bool supershuckie_frontend_run_test_suite(
+    struct SuperShuckieFrontendRaw *frontend,
+    const char *directory,
+    uint32_t max_frames,
+    const char *report_path,
+    const char *previous_report_path,
+    uint32_t *passed,
+    uint32_t *failed,
+    uint32_t *regressions,
+    char *error,
+    size_t error_len
+);
+
+enum SuperShuckieStorageCategory {
+    SuperShuckieStorageCategory__SaveStates = 0,
+    SuperShuckieStorageCategory__SaveData = 1,
+    SuperShuckieStorageCategory__Replays = 2,
+    SuperShuckieStorageCategory__Printer = 3,
+    SuperShuckieStorageCategory__Screenshots = 4
+};
 
 /**
- * Get whether or not Poke-A-Byte is enabled.
- *
- * If false, error may be filled with error data if there is any error data (or it will be empty if it is simply not
- * enabled).
+ * Get the base directory used for the storage category, or null if the user directory is used.
  *
  * Safety:
- * - error must not be null and must be at least error_len bytes long.
+ * - The returned value may no longer be valid once any future call to this API is made.
  */
-bool supershuckie_frontend_is_pokeabyte_enabled(const struct SuperShuckieFrontendRaw *frontend, char *error, size_t error_len);
+const char *supershuckie_frontend_get_storage_dir(const struct SuperShuckieFrontendRaw *frontend, uint32_t category);
 
 /**
- * Set whether or not Poke-A-Byte is enabled.
+ * Set the base directory used for the storage category, or null to use the user directory.
  *
- * Returns false if an error occurs, filling the error buffer with the error.
+ * Data for each ROM is stored in "<dir>/<rom>-data/<category>", the same layout as the user directory (see
+ * supershuckie_frontend_set_key_user_dirs_by_checksum_setting). The directory must be an absolute path.
+ *
+ * If false is returned, an error will be written.
  *
  * Safety:
  * - error must not be null and must be at least error_len bytes long.
  */
-bool supershuckie_frontend_set_pokeabyte_enabled(const struct SuperShuckieFrontendRaw *frontend, bool enabled, char *error, size_t error_len);
+bool supershuckie_frontend_set_storage_dir(struct SuperShuckieFrontendRaw *frontend, uint32_t category, const char *dir, char *error, size_t error_len);
 
 /**
- * Return true if the emulator is currently manually paused.
+ * Set whether to check the current ROM's save states, save data, and replays for files added or removed by other
+ * programs (e.g. cloud sync), emitting SuperShuckieEventType__StorageChanged events.
+ *
+ * Folders are checked every couple of seconds. Files added or removed by this frontend are reported, too.
  */
-bool supershuckie_frontend_is_paused(const struct SuperShuckieFrontendRaw *frontend);
+void supershuckie_frontend_set_watch_storage_setting(struct SuperShuckieFrontendRaw *frontend, bool watch);
 
 /**
- * Get the currently recorded replay file, or nullptr if none.
+ * Get whether to check for files added or removed by other programs.
  */
-const char *supershuckie_frontend_get_recording_replay_file(const struct SuperShuckieFrontendRaw *frontend);
+bool supershuckie_frontend_get_watch_storage_setting(const struct SuperShuckieFrontendRaw *frontend);
 
 /**
- * Create a save state of the given name, or null to use a default name.
+ * Set whether each ROM's folders are named after the ROM's checksum ("<checksum>-data") rather than its filename, so
+ * they are still found if the ROM is renamed or moved.
  *
- * If true is returned, the name of the save state (besides the extension) will be written to result (ensure it is long enough).
+ * The running game's folders are moved right away. Other ROMs' folders are moved when they are next loaded, or by
+ * supershuckie_frontend_migrate_user_dirs.
  *
  * If false is returned, an error will be written.
  *
  * Safety:
- * - result must not be null and must be at least result_len bytes long.
+ * - error must not be null and must be at least error_len bytes long.
  */
-bool supershuckie_frontend_create_save_state(struct SuperShuckieFrontendRaw *frontend, const char *name, char *result, size_t result_len);
+bool supershuckie_frontend_set_key_user_dirs_by_checksum_setting(struct SuperShuckieFrontendRaw *frontend, bool key_by_checksum, char *error, size_t error_len);
 
 /**
- * Load a save state of the given name.
+ * Get whether each ROM's folders are named after the ROM's checksum.
+ */
+bool supershuckie_frontend_get_key_user_dirs_by_checksum_setting(const struct SuperShuckieFrontendRaw *frontend);
+
+/**
+ * Move the folders of every ROM with a known checksum to the naming scheme in use.
  *
- * If false is returned, an error will be written UNLESS it was because the save state did not exist, in which case the
- * error will be empty.
+ * Checksums are only known for ROMs that were loaded before, so if rom_dir is not null, ROMs in it (including ones in
+ * ZIP archives) that have folders are checksummed first.
+ *
+ * If moved is not null, the number of ROMs whose folders were moved is written to it. Returns why each ROM or file
+ * that could not be checksummed or moved was skipped.
+ *
+ * This array must be freed with supershuckie_stringarray_free
  *
  * Safety:
- * - name must not be null
- * - error must be at least result_len bytes long.
+ * - rom_dir must be null or null-terminated, UTF-8
  */
-bool supershuckie_frontend_load_save_state(struct SuperShuckieFrontendRaw *frontend, const char *name, char *error, size_t error_len);
+struct SuperShuckieStringArrayRaw *supershuckie_frontend_migrate_user_dirs(struct SuperShuckieFrontendRaw *frontend, const char *rom_dir, uint32_t *moved);
+
+enum SuperShuckieGBCMode {
+    SuperShuckieGBCMode__AlwaysGBC = 0,
+    SuperShuckieGBCMode__GBInGBMode = 1,
+    SuperShuckieGBCMode__AlwaysGB = 2
+};
 
 /**
- * Undo loading a save state, storing a backup of the current state in the stack.
- *
- * Returns true if successful or false if the end of the stack has been reached.
+ * Set the GBC mode.
  */
-bool supershuckie_frontend_undo_load_save_state(struct SuperShuckieFrontendRaw *frontend);
+void supershuckie_frontend_set_gbc_mode(struct SuperShuckieFrontendRaw *frontend, uint32_t mode);
 
 /**
- * Redo loading a save state, storing a backup of the current state in the stack.
- *
- * Returns true if successful or false if the end of the stack has been reached.
+ * Get the GBC mode.
  */
-bool supershuckie_frontend_redo_load_save_state(struct SuperShuckieFrontendRaw *frontend);
+uint32_t supershuckie_frontend_get_gbc_mode(struct SuperShuckieFrontendRaw *frontend);
 
 /**
- * Load the given ROM, returning true or false depending on whether or not it was successfully loaded.
- *
- * Safety:
- * - path must be null-terminated, UTF-8
- * - error must point to a buffer of at least `error_len` bytes (it can be null if error_len is 0)
+ * Get whether or not SGB is enabled.
  */
-bool supershuckie_frontend_load_rom(struct SuperShuckieFrontendRaw *frontend, const char *path, char *error, size_t error_len);
+bool supershuckie_frontend_is_sgb_enabled(struct SuperShuckieFrontendRaw *frontend);
+
+enum SuperShuckieColorCorrection {
+    SuperShuckieColorCorrection__Disabled = 0,
+    SuperShuckieColorCorrection__CorrectCurves = 1,
+    SuperShuckieColorCorrection__ModernAccurate = 2,
+    SuperShuckieColorCorrection__ModernBalanced = 3,
+    SuperShuckieColorCorrection__ModernBoostContrast = 4,
+    SuperShuckieColorCorrection__ReduceContrast = 5,
+    SuperShuckieColorCorrection__LowContrast = 6
+};
 
 /**
- * Write SRAM to disk, returning true if successful.
+ * Set the color correction mode (as a SuperShuckieColorCorrection).
  *
- * Safety:
- * - error must be at least result_len bytes long.
+ * This is applied to the running game immediately.
  */
-bool supershuckie_frontend_save_sram(struct SuperShuckieFrontendRaw *frontend, char *error, size_t error_len);
+void supershuckie_frontend_set_color_correction(struct SuperShuckieFrontendRaw *frontend, uint32_t color_correction);
 
 /**
- * Set the auto stop playback setting.
+ * Get the color correction mode (as a SuperShuckieColorCorrection).
  */
-void supershuckie_frontend_set_auto_stop_playback_on_input_setting(struct SuperShuckieFrontendRaw *frontend, bool new_setting);
+uint32_t supershuckie_frontend_get_color_correction(struct SuperShuckieFrontendRaw *frontend);
 
 /**
- * Get the auto stop playback setting.
+ * Set the ambient light temperature, from -1.0 (warm red) to 1.0 (cool blue). The default is 0.0.
+ *
+ * Values outside this range are clamped.
  */
-bool supershuckie_frontend_get_auto_stop_playback_on_input_setting(const struct SuperShuckieFrontendRaw *frontend);
+void supershuckie_frontend_set_light_temperature(struct SuperShuckieFrontendRaw *frontend, double light_temperature);
+
+/**
+ * Get the ambient light temperature.
+ */
+double supershuckie_frontend_get_light_temperature(struct SuperShuckieFrontendRaw *frontend);
+
+/**
+ * Set the language of messages shown to the user (e.g. "en").
+ *
+ * Languages other than "en" are loaded from locale/<language>.json in the user data directory, which maps message keys
+ * to templates. Any messages missing from it fall back to English.
+ *
+ * Safety:
+ * - language must be null-terminated, UTF-8
+ * - error must point to a buffer of at least `error_len` bytes (it can be null if error_len is 0)
+ */
+bool supershuckie_frontend_set_language(struct SuperShuckieFrontendRaw *frontend, const char *language, char *error, size_t error_len);
+
+/**
+ * Get the language of messages shown to the user.
+ *
+ * The string is valid until the language is changed or the frontend is freed.
+ */
+const char *supershuckie_frontend_get_language(const struct SuperShuckieFrontendRaw *frontend);
+
+/**
+ * Get all languages that can be set with supershuckie_frontend_set_language.
+ *
+ * This array must be freed with supershuckie_stringarray_free
+ */
+struct SuperShuckieStringArrayRaw *supershuckie_frontend_get_available_languages(const struct SuperShuckieFrontendRaw *frontend);
+
+/**
+ * Get the template for a message key in the current language, returning false if there is no such message.
+ *
+ * Templates refer to arguments by name in braces (e.g. "Failed to create autosnap: {error}").
+ *
+ * Safety:
+ * - key must be null-terminated, UTF-8
+ * - message must point to a buffer of at least `message_len` bytes (it can be null if message_len is 0)
+ */
+bool supershuckie_frontend_get_message(const struct SuperShuckieFrontendRaw *frontend, const char *key, char *message, size_t message_len);
+
+/**
+ * Get the keys of all options of the running core (e.g. "rtc_mode"). The array is empty if no game is running.
+ *
+ * This array must be freed with supershuckie_stringarray_free
+ */
+struct SuperShuckieStringArrayRaw *supershuckie_frontend_get_core_option_keys(const struct SuperShuckieFrontendRaw *frontend);
+
+/**
+ * Get the human-readable names of all options of the running core, in the same order as
+ * supershuckie_frontend_get_core_option_keys.
+ *
+ * This array must be freed with supershuckie_stringarray_free
+ */
+struct SuperShuckieStringArrayRaw *supershuckie_frontend_get_core_option_names(const struct SuperShuckieFrontendRaw *frontend);
+
+/**
+ * Get all values the core option with the given key can be set to. The array is empty if the option doesn't exist.
+ *
+ * If the option exists and current is non-null, the index of the option's current value is written to it.
+ *
+ * This array must be freed with supershuckie_stringarray_free
+ *
+ * Safety:
+ * - key must be a valid null-terminated UTF-8 string.
+ * - current must be null or point to a size_t.
+ */
+struct SuperShuckieStringArrayRaw *supershuckie_frontend_get_core_option_values(
+    const struct SuperShuckieFrontendRaw *frontend,
+    const char *key,
+    size_t *current
+);
+
+/**
+ * Set an option of the running core to one of the values from supershuckie_frontend_get_core_option_values.
+ *
+ * The option is saved and applied to games loaded later. Returns false if an error occurs, filling the error buffer
+ * with the error.
+ *
+ * Safety:
+ * - key and value must be valid null-terminated UTF-8 strings.
+ * - error must not be null and must be at least error_len bytes long.
+ */
+bool supershuckie_frontend_set_core_option(
+    struct SuperShuckieFrontendRaw *frontend,
+    const char *key,
+    const char *value,
+    char *error,
+    size_t error_len
+);
+
+/**
+ * Set whether or not each frame is mixed with the previous one to simulate LCD ghosting.
+ *
+ * This also makes sprites that flicker every other frame look transparent, like on real hardware.
+ */
+void supershuckie_frontend_set_frame_blending_enabled(struct SuperShuckieFrontendRaw *frontend, bool enabled);
+
+/**
+ * Get whether or not frame blending is enabled.
+ */
+bool supershuckie_frontend_is_frame_blending_enabled(struct SuperShuckieFrontendRaw *frontend);
+
+/**
+ * Set whether or not the previous frames are overlaid onto each frame (onion skinning), which helps line up
+ * frame-perfect tricks.
+ *
+ * This can also be toggled with the "Toggle onion skin" control.
+ */
+void supershuckie_frontend_set_onion_skin_enabled(struct SuperShuckieFrontendRaw *frontend, bool enabled);
+
+/**
+ * Get whether or not onion skinning is enabled.
+ */
+bool supershuckie_frontend_is_onion_skin_enabled(struct SuperShuckieFrontendRaw *frontend);
+
+/**
+ * Set the number of previous frames overlaid by onion skinning, up to 16.
+ *
+ * If frames is 0, it will default to 1.
+ */
+void supershuckie_frontend_set_onion_skin_frames(struct SuperShuckieFrontendRaw *frontend, uint8_t frames);
+
+/**
+ * Get the number of previous frames overlaid by onion skinning.
+ */
+uint8_t supershuckie_frontend_get_onion_skin_frames(struct SuperShuckieFrontendRaw *frontend);
+
+/**
+ * Set the opacity of the most recent frame overlaid by onion skinning, from 0.0 to 1.0.
+ *
+ * Older frames are overlaid with less opacity the older they are.
+ */
+void supershuckie_frontend_set_onion_skin_opacity(struct SuperShuckieFrontendRaw *frontend, double opacity);
+
+/**
+ * Get the opacity of the most recent frame overlaid by onion skinning.
+ */
+double supershuckie_frontend_get_onion_skin_opacity(struct SuperShuckieFrontendRaw *frontend);
+
+/**
+ * Set whether or not the frontend is paused.
+ */
+void supershuckie_frontend_set_paused(struct SuperShuckieFrontendRaw *frontend, bool paused);
+
+/**
+ * Manually invoke the refresh screens callback even if no updates have occurred.
+ */
+void supershuckie_frontend_force_refresh_screens(struct SuperShuckieFrontendRaw *frontend);
+
+/**
+ * Choose the encoding of the screens passed to the refresh_screens and change_video_mode callbacks, returning the first
+ * supported SuperShuckieScreenDataEncoding in encodings (or A8R8G8B8 if there are none).
+ *
+ * Other encodings than A8R8G8B8 are converted once per displayed frame before the callback is called, so the embedder
+ * does not need to. If the encoding changes, the change_video_mode callback is called with the new encoding.
+ *
+ * Screens read with supershuckie_frontend_read_screen are always A8R8G8B8.
+ *
+ * Safety:
+ * - encodings must point to at least encoding_count values (it can be null if encoding_count is 0)
+ */
+uint32_t supershuckie_frontend_set_preferred_screen_encodings(
+    struct SuperShuckieFrontendRaw *frontend,
+    const uint32_t *encodings,
+    size_t encoding_count
+);
+
+/**
+ * Set the video scale.
+ *
+ * If scale is 0, it will default to 1.
+ */
+void supershuckie_frontend_set_video_scale(struct SuperShuckieFrontendRaw *frontend, uint8_t scale);
+
+/**
+ * Get the current speed settings.
+ *
+ * Safety:
+ * - base and/or turbo can be null
+ */
+void supershuckie_frontend_get_speed_settings(const struct SuperShuckieFrontendRaw *frontend, double *base, double *turbo);
+
+/**
+ * Set the current speed settings, returning false if either is NaN.
+ *
+ * Both are rounded to the nearest speed that can actually be used (see supershuckie_speed_quantize), clamping them
+ * to the bounds given by supershuckie_speed_get_bounds.
+ *
+ * Safety:
+ * - error must be at least error_len bytes long
+ */
+bool supershuckie_frontend_set_speed_settings(
+    struct SuperShuckieFrontendRaw *frontend,
+    double base,
+    double turbo,
+    char *error,
+    size_t error_len
+);
+
+/**
+ * Get the speed the game is actually running at, including turbo, seeking, and power saving.
+ */
+double supershuckie_frontend_get_effective_speed(const struct SuperShuckieFrontendRaw *frontend);
+
+/**
+ * Round a speed multiplier to the speed that would actually be used (speeds are stored in 1/256 steps).
+ *
+ * Speeds out of range are clamped.
+ */
+double supershuckie_speed_quantize(double multiplier);
+
+/**
+ * Get the slowest and fastest possible speed multipliers.
+ *
+ * Safety:
+ * - min and/or max can be null
+ */
+void supershuckie_speed_get_bounds(double *min, double *max);
+
+/**
+ * Switch the base speed to the next faster (or slower) speed preset, if any.
+ */
+void supershuckie_frontend_cycle_speed_preset(struct SuperShuckieFrontendRaw *frontend, bool faster);
+
+/**
+ * Get the setting, or null if no setting is set.
+ *
+ * Safety:
+ * - setting must not be null
+ * - The returned value may no longer be valid once any future call to this API is made.
+ */
+const char *supershuckie_frontend_get_custom_setting(const struct SuperShuckieFrontendRaw *frontend, const char *setting);
+
+/**
+ * Set the setting to the value, or null to unset.
+ *
+ * Safety:
+ * - setting must not be null
+ */
+void supershuckie_frontend_set_custom_setting(const struct SuperShuckieFrontendRaw *frontend, const char *setting, const char *value);
+
+/**
+ * Start recording a replay with the given name, or null to use a default name.
+ *
+ * If true is returned, the name of the replay (besides the extension) will be written to result (ensure it is long enough).
+ *
+ * If false is returned, an error will be written.
+ *
+ * Safety:
+ * - result must not be null and must be at least result_len bytes long.
+ */
+bool supershuckie_frontend_start_recording_replay(struct SuperShuckieFrontendRaw *frontend, const char *name, char *result, size_t result_len);
+
+/**
+ * Stop recording a replay.
+ */
+void supershuckie_frontend_stop_recording_replay(struct SuperShuckieFrontendRaw *frontend);
+
+/**
+ * Get whether or not Poke-A-Byte is enabled.
+ *
+ * If false, error may be filled with error data if there is any error data (or it will be empty if it is simply not
+ * enabled).
+ *
+ * Safety:
+ * - error must not be null and must be at least error_len bytes long.
+ */
+bool supershuckie_frontend_is_pokeabyte_enabled(const struct SuperShuckieFrontendRaw *frontend, char *error, size_t error_len);
+
+/**
+ * Set whether or not Poke-A-Byte is enabled.
+ *
+ * Returns false if an error occurs, filling the error buffer with the error.
+ *
+ * Safety:
+ * - error must not be null and must be at least error_len bytes long.
+ */
+bool supershuckie_frontend_set_pokeabyte_enabled(const struct SuperShuckieFrontendRaw *frontend, bool enabled, char *error, size_t error_len);
+
+/**
+ * Host a netplay session, listening for the other instance on the given UDP port.
+ *
+ * Both instances control the same console, and the current state is sent to the other instance once it connects. The
+ * ROM's input delay is applied to local input. The session ends if a save state is loaded, the console is reset, or a
+ * replay is recorded or played back.
+ *
+ * Returns false if an error occurs, filling the error buffer with the error.
+ *
+ * Safety:
+ * - error must not be null and must be at least error_len bytes long.
+ */
+bool supershuckie_frontend_host_netplay(struct SuperShuckieFrontendRaw *frontend, uint16_t port, char *error, size_t error_len);
+
+/**
+ * Join a netplay session hosted at the given address (e.g. "192.168.1.2:7845").
+ *
+ * The same ROM must be loaded by both instances.
+ *
+ * Returns false if an error occurs, filling the error buffer with the error.
+ *
+ * Safety:
+ * - address must be a null-terminated UTF-8 string
+ * - error must not be null and must be at least error_len bytes long.
+ */
+bool supershuckie_frontend_join_netplay(struct SuperShuckieFrontendRaw *frontend, const char *address, char *error, size_t error_len);
+
+/**
+ * Watch a netplay session hosted at the given address (e.g. "192.168.1.2:7845").
+ *
+ * The session is played back slightly delayed, and local input is ignored. The same ROM must be loaded as the host.
+ *
+ * Returns false if an error occurs, filling the error buffer with the error.
+ *
+ * Safety:
+ * - address must be a null-terminated UTF-8 string
+ * - error must not be null and must be at least error_len bytes long.
+ */
+bool supershuckie_frontend_spectate_netplay(struct SuperShuckieFrontendRaw *frontend, const char *address, char *error, size_t error_len);
+
+/**
+ * End the current netplay session, if any.
+ */
+void supershuckie_frontend_stop_netplay(struct SuperShuckieFrontendRaw *frontend);
+
+enum SuperShuckieNetplayStatus {
+    SuperShuckieNetplayStatus__Inactive = 0,
+    SuperShuckieNetplayStatus__WaitingForPeer = 1,
+    SuperShuckieNetplayStatus__Synchronizing = 2,
+    SuperShuckieNetplayStatus__Connected = 3,
+    SuperShuckieNetplayStatus__Disconnected = 4,
+    SuperShuckieNetplayStatus__Mismatched = 5
+};
+
+/**
+ * Get the status of the current netplay session (as a SuperShuckieNetplayStatus).
+ *
+ * When spectating, Synchronizing means frames are being buffered.
+ */
+uint32_t supershuckie_frontend_get_netplay_status(const struct SuperShuckieFrontendRaw *frontend);
+
+/**
+ * Get the address the Poke-A-Byte integration server is listening on, or null if it is not enabled.
+ *
+ * If the configured UDP port was in use (e.g. by another instance), the next free port of the following 15 is used, and
+ * this returns that address so the client can be pointed at this instance. This string is valid until the server is
+ * enabled or disabled.
+ */
+const char *supershuckie_frontend_get_pokeabyte_listen_address(const struct SuperShuckieFrontendRaw *frontend);
+
+enum SuperShuckiePokeAByteTransport {
+    SuperShuckiePokeAByteTransport__Udp = 0,
+    SuperShuckiePokeAByteTransport__UnixSocket = 1,
+    SuperShuckiePokeAByteTransport__AbstractSocket = 2
+};
+
+/**
+ * Get the transport used to listen for Poke-A-Byte (as a SuperShuckiePokeAByteTransport).
+ *
+ * If address is non-null, it is set to the transport's address, which is empty if the default is used. This string is
+ * valid until the transport is changed.
+ */
+uint32_t supershuckie_frontend_get_pokeabyte_transport(const struct SuperShuckieFrontendRaw *frontend, const char **address);
+
+/**
+ * Set the transport used to listen for Poke-A-Byte (as a SuperShuckiePokeAByteTransport) and its address.
+ *
+ * - Udp takes an IPv4 or IPv6 address and port (e.g. "127.0.0.1:55356" or "[::1]:55356")
+ * - UnixSocket takes a socket path (Unix only)
+ * - AbstractSocket takes a name in the abstract socket namespace (Linux only)
+ *
+ * Pass null or an empty address to use the transport's default. If the integration server is enabled, it is restarted
+ * with the new transport.
+ *
+ * Returns false if an error occurs, filling the error buffer with the error.
+ *
+ * Safety:
+ * - address must be null or a valid null-terminated UTF-8 string.
+ * - error must not be null and must be at least error_len bytes long.
+ */
+bool supershuckie_frontend_set_pokeabyte_transport(
+    struct SuperShuckieFrontendRaw *frontend,
+    uint32_t transport,
+    const char *address,
+    char *error,
+    size_t error_len
+);
+
+/**
+ * Disconnect the current Poke-A-Byte client, if any.
+ *
+ * Only one client can be connected at a time; others are sent error responses until the current client closes its
+ * session or is disconnected.
+ */
+void supershuckie_frontend_disconnect_pokeabyte_client(struct SuperShuckieFrontendRaw *frontend);
+
+/**
+ * Get whether or not Poke-A-Byte writes are rejected while a replay is being played back.
+ *
+ * If false, writes are instead held and applied once playback ends.
+ */
+bool supershuckie_frontend_is_rejecting_pokeabyte_writes_during_playback(const struct SuperShuckieFrontendRaw *frontend);
+
+/**
+ * Set whether or not Poke-A-Byte writes are rejected (with an error response to the client) while a replay is being
+ * played back.
+ *
+ * If false, writes are instead held and applied once playback ends.
+ */
+void supershuckie_frontend_set_rejecting_pokeabyte_writes_during_playback(struct SuperShuckieFrontendRaw *frontend, bool reject);
+
+/**
+ * Get whether or not failed RAM reads and writes pause emulation with an error instead of being ignored.
+ */
+bool supershuckie_frontend_is_strict_ram_access(const struct SuperShuckieFrontendRaw *frontend);
+
+/**
+ * Set whether or not failed RAM reads and writes (e.g. from a bad Poke-A-Byte mapper or during replay playback) pause
+ * emulation with an error instead of being ignored.
+ *
+ * The error (including the address and the core's message) is shown with the error callback. Replays being played back
+ * stall at the failed write, and failed writes are not recorded.
+ */
+void supershuckie_frontend_set_strict_ram_access(struct SuperShuckieFrontendRaw *frontend, bool strict);
+
+enum SuperShuckiePokeAByteFrameSkip {
+    SuperShuckiePokeAByteFrameSkip__Ignore = 0,
+    SuperShuckiePokeAByteFrameSkip__Honor = 1,
+    SuperShuckiePokeAByteFrameSkip__Custom = 2
+};
+
+/**
+ * Get how the number of frames Poke-A-Byte suggests skipping between reads is handled (as a
+ * SuperShuckiePokeAByteFrameSkip).
+ *
+ * Ignore updates reads every frame, Honor skips the suggested number of frames, and Custom skips a custom number of
+ * frames regardless of the suggestion. The number of frames skipped with SuperShuckiePokeAByteFrameSkip__Custom is written to custom_frame_skip.
+ */
+uint32_t supershuckie_frontend_get_pokeabyte_frame_skip(const struct SuperShuckieFrontendRaw *frontend, uint32_t *custom_frame_skip);
+
+/**
+ * Set how the number of frames Poke-A-Byte suggests skipping between reads is handled (as a
+ * SuperShuckiePokeAByteFrameSkip).
+ *
+ * custom_frame_skip is only used with SuperShuckiePokeAByteFrameSkip__Custom. The frame skip actually used is reported
+ * to the client in the response to its next setup request.
+ */
+void supershuckie_frontend_set_pokeabyte_frame_skip(struct SuperShuckieFrontendRaw *frontend, uint32_t frame_skip, uint32_t custom_frame_skip);
+
+/**
+ * Return true if the emulator is currently manually paused.
+ */
+bool supershuckie_frontend_is_paused(const struct SuperShuckieFrontendRaw *frontend);
+
+/**
+ * Get the currently recorded replay file, or nullptr if none.
+ */
+const char *supershuckie_frontend_get_recording_replay_file(const struct SuperShuckieFrontendRaw *frontend);
+
+/**
+ * Create a save state of the given name, or null to use a default name.
+ *
+ * If true is returned, the name of the save state (besides the extension) will be written to result (ensure it is long enough).
+ *
+ * If false is returned, an error will be written. If cancel is non-null and gets cancelled before the save state is
+ * created, nothing is saved and the error is "Cancelled".
+ *
+ * Safety:
+ * - result must not be null and must be at least result_len bytes long.
+ */
+bool supershuckie_frontend_create_save_state(
+    struct SuperShuckieFrontendRaw *frontend,
+    const char *name,
+    const struct SuperShuckieCancellationTokenRaw *cancel,
+    char *result,
+    size_t result_len
+);
+
+/**
+ * Largest scale screenshots and clips can be exported at.
+ */
+#define SUPERSHUCKIE_MAX_EXPORT_SCALE 16
+
+/**
+ * Save what is currently shown on screen (including frame blending and visualizers) as a PNG in the ROM's screenshots
+ * directory, upscaled by scale with nearest-neighbor scaling. If scale is 0, it will default to 1. Scales above
+ * SUPERSHUCKIE_MAX_EXPORT_SCALE are an error.
+ *
+ * If true is returned, the filename of the screenshot will be written to result (ensure it is long enough).
+ *
+ * If false is returned, an error will be written.
+ *
+ * Safety:
+ * - result must not be null and must be at least result_len bytes long.
+ */
+bool supershuckie_frontend_save_screenshot(struct SuperShuckieFrontendRaw *frontend, uint8_t scale, char *result, size_t result_len);
+
+/**
+ * Render frames first_frame through last_frame (inclusive) of the replay with the given name as a looping GIF in the
+ * ROM's screenshots directory, upscaled by scale with nearest-neighbor scaling. If scale is 0, it will default to 1.
+ * Scales above SUPERSHUCKIE_MAX_EXPORT_SCALE are an error.
+ *
+ * Frames are rendered on a scratch core, so the running game is not disturbed. Clips are limited to about 30 seconds.
+ *
+ * If true is returned, the filename of the clip will be written to result (ensure it is long enough).
+ *
+ * If false is returned, an error will be written.
+ *
+ * Safety:
+ * - name must be a null-terminated UTF-8 string
+ * - result must not be null and must be at least result_len bytes long.
+ */
+bool supershuckie_frontend_export_replay_clip(struct SuperShuckieFrontendRaw *frontend, const char *name, uint32_t first_frame, uint32_t last_frame, uint8_t scale, char *result, size_t result_len);
+
+/**
+ * Render the audio of frames first_frame through last_frame (inclusive) of the replay with the given name as a WAV
+ * file in the ROM's screenshots directory, at the rate set with supershuckie_frontend_set_audio_sample_rate.
+ *
+ * This covers exactly the same time as supershuckie_frontend_export_replay_clip with the same frames, so the two can be
+ * combined into one video. The same limits apply.
+ *
+ * If true is returned, the filename of the recording will be written to result (ensure it is long enough).
+ *
+ * If false is returned, an error will be written.
+ *
+ * Safety:
+ * - name must be a null-terminated UTF-8 string
+ * - result must not be null and must be at least result_len bytes long.
+ */
+bool supershuckie_frontend_export_replay_audio(struct SuperShuckieFrontendRaw *frontend, const char *name, uint32_t first_frame, uint32_t last_frame, char *result, size_t result_len);
+
+/**
+ * Benchmark replay compression on a sample recorded from the running game, and use the compression level that
+ * compresses the best while still keeping up with recording.
+ *
+ * This can take a few seconds.
+ *
+ * If true is returned, the new compression level will be written to level (if non-null).
+ *
+ * If false is returned, an error will be written.
+ *
+ * Safety:
+ * - error must not be null and must be at least error_len bytes long.
+ */
+bool supershuckie_frontend_optimize_replay_compression_level(struct SuperShuckieFrontendRaw *frontend, int32_t *level, char *error, size_t error_len);
+
+/**
+ * Load a save state of the given name.
+ *
+ * If false is returned, an error will be written UNLESS it was because the save state did not exist, in which case the
+ * error will be empty.
+ *
+ * If the save state exists but the core fails to load it, it is moved to quarantine (see
+ * supershuckie_frontend_get_quarantined_save_states_for_rom) and the error says so.
+ *
+ * Safety:
+ * - name must not be null
+ * - error must be at least result_len bytes long.
+ */
+bool supershuckie_frontend_load_save_state(struct SuperShuckieFrontendRaw *frontend, const char *name, char *error, size_t error_len);
+
+/**
+ * Render a preview of the save state of the given name without disturbing the running game.
+ *
+ * On success, the dimensions of the first screen are written to width and height, and up to pixel_count pixels are
+ * copied into pixels. Pass a null pixels pointer to only query the dimensions.
+ *
+ * Returns false if the save state does not exist or could not be rendered.
+ *
+ * Safety:
+ * - name, width, and height must not be null
+ * - if pixels is not null, it must be at least pixel_count pixels long
+ */
+bool supershuckie_frontend_render_save_state_preview(
+    struct SuperShuckieFrontendRaw *frontend,
+    const char *name,
+    uint32_t *pixels,
+    size_t pixel_count,
+    uint32_t *width,
+    uint32_t *height
+);
+
+/**
+ * Undo loading a save state, storing a backup of the current state in the stack.
+ *
+ * Returns true if successful or false if the end of the stack has been reached.
+ */
+bool supershuckie_frontend_undo_load_save_state(struct SuperShuckieFrontendRaw *frontend);
+
+/**
+ * Redo loading a save state, storing a backup of the current state in the stack.
+ *
+ * Returns true if successful or false if the end of the stack has been reached.
+ */
+bool supershuckie_frontend_redo_load_save_state(struct SuperShuckieFrontendRaw *frontend);
+
+/**
+ * Load the given ROM, returning true or false depending on whether or not it was successfully loaded.
+ *
+ * If path is a ZIP archive, the single ROM inside of it is loaded. If it contains more than one ROM, this fails, and
+ * one has to be chosen with supershuckie_frontend_get_roms_in_archive and loaded with
+ * supershuckie_frontend_load_rom_from_archive.
+ *
+ * Safety:
+ * - path must be null-terminated, UTF-8
+ * - error must point to a buffer of at least `error_len` bytes (it can be null if error_len is 0)
+ */
+bool supershuckie_frontend_load_rom(struct SuperShuckieFrontendRaw *frontend, const char *path, char *error, size_t error_len);
+
+/**
+ * Get the ROMs in the ZIP archive at the given path that can be loaded with supershuckie_frontend_load_rom_from_archive.
+ *
+ * Returns null if the archive can't be read.
+ *
+ * This array must be freed with supershuckie_stringarray_free
+ *
+ * Safety:
+ * - path must be null-terminated, UTF-8
+ * - error must point to a buffer of at least `error_len` bytes
+ */
+struct SuperShuckieStringArrayRaw *supershuckie_frontend_get_roms_in_archive(const struct SuperShuckieFrontendRaw *frontend, const char *path, char *error, size_t error_len);
+
+/**
+ * Load a ROM from the ZIP archive at the given path, returning true or false depending on whether or not it was
+ * successfully loaded.
+ *
+ * The ROM's save data and other user data are stored under the ROM's own filename rather than the archive's.
+ *
+ * Safety:
+ * - path and entry must be null-terminated, UTF-8
+ * - entry should be one of the names returned by supershuckie_frontend_get_roms_in_archive
+ * - error must point to a buffer of at least `error_len` bytes
+ */
+bool supershuckie_frontend_load_rom_from_archive(struct SuperShuckieFrontendRaw *frontend, const char *path, const char *entry, char *error, size_t error_len);
+
+/**
+ * Write a file through the frontend's storage, creating its folder if needed.
+ *
+ * This is mainly for adding ROMs to a frontend created with supershuckie_frontend_new_in_memory().
+ *
+ * Safety:
+ * - path must be null-terminated, UTF-8
+ * - data must point to at least data_len bytes (it can be null if data_len is 0)
+ * - error must be at least error_len bytes long
+ */
+bool supershuckie_frontend_write_file(
+    const struct SuperShuckieFrontendRaw *frontend,
+    const char *path,
+    const uint8_t *data,
+    size_t data_len,
+    char *error,
+    size_t error_len
+);
+
+/**
+ * Write SRAM to disk, returning true if successful.
+ *
+ * Safety:
+ * - error must be at least result_len bytes long.
+ */
+bool supershuckie_frontend_save_sram(struct SuperShuckieFrontendRaw *frontend, char *error, size_t error_len);
+
+/**
+ * Set the auto stop playback setting.
+ */
+void supershuckie_frontend_set_auto_stop_playback_on_input_setting(struct SuperShuckieFrontendRaw *frontend, bool new_setting);
+
+/**
+ * Get the auto stop playback setting.
+ */
+bool supershuckie_frontend_get_auto_stop_playback_on_input_setting(const struct SuperShuckieFrontendRaw *frontend);
 
 /**
  * Set the auto unpause setting.
  */
-void supershuckie_frontend_set_auto_unpause_on_input_setting(struct SuperShuckieFrontendRaw *frontend, bool new_setting);
+void supershuckie_frontend_set_auto_unpause_on_input_setting(struct SuperShuckieFrontendRaw *frontend, bool new_setting);
+
+/**
+ * Get the auto unpause setting.
+ */
+bool supershuckie_frontend_get_auto_unpause_on_input_setting(const struct SuperShuckieFrontendRaw *frontend);
+
+/**
+ * Set the auto pause on record setting.
+ */
+void supershuckie_frontend_set_auto_pause_on_record_setting(struct SuperShuckieFrontendRaw *frontend, bool new_setting);
+
+/**
+ * Get the auto pause on record setting.
+ */
+bool supershuckie_frontend_get_auto_pause_on_record_setting(const struct SuperShuckieFrontendRaw *frontend);
+
+/**
+ * Set whether or not save data written during replay playback is discarded instead of being saved under the "replay"
+ * save file.
+ *
+ * This takes effect the next time a replay is played back.
+ */
+void supershuckie_frontend_set_sandbox_playback_sram_setting(struct SuperShuckieFrontendRaw *frontend, bool new_setting);
+
+/**
+ * Get whether or not save data written during replay playback is discarded.
+ */
+bool supershuckie_frontend_get_sandbox_playback_sram_setting(const struct SuperShuckieFrontendRaw *frontend);
+
+/**
+ * Set how often replays are checked for determinism while recording, or 0 to not check.
+ *
+ * When enabled, one out of every `keyframes` keyframe intervals is re-simulated on a second core and compared with the
+ * live game. A mismatch is shown as an error while recording continues. This is slow, so it is meant for catching
+ * recording bugs.
+ *
+ * This takes effect the next time a replay is recorded.
+ */
+void supershuckie_frontend_set_determinism_check_keyframes_setting(struct SuperShuckieFrontendRaw *frontend, uint64_t keyframes);
+
+/**
+ * Get how often replays are checked for determinism while recording, or 0 if not checked.
+ */
+uint64_t supershuckie_frontend_get_determinism_check_keyframes_setting(const struct SuperShuckieFrontendRaw *frontend);
+
+/**
+ * Return true if the save data came from a replay and is not being saved.
+ *
+ * This stays set after playback stops, until another save file is chosen (e.g. with
+ * supershuckie_frontend_set_current_save_file) or the ROM is reloaded.
+ */
+bool supershuckie_frontend_is_sram_sandboxed(const struct SuperShuckieFrontendRaw *frontend);
+
+enum SuperShuckieLoadStateRecordMode {
+    SuperShuckieLoadStateRecordMode__Embed = 0,
+    SuperShuckieLoadStateRecordMode__RestoreKeyframe = 1,
+    SuperShuckieLoadStateRecordMode__Branch = 2
+};
+
+/**
+ * Set what to record when a save state is loaded while recording a replay.
+ *
+ * This takes effect the next time a recording is started.
+ */
+void supershuckie_frontend_set_load_state_record_mode_setting(struct SuperShuckieFrontendRaw *frontend, uint32_t mode);
+
+/**
+ * Get what to record when a save state is loaded while recording a replay.
+ */
+uint32_t supershuckie_frontend_get_load_state_record_mode_setting(const struct SuperShuckieFrontendRaw *frontend);
+
+/**
+ * Set whether or not save states are automatically taken periodically while playing.
+ */
+void supershuckie_frontend_set_autosnap_enabled_setting(struct SuperShuckieFrontendRaw *frontend, bool new_setting);
+
+/**
+ * Get whether or not save states are automatically taken periodically while playing.
+ */
+bool supershuckie_frontend_get_autosnap_enabled_setting(const struct SuperShuckieFrontendRaw *frontend);
+
+/**
+ * Set whether or not save data is backed up whenever it is overwritten or deleted.
+ */
+void supershuckie_frontend_set_save_backups_enabled_setting(struct SuperShuckieFrontendRaw *frontend, bool new_setting);
+
+/**
+ * Get whether or not save data is backed up whenever it is overwritten or deleted.
+ */
+bool supershuckie_frontend_get_save_backups_enabled_setting(const struct SuperShuckieFrontendRaw *frontend);
+
+/**
+ * Set whether or not the game is paused while the window is unfocused (see supershuckie_frontend_set_focused).
+ */
+void supershuckie_frontend_set_pause_on_focus_loss_setting(struct SuperShuckieFrontendRaw *frontend, bool new_setting);
+
+/**
+ * Get whether or not the game is paused while the window is unfocused.
+ */
+bool supershuckie_frontend_get_pause_on_focus_loss_setting(const struct SuperShuckieFrontendRaw *frontend);
+
+/**
+ * Tell the frontend whether or not the window is focused.
+ *
+ * If pausing on focus loss is enabled, the game is paused when focus is lost (unless recording a replay or in a
+ * netplay session), then unpaused when focus is regained if it wasn't paused or unpaused some other way in the meantime.
+ */
+void supershuckie_frontend_set_focused(struct SuperShuckieFrontendRaw *frontend, bool focused);
+
+/**
+ * Set whether or not screens are skipped while the window is minimized (see supershuckie_frontend_set_minimized).
+ */
+void supershuckie_frontend_set_throttle_when_minimized_setting(struct SuperShuckieFrontendRaw *frontend, bool new_setting);
+
+/**
+ * Get whether or not screens are skipped while the window is minimized.
+ */
+bool supershuckie_frontend_get_throttle_when_minimized_setting(const struct SuperShuckieFrontendRaw *frontend);
+
+/**
+ * Tell the frontend whether or not the window is minimized.
+ *
+ * If throttling when minimized is enabled, the refresh_screens callback isn't called until the window is restored, but
+ * emulation and Poke-A-Byte integration keep running.
+ */
+void supershuckie_frontend_set_minimized(struct SuperShuckieFrontendRaw *frontend, bool minimized);
+
+enum SuperShuckiePowerSaving {
+    SuperShuckiePowerSaving__Off = 0,
+    SuperShuckiePowerSaving__OnBattery = 1,
+    SuperShuckiePowerSaving__Always = 2
+};
+
+/**
+ * Set when to save power by capping the speed at 1x, skipping every other frame, and lowering the core thread's
+ * priority.
+ */
+void supershuckie_frontend_set_power_saving_setting(struct SuperShuckieFrontendRaw *frontend, uint32_t mode);
+
+/**
+ * Get when to save power.
+ */
+uint32_t supershuckie_frontend_get_power_saving_setting(const struct SuperShuckieFrontendRaw *frontend);
+
+/**
+ * Get whether or not power is currently being saved (e.g. because the host is on battery).
+ */
+bool supershuckie_frontend_is_power_saving_active(const struct SuperShuckieFrontendRaw *frontend);
+
+/**
+ * Set whether or not starting another instance with the same user data path forwards its ROM to this one.
+ */
+void supershuckie_frontend_set_single_instance_setting(struct SuperShuckieFrontendRaw *frontend, bool new_setting);
+
+/**
+ * Get whether or not starting another instance with the same user data path forwards its ROM to this one.
+ */
+bool supershuckie_frontend_get_single_instance_setting(const struct SuperShuckieFrontendRaw *frontend);
+
+/**
+ * Set the current frame for playback.
+ */
+void supershuckie_frontend_set_playback_frame(struct SuperShuckieFrontendRaw *frontend, uint32_t frame);
+
+/**
+ * Set the current frame for playback, stopping the seek early (wherever it got to) if cancel is cancelled.
+ *
+ * The seek happens in the background, so cancel must not be freed until the seek is done (e.g. until the Finished
+ * progress report, if any) or it is cancelled.
+ */
+void supershuckie_frontend_set_playback_frame_cancellable(
+    struct SuperShuckieFrontendRaw *frontend,
+    uint32_t frame,
+    const struct SuperShuckieCancellationTokenRaw *cancel
+);
+
+/**
+ * Advance or go back a set number of frames.
+ */
+void supershuckie_frontend_advance_playback_frames(struct SuperShuckieFrontendRaw *frontend, int32_t delta);
+
+/**
+ * Advance one frame.
+ *
+ * While playing back a replay, this steps forward one frame of the replay. Otherwise, this pauses the game if it is
+ * running, or runs exactly one frame if it is already paused. This does nothing in a netplay session.
+ *
+ * This can also be done with the "Frame advance" control.
+ */
+void supershuckie_frontend_frame_advance(struct SuperShuckieFrontendRaw *frontend);
+
+/**
+ * Set whether or not rewind is being held (e.g. call with true when a button is pressed and false when it is released).
+ *
+ * While playing back a replay, this steps backward by one keyframe interval at a time until released. Otherwise, this
+ * rewinds through captured states if rewinding is enabled (see supershuckie_frontend_set_rewind_enabled).
+ */
+void supershuckie_frontend_rewind_hold(struct SuperShuckieFrontendRaw *frontend, bool held);
+
+/**
+ * Set whether or not states are captured while playing so that rewind can go back in time.
+ *
+ * Disabling this discards all captured states.
+ */
+void supershuckie_frontend_set_rewind_enabled(struct SuperShuckieFrontendRaw *frontend, bool enabled);
+
+/**
+ * Get whether or not states are captured for rewinding.
+ */
+bool supershuckie_frontend_is_rewind_enabled(struct SuperShuckieFrontendRaw *frontend);
+
+/**
+ * Set the number of frames between states captured for rewinding (0 is treated as 1).
+ *
+ * This discards all captured states.
+ */
+void supershuckie_frontend_set_rewind_interval_frames(struct SuperShuckieFrontendRaw *frontend, uint64_t frames);
+
+/**
+ * Get the number of frames between states captured for rewinding.
+ */
+uint64_t supershuckie_frontend_get_rewind_interval_frames(struct SuperShuckieFrontendRaw *frontend);
+
+/**
+ * Set the memory used by states captured for rewinding, in MiB (0 is treated as 1). The oldest states are discarded
+ * first.
+ *
+ * This discards all captured states.
+ */
+void supershuckie_frontend_set_rewind_memory_budget_mib(struct SuperShuckieFrontendRaw *frontend, uint32_t mib);
+
+/**
+ * Get the memory used by states captured for rewinding, in MiB.
+ */
+uint32_t supershuckie_frontend_get_rewind_memory_budget_mib(struct SuperShuckieFrontendRaw *frontend);
+
+/**
+ * Set paused (temporarily)
+ */
+void supershuckie_frontend_set_playback_frozen(struct SuperShuckieFrontendRaw *frontend, bool paused);
+
+/**
+ * Get the average time spent per frame, in microseconds, in each part of the core thread, returning the number of frames
+ * averaged (0 if no frames have been run yet).
+ *
+ * This is useful for figuring out why emulation is slow. Each output, if non-null, will be written its respective value.
+ */
+uint32_t supershuckie_frontend_get_frame_timings(
+    const struct SuperShuckieFrontendRaw *frontend,
+    uint32_t *emulation_us,
+    uint32_t *screen_copy_us,
+    uint32_t *pokeabyte_us,
+    uint32_t *recording_us
+);
+
+/**
+ * Get the input the replay being played back is pressing on the current frame, returning true if currently playing back a
+ * replay.
+ *
+ * buttons is a bitfield in the same format as supershuckie_frontend_get_input_descriptor, with the touch and tilt bits
+ * set if the screen is being touched or the console is being tilted, respectively. Each output, if non-null, will be
+ * written its respective value.
+ */
+bool supershuckie_frontend_get_playback_input(
+    const struct SuperShuckieFrontendRaw *frontend,
+    uint32_t *buttons,
+    uint16_t *touch_x,
+    uint16_t *touch_y,
+    int16_t *tilt_x,
+    int16_t *tilt_y
+);
+
+/**
+ * Get all keyframes in the replay being played back, sorted by frame. The array is empty if not playing back.
+ *
+ * Keyframes have empty names. Use supershuckie_frontend_set_playback_frame to seek to one.
+ *
+ * This array must be freed with supershuckie_replaymarkerarray_free
+ */
+struct SuperShuckieReplayMarkerArrayRaw *supershuckie_frontend_get_playback_keyframes(const struct SuperShuckieFrontendRaw *frontend);
+
+/**
+ * Get all bookmarks in the replay being played back, sorted by frame. The array is empty if not playing back.
+ *
+ * This array must be freed with supershuckie_replaymarkerarray_free
+ */
+struct SuperShuckieReplayMarkerArrayRaw *supershuckie_frontend_get_playback_bookmarks(const struct SuperShuckieFrontendRaw *frontend);
+
+/**
+ * Add a bookmark with the given name to the current frame of the replay being recorded. Does nothing if not recording.
+ *
+ * Safety:
+ * - name must be a valid null-terminated UTF-8 string.
+ */
+void supershuckie_frontend_add_bookmark(struct SuperShuckieFrontendRaw *frontend, const char *name);
+
+/**
+ * Get all bookmarks in the replay being recorded or played back, sorted by frame. The array is empty if neither.
+ *
+ * This array must be freed with supershuckie_replaymarkerarray_free
+ */
+struct SuperShuckieReplayMarkerArrayRaw *supershuckie_frontend_list_bookmarks(const struct SuperShuckieFrontendRaw *frontend);
+
+/**
+ * Get the replay playback stats, returning true if currently playing back a replay.
+ *
+ * total_frames and total_milliseconds, if non-null, will be written their respective values.
+ */
+bool supershuckie_frontend_get_replay_playback_time(
+    const struct SuperShuckieFrontendRaw *frontend,
+    uint32_t *total_frames,
+    uint32_t *total_milliseconds
+);
+
+/**
+ * Get the play stats for the given ROM, or the currently loaded ROM if no ROM passed in.
+ *
+ * Each parameter, if non-null, will be written its respective value:
+ * - playtime_milliseconds: total time spent playing (unpaused)
+ * - sessions: number of times the ROM was loaded
+ * - last_played: time the ROM was last loaded, in seconds since the Unix epoch (or 0 if never)
+ * - hard_resets: number of times the console was hard reset or power cycled
+ *
+ * Returns false (writing zeroes) if rom is null and no ROM is loaded.
+ */
+bool supershuckie_frontend_get_rom_stats(
+    const struct SuperShuckieFrontendRaw *frontend,
+    const char *rom,
+    uint64_t *playtime_milliseconds,
+    uint64_t *sessions,
+    uint64_t *last_played,
+    uint64_t *hard_resets
+);
+
+/**
+ * Get the number of milliseconds and frames elapsed.
+ *
+ * elapsed_frames and elapsed_milliseconds, if non-null, will be written their respective values.
+ */
+void supershuckie_frontend_get_elapsed_time(
+    const struct SuperShuckieFrontendRaw *frontend,
+    uint32_t *elapsed_frames,
+    uint32_t *elapsed_milliseconds
+);
+
+/**
+ * Load the given replay, returning true or false depending on whether or not it was successfully loaded.
+ *
+ * Safety:
+ * - path must be null-terminated, UTF-8
+ * - error must point to a buffer of at least `error_len` bytes (it can be null if error_len is 0)
+ */
+bool supershuckie_frontend_load_replay(
+    struct SuperShuckieFrontendRaw *frontend,
+    const char *name,
+    bool ignore_some_errors,
+    char *error,
+    size_t error_len
+);
+
+/**
+ * Kinds of metadata mismatches between a replay and what is loaded. These can be combined into a mask.
+ */
+enum SuperShuckieReplayMismatch {
+    SuperShuckieReplayMismatch__ROMChecksum = 1,
+    SuperShuckieReplayMismatch__BIOSChecksum = 2,
+    SuperShuckieReplayMismatch__Core = 4,
+    SuperShuckieReplayMismatch__SRAMChecksum = 8
+};
+
+/**
+ * Load the given replay, playing it back despite the metadata mismatches in the `acknowledged` mask.
+ *
+ * Mismatches in the `remembered` mask are also acknowledged, and they will continue to be acknowledged for this replay
+ * as long as the same ROM, BIOS, save data, or core is loaded.
+ *
+ * If any mismatches were not acknowledged, false is returned, and they can be found with
+ * supershuckie_frontend_get_replay_mismatches.
+ *
+ * If cancel is non-null and gets cancelled while the replay is being read, false is returned with the error
+ * "Cancelled", and nothing is changed.
+ *
+ * Safety:
+ * - name must be null-terminated, UTF-8
+ * - error must point to a buffer of at least `error_len` bytes (it can be null if error_len is 0)
+ */
+bool supershuckie_frontend_load_replay_acknowledging(
+    struct SuperShuckieFrontendRaw *frontend,
+    const char *name,
+    uint32_t acknowledged,
+    uint32_t remembered,
+    const struct SuperShuckieCancellationTokenRaw *cancel,
+    char *error,
+    size_t error_len
+);
+
+/**
+ * Get a mask of the metadata mismatches that were not acknowledged the last time a replay was loaded.
+ */
+uint32_t supershuckie_frontend_get_replay_mismatches(const struct SuperShuckieFrontendRaw *frontend);
+
+/**
+ * Get a description of one of the mismatches from supershuckie_frontend_get_replay_mismatches.
+ *
+ * Returns false if the mismatch was not found.
+ *
+ * Safety:
+ * - description must point to a buffer of at least `description_len` bytes (it can be null if description_len is 0)
+ */
+bool supershuckie_frontend_get_replay_mismatch_description(
+    const struct SuperShuckieFrontendRaw *frontend,
+    uint32_t kind,
+    char *description,
+    size_t description_len
+);
+
+/**
+ * Stop acknowledging previously remembered metadata mismatches for the given replay of the current ROM.
+ *
+ * Safety:
+ * - name must be null-terminated, UTF-8
+ */
+void supershuckie_frontend_forget_replay_mismatches(struct SuperShuckieFrontendRaw *frontend, const char *name);
+
+/**
+ * Stop the currently playing replay, if any.
+ */
+void supershuckie_frontend_stop_replay_playback(struct SuperShuckieFrontendRaw *frontend);
+
+/**
+ * Check that the given replay plays back to its last frame with the loaded ROM, returning true if it does.
+ *
+ * The replay is played back as fast as possible without disturbing the running game. Problems such as a ROM checksum
+ * mismatch are never ignored.
+ *
+ * Safety:
+ * - name must be null-terminated, UTF-8
+ * - frames can be null; if not, the number of frames played back is written to it on success
+ * - error must point to a buffer of at least `error_len` bytes (it can be null if error_len is 0)
+ */
+bool supershuckie_frontend_verify_replay(
+    struct SuperShuckieFrontendRaw *frontend,
+    const char *name,
+    uint32_t *frames,
+    char *error,
+    size_t error_len
+);
+
+/**
+ * If there is a ROM running, return the name. Otherwise, return null.
+ */
+const char *supershuckie_frontend_get_rom_name(const struct SuperShuckieFrontendRaw *frontend);
+
+/**
+ * If there is a ROM running, return the name of the current save file. Otherwise, return null.
+ */
+const char *supershuckie_frontend_get_current_save_name(const struct SuperShuckieFrontendRaw *frontend);
+
+/**
+ * If there is a ROM running, return its BLAKE3 checksum in hex (as used by game profiles). Otherwise, return null.
+ */
+const char *supershuckie_frontend_get_rom_checksum(const struct SuperShuckieFrontendRaw *frontend);
+
+/**
+ * Add a game profile from JSON, such as one embedded in the frontend.
+ *
+ * Game profiles map ROM checksums to addresses (e.g. RNG seeds or positions) that are watched automatically when a
+ * matching ROM is loaded. Profiles in the "game profiles" folder of the user directory take precedence over ones added
+ * here.
+ *
+ * If a game is running without a profile, this one is loaded right away if it matches.
+ *
+ * Safety:
+ * - json must be a null-terminated UTF-8 string
+ * - error must be at least error_len bytes long
+ */
+bool supershuckie_frontend_add_game_profile(
+    struct SuperShuckieFrontendRaw *frontend,
+    const char *json,
+    char *error,
+    size_t error_len
+);
+
+/**
+ * If the running ROM has a game profile, return its name. Otherwise, return null.
+ */
+const char *supershuckie_frontend_get_game_profile_name(const struct SuperShuckieFrontendRaw *frontend);
+
+/**
+ * Get the name of each watch in the running ROM's game profile, in order.
+ *
+ * This array must be freed with supershuckie_stringarray_free
+ */
+struct SuperShuckieStringArrayRaw *supershuckie_frontend_get_game_profile_watch_names(const struct SuperShuckieFrontendRaw *frontend);
+
+/**
+ * Get the current value of each watch in the running ROM's game profile, formatted for display, in the same order as
+ * supershuckie_frontend_get_game_profile_watch_names.
+ *
+ * Values are empty if they have not been read yet.
+ *
+ * This array must be freed with supershuckie_stringarray_free
+ */
+struct SuperShuckieStringArrayRaw *supershuckie_frontend_get_game_profile_watch_values(const struct SuperShuckieFrontendRaw *frontend);
+
+/**
+ * Write settings to the given settings file.
+ */
+void supershuckie_frontend_write_settings(const struct SuperShuckieFrontendRaw *frontend);
+
+/**
+ * Return true if there is currently a game running.
+ */
+bool supershuckie_frontend_is_game_running(const struct SuperShuckieFrontendRaw *frontend);
+
+/**
+ * Unload the current ROM, if any.
+ *
+ * Will also try to save the SRAM.
+ */
+void supershuckie_frontend_close_rom(struct SuperShuckieFrontendRaw *frontend);
+
+/**
+ * Unload the current ROM, if any.
+ *
+ * Does NOT save the SRAM.
+ */
+void supershuckie_frontend_unload_rom(struct SuperShuckieFrontendRaw *frontend);
+
+/**
+ * Load a save save file, automatically saving the current SRAM before switching.
+ *
+ * If initialize is true, the save file will be deleted if it exists.
+ *
+ * Safety:
+ * - save_name must be null-terminated UTF-8
+ */
+void supershuckie_frontend_load_or_create_save_file(struct SuperShuckieFrontendRaw *frontend, const char *save_name, bool initialize);
+
+/**
+ * Set the current save file without reloading anything.
+ *
+ * Safety:
+ * - save_name must be null-terminated UTF-8
+ */
+void supershuckie_frontend_set_current_save_file(struct SuperShuckieFrontendRaw *frontend, const char *save_name);
+
+/**
+ * Hard reset the console, simulating switching off/on.
+ */
+void supershuckie_frontend_hard_reset_console(struct SuperShuckieFrontendRaw *frontend);
+
+/**
+ * Soft reset the console, if supported.
+ *
+ * @return false if the console does not support soft resetting
+ */
+bool supershuckie_frontend_soft_reset_console(struct SuperShuckieFrontendRaw *frontend);
+
+/**
+ * Delete the current save file's data and reload the game.
+ *
+ * This will stop recording any replay. Returns false if the save data could not be backed up or deleted, in which case
+ * the game keeps running as-is.
+ *
+ * Safety:
+ * - error must point to a buffer of at least `error_len` bytes (it can be null if error_len is 0)
+ */
+bool supershuckie_frontend_power_cycle_with_fresh_sram(struct SuperShuckieFrontendRaw *frontend, char *error, size_t error_len);
+
+/**
+ * Should be called regularly.
+ */
+void supershuckie_frontend_tick(struct SuperShuckieFrontendRaw *frontend);
+
+/**
+ * Set whether or not events are queued for supershuckie_frontend_poll_event. This is off by default.
+ *
+ * Turning it off discards any queued events. Callbacks are called either way, so frontends that only poll can leave
+ * them null.
+ */
+void supershuckie_frontend_set_event_polling_enabled(struct SuperShuckieFrontendRaw *frontend, bool enabled);
+
+/**
+ * Take the oldest queued event, writing it to event and returning true, or return false if there are no events.
+ *
+ * Events are queued by supershuckie_frontend_tick (among other functions), so call this until it returns false after
+ * every tick. If events are not polled, the oldest ones are eventually discarded.
+ */
+bool supershuckie_frontend_poll_event(struct SuperShuckieFrontendRaw *frontend, struct SuperShuckieEvent *event);
+
+/**
+ * Called with an event as it happens. event is only valid for the duration of the call.
+ */
+typedef void (*SuperShuckieEventHook)(void *userdata, const struct SuperShuckieEvent *event);
+
+/**
+ * Add a hook which is called with each event whose type (as a SuperShuckieEventType) is set in event_mask, e.g.
+ * (1 << SuperShuckieEventType__RomLoaded) | (1 << SuperShuckieEventType__Reset).
+ *
+ * This is for integrations (e.g. achievements, rich presence, or scripts) that need to know when things happen. It
+ * works even if event polling is disabled.
+ *
+ * Returns the hook's ID, or 0 if hook is null.
+ *
+ * Safety:
+ * - userdata must remain valid for hook until the hook is removed or the frontend is freed
+ */
+uint32_t supershuckie_frontend_add_event_hook(
+    struct SuperShuckieFrontendRaw *frontend,
+    uint32_t event_mask,
+    SuperShuckieEventHook hook,
+    void *userdata
+);
 
 /**
- * Get the auto unpause setting.
+ * Remove a hook added with supershuckie_frontend_add_event_hook, returning true if it was removed.
  */
-bool supershuckie_frontend_get_auto_unpause_on_input_setting(const struct SuperShuckieFrontendRaw *frontend);
+bool supershuckie_frontend_remove_event_hook(struct SuperShuckieFrontendRaw *frontend, uint32_t id);
 
 /**
- * Set the auto pause on record setting.
+ * Copy the pixels of the given screen to pixels, returning true if successful.
+ *
+ * width and height, if non-null, are written the size of the screen if it exists. Returns false without copying if
+ * the screen does not exist or pixel_count is less than width * height. Pass null pixels to just get the size.
+ *
+ * Safety:
+ * - pixels must be null or point to at least pixel_count pixels
  */
-void supershuckie_frontend_set_auto_pause_on_record_setting(struct SuperShuckieFrontendRaw *frontend, bool new_setting);
+bool supershuckie_frontend_read_screen(
+    const struct SuperShuckieFrontendRaw *frontend,
+    size_t screen,
+    uint32_t *width,
+    uint32_t *height,
+    uint32_t *pixels,
+    size_t pixel_count
+);
 
 /**
- * Get the auto pause on record setting.
+ * Get the backups of the current save data, newest first, or an empty array if no ROM is running.
+ *
+ * Backups are named by the time they were made.
+ *
+ * This array must be freed with supershuckie_stringarray_free
  */
-bool supershuckie_frontend_get_auto_pause_on_record_setting(const struct SuperShuckieFrontendRaw *frontend);
+struct SuperShuckieStringArrayRaw *supershuckie_frontend_get_save_backups(const struct SuperShuckieFrontendRaw *frontend);
 
 /**
- * Set the current frame for playback.
+ * Replace the current save data with the given backup and reload the game.
+ *
+ * The current save data is backed up first, so this can be undone.
+ *
+ * Safety:
+ * - backup must be a null-terminated UTF-8 string
+ * - error must be at least error_len bytes long
  */
-void supershuckie_frontend_set_playback_frame(struct SuperShuckieFrontendRaw *frontend, uint32_t frame);
+bool supershuckie_frontend_restore_save_backup(
+    struct SuperShuckieFrontendRaw *frontend,
+    const char *backup,
+    char *error,
+    size_t error_len
+);
 
 /**
- * Advance or go back a set number of frames.
+ * Get the names of all save files for the given rom, or the currently loaded ROM if no ROM passed in, sorted by name.
+ *
+ * Unlike supershuckie_frontend_get_all_saves_for_rom, this includes save files that were created but not written yet.
+ *
+ * This array must be freed with supershuckie_stringarray_free
  */
-void supershuckie_frontend_advance_playback_frames(struct SuperShuckieFrontendRaw *frontend, int32_t delta);
+struct SuperShuckieStringArrayRaw *supershuckie_frontend_get_save_profiles(const struct SuperShuckieFrontendRaw *frontend, const char *rom);
 
 /**
- * Set paused (temporarily)
+ * Get the metadata of a save file for the given rom, or the currently loaded ROM if no ROM passed in.
+ *
+ * Each parameter, if non-null, will be written its respective value:
+ * - created: time the save file was created, in seconds since the Unix epoch (or 0 if unknown)
+ * - last_used: time the save file was last used, in seconds since the Unix epoch (or 0 if never)
+ * - playtime_milliseconds: total time spent playing with the save file
+ *
+ * Returns false (writing zeroes) if the save file does not exist.
+ *
+ * Safety:
+ * - name must be a null-terminated UTF-8 string
  */
-void supershuckie_frontend_set_playback_frozen(struct SuperShuckieFrontendRaw *frontend, bool paused);
+bool supershuckie_frontend_get_save_profile_info(
+    const struct SuperShuckieFrontendRaw *frontend,
+    const char *rom,
+    const char *name,
+    uint64_t *created,
+    uint64_t *last_used,
+    uint64_t *playtime_milliseconds
+);
 
 /**
- * Get the replay playback stats, returning true if currently playing back a replay.
+ * Create an empty save file for the current ROM without switching to it.
  *
- * total_frames and total_milliseconds, if non-null, will be written their respective values.
+ * Safety:
+ * - name must be a null-terminated UTF-8 string
+ * - error must be at least error_len bytes long
  */
-bool supershuckie_frontend_get_replay_playback_time(
-    const struct SuperShuckieFrontendRaw *frontend,
-    uint32_t *total_frames,
-    uint32_t *total_milliseconds
+bool supershuckie_frontend_create_save_profile(
+    struct SuperShuckieFrontendRaw *frontend,
+    const char *name,
+    char *error,
+    size_t error_len
 );
 
 /**
- * Get the number of milliseconds and frames elapsed.
+ * Delete a save file of the current ROM, backing it up first if save backups are enabled.
  *
- * elapsed_frames and elapsed_milliseconds, if non-null, will be written their respective values.
+ * The current save file can't be deleted.
+ *
+ * Safety:
+ * - name must be a null-terminated UTF-8 string
+ * - error must be at least error_len bytes long
  */
-void supershuckie_frontend_get_elapsed_time(
-    const struct SuperShuckieFrontendRaw *frontend,
-    uint32_t *elapsed_frames,
-    uint32_t *elapsed_milliseconds
+bool supershuckie_frontend_delete_save_profile(
+    struct SuperShuckieFrontendRaw *frontend,
+    const char *name,
+    char *error,
+    size_t error_len
 );
 
 /**
- * Load the given replay, returning true or false depending on whether or not it was successfully loaded.
+ * Copy a save file of the current ROM, along with its playtime, to a new save file.
  *
  * Safety:
- * - path must be null-terminated, UTF-8
- * - error must point to a buffer of at least `error_len` bytes (it can be null if error_len is 0)
+ * - name must be a null-terminated UTF-8 string
+ * - new_name must be a null-terminated UTF-8 string
+ * - error must be at least error_len bytes long
  */
-bool supershuckie_frontend_load_replay(
+bool supershuckie_frontend_duplicate_save_profile(
     struct SuperShuckieFrontendRaw *frontend,
     const char *name,
-    bool ignore_some_errors,
+    const char *new_name,
     char *error,
     size_t error_len
 );
 
 /**
- * Stop the currently playing replay, if any.
+ * Rename a save file of the current ROM, along with its metadata and backups.
+ *
+ * Safety:
+ * - name must be a null-terminated UTF-8 string
+ * - new_name must be a null-terminated UTF-8 string
+ * - error must be at least error_len bytes long
  */
-void supershuckie_frontend_stop_replay_playback(struct SuperShuckieFrontendRaw *frontend);
+bool supershuckie_frontend_rename_save_profile(
+    struct SuperShuckieFrontendRaw *frontend,
+    const char *name,
+    const char *new_name,
+    char *error,
+    size_t error_len
+);
 
 /**
- * If there is a ROM running, return the name. Otherwise, return null.
+ * Get all replays for the given rom, or the currently loaded ROM if no ROM passed in.
+ *
+ * This array must be freed with supershuckie_stringarray_free
  */
-const char *supershuckie_frontend_get_rom_name(const struct SuperShuckieFrontendRaw *frontend);
+struct SuperShuckieStringArrayRaw *supershuckie_frontend_get_all_replays_for_rom(const struct SuperShuckieFrontendRaw *frontend, const char *rom);
 
 /**
- * Write settings to the given settings file.
+ * Scrub identifying info from the header of a replay of the given rom (or the currently loaded ROM if null) so it can
+ * be shared publicly.
+ *
+ * The internal ROM name is replaced with rom_name and the ROM filename is cleared. Nothing else is changed, so it
+ * still plays back the same.
+ *
+ * Safety:
+ * - name and rom_name must be null-terminated UTF-8 strings
+ * - rom can be null
+ * - error must be at least error_len bytes long
  */
-void supershuckie_frontend_write_settings(const struct SuperShuckieFrontendRaw *frontend);
+bool supershuckie_frontend_scrub_replay(
+    const struct SuperShuckieFrontendRaw *frontend,
+    const char *rom,
+    const char *name,
+    const char *rom_name,
+    char *error,
+    size_t error_len
+);
+
+enum SuperShuckieReplaySort {
+    SuperShuckieReplaySort__Name = 0,
+    SuperShuckieReplaySort__Duration = 1,
+    SuperShuckieReplaySort__Size = 2,
+    SuperShuckieReplaySort__Created = 3
+};
 
 /**
- * Return true if there is currently a game running.
+ * Get all replays for the given rom, or the currently loaded ROM if no ROM passed in, along with their details.
+ *
+ * Replays are sorted by `sort` (a SuperShuckieReplaySort). If name_filter is non-null, only replays with names
+ * containing it (ignoring case) are included. If compatible_only is set, only replays that can be played back with the
+ * loaded ROM without acknowledging any mismatches are included.
+ *
+ * Compatibility can only be checked if the ROM is loaded. Files are only re-read if they changed since the last call.
+ *
+ * This array must be freed with supershuckie_replayinfoarray_free
+ *
+ * Safety:
+ * - rom and name_filter must be null or null-terminated, UTF-8
  */
-bool supershuckie_frontend_is_game_running(const struct SuperShuckieFrontendRaw *frontend);
+struct SuperShuckieReplayInfoArrayRaw *supershuckie_frontend_browse_replays(
+    struct SuperShuckieFrontendRaw *frontend,
+    const char *rom,
+    uint32_t sort,
+    bool descending,
+    const char *name_filter,
+    bool compatible_only
+);
 
 /**
- * Unload the current ROM, if any.
+ * Get all save states for the given rom, or the currently loaded ROM if no ROM passed in.
  *
- * Will also try to save the SRAM.
+ * This array must be freed with supershuckie_stringarray_free
  */
-void supershuckie_frontend_close_rom(struct SuperShuckieFrontendRaw *frontend);
+struct SuperShuckieStringArrayRaw *supershuckie_frontend_get_all_save_states_for_rom(const struct SuperShuckieFrontendRaw *frontend, const char *rom);
 
 /**
- * Unload the current ROM, if any.
+ * Get all save states for the given rom, or the currently loaded ROM if no ROM passed in, that were quarantined
+ * because they failed to load.
  *
- * Does NOT save the SRAM.
+ * This array must be freed with supershuckie_stringarray_free
  */
-void supershuckie_frontend_unload_rom(struct SuperShuckieFrontendRaw *frontend);
+struct SuperShuckieStringArrayRaw *supershuckie_frontend_get_quarantined_save_states_for_rom(const struct SuperShuckieFrontendRaw *frontend, const char *rom);
 
 /**
- * Load a save save file, automatically saving the current SRAM before switching.
+ * Get why a quarantined save state for the given rom, or the currently loaded ROM if no ROM passed in, failed to load.
  *
- * If initialize is true, the save file will be deleted if it exists.
+ * Returns false if no such save state is quarantined.
  *
  * Safety:
- * - save_name must be null-terminated UTF-8
+ * - name must not be null
+ * - reason must be at least reason_len bytes long
  */
-void supershuckie_frontend_load_or_create_save_file(struct SuperShuckieFrontendRaw *frontend, const char *save_name, bool initialize);
+bool supershuckie_frontend_get_quarantined_save_state_reason(
+    const struct SuperShuckieFrontendRaw *frontend,
+    const char *rom,
+    const char *name,
+    char *reason,
+    size_t reason_len
+);
 
 /**
- * Set the current save file without reloading anything.
+ * Try loading a quarantined save state for the current ROM again, such as after switching cores or changing settings.
+ *
+ * If successful, it is moved back with the other save states, and its name is written to result (which may differ if a
+ * save state of the same name was made since). Otherwise, the error is written to result, and it stays in quarantine.
  *
  * Safety:
- * - save_name must be null-terminated UTF-8
+ * - name must not be null
+ * - result must be at least result_len bytes long
  */
-void supershuckie_frontend_set_current_save_file(struct SuperShuckieFrontendRaw *frontend, const char *save_name);
+bool supershuckie_frontend_retry_quarantined_save_state(struct SuperShuckieFrontendRaw *frontend, const char *name, char *result, size_t result_len);
 
 /**
- * Hard reset the console, simulating switching off/on.
+ * Get all saves for the given rom, or the currently loaded ROM if no ROM passed in.
+ *
+ * This array must be freed with supershuckie_stringarray_free
  */
-void supershuckie_frontend_hard_reset_console(struct SuperShuckieFrontendRaw *frontend);
+struct SuperShuckieStringArrayRaw *supershuckie_frontend_get_all_saves_for_rom(const struct SuperShuckieFrontendRaw *frontend, const char *rom);
 
 /**
- * Should be called regularly.
+ * Copy the control settings.
+ *
+ * This pointer must be freed with supershuckie_control_settings_free to avoid memory leaks.
  */
-void supershuckie_frontend_tick(struct SuperShuckieFrontendRaw *frontend);
+SuperShuckieControlSettingsRaw *supershuckie_frontend_get_control_settings(const struct SuperShuckieFrontendRaw *frontend);
 
 /**
- * Get all replays for the given rom, or the currently loaded ROM if no ROM passed in.
+ * Overwrite the control settings.
+ */
+void supershuckie_frontend_set_control_settings(struct SuperShuckieFrontendRaw *frontend, const SuperShuckieControlSettingsRaw *settings);
+
+/**
+ * Replace the keyboard controls with the default keyboard controls.
  *
- * This array must be freed with supershuckie_stringarray_free
+ * This is done automatically on first run.
  */
-struct SuperShuckieStringArrayRaw *supershuckie_frontend_get_all_replays_for_rom(const struct SuperShuckieFrontendRaw *frontend, const char *rom);
+void supershuckie_frontend_reset_keyboard_controls_to_defaults(struct SuperShuckieFrontendRaw *frontend);
 
 /**
- * Get all save states for the given rom, or the currently loaded ROM if no ROM passed in.
+ * Return the keycode a keyboard control bound to keycode should be bound to instead.
+ */
+typedef int32_t (*SuperShuckieRemapKeycode)(void *userdata, int32_t keycode);
+
+/**
+ * Replace the keycode of every keyboard control, including per-ROM ones, with the one returned by remap.
  *
- * This array must be freed with supershuckie_stringarray_free
+ * This is for migrating keyboard controls when the keycodes passed to supershuckie_frontend_key_press change meaning.
+ * If two keycodes are remapped to the same one, the control bound to the greater keycode is kept, and the user is told
+ * that the others were removed.
+ *
+ * Returns the number of controls removed.
  */
-struct SuperShuckieStringArrayRaw *supershuckie_frontend_get_all_save_states_for_rom(const struct SuperShuckieFrontendRaw *frontend, const char *rom);
+size_t supershuckie_frontend_remap_keyboard_controls(
+    struct SuperShuckieFrontendRaw *frontend,
+    SuperShuckieRemapKeycode remap,
+    void *userdata
+);
 
 /**
- * Get all saves for the given rom, or the currently loaded ROM if no ROM passed in.
+ * Copy the per-ROM control settings for the given rom, or the currently loaded ROM if no ROM passed in.
+ *
+ * Returns null if the ROM has no per-ROM control settings (i.e. it uses the global control settings).
+ *
+ * This pointer must be freed with supershuckie_control_settings_free to avoid memory leaks.
+ */
+SuperShuckieControlSettingsRaw *supershuckie_frontend_get_rom_control_settings(const struct SuperShuckieFrontendRaw *frontend, const char *rom);
+
+/**
+ * Overwrite the per-ROM control settings for the given rom, or the currently loaded ROM if no ROM passed in.
+ *
+ * If settings is null, the ROM will use the global control settings.
+ */
+void supershuckie_frontend_set_rom_control_settings(struct SuperShuckieFrontendRaw *frontend, const char *rom, const SuperShuckieControlSettingsRaw *settings);
+
+/**
+ * Copy the global control settings into the per-ROM control settings for the given rom, or the currently loaded ROM
+ * if no ROM passed in.
+ */
+void supershuckie_frontend_copy_control_settings_to_rom(struct SuperShuckieFrontendRaw *frontend, const char *rom);
+
+/**
+ * Get the number of frames input is delayed by for the given rom, or the currently loaded ROM if no ROM passed in.
+ */
+uint8_t supershuckie_frontend_get_rom_input_delay(const struct SuperShuckieFrontendRaw *frontend, const char *rom);
+
+/**
+ * Set the number of frames input is delayed by for the given rom, or the currently loaded ROM if no ROM passed in.
+ *
+ * If the ROM is currently loaded, this takes effect immediately.
+ */
+void supershuckie_frontend_set_rom_input_delay(struct SuperShuckieFrontendRaw *frontend, const char *rom, uint8_t frames);
+
+/**
+ * Get the GameShark and Game Genie codes saved for the given rom, or the currently loaded ROM if no ROM passed in, in
+ * the order they were added.
  *
  * This array must be freed with supershuckie_stringarray_free
  */
-struct SuperShuckieStringArrayRaw *supershuckie_frontend_get_all_saves_for_rom(const struct SuperShuckieFrontendRaw *frontend, const char *rom);
+struct SuperShuckieStringArrayRaw *supershuckie_frontend_get_rom_cheats(const struct SuperShuckieFrontendRaw *frontend, const char *rom);
 
 /**
- * Copy the control settings.
+ * Get the cheat at index in supershuckie_frontend_get_rom_cheats for the given rom, or the currently loaded ROM if no
+ * ROM passed in.
  *
- * This pointer must be freed with supershuckie_control_settings_free to avoid memory leaks.
+ * The description (which can be empty) is written to description, and whether the cheat is enabled is written to
+ * enabled if non-null.
+ *
+ * Returns false if there is no cheat at index.
+ *
+ * Safety:
+ * - description must point to a buffer of at least `description_len` bytes (it can be null if description_len is 0)
  */
-SuperShuckieControlSettingsRaw *supershuckie_frontend_get_control_settings(const struct SuperShuckieFrontendRaw *frontend);
+bool supershuckie_frontend_get_rom_cheat_info(
+    const struct SuperShuckieFrontendRaw *frontend,
+    const char *rom,
+    size_t index,
+    char *description,
+    size_t description_len,
+    bool *enabled
+);
 
 /**
- * Overwrite the control settings.
+ * Save a GameShark (e.g. "01FF34C1") or Game Genie (e.g. "00A-17B-C49") code for the given rom, or the currently
+ * loaded ROM if no ROM passed in, enabled.
+ *
+ * Enabled cheats are applied every frame while the ROM is running, and they are recorded in replays. If the ROM is
+ * currently loaded, this takes effect immediately, and the code is only saved if it applies to the ROM.
+ *
+ * If false is returned, an error will be written.
+ *
+ * Safety:
+ * - code must be a null-terminated UTF-8 string
+ * - description must be null or a null-terminated UTF-8 string
+ * - error must be at least error_len bytes long
  */
-void supershuckie_frontend_set_control_settings(struct SuperShuckieFrontendRaw *frontend, const SuperShuckieControlSettingsRaw *settings);
+bool supershuckie_frontend_add_rom_cheat(
+    struct SuperShuckieFrontendRaw *frontend,
+    const char *rom,
+    const char *code,
+    const char *description,
+    char *error,
+    size_t error_len
+);
+
+/**
+ * Remove the cheat at index in supershuckie_frontend_get_rom_cheats from the given rom, or the currently loaded ROM if
+ * no ROM passed in.
+ *
+ * Returns false if there is no cheat at index.
+ */
+bool supershuckie_frontend_remove_rom_cheat(struct SuperShuckieFrontendRaw *frontend, const char *rom, size_t index);
+
+/**
+ * Enable or disable the cheat at index in supershuckie_frontend_get_rom_cheats for the given rom, or the currently
+ * loaded ROM if no ROM passed in.
+ *
+ * If the ROM is currently loaded, this takes effect immediately. If false is returned, an error will be written.
+ *
+ * Safety:
+ * - error must be at least error_len bytes long
+ */
+bool supershuckie_frontend_set_rom_cheat_enabled(
+    struct SuperShuckieFrontendRaw *frontend,
+    const char *rom,
+    size_t index,
+    bool enabled,
+    char *error,
+    size_t error_len
+);
 
 /**
  * Get a list of all controllers.
@@ -468,6 +2431,97 @@ void supershuckie_frontend_disconnect_controller(
     SuperShuckieConnectedControllerIndex controller
 );
 
+/**
+ * Set the GUID of the controller at the given index (e.g. from SDL_GUIDToString).
+ *
+ * This is saved with its controls and included in exported control profiles, so profiles can be installed for the same
+ * model even if it reports a different name.
+ *
+ * Safety: The GUID must be a null-terminated UTF-8 string
+ */
+void supershuckie_frontend_set_controller_guid(
+    struct SuperShuckieFrontendRaw *frontend,
+    SuperShuckieConnectedControllerIndex controller,
+    const char *guid
+);
+
+/**
+ * Export controls to a control profile (JSON) file at path so they can be shared.
+ *
+ * If rom is not null, its per-ROM controls are exported instead of the global controls. If controller is not null,
+ * only that controller's controls are exported (and not the keyboard's).
+ *
+ * Safety:
+ * - path and name must not be null
+ * - error must be at least error_len bytes long
+ */
+bool supershuckie_frontend_export_control_profile(
+    const struct SuperShuckieFrontendRaw *frontend,
+    const char *path,
+    const char *rom,
+    const char *controller,
+    const char *name,
+    char *error,
+    size_t error_len
+);
+
+/**
+ * Install the control profile file at path, replacing the controls of each controller in it (and the keyboard controls,
+ * if it has any).
+ *
+ * Controllers are installed for a connected controller with the same GUID, if any, even if its name differs.
+ *
+ * If rom is not null, the profile is installed into that ROM's controls (starting from a copy of the global controls if
+ * it has none) instead of the global controls.
+ *
+ * If successful, the profile's name is written to result. Otherwise, the error is written to result.
+ *
+ * Safety:
+ * - path must not be null
+ * - result must be at least result_len bytes long
+ */
+bool supershuckie_frontend_install_control_profile(
+    struct SuperShuckieFrontendRaw *frontend,
+    const char *path,
+    const char *rom,
+    char *result,
+    size_t result_len
+);
+
+/**
+ * Export the loaded ROM's shareable user data (its own controls, input delay, and game profile) to a game bundle (JSON)
+ * file at path, along with notes. The ROM itself and save data are not included.
+ *
+ * Safety:
+ * - path must not be null
+ * - notes can be null (no notes)
+ * - error must be at least error_len bytes long
+ */
+bool supershuckie_frontend_export_game_bundle(
+    const struct SuperShuckieFrontendRaw *frontend,
+    const char *path,
+    const char *notes,
+    char *error,
+    size_t error_len
+);
+
+/**
+ * Import the game bundle file at path into the loaded ROM, replacing its controls and input delay, and installing its
+ * game profile, if any. The bundle must have been exported from the same ROM.
+ *
+ * If successful, the bundle's notes are written to result. Otherwise, the error is written to result.
+ *
+ * Safety:
+ * - path must not be null
+ * - result must be at least result_len bytes long
+ */
+bool supershuckie_frontend_import_game_bundle(
+    struct SuperShuckieFrontendRaw *frontend,
+    const char *path,
+    char *result,
+    size_t result_len
+);
+
 /**
  * Get the name of the controller, returning null if the index is invalid.
  */
@@ -485,7 +2539,7 @@ enum SuperShuckieReplayState supershuckie_frontend_get_replay_state(const struct
  * Free the core
  *
  * Safety:
- * - frontend must either be created with supershuckie_frontend_new OR it can be null
+ * - frontend must either be created with supershuckie_frontend_new (or supershuckie_frontend_new_in_memory) OR it can be null
  * - frontend, if non-null, may only be freed once
  */
 void supershuckie_frontend_free(struct SuperShuckieFrontendRaw *frontend);
@@ -0,0 +1,65 @@
+#ifndef __SUPERSHUCKIE_REPLAY_INFO_ARRAY_H_
+#define __SUPERSHUCKIE_REPLAY_INFO_ARRAY_H_
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#include <stdlib.h>
+#include <stdint.h>
+
+/**
+ * An array holding zero or more replay files, each with a null-terminated UTF8 name and details read from the file.
+ *
+ * This can never be null EXCEPT in the supershuckie_replayinfoarray_free function (which this array must be freed in
+ * if retrieved from SuperShuckie).
+ */
+struct SuperShuckieReplayInfoArrayRaw;
+
+enum SuperShuckieReplayCompatibility {
+    SuperShuckieReplayCompatibility__Unknown = 0,
+    SuperShuckieReplayCompatibility__Compatible = 1,
+    SuperShuckieReplayCompatibility__Mismatched = 2,
+    SuperShuckieReplayCompatibility__Incompatible = 3,
+    SuperShuckieReplayCompatibility__Unreadable = 4
+};
+
+/**
+ * Get the length of a replay info array.
+ */
+size_t supershuckie_replayinfoarray_len(const struct SuperShuckieReplayInfoArrayRaw *array);
+
+/**
+ * Get the name of the replay at the given position in the array, or null if out-of-bounds.
+ *
+ * The remaining parameters, if non-null and in-bounds, will be written their respective values:
+ * - created is in seconds since the Unix epoch, or 0 if unknown
+ * - console_type is the replay's console (0 if unknown or unreadable)
+ * - compatibility is a SuperShuckieReplayCompatibility
+ * - mismatches is a mask of SuperShuckieReplayMismatch values that have not been remembered as acknowledged
+ */
+const char *supershuckie_replayinfoarray_get(
+    const struct SuperShuckieReplayInfoArrayRaw *array,
+    size_t position,
+    uint32_t *total_frames,
+    uint32_t *total_milliseconds,
+    uint64_t *size,
+    uint64_t *created,
+    uint32_t *console_type,
+    uint32_t *compatibility,
+    uint32_t *mismatches
+);
+
+/**
+ * Free the replay info array.
+ *
+ * Safety:
+ * - A pointer may only be freed once (unless the pointer is null)
+ */
+void supershuckie_replayinfoarray_free(struct SuperShuckieReplayInfoArrayRaw *array);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
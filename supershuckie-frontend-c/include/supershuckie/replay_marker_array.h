@@ -0,0 +1,49 @@
+#ifndef __SUPERSHUCKIE_REPLAY_MARKER_ARRAY_H_
+#define __SUPERSHUCKIE_REPLAY_MARKER_ARRAY_H_
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#include <stdlib.h>
+#include <stdint.h>
+
+/**
+ * An array holding zero or more replay markers (keyframes or bookmarks), each with a frame, a timestamp, and a
+ * null-terminated UTF8 name.
+ *
+ * This can never be null EXCEPT in the supershuckie_replaymarkerarray_free function (which this array must be freed in
+ * if retrieved from SuperShuckie).
+ */
+struct SuperShuckieReplayMarkerArrayRaw;
+
+/**
+ * Get the length of a replay marker array.
+ */
+size_t supershuckie_replaymarkerarray_len(const struct SuperShuckieReplayMarkerArrayRaw *array);
+
+/**
+ * Get the name of the marker at the given position in the array, or null if out-of-bounds.
+ *
+ * elapsed_frames and elapsed_milliseconds, if non-null and in-bounds, will be written their respective values.
+ */
+const char *supershuckie_replaymarkerarray_get(
+    const struct SuperShuckieReplayMarkerArrayRaw *array,
+    size_t position,
+    uint32_t *elapsed_frames,
+    uint32_t *elapsed_milliseconds
+);
+
+/**
+ * Free the replay marker array.
+ *
+ * Safety:
+ * - A pointer may only be freed once (unless the pointer is null)
+ */
+void supershuckie_replaymarkerarray_free(struct SuperShuckieReplayMarkerArrayRaw *array);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
@@ -2,7 +2,11 @@
 #define __SUPERSHUCKIE_H__
 
 #include "string_array.h"
+#include "cancellation_token.h"
+#include "replay_marker_array.h"
+#include "replay_info_array.h"
 #include "frontend.h"
 #include "control_settings.h"
+#include "visualizer.h"
 
 #endif
@@ -0,0 +1,108 @@
+#ifndef __SUPERSHUCKIE_VISUALIZER_H_
+#define __SUPERSHUCKIE_VISUALIZER_H_
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#include <stdlib.h>
+#include <stdint.h>
+#include <stdbool.h>
+
+#include "frontend.h"
+
+/**
+ * Version of the visualizer API described in this header.
+ *
+ * This is only changed if SuperShuckieVisualizer or SuperShuckieVisualizeCallback change in an incompatible way.
+ */
+#define SUPERSHUCKIE_VISUALIZER_API_VERSION 1
+
+/**
+ * A range of memory to read every frame.
+ *
+ * The address is interpreted the same way as Poke-A-Byte addresses for the current core.
+ */
+struct SuperShuckieWatchedMemoryRange {
+    uint32_t address;
+    size_t length;
+};
+
+/**
+ * Draw an overlay for a finished frame, returning false if nothing was drawn.
+ *
+ * - screen_data and pixels each have screen_count elements, one per screen.
+ * - memory has memory_count elements, one per watched memory range (in the order they were given), each with the
+ *   length of that range. Ranges that could not be read are zeroed.
+ * - overlays has screen_count elements, each the same size and encoding as its screen and cleared to fully
+ *   transparent. Anything drawn to them is alpha blended onto the screens.
+ *
+ * None of these pointers are valid after the callback returns.
+ */
+typedef bool (*SuperShuckieVisualizeCallback)(
+    void *user_data,
+    size_t screen_count,
+    const struct SuperShuckieScreenData *screen_data,
+    const uint32_t *const *pixels,
+    size_t memory_count,
+    const uint8_t *const *memory,
+    uint32_t *const *overlays
+);
+
+/**
+ * Free a visualizer's user data when it is removed (or the frontend is freed).
+ */
+typedef void (*SuperShuckieVisualizerFreeCallback)(void *user_data);
+
+/**
+ * A visualizer, such as a map viewer, hitbox overlay, or stat HUD, that draws over the screens every frame.
+ */
+struct SuperShuckieVisualizer {
+    /**
+     * Must be SUPERSHUCKIE_VISUALIZER_API_VERSION
+     */
+    uint32_t api_version;
+
+    void *user_data;
+
+    /**
+     * Memory to read every frame; this is copied when the visualizer is added
+     */
+    const struct SuperShuckieWatchedMemoryRange *watched_memory;
+    size_t watched_memory_count;
+
+    /**
+     * Must be non-null
+     */
+    SuperShuckieVisualizeCallback visualize;
+
+    /**
+     * Optional
+     */
+    SuperShuckieVisualizerFreeCallback free;
+};
+
+/**
+ * Add a visualizer, returning its ID, or 0 if its API version is unsupported or visualize is null.
+ *
+ * Visualizers are called from supershuckie_frontend_tick, in the order they were added, before the refresh screens
+ * callback is called with the composited screens.
+ *
+ * Safety:
+ * - visualizer->watched_memory must point to at least visualizer->watched_memory_count ranges
+ */
+uint32_t supershuckie_frontend_add_visualizer(
+    struct SuperShuckieFrontendRaw *frontend,
+    const struct SuperShuckieVisualizer *visualizer
+);
+
+/**
+ * Remove a visualizer, freeing its user data, and return true if it was removed.
+ */
+bool supershuckie_frontend_remove_visualizer(struct SuperShuckieFrontendRaw *frontend, uint32_t id);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
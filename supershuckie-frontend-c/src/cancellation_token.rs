@@ -0,0 +1,42 @@
+use supershuckie_core::CancellationToken;
+use crate::ffi::ffi_guard;
+
+#[repr(transparent)]
+#[derive(Default)]
+pub struct SuperShuckieCancellationToken(pub CancellationToken);
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_cancellationtoken_new() -> *mut SuperShuckieCancellationToken {
+    ffi_guard("supershuckie_cancellationtoken_new", || {
+        Box::into_raw(Box::new(SuperShuckieCancellationToken::default()))
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_cancellationtoken_cancel(
+    token: &SuperShuckieCancellationToken
+) {
+    ffi_guard("supershuckie_cancellationtoken_cancel", || {
+        token.0.cancel()
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_cancellationtoken_is_cancelled(
+    token: &SuperShuckieCancellationToken
+) -> bool {
+    ffi_guard("supershuckie_cancellationtoken_is_cancelled", || {
+        token.0.is_cancelled()
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_cancellationtoken_free(
+    token: *mut SuperShuckieCancellationToken
+) {
+    ffi_guard("supershuckie_cancellationtoken_free", || {
+        if !token.is_null() {
+            let _ = unsafe { Box::from_raw(token) };
+        }
+    })
+}
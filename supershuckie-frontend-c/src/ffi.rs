@@ -0,0 +1,200 @@
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::slice::from_raw_parts_mut;
+use supershuckie_frontend::locale::LocalizedError;
+use supershuckie_frontend::SuperShuckieFrontend;
+use supershuckie_frontend::util::UTF8CString;
+use supershuckie_frontend::settings::{ColorCorrection, GameBoyMode, LoadStateRecordMode, PokeAByteFrameSkip, PokeAByteTransportKind, PowerSaving};
+use crate::frontend::{write_str_to_data, SuperShuckieReplayState};
+use crate::string_array::SuperShuckieStringArray;
+
+/// Kind of error recorded by the last function call that failed at the C boundary.
+#[repr(u32)]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum SuperShuckieErrorCode {
+    /// No error was recorded
+    None = 0,
+
+    /// The function panicked, so it returned a fallback value (false, null, zero, etc.) instead
+    Panic = 1,
+
+    /// The frontend returned an error, which has a message key and arguments
+    Frontend = 2
+}
+
+/// What happens when a function panics at the C boundary.
+#[repr(u32)]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum SuperShuckiePanicBehavior {
+    /// Record the error (see `supershuckie_get_last_error`) and return a fallback value
+    Recover = 0,
+
+    /// Abort the process
+    Abort = 1
+}
+
+static PANIC_BEHAVIOR: AtomicU32 = AtomicU32::new(SuperShuckiePanicBehavior::Recover as u32);
+
+/// Error recorded for `supershuckie_get_last_error`.
+struct LastError {
+    code: SuperShuckieErrorCode,
+    message: String,
+    localized: Option<LocalizedError>
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<LastError>> = const { RefCell::new(None) };
+}
+
+/// Value returned from a C function if it panics.
+pub(crate) trait FfiFallback {
+    fn ffi_fallback() -> Self;
+}
+
+macro_rules! ffi_fallback_default {
+    ($($t:ty),*) => {
+        $(impl FfiFallback for $t {
+            fn ffi_fallback() -> Self {
+                Default::default()
+            }
+        })*
+    };
+}
+
+ffi_fallback_default!((), bool, u8, u32, u64, usize, i32, f64, ColorCorrection, GameBoyMode, LoadStateRecordMode, PokeAByteFrameSkip, PokeAByteTransportKind, PowerSaving);
+
+impl<T> FfiFallback for *const T {
+    fn ffi_fallback() -> Self {
+        std::ptr::null()
+    }
+}
+
+impl<T> FfiFallback for *mut T {
+    fn ffi_fallback() -> Self {
+        std::ptr::null_mut()
+    }
+}
+
+impl FfiFallback for SuperShuckieReplayState {
+    fn ffi_fallback() -> Self {
+        SuperShuckieReplayState::NoReplay
+    }
+}
+
+/// Run the body of an exported function, catching any panic so it doesn't unwind into C.
+///
+/// If it panics, the error is recorded for `supershuckie_get_last_error` and `R::ffi_fallback()`
+/// is returned, unless [`SuperShuckiePanicBehavior::Abort`] is set.
+pub(crate) fn ffi_guard<R: FfiFallback>(function: &'static str, f: impl FnOnce() -> R) -> R {
+    let payload = match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(result) => return result,
+        Err(payload) => payload
+    };
+
+    let message = payload.downcast_ref::<&str>().copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown panic");
+
+    if PANIC_BEHAVIOR.load(Ordering::Relaxed) == SuperShuckiePanicBehavior::Abort as u32 {
+        eprintln!("{function} panicked: {message}");
+        std::process::abort();
+    }
+
+    let error = LastError {
+        code: SuperShuckieErrorCode::Panic,
+        message: format!("{function} panicked: {message}"),
+        localized: None
+    };
+    LAST_ERROR.with(|e| *e.borrow_mut() = Some(error));
+    R::ffi_fallback()
+}
+
+/// Write an error from the frontend to `buffer` in the frontend's language, and record it for
+/// `supershuckie_get_last_error` so its key and arguments can be retrieved.
+pub(crate) fn write_frontend_error(frontend: &SuperShuckieFrontend, error: &LocalizedError, buffer: &mut [u8]) {
+    let message = error.format(frontend.get_messages());
+    write_str_to_data(&message, buffer);
+
+    let error = LastError {
+        code: SuperShuckieErrorCode::Frontend,
+        message,
+        localized: Some(error.clone())
+    };
+    LAST_ERROR.with(|e| *e.borrow_mut() = Some(error));
+}
+
+/// Get the names or values of the arguments of the last frontend error, or null if there is none.
+fn last_error_arguments(f: impl Fn(&(&'static str, String)) -> UTF8CString) -> *mut SuperShuckieStringArray {
+    LAST_ERROR.with(|e| {
+        let e = e.borrow();
+        let Some(error) = e.as_ref().and_then(|e| e.localized.as_ref()) else {
+            return std::ptr::null_mut()
+        };
+        let arguments = error.args().iter().map(f).collect();
+        Box::into_raw(Box::new(SuperShuckieStringArray(arguments)))
+    })
+}
+
+/// Read a C string, replacing any invalid UTF-8 instead of failing.
+///
+/// # Safety
+///
+/// `string` must be a valid null-terminated string that outlives `'a`.
+pub(crate) unsafe fn c_str_lossy<'a>(string: *const c_char) -> Cow<'a, str> {
+    unsafe { CStr::from_ptr(string) }.to_string_lossy()
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_set_panic_behavior(behavior: SuperShuckiePanicBehavior) {
+    PANIC_BEHAVIOR.store(behavior as u32, Ordering::Relaxed);
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_get_last_error(message: *mut u8, message_len: usize) -> SuperShuckieErrorCode {
+    LAST_ERROR.with(|e| {
+        let e = e.borrow();
+        let (code, error) = e.as_ref().map(|e| (e.code, e.message.as_str())).unwrap_or((SuperShuckieErrorCode::None, ""));
+        if message_len > 0 {
+            write_str_to_data(error, unsafe { from_raw_parts_mut(message, message_len) });
+        }
+        code
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_get_last_error_key(key: *mut u8, key_len: usize) -> bool {
+    ffi_guard("supershuckie_get_last_error_key", || {
+        LAST_ERROR.with(|e| {
+            let e = e.borrow();
+            let Some(error) = e.as_ref().and_then(|e| e.localized.as_ref()) else {
+                return false
+            };
+            if key_len > 0 {
+                write_str_to_data(error.key(), unsafe { from_raw_parts_mut(key, key_len) });
+            }
+            true
+        })
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_get_last_error_argument_names() -> *mut SuperShuckieStringArray {
+    ffi_guard("supershuckie_get_last_error_argument_names", || {
+        last_error_arguments(|(name, _)| (*name).into())
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_get_last_error_argument_values() -> *mut SuperShuckieStringArray {
+    ffi_guard("supershuckie_get_last_error_argument_values", || {
+        last_error_arguments(|(_, value)| value.as_str().into())
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_clear_last_error() {
+    LAST_ERROR.with(|e| *e.borrow_mut() = None);
+}
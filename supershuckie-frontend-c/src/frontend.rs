@@ -1,14 +1,25 @@
+use std::borrow::Cow;
 use std::ffi::{c_char, c_void, CStr};
 use std::mem::MaybeUninit;
-use std::num::NonZeroU8;
-use std::ptr::null;
-use std::slice::from_raw_parts_mut;
-use supershuckie_core::emulator::{ScreenData, ScreenDataEncoding};
-use supershuckie_frontend::{ConnectedControllerIndex, SuperShuckieFrontend, SuperShuckieFrontendCallbacks, UserInput};
-use supershuckie_frontend::settings::GameBoyMode;
+use std::num::{NonZeroU32, NonZeroU64, NonZeroU8};
+use std::path::Path;
+use std::ptr::{null, null_mut};
+use std::slice::{from_raw_parts, from_raw_parts_mut};
+use supershuckie_core::{CoreProgressEvent, Speed};
+use supershuckie_core::emulator::{InputDescriptor, ScreenData, ScreenDataEncoding};
+use supershuckie_frontend::{ConnectedControllerIndex, FrontendEvent, SuperShuckieFrontend, SuperShuckieFrontendCallbacks, UserInput};
+use supershuckie_frontend::settings::{ColorCorrection, Control, GameBoyMode, LoadStateRecordMode, PhysicalKey, PokeAByteFrameSkip, PokeAByteTransportKind, PowerSaving, ReplayMismatchKind, StorageCategory};
+use supershuckie_frontend::replay_browser::{ReplayBrowserQuery, ReplaySort};
+use supershuckie_frontend::locale::LocalizedError;
+use supershuckie_frontend::serial_test::TestSuiteReport;
+use supershuckie_frontend::file_store::{DiskFileStore, MemoryFileStore};
 use supershuckie_frontend::util::UTF8CString;
 use crate::control_settings::SuperShuckieControlSettings;
 use crate::string_array::SuperShuckieStringArray;
+use crate::replay_marker_array::SuperShuckieReplayMarkerArray;
+use crate::replay_info_array::SuperShuckieReplayInfoArray;
+use crate::cancellation_token::SuperShuckieCancellationToken;
+use crate::ffi::{c_str_lossy, ffi_guard, write_frontend_error};
 
 #[repr(C)]
 #[derive(Copy, Clone, Debug)]
@@ -25,6 +36,13 @@ pub struct SuperShuckieFrontendCallbacksC {
 
     pub refresh_screens: Option<unsafe extern "C" fn(userdata: *mut c_void, screen_count: usize, screen_data: *const *const u32)>,
     pub change_video_mode: Option<unsafe extern "C" fn(userdata: *mut c_void, screen_count: usize, screen_data: *const SuperShuckieScreenDataC, screen_scale: NonZeroU8)>,
+    pub keycode_for_physical_key: Option<unsafe extern "C" fn(userdata: *mut c_void, physical_key: u32, keycode: &mut i32) -> bool>,
+    pub show_message: Option<unsafe extern "C" fn(userdata: *mut c_void, message: *const c_char)>,
+    pub set_rumble: Option<unsafe extern "C" fn(userdata: *mut c_void, amplitude: f64)>,
+    pub set_infrared_led: Option<unsafe extern "C" fn(userdata: *mut c_void, on: bool)>,
+    pub report_progress: Option<unsafe extern "C" fn(userdata: *mut c_void, operation_id: u32, operation: u32, stage: u32, percent: u8)>,
+    pub ui_navigation: Option<unsafe extern "C" fn(userdata: *mut c_void, controller: ConnectedControllerIndex, button: i32, pressed: bool)>,
+    pub push_audio: Option<unsafe extern "C" fn(userdata: *mut c_void, samples: *const i16, sample_count: usize, sample_rate: u32)>,
 }
 
 impl SuperShuckieFrontendCallbacks for SuperShuckieFrontendCallbacksC {
@@ -53,6 +71,118 @@ impl SuperShuckieFrontendCallbacks for SuperShuckieFrontendCallbacksC {
 
         unsafe { s(self.userdata, screens.len(), screens_buf.as_ptr() as *const SuperShuckieScreenDataC, scaling) };
     }
+
+    fn keycode_for_physical_key(&mut self, key: PhysicalKey) -> Option<i32> {
+        let s = self.keycode_for_physical_key?;
+
+        let mut keycode = 0;
+        unsafe { s(self.userdata, key as u32, &mut keycode) }.then_some(keycode)
+    }
+
+    fn show_message(&mut self, message: &str) {
+        let Some(s) = self.show_message else { return };
+
+        let message = UTF8CString::from_str(message);
+        unsafe { s(self.userdata, message.as_c_str().as_ptr()) };
+    }
+
+    fn set_rumble(&mut self, amplitude: f64) {
+        let Some(s) = self.set_rumble else { return };
+        unsafe { s(self.userdata, amplitude) };
+    }
+
+    fn set_infrared_led(&mut self, on: bool) {
+        let Some(s) = self.set_infrared_led else { return };
+        unsafe { s(self.userdata, on) };
+    }
+
+    fn report_progress(&mut self, event: CoreProgressEvent) {
+        let Some(s) = self.report_progress else { return };
+        unsafe { s(self.userdata, event.id, event.operation as u32, event.stage as u32, event.percent) };
+    }
+
+    fn ui_navigation(&mut self, controller: ConnectedControllerIndex, button: i32, pressed: bool) {
+        let Some(s) = self.ui_navigation else { return };
+        unsafe { s(self.userdata, controller, button, pressed) };
+    }
+
+    fn push_audio(&mut self, samples: &[i16], sample_rate: u32) {
+        let Some(s) = self.push_audio else { return };
+        unsafe { s(self.userdata, samples.as_ptr(), samples.len(), sample_rate) };
+    }
+}
+
+/// Length of SuperShuckieEventC::message, including the null terminator.
+const EVENT_MESSAGE_LENGTH: usize = 256;
+
+/// Length of SuperShuckieEventC::key, including the null terminator.
+const EVENT_KEY_LENGTH: usize = 64;
+
+#[repr(u32)]
+#[derive(Copy, Clone, Debug)]
+pub enum SuperShuckieEventType {
+    FrameReady = 0,
+    Error = 1,
+    PlaybackFinished = 2,
+    PokeAByteStatus = 3,
+    Message = 4,
+    RomLoaded = 5,
+    Reset = 6,
+    StateLoaded = 7,
+    ReplayStarted = 8,
+    ReplayEnded = 9,
+    SramSaved = 10,
+    StorageChanged = 11
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct SuperShuckieFrameReadyEventC {
+    pub frame: u32
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct SuperShuckiePokeAByteStatusEventC {
+    pub enabled: bool
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct SuperShuckieResetEventC {
+    pub hard: bool
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct SuperShuckieReplayEventC {
+    pub recording: bool
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct SuperShuckieStorageChangedEventC {
+    pub category: u32,
+    pub added: bool
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub union SuperShuckieEventDataC {
+    pub frame_ready: SuperShuckieFrameReadyEventC,
+    pub pokeabyte_status: SuperShuckiePokeAByteStatusEventC,
+    pub reset: SuperShuckieResetEventC,
+    pub replay: SuperShuckieReplayEventC,
+    pub storage_changed: SuperShuckieStorageChangedEventC
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct SuperShuckieEventC {
+    pub event_type: SuperShuckieEventType,
+    pub data: SuperShuckieEventDataC,
+    pub message: [u8; EVENT_MESSAGE_LENGTH],
+    pub key: [u8; EVENT_KEY_LENGTH]
 }
 
 #[unsafe(no_mangle)]
@@ -60,11 +190,56 @@ pub unsafe extern "C" fn supershuckie_frontend_new(
     user_dir: *const c_char,
     callbacks: &SuperShuckieFrontendCallbacksC
 ) -> *mut SuperShuckieFrontend {
-    let user_dir = unsafe { CStr::from_ptr(user_dir) }
-        .to_str()
-        .expect("path is not UTF-8");
+    ffi_guard("supershuckie_frontend_new", || {
+        let user_dir = &*unsafe { c_str_lossy(user_dir) };
+
+        Box::into_raw(Box::new(SuperShuckieFrontend::new(user_dir, Box::new(*callbacks))))
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_new_in_memory(
+    user_dir: *const c_char,
+    callbacks: &SuperShuckieFrontendCallbacksC
+) -> *mut SuperShuckieFrontend {
+    ffi_guard("supershuckie_frontend_new_in_memory", || {
+        let user_dir = &*unsafe { c_str_lossy(user_dir) };
+
+        Box::into_raw(Box::new(SuperShuckieFrontend::new_with_file_store(user_dir, Box::new(*callbacks), Box::new(MemoryFileStore::new()))))
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_forward_to_running_instance(
+    user_dir: *const c_char,
+    rom_path: *const c_char
+) -> bool {
+    ffi_guard("supershuckie_frontend_forward_to_running_instance", || {
+        let user_dir = &*unsafe { c_str_lossy(user_dir) };
+
+        let rom_path = if rom_path.is_null() {
+            None
+        }
+        else {
+            Some(&*unsafe { c_str_lossy(rom_path) })
+        };
+
+        supershuckie_frontend::instance::forward_to_running_instance(&DiskFileStore, user_dir.as_ref(), rom_path.map(|p| p.as_ref()))
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_set_ui_navigation_captured(frontend: &mut SuperShuckieFrontend, captured: bool) {
+    ffi_guard("supershuckie_frontend_set_ui_navigation_captured", || {
+        frontend.set_ui_navigation_captured(captured);
+    })
+}
 
-    Box::into_raw(Box::new(SuperShuckieFrontend::new(user_dir, Box::new(*callbacks))))
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_is_ui_navigation_captured(frontend: &SuperShuckieFrontend) -> bool {
+    ffi_guard("supershuckie_frontend_is_ui_navigation_captured", || {
+        frontend.is_ui_navigation_captured()
+    })
 }
 
 #[unsafe(no_mangle)]
@@ -73,7 +248,9 @@ pub unsafe extern "C" fn supershuckie_frontend_key_press(
     keycode: i32,
     pressed: bool
 ) {
-    frontend.on_user_input(UserInput::Keyboard { keycode }, pressed.then_some(1.0).unwrap_or(0.0));
+    ffi_guard("supershuckie_frontend_key_press", || {
+        frontend.on_user_input(UserInput::Keyboard { keycode }, pressed.then_some(1.0).unwrap_or(0.0));
+    })
 }
 
 #[unsafe(no_mangle)]
@@ -83,7 +260,9 @@ pub unsafe extern "C" fn supershuckie_frontend_button_press(
     button: i32,
     pressed: bool
 ) {
-    frontend.on_user_input(UserInput::Button { controller, button }, pressed.then_some(1.0).unwrap_or(0.0));
+    ffi_guard("supershuckie_frontend_button_press", || {
+        frontend.on_user_input(UserInput::Button { controller, button }, pressed.then_some(1.0).unwrap_or(0.0));
+    })
 }
 
 #[unsafe(no_mangle)]
@@ -93,7 +272,9 @@ pub unsafe extern "C" fn supershuckie_frontend_axis(
     axis: i32,
     value: f64
 ) {
-    frontend.on_user_input(UserInput::Axis { controller, axis }, value);
+    ffi_guard("supershuckie_frontend_axis", || {
+        frontend.on_user_input(UserInput::Axis { controller, axis }, value);
+    })
 }
 
 #[unsafe(no_mangle)]
@@ -101,14 +282,174 @@ pub unsafe extern "C" fn supershuckie_frontend_set_paused(
     frontend: &mut SuperShuckieFrontend,
     paused: bool
 ) {
-    frontend.set_paused(paused);
+    ffi_guard("supershuckie_frontend_set_paused", || {
+        frontend.set_paused(paused);
+    })
 }
 
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn supershuckie_frontend_tick(
     frontend: &mut SuperShuckieFrontend
 ) {
-    frontend.tick();
+    ffi_guard("supershuckie_frontend_tick", || {
+        frontend.tick();
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_set_event_polling_enabled(
+    frontend: &mut SuperShuckieFrontend,
+    enabled: bool
+) {
+    ffi_guard("supershuckie_frontend_set_event_polling_enabled", || {
+        frontend.set_event_polling_enabled(enabled);
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_poll_event(
+    frontend: &mut SuperShuckieFrontend,
+    event: &mut SuperShuckieEventC
+) -> bool {
+    ffi_guard("supershuckie_frontend_poll_event", || {
+        let Some(polled) = frontend.poll_event() else {
+            return false
+        };
+
+        write_event(&polled, event);
+        true
+    })
+}
+
+pub type SuperShuckieEventHookC = unsafe extern "C" fn(userdata: *mut c_void, event: &SuperShuckieEventC);
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_add_event_hook(
+    frontend: &mut SuperShuckieFrontend,
+    event_mask: u32,
+    hook: Option<SuperShuckieEventHookC>,
+    userdata: *mut c_void
+) -> u32 {
+    ffi_guard("supershuckie_frontend_add_event_hook", || {
+        let Some(hook) = hook else {
+            return 0
+        };
+
+        let mut event = SuperShuckieEventC {
+            event_type: SuperShuckieEventType::FrameReady,
+            data: SuperShuckieEventDataC { frame_ready: SuperShuckieFrameReadyEventC { frame: 0 } },
+            message: [0; EVENT_MESSAGE_LENGTH],
+            key: [0; EVENT_KEY_LENGTH]
+        };
+
+        frontend.add_event_hook(Box::new(move |e| {
+            write_event(e, &mut event);
+            if event_mask & (1 << event.event_type as u32) != 0 {
+                unsafe { hook(userdata, &event) };
+            }
+        }))
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_remove_event_hook(
+    frontend: &mut SuperShuckieFrontend,
+    id: u32
+) -> bool {
+    ffi_guard("supershuckie_frontend_remove_event_hook", || {
+        frontend.remove_event_hook(id)
+    })
+}
+
+fn write_event(polled: &FrontendEvent, event: &mut SuperShuckieEventC) {
+    // Events without data leave it zeroed
+    let mut data = SuperShuckieEventDataC { frame_ready: SuperShuckieFrameReadyEventC { frame: 0 } };
+    let mut message = "";
+    let mut key = "";
+    let event_type = match polled {
+        FrontendEvent::FrameReady { frame } => {
+            data.frame_ready = SuperShuckieFrameReadyEventC { frame: *frame };
+            SuperShuckieEventType::FrameReady
+        },
+        FrontendEvent::Error { key: k, message: m } => {
+            key = k;
+            message = m;
+            SuperShuckieEventType::Error
+        },
+        FrontendEvent::PlaybackFinished => SuperShuckieEventType::PlaybackFinished,
+        FrontendEvent::PokeAByteStatus { enabled, address, error } => {
+            data.pokeabyte_status = SuperShuckiePokeAByteStatusEventC { enabled: *enabled };
+            message = error.as_deref().or(address.as_deref()).unwrap_or_default();
+            SuperShuckieEventType::PokeAByteStatus
+        },
+        FrontendEvent::Message { key: k, message: m } => {
+            key = k;
+            message = m;
+            SuperShuckieEventType::Message
+        },
+        FrontendEvent::RomLoaded { rom_name } => {
+            message = rom_name;
+            SuperShuckieEventType::RomLoaded
+        },
+        FrontendEvent::Reset { hard } => {
+            data.reset = SuperShuckieResetEventC { hard: *hard };
+            SuperShuckieEventType::Reset
+        },
+        FrontendEvent::StateLoaded { name } => {
+            message = name.as_deref().unwrap_or_default();
+            SuperShuckieEventType::StateLoaded
+        },
+        FrontendEvent::ReplayStarted { name, recording } => {
+            data.replay = SuperShuckieReplayEventC { recording: *recording };
+            message = name;
+            SuperShuckieEventType::ReplayStarted
+        },
+        FrontendEvent::ReplayEnded { recording } => {
+            data.replay = SuperShuckieReplayEventC { recording: *recording };
+            SuperShuckieEventType::ReplayEnded
+        },
+        FrontendEvent::SramSaved => SuperShuckieEventType::SramSaved,
+        FrontendEvent::StorageChanged { category, name, added } => {
+            data.storage_changed = SuperShuckieStorageChangedEventC { category: *category as u32, added: *added };
+            message = name;
+            SuperShuckieEventType::StorageChanged
+        }
+    };
+
+    event.event_type = event_type;
+    event.data = data;
+    write_str_to_data(message, &mut event.message);
+    write_str_to_data(key, &mut event.key);
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_read_screen(
+    frontend: &SuperShuckieFrontend,
+    screen: usize,
+    width: *mut u32,
+    height: *mut u32,
+    pixels: *mut u32,
+    pixel_count: usize
+) -> bool {
+    ffi_guard("supershuckie_frontend_read_screen", || {
+        let width = unsafe { nullable_reference!(width) };
+        let height = unsafe { nullable_reference!(height) };
+
+        frontend.read_screens(|screens| {
+            let Some(screen) = screens.get(screen) else {
+                return false
+            };
+
+            *width = screen.width as u32;
+            *height = screen.height as u32;
+
+            if pixels.is_null() || pixel_count < screen.pixels.len() {
+                return false
+            }
+            unsafe { from_raw_parts_mut(pixels, screen.pixels.len()) }.copy_from_slice(screen.pixels.as_slice());
+            true
+        })
+    })
 }
 
 #[unsafe(no_mangle)]
@@ -118,28 +459,94 @@ pub unsafe extern "C" fn supershuckie_frontend_load_rom(
     error: *mut u8,
     error_len: usize
 ) -> bool {
-    let path = unsafe { CStr::from_ptr(path) };
-    if error_len > 0 && let Err(e) = frontend.load_rom(path.to_str().expect("supershuckie_frontend_load_rom with non-UTF-8 path")) {
-        write_str_to_data(e.as_str(), unsafe { from_raw_parts_mut(error, error_len) });
-        false
-    }
-    else {
-        true
-    }
+    ffi_guard("supershuckie_frontend_load_rom", || {
+        if error_len > 0 && let Err(e) = frontend.load_rom(&*unsafe { c_str_lossy(path) }) {
+            write_frontend_error(frontend, &e, unsafe { from_raw_parts_mut(error, error_len) });
+            false
+        }
+        else {
+            true
+        }
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_get_roms_in_archive(
+    frontend: &SuperShuckieFrontend,
+    path: *const c_char,
+    error: *mut u8,
+    error_len: usize
+) -> *mut SuperShuckieStringArray {
+    ffi_guard("supershuckie_frontend_get_roms_in_archive", || {
+        match frontend.get_roms_in_archive(&*unsafe { c_str_lossy(path) }) {
+            Ok(roms) => Box::into_raw(Box::new(SuperShuckieStringArray(roms))),
+            Err(e) => {
+                write_frontend_error(frontend, &e, unsafe { from_raw_parts_mut(error, error_len) });
+                null_mut()
+            }
+        }
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_load_rom_from_archive(
+    frontend: &mut SuperShuckieFrontend,
+    path: *const c_char,
+    entry: *const c_char,
+    error: *mut u8,
+    error_len: usize
+) -> bool {
+    ffi_guard("supershuckie_frontend_load_rom_from_archive", || {
+        let path = &*unsafe { c_str_lossy(path) };
+        let entry = &*unsafe { c_str_lossy(entry) };
+        match frontend.load_rom_from_archive(path, entry) {
+            Ok(()) => true,
+            Err(e) => {
+                write_frontend_error(frontend, &e, unsafe { from_raw_parts_mut(error, error_len) });
+                false
+            }
+        }
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_write_file(
+    frontend: &SuperShuckieFrontend,
+    path: *const c_char,
+    data: *const u8,
+    data_len: usize,
+    error: *mut u8,
+    error_len: usize
+) -> bool {
+    ffi_guard("supershuckie_frontend_write_file", || {
+        let path = &*unsafe { c_str_lossy(path) };
+        let data = if data_len == 0 { &[][..] } else { unsafe { from_raw_parts(data, data_len) } };
+        match frontend.write_file(Path::new(path), data) {
+            Ok(()) => true,
+            Err(e) => {
+                write_frontend_error(frontend, &e, unsafe { from_raw_parts_mut(error, error_len) });
+                false
+            }
+        }
+    })
 }
 
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn supershuckie_frontend_close_rom(
     frontend: &mut SuperShuckieFrontend
 ) {
-    let _ = frontend.close_rom();
+    ffi_guard("supershuckie_frontend_close_rom", || {
+        let _ = frontend.close_rom();
+    })
 }
 
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn supershuckie_frontend_unload_rom(
     frontend: &mut SuperShuckieFrontend
 ) {
-    frontend.unload_rom();
+    ffi_guard("supershuckie_frontend_unload_rom", || {
+        frontend.unload_rom();
+    })
 }
 
 #[unsafe(no_mangle)]
@@ -148,8 +555,10 @@ pub unsafe extern "C" fn supershuckie_frontend_load_or_create_save_file(
     save_file: *const c_char,
     initialize: bool
 ) {
-    let save_file = unsafe { CStr::from_ptr(save_file) }.to_str().expect("save file not utf-8");
-    frontend.load_or_create_save_file(save_file, initialize);
+    ffi_guard("supershuckie_frontend_load_or_create_save_file", || {
+        let save_file = &*unsafe { c_str_lossy(save_file) };
+        frontend.load_or_create_save_file(save_file, initialize);
+    })
 }
 
 #[unsafe(no_mangle)]
@@ -157,25 +566,58 @@ pub unsafe extern "C" fn supershuckie_frontend_set_current_save_file(
     frontend: &mut SuperShuckieFrontend,
     save_file: *const c_char
 ) {
-    let save_file = unsafe { CStr::from_ptr(save_file) }.to_str().expect("save file not utf-8");
-    frontend.set_current_save_file(save_file);
+    ffi_guard("supershuckie_frontend_set_current_save_file", || {
+        let save_file = &*unsafe { c_str_lossy(save_file) };
+        frontend.set_current_save_file(save_file);
+    })
 }
 
 #[unsafe(no_mangle)]
 pub extern "C" fn supershuckie_frontend_hard_reset_console(
     frontend: &mut SuperShuckieFrontend
 ) {
-    frontend.hard_reset_console();
+    ffi_guard("supershuckie_frontend_hard_reset_console", || {
+        frontend.hard_reset_console();
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_soft_reset_console(
+    frontend: &mut SuperShuckieFrontend
+) -> bool {
+    ffi_guard("supershuckie_frontend_soft_reset_console", || {
+        frontend.soft_reset_console()
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_power_cycle_with_fresh_sram(
+    frontend: &mut SuperShuckieFrontend,
+    error: *mut u8,
+    error_len: usize
+) -> bool {
+    ffi_guard("supershuckie_frontend_power_cycle_with_fresh_sram", || {
+        match frontend.power_cycle_with_fresh_sram() {
+            Ok(()) => true,
+            Err(_) if error_len == 0 => false,
+            Err(e) => {
+                write_frontend_error(frontend, &e, unsafe { from_raw_parts_mut(error, error_len) });
+                false
+            }
+        }
+    })
 }
 
 #[unsafe(no_mangle)]
 pub extern "C" fn supershuckie_frontend_is_game_running(
     frontend: &SuperShuckieFrontend
 ) -> bool {
-    frontend.is_game_running()
+    ffi_guard("supershuckie_frontend_is_game_running", || {
+        frontend.is_game_running()
+    })
 }
 
-fn write_str_to_data(string: &str, buffer: &mut [u8]) {
+pub(crate) fn write_str_to_data(string: &str, buffer: &mut [u8]) {
     if buffer.is_empty() {
         return
     }
@@ -202,7 +644,25 @@ fn write_str_to_data(string: &str, buffer: &mut [u8]) {
 pub unsafe extern "C" fn supershuckie_frontend_force_refresh_screens(
     frontend: &mut SuperShuckieFrontend
 ) {
-    frontend.force_refresh_screens();
+    ffi_guard("supershuckie_frontend_force_refresh_screens", || {
+        frontend.force_refresh_screens();
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_set_preferred_screen_encodings(
+    frontend: &mut SuperShuckieFrontend,
+    encodings: *const u32,
+    encoding_count: usize
+) -> u32 {
+    ffi_guard("supershuckie_frontend_set_preferred_screen_encodings", || {
+        let encodings: &[u32] = if encoding_count == 0 { &[] } else { unsafe { from_raw_parts(encodings, encoding_count) } };
+        let preferred: Vec<ScreenDataEncoding> = encodings
+            .iter()
+            .filter_map(|&e| ScreenDataEncoding::ALL.into_iter().find(|i| *i as u32 == e))
+            .collect();
+        frontend.set_preferred_screen_encodings(&preferred) as u32
+    })
 }
 
 #[unsafe(no_mangle)]
@@ -210,7 +670,9 @@ pub unsafe extern "C" fn supershuckie_frontend_set_video_scale(
     frontend: &mut SuperShuckieFrontend,
     scale: u8
 ) {
-    frontend.set_video_scale(NonZeroU8::new(scale).unwrap_or(unsafe { NonZeroU8::new_unchecked(1) }));
+    ffi_guard("supershuckie_frontend_set_video_scale", || {
+        frontend.set_video_scale(NonZeroU8::new(scale).unwrap_or(unsafe { NonZeroU8::new_unchecked(1) }));
+    })
 }
 
 #[unsafe(no_mangle)]
@@ -218,9 +680,11 @@ pub unsafe extern "C" fn supershuckie_frontend_get_custom_setting(
     frontend: &SuperShuckieFrontend,
     setting: *const c_char
 ) -> *const c_char {
-    frontend.get_custom_setting(unsafe { CStr::from_ptr(setting) }.to_str().expect("supershuckie_frontend_get_custom_setting bad setting"))
-        .map(|i| i.as_c_str().as_ptr())
-        .unwrap_or(null())
+    ffi_guard("supershuckie_frontend_get_custom_setting", || {
+        frontend.get_custom_setting(&unsafe { c_str_lossy(setting) })
+            .map(|i| i.as_c_str().as_ptr())
+            .unwrap_or(null())
+    })
 }
 
 #[unsafe(no_mangle)]
@@ -230,59 +694,184 @@ pub unsafe extern "C" fn supershuckie_frontend_start_recording_replay(
     result: *mut u8,
     result_len: usize
 ) -> bool {
-    let name = if !name.is_null() { Some(unsafe { CStr::from_ptr(name) }.to_str().expect("name not UTF-8")) } else { None };
-    let (success, msg) = match frontend.start_recording_replay(name) {
-        Ok(n) => (true, n),
-        Err(n) => (false, n)
-    };
-
-    write_str_to_data(msg.as_str(), unsafe { from_raw_parts_mut(result, result_len) });
-    success
+    ffi_guard("supershuckie_frontend_start_recording_replay", || {
+        let name = if !name.is_null() { Some(&*unsafe { c_str_lossy(name) }) } else { None };
+        match frontend.start_recording_replay(name) {
+            Ok(n) => {
+                write_str_to_data(n.as_str(), unsafe { from_raw_parts_mut(result, result_len) });
+                true
+            },
+            Err(e) => {
+                write_frontend_error(frontend, &e, unsafe { from_raw_parts_mut(result, result_len) });
+                false
+            }
+        }
+    })
 }
 
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn supershuckie_frontend_stop_recording_replay(
     frontend: &mut SuperShuckieFrontend
 ) {
-    frontend.stop_recording_replay();
+    ffi_guard("supershuckie_frontend_stop_recording_replay", || {
+        frontend.stop_recording_replay();
+    })
 }
 
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn supershuckie_frontend_get_recording_replay_file(
     frontend: &SuperShuckieFrontend
 ) -> *const c_char {
-    frontend.get_replay_file_info().map(|i| i.final_replay_name.as_c_str().as_ptr()).unwrap_or(null())
+    ffi_guard("supershuckie_frontend_get_recording_replay_file", || {
+        frontend.get_replay_file_info().map(|i| i.final_replay_name.as_c_str().as_ptr()).unwrap_or(null())
+    })
 }
 
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn supershuckie_frontend_create_save_state(
     frontend: &mut SuperShuckieFrontend,
     name: *const c_char,
+    cancel: *const SuperShuckieCancellationToken,
     result: *mut u8,
     result_len: usize
 ) -> bool {
-    let name = if !name.is_null() { Some(unsafe { CStr::from_ptr(name) }.to_str().expect("name not UTF-8")) } else { None };
-    let (success, msg) = match frontend.create_save_state(name) {
-        Ok(n) => (true, n),
-        Err(n) => (false, n)
-    };
+    ffi_guard("supershuckie_frontend_create_save_state", || {
+        let name = if !name.is_null() { Some(&*unsafe { c_str_lossy(name) }) } else { None };
+        let cancel = unsafe { cancel.as_ref() }.map(|c| &c.0);
+        match frontend.create_save_state(name, cancel) {
+            Ok(n) => {
+                write_str_to_data(n.as_str(), unsafe { from_raw_parts_mut(result, result_len) });
+                true
+            },
+            Err(e) => {
+                write_frontend_error(frontend, &e, unsafe { from_raw_parts_mut(result, result_len) });
+                false
+            }
+        }
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_save_screenshot(
+    frontend: &mut SuperShuckieFrontend,
+    scale: u8,
+    result: *mut u8,
+    result_len: usize
+) -> bool {
+    ffi_guard("supershuckie_frontend_save_screenshot", || {
+        let scale = NonZeroU8::new(scale).unwrap_or(NonZeroU8::MIN);
+        match frontend.save_screenshot(scale) {
+            Ok(n) => {
+                write_str_to_data(n.as_str(), unsafe { from_raw_parts_mut(result, result_len) });
+                true
+            },
+            Err(e) => {
+                write_frontend_error(frontend, &e, unsafe { from_raw_parts_mut(result, result_len) });
+                false
+            }
+        }
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_export_replay_clip(
+    frontend: &mut SuperShuckieFrontend,
+    name: *const c_char,
+    first_frame: u32,
+    last_frame: u32,
+    scale: u8,
+    result: *mut u8,
+    result_len: usize
+) -> bool {
+    ffi_guard("supershuckie_frontend_export_replay_clip", || {
+        let name = &*unsafe { c_str_lossy(name) };
+        let scale = NonZeroU8::new(scale).unwrap_or(NonZeroU8::MIN);
+        match frontend.export_replay_clip(name, first_frame, last_frame, scale) {
+            Ok(n) => {
+                write_str_to_data(n.as_str(), unsafe { from_raw_parts_mut(result, result_len) });
+                true
+            },
+            Err(e) => {
+                write_frontend_error(frontend, &e, unsafe { from_raw_parts_mut(result, result_len) });
+                false
+            }
+        }
+    })
+}
 
-    write_str_to_data(msg.as_str(), unsafe { from_raw_parts_mut(result, result_len) });
-    success
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_export_replay_audio(
+    frontend: &mut SuperShuckieFrontend,
+    name: *const c_char,
+    first_frame: u32,
+    last_frame: u32,
+    result: *mut u8,
+    result_len: usize
+) -> bool {
+    ffi_guard("supershuckie_frontend_export_replay_audio", || {
+        let name = &*unsafe { c_str_lossy(name) };
+        match frontend.export_replay_audio(name, first_frame, last_frame) {
+            Ok(n) => {
+                write_str_to_data(n.as_str(), unsafe { from_raw_parts_mut(result, result_len) });
+                true
+            },
+            Err(e) => {
+                write_frontend_error(frontend, &e, unsafe { from_raw_parts_mut(result, result_len) });
+                false
+            }
+        }
+    })
 }
 
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn supershuckie_frontend_undo_load_save_state(
     frontend: &mut SuperShuckieFrontend
 ) -> bool {
-    frontend.undo_load_save_state()
+    ffi_guard("supershuckie_frontend_undo_load_save_state", || {
+        frontend.undo_load_save_state()
+    })
 }
 
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn supershuckie_frontend_redo_load_save_state(
     frontend: &mut SuperShuckieFrontend
 ) -> bool {
-    frontend.redo_load_save_state()
+    ffi_guard("supershuckie_frontend_redo_load_save_state", || {
+        frontend.redo_load_save_state()
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_render_save_state_preview(
+    frontend: &SuperShuckieFrontend,
+    name: *const c_char,
+    pixels: *mut u32,
+    pixel_count: usize,
+    width: *mut u32,
+    height: *mut u32
+) -> bool {
+    ffi_guard("supershuckie_frontend_render_save_state_preview", || {
+        let name = &*unsafe { c_str_lossy(name) };
+        let width = unsafe { nullable_reference!(width) };
+        let height = unsafe { nullable_reference!(height) };
+
+        let Ok(Some(screens)) = frontend.render_save_state_preview(name) else {
+            return false
+        };
+        let Some(screen) = screens.first() else {
+            return false
+        };
+
+        *width = screen.width as u32;
+        *height = screen.height as u32;
+
+        if !pixels.is_null() {
+            let count = pixel_count.min(screen.pixels.len());
+            unsafe { from_raw_parts_mut(pixels, count) }.copy_from_slice(&screen.pixels[..count]);
+        }
+
+        true
+    })
 }
 
 #[unsafe(no_mangle)]
@@ -292,21 +881,23 @@ pub unsafe extern "C" fn supershuckie_frontend_load_save_state(
     error: *mut u8,
     error_len: usize
 ) -> bool {
-    let name = unsafe { CStr::from_ptr(name) }.to_str().expect("name not UTF-8");
-    match frontend.load_save_state_if_exists(name) {
-        Ok(true) => true,
-        Ok(false) => {
-            if error_len >= 1 {
-                unsafe { *error = 0 };
+    ffi_guard("supershuckie_frontend_load_save_state", || {
+        let name = &*unsafe { c_str_lossy(name) };
+        match frontend.load_save_state_if_exists(name) {
+            Ok(true) => true,
+            Ok(false) => {
+                if error_len >= 1 {
+                    unsafe { *error = 0 };
+                }
+                false
+            }
+            Err(_) if error_len == 0 => false,
+            Err(e) => {
+                write_frontend_error(frontend, &e, unsafe { from_raw_parts_mut(error, error_len) });
+                false
             }
-            false
-        }
-        Err(_) if error_len == 0 => false,
-        Err(e) => {
-            write_str_to_data(e.as_str(), unsafe { from_raw_parts_mut(error, error_len) });
-            false
         }
-    }
+    })
 }
 
 #[unsafe(no_mangle)]
@@ -315,23 +906,27 @@ pub unsafe extern "C" fn supershuckie_frontend_is_pokeabyte_enabled(
     error: *mut u8,
     error_len: usize
 ) -> bool {
-    match frontend.is_pokeabyte_enabled() {
-        Ok(n) => {
-            unsafe { *error = 0 };
-            n
-        },
-        Err(e) => {
-            write_str_to_data(e.as_str(), unsafe { from_raw_parts_mut(error, error_len) });
-            false
+    ffi_guard("supershuckie_frontend_is_pokeabyte_enabled", || {
+        match frontend.is_pokeabyte_enabled() {
+            Ok(n) => {
+                unsafe { *error = 0 };
+                n
+            },
+            Err(e) => {
+                write_frontend_error(frontend, e, unsafe { from_raw_parts_mut(error, error_len) });
+                false
+            }
         }
-    }
+    })
 }
 
 #[unsafe(no_mangle)]
 pub extern "C" fn supershuckie_frontend_is_paused(
     frontend: &SuperShuckieFrontend
 ) -> bool {
-    frontend.is_paused()
+    ffi_guard("supershuckie_frontend_is_paused", || {
+        frontend.is_paused()
+    })
 }
 
 #[unsafe(no_mangle)]
@@ -341,361 +936,2280 @@ pub unsafe extern "C" fn supershuckie_frontend_set_pokeabyte_enabled(
     error: *mut u8,
     error_len: usize
 ) -> bool {
-    match frontend.set_pokeabyte_enabled(enabled) {
-        Ok(_) => true,
-        Err(e) => {
-            write_str_to_data(e.as_str(), unsafe { from_raw_parts_mut(error, error_len) });
-            false
+    ffi_guard("supershuckie_frontend_set_pokeabyte_enabled", || {
+        match frontend.set_pokeabyte_enabled(enabled) {
+            Ok(_) => true,
+            Err(e) => {
+                let e = e.clone();
+                write_frontend_error(frontend, &e, unsafe { from_raw_parts_mut(error, error_len) });
+                false
+            }
         }
-    }
+    })
 }
 
 #[unsafe(no_mangle)]
-pub extern "C" fn supershuckie_frontend_set_auto_stop_playback_on_input_setting(
+pub unsafe extern "C" fn supershuckie_frontend_host_netplay(
     frontend: &mut SuperShuckieFrontend,
-    new_setting: bool
-) {
-    frontend.set_auto_stop_playback_on_input_setting(new_setting);
-}
-
-#[unsafe(no_mangle)]
-pub extern "C" fn supershuckie_frontend_get_auto_stop_playback_on_input_setting(frontend: &SuperShuckieFrontend) -> bool {
-    frontend.get_auto_stop_playback_on_input_setting()
+    port: u16,
+    error: *mut u8,
+    error_len: usize
+) -> bool {
+    ffi_guard("supershuckie_frontend_host_netplay", || {
+        match frontend.host_netplay(port) {
+            Ok(_) => true,
+            Err(e) => {
+                write_frontend_error(frontend, &e, unsafe { from_raw_parts_mut(error, error_len) });
+                false
+            }
+        }
+    })
 }
 
 #[unsafe(no_mangle)]
-pub extern "C" fn supershuckie_frontend_set_auto_unpause_on_input_setting(
+pub unsafe extern "C" fn supershuckie_frontend_join_netplay(
     frontend: &mut SuperShuckieFrontend,
-    new_setting: bool
-) {
-    frontend.set_auto_unpause_on_input_setting(new_setting);
+    address: *const c_char,
+    error: *mut u8,
+    error_len: usize
+) -> bool {
+    ffi_guard("supershuckie_frontend_join_netplay", || {
+        let address = &*unsafe { c_str_lossy(address) };
+        match frontend.join_netplay(address) {
+            Ok(_) => true,
+            Err(e) => {
+                write_frontend_error(frontend, &e, unsafe { from_raw_parts_mut(error, error_len) });
+                false
+            }
+        }
+    })
 }
 
 #[unsafe(no_mangle)]
-pub extern "C" fn supershuckie_frontend_get_auto_unpause_on_input_setting(frontend: &SuperShuckieFrontend) -> bool {
-    frontend.get_auto_unpause_on_input_setting()
+pub unsafe extern "C" fn supershuckie_frontend_spectate_netplay(
+    frontend: &mut SuperShuckieFrontend,
+    address: *const c_char,
+    error: *mut u8,
+    error_len: usize
+) -> bool {
+    ffi_guard("supershuckie_frontend_spectate_netplay", || {
+        let address = &*unsafe { c_str_lossy(address) };
+        match frontend.spectate_netplay(address) {
+            Ok(_) => true,
+            Err(e) => {
+                write_frontend_error(frontend, &e, unsafe { from_raw_parts_mut(error, error_len) });
+                false
+            }
+        }
+    })
 }
 
 #[unsafe(no_mangle)]
-pub extern "C" fn supershuckie_frontend_set_auto_pause_on_record_setting(
-    frontend: &mut SuperShuckieFrontend,
-    new_setting: bool
+pub extern "C" fn supershuckie_frontend_stop_netplay(
+    frontend: &mut SuperShuckieFrontend
 ) {
-    frontend.set_auto_pause_on_record_setting(new_setting);
+    ffi_guard("supershuckie_frontend_stop_netplay", || {
+        frontend.stop_netplay()
+    })
 }
 
 #[unsafe(no_mangle)]
-pub extern "C" fn supershuckie_frontend_get_auto_pause_on_record_setting(frontend: &SuperShuckieFrontend) -> bool {
-    frontend.get_auto_pause_on_record_setting()
+pub extern "C" fn supershuckie_frontend_get_netplay_status(
+    frontend: &SuperShuckieFrontend
+) -> u32 {
+    ffi_guard("supershuckie_frontend_get_netplay_status", || {
+        frontend.get_netplay_status() as u32
+    })
 }
 
 #[unsafe(no_mangle)]
-pub extern "C" fn supershuckie_frontend_set_auto_decompress_replays_upfront_setting(
-    frontend: &mut SuperShuckieFrontend,
-    new_setting: bool
-) {
-    frontend.set_auto_decompress_replays_upfront_setting(new_setting);
+pub extern "C" fn supershuckie_frontend_get_pokeabyte_listen_address(
+    frontend: &SuperShuckieFrontend
+) -> *const c_char {
+    ffi_guard("supershuckie_frontend_get_pokeabyte_listen_address", || {
+        frontend.get_pokeabyte_listen_address().map(|i| i.as_c_str().as_ptr()).unwrap_or(null())
+    })
 }
 
 #[unsafe(no_mangle)]
-pub extern "C" fn supershuckie_frontend_get_auto_decompress_replays_upfront_setting(frontend: &SuperShuckieFrontend) -> bool {
-    frontend.get_auto_decompress_replays_upfront_setting()
+pub unsafe extern "C" fn supershuckie_frontend_get_pokeabyte_transport(
+    frontend: &SuperShuckieFrontend,
+    address: *mut *const c_char
+) -> PokeAByteTransportKind {
+    ffi_guard("supershuckie_frontend_get_pokeabyte_transport", || {
+        let (transport, transport_address) = frontend.get_pokeabyte_transport();
+        if !address.is_null() {
+            unsafe { *address = transport_address.as_c_str().as_ptr() };
+        }
+        transport
+    })
 }
 
 #[unsafe(no_mangle)]
-pub unsafe extern "C" fn supershuckie_frontend_save_sram(
+pub unsafe extern "C" fn supershuckie_frontend_set_pokeabyte_transport(
     frontend: &mut SuperShuckieFrontend,
+    transport: u32,
+    address: *const c_char,
     error: *mut u8,
     error_len: usize
 ) -> bool {
-    match frontend.save_sram() {
-        Ok(_) => true,
-        Err(_) if error_len == 0 => false,
-        Err(e) => {
-            write_str_to_data(e.as_str(), unsafe { from_raw_parts_mut(error, error_len) });
-            false
+    ffi_guard("supershuckie_frontend_set_pokeabyte_transport", || {
+        let address = if address.is_null() {
+            ""
         }
-    }
+        else {
+            &*unsafe { c_str_lossy(address) }
+        };
+        let error = unsafe { from_raw_parts_mut(error, error_len) };
+        let Ok(transport) = PokeAByteTransportKind::try_from(transport) else {
+            write_frontend_error(frontend, &LocalizedError::new("pokeabyte_transport_unknown"), error);
+            return false
+        };
+        match frontend.set_pokeabyte_transport(transport, address) {
+            Ok(_) => true,
+            Err(e) => {
+                let e = e.clone();
+                write_frontend_error(frontend, &e, error);
+                false
+            }
+        }
+    })
 }
 
 #[unsafe(no_mangle)]
-pub unsafe extern "C" fn supershuckie_frontend_set_custom_setting(
-    frontend: &mut SuperShuckieFrontend,
-    setting: *const c_char,
-    value: *const c_char
+pub extern "C" fn supershuckie_frontend_disconnect_pokeabyte_client(
+    frontend: &mut SuperShuckieFrontend
 ) {
-    frontend.set_custom_setting(
-        unsafe { CStr::from_ptr(setting) }.to_str().expect("supershuckie_frontend_set_custom_setting bad setting"),
-        if value.is_null() {
-            None
-        }
-        else {
-            Some(UTF8CString::from_cstr(unsafe { CStr::from_ptr(value) }))
-        }
-    );
+    ffi_guard("supershuckie_frontend_disconnect_pokeabyte_client", || {
+        frontend.disconnect_pokeabyte_client();
+    })
 }
 
 #[unsafe(no_mangle)]
-pub unsafe extern "C" fn supershuckie_frontend_get_rom_name(
+pub extern "C" fn supershuckie_frontend_is_rejecting_pokeabyte_writes_during_playback(
     frontend: &SuperShuckieFrontend
-) -> *const c_char {
-    frontend.get_current_rom_name_c_str().map(|i| i.as_ptr()).unwrap_or(null())
+) -> bool {
+    ffi_guard("supershuckie_frontend_is_rejecting_pokeabyte_writes_during_playback", || {
+        frontend.is_rejecting_pokeabyte_writes_during_playback()
+    })
 }
 
 #[unsafe(no_mangle)]
-pub unsafe extern "C" fn supershuckie_frontend_write_settings(
+pub extern "C" fn supershuckie_frontend_set_rejecting_pokeabyte_writes_during_playback(
+    frontend: &mut SuperShuckieFrontend,
+    reject: bool
+) {
+    ffi_guard("supershuckie_frontend_set_rejecting_pokeabyte_writes_during_playback", || {
+        frontend.set_rejecting_pokeabyte_writes_during_playback(reject);
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_is_strict_ram_access(
     frontend: &SuperShuckieFrontend
+) -> bool {
+    ffi_guard("supershuckie_frontend_is_strict_ram_access", || {
+        frontend.is_strict_ram_access()
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_set_strict_ram_access(
+    frontend: &mut SuperShuckieFrontend,
+    strict: bool
 ) {
-    frontend.write_settings();
+    ffi_guard("supershuckie_frontend_set_strict_ram_access", || {
+        frontend.set_strict_ram_access(strict);
+    })
 }
 
 #[unsafe(no_mangle)]
-pub unsafe extern "C" fn supershuckie_frontend_get_speed_settings(
+pub extern "C" fn supershuckie_frontend_get_pokeabyte_frame_skip(
     frontend: &SuperShuckieFrontend,
-    base: *mut f64,
-    turbo: *mut f64
-) {
-    let base = unsafe { nullable_reference!(base) };
-    let turbo = unsafe { nullable_reference!(turbo) };
-    frontend.get_speed_settings(base, turbo);
+    custom_frame_skip: &mut u32
+) -> PokeAByteFrameSkip {
+    ffi_guard("supershuckie_frontend_get_pokeabyte_frame_skip", || {
+        *custom_frame_skip = frontend.get_pokeabyte_custom_frame_skip();
+        frontend.get_pokeabyte_frame_skip()
+    })
 }
 
 #[unsafe(no_mangle)]
-pub extern "C" fn supershuckie_frontend_set_speed_settings(
+pub extern "C" fn supershuckie_frontend_set_pokeabyte_frame_skip(
     frontend: &mut SuperShuckieFrontend,
-    base: f64,
-    turbo: f64
+    frame_skip: u32,
+    custom_frame_skip: u32
 ) {
-    frontend.set_speed_settings(base, turbo);
+    ffi_guard("supershuckie_frontend_set_pokeabyte_frame_skip", || {
+        if let Ok(f) = PokeAByteFrameSkip::try_from(frame_skip) {
+            frontend.set_pokeabyte_frame_skip(f, custom_frame_skip);
+        }
+    })
 }
 
 #[unsafe(no_mangle)]
-pub unsafe extern "C" fn supershuckie_frontend_free(
-    frontend: *mut SuperShuckieFrontend
+pub extern "C" fn supershuckie_frontend_set_auto_stop_playback_on_input_setting(
+    frontend: &mut SuperShuckieFrontend,
+    new_setting: bool
 ) {
-    if !frontend.is_null() {
-        let _ = unsafe { Box::from_raw(frontend) };
-    }
+    ffi_guard("supershuckie_frontend_set_auto_stop_playback_on_input_setting", || {
+        frontend.set_auto_stop_playback_on_input_setting(new_setting);
+    })
 }
 
 #[unsafe(no_mangle)]
-pub unsafe extern "C" fn supershuckie_frontend_get_all_replays_for_rom(
-    frontend: &SuperShuckieFrontend,
-    rom: *const c_char
-) -> *mut SuperShuckieStringArray {
-    let array = match unsafe { current_rom_or_null(frontend, rom) } {
-        Some(rom) => SuperShuckieStringArray(frontend.get_all_replays_for_rom(rom)),
-        None => SuperShuckieStringArray::default()
-    };
-    Box::into_raw(Box::new(array))
+pub extern "C" fn supershuckie_frontend_get_auto_stop_playback_on_input_setting(frontend: &SuperShuckieFrontend) -> bool {
+    ffi_guard("supershuckie_frontend_get_auto_stop_playback_on_input_setting", || {
+        frontend.get_auto_stop_playback_on_input_setting()
+    })
 }
 
 #[unsafe(no_mangle)]
-pub unsafe extern "C" fn supershuckie_frontend_get_all_saves_for_rom(
-    frontend: &SuperShuckieFrontend,
-    rom: *const c_char
-) -> *mut SuperShuckieStringArray {
-    let array = match unsafe { current_rom_or_null(frontend, rom) } {
-        Some(rom) => SuperShuckieStringArray(frontend.get_all_saves_for_rom(rom)),
-        None => SuperShuckieStringArray::default()
-    };
-    Box::into_raw(Box::new(array))
+pub extern "C" fn supershuckie_frontend_set_auto_unpause_on_input_setting(
+    frontend: &mut SuperShuckieFrontend,
+    new_setting: bool
+) {
+    ffi_guard("supershuckie_frontend_set_auto_unpause_on_input_setting", || {
+        frontend.set_auto_unpause_on_input_setting(new_setting);
+    })
 }
 
 #[unsafe(no_mangle)]
-pub unsafe extern "C" fn supershuckie_frontend_get_all_save_states_for_rom(
-    frontend: &SuperShuckieFrontend,
-    rom: *const c_char
-) -> *mut SuperShuckieStringArray {
-    let array = match unsafe { current_rom_or_null(frontend, rom) } {
-        Some(rom) => SuperShuckieStringArray(frontend.get_all_save_states_for_rom(rom)),
-        None => SuperShuckieStringArray::default()
-    };
-    Box::into_raw(Box::new(array))
+pub extern "C" fn supershuckie_frontend_get_auto_unpause_on_input_setting(frontend: &SuperShuckieFrontend) -> bool {
+    ffi_guard("supershuckie_frontend_get_auto_unpause_on_input_setting", || {
+        frontend.get_auto_unpause_on_input_setting()
+    })
 }
 
 #[unsafe(no_mangle)]
-pub unsafe extern "C" fn supershuckie_frontend_get_elapsed_time(
-    frontend: &SuperShuckieFrontend,
-    elapsed_frames: *mut u32,
-    elapsed_milliseconds: *mut u32
+pub extern "C" fn supershuckie_frontend_set_auto_pause_on_record_setting(
+    frontend: &mut SuperShuckieFrontend,
+    new_setting: bool
 ) {
-    let elapsed_frames = unsafe { nullable_reference!(elapsed_frames) };
-    let elapsed_milliseconds = unsafe { nullable_reference!(elapsed_milliseconds) };
+    ffi_guard("supershuckie_frontend_set_auto_pause_on_record_setting", || {
+        frontend.set_auto_pause_on_record_setting(new_setting);
+    })
+}
 
-    *elapsed_milliseconds = frontend.get_elapsed_milliseconds();
-    *elapsed_frames = frontend.get_elapsed_frames();
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_get_auto_pause_on_record_setting(frontend: &SuperShuckieFrontend) -> bool {
+    ffi_guard("supershuckie_frontend_get_auto_pause_on_record_setting", || {
+        frontend.get_auto_pause_on_record_setting()
+    })
 }
 
 #[unsafe(no_mangle)]
-pub unsafe extern "C" fn supershuckie_frontend_get_replay_playback_time(
-    frontend: &SuperShuckieFrontend,
-    total_frames: *mut u32,
-    total_milliseconds: *mut u32
-) -> bool {
-    let total_frames = unsafe { nullable_reference!(total_frames) };
-    let total_milliseconds = unsafe { nullable_reference!(total_milliseconds) };
+pub extern "C" fn supershuckie_frontend_set_sandbox_playback_sram_setting(
+    frontend: &mut SuperShuckieFrontend,
+    new_setting: bool
+) {
+    ffi_guard("supershuckie_frontend_set_sandbox_playback_sram_setting", || {
+        frontend.set_sandbox_playback_sram_setting(new_setting);
+    })
+}
 
-    match frontend.get_replay_playback_stats() {
-        Some(n) => {
-            *total_frames = n.total_frames;
-            *total_milliseconds = n.total_milliseconds;
-            true
-        },
-        None => {
-            *total_frames = 0;
-            *total_milliseconds = 0;
-            false
-        }
-    }
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_get_sandbox_playback_sram_setting(frontend: &SuperShuckieFrontend) -> bool {
+    ffi_guard("supershuckie_frontend_get_sandbox_playback_sram_setting", || {
+        frontend.get_sandbox_playback_sram_setting()
+    })
 }
 
 #[unsafe(no_mangle)]
-pub unsafe extern "C" fn supershuckie_frontend_load_replay(
+pub extern "C" fn supershuckie_frontend_set_determinism_check_keyframes_setting(
     frontend: &mut SuperShuckieFrontend,
-    name: *const c_char,
-    override_errors: bool,
-    error: *mut u8,
-    error_len: usize
-) -> bool {
-    let name = unsafe { CStr::from_ptr(name).to_str().expect("replay name is not UTF-8") };
+    keyframes: u64
+) {
+    ffi_guard("supershuckie_frontend_set_determinism_check_keyframes_setting", || {
+        frontend.set_determinism_check_keyframes_setting(NonZeroU64::new(keyframes));
+    })
+}
 
-    match frontend.load_replay_if_exists(name, override_errors) {
-        Ok(_) => true,
-        Err(e) => {
-            write_str_to_data(e.as_str(), unsafe { from_raw_parts_mut(error, error_len) });
-            false
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_get_determinism_check_keyframes_setting(frontend: &SuperShuckieFrontend) -> u64 {
+    ffi_guard("supershuckie_frontend_get_determinism_check_keyframes_setting", || {
+        frontend.get_determinism_check_keyframes_setting().map_or(0, NonZeroU64::get)
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_is_sram_sandboxed(frontend: &SuperShuckieFrontend) -> bool {
+    ffi_guard("supershuckie_frontend_is_sram_sandboxed", || {
+        frontend.is_sram_sandboxed()
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_set_load_state_record_mode_setting(frontend: &mut SuperShuckieFrontend, mode: u32) {
+    ffi_guard("supershuckie_frontend_set_load_state_record_mode_setting", || {
+        if let Ok(m) = LoadStateRecordMode::try_from(mode) {
+            frontend.set_load_state_record_mode_setting(m)
         }
-    }
+    })
 }
 
 #[unsafe(no_mangle)]
-pub extern "C" fn supershuckie_frontend_stop_replay_playback(
-    frontend: &mut SuperShuckieFrontend
-) {
-    frontend.stop_replay_playback();
+pub extern "C" fn supershuckie_frontend_get_load_state_record_mode_setting(frontend: &SuperShuckieFrontend) -> LoadStateRecordMode {
+    ffi_guard("supershuckie_frontend_get_load_state_record_mode_setting", || {
+        frontend.get_load_state_record_mode_setting()
+    })
 }
 
-unsafe fn current_rom_or_null(frontend: &SuperShuckieFrontend, rom: *const c_char) -> Option<&str> {
-    if rom.is_null() {
-        frontend.get_current_rom_name()
-    }
-    else {
-        Some(unsafe { CStr::from_ptr(rom) }.to_str().expect("save file not utf-8"))
-    }
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_set_autosnap_enabled_setting(
+    frontend: &mut SuperShuckieFrontend,
+    new_setting: bool
+) {
+    ffi_guard("supershuckie_frontend_set_autosnap_enabled_setting", || {
+        frontend.set_autosnap_enabled_setting(new_setting);
+    })
 }
 
 #[unsafe(no_mangle)]
-pub extern "C" fn supershuckie_frontend_get_control_settings(
-    frontend: &SuperShuckieFrontend
-) -> *mut SuperShuckieControlSettings {
-    Box::into_raw(Box::new(SuperShuckieControlSettings(frontend.get_control_settings().clone())))
+pub extern "C" fn supershuckie_frontend_get_autosnap_enabled_setting(frontend: &SuperShuckieFrontend) -> bool {
+    ffi_guard("supershuckie_frontend_get_autosnap_enabled_setting", || {
+        frontend.get_autosnap_enabled_setting()
+    })
 }
 
 #[unsafe(no_mangle)]
-pub extern "C" fn supershuckie_frontend_set_control_settings(
+pub extern "C" fn supershuckie_frontend_set_save_backups_enabled_setting(
     frontend: &mut SuperShuckieFrontend,
-    settings: &SuperShuckieControlSettings
+    new_setting: bool
 ) {
-    frontend.set_control_settings(settings.0.clone())
+    ffi_guard("supershuckie_frontend_set_save_backups_enabled_setting", || {
+        frontend.set_save_backups_enabled_setting(new_setting);
+    })
 }
 
 #[unsafe(no_mangle)]
-pub extern "C" fn supershuckie_frontend_get_connected_controllers(
-    frontend: &SuperShuckieFrontend
-) -> *mut SuperShuckieStringArray {
-    Box::into_raw(Box::new(SuperShuckieStringArray(frontend.get_connected_controllers())))
+pub extern "C" fn supershuckie_frontend_get_save_backups_enabled_setting(frontend: &SuperShuckieFrontend) -> bool {
+    ffi_guard("supershuckie_frontend_get_save_backups_enabled_setting", || {
+        frontend.get_save_backups_enabled_setting()
+    })
 }
 
 #[unsafe(no_mangle)]
-pub unsafe extern "C" fn supershuckie_frontend_connect_controller(
+pub extern "C" fn supershuckie_frontend_set_pause_on_focus_loss_setting(
     frontend: &mut SuperShuckieFrontend,
-    controller: *mut c_char
-) -> ConnectedControllerIndex {
-    let controller_name = unsafe { CStr::from_ptr(controller).to_str().expect("controller name not UTF-8") };
-    frontend.connect_controller(controller_name)
+    new_setting: bool
+) {
+    ffi_guard("supershuckie_frontend_set_pause_on_focus_loss_setting", || {
+        frontend.set_pause_on_focus_loss_setting(new_setting);
+    })
 }
 
 #[unsafe(no_mangle)]
-pub extern "C" fn supershuckie_frontend_disconnect_controller(
+pub extern "C" fn supershuckie_frontend_get_pause_on_focus_loss_setting(frontend: &SuperShuckieFrontend) -> bool {
+    ffi_guard("supershuckie_frontend_get_pause_on_focus_loss_setting", || {
+        frontend.get_pause_on_focus_loss_setting()
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_set_focused(frontend: &mut SuperShuckieFrontend, focused: bool) {
+    ffi_guard("supershuckie_frontend_set_focused", || {
+        frontend.set_focused(focused);
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_set_throttle_when_minimized_setting(
     frontend: &mut SuperShuckieFrontend,
-    controller: ConnectedControllerIndex
+    new_setting: bool
 ) {
-    frontend.disconnect_controller(controller);
+    ffi_guard("supershuckie_frontend_set_throttle_when_minimized_setting", || {
+        frontend.set_throttle_when_minimized_setting(new_setting);
+    })
 }
 
 #[unsafe(no_mangle)]
-pub extern "C" fn supershuckie_frontend_get_name_of_controller(
-    frontend: &SuperShuckieFrontend,
-    controller: ConnectedControllerIndex
-) -> *const c_char {
-    frontend.name_of_controller_c_str(controller).map(|i| i.as_ptr()).unwrap_or(null())
+pub extern "C" fn supershuckie_frontend_get_throttle_when_minimized_setting(frontend: &SuperShuckieFrontend) -> bool {
+    ffi_guard("supershuckie_frontend_get_throttle_when_minimized_setting", || {
+        frontend.get_throttle_when_minimized_setting()
+    })
 }
 
 #[unsafe(no_mangle)]
-pub extern "C" fn supershuckie_frontend_set_playback_frame(
+pub extern "C" fn supershuckie_frontend_set_minimized(frontend: &mut SuperShuckieFrontend, minimized: bool) {
+    ffi_guard("supershuckie_frontend_set_minimized", || {
+        frontend.set_minimized(minimized);
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_set_power_saving_setting(frontend: &mut SuperShuckieFrontend, mode: u32) {
+    ffi_guard("supershuckie_frontend_set_power_saving_setting", || {
+        if let Ok(m) = PowerSaving::try_from(mode) {
+            frontend.set_power_saving_setting(m)
+        }
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_get_power_saving_setting(frontend: &SuperShuckieFrontend) -> PowerSaving {
+    ffi_guard("supershuckie_frontend_get_power_saving_setting", || {
+        frontend.get_power_saving_setting()
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_is_power_saving_active(frontend: &SuperShuckieFrontend) -> bool {
+    ffi_guard("supershuckie_frontend_is_power_saving_active", || {
+        frontend.is_power_saving_active()
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_set_single_instance_setting(
     frontend: &mut SuperShuckieFrontend,
-    frame: u32
+    new_setting: bool
 ) {
-    frontend.go_to_replay_frame(frame)
+    ffi_guard("supershuckie_frontend_set_single_instance_setting", || {
+        frontend.set_single_instance_setting(new_setting);
+    })
 }
 
 #[unsafe(no_mangle)]
-pub extern "C" fn supershuckie_frontend_advance_playback_frames(
+pub extern "C" fn supershuckie_frontend_get_single_instance_setting(frontend: &SuperShuckieFrontend) -> bool {
+    ffi_guard("supershuckie_frontend_get_single_instance_setting", || {
+        frontend.get_single_instance_setting()
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_set_auto_decompress_replays_upfront_setting(
     frontend: &mut SuperShuckieFrontend,
-    frames: i32
+    new_setting: bool
 ) {
-    frontend.advance_playback_frames(frames)
+    ffi_guard("supershuckie_frontend_set_auto_decompress_replays_upfront_setting", || {
+        frontend.set_auto_decompress_replays_upfront_setting(new_setting);
+    })
 }
 
 #[unsafe(no_mangle)]
-pub extern "C" fn supershuckie_frontend_set_playback_frozen(
+pub extern "C" fn supershuckie_frontend_get_auto_decompress_replays_upfront_setting(frontend: &SuperShuckieFrontend) -> bool {
+    ffi_guard("supershuckie_frontend_get_auto_decompress_replays_upfront_setting", || {
+        frontend.get_auto_decompress_replays_upfront_setting()
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_save_sram(
     frontend: &mut SuperShuckieFrontend,
-    paused: bool
+    error: *mut u8,
+    error_len: usize
+) -> bool {
+    ffi_guard("supershuckie_frontend_save_sram", || {
+        match frontend.save_sram() {
+            Ok(_) => true,
+            Err(_) if error_len == 0 => false,
+            Err(e) => {
+                write_frontend_error(frontend, &e, unsafe { from_raw_parts_mut(error, error_len) });
+                false
+            }
+        }
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_set_custom_setting(
+    frontend: &mut SuperShuckieFrontend,
+    setting: *const c_char,
+    value: *const c_char
 ) {
-    frontend.set_playback_frozen(paused)
+    ffi_guard("supershuckie_frontend_set_custom_setting", || {
+        frontend.set_custom_setting(
+            &unsafe { c_str_lossy(setting) },
+            if value.is_null() {
+                None
+            }
+            else {
+                Some(UTF8CString::from_cstr(unsafe { CStr::from_ptr(value) }))
+            }
+        );
+    })
 }
 
-#[repr(C)]
-pub enum SuperShuckieReplayState {
-    NoReplay,
-    Recording,
-    Playback
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_get_rom_name(
+    frontend: &SuperShuckieFrontend
+) -> *const c_char {
+    ffi_guard("supershuckie_frontend_get_rom_name", || {
+        frontend.get_current_rom_name_c_str().map(|i| i.as_ptr()).unwrap_or(null())
+    })
 }
 
 #[unsafe(no_mangle)]
-pub extern "C" fn supershuckie_frontend_get_replay_state(
+pub extern "C" fn supershuckie_frontend_get_current_save_name(
     frontend: &SuperShuckieFrontend
-) -> SuperShuckieReplayState {
-    if frontend.get_replay_playback_stats().is_some() {
-        SuperShuckieReplayState::Playback
-    }
-    else if frontend.get_replay_file_info().is_some() {
-        SuperShuckieReplayState::Recording
-    }
-    else {
-        SuperShuckieReplayState::NoReplay
-    }
+) -> *const c_char {
+    ffi_guard("supershuckie_frontend_get_current_save_name", || {
+        frontend.get_current_save_name_c_str().map(|i| i.as_ptr()).unwrap_or(null())
+    })
 }
 
 #[unsafe(no_mangle)]
-pub extern "C" fn supershuckie_frontend_get_gbc_mode(frontend: &SuperShuckieFrontend) -> GameBoyMode {
-    frontend.get_gbc_mode()
+pub extern "C" fn supershuckie_frontend_get_rom_checksum(frontend: &SuperShuckieFrontend) -> *const c_char {
+    ffi_guard("supershuckie_frontend_get_rom_checksum", || {
+        frontend.get_rom_checksum().map(|i| i.as_c_str().as_ptr()).unwrap_or(null())
+    })
 }
 
 #[unsafe(no_mangle)]
-pub extern "C" fn supershuckie_frontend_set_gbc_mode(frontend: &mut SuperShuckieFrontend, mode: u32) {
-    if let Ok(m) = GameBoyMode::try_from(mode) {
-        frontend.set_gbc_mode(m)
-    }
+pub unsafe extern "C" fn supershuckie_frontend_add_game_profile(
+    frontend: &mut SuperShuckieFrontend,
+    json: *const c_char,
+    error: *mut u8,
+    error_len: usize
+) -> bool {
+    ffi_guard("supershuckie_frontend_add_game_profile", || {
+        let json = &*unsafe { c_str_lossy(json) };
+        match frontend.add_game_profile(json) {
+            Ok(()) => true,
+            Err(e) => {
+                write_frontend_error(frontend, &e, unsafe { from_raw_parts_mut(error, error_len) });
+                false
+            }
+        }
+    })
 }
 
 #[unsafe(no_mangle)]
-pub extern "C" fn supershuckie_frontend_is_sgb_enabled(frontend: &SuperShuckieFrontend) -> bool {
-    frontend.is_sgb_enabled()
+pub extern "C" fn supershuckie_frontend_get_game_profile_name(frontend: &SuperShuckieFrontend) -> *const c_char {
+    ffi_guard("supershuckie_frontend_get_game_profile_name", || {
+        frontend.get_game_profile().map(|i| i.name.as_c_str().as_ptr()).unwrap_or(null())
+    })
 }
 
 #[unsafe(no_mangle)]
-pub extern "C" fn supershuckie_frontend_set_sgb_enabled(frontend: &mut SuperShuckieFrontend, enabled: bool) {
-    frontend.set_sgb_enabled(enabled);
+pub extern "C" fn supershuckie_frontend_get_game_profile_watch_names(frontend: &SuperShuckieFrontend) -> *mut SuperShuckieStringArray {
+    ffi_guard("supershuckie_frontend_get_game_profile_watch_names", || {
+        let names = frontend.get_game_profile_watches().into_iter().map(|w| w.name).collect();
+        Box::into_raw(Box::new(SuperShuckieStringArray(names)))
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_get_game_profile_watch_values(frontend: &SuperShuckieFrontend) -> *mut SuperShuckieStringArray {
+    ffi_guard("supershuckie_frontend_get_game_profile_watch_values", || {
+        let values = frontend.get_game_profile_watches().into_iter().map(|w| w.value.unwrap_or_default()).collect();
+        Box::into_raw(Box::new(SuperShuckieStringArray(values)))
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_write_settings(
+    frontend: &SuperShuckieFrontend
+) {
+    ffi_guard("supershuckie_frontend_write_settings", || {
+        frontend.write_settings();
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_get_speed_settings(
+    frontend: &SuperShuckieFrontend,
+    base: *mut f64,
+    turbo: *mut f64
+) {
+    ffi_guard("supershuckie_frontend_get_speed_settings", || {
+        let base = unsafe { nullable_reference!(base) };
+        let turbo = unsafe { nullable_reference!(turbo) };
+        frontend.get_speed_settings(base, turbo);
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_set_speed_settings(
+    frontend: &mut SuperShuckieFrontend,
+    base: f64,
+    turbo: f64,
+    error: *mut u8,
+    error_len: usize
+) -> bool {
+    ffi_guard("supershuckie_frontend_set_speed_settings", || {
+        match frontend.set_speed_settings(base, turbo) {
+            Ok(()) => true,
+            Err(e) => {
+                write_frontend_error(frontend, &e, unsafe { from_raw_parts_mut(error, error_len) });
+                false
+            }
+        }
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_get_effective_speed(frontend: &SuperShuckieFrontend) -> f64 {
+    ffi_guard("supershuckie_frontend_get_effective_speed", || {
+        frontend.get_effective_speed()
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_speed_quantize(multiplier: f64) -> f64 {
+    ffi_guard("supershuckie_speed_quantize", || {
+        Speed::quantize(multiplier)
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_speed_get_bounds(min: *mut f64, max: *mut f64) {
+    ffi_guard("supershuckie_speed_get_bounds", || {
+        let min = unsafe { nullable_reference!(min) };
+        let max = unsafe { nullable_reference!(max) };
+        *min = Speed::MIN.into_multiplier_float();
+        *max = Speed::MAX.into_multiplier_float();
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_cycle_speed_preset(
+    frontend: &mut SuperShuckieFrontend,
+    faster: bool
+) {
+    ffi_guard("supershuckie_frontend_cycle_speed_preset", || {
+        frontend.cycle_speed_preset(faster)
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_free(
+    frontend: *mut SuperShuckieFrontend
+) {
+    ffi_guard("supershuckie_frontend_free", || {
+        if !frontend.is_null() {
+            let _ = unsafe { Box::from_raw(frontend) };
+        }
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_get_all_replays_for_rom(
+    frontend: &SuperShuckieFrontend,
+    rom: *const c_char
+) -> *mut SuperShuckieStringArray {
+    ffi_guard("supershuckie_frontend_get_all_replays_for_rom", || {
+        let array = match unsafe { current_rom_or_null(frontend, rom) } {
+            Some(rom) => SuperShuckieStringArray(frontend.get_all_replays_for_rom(&rom)),
+            None => SuperShuckieStringArray::default()
+        };
+        Box::into_raw(Box::new(array))
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_scrub_replay(
+    frontend: &SuperShuckieFrontend,
+    rom: *const c_char,
+    name: *const c_char,
+    rom_name: *const c_char,
+    error: *mut u8,
+    error_len: usize
+) -> bool {
+    ffi_guard("supershuckie_frontend_scrub_replay", || {
+        let Some(rom) = (unsafe { current_rom_or_null(frontend, rom) }) else {
+            write_frontend_error(frontend, &LocalizedError::new("game_not_running"), unsafe { from_raw_parts_mut(error, error_len) });
+            return false
+        };
+        let name = &*unsafe { c_str_lossy(name) };
+        let rom_name = &*unsafe { c_str_lossy(rom_name) };
+
+        match frontend.scrub_replay(&rom, name, rom_name) {
+            Ok(()) => true,
+            Err(e) => {
+                write_frontend_error(frontend, &e, unsafe { from_raw_parts_mut(error, error_len) });
+                false
+            }
+        }
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_browse_replays(
+    frontend: &mut SuperShuckieFrontend,
+    rom: *const c_char,
+    sort: u32,
+    descending: bool,
+    name_filter: *const c_char,
+    compatible_only: bool
+) -> *mut SuperShuckieReplayInfoArray {
+    ffi_guard("supershuckie_frontend_browse_replays", || {
+        let name_filter = if name_filter.is_null() {
+            ""
+        }
+        else {
+            &*unsafe { c_str_lossy(name_filter) }
+        };
+
+        let query = ReplayBrowserQuery {
+            sort: ReplaySort::try_from(sort).unwrap_or_default(),
+            descending,
+            name_filter,
+            compatible_only
+        };
+
+        let array = match unsafe { current_rom_or_null(frontend, rom) }.map(Cow::into_owned) {
+            Some(rom) => SuperShuckieReplayInfoArray(frontend.browse_replays(&rom, &query)),
+            None => SuperShuckieReplayInfoArray::default()
+        };
+        Box::into_raw(Box::new(array))
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_get_all_saves_for_rom(
+    frontend: &SuperShuckieFrontend,
+    rom: *const c_char
+) -> *mut SuperShuckieStringArray {
+    ffi_guard("supershuckie_frontend_get_all_saves_for_rom", || {
+        let array = match unsafe { current_rom_or_null(frontend, rom) } {
+            Some(rom) => SuperShuckieStringArray(frontend.get_all_saves_for_rom(&rom)),
+            None => SuperShuckieStringArray::default()
+        };
+        Box::into_raw(Box::new(array))
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_get_save_backups(frontend: &SuperShuckieFrontend) -> *mut SuperShuckieStringArray {
+    ffi_guard("supershuckie_frontend_get_save_backups", || {
+        let array = match (frontend.get_current_rom_name(), frontend.get_current_save_name()) {
+            (Some(rom), Some(save)) => SuperShuckieStringArray(frontend.get_save_backups_for_rom(rom, save)),
+            _ => SuperShuckieStringArray::default()
+        };
+        Box::into_raw(Box::new(array))
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_restore_save_backup(
+    frontend: &mut SuperShuckieFrontend,
+    backup: *const c_char,
+    error: *mut u8,
+    error_len: usize
+) -> bool {
+    ffi_guard("supershuckie_frontend_restore_save_backup", || {
+        let backup = &*unsafe { c_str_lossy(backup) };
+        match frontend.restore_save_backup(backup) {
+            Ok(()) => true,
+            Err(e) => {
+                write_frontend_error(frontend, &e, unsafe { from_raw_parts_mut(error, error_len) });
+                false
+            }
+        }
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_get_save_profiles(
+    frontend: &SuperShuckieFrontend,
+    rom: *const c_char
+) -> *mut SuperShuckieStringArray {
+    ffi_guard("supershuckie_frontend_get_save_profiles", || {
+        let array = match unsafe { current_rom_or_null(frontend, rom) } {
+            Some(rom) => SuperShuckieStringArray(frontend.get_save_profiles_for_rom(&rom).into_iter().map(|p| p.name).collect()),
+            None => SuperShuckieStringArray::default()
+        };
+        Box::into_raw(Box::new(array))
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_get_save_profile_info(
+    frontend: &SuperShuckieFrontend,
+    rom: *const c_char,
+    name: *const c_char,
+    created: *mut u64,
+    last_used: *mut u64,
+    playtime_milliseconds: *mut u64
+) -> bool {
+    ffi_guard("supershuckie_frontend_get_save_profile_info", || {
+        let created = unsafe { nullable_reference!(created) };
+        let last_used = unsafe { nullable_reference!(last_used) };
+        let playtime_milliseconds = unsafe { nullable_reference!(playtime_milliseconds) };
+        let name = &*unsafe { c_str_lossy(name) };
+
+        *created = 0;
+        *last_used = 0;
+        *playtime_milliseconds = 0;
+
+        let Some(rom) = (unsafe { current_rom_or_null(frontend, rom) }) else {
+            return false
+        };
+        let Some(profile) = frontend.get_save_profiles_for_rom(&rom).into_iter().find(|p| p.name.as_str() == name) else {
+            return false
+        };
+
+        *created = profile.metadata.created.unwrap_or(0);
+        *last_used = profile.metadata.last_used.unwrap_or(0);
+        *playtime_milliseconds = profile.metadata.playtime_milliseconds;
+        true
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_create_save_profile(
+    frontend: &mut SuperShuckieFrontend,
+    name: *const c_char,
+    error: *mut u8,
+    error_len: usize
+) -> bool {
+    ffi_guard("supershuckie_frontend_create_save_profile", || {
+        let name = &*unsafe { c_str_lossy(name) };
+        match frontend.create_save_profile(name) {
+            Ok(()) => true,
+            Err(e) => {
+                write_frontend_error(frontend, &e, unsafe { from_raw_parts_mut(error, error_len) });
+                false
+            }
+        }
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_delete_save_profile(
+    frontend: &mut SuperShuckieFrontend,
+    name: *const c_char,
+    error: *mut u8,
+    error_len: usize
+) -> bool {
+    ffi_guard("supershuckie_frontend_delete_save_profile", || {
+        let name = &*unsafe { c_str_lossy(name) };
+        match frontend.delete_save_profile(name) {
+            Ok(()) => true,
+            Err(e) => {
+                write_frontend_error(frontend, &e, unsafe { from_raw_parts_mut(error, error_len) });
+                false
+            }
+        }
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_duplicate_save_profile(
+    frontend: &mut SuperShuckieFrontend,
+    name: *const c_char,
+    new_name: *const c_char,
+    error: *mut u8,
+    error_len: usize
+) -> bool {
+    ffi_guard("supershuckie_frontend_duplicate_save_profile", || {
+        let name = &*unsafe { c_str_lossy(name) };
+        let new_name = &*unsafe { c_str_lossy(new_name) };
+        match frontend.duplicate_save_profile(name, new_name) {
+            Ok(()) => true,
+            Err(e) => {
+                write_frontend_error(frontend, &e, unsafe { from_raw_parts_mut(error, error_len) });
+                false
+            }
+        }
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_rename_save_profile(
+    frontend: &mut SuperShuckieFrontend,
+    name: *const c_char,
+    new_name: *const c_char,
+    error: *mut u8,
+    error_len: usize
+) -> bool {
+    ffi_guard("supershuckie_frontend_rename_save_profile", || {
+        let name = &*unsafe { c_str_lossy(name) };
+        let new_name = &*unsafe { c_str_lossy(new_name) };
+        match frontend.rename_save_profile(name, new_name) {
+            Ok(()) => true,
+            Err(e) => {
+                write_frontend_error(frontend, &e, unsafe { from_raw_parts_mut(error, error_len) });
+                false
+            }
+        }
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_get_all_save_states_for_rom(
+    frontend: &SuperShuckieFrontend,
+    rom: *const c_char
+) -> *mut SuperShuckieStringArray {
+    ffi_guard("supershuckie_frontend_get_all_save_states_for_rom", || {
+        let array = match unsafe { current_rom_or_null(frontend, rom) } {
+            Some(rom) => SuperShuckieStringArray(frontend.get_all_save_states_for_rom(&rom)),
+            None => SuperShuckieStringArray::default()
+        };
+        Box::into_raw(Box::new(array))
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_get_quarantined_save_states_for_rom(
+    frontend: &SuperShuckieFrontend,
+    rom: *const c_char
+) -> *mut SuperShuckieStringArray {
+    ffi_guard("supershuckie_frontend_get_quarantined_save_states_for_rom", || {
+        let array = match unsafe { current_rom_or_null(frontend, rom) } {
+            Some(rom) => SuperShuckieStringArray(frontend.get_quarantined_save_states_for_rom(&rom).into_iter().map(|s| s.name).collect()),
+            None => SuperShuckieStringArray::default()
+        };
+        Box::into_raw(Box::new(array))
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_get_quarantined_save_state_reason(
+    frontend: &SuperShuckieFrontend,
+    rom: *const c_char,
+    name: *const c_char,
+    reason: *mut u8,
+    reason_len: usize
+) -> bool {
+    ffi_guard("supershuckie_frontend_get_quarantined_save_state_reason", || {
+        let name = &*unsafe { c_str_lossy(name) };
+        let Some(rom) = (unsafe { current_rom_or_null(frontend, rom) }) else {
+            return false
+        };
+        let Some(state) = frontend.get_quarantined_save_states_for_rom(&rom).into_iter().find(|s| s.name.as_str() == name) else {
+            return false
+        };
+
+        write_str_to_data(state.reason.as_str(), unsafe { from_raw_parts_mut(reason, reason_len) });
+        true
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_retry_quarantined_save_state(
+    frontend: &mut SuperShuckieFrontend,
+    name: *const c_char,
+    result: *mut u8,
+    result_len: usize
+) -> bool {
+    ffi_guard("supershuckie_frontend_retry_quarantined_save_state", || {
+        let name = &*unsafe { c_str_lossy(name) };
+        match frontend.retry_quarantined_save_state(name) {
+            Ok(n) => {
+                write_str_to_data(n.as_str(), unsafe { from_raw_parts_mut(result, result_len) });
+                true
+            },
+            Err(e) => {
+                write_frontend_error(frontend, &e, unsafe { from_raw_parts_mut(result, result_len) });
+                false
+            }
+        }
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_get_elapsed_time(
+    frontend: &SuperShuckieFrontend,
+    elapsed_frames: *mut u32,
+    elapsed_milliseconds: *mut u32
+) {
+    ffi_guard("supershuckie_frontend_get_elapsed_time", || {
+        let elapsed_frames = unsafe { nullable_reference!(elapsed_frames) };
+        let elapsed_milliseconds = unsafe { nullable_reference!(elapsed_milliseconds) };
+
+        *elapsed_milliseconds = frontend.get_elapsed_milliseconds();
+        *elapsed_frames = frontend.get_elapsed_frames();
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_get_frame_timings(
+    frontend: &SuperShuckieFrontend,
+    emulation_us: *mut u32,
+    screen_copy_us: *mut u32,
+    pokeabyte_us: *mut u32,
+    recording_us: *mut u32
+) -> u32 {
+    ffi_guard("supershuckie_frontend_get_frame_timings", || {
+        let emulation_us = unsafe { nullable_reference!(emulation_us) };
+        let screen_copy_us = unsafe { nullable_reference!(screen_copy_us) };
+        let pokeabyte_us = unsafe { nullable_reference!(pokeabyte_us) };
+        let recording_us = unsafe { nullable_reference!(recording_us) };
+
+        let timings = frontend.get_frame_timings();
+        *emulation_us = timings.emulation.as_micros() as u32;
+        *screen_copy_us = timings.screen_copy.as_micros() as u32;
+        *pokeabyte_us = timings.pokeabyte.as_micros() as u32;
+        *recording_us = timings.recording.as_micros() as u32;
+        timings.frames
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_get_playback_input(
+    frontend: &SuperShuckieFrontend,
+    buttons: *mut u32,
+    touch_x: *mut u16,
+    touch_y: *mut u16,
+    tilt_x: *mut i16,
+    tilt_y: *mut i16
+) -> bool {
+    ffi_guard("supershuckie_frontend_get_playback_input", || {
+        let buttons = unsafe { nullable_reference!(buttons) };
+        let touch_x = unsafe { nullable_reference!(touch_x) };
+        let touch_y = unsafe { nullable_reference!(touch_y) };
+        let tilt_x = unsafe { nullable_reference!(tilt_x) };
+        let tilt_y = unsafe { nullable_reference!(tilt_y) };
+
+        let Some(input) = frontend.get_playback_input() else {
+            return false
+        };
+
+        *buttons = InputDescriptor::of_input(&input).mask();
+        (*touch_x, *touch_y) = input.touch.unwrap_or_default();
+        (*tilt_x, *tilt_y) = input.tilt.unwrap_or_default();
+        true
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_get_playback_keyframes(
+    frontend: &SuperShuckieFrontend
+) -> *mut SuperShuckieReplayMarkerArray {
+    ffi_guard("supershuckie_frontend_get_playback_keyframes", || {
+        let array = SuperShuckieReplayMarkerArray::from_markers(frontend.get_playback_keyframes());
+        Box::into_raw(Box::new(array))
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_get_playback_bookmarks(
+    frontend: &SuperShuckieFrontend
+) -> *mut SuperShuckieReplayMarkerArray {
+    ffi_guard("supershuckie_frontend_get_playback_bookmarks", || {
+        let array = SuperShuckieReplayMarkerArray::from_markers(frontend.get_playback_bookmarks());
+        Box::into_raw(Box::new(array))
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_get_replay_playback_time(
+    frontend: &SuperShuckieFrontend,
+    total_frames: *mut u32,
+    total_milliseconds: *mut u32
+) -> bool {
+    ffi_guard("supershuckie_frontend_get_replay_playback_time", || {
+        let total_frames = unsafe { nullable_reference!(total_frames) };
+        let total_milliseconds = unsafe { nullable_reference!(total_milliseconds) };
+
+        match frontend.get_replay_playback_stats() {
+            Some(n) => {
+                *total_frames = n.total_frames;
+                *total_milliseconds = n.total_milliseconds;
+                true
+            },
+            None => {
+                *total_frames = 0;
+                *total_milliseconds = 0;
+                false
+            }
+        }
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_load_replay(
+    frontend: &mut SuperShuckieFrontend,
+    name: *const c_char,
+    override_errors: bool,
+    error: *mut u8,
+    error_len: usize
+) -> bool {
+    ffi_guard("supershuckie_frontend_load_replay", || {
+        let name = &*unsafe { c_str_lossy(name) };
+
+        match frontend.load_replay_if_exists(name, override_errors) {
+            Ok(_) => true,
+            Err(e) => {
+                write_frontend_error(frontend, &e, unsafe { from_raw_parts_mut(error, error_len) });
+                false
+            }
+        }
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_load_replay_acknowledging(
+    frontend: &mut SuperShuckieFrontend,
+    name: *const c_char,
+    acknowledged: u32,
+    remembered: u32,
+    cancel: *const SuperShuckieCancellationToken,
+    error: *mut u8,
+    error_len: usize
+) -> bool {
+    ffi_guard("supershuckie_frontend_load_replay_acknowledging", || {
+        let name = &*unsafe { c_str_lossy(name) };
+        let cancel = unsafe { cancel.as_ref() }.map(|c| &c.0);
+        let mismatch_kinds = |mask: u32| -> Vec<ReplayMismatchKind> {
+            ReplayMismatchKind::ALL.into_iter().filter(|k| mask & (*k as u32) != 0).collect()
+        };
+
+        match frontend.load_replay_acknowledging(name, &mismatch_kinds(acknowledged), &mismatch_kinds(remembered), cancel) {
+            Ok(_) => true,
+            Err(e) => {
+                write_frontend_error(frontend, &e, unsafe { from_raw_parts_mut(error, error_len) });
+                false
+            }
+        }
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_get_replay_mismatches(frontend: &SuperShuckieFrontend) -> u32 {
+    ffi_guard("supershuckie_frontend_get_replay_mismatches", || {
+        frontend.get_replay_mismatches().iter().fold(0, |mask, m| mask | m.kind as u32)
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_get_replay_mismatch_description(
+    frontend: &SuperShuckieFrontend,
+    kind: u32,
+    description: *mut u8,
+    description_len: usize
+) -> bool {
+    ffi_guard("supershuckie_frontend_get_replay_mismatch_description", || {
+        let Some(mismatch) = frontend.get_replay_mismatches().iter().find(|m| m.kind as u32 == kind) else {
+            return false
+        };
+        write_str_to_data(mismatch.description.as_str(), unsafe { from_raw_parts_mut(description, description_len) });
+        true
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_forget_replay_mismatches(
+    frontend: &mut SuperShuckieFrontend,
+    name: *const c_char
+) {
+    ffi_guard("supershuckie_frontend_forget_replay_mismatches", || {
+        let name = &*unsafe { c_str_lossy(name) };
+        frontend.forget_replay_mismatches(name);
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_verify_replay(
+    frontend: &mut SuperShuckieFrontend,
+    name: *const c_char,
+    frames: *mut u32,
+    error: *mut u8,
+    error_len: usize
+) -> bool {
+    ffi_guard("supershuckie_frontend_verify_replay", || {
+        let name = &*unsafe { c_str_lossy(name) };
+
+        match frontend.verify_replay(name) {
+            Ok(n) => {
+                if !frames.is_null() {
+                    unsafe { *frames = n };
+                }
+                true
+            },
+            Err(e) => {
+                write_frontend_error(frontend, &e, unsafe { from_raw_parts_mut(error, error_len) });
+                false
+            }
+        }
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_stop_replay_playback(
+    frontend: &mut SuperShuckieFrontend
+) {
+    ffi_guard("supershuckie_frontend_stop_replay_playback", || {
+        frontend.stop_replay_playback();
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_get_rom_stats(
+    frontend: &SuperShuckieFrontend,
+    rom: *const c_char,
+    playtime_milliseconds: *mut u64,
+    sessions: *mut u64,
+    last_played: *mut u64,
+    hard_resets: *mut u64
+) -> bool {
+    ffi_guard("supershuckie_frontend_get_rom_stats", || {
+        let playtime_milliseconds = unsafe { nullable_reference!(playtime_milliseconds) };
+        let sessions = unsafe { nullable_reference!(sessions) };
+        let last_played = unsafe { nullable_reference!(last_played) };
+        let hard_resets = unsafe { nullable_reference!(hard_resets) };
+
+        let Some(rom) = (unsafe { current_rom_or_null(frontend, rom) }) else {
+            *playtime_milliseconds = 0;
+            *sessions = 0;
+            *last_played = 0;
+            *hard_resets = 0;
+            return false
+        };
+
+        let stats = frontend.get_rom_stats(&rom);
+        *playtime_milliseconds = stats.playtime_milliseconds;
+        *sessions = stats.sessions;
+        *last_played = stats.last_played.unwrap_or(0);
+        *hard_resets = stats.hard_resets;
+        true
+    })
+}
+
+unsafe fn current_rom_or_null(frontend: &SuperShuckieFrontend, rom: *const c_char) -> Option<Cow<'_, str>> {
+    if rom.is_null() {
+        frontend.get_current_rom_name().map(Cow::Borrowed)
+    }
+    else {
+        Some(unsafe { c_str_lossy(rom) })
+    }
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_get_control_settings(
+    frontend: &SuperShuckieFrontend
+) -> *mut SuperShuckieControlSettings {
+    ffi_guard("supershuckie_frontend_get_control_settings", || {
+        Box::into_raw(Box::new(SuperShuckieControlSettings(frontend.get_control_settings().clone())))
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_set_control_settings(
+    frontend: &mut SuperShuckieFrontend,
+    settings: &SuperShuckieControlSettings
+) {
+    ffi_guard("supershuckie_frontend_set_control_settings", || {
+        frontend.set_control_settings(settings.0.clone())
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_reset_keyboard_controls_to_defaults(
+    frontend: &mut SuperShuckieFrontend
+) {
+    ffi_guard("supershuckie_frontend_reset_keyboard_controls_to_defaults", || {
+        frontend.reset_keyboard_controls_to_defaults()
+    })
+}
+
+pub type SuperShuckieRemapKeycodeC = unsafe extern "C" fn(userdata: *mut c_void, keycode: i32) -> i32;
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_remap_keyboard_controls(
+    frontend: &mut SuperShuckieFrontend,
+    remap: Option<SuperShuckieRemapKeycodeC>,
+    userdata: *mut c_void
+) -> usize {
+    ffi_guard("supershuckie_frontend_remap_keyboard_controls", || {
+        match remap {
+            Some(remap) => frontend.remap_keyboard_controls(|keycode| unsafe { remap(userdata, keycode) }).len(),
+            None => 0
+        }
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_get_rom_control_settings(
+    frontend: &SuperShuckieFrontend,
+    rom: *const c_char
+) -> *mut SuperShuckieControlSettings {
+    ffi_guard("supershuckie_frontend_get_rom_control_settings", || {
+        let Some(controls) = unsafe { current_rom_or_null(frontend, rom) }.and_then(|rom| frontend.get_rom_control_settings(&rom)) else {
+            return null_mut()
+        };
+        Box::into_raw(Box::new(SuperShuckieControlSettings(controls.clone())))
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_set_rom_control_settings(
+    frontend: &mut SuperShuckieFrontend,
+    rom: *const c_char,
+    settings: *const SuperShuckieControlSettings
+) {
+    ffi_guard("supershuckie_frontend_set_rom_control_settings", || {
+        let Some(rom) = (unsafe { current_rom_or_null(frontend, rom) }).map(Cow::into_owned) else {
+            return
+        };
+        let controls = unsafe { settings.as_ref() }.map(|i| i.0.clone());
+        frontend.set_rom_control_settings(&rom, controls)
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_copy_control_settings_to_rom(
+    frontend: &mut SuperShuckieFrontend,
+    rom: *const c_char
+) {
+    ffi_guard("supershuckie_frontend_copy_control_settings_to_rom", || {
+        let Some(rom) = (unsafe { current_rom_or_null(frontend, rom) }).map(Cow::into_owned) else {
+            return
+        };
+        frontend.copy_control_settings_to_rom(&rom)
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_get_rom_input_delay(
+    frontend: &SuperShuckieFrontend,
+    rom: *const c_char
+) -> u8 {
+    ffi_guard("supershuckie_frontend_get_rom_input_delay", || {
+        unsafe { current_rom_or_null(frontend, rom) }.map(|rom| frontend.get_rom_input_delay(&rom)).unwrap_or(0)
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_set_rom_input_delay(
+    frontend: &mut SuperShuckieFrontend,
+    rom: *const c_char,
+    frames: u8
+) {
+    ffi_guard("supershuckie_frontend_set_rom_input_delay", || {
+        let Some(rom) = (unsafe { current_rom_or_null(frontend, rom) }).map(Cow::into_owned) else {
+            return
+        };
+        frontend.set_rom_input_delay(&rom, frames)
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_get_rom_cheats(
+    frontend: &SuperShuckieFrontend,
+    rom: *const c_char
+) -> *mut SuperShuckieStringArray {
+    ffi_guard("supershuckie_frontend_get_rom_cheats", || {
+        let codes = unsafe { current_rom_or_null(frontend, rom) }
+            .map(|rom| frontend.get_rom_cheats(&rom).iter().map(|c| c.code.clone()).collect())
+            .unwrap_or_default();
+        Box::into_raw(Box::new(SuperShuckieStringArray(codes)))
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_get_rom_cheat_info(
+    frontend: &SuperShuckieFrontend,
+    rom: *const c_char,
+    index: usize,
+    description: *mut u8,
+    description_len: usize,
+    enabled: *mut bool
+) -> bool {
+    ffi_guard("supershuckie_frontend_get_rom_cheat_info", || {
+        let enabled = unsafe { nullable_reference!(enabled) };
+        *enabled = false;
+
+        let Some(rom) = (unsafe { current_rom_or_null(frontend, rom) }) else {
+            return false
+        };
+        let Some(cheat) = frontend.get_rom_cheats(&rom).get(index) else {
+            return false
+        };
+
+        write_str_to_data(cheat.description.as_str(), unsafe { from_raw_parts_mut(description, description_len) });
+        *enabled = cheat.enabled;
+        true
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_add_rom_cheat(
+    frontend: &mut SuperShuckieFrontend,
+    rom: *const c_char,
+    code: *const c_char,
+    description: *const c_char,
+    error: *mut u8,
+    error_len: usize
+) -> bool {
+    ffi_guard("supershuckie_frontend_add_rom_cheat", || {
+        let Some(rom) = (unsafe { current_rom_or_null(frontend, rom) }).map(Cow::into_owned) else {
+            write_frontend_error(frontend, &LocalizedError::new("game_not_running"), unsafe { from_raw_parts_mut(error, error_len) });
+            return false
+        };
+        let code = &*unsafe { c_str_lossy(code) };
+        let description = if description.is_null() { Cow::Borrowed("") } else { unsafe { c_str_lossy(description) } };
+
+        match frontend.add_rom_cheat(&rom, code, &description) {
+            Ok(()) => true,
+            Err(e) => {
+                write_frontend_error(frontend, &e, unsafe { from_raw_parts_mut(error, error_len) });
+                false
+            }
+        }
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_remove_rom_cheat(
+    frontend: &mut SuperShuckieFrontend,
+    rom: *const c_char,
+    index: usize
+) -> bool {
+    ffi_guard("supershuckie_frontend_remove_rom_cheat", || {
+        let Some(rom) = (unsafe { current_rom_or_null(frontend, rom) }).map(Cow::into_owned) else {
+            return false
+        };
+        frontend.remove_rom_cheat(&rom, index)
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_set_rom_cheat_enabled(
+    frontend: &mut SuperShuckieFrontend,
+    rom: *const c_char,
+    index: usize,
+    enabled: bool,
+    error: *mut u8,
+    error_len: usize
+) -> bool {
+    ffi_guard("supershuckie_frontend_set_rom_cheat_enabled", || {
+        let Some(rom) = (unsafe { current_rom_or_null(frontend, rom) }).map(Cow::into_owned) else {
+            write_frontend_error(frontend, &LocalizedError::new("game_not_running"), unsafe { from_raw_parts_mut(error, error_len) });
+            return false
+        };
+
+        match frontend.set_rom_cheat_enabled(&rom, index, enabled) {
+            Ok(()) => true,
+            Err(e) => {
+                write_frontend_error(frontend, &e, unsafe { from_raw_parts_mut(error, error_len) });
+                false
+            }
+        }
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_get_connected_controllers(
+    frontend: &SuperShuckieFrontend
+) -> *mut SuperShuckieStringArray {
+    ffi_guard("supershuckie_frontend_get_connected_controllers", || {
+        Box::into_raw(Box::new(SuperShuckieStringArray(frontend.get_connected_controllers())))
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_connect_controller(
+    frontend: &mut SuperShuckieFrontend,
+    controller: *mut c_char
+) -> ConnectedControllerIndex {
+    ffi_guard("supershuckie_frontend_connect_controller", || {
+        let controller_name = &*unsafe { c_str_lossy(controller) };
+        frontend.connect_controller(controller_name)
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_disconnect_controller(
+    frontend: &mut SuperShuckieFrontend,
+    controller: ConnectedControllerIndex
+) {
+    ffi_guard("supershuckie_frontend_disconnect_controller", || {
+        frontend.disconnect_controller(controller);
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_set_controller_guid(
+    frontend: &mut SuperShuckieFrontend,
+    controller: ConnectedControllerIndex,
+    guid: *const c_char
+) {
+    ffi_guard("supershuckie_frontend_set_controller_guid", || {
+        let guid = &*unsafe { c_str_lossy(guid) };
+        frontend.set_controller_guid(controller, guid);
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_export_control_profile(
+    frontend: &SuperShuckieFrontend,
+    path: *const c_char,
+    rom: *const c_char,
+    controller: *const c_char,
+    name: *const c_char,
+    error: *mut u8,
+    error_len: usize
+) -> bool {
+    ffi_guard("supershuckie_frontend_export_control_profile", || {
+        let path = &*unsafe { c_str_lossy(path) };
+        let rom = if !rom.is_null() { Some(&*unsafe { c_str_lossy(rom) }) } else { None };
+        let controller = if !controller.is_null() { Some(&*unsafe { c_str_lossy(controller) }) } else { None };
+        let name = &*unsafe { c_str_lossy(name) };
+
+        match frontend.export_control_profile(Path::new(path), rom, controller, name) {
+            Ok(()) => true,
+            Err(e) => {
+                write_frontend_error(frontend, &e, unsafe { from_raw_parts_mut(error, error_len) });
+                false
+            }
+        }
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_install_control_profile(
+    frontend: &mut SuperShuckieFrontend,
+    path: *const c_char,
+    rom: *const c_char,
+    result: *mut u8,
+    result_len: usize
+) -> bool {
+    ffi_guard("supershuckie_frontend_install_control_profile", || {
+        let path = &*unsafe { c_str_lossy(path) };
+        let rom = if !rom.is_null() { Some(&*unsafe { c_str_lossy(rom) }) } else { None };
+
+        match frontend.install_control_profile(Path::new(path), rom) {
+            Ok(n) => {
+                write_str_to_data(n.as_str(), unsafe { from_raw_parts_mut(result, result_len) });
+                true
+            },
+            Err(e) => {
+                write_frontend_error(frontend, &e, unsafe { from_raw_parts_mut(result, result_len) });
+                false
+            }
+        }
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_export_game_bundle(
+    frontend: &SuperShuckieFrontend,
+    path: *const c_char,
+    notes: *const c_char,
+    error: *mut u8,
+    error_len: usize
+) -> bool {
+    ffi_guard("supershuckie_frontend_export_game_bundle", || {
+        let path = &*unsafe { c_str_lossy(path) };
+        let notes = if !notes.is_null() { &*unsafe { c_str_lossy(notes) } } else { "" };
+
+        match frontend.export_game_bundle(Path::new(path), notes) {
+            Ok(()) => true,
+            Err(e) => {
+                write_frontend_error(frontend, &e, unsafe { from_raw_parts_mut(error, error_len) });
+                false
+            }
+        }
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_import_game_bundle(
+    frontend: &mut SuperShuckieFrontend,
+    path: *const c_char,
+    result: *mut u8,
+    result_len: usize
+) -> bool {
+    ffi_guard("supershuckie_frontend_import_game_bundle", || {
+        let path = &*unsafe { c_str_lossy(path) };
+
+        match frontend.import_game_bundle(Path::new(path)) {
+            Ok(n) => {
+                write_str_to_data(n.as_str(), unsafe { from_raw_parts_mut(result, result_len) });
+                true
+            },
+            Err(e) => {
+                write_frontend_error(frontend, &e, unsafe { from_raw_parts_mut(result, result_len) });
+                false
+            }
+        }
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_list_bookmarks(
+    frontend: &SuperShuckieFrontend
+) -> *mut SuperShuckieReplayMarkerArray {
+    ffi_guard("supershuckie_frontend_list_bookmarks", || {
+        let array = SuperShuckieReplayMarkerArray::from_markers(frontend.get_bookmarks());
+        Box::into_raw(Box::new(array))
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_add_bookmark(
+    frontend: &mut SuperShuckieFrontend,
+    name: *const c_char
+) {
+    ffi_guard("supershuckie_frontend_add_bookmark", || {
+        let name = &*unsafe { c_str_lossy(name) };
+        frontend.add_replay_bookmark(name);
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_get_name_of_controller(
+    frontend: &SuperShuckieFrontend,
+    controller: ConnectedControllerIndex
+) -> *const c_char {
+    ffi_guard("supershuckie_frontend_get_name_of_controller", || {
+        frontend.name_of_controller_c_str(controller).map(|i| i.as_ptr()).unwrap_or(null())
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_set_playback_frame(
+    frontend: &mut SuperShuckieFrontend,
+    frame: u32
+) {
+    ffi_guard("supershuckie_frontend_set_playback_frame", || {
+        frontend.go_to_replay_frame(frame)
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_set_playback_frame_cancellable(
+    frontend: &mut SuperShuckieFrontend,
+    frame: u32,
+    cancel: &SuperShuckieCancellationToken
+) {
+    ffi_guard("supershuckie_frontend_set_playback_frame_cancellable", || {
+        frontend.go_to_replay_frame_cancellable(frame, cancel.0.clone())
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_advance_playback_frames(
+    frontend: &mut SuperShuckieFrontend,
+    frames: i32
+) {
+    ffi_guard("supershuckie_frontend_advance_playback_frames", || {
+        frontend.advance_playback_frames(frames)
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_frame_advance(
+    frontend: &mut SuperShuckieFrontend
+) {
+    ffi_guard("supershuckie_frontend_frame_advance", || {
+        frontend.frame_advance()
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_rewind_hold(
+    frontend: &mut SuperShuckieFrontend,
+    held: bool
+) {
+    ffi_guard("supershuckie_frontend_rewind_hold", || {
+        frontend.set_rewinding(held)
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_is_rewind_enabled(frontend: &SuperShuckieFrontend) -> bool {
+    ffi_guard("supershuckie_frontend_is_rewind_enabled", || {
+        frontend.is_rewind_enabled()
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_set_rewind_enabled(frontend: &mut SuperShuckieFrontend, enabled: bool) {
+    ffi_guard("supershuckie_frontend_set_rewind_enabled", || {
+        frontend.set_rewind_enabled(enabled);
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_get_rewind_interval_frames(frontend: &SuperShuckieFrontend) -> u64 {
+    ffi_guard("supershuckie_frontend_get_rewind_interval_frames", || {
+        frontend.get_rewind_interval_frames().get()
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_set_rewind_interval_frames(frontend: &mut SuperShuckieFrontend, frames: u64) {
+    ffi_guard("supershuckie_frontend_set_rewind_interval_frames", || {
+        frontend.set_rewind_interval_frames(NonZeroU64::new(frames).unwrap_or(NonZeroU64::MIN));
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_get_rewind_memory_budget_mib(frontend: &SuperShuckieFrontend) -> u32 {
+    ffi_guard("supershuckie_frontend_get_rewind_memory_budget_mib", || {
+        frontend.get_rewind_memory_budget_mib().get()
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_set_rewind_memory_budget_mib(frontend: &mut SuperShuckieFrontend, mib: u32) {
+    ffi_guard("supershuckie_frontend_set_rewind_memory_budget_mib", || {
+        frontend.set_rewind_memory_budget_mib(NonZeroU32::new(mib).unwrap_or(NonZeroU32::MIN));
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_set_playback_frozen(
+    frontend: &mut SuperShuckieFrontend,
+    paused: bool
+) {
+    ffi_guard("supershuckie_frontend_set_playback_frozen", || {
+        frontend.set_playback_frozen(paused)
+    })
+}
+
+#[repr(C)]
+pub enum SuperShuckieReplayState {
+    NoReplay,
+    Recording,
+    Playback
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_get_replay_state(
+    frontend: &SuperShuckieFrontend
+) -> SuperShuckieReplayState {
+    ffi_guard("supershuckie_frontend_get_replay_state", || {
+        if frontend.get_replay_playback_stats().is_some() {
+            SuperShuckieReplayState::Playback
+        }
+        else if frontend.get_replay_file_info().is_some() {
+            SuperShuckieReplayState::Recording
+        }
+        else {
+            SuperShuckieReplayState::NoReplay
+        }
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_get_gbc_mode(frontend: &SuperShuckieFrontend) -> GameBoyMode {
+    ffi_guard("supershuckie_frontend_get_gbc_mode", || {
+        frontend.get_gbc_mode()
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_set_gbc_mode(frontend: &mut SuperShuckieFrontend, mode: u32) {
+    ffi_guard("supershuckie_frontend_set_gbc_mode", || {
+        if let Ok(m) = GameBoyMode::try_from(mode) {
+            frontend.set_gbc_mode(m)
+        }
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_is_sgb_enabled(frontend: &SuperShuckieFrontend) -> bool {
+    ffi_guard("supershuckie_frontend_is_sgb_enabled", || {
+        frontend.is_sgb_enabled()
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_set_sgb_enabled(frontend: &mut SuperShuckieFrontend, enabled: bool) {
+    ffi_guard("supershuckie_frontend_set_sgb_enabled", || {
+        frontend.set_sgb_enabled(enabled);
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_get_color_correction(frontend: &SuperShuckieFrontend) -> ColorCorrection {
+    ffi_guard("supershuckie_frontend_get_color_correction", || {
+        frontend.get_color_correction()
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_set_color_correction(frontend: &mut SuperShuckieFrontend, color_correction: u32) {
+    ffi_guard("supershuckie_frontend_set_color_correction", || {
+        if let Ok(c) = ColorCorrection::try_from(color_correction) {
+            frontend.set_color_correction(c)
+        }
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_get_light_temperature(frontend: &SuperShuckieFrontend) -> f64 {
+    ffi_guard("supershuckie_frontend_get_light_temperature", || {
+        frontend.get_light_temperature()
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_set_light_temperature(frontend: &mut SuperShuckieFrontend, light_temperature: f64) {
+    ffi_guard("supershuckie_frontend_set_light_temperature", || {
+        frontend.set_light_temperature(light_temperature);
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_set_language(
+    frontend: &mut SuperShuckieFrontend,
+    language: *const c_char,
+    error: *mut u8,
+    error_len: usize
+) -> bool {
+    ffi_guard("supershuckie_frontend_set_language", || {
+        let language = &*unsafe { c_str_lossy(language) };
+        match frontend.set_language(language) {
+            Ok(()) => true,
+            Err(e) => {
+                write_frontend_error(frontend, &e, unsafe { from_raw_parts_mut(error, error_len) });
+                false
+            }
+        }
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_get_language(frontend: &SuperShuckieFrontend) -> *const c_char {
+    ffi_guard("supershuckie_frontend_get_language", || {
+        frontend.get_language().as_c_str().as_ptr()
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_get_available_languages(frontend: &SuperShuckieFrontend) -> *mut SuperShuckieStringArray {
+    ffi_guard("supershuckie_frontend_get_available_languages", || {
+        Box::into_raw(Box::new(SuperShuckieStringArray(frontend.get_available_languages())))
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_get_message(
+    frontend: &SuperShuckieFrontend,
+    key: *const c_char,
+    message: *mut u8,
+    message_len: usize
+) -> bool {
+    ffi_guard("supershuckie_frontend_get_message", || {
+        let key = &*unsafe { c_str_lossy(key) };
+        let Some(template) = frontend.get_messages().get(key) else {
+            return false
+        };
+        write_str_to_data(template, unsafe { from_raw_parts_mut(message, message_len) });
+        true
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_get_core_option_keys(frontend: &SuperShuckieFrontend) -> *mut SuperShuckieStringArray {
+    ffi_guard("supershuckie_frontend_get_core_option_keys", || {
+        let keys = frontend.get_core_options().iter().map(|o| o.key.into()).collect();
+        Box::into_raw(Box::new(SuperShuckieStringArray(keys)))
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_get_core_option_names(frontend: &SuperShuckieFrontend) -> *mut SuperShuckieStringArray {
+    ffi_guard("supershuckie_frontend_get_core_option_names", || {
+        let names = frontend.get_core_options().iter().map(|o| o.name.into()).collect();
+        Box::into_raw(Box::new(SuperShuckieStringArray(names)))
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_get_core_option_values(
+    frontend: &SuperShuckieFrontend,
+    key: *const c_char,
+    current: *mut usize
+) -> *mut SuperShuckieStringArray {
+    ffi_guard("supershuckie_frontend_get_core_option_values", || {
+        let key = &*unsafe { c_str_lossy(key) };
+        let options = frontend.get_core_options();
+        let array = match options.iter().find(|o| o.key == key) {
+            Some(option) => {
+                if !current.is_null() {
+                    let index = option.values.iter().position(|v| *v == option.value).unwrap_or(0);
+                    unsafe { *current = index };
+                }
+                SuperShuckieStringArray(option.values.iter().map(|v| (*v).into()).collect())
+            },
+            None => SuperShuckieStringArray::default()
+        };
+        Box::into_raw(Box::new(array))
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_set_core_option(
+    frontend: &mut SuperShuckieFrontend,
+    key: *const c_char,
+    value: *const c_char,
+    error: *mut u8,
+    error_len: usize
+) -> bool {
+    ffi_guard("supershuckie_frontend_set_core_option", || {
+        let key = &*unsafe { c_str_lossy(key) };
+        let value = &*unsafe { c_str_lossy(value) };
+        match frontend.set_core_option(key, value) {
+            Ok(_) => true,
+            Err(e) => {
+                write_frontend_error(frontend, &e, unsafe { from_raw_parts_mut(error, error_len) });
+                false
+            }
+        }
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_is_frame_blending_enabled(frontend: &SuperShuckieFrontend) -> bool {
+    ffi_guard("supershuckie_frontend_is_frame_blending_enabled", || {
+        frontend.is_frame_blending_enabled()
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_set_frame_blending_enabled(frontend: &mut SuperShuckieFrontend, enabled: bool) {
+    ffi_guard("supershuckie_frontend_set_frame_blending_enabled", || {
+        frontend.set_frame_blending_enabled(enabled);
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_is_onion_skin_enabled(frontend: &SuperShuckieFrontend) -> bool {
+    ffi_guard("supershuckie_frontend_is_onion_skin_enabled", || {
+        frontend.is_onion_skin_enabled()
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_set_onion_skin_enabled(frontend: &mut SuperShuckieFrontend, enabled: bool) {
+    ffi_guard("supershuckie_frontend_set_onion_skin_enabled", || {
+        frontend.set_onion_skin_enabled(enabled);
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_get_onion_skin_frames(frontend: &SuperShuckieFrontend) -> u8 {
+    ffi_guard("supershuckie_frontend_get_onion_skin_frames", || {
+        frontend.get_onion_skin_frames().get()
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_set_onion_skin_frames(frontend: &mut SuperShuckieFrontend, frames: u8) {
+    ffi_guard("supershuckie_frontend_set_onion_skin_frames", || {
+        frontend.set_onion_skin_frames(NonZeroU8::new(frames).unwrap_or(unsafe { NonZeroU8::new_unchecked(1) }));
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_get_onion_skin_opacity(frontend: &SuperShuckieFrontend) -> f64 {
+    ffi_guard("supershuckie_frontend_get_onion_skin_opacity", || {
+        frontend.get_onion_skin_opacity()
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_set_onion_skin_opacity(frontend: &mut SuperShuckieFrontend, opacity: f64) {
+    ffi_guard("supershuckie_frontend_set_onion_skin_opacity", || {
+        frontend.set_onion_skin_opacity(opacity);
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_get_input_descriptor(frontend: &SuperShuckieFrontend) -> u32 {
+    ffi_guard("supershuckie_frontend_get_input_descriptor", || {
+        frontend.get_input_descriptor().mask()
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_is_control_supported(frontend: &SuperShuckieFrontend, control: u32) -> bool {
+    ffi_guard("supershuckie_frontend_is_control_supported", || {
+        Control::try_from(control).is_ok_and(|i| frontend.is_control_supported(i))
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_is_printer_connected(frontend: &SuperShuckieFrontend) -> bool {
+    ffi_guard("supershuckie_frontend_is_printer_connected", || {
+        frontend.is_printer_connected()
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_set_printer_connected(frontend: &mut SuperShuckieFrontend, connected: bool) {
+    ffi_guard("supershuckie_frontend_set_printer_connected", || {
+        frontend.set_printer_connected(connected);
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_set_audio_sample_rate(frontend: &mut SuperShuckieFrontend, sample_rate: u32) -> bool {
+    ffi_guard("supershuckie_frontend_set_audio_sample_rate", || {
+        frontend.set_audio_sample_rate(sample_rate)
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_get_audio_sample_rate(frontend: &SuperShuckieFrontend) -> u32 {
+    ffi_guard("supershuckie_frontend_get_audio_sample_rate", || {
+        frontend.get_audio_sample_rate()
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_start_recording_audio(
+    frontend: &mut SuperShuckieFrontend,
+    name: *const c_char,
+    result: *mut u8,
+    result_len: usize
+) -> bool {
+    ffi_guard("supershuckie_frontend_start_recording_audio", || {
+        let name = if !name.is_null() { Some(&*unsafe { c_str_lossy(name) }) } else { None };
+        match frontend.start_recording_audio(name) {
+            Ok(n) => {
+                write_str_to_data(n.as_str(), unsafe { from_raw_parts_mut(result, result_len) });
+                true
+            },
+            Err(e) => {
+                write_frontend_error(frontend, &e, unsafe { from_raw_parts_mut(result, result_len) });
+                false
+            }
+        }
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_stop_recording_audio(frontend: &mut SuperShuckieFrontend) {
+    ffi_guard("supershuckie_frontend_stop_recording_audio", || {
+        frontend.stop_recording_audio()
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_is_recording_audio(frontend: &SuperShuckieFrontend) -> bool {
+    ffi_guard("supershuckie_frontend_is_recording_audio", || {
+        frontend.is_recording_audio()
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_set_serial_capture_enabled(frontend: &mut SuperShuckieFrontend, enabled: bool) -> bool {
+    ffi_guard("supershuckie_frontend_set_serial_capture_enabled", || {
+        frontend.set_serial_capture_enabled(enabled)
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_take_serial_output(
+    frontend: &mut SuperShuckieFrontend,
+    buffer: *mut u8,
+    buffer_len: usize
+) -> usize {
+    ffi_guard("supershuckie_frontend_take_serial_output", || {
+        if buffer_len == 0 {
+            return 0
+        }
+        let output = frontend.take_serial_output(buffer_len);
+        let buffer = unsafe { from_raw_parts_mut(buffer, buffer_len) };
+        buffer[..output.len()].copy_from_slice(&output);
+        output.len()
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_run_serial_test(
+    frontend: &mut SuperShuckieFrontend,
+    max_frames: u32,
+    verdict: *mut u32,
+    frames: *mut u32,
+    output: *mut u8,
+    output_len: usize
+) -> bool {
+    ffi_guard("supershuckie_frontend_run_serial_test", || {
+        let result = match frontend.run_serial_test(max_frames) {
+            Ok(result) => result,
+            Err(e) => {
+                let output: &mut [u8] = if output_len > 0 { unsafe { from_raw_parts_mut(output, output_len) } } else { &mut [] };
+                write_frontend_error(frontend, &e, output);
+                return false
+            }
+        };
+
+        if !verdict.is_null() {
+            unsafe { *verdict = result.verdict as u32 };
+        }
+        if !frames.is_null() {
+            unsafe { *frames = result.frames };
+        }
+        if output_len > 0 {
+            write_str_to_data(&String::from_utf8_lossy(&result.output), unsafe { from_raw_parts_mut(output, output_len) });
+        }
+        true
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_run_test_suite(
+    frontend: &SuperShuckieFrontend,
+    directory: *const c_char,
+    max_frames: u32,
+    report_path: *const c_char,
+    previous_report_path: *const c_char,
+    passed: *mut u32,
+    failed: *mut u32,
+    regressions: *mut u32,
+    error: *mut u8,
+    error_len: usize
+) -> bool {
+    ffi_guard("supershuckie_frontend_run_test_suite", || {
+        let directory = &*unsafe { c_str_lossy(directory) };
+
+        let result = frontend.run_test_suite(directory, max_frames).and_then(|report| {
+            let previous = if previous_report_path.is_null() {
+                None
+            }
+            else {
+                let path = &*unsafe { c_str_lossy(previous_report_path) };
+                let json = std::fs::read_to_string(path)
+                    .map_err(|e| LocalizedError::new("test_report_read_failed").arg("path", path).arg("error", e))?;
+                Some(TestSuiteReport::from_json(&json).map_err(|e| LocalizedError::new("test_report_invalid").arg("error", e))?)
+            };
+
+            if !report_path.is_null() {
+                let path = &*unsafe { c_str_lossy(report_path) };
+                std::fs::write(path, report.to_json())
+                    .map_err(|e| LocalizedError::new("test_report_write_failed").arg("path", path).arg("error", e))?;
+            }
+
+            Ok((report, previous))
+        });
+
+        match result {
+            Ok((report, previous)) => {
+                if !passed.is_null() {
+                    unsafe { *passed = report.passed() as u32 };
+                }
+                if !failed.is_null() {
+                    unsafe { *failed = report.failed() as u32 };
+                }
+                if !regressions.is_null() {
+                    unsafe { *regressions = previous.map(|p| report.regressions(&p).len() as u32).unwrap_or(0) };
+                }
+                true
+            },
+            Err(e) => {
+                write_frontend_error(frontend, &e, unsafe { from_raw_parts_mut(error, error_len) });
+                false
+            }
+        }
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_get_storage_dir(frontend: &SuperShuckieFrontend, category: u32) -> *const c_char {
+    ffi_guard("supershuckie_frontend_get_storage_dir", || {
+        StorageCategory::try_from(category)
+            .ok()
+            .and_then(|i| frontend.get_storage_dir(i))
+            .map(|i| i.as_c_str().as_ptr())
+            .unwrap_or(null())
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_set_watch_storage_setting(frontend: &mut SuperShuckieFrontend, watch: bool) {
+    ffi_guard("supershuckie_frontend_set_watch_storage_setting", || {
+        frontend.set_watch_storage_setting(watch);
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_get_watch_storage_setting(frontend: &SuperShuckieFrontend) -> bool {
+    ffi_guard("supershuckie_frontend_get_watch_storage_setting", || {
+        frontend.get_watch_storage_setting()
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_set_key_user_dirs_by_checksum_setting(
+    frontend: &mut SuperShuckieFrontend,
+    key_by_checksum: bool,
+    error: *mut u8,
+    error_len: usize
+) -> bool {
+    ffi_guard("supershuckie_frontend_set_key_user_dirs_by_checksum_setting", || {
+        match frontend.set_key_user_dirs_by_checksum_setting(key_by_checksum) {
+            Ok(()) => true,
+            Err(e) => {
+                write_frontend_error(frontend, &e, unsafe { from_raw_parts_mut(error, error_len) });
+                false
+            }
+        }
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_get_key_user_dirs_by_checksum_setting(frontend: &SuperShuckieFrontend) -> bool {
+    ffi_guard("supershuckie_frontend_get_key_user_dirs_by_checksum_setting", || {
+        frontend.get_key_user_dirs_by_checksum_setting()
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_migrate_user_dirs(
+    frontend: &mut SuperShuckieFrontend,
+    rom_dir: *const c_char,
+    moved: *mut u32
+) -> *mut SuperShuckieStringArray {
+    ffi_guard("supershuckie_frontend_migrate_user_dirs", || {
+        let migration = if rom_dir.is_null() {
+            frontend.migrate_user_dirs::<&str>(&[])
+        }
+        else {
+            frontend.migrate_user_dirs(&[&*unsafe { c_str_lossy(rom_dir) }])
+        };
+
+        if !moved.is_null() {
+            unsafe { *moved = migration.moved.len() as u32 };
+        }
+        Box::into_raw(Box::new(SuperShuckieStringArray(migration.skipped)))
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_set_storage_dir(
+    frontend: &mut SuperShuckieFrontend,
+    category: u32,
+    dir: *const c_char,
+    error: *mut u8,
+    error_len: usize
+) -> bool {
+    ffi_guard("supershuckie_frontend_set_storage_dir", || {
+        let result = match StorageCategory::try_from(category) {
+            Ok(category) => {
+                let dir = if dir.is_null() { None } else { Some(UTF8CString::from_cstr(unsafe { CStr::from_ptr(dir) })) };
+                frontend.set_storage_dir(category, dir)
+            },
+            Err(_) => Err(LocalizedError::new("storage_category_unknown").arg("category", category))
+        };
+
+        match result {
+            Ok(()) => true,
+            Err(e) => {
+                write_frontend_error(frontend, &e, unsafe { from_raw_parts_mut(error, error_len) });
+                false
+            }
+        }
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_optimize_replay_compression_level(
+    frontend: &mut SuperShuckieFrontend,
+    level: *mut i32,
+    error: *mut u8,
+    error_len: usize
+) -> bool {
+    ffi_guard("supershuckie_frontend_optimize_replay_compression_level", || {
+        let level = unsafe { nullable_reference!(level) };
+        match frontend.optimize_replay_compression_level() {
+            Ok(n) => {
+                *level = n;
+                true
+            },
+            Err(e) => {
+                write_frontend_error(frontend, &e, unsafe { from_raw_parts_mut(error, error_len) });
+                false
+            }
+        }
+    })
 }
@@ -5,6 +5,11 @@ macro_rules! nullable_reference {
     };
 }
 
+pub mod ffi;
 pub mod frontend;
 pub mod string_array;
+pub mod replay_marker_array;
+pub mod replay_info_array;
 pub mod control_settings;
+pub mod cancellation_token;
+pub mod visualizer;
@@ -0,0 +1,63 @@
+use std::ffi::c_char;
+use std::ptr::null;
+use supershuckie_frontend::replay_browser::ReplayBrowserEntry;
+use crate::ffi::ffi_guard;
+
+#[derive(Default)]
+pub struct SuperShuckieReplayInfoArray(pub Vec<ReplayBrowserEntry>);
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_replayinfoarray_len(
+    arr: &SuperShuckieReplayInfoArray
+) -> usize {
+    ffi_guard("supershuckie_replayinfoarray_len", || {
+        arr.0.len()
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_replayinfoarray_get(
+    arr: &SuperShuckieReplayInfoArray,
+    element: usize,
+    total_frames: *mut u32,
+    total_milliseconds: *mut u32,
+    size: *mut u64,
+    created: *mut u64,
+    console_type: *mut u32,
+    compatibility: *mut u32,
+    mismatches: *mut u32
+) -> *const c_char {
+    ffi_guard("supershuckie_replayinfoarray_get", || {
+        let Some(entry) = arr.0.get(element) else {
+            return null()
+        };
+
+        let total_frames = unsafe { nullable_reference!(total_frames) };
+        let total_milliseconds = unsafe { nullable_reference!(total_milliseconds) };
+        let size = unsafe { nullable_reference!(size) };
+        let created = unsafe { nullable_reference!(created) };
+        let console_type = unsafe { nullable_reference!(console_type) };
+        let compatibility = unsafe { nullable_reference!(compatibility) };
+        let mismatches = unsafe { nullable_reference!(mismatches) };
+
+        *total_frames = entry.total_frames;
+        *total_milliseconds = entry.total_milliseconds;
+        *size = entry.size;
+        *created = entry.created.unwrap_or(0);
+        *console_type = entry.console_type.into();
+        *compatibility = entry.compatibility as u32;
+        *mismatches = entry.mismatches.iter().fold(0, |mask, m| mask | *m as u32);
+        entry.name.as_c_str().as_ptr()
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_replayinfoarray_free(
+    arr: *mut SuperShuckieReplayInfoArray
+) {
+    ffi_guard("supershuckie_replayinfoarray_free", || {
+        if !arr.is_null() {
+            let _ = unsafe { Box::from_raw(arr) };
+        }
+    })
+}
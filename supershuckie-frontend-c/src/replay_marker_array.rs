@@ -0,0 +1,72 @@
+use std::ffi::c_char;
+use std::ptr::null;
+use supershuckie_core::ReplayMarker;
+use supershuckie_frontend::util::UTF8CString;
+use crate::ffi::ffi_guard;
+
+pub struct SuperShuckieReplayMarker {
+    pub elapsed_frames: u32,
+    pub elapsed_milliseconds: u32,
+    pub name: UTF8CString
+}
+
+impl From<&ReplayMarker> for SuperShuckieReplayMarker {
+    fn from(value: &ReplayMarker) -> Self {
+        Self {
+            elapsed_frames: value.elapsed_frames as u32,
+            elapsed_milliseconds: value.elapsed_millis as u32,
+            // names come from the replay file, so they could have anything in them
+            name: value.name.replace('\0', "").into()
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct SuperShuckieReplayMarkerArray(pub Vec<SuperShuckieReplayMarker>);
+
+impl SuperShuckieReplayMarkerArray {
+    pub fn from_markers(markers: &[ReplayMarker]) -> Self {
+        Self(markers.iter().map(SuperShuckieReplayMarker::from).collect())
+    }
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_replaymarkerarray_len(
+    arr: &SuperShuckieReplayMarkerArray
+) -> usize {
+    ffi_guard("supershuckie_replaymarkerarray_len", || {
+        arr.0.len()
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_replaymarkerarray_get(
+    arr: &SuperShuckieReplayMarkerArray,
+    element: usize,
+    elapsed_frames: *mut u32,
+    elapsed_milliseconds: *mut u32
+) -> *const c_char {
+    ffi_guard("supershuckie_replaymarkerarray_get", || {
+        let Some(marker) = arr.0.get(element) else {
+            return null()
+        };
+
+        let elapsed_frames = unsafe { nullable_reference!(elapsed_frames) };
+        let elapsed_milliseconds = unsafe { nullable_reference!(elapsed_milliseconds) };
+
+        *elapsed_frames = marker.elapsed_frames;
+        *elapsed_milliseconds = marker.elapsed_milliseconds;
+        marker.name.as_c_str().as_ptr()
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_replaymarkerarray_free(
+    arr: *mut SuperShuckieReplayMarkerArray
+) {
+    ffi_guard("supershuckie_replaymarkerarray_free", || {
+        if !arr.is_null() {
+            let _ = unsafe { Box::from_raw(arr) };
+        }
+    })
+}
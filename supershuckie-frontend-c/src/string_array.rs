@@ -1,6 +1,7 @@
 use std::ffi::c_char;
 use std::ptr::null;
 use supershuckie_frontend::util::UTF8CString;
+use crate::ffi::ffi_guard;
 
 #[repr(transparent)]
 #[derive(Default)]
@@ -10,7 +11,9 @@ pub struct SuperShuckieStringArray(pub Vec<UTF8CString>);
 pub extern "C" fn supershuckie_stringarray_len(
     arr: &SuperShuckieStringArray
 ) -> usize {
-    arr.0.len()
+    ffi_guard("supershuckie_stringarray_len", || {
+        arr.0.len()
+    })
 }
 
 #[unsafe(no_mangle)]
@@ -18,14 +21,18 @@ pub extern "C" fn supershuckie_stringarray_get(
     arr: &SuperShuckieStringArray,
     element: usize
 ) -> *const c_char {
-    arr.0.get(element).map(|i| i.as_c_str().as_ptr()).unwrap_or(null())
+    ffi_guard("supershuckie_stringarray_get", || {
+        arr.0.get(element).map(|i| i.as_c_str().as_ptr()).unwrap_or(null())
+    })
 }
 
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn supershuckie_stringarray_free(
     arr: *mut SuperShuckieStringArray
 ) {
-    if !arr.is_null() {
-        let _ = unsafe { Box::from_raw(arr) };
-    }
+    ffi_guard("supershuckie_stringarray_free", || {
+        if !arr.is_null() {
+            let _ = unsafe { Box::from_raw(arr) };
+        }
+    })
 }
@@ -0,0 +1,122 @@
+use std::ffi::c_void;
+use std::slice::from_raw_parts;
+use supershuckie_core::emulator::ScreenData;
+use supershuckie_core::WatchedMemoryRange;
+use supershuckie_frontend::visualizer::{Visualizer, VisualizerId};
+use supershuckie_frontend::SuperShuckieFrontend;
+use crate::frontend::SuperShuckieScreenDataC;
+use crate::ffi::ffi_guard;
+
+/// Must match SUPERSHUCKIE_VISUALIZER_API_VERSION in visualizer.h.
+const VISUALIZER_API_VERSION: u32 = 1;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct SuperShuckieWatchedMemoryRangeC {
+    pub address: u32,
+    pub length: usize
+}
+
+type VisualizeFn = unsafe extern "C" fn(
+    userdata: *mut c_void,
+    screen_count: usize,
+    screen_data: *const SuperShuckieScreenDataC,
+    pixels: *const *const u32,
+    memory_count: usize,
+    memory: *const *const u8,
+    overlays: *const *mut u32
+) -> bool;
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct SuperShuckieVisualizerC {
+    pub api_version: u32,
+    pub userdata: *mut c_void,
+    pub watched_memory: *const SuperShuckieWatchedMemoryRangeC,
+    pub watched_memory_count: usize,
+    pub visualize: Option<VisualizeFn>,
+    pub free: Option<unsafe extern "C" fn(userdata: *mut c_void)>
+}
+
+struct CVisualizer {
+    userdata: *mut c_void,
+    watched_memory: Vec<WatchedMemoryRange>,
+    visualize: VisualizeFn,
+    free: Option<unsafe extern "C" fn(userdata: *mut c_void)>
+}
+
+impl Visualizer for CVisualizer {
+    fn watched_memory(&self) -> Vec<WatchedMemoryRange> {
+        self.watched_memory.clone()
+    }
+
+    fn visualize(&mut self, screens: &[ScreenData], memory: &[Vec<u8>], overlays: &mut [ScreenData]) -> bool {
+        let screen_data: Vec<SuperShuckieScreenDataC> = screens.iter().map(|screen| SuperShuckieScreenDataC {
+            width: screen.width as u32,
+            height: screen.height as u32,
+            screen_data_encoding: screen.encoding
+        }).collect();
+        let pixels: Vec<*const u32> = screens.iter().map(|i| i.pixels.as_ptr()).collect();
+        let memory: Vec<*const u8> = memory.iter().map(|i| i.as_ptr()).collect();
+        let overlays: Vec<*mut u32> = overlays.iter_mut().map(|i| i.pixels.as_mut_ptr()).collect();
+
+        unsafe {
+            (self.visualize)(
+                self.userdata,
+                screens.len(),
+                screen_data.as_ptr(),
+                pixels.as_ptr(),
+                memory.len(),
+                memory.as_ptr(),
+                overlays.as_ptr()
+            )
+        }
+    }
+}
+
+impl Drop for CVisualizer {
+    fn drop(&mut self) {
+        if let Some(free) = self.free {
+            unsafe { free(self.userdata) };
+        }
+    }
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn supershuckie_frontend_add_visualizer(
+    frontend: &mut SuperShuckieFrontend,
+    visualizer: &SuperShuckieVisualizerC
+) -> VisualizerId {
+    ffi_guard("supershuckie_frontend_add_visualizer", || {
+        if visualizer.api_version != VISUALIZER_API_VERSION {
+            return 0
+        }
+        let Some(visualize) = visualizer.visualize else {
+            return 0
+        };
+
+        let watched_memory = if visualizer.watched_memory_count == 0 {
+            &[]
+        }
+        else {
+            unsafe { from_raw_parts(visualizer.watched_memory, visualizer.watched_memory_count) }
+        };
+
+        frontend.add_visualizer(Box::new(CVisualizer {
+            userdata: visualizer.userdata,
+            watched_memory: watched_memory.iter().map(|i| WatchedMemoryRange { address: i.address, length: i.length }).collect(),
+            visualize,
+            free: visualizer.free
+        }))
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn supershuckie_frontend_remove_visualizer(
+    frontend: &mut SuperShuckieFrontend,
+    id: VisualizerId
+) -> bool {
+    ffi_guard("supershuckie_frontend_remove_visualizer", || {
+        frontend.remove_visualizer(id)
+    })
+}
@@ -0,0 +1,476 @@
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::io::{Error, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+use supershuckie_core::ThreadedReplayFileSink;
+use supershuckie_replay_recorder::replay_file::record::{BufferedReplayFileSink, MmapReplayFileSink, ReplayFileSink, ReplayFileWriteError};
+
+/// Size and timestamps of a file in a [`FileStore`].
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct FileStoreMetadata {
+    pub len: u64,
+    pub created: Option<SystemTime>,
+    pub modified: Option<SystemTime>
+}
+
+/// How a replay file opened with [`FileStore::create_replay_sink`] is written to.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum ReplaySinkKind {
+    /// The finished replay, which is only ever appended to, buffering up to `flush_threshold`
+    /// bytes between writes
+    Final { flush_threshold: usize },
+
+    /// The temporary replay, which is truncated often
+    Temporary
+}
+
+/// Filesystem used by [`SuperShuckieFrontend`](crate::SuperShuckieFrontend) for everything in the
+/// user directory, so it can run against something other than the disk (e.g. in tests).
+pub trait FileStore {
+    /// Read the whole file.
+    fn read(&self, path: &Path) -> std::io::Result<Vec<u8>>;
+
+    /// Create or replace a file. The parent folder must exist.
+    fn write(&self, path: &Path, data: &[u8]) -> std::io::Result<()>;
+
+    /// Like [`FileStore::write`], but fails with [`ErrorKind::AlreadyExists`] if the file exists.
+    fn write_new(&self, path: &Path, data: &[u8]) -> std::io::Result<()>;
+
+    /// Like [`FileStore::write`], but only the current user can read the file where that's
+    /// supported.
+    fn write_private(&self, path: &Path, data: &[u8]) -> std::io::Result<()>;
+
+    /// Overwrite part of an existing file starting at `offset`, extending it if needed.
+    fn write_at(&self, path: &Path, offset: u64, data: &[u8]) -> std::io::Result<()>;
+
+    /// Create a folder and any missing parents.
+    fn create_dir_all(&self, path: &Path) -> std::io::Result<()>;
+
+    fn remove_file(&self, path: &Path) -> std::io::Result<()>;
+
+    /// Move a file or folder, replacing any file at `to`.
+    fn rename(&self, from: &Path, to: &Path) -> std::io::Result<()>;
+
+    /// Copy a file, replacing any file at `to`.
+    fn copy(&self, from: &Path, to: &Path) -> std::io::Result<()>;
+
+    /// List the paths of the files and folders directly in a folder.
+    fn read_dir(&self, path: &Path) -> std::io::Result<Vec<PathBuf>>;
+
+    fn metadata(&self, path: &Path) -> std::io::Result<FileStoreMetadata>;
+
+    fn is_file(&self, path: &Path) -> bool;
+
+    fn is_dir(&self, path: &Path) -> bool;
+
+    /// Get the absolute path of an existing file or folder.
+    fn canonicalize(&self, path: &Path) -> std::io::Result<PathBuf>;
+
+    /// Create a file for a replay to be recorded into on the core thread.
+    ///
+    /// If `create_new` is set, this fails with [`ErrorKind::AlreadyExists`] if the file exists.
+    /// Otherwise, any existing file is replaced. The parent folder must exist.
+    fn create_replay_sink(&self, path: &Path, create_new: bool, kind: ReplaySinkKind) -> std::io::Result<ThreadedReplayFileSink>;
+
+    /// Read the whole file as UTF-8.
+    fn read_to_string(&self, path: &Path) -> std::io::Result<String> {
+        String::from_utf8(self.read(path)?).map_err(|e| Error::new(ErrorKind::InvalidData, e))
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.is_file(path) || self.is_dir(path)
+    }
+}
+
+impl<T: FileStore + ?Sized> FileStore for Arc<T> {
+    fn read(&self, path: &Path) -> std::io::Result<Vec<u8>> {
+        self.as_ref().read(path)
+    }
+
+    fn write(&self, path: &Path, data: &[u8]) -> std::io::Result<()> {
+        self.as_ref().write(path, data)
+    }
+
+    fn write_new(&self, path: &Path, data: &[u8]) -> std::io::Result<()> {
+        self.as_ref().write_new(path, data)
+    }
+
+    fn write_private(&self, path: &Path, data: &[u8]) -> std::io::Result<()> {
+        self.as_ref().write_private(path, data)
+    }
+
+    fn write_at(&self, path: &Path, offset: u64, data: &[u8]) -> std::io::Result<()> {
+        self.as_ref().write_at(path, offset, data)
+    }
+
+    fn create_dir_all(&self, path: &Path) -> std::io::Result<()> {
+        self.as_ref().create_dir_all(path)
+    }
+
+    fn remove_file(&self, path: &Path) -> std::io::Result<()> {
+        self.as_ref().remove_file(path)
+    }
+
+    fn rename(&self, from: &Path, to: &Path) -> std::io::Result<()> {
+        self.as_ref().rename(from, to)
+    }
+
+    fn copy(&self, from: &Path, to: &Path) -> std::io::Result<()> {
+        self.as_ref().copy(from, to)
+    }
+
+    fn read_dir(&self, path: &Path) -> std::io::Result<Vec<PathBuf>> {
+        self.as_ref().read_dir(path)
+    }
+
+    fn metadata(&self, path: &Path) -> std::io::Result<FileStoreMetadata> {
+        self.as_ref().metadata(path)
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        self.as_ref().is_file(path)
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        self.as_ref().is_dir(path)
+    }
+
+    fn canonicalize(&self, path: &Path) -> std::io::Result<PathBuf> {
+        self.as_ref().canonicalize(path)
+    }
+
+    fn create_replay_sink(&self, path: &Path, create_new: bool, kind: ReplaySinkKind) -> std::io::Result<ThreadedReplayFileSink> {
+        self.as_ref().create_replay_sink(path, create_new, kind)
+    }
+}
+
+/// [`FileStore`] backed by the real filesystem.
+#[derive(Copy, Clone, Default, Debug)]
+pub struct DiskFileStore;
+
+impl FileStore for DiskFileStore {
+    fn read(&self, path: &Path) -> std::io::Result<Vec<u8>> {
+        std::fs::read(path)
+    }
+
+    fn write(&self, path: &Path, data: &[u8]) -> std::io::Result<()> {
+        std::fs::write(path, data)
+    }
+
+    fn write_new(&self, path: &Path, data: &[u8]) -> std::io::Result<()> {
+        use std::io::Write;
+        std::fs::File::options().write(true).create_new(true).open(path)?.write_all(data)
+    }
+
+    #[cfg(unix)]
+    fn write_private(&self, path: &Path, data: &[u8]) -> std::io::Result<()> {
+        use std::io::Write;
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+        // The mode only applies to new files, so an existing file's permissions are fixed, too
+        let mut file = std::fs::File::options().write(true).create(true).truncate(true).mode(0o600).open(path)?;
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+        file.write_all(data)
+    }
+
+    #[cfg(not(unix))]
+    fn write_private(&self, path: &Path, data: &[u8]) -> std::io::Result<()> {
+        std::fs::write(path, data)
+    }
+
+    fn write_at(&self, path: &Path, offset: u64, data: &[u8]) -> std::io::Result<()> {
+        use std::io::{Seek, SeekFrom, Write};
+        let mut file = std::fs::File::options().write(true).open(path)?;
+        file.seek(SeekFrom::Start(offset))?;
+        file.write_all(data)
+    }
+
+    fn create_dir_all(&self, path: &Path) -> std::io::Result<()> {
+        std::fs::create_dir_all(path)
+    }
+
+    fn remove_file(&self, path: &Path) -> std::io::Result<()> {
+        std::fs::remove_file(path)
+    }
+
+    fn rename(&self, from: &Path, to: &Path) -> std::io::Result<()> {
+        std::fs::rename(from, to)
+    }
+
+    fn copy(&self, from: &Path, to: &Path) -> std::io::Result<()> {
+        std::fs::copy(from, to).map(|_| ())
+    }
+
+    fn read_dir(&self, path: &Path) -> std::io::Result<Vec<PathBuf>> {
+        std::fs::read_dir(path)?.map(|e| e.map(|e| e.path())).collect()
+    }
+
+    fn metadata(&self, path: &Path) -> std::io::Result<FileStoreMetadata> {
+        let metadata = std::fs::metadata(path)?;
+        Ok(FileStoreMetadata {
+            len: metadata.len(),
+            created: metadata.created().ok(),
+            modified: metadata.modified().ok()
+        })
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        path.is_file()
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        path.is_dir()
+    }
+
+    fn canonicalize(&self, path: &Path) -> std::io::Result<PathBuf> {
+        std::fs::canonicalize(path)
+    }
+
+    fn create_replay_sink(&self, path: &Path, create_new: bool, kind: ReplaySinkKind) -> std::io::Result<ThreadedReplayFileSink> {
+        let mut options = File::options();
+        options.read(true).write(true);
+        match create_new {
+            true => options.create_new(true),
+            false => options.create(true).truncate(true)
+        };
+        let file = options.open(path)?;
+
+        Ok(match kind {
+            ReplaySinkKind::Final { flush_threshold } => Box::new(BufferedReplayFileSink::with_flush_threshold(file, flush_threshold)),
+            ReplaySinkKind::Temporary => Box::new(MmapReplayFileSink::new(file).map_err(|e| Error::other(format!("Can't map {}: {e:?}", path.display())))?)
+        })
+    }
+}
+
+/// [`FileStore`] that only exists in memory, for testing without touching the disk.
+///
+/// Paths are compared as-is (`..` and symlinks are not resolved).
+#[derive(Default)]
+pub struct MemoryFileStore {
+    inner: Arc<Mutex<MemoryFileStoreInner>>
+}
+
+#[derive(Default)]
+struct MemoryFileStoreInner {
+    files: BTreeMap<PathBuf, MemoryFile>,
+    dirs: BTreeSet<PathBuf>
+}
+
+struct MemoryFile {
+    data: Vec<u8>,
+    created: SystemTime,
+    modified: SystemTime
+}
+
+impl MemoryFileStore {
+    /// Create an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the paths of every file in the store.
+    pub fn files(&self) -> Vec<PathBuf> {
+        self.lock().files.keys().cloned().collect()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, MemoryFileStoreInner> {
+        lock_inner(&self.inner)
+    }
+}
+
+fn lock_inner(inner: &Mutex<MemoryFileStoreInner>) -> std::sync::MutexGuard<'_, MemoryFileStoreInner> {
+    inner.lock().expect("can't get memory file store mutex")
+}
+
+impl MemoryFileStoreInner {
+    fn parent_exists(&self, path: &Path) -> bool {
+        path.parent().is_none_or(|p| p.as_os_str().is_empty() || self.dirs.contains(p))
+    }
+
+    fn insert_file(&mut self, path: &Path, data: Vec<u8>) -> std::io::Result<()> {
+        if !self.parent_exists(path) {
+            return Err(not_found(path))
+        }
+        if self.dirs.contains(path) {
+            return Err(Error::new(ErrorKind::IsADirectory, format!("{} is a folder", path.display())))
+        }
+
+        let now = SystemTime::now();
+        let created = self.files.get(path).map(|f| f.created).unwrap_or(now);
+        self.files.insert(path.to_owned(), MemoryFile { data, created, modified: now });
+        Ok(())
+    }
+}
+
+impl FileStore for MemoryFileStore {
+    fn read(&self, path: &Path) -> std::io::Result<Vec<u8>> {
+        self.lock().files.get(path).map(|f| f.data.clone()).ok_or_else(|| not_found(path))
+    }
+
+    fn write(&self, path: &Path, data: &[u8]) -> std::io::Result<()> {
+        self.lock().insert_file(path, data.to_vec())
+    }
+
+    fn write_new(&self, path: &Path, data: &[u8]) -> std::io::Result<()> {
+        let mut inner = self.lock();
+        if inner.files.contains_key(path) {
+            return Err(Error::new(ErrorKind::AlreadyExists, format!("{} already exists", path.display())))
+        }
+        inner.insert_file(path, data.to_vec())
+    }
+
+    fn write_private(&self, path: &Path, data: &[u8]) -> std::io::Result<()> {
+        self.write(path, data)
+    }
+
+    fn write_at(&self, path: &Path, offset: u64, data: &[u8]) -> std::io::Result<()> {
+        let mut inner = self.lock();
+        let file = inner.files.get_mut(path).ok_or_else(|| not_found(path))?;
+        let start = usize::try_from(offset).map_err(|_| Error::new(ErrorKind::InvalidInput, "offset does not fit in memory"))?;
+        let end = start + data.len();
+        if file.data.len() < end {
+            file.data.resize(end, 0);
+        }
+        file.data[start..end].copy_from_slice(data);
+        file.modified = SystemTime::now();
+        Ok(())
+    }
+
+    fn create_dir_all(&self, path: &Path) -> std::io::Result<()> {
+        let mut inner = self.lock();
+        for ancestor in path.ancestors().filter(|p| !p.as_os_str().is_empty()) {
+            if inner.files.contains_key(ancestor) {
+                return Err(Error::new(ErrorKind::AlreadyExists, format!("{} is a file", ancestor.display())))
+            }
+            inner.dirs.insert(ancestor.to_owned());
+        }
+        Ok(())
+    }
+
+    fn remove_file(&self, path: &Path) -> std::io::Result<()> {
+        self.lock().files.remove(path).map(|_| ()).ok_or_else(|| not_found(path))
+    }
+
+    fn rename(&self, from: &Path, to: &Path) -> std::io::Result<()> {
+        let mut inner = self.lock();
+        if !inner.parent_exists(to) {
+            return Err(not_found(to))
+        }
+
+        if let Some(file) = inner.files.remove(from) {
+            inner.files.insert(to.to_owned(), file);
+            return Ok(())
+        }
+
+        if !inner.dirs.contains(from) {
+            return Err(not_found(from))
+        }
+
+        let moved_dirs: Vec<PathBuf> = inner.dirs.iter().filter(|d| d.starts_with(from)).cloned().collect();
+        for dir in moved_dirs {
+            inner.dirs.remove(&dir);
+            inner.dirs.insert(moved_path(&dir, from, to));
+        }
+
+        let moved_files: Vec<PathBuf> = inner.files.keys().filter(|f| f.starts_with(from)).cloned().collect();
+        for path in moved_files {
+            let file = inner.files.remove(&path).expect("we just listed this");
+            inner.files.insert(moved_path(&path, from, to), file);
+        }
+
+        Ok(())
+    }
+
+    fn copy(&self, from: &Path, to: &Path) -> std::io::Result<()> {
+        let data = self.read(from)?;
+        self.write(to, &data)
+    }
+
+    fn read_dir(&self, path: &Path) -> std::io::Result<Vec<PathBuf>> {
+        let inner = self.lock();
+        if !inner.dirs.contains(path) {
+            return Err(not_found(path))
+        }
+
+        let is_child = |p: &&PathBuf| p.parent() == Some(path);
+        Ok(inner.dirs.iter().filter(is_child).chain(inner.files.keys().filter(is_child)).cloned().collect())
+    }
+
+    fn metadata(&self, path: &Path) -> std::io::Result<FileStoreMetadata> {
+        let inner = self.lock();
+        if let Some(file) = inner.files.get(path) {
+            return Ok(FileStoreMetadata { len: file.data.len() as u64, created: Some(file.created), modified: Some(file.modified) })
+        }
+        match inner.dirs.contains(path) {
+            true => Ok(FileStoreMetadata { len: 0, created: None, modified: None }),
+            false => Err(not_found(path))
+        }
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        self.lock().files.contains_key(path)
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        self.lock().dirs.contains(path)
+    }
+
+    fn canonicalize(&self, path: &Path) -> std::io::Result<PathBuf> {
+        match self.exists(path) {
+            true => Ok(path.to_owned()),
+            false => Err(not_found(path))
+        }
+    }
+
+    fn create_replay_sink(&self, path: &Path, create_new: bool, _kind: ReplaySinkKind) -> std::io::Result<ThreadedReplayFileSink> {
+        match create_new {
+            true => self.write_new(path, &[])?,
+            false => self.write(path, &[])?
+        }
+        Ok(Box::new(MemoryReplayFileSink { inner: self.inner.clone(), path: path.to_owned() }))
+    }
+}
+
+/// Replay file in a [`MemoryFileStore`], which is written to directly.
+struct MemoryReplayFileSink {
+    inner: Arc<Mutex<MemoryFileStoreInner>>,
+    path: PathBuf
+}
+
+impl MemoryReplayFileSink {
+    fn modify(&self, what: impl FnOnce(&mut Vec<u8>)) -> Result<(), ReplayFileWriteError> {
+        let mut inner = lock_inner(&self.inner);
+        let Some(file) = inner.files.get_mut(&self.path) else {
+            return Err(ReplayFileWriteError::Other { explanation: Cow::Owned(format!("{} was removed", self.path.display())) })
+        };
+        what(&mut file.data);
+        file.modified = SystemTime::now();
+        Ok(())
+    }
+}
+
+impl ReplayFileSink for MemoryReplayFileSink {
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), ReplayFileWriteError> {
+        self.modify(|data| data.extend_from_slice(bytes))
+    }
+
+    fn truncate(&mut self, size: u64) -> Result<(), ReplayFileWriteError> {
+        let size = usize::try_from(size).map_err(|_| ReplayFileWriteError::BadInput { explanation: Cow::Borrowed("size does not fit in memory") })?;
+        self.modify(|data| data.resize(size, 0))
+    }
+}
+
+/// Get where `path` ends up if `from` is moved to `to`.
+fn moved_path(path: &Path, from: &Path, to: &Path) -> PathBuf {
+    match path.strip_prefix(from).expect("path is not in the moved folder") {
+        rest if rest.as_os_str().is_empty() => to.to_owned(),
+        rest => to.join(rest)
+    }
+}
+
+fn not_found(path: &Path) -> Error {
+    Error::new(ErrorKind::NotFound, format!("{} does not exist", path.display()))
+}
@@ -0,0 +1,72 @@
+use serde::{Deserialize, Serialize};
+use crate::game_profile::GameProfile;
+use crate::settings::Controls;
+
+/// A ROM's shareable user data in a single file, bound to the ROM by checksum.
+///
+/// The ROM itself, save data, and save states are never included.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct GameBundle {
+    /// Version of the bundle format (see [`GameBundle::VERSION`])
+    #[serde(default = "GameBundle::VERSION")]
+    pub version: u32,
+
+    /// BLAKE3 checksum of the ROM this bundle is for, in hex (ignoring case)
+    ///
+    /// See [`SuperShuckieFrontend::get_rom_checksum`](crate::SuperShuckieFrontend::get_rom_checksum).
+    pub checksum: String,
+
+    /// Name of the ROM it was exported from, for display only
+    #[serde(default = "String::default")]
+    pub rom_name: String,
+
+    /// Free-form notes from whoever made the bundle
+    #[serde(default = "String::default")]
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub notes: String,
+
+    /// Per-ROM controls, if the ROM has its own
+    #[serde(default = "Option::default")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub controls: Option<Controls>,
+
+    /// Number of frames to delay input by
+    #[serde(default = "u8::default")]
+    pub input_delay_frames: u8,
+
+    /// Game profile with the watches for the ROM, if any
+    #[serde(default = "Option::default")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub game_profile: Option<GameProfile>
+}
+
+impl GameBundle {
+    /// Current version of the bundle format.
+    pub const VERSION: fn() -> u32 = || 1;
+
+    /// Parse and validate a bundle.
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let mut bundle: GameBundle = serde_json::from_str(json).map_err(|e| format!("Can't parse game bundle: {e}"))?;
+
+        if bundle.version > Self::VERSION() {
+            return Err(format!("Game bundle version {} is newer than this version of SuperShuckie supports", bundle.version))
+        }
+
+        if let Some(mut profile) = bundle.game_profile.take() {
+            if !profile.matches(&bundle.checksum) {
+                profile.checksums.push(bundle.checksum.clone());
+            }
+
+            // Go through the same validation as profiles loaded from the user directory
+            let profile_json = serde_json::to_string(&profile).expect("failed to serialize game profile");
+            bundle.game_profile = Some(GameProfile::from_json(&profile_json)?);
+        }
+
+        Ok(bundle)
+    }
+
+    /// Return true if this bundle is for the ROM with the given checksum.
+    pub fn matches(&self, checksum: &str) -> bool {
+        self.checksum.eq_ignore_ascii_case(checksum)
+    }
+}
@@ -0,0 +1,165 @@
+use std::num::NonZeroUsize;
+use std::path::Path;
+use serde::{Deserialize, Deserializer, Serialize};
+use supershuckie_core::WatchedMemoryRange;
+use crate::file_store::FileStore;
+use crate::util::UTF8CString;
+
+/// Folder in the user directory with one `.json` file per [`GameProfile`].
+///
+/// Profiles here take precedence over ones added with
+/// [`SuperShuckieFrontend::add_game_profile`](crate::SuperShuckieFrontend::add_game_profile).
+pub const GAME_PROFILES_FOLDER: &str = "game profiles";
+
+/// Interesting addresses (e.g. RNG seeds or positions) for known games, which are watched
+/// automatically when a matching ROM is loaded.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GameProfile {
+    pub name: UTF8CString,
+
+    /// BLAKE3 checksums of the ROMs this profile is for, in hex (ignoring case)
+    ///
+    /// See [`SuperShuckieFrontend::get_rom_checksum`](crate::SuperShuckieFrontend::get_rom_checksum).
+    pub checksums: Vec<String>,
+
+    #[serde(default = "Vec::default")]
+    pub watches: Vec<GameProfileWatch>
+}
+
+/// An address in a [`GameProfile`] to read every frame.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GameProfileWatch {
+    pub name: UTF8CString,
+
+    /// Address to read from (interpreted the same way as `EmulatorCore::read_ram`), either as a
+    /// number or a hex string (e.g. `"0xFFD3"`)
+    #[serde(deserialize_with = "deserialize_address")]
+    pub address: u32,
+
+    /// Number of bytes to read
+    #[serde(default = "GameProfileWatch::DEFAULT_LENGTH")]
+    pub length: NonZeroUsize,
+
+    #[serde(default = "WatchFormat::default")]
+    pub format: WatchFormat
+}
+
+/// How a [`GameProfileWatch`] value is shown.
+#[derive(Copy, Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WatchFormat {
+    /// Little-endian integer in hex, or each byte in hex if longer than 8 bytes
+    #[default]
+    Hex,
+
+    /// Little-endian unsigned integer
+    Unsigned,
+
+    /// Little-endian two's complement signed integer
+    Signed
+}
+
+/// The current value of a [`GameProfileWatch`].
+#[derive(Clone, Debug)]
+pub struct GameProfileWatchValue {
+    pub name: UTF8CString,
+
+    /// `None` if it hasn't been read yet
+    pub value: Option<UTF8CString>
+}
+
+impl GameProfile {
+    /// Parse and validate a profile.
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let profile: GameProfile = serde_json::from_str(json).map_err(|e| format!("Can't parse game profile: {e}"))?;
+
+        if profile.checksums.is_empty() {
+            return Err(format!("Game profile {} has no checksums", profile.name))
+        }
+
+        for watch in &profile.watches {
+            if watch.format != WatchFormat::Hex && watch.length.get() > size_of::<u64>() {
+                return Err(format!("Watch {} in game profile {} is too long to show as an integer", watch.name, profile.name))
+            }
+        }
+
+        Ok(profile)
+    }
+
+    /// Return true if this profile is for the ROM with the given checksum.
+    pub fn matches(&self, checksum: &str) -> bool {
+        self.checksums.iter().any(|c| c.eq_ignore_ascii_case(checksum))
+    }
+
+    pub(crate) fn watched_memory(&self) -> impl Iterator<Item = WatchedMemoryRange> {
+        self.watches.iter().map(|w| WatchedMemoryRange { address: w.address, length: w.length.get() })
+    }
+}
+
+impl GameProfileWatch {
+    const DEFAULT_LENGTH: fn() -> NonZeroUsize = || NonZeroUsize::MIN;
+
+    /// Format memory read for this watch.
+    pub fn format_value(&self, data: &[u8]) -> String {
+        if data.len() > size_of::<u64>() {
+            return data.iter().map(|b| format!("{b:02X}")).collect::<Vec<_>>().join(" ")
+        }
+
+        let mut bytes = [0u8; size_of::<u64>()];
+        bytes[..data.len()].copy_from_slice(data);
+        let value = u64::from_le_bytes(bytes);
+
+        match self.format {
+            WatchFormat::Hex => format!("0x{value:0width$X}", width = data.len() * 2),
+            WatchFormat::Unsigned => value.to_string(),
+            WatchFormat::Signed => {
+                let unused_bits = (size_of::<u64>() - data.len()) as u32 * 8;
+                ((value << unused_bits) as i64 >> unused_bits).to_string()
+            }
+        }
+    }
+}
+
+/// Find the profile for the ROM with the given checksum, checking the user directory before
+/// `embedded`.
+///
+/// Files in the user directory that can't be read are returned as errors and skipped.
+pub(crate) fn find_game_profile(files: &dyn FileStore, user_dir: &Path, embedded: &[GameProfile], checksum: &str) -> (Option<GameProfile>, Vec<String>) {
+    let mut errors = Vec::new();
+
+    let mut paths: Vec<_> = files.read_dir(&user_dir.join(GAME_PROFILES_FOLDER))
+        .map(|dir| dir.into_iter().filter(|p| p.extension().and_then(|e| e.to_str()) == Some("json")).collect())
+        .unwrap_or_default();
+    paths.sort();
+
+    for path in paths {
+        let profile = files.read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|json| GameProfile::from_json(&json));
+
+        match profile {
+            Ok(profile) if profile.matches(checksum) => return (Some(profile), errors),
+            Ok(_) => (),
+            Err(e) => errors.push(format!("{}: {e}", path.display()))
+        }
+    }
+
+    (embedded.iter().find(|p| p.matches(checksum)).cloned(), errors)
+}
+
+fn deserialize_address<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u32, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Address {
+        Number(u32),
+        String(String)
+    }
+
+    match Address::deserialize(deserializer)? {
+        Address::Number(n) => Ok(n),
+        Address::String(s) => {
+            let hex = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")).unwrap_or(&s);
+            u32::from_str_radix(hex, 16).map_err(|_| serde::de::Error::custom(format!("{s} is not a valid hex address")))
+        }
+    }
+}
@@ -0,0 +1,214 @@
+use std::collections::HashMap;
+use supershuckie_core::emulator::ScreenData;
+
+const GIF_SIGNATURE: &[u8; 6] = b"GIF89a";
+
+/// Largest LZW code allowed by GIF; the table is cleared once it fills up.
+const MAX_LZW_CODE: u16 = (1 << 12) - 1;
+
+/// Maximum length of a data sub-block.
+const MAX_SUB_BLOCK_LENGTH: usize = 255;
+
+/// Encodes frames as a looping animated GIF, upscaled with nearest-neighbor scaling.
+///
+/// Each frame gets its own color table. Frames with more than 256 colors are reduced to RGB332.
+pub(crate) struct GifEncoder {
+    gif: Vec<u8>,
+    width: usize,
+    height: usize,
+    scale: usize,
+    indices: Vec<u8>
+}
+
+impl GifEncoder {
+    /// Start a GIF for frames of the given (unscaled) dimensions.
+    ///
+    /// Returns `None` if the scaled dimensions do not fit in a GIF.
+    pub(crate) fn new(width: usize, height: usize, scale: usize) -> Option<Self> {
+        let scaled_width = u16::try_from(width.checked_mul(scale)?).ok()?;
+        let scaled_height = u16::try_from(height.checked_mul(scale)?).ok()?;
+
+        let mut gif = Vec::new();
+        gif.extend_from_slice(GIF_SIGNATURE);
+
+        // logical screen descriptor (no global color table)
+        gif.extend_from_slice(&scaled_width.to_le_bytes());
+        gif.extend_from_slice(&scaled_height.to_le_bytes());
+        gif.extend_from_slice(&[0x00, 0x00, 0x00]);
+
+        // loop forever
+        gif.extend_from_slice(&[0x21, 0xFF, 0x0B]);
+        gif.extend_from_slice(b"NETSCAPE2.0");
+        gif.extend_from_slice(&[0x03, 0x01, 0x00, 0x00, 0x00]);
+
+        Some(Self { gif, width, height, scale, indices: Vec::new() })
+    }
+
+    /// Add a frame which is shown for `delay_centiseconds`.
+    ///
+    /// The screen must have the dimensions passed to [`GifEncoder::new`].
+    pub(crate) fn add_frame(&mut self, screen: &ScreenData, delay_centiseconds: u16) {
+        assert_eq!((screen.width, screen.height), (self.width, self.height), "frame dimensions changed");
+
+        let (palette, frame_indices) = make_palette(screen);
+        let color_table_bits = palette.len().next_power_of_two().trailing_zeros().max(1);
+
+        self.indices.clear();
+        for row in frame_indices.chunks_exact(self.width) {
+            let start = self.indices.len();
+            for &index in row {
+                for _ in 0..self.scale {
+                    self.indices.push(index);
+                }
+            }
+            for _ in 1..self.scale {
+                self.indices.extend_from_within(start..start + self.width * self.scale);
+            }
+        }
+
+        // graphic control extension (do not dispose)
+        self.gif.extend_from_slice(&[0x21, 0xF9, 0x04, 0x04]);
+        self.gif.extend_from_slice(&delay_centiseconds.to_le_bytes());
+        self.gif.extend_from_slice(&[0x00, 0x00]);
+
+        // image descriptor with a local color table
+        self.gif.push(0x2C);
+        self.gif.extend_from_slice(&[0x00, 0x00, 0x00, 0x00]);
+        self.gif.extend_from_slice(&((self.width * self.scale) as u16).to_le_bytes());
+        self.gif.extend_from_slice(&((self.height * self.scale) as u16).to_le_bytes());
+        self.gif.push(0x80 | (color_table_bits as u8 - 1));
+
+        for i in 0..1usize << color_table_bits {
+            self.gif.extend_from_slice(palette.get(i).unwrap_or(&[0, 0, 0]));
+        }
+
+        let min_code_size = color_table_bits.max(2) as u8;
+        self.gif.push(min_code_size);
+        let data = lzw_encode(&self.indices, min_code_size);
+        for block in data.chunks(MAX_SUB_BLOCK_LENGTH) {
+            self.gif.push(block.len() as u8);
+            self.gif.extend_from_slice(block);
+        }
+        self.gif.push(0x00);
+    }
+
+    /// Finish the GIF, returning its data.
+    pub(crate) fn finish(mut self) -> Vec<u8> {
+        self.gif.push(0x3B);
+        self.gif
+    }
+}
+
+/// Make a palette of at most 256 colors for the frame, returning it along with the palette index
+/// of each pixel.
+fn make_palette(screen: &ScreenData) -> (Vec<[u8; 3]>, Vec<u8>) {
+    let colors = screen.rgb_pixels();
+
+    let mut palette = Vec::new();
+    let mut palette_indices: HashMap<[u8; 3], u8> = HashMap::new();
+    let mut indices = Vec::with_capacity(screen.pixels.len());
+    for color in colors.clone() {
+        let index = match palette_indices.get(&color) {
+            Some(&index) => index,
+            None if palette.len() < 256 => {
+                let index = palette.len() as u8;
+                palette_indices.insert(color, index);
+                palette.push(color);
+                index
+            },
+            None => {
+                // Too many colors; fall back to RGB332
+                let palette = (0..=255u32).map(|i| {
+                    let r = (i >> 5) & 7;
+                    let g = (i >> 2) & 7;
+                    let b = i & 3;
+                    [(r * 255 / 7) as u8, (g * 255 / 7) as u8, (b * 255 / 3) as u8]
+                }).collect();
+                let indices = colors.map(|[r, g, b]| (r & 0xE0) | ((g & 0xE0) >> 3) | (b >> 6)).collect();
+                return (palette, indices)
+            }
+        };
+        indices.push(index);
+    }
+
+    (palette, indices)
+}
+
+/// Compress the indices with GIF-flavored LZW.
+fn lzw_encode(indices: &[u8], min_code_size: u8) -> Vec<u8> {
+    let clear_code = 1u16 << min_code_size;
+    let end_code = clear_code + 1;
+
+    let mut writer = LzwBitWriter { output: Vec::new(), buffer: 0, bits: 0, code_size: min_code_size + 1 };
+    let mut table: HashMap<(u16, u8), u16> = HashMap::new();
+
+    // The code most recently added to the table. The code size grows once it no longer fits.
+    let mut last_code = end_code;
+
+    writer.write(clear_code);
+
+    let Some((&first, rest)) = indices.split_first() else {
+        writer.write(end_code);
+        return writer.finish()
+    };
+
+    let mut current = first as u16;
+    for &index in rest {
+        if let Some(&code) = table.get(&(current, index)) {
+            current = code;
+            continue
+        }
+
+        writer.write(current);
+        let prefix = current;
+        current = index as u16;
+
+        last_code += 1;
+        if last_code == 1 << writer.code_size {
+            writer.code_size += 1;
+        }
+        if last_code == MAX_LZW_CODE {
+            writer.write(clear_code);
+            writer.code_size = min_code_size + 1;
+            last_code = end_code;
+            table.clear();
+            continue
+        }
+        table.insert((prefix, index), last_code);
+    }
+
+    writer.write(current);
+    last_code += 1;
+    if last_code == 1 << writer.code_size && writer.code_size < 12 {
+        writer.code_size += 1;
+    }
+    writer.write(end_code);
+    writer.finish()
+}
+
+/// Packs codes least significant bit first.
+struct LzwBitWriter {
+    output: Vec<u8>,
+    buffer: u32,
+    bits: u8,
+    code_size: u8
+}
+
+impl LzwBitWriter {
+    fn write(&mut self, code: u16) {
+        self.buffer |= (code as u32) << self.bits;
+        self.bits += self.code_size;
+        while self.bits >= 8 {
+            self.output.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.bits -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.bits > 0 {
+            self.output.push(self.buffer as u8);
+        }
+        self.output
+    }
+}
@@ -0,0 +1,188 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use crate::file_store::FileStore;
+
+/// File in the user directory holding the port the running instance accepts ROM paths on, followed
+/// by the token other instances have to send.
+///
+/// Only the current user can read it, so the token keeps other users (and anything else on the
+/// machine that can connect to loopback) from opening ROMs.
+const INSTANCE_FILE: &str = "instance.port";
+
+/// Sent by a new instance before the token, so a stale port that got reused by something else
+/// isn't mistaken for a running instance.
+const HANDSHAKE: &str = "supershuckie-instance";
+
+/// Sent back by the running instance once it has received the ROM path.
+const ACKNOWLEDGEMENT: &str = "ok";
+
+/// Number of random bytes in a token.
+const TOKEN_LENGTH: usize = 16;
+
+/// Time either side has to finish reading everything it expects from a connection.
+const CONNECTION_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Most bytes read from a connection, which is plenty for the handshake, token, and a ROM path.
+const MAX_CONNECTION_BYTES: u64 = 64 * 1024;
+
+/// Listens for ROM paths forwarded from new instances (see [`forward_to_running_instance`]).
+///
+/// Connections are accepted on a separate thread, so a slow or misbehaving client can't stall the
+/// frontend.
+pub(crate) struct InstanceListener {
+    port: u16,
+    token: String,
+    instance_file: PathBuf,
+    paths: Receiver<PathBuf>,
+    closed: Arc<AtomicBool>
+}
+
+impl InstanceListener {
+    /// Start listening, advertising the port and a new token in the user directory.
+    pub(crate) fn bind(files: &dyn FileStore, user_dir: &Path) -> Result<Self, String> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+            .map_err(|e| format!("Can't listen for other instances: {e}"))?;
+        let port = listener.local_addr()
+            .map_err(|e| format!("Can't listen for other instances: {e}"))?
+            .port();
+
+        let mut token = [0u8; TOKEN_LENGTH];
+        getrandom::fill(&mut token).map_err(|e| format!("Can't listen for other instances: {e}"))?;
+        let token: String = token.iter().map(|b| format!("{b:02x}")).collect();
+
+        let instance_file = user_dir.join(INSTANCE_FILE);
+        files.write_private(&instance_file, format!("{port}\n{token}\n").as_bytes())
+            .map_err(|e| format!("Can't write to {}: {e}", instance_file.display()))?;
+
+        let (sender, paths) = channel();
+        let closed = Arc::new(AtomicBool::new(false));
+        let thread_token = token.clone();
+        let thread_closed = closed.clone();
+        let spawned = std::thread::Builder::new().name("InstanceListener".to_owned()).spawn(move || {
+            Self::thread(listener, thread_token, sender, thread_closed)
+        });
+        if let Err(e) = spawned {
+            let _ = files.remove_file(&instance_file);
+            return Err(format!("Can't listen for other instances: {e}"))
+        }
+
+        Ok(Self { port, token, instance_file, paths, closed })
+    }
+
+    fn thread(listener: TcpListener, token: String, sender: Sender<PathBuf>, closed: Arc<AtomicBool>) {
+        for stream in listener.incoming() {
+            if closed.load(Ordering::Relaxed) {
+                return
+            }
+            let Some(path) = stream.ok().and_then(|s| Self::receive_path(s, &token)) else {
+                continue
+            };
+            if sender.send(path).is_err() {
+                return
+            }
+        }
+    }
+
+    /// Get the ROM paths forwarded since this was last called.
+    ///
+    /// An empty path means the new instance was started without a ROM.
+    pub(crate) fn accept_forwarded_paths(&self) -> Vec<PathBuf> {
+        self.paths.try_iter().collect()
+    }
+
+    fn receive_path(stream: TcpStream, token: &str) -> Option<PathBuf> {
+        let deadline = Instant::now() + CONNECTION_TIMEOUT;
+        stream.set_write_timeout(Some(CONNECTION_TIMEOUT)).ok()?;
+
+        let mut reader = BufReader::new((&stream).take(MAX_CONNECTION_BYTES));
+        if read_line(&mut reader, &stream, deadline)? != HANDSHAKE || read_line(&mut reader, &stream, deadline)? != token {
+            return None
+        }
+        let path = read_line(&mut reader, &stream, deadline)?;
+
+        writeln!(&stream, "{ACKNOWLEDGEMENT}").ok()?;
+        Some(PathBuf::from(path))
+    }
+
+    /// Stop listening, removing the advertised port.
+    pub(crate) fn close(self, files: &dyn FileStore) {
+        // Wake the thread up so it sees it's closed
+        self.closed.store(true, Ordering::Relaxed);
+        let _ = TcpStream::connect_timeout(&SocketAddr::from((Ipv4Addr::LOCALHOST, self.port)), CONNECTION_TIMEOUT);
+
+        // Only remove the file if another instance hasn't taken over since.
+        if files.read_to_string(&self.instance_file).is_ok_and(|f| f == format!("{}\n{}\n", self.port, self.token)) {
+            let _ = files.remove_file(&self.instance_file);
+        }
+    }
+}
+
+/// Forward a ROM path (or none) to an instance already running in single-instance mode with the
+/// given user directory.
+///
+/// Returns `true` if the running instance received it, in which case this instance should exit.
+/// Returns `false` if there is no such instance (e.g. it was closed or single-instance mode is
+/// off).
+pub fn forward_to_running_instance(files: &dyn FileStore, user_dir: &Path, rom_path: Option<&Path>) -> bool {
+    let Ok(instance) = files.read_to_string(&user_dir.join(INSTANCE_FILE)) else {
+        return false
+    };
+    let mut lines = instance.lines();
+    let (Some(port), Some(token)) = (lines.next().and_then(|p| p.parse::<u16>().ok()), lines.next()) else {
+        return false
+    };
+
+    let rom_path = match rom_path {
+        Some(path) => match files.canonicalize(path).unwrap_or_else(|_| path.to_owned()).to_str() {
+            Some(path) => path.to_owned(),
+            None => return false
+        },
+        None => String::new()
+    };
+
+    try_forward(port, token, &rom_path).is_some()
+}
+
+fn try_forward(port: u16, token: &str, rom_path: &str) -> Option<()> {
+    let address = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    let stream = TcpStream::connect_timeout(&address, CONNECTION_TIMEOUT).ok()?;
+    stream.set_write_timeout(Some(CONNECTION_TIMEOUT)).ok()?;
+
+    write!(&stream, "{HANDSHAKE}\n{token}\n{rom_path}\n").ok()?;
+    let deadline = Instant::now() + CONNECTION_TIMEOUT;
+    let mut reader = BufReader::new((&stream).take(MAX_CONNECTION_BYTES));
+    (read_line(&mut reader, &stream, deadline)? == ACKNOWLEDGEMENT).then_some(())
+}
+
+/// Read a line from `stream` through `reader`, giving up once `deadline` passes.
+///
+/// The read timeout only applies to each read, so it's shortened before every read to keep a
+/// client trickling bytes in from holding the connection open past the deadline.
+fn read_line(reader: &mut impl BufRead, stream: &TcpStream, deadline: Instant) -> Option<String> {
+    let mut line = Vec::new();
+    loop {
+        let remaining = deadline.checked_duration_since(Instant::now()).filter(|d| !d.is_zero())?;
+        stream.set_read_timeout(Some(remaining)).ok()?;
+
+        let buffer = reader.fill_buf().ok()?;
+        if buffer.is_empty() {
+            return None
+        }
+        let (length, found) = match buffer.iter().position(|b| *b == b'\n') {
+            Some(end) => (end + 1, true),
+            None => (buffer.len(), false)
+        };
+        line.extend_from_slice(&buffer[..length]);
+        reader.consume(length);
+
+        if found {
+            line.pop();
+            return String::from_utf8(line).ok()
+        }
+    }
+}
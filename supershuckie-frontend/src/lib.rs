@@ -1,29 +1,118 @@
 pub mod util;
 pub mod settings;
-
-use std::collections::BTreeMap;
+pub mod stats;
+pub mod visualizer;
+pub mod instance;
+pub mod power;
+pub mod locale;
+pub mod replay_browser;
+pub mod game_profile;
+pub mod game_bundle;
+pub mod save_profile;
+pub mod file_store;
+pub mod serial_test;
+mod gif;
+mod onion_skin;
+mod png;
+mod wav;
+mod zip;
+
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
 use crate::settings::*;
-use crate::util::UTF8CString;
+use crate::file_store::{DiskFileStore, FileStore, ReplaySinkKind};
+use crate::game_bundle::GameBundle;
+use crate::game_profile::{find_game_profile, GameProfile, GameProfileWatchValue, GAME_PROFILES_FOLDER};
+use crate::instance::InstanceListener;
+use crate::locale::{LocalizedError, MessageCatalog};
+use crate::onion_skin::{OnionSkin, MAX_ONION_SKIN_FRAMES};
+use crate::serial_test::{grade_serial_output, SerialTestResult, SerialTestVerdict, TestSuiteEntry, TestSuiteReport, EXPECTED_OUTPUT_EXTENSION};
+use crate::save_profile::{SaveProfile, SaveProfileMetadata, SaveProfiles, SAVE_PROFILES_FILE};
+use crate::replay_browser::{ReplayBrowserCache, ReplayBrowserEntry, ReplayBrowserQuery, ReplayCompatibility};
+use crate::stats::ROMStats;
+use crate::util::{format_filename_timestamp, sanitize_user_name, UTF8CString};
+use crate::visualizer::{Visualizer, VisualizerId, Visualizers};
+use crate::zip::{ZipArchive, ZipEntry};
 use std::ffi::CStr;
-use std::fs::File;
-use std::io::Write;
-use std::num::{NonZeroU64, NonZeroU8};
+use std::num::{NonZeroU32, NonZeroU64, NonZeroU8};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use supershuckie_core::emulator::{EmulatorCore, GameBoyColor, Input, Model, NullEmulatorCore, PartialReplayRecordMetadata, ScreenData};
-use supershuckie_core::{ReplayPlayerAttachError, Speed, SuperShuckieRapidFire, ThreadedSuperShuckieCore};
-use supershuckie_replay_recorder::replay_file::{ReplayConsoleType, ReplayHeaderBlake3Hash, ReplayPatchFormat};
-use supershuckie_replay_recorder::ByteVec;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use supershuckie_core::emulator::{ColorCorrectionMode, CoreOption, EmulatorCore, GameBoyColor, Input, InputDescriptor, Model, NullEmulatorCore, PartialReplayRecordMetadata, PeripheralOutput, ScreenData, ScreenDataEncoding};
+use supershuckie_core::{CancellationToken, CheatCode, CoreProgressEvent, DeterminismCheckSettings, EmulatedTimestampProvider, FrameTimings, NetplaySession, NetplayStatus, PokeAByteFrameSkipPolicy, PokeAByteTransport, PokeAByteWritePolicy, ReplayMarker, ReplayPlayerAttachError, ReplayPlayerMetadataMismatchKind, RewindSettings, Speed, SpeedBounds, SuperShuckieCore, SuperShuckieRapidFire, ThreadedReplayFileSink, ThreadedSuperShuckieCore};
+use supershuckie_replay_recorder::replay_file::{blake3_hash_to_ascii, scrub_replay_header, ReplayConsoleType, ReplayHeaderBlake3Hash, ReplayPatchFormat};
+use supershuckie_replay_recorder::{blake3_hash, ByteVec, InputBuffer, KeyframeMetadata, Packet, PacketIO, UnsignedInteger};
 use supershuckie_replay_recorder::replay_file::playback::ReplayFilePlayer;
-use supershuckie_replay_recorder::replay_file::record::ReplayFileRecorderSettings;
+use supershuckie_replay_recorder::replay_file::record::{benchmark_compression_levels, pick_compression_level, supported_compression_levels, CompressionBenchmarkResult, ReplayFileRecorderSettings, ReplayFileSink};
 
 const SETTINGS_FILE: &str = "settings.json";
 const SAVE_STATE_EXTENSION: &str = "save_state";
 const SAVE_DATA_EXTENSION: &str = "sav";
 const REPLAY_EXTENSION: &str = "replay";
+const STATS_FILE: &str = "stats.json";
+const PRINTED_PAGE_EXTENSION: &str = "png";
+const SCREENSHOT_EXTENSION: &str = "png";
+const CLIP_EXTENSION: &str = "gif";
+const AUDIO_EXTENSION: &str = "wav";
+const ARCHIVE_EXTENSION: &str = "zip";
+
+/// How often play stats are written while a ROM is running.
+const STATS_WRITE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Subfolder of the save states folder that autosnaps are stored in (see [`AutosnapSettings`]).
+const AUTOSNAP_FOLDER: &str = "autosnap";
+
+/// Subfolder of the save data folder that save data backups are stored in (see
+/// [`SaveBackupSettings`]).
+const SAVE_BACKUP_FOLDER: &str = "backups";
+
+/// Folder next to the save states folder that save states which failed to load are moved to.
+const QUARANTINE_FOLDER: &str = "quarantined save states";
+
+/// Extension of the file next to each quarantined save state that explains why it failed to load.
+const QUARANTINE_REASON_EXTENSION: &str = "txt";
+
+/// Number of keyframes recorded for the compression benchmark sample.
+const COMPRESSION_SAMPLE_KEYFRAMES: u64 = 8;
+
+/// How much faster than recording compression must be when picking a compression level.
+///
+/// Compression happens on a separate thread, but it still has to keep up with recording, and the
+/// benchmark only gives a rough idea of what will happen while the game is also running.
+const COMPRESSION_SPEED_MARGIN: f64 = 4.0;
+
+/// How often to check whether the host is on battery (see [`PowerSaving::OnBattery`]).
+const POWER_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How often the ROM's folders are checked for files added or removed by other programs.
+const STORAGE_CHECK_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Categories checked for files added or removed by other programs.
+const WATCHED_STORAGE_CATEGORIES: [StorageCategory; 3] = [StorageCategory::SaveStates, StorageCategory::SaveData, StorageCategory::Replays];
+
+/// Maximum number of events kept for [`SuperShuckieFrontend::poll_event`].
+const MAX_QUEUED_EVENTS: usize = 256;
+
+/// Maximum number of frames in a clip made with [`SuperShuckieFrontend::export_replay_clip`]
+/// (about 30 seconds).
+const MAX_CLIP_FRAMES: u32 = 30 * 60;
+
+/// Shortest delay between clip frames, as many GIF viewers slow down anything shorter.
+const MIN_CLIP_FRAME_DELAY_CENTISECONDS: u64 = 2;
+
+/// Bytes of recorded audio kept in memory before being written out.
+const AUDIO_RECORDING_BUFFER_SIZE: usize = 256 * 1024;
+
+/// Largest scale screenshots and clips can be exported at.
+pub const MAX_EXPORT_SCALE: u8 = 16;
 
 pub type ConnectedControllerIndex = u32;
 
+/// Identifies a hook added with [`SuperShuckieFrontend::add_event_hook`].
+pub type EventHookId = u32;
+
+/// Called with every [`FrontendEvent`] (see [`SuperShuckieFrontend::add_event_hook`]).
+pub type EventHook = Box<dyn FnMut(&FrontendEvent)>;
+
 #[derive(Copy, Clone, PartialEq, Debug)]
 pub enum SuperShuckieEmulatorType {
     GameBoy,
@@ -43,46 +132,114 @@ pub struct SuperShuckieFrontend {
 
     callbacks: Box<dyn SuperShuckieFrontendCallbacks>,
 
+    /// Everything in `user_dir` is accessed through this
+    files: Box<dyn FileStore>,
     user_dir: PathBuf,
     frame_count: u32,
-    pokeabyte_error: Option<UTF8CString>,
+    pokeabyte_error: Option<LocalizedError>,
+    pokeabyte_address: Option<UTF8CString>,
 
     loaded_rom_data: Option<Vec<u8>>,
+    rom_checksum: Option<UTF8CString>,
+
+    /// Profiles added with [`SuperShuckieFrontend::add_game_profile`]
+    embedded_game_profiles: Vec<GameProfile>,
+    game_profile: Option<GameProfile>,
 
     current_input: Input,
     current_rapid_fire_input: Option<SuperShuckieRapidFire>,
     current_toggled_input: Option<Input>,
+    current_tilt: [f64; 4],
     current_save_state_history: Vec<Vec<u8>>,
     current_save_state_history_position: usize,
 
     connected_controllers: BTreeMap<ConnectedControllerIndex, UTF8CString>,
 
+    /// GUIDs of connected controllers, if known (see [`SuperShuckieFrontend::set_controller_guid`])
+    connected_controller_guids: BTreeMap<ConnectedControllerIndex, String>,
+
     rom_name: Option<Arc<UTF8CString>>,
     save_file: Option<Arc<UTF8CString>>,
+
+    /// Set if the SRAM came from a replay and must not be written to disk (see
+    /// [`ReplaySettings::sandbox_playback_sram`])
+    sram_sandboxed: bool,
     recording_replay_file: Option<ReplayFileInfo>,
 
     paused: bool,
+    paused_by_focus_loss: bool,
+    minimized: bool,
+    ui_navigation_captured: bool,
+    power_saving_active: bool,
+    last_power_check: Option<Instant>,
+    skip_next_render: bool,
+    replay_mismatches: Vec<ReplayMismatch>,
+    serial_capture: bool,
+    serial_output: Vec<u8>,
+    audio_sample_rate: u32,
+    audio_samples: Vec<i16>,
+    audio_recording: Option<AudioRecording>,
+    replay_browser_cache: ReplayBrowserCache,
+    storage_snapshot: Option<Vec<BTreeSet<String>>>,
+    last_storage_check: Instant,
+    seeking_forward: bool,
+    next_seek_backward: Option<Instant>,
+    peripheral_output: PeripheralOutput,
+
+    rom_stats: Option<ROMStats>,
+    save_profiles: Option<SaveProfiles>,
+    last_playtime_update: Instant,
+    last_stats_write: Instant,
+    autosnap_playtime: Duration,
+    instance_listener: Option<InstanceListener>,
+
+    events: Option<VecDeque<FrontendEvent>>,
+    event_hooks: Vec<(EventHookId, EventHook)>,
+    next_event_hook_id: EventHookId,
+    playback_finished: bool,
+    visualizers: Visualizers,
+    messages: MessageCatalog,
+    previous_screens: Vec<ScreenData>,
+    onion_skin: OnionSkin,
+    effective_speed: Speed,
+    displayed_screens: Vec<ScreenData>,
+
+    /// Encoding of screens passed to the callbacks (see [`SuperShuckieFrontend::set_preferred_screen_encodings`])
+    screen_encoding: ScreenDataEncoding,
+    encoded_screens: Vec<ScreenData>,
 
     settings: Settings
 }
 
 impl SuperShuckieFrontend {
     pub fn new<P: AsRef<Path>>(user_dir: P, callbacks: Box<dyn SuperShuckieFrontendCallbacks>) -> Self {
+        Self::new_with_file_store(user_dir, callbacks, Box::new(DiskFileStore))
+    }
+
+    /// Like [`SuperShuckieFrontend::new`], but access the user directory and ROMs through `files`
+    /// instead of the disk.
+    pub fn new_with_file_store<P: AsRef<Path>>(user_dir: P, callbacks: Box<dyn SuperShuckieFrontendCallbacks>, files: Box<dyn FileStore>) -> Self {
         let user_dir = user_dir.as_ref().to_owned();
 
         // FIXME: Check this
-        let settings = try_to_init_user_dir_and_get_settings(user_dir.as_ref()).expect("failed to init user_dir");
+        let (settings, first_run) = try_to_init_user_dir_and_get_settings(files.as_ref(), user_dir.as_ref()).expect("failed to init user_dir");
 
         let mut s = Self {
             core: ThreadedSuperShuckieCore::new(Box::new(NullEmulatorCore)),
             core_metadata: CoreMetadata { emulator_type: None },
+            files,
             user_dir,
             rom_name: None,
             save_file: None,
+            sram_sandboxed: false,
             loaded_rom_data: None,
+            rom_checksum: None,
+            embedded_game_profiles: Vec::new(),
+            game_profile: None,
             frame_count: 0,
             current_rapid_fire_input: None,
             current_toggled_input: None,
+            current_tilt: [0.0; 4],
             callbacks,
             settings,
             current_input: Input::default(),
@@ -90,34 +247,101 @@ impl SuperShuckieFrontend {
             current_save_state_history_position: 0,
             recording_replay_file: None,
             pokeabyte_error: None,
+            pokeabyte_address: None,
             paused: false,
-            connected_controllers: BTreeMap::new()
+            paused_by_focus_loss: false,
+            minimized: false,
+            ui_navigation_captured: false,
+            power_saving_active: false,
+            last_power_check: None,
+            skip_next_render: false,
+            replay_mismatches: Vec::new(),
+            serial_capture: false,
+            serial_output: Vec::new(),
+            audio_sample_rate: 0,
+            audio_samples: Vec::new(),
+            audio_recording: None,
+            replay_browser_cache: ReplayBrowserCache::default(),
+            storage_snapshot: None,
+            last_storage_check: Instant::now(),
+            seeking_forward: false,
+            next_seek_backward: None,
+            peripheral_output: PeripheralOutput::default(),
+            rom_stats: None,
+            save_profiles: None,
+            last_playtime_update: Instant::now(),
+            last_stats_write: Instant::now(),
+            autosnap_playtime: Duration::ZERO,
+            instance_listener: None,
+            events: None,
+            event_hooks: Vec::new(),
+            next_event_hook_id: 0,
+            playback_finished: false,
+            visualizers: Visualizers::default(),
+            messages: MessageCatalog::default(),
+            previous_screens: Vec::new(),
+            onion_skin: OnionSkin::default(),
+            effective_speed: Speed::default(),
+            displayed_screens: Vec::new(),
+            screen_encoding: ScreenDataEncoding::default(),
+            encoded_screens: Vec::new(),
+            connected_controllers: BTreeMap::new(),
+            connected_controller_guids: BTreeMap::new()
         };
 
         s.unload_rom();
 
+        if first_run {
+            s.reset_keyboard_controls_to_defaults();
+            s.write_settings();
+        }
+
+        if s.settings.instance.single_instance {
+            s.begin_instance_listener();
+        }
+
+        s.update_power_saving();
+
+        // Fall back to the default language if it's gone
+        if let Ok(messages) = MessageCatalog::load(s.files.as_ref(), &s.user_dir, s.settings.locale.language.as_str()) {
+            s.messages = messages;
+        }
+
         s
     }
 
+    /// Write a file through the frontend's file store, creating its folder if needed.
+    ///
+    /// This is mainly for adding ROMs to a store that isn't on disk (see
+    /// [`SuperShuckieFrontend::new_with_file_store`]).
+    pub fn write_file(&self, path: &Path, data: &[u8]) -> Result<(), LocalizedError> {
+        if let Some(parent) = path.parent() && !parent.as_os_str().is_empty() {
+            self.files.create_dir_all(parent).map_err(|e| LocalizedError::new("folder_create_failed").arg("path", parent.display()).arg("error", e))?;
+        }
+        self.files.write(path, data).map_err(|e| LocalizedError::new("file_write_failed").arg("path", path.display()).arg("error", e))
+    }
+
     /// Create a save state.
     ///
     /// If `name` is set, that name will be used.
     ///
+    /// If `cancel` is cancelled before the save state is created, nothing is written.
+    ///
     /// Returns the name of the save state if created.
-    pub fn create_save_state(&mut self, name: Option<&str>) -> Result<UTF8CString, UTF8CString> {
+    pub fn create_save_state(&mut self, name: Option<&str>, cancel: Option<&CancellationToken>) -> Result<UTF8CString, LocalizedError> {
         if !self.is_game_running() {
-            return Err("Game not running".into())
+            return Err(LocalizedError::new("game_not_running"))
         }
 
         let current_rom_name = self.get_current_rom_name().expect("no rom name when game is running in create_save_state");
         let save_states_dir = self.get_save_states_dir_for_rom(current_rom_name);
 
-        let (mut file, filename, _) = self.load_file_or_make_generic(&save_states_dir, name, None, SAVE_STATE_EXTENSION)?;
-
-        let state = self.create_save_state_now();
-        file.write_all(&state)
-            .map_err(|e| format!("Can't write to {filename}: {e}").into())
-            .map(|_| filename.into())
+        let callbacks = &mut self.callbacks;
+        let Some(state) = self.core.create_save_state_with_progress(cancel, |e| callbacks.report_progress(e)) else {
+            return Err(cancelled_or(cancel, LocalizedError::new("save_state_create_failed")))
+        };
+        self.write_file_or_make_generic(&save_states_dir, name, None, SAVE_STATE_EXTENSION, &state)
+            .map(|filename| filename.into())
     }
 
     /// Connect a controller.
@@ -141,6 +365,160 @@ impl SuperShuckieFrontend {
     /// Disconnect a controller.
     pub fn disconnect_controller(&mut self, controller: ConnectedControllerIndex) {
         self.connected_controllers.remove(&controller);
+        self.connected_controller_guids.remove(&controller);
+    }
+
+    /// Set the GUID of a connected controller (e.g. from SDL).
+    ///
+    /// This is saved with its controls and included in exported control profiles, so profiles can
+    /// be installed for the same model even if it reports a different name.
+    pub fn set_controller_guid(&mut self, controller: ConnectedControllerIndex, guid: &str) {
+        let Some(name) = self.connected_controllers.get(&controller) else {
+            return
+        };
+
+        if let Some(settings) = self.settings.controls.controller_controls.get_mut(name.as_str()) {
+            settings.guid = Some(guid.to_owned());
+        }
+        self.connected_controller_guids.insert(controller, guid.to_owned());
+    }
+
+    /// Export controls to a control profile file at `path`.
+    ///
+    /// If `rom` is set, its per-ROM controls are exported instead of the global controls. If
+    /// `controller` is set, only that controller's controls are exported (and not the keyboard's).
+    pub fn export_control_profile(&self, path: &Path, rom: Option<&str>, controller: Option<&str>, name: &str) -> Result<(), LocalizedError> {
+        let controls = match rom {
+            Some(rom) => self.get_rom_control_settings(rom).ok_or_else(|| LocalizedError::new("rom_has_no_controls").arg("rom", rom))?,
+            None => &self.settings.controls
+        };
+
+        let guid_for = |controller_name: &str| {
+            self.connected_controllers
+                .iter()
+                .find(|(_, name)| name.as_str() == controller_name)
+                .and_then(|(index, _)| self.connected_controller_guids.get(index).cloned())
+        };
+        let profile = ControlProfile::from_controls(controls, name, controller, guid_for)
+            .map_err(|e| LocalizedError::new("control_profile_export_failed").arg("error", e))?;
+
+        let json = serde_json::to_string_pretty(&profile).expect("failed to serialize control profile");
+        self.files.write(path, json.as_bytes()).map_err(|e| LocalizedError::new("file_write_failed").arg("path", path.display()).arg("error", e))?;
+        Ok(())
+    }
+
+    /// Install the control profile file at `path`, replacing the controls of each controller in it
+    /// (and the keyboard controls, if it has any).
+    ///
+    /// If `rom` is set, it is installed into that ROM's controls instead of the global controls,
+    /// starting from a copy of the global controls if the ROM does not have its own.
+    ///
+    /// Returns the name of the profile.
+    pub fn install_control_profile(&mut self, path: &Path, rom: Option<&str>) -> Result<UTF8CString, LocalizedError> {
+        let json = self.files.read_to_string(path).map_err(|e| LocalizedError::new("file_read_failed").arg("path", path.display()).arg("error", e))?;
+        let profile = ControlProfile::from_json(&json).map_err(|e| LocalizedError::new("control_profile_invalid").arg("error", e))?;
+
+        let connected: Vec<(&str, Option<&str>)> = self.connected_controllers
+            .iter()
+            .map(|(index, name)| (name.as_str(), self.connected_controller_guids.get(index).map(String::as_str)))
+            .collect();
+
+        let mut controls = match rom {
+            Some(rom) => self.get_rom_control_settings(rom).unwrap_or(&self.settings.controls).clone(),
+            None => self.settings.controls.clone()
+        };
+        profile.install_into(&mut controls, &connected);
+
+        match rom {
+            Some(rom) => self.set_rom_control_settings(rom, Some(controls)),
+            None => self.set_control_settings(controls)
+        }
+
+        Ok(profile.name.into())
+    }
+
+    /// Export the loaded ROM's shareable user data (its own controls, input delay, and game profile)
+    /// to a game bundle file at `path`, along with `notes`.
+    ///
+    /// See [`GameBundle`].
+    pub fn export_game_bundle(&self, path: &Path, notes: &str) -> Result<(), LocalizedError> {
+        let (Some(rom_name), Some(checksum)) = (self.get_current_rom_name(), self.rom_checksum.as_ref()) else {
+            return Err(LocalizedError::new("game_not_running"))
+        };
+
+        let rom_config = self.settings.rom_config.get(rom_name);
+        let bundle = GameBundle {
+            version: GameBundle::VERSION(),
+            checksum: checksum.as_str().to_owned(),
+            rom_name: rom_name.to_owned(),
+            notes: notes.to_owned(),
+            controls: rom_config.and_then(|c| c.controls.clone()),
+            input_delay_frames: rom_config.map(|c| c.input_delay_frames).unwrap_or(0),
+            game_profile: self.game_profile.clone()
+        };
+
+        let json = serde_json::to_string_pretty(&bundle).expect("failed to serialize game bundle");
+        self.files.write(path, json.as_bytes()).map_err(|e| LocalizedError::new("file_write_failed").arg("path", path.display()).arg("error", e))?;
+        Ok(())
+    }
+
+    /// Import the game bundle file at `path` into the loaded ROM, replacing its controls and input
+    /// delay.
+    ///
+    /// The bundle must be for the loaded ROM. If it has a game profile, it is saved to the user
+    /// directory's [`GAME_PROFILES_FOLDER`] and loaded.
+    ///
+    /// Returns the bundle's notes.
+    pub fn import_game_bundle(&mut self, path: &Path) -> Result<UTF8CString, LocalizedError> {
+        let (Some(rom_name), Some(checksum)) = (self.get_current_rom_name_arc(), self.rom_checksum.clone()) else {
+            return Err(LocalizedError::new("game_not_running"))
+        };
+
+        let json = self.files.read_to_string(path).map_err(|e| LocalizedError::new("file_read_failed").arg("path", path.display()).arg("error", e))?;
+        let bundle = GameBundle::from_json(&json).map_err(|e| LocalizedError::new("game_bundle_invalid").arg("error", e))?;
+
+        if !bundle.matches(checksum.as_str()) {
+            return Err(match bundle.rom_name.is_empty() {
+                true => LocalizedError::new("game_bundle_wrong_rom"),
+                false => LocalizedError::new("game_bundle_wrong_named_rom").arg("rom", &bundle.rom_name)
+            })
+        }
+
+        if let Some(profile) = bundle.game_profile.as_ref() {
+            let profiles_dir = self.user_dir.join(GAME_PROFILES_FOLDER);
+            let profile_path = profiles_dir.join(format!("bundle-{}.json", checksum.as_str().to_ascii_lowercase()));
+            let profile_json = serde_json::to_string_pretty(profile).expect("failed to serialize game profile");
+            self.files.create_dir_all(&profiles_dir).map_err(|e| LocalizedError::new("folder_create_failed").arg("path", profiles_dir.display()).arg("error", e))?;
+            self.files.write(&profile_path, profile_json.as_bytes()).map_err(|e| LocalizedError::new("file_write_failed").arg("path", profile_path.display()).arg("error", e))?;
+        }
+
+        self.set_rom_control_settings(rom_name.as_str(), bundle.controls);
+        self.set_rom_input_delay(rom_name.as_str(), bundle.input_delay_frames);
+        if bundle.game_profile.is_some() {
+            self.load_game_profile();
+        }
+
+        Ok(bundle.notes.into())
+    }
+
+    /// Set whether or not all controller buttons go to the `ui_navigation` callback instead of the
+    /// game (e.g. while a controller-driven menu is open).
+    ///
+    /// Buttons not bound to any control always go to the callback.
+    pub fn set_ui_navigation_captured(&mut self, captured: bool) {
+        self.ui_navigation_captured = captured;
+
+        // Otherwise, anything held down would stay held down, since the release goes to the UI
+        if captured && self.is_game_running() {
+            self.current_input = Input::default();
+            self.core.enqueue_input(self.current_input);
+        }
+    }
+
+    /// Get whether or not all controller buttons go to the `ui_navigation` callback.
+    #[inline]
+    pub fn is_ui_navigation_captured(&self) -> bool {
+        self.ui_navigation_captured
     }
 
     /// Get the name of the connected controller.
@@ -153,24 +531,62 @@ impl SuperShuckieFrontend {
         self.connected_controllers.get(&controller).map(|i| i.as_c_str())
     }
 
-    fn load_file_or_make_generic(&mut self, dir: &Path, name: Option<&str>, generic_prefix: Option<&str>, extension: &str) -> Result<(File, String, PathBuf), UTF8CString> {
+    /// Create a replay file through the file store, named `name` if set, or otherwise the first
+    /// unused generic name.
+    fn create_replay_sink_or_make_generic(&self, dir: &Path, name: Option<&str>, generic_prefix: Option<&str>, extension: &str, kind: ReplaySinkKind) -> Result<(ThreadedReplayFileSink, String, PathBuf), LocalizedError> {
         match name {
             Some(name) => {
-                let filename = format!("{name}.{extension}");
-                let path = dir.join(&filename);
-                Ok((File::create(&path).map_err(|e| format!("Can't open {name} for writing: {e}"))?, filename, path))
+                let (filename, path) = get_named_file_path(dir, name, extension)?;
+                if let Some(parent) = path.parent() {
+                    self.files.create_dir_all(parent).map_err(|e| LocalizedError::new("named_folder_create_failed").arg("name", name).arg("error", e))?;
+                }
+                let sink = self.files.create_replay_sink(&path, false, kind);
+                Ok((sink.map_err(|e| LocalizedError::new("named_file_open_failed").arg("name", name).arg("error", e))?, filename, path))
             },
             None => {
-                let prefix = generic_prefix.unwrap_or(self.get_current_save_name().expect("no save name when game is running in load_file_or_make_generic"));
+                let prefix = generic_prefix.unwrap_or(self.get_current_save_name().expect("no save name when game is running in create_replay_sink_or_make_generic"));
                 let mut i = 0u64;
                 loop {
                     let filename = format!("{prefix}-{i}.{extension}");
                     let path = dir.join(&filename);
-                    let Ok(file) = File::create_new(&path) else {
-                        i = i.checked_add(1).ok_or_else(|| UTF8CString::from_str("Maximum number of generics reached."))?;
-                        continue
-                    };
-                    return Ok((file, filename, path))
+                    match self.files.create_replay_sink(&path, true, kind) {
+                        Ok(sink) => return Ok((sink, filename, path)),
+                        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                            i = i.checked_add(1).ok_or_else(|| LocalizedError::new("too_many_generic_names"))?;
+                        },
+                        Err(e) => return Err(LocalizedError::new("named_file_open_failed").arg("name", filename).arg("error", e))
+                    }
+                }
+            }
+        }
+    }
+
+    /// Like [`SuperShuckieFrontend::create_replay_sink_or_make_generic`], but write `data` instead
+    /// of opening the file for recording.
+    ///
+    /// Returns the filename.
+    fn write_file_or_make_generic(&self, dir: &Path, name: Option<&str>, generic_prefix: Option<&str>, extension: &str, data: &[u8]) -> Result<String, LocalizedError> {
+        match name {
+            Some(name) => {
+                let (filename, path) = get_named_file_path(dir, name, extension)?;
+                if let Some(parent) = path.parent() {
+                    self.files.create_dir_all(parent).map_err(|e| LocalizedError::new("named_folder_create_failed").arg("name", name).arg("error", e))?;
+                }
+                self.files.write(&path, data).map_err(|e| LocalizedError::new("file_write_failed").arg("path", &filename).arg("error", e))?;
+                Ok(filename)
+            },
+            None => {
+                let prefix = generic_prefix.unwrap_or(self.get_current_save_name().expect("no save name when game is running in write_file_or_make_generic"));
+                let mut i = 0u64;
+                loop {
+                    let filename = format!("{prefix}-{i}.{extension}");
+                    match self.files.write_new(&dir.join(&filename), data) {
+                        Ok(()) => return Ok(filename),
+                        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                            i = i.checked_add(1).ok_or_else(|| LocalizedError::new("too_many_generic_names"))?;
+                        },
+                        Err(e) => return Err(LocalizedError::new("file_write_failed").arg("path", filename).arg("error", e))
+                    }
                 }
             }
         }
@@ -181,197 +597,741 @@ impl SuperShuckieFrontend {
     /// If it does, and it is successfully loaded, `Ok(true)` is returned.
     ///
     /// If it does not exist, `Ok(false)` is returned.
-    pub fn load_save_state_if_exists(&mut self, name: &str) -> Result<bool, UTF8CString> {
+    pub fn load_save_state_if_exists(&mut self, name: &str) -> Result<bool, LocalizedError> {
         if !self.is_game_running() {
-            return Err("Game not running".into())
+            return Err(LocalizedError::new("game_not_running"))
         }
 
         let current_rom_name = self.get_current_rom_name().expect("no rom name when game is running in load_save_state_if_exists");
         let save_states_dir = self.get_save_states_dir_for_rom(current_rom_name);
-        let save_state_file = save_states_dir.join(format!("{name}.{SAVE_STATE_EXTENSION}"));
+        let (_, save_state_file) = get_named_file_path(&save_states_dir, name, SAVE_STATE_EXTENSION)?;
 
-        if !save_state_file.is_file() {
+        if !self.files.is_file(&save_state_file) {
             return Ok(false)
         }
 
-        self.push_save_state_history();
+        let save_state = self.files.read(&save_state_file).map_err(|e| LocalizedError::new("save_state_load_failed").arg("name", name).arg("error", e))?;
+        let backup = self.create_save_state_now();
+        if let Err(e) = self.core.load_save_state(save_state) {
+            return Err(self.quarantine_save_state(name, &save_state_file, &e))
+        }
 
-        let save_state = std::fs::read(save_state_file).map_err(|e| format!("Failed to load save state {name}: {e}"))?;
-        self.core.load_save_state(save_state);
+        self.push_save_state_history(backup);
+        self.push_event(FrontendEvent::StateLoaded { name: Some(name.to_owned()) });
         Ok(true)
     }
 
-    /// Loads a replay with the given name if it exists.
-    ///
-    /// If it does, and it is successfully loaded, `Ok(true)` is returned.
+    /// Move a save state that failed to load into quarantine, returning an error describing what
+    /// happened.
+    fn quarantine_save_state(&self, name: &str, path: &Path, error: &str) -> LocalizedError {
+        let rom_name = self.get_current_rom_name().expect("no rom name when game is running in quarantine_save_state");
+        let quarantine_dir = self.get_quarantine_dir_for_rom(rom_name);
+
+        let quarantine = || -> Result<String, LocalizedError> {
+            let (quarantined_name, target) = get_unused_named_file_path(self.files.as_ref(), &quarantine_dir, name, SAVE_STATE_EXTENSION)?;
+            let (_, reason_file) = get_named_file_path(&quarantine_dir, &quarantined_name, QUARANTINE_REASON_EXTENSION)?;
+
+            if let Some(parent) = target.parent() {
+                self.files.create_dir_all(parent).map_err(|e| LocalizedError::new("folder_create_failed").arg("path", parent.display()).arg("error", e))?;
+            }
+            self.files.rename(path, &target).map_err(|e| LocalizedError::new("save_state_move_failed").arg("path", target.display()).arg("error", e))?;
+            let _ = self.files.write(&reason_file, self.describe_save_state_load_error(error).as_bytes());
+
+            Ok(quarantined_name)
+        };
+
+        match quarantine() {
+            Ok(quarantined_name) => LocalizedError::new("save_state_quarantined")
+                .arg("name", name)
+                .arg("quarantined_name", quarantined_name)
+                .arg("error", error),
+            Err(e) => LocalizedError::new("save_state_quarantine_failed")
+                .arg("name", name)
+                .arg("error", error)
+                .arg("quarantine_error", e.format(&self.messages))
+        }
+    }
+
+    fn describe_save_state_load_error(&self, error: &str) -> String {
+        format!("This save state failed to load with {}:\n\n{error}\n", self.core.get_core_name())
+    }
+
+    /// Get all save states for the given ROM that were quarantined because they failed to load.
+    pub fn get_quarantined_save_states_for_rom(&self, rom: &str) -> Vec<QuarantinedSaveState> {
+        let quarantine_dir = self.get_quarantine_dir_for_rom(rom);
+        list_named_files_in_dir_with_extension(self.files.as_ref(), &quarantine_dir, SAVE_STATE_EXTENSION)
+            .into_iter()
+            .map(|name| {
+                let reason = get_named_file_path(&quarantine_dir, name.as_str(), QUARANTINE_REASON_EXTENSION)
+                    .ok()
+                    .and_then(|(_, path)| self.files.read_to_string(&path).ok())
+                    .unwrap_or_default();
+                QuarantinedSaveState { name, reason: reason.trim_end().into() }
+            })
+            .collect()
+    }
+
+    /// Try loading a quarantined save state again, such as after switching cores or changing
+    /// settings.
     ///
-    /// If it does not exist, `Ok(false)` is returned.
-    pub fn load_replay_if_exists(&mut self, name: &str, override_errors: bool) -> Result<bool, UTF8CString> {
+    /// If it loads, it is moved back to the save states folder, and its new name is returned (which
+    /// only differs if a save state with the same name was made since). Otherwise, it stays in
+    /// quarantine with an updated reason.
+    pub fn retry_quarantined_save_state(&mut self, name: &str) -> Result<UTF8CString, LocalizedError> {
         if !self.is_game_running() {
-            return Err("Game not running".into())
+            return Err(LocalizedError::new("game_not_running"))
         }
 
-        let current_rom_name = self.get_current_rom_name().expect("no rom name when game is running in load_replay_if_exists");
-        let replay_dir = self.get_replays_dir_for_rom(current_rom_name);
-        let replay_file = replay_dir.join(format!("{name}.{REPLAY_EXTENSION}"));
+        let rom_name = self.get_current_rom_name_arc().expect("no rom name when game is running in retry_quarantined_save_state");
+        let quarantine_dir = self.get_quarantine_dir_for_rom(rom_name.as_str());
+        let (_, quarantined_file) = get_named_file_path(&quarantine_dir, name, SAVE_STATE_EXTENSION)?;
+        let (_, reason_file) = get_named_file_path(&quarantine_dir, name, QUARANTINE_REASON_EXTENSION)?;
 
-        if !replay_file.is_file() {
-            return Ok(false)
+        if !self.files.is_file(&quarantined_file) {
+            return Err(LocalizedError::new("quarantined_save_state_not_found").arg("name", name))
         }
 
-        let file = match std::fs::read(replay_file) {
-            Ok(n) => n,
-            Err(e) => {
-                return Err(format!("Failed to read replay {name}:\n\n{e}").into())
-            }
-        };
+        let save_state = self.files.read(&quarantined_file).map_err(|e| LocalizedError::new("save_state_load_failed").arg("name", name).arg("error", e))?;
+        let backup = self.create_save_state_now();
+        if let Err(e) = self.core.load_save_state(save_state) {
+            let _ = self.files.write(&reason_file, self.describe_save_state_load_error(&e).as_bytes());
+            return Err(LocalizedError::new("save_state_still_fails").arg("name", name).arg("error", e))
+        }
 
-        let mut player = match ReplayFilePlayer::new(file, override_errors) {
-            Ok(n) => n,
-            Err(e) => {
-                return Err(format!("Failed to parse replay {name}:\n\n{e:?}").into())
-            }
-        };
+        self.push_save_state_history(backup);
 
-        if self.settings.replay_settings.auto_decompress_replays_upfront {
-            player.decompress_all_blobs();
+        let (restored_name, restored_file) = get_unused_named_file_path(self.files.as_ref(), &self.get_save_states_dir_for_rom(rom_name.as_str()), name, SAVE_STATE_EXTENSION)?;
+        if let Some(parent) = restored_file.parent() {
+            self.files.create_dir_all(parent).map_err(|e| LocalizedError::new("save_state_restore_failed").arg("name", name).arg("path", parent.display()).arg("error", e))?;
         }
+        self.files.rename(&quarantined_file, &restored_file)
+            .map_err(|e| LocalizedError::new("save_state_restore_move_failed").arg("name", name).arg("path", restored_file.display()).arg("error", e))?;
+        let _ = self.files.remove_file(&reason_file);
 
-        let current_emulator_type = self.core_metadata.emulator_type.expect("???? no emulator type when reloading a replay?");
-        let metadata = player.get_replay_metadata();
-        let expected_type = match metadata.console_type {
-            ReplayConsoleType::GameBoy => SuperShuckieEmulatorType::GameBoy,
-            ReplayConsoleType::SuperGameBoy2 => SuperShuckieEmulatorType::GameBoySGB2,
-            ReplayConsoleType::GameBoyColor => SuperShuckieEmulatorType::GameBoyColor,
-            _ => current_emulator_type
-        };
+        self.push_event(FrontendEvent::StateLoaded { name: Some(restored_name.clone()) });
+        Ok(restored_name.into())
+    }
 
-        if current_emulator_type != expected_type {
-            self.instantiate_and_load_core(expected_type);
+    /// Render a preview of the save state with the given name without disturbing the running game.
+    ///
+    /// The save state is loaded into a scratch core which is then run until it produces a frame.
+    ///
+    /// If the save state does not exist, `Ok(None)` is returned.
+    pub fn render_save_state_preview(&self, name: &str) -> Result<Option<Vec<ScreenData>>, LocalizedError> {
+        if !self.is_game_running() {
+            return Err(LocalizedError::new("game_not_running"))
         }
 
-        if let Err(e) = self.core.attach_replay_player(player, override_errors) {
-            return match e {
-                ReplayPlayerAttachError::Incompatible { description } => {
-                    Err(format!("This replay file is incompatible:\n\n{description}").into())
-                }
-                ReplayPlayerAttachError::MismatchedMetadata { issues } => {
-                    let mut err = String::new();
+        let current_rom_name = self.get_current_rom_name().expect("no rom name when game is running in render_save_state_preview");
+        let (_, save_state_file) = get_named_file_path(&self.get_save_states_dir_for_rom(current_rom_name), name, SAVE_STATE_EXTENSION)?;
 
-                    err += "This replay file has mismatched data which may prevent playback:";
+        if !self.files.is_file(&save_state_file) {
+            return Ok(None)
+        }
 
-                    for issue in issues {
-                        err += "\n\n";
-                        err += &issue.to_string();
-                    }
+        let save_state = self.files.read(&save_state_file).map_err(|e| LocalizedError::new("save_state_load_failed").arg("name", name).arg("error", e))?;
 
-                    Err(err.into())
-                }
+        let emulator_type = self.core_metadata.emulator_type.expect("render_save_state_preview with no emulator type");
+        let rom_data = self.loaded_rom_data.as_ref().expect("render_save_state_preview with no loaded rom");
+        let mut core = self.make_new_core(rom_data, None, emulator_type);
+        core.load_save_state(&save_state).map_err(|e| LocalizedError::new("save_state_load_failed").arg("name", name).arg("error", e))?;
+
+        // Don't spin forever if the game never finishes a frame for some reason
+        let max_ticks = core.ticks_per_second();
+        let mut ticks = 0u64;
+        while ticks < max_ticks {
+            let time = core.run_unlocked();
+            if time.frames > 0 {
+                break
             }
+            ticks = ticks.saturating_add(time.ticks.max(1));
         }
 
-        self.save_file = Some(Arc::new("replay".into()));
+        Ok(Some(core.get_screens().to_vec()))
+    }
 
-        Ok(true)
+    /// Compress a sample of replay data from the running game at each compression level from 1 to
+    /// the maximum, reporting the size and time taken for each.
+    ///
+    /// The sample is made by running a scratch core from the current state and recording keyframes
+    /// the same way a real recording would, so the game is not disturbed. This can take a few
+    /// seconds on slower machines.
+    pub fn benchmark_replay_compression(&self) -> Result<Vec<CompressionBenchmarkResult>, LocalizedError> {
+        self.benchmark_replay_compression_with_duration().map(|i| i.0)
     }
 
-    /// Stop playing back any currently playing replay.
-    #[inline]
-    pub fn stop_replay_playback(&mut self) {
-        self.core.detach_replay_player();
-        self.reset_speed();
-        self.current_input = Input::default();
+    /// Benchmark compression (see [`SuperShuckieFrontend::benchmark_replay_compression`]) and use
+    /// the compression level that compresses the best while comfortably keeping up with recording
+    /// at turbo speed.
+    ///
+    /// Returns the new compression level.
+    pub fn optimize_replay_compression_level(&mut self) -> Result<i32, LocalizedError> {
+        let (results, sample_duration) = self.benchmark_replay_compression_with_duration()?;
+        let Some(first) = results.first() else {
+            return Err(LocalizedError::new("no_compression_levels"))
+        };
+
+        // Bytes recorded per second of gameplay at 1x speed
+        let bytes_per_second = first.uncompressed_size as f64 / sample_duration.as_secs_f64().max(f64::MIN_POSITIVE);
+
+        let max_speed = self.settings.emulation.base_speed_multiplier.max(self.settings.emulation.turbo_speed_multiplier);
+        let required = bytes_per_second * max_speed * COMPRESSION_SPEED_MARGIN;
+
+        let level = pick_compression_level(&results, required).expect("results should not be empty");
+        self.settings.replay_settings.zstd_compression_level = level;
+        Ok(level)
     }
 
-    /// Get the replay playback stats if currently playing back.
-    pub fn get_replay_playback_stats(&self) -> Option<SuperShuckieReplayTimes> {
-        if !self.core.is_playing_back() {
-            return None;
-        }
-        
-        let frames = self.core.get_playback_total_frames();
-        let ms = self.core.get_playback_total_milliseconds();
-        Some(SuperShuckieReplayTimes { total_milliseconds: ms, total_frames: frames })
+    /// Also returns the length of gameplay the sample covers.
+    fn benchmark_replay_compression_with_duration(&self) -> Result<(Vec<CompressionBenchmarkResult>, Duration), LocalizedError> {
+        let (sample, sample_duration) = self.make_replay_compression_sample()?;
+        let levels = 1..=*supported_compression_levels().end();
+        let results = benchmark_compression_levels(sample.as_slice(), levels).map_err(|e| LocalizedError::new("compression_benchmark_failed").arg("error", format!("{e:?}")))?;
+        Ok((results, sample_duration))
     }
 
-    fn push_save_state_history(&mut self) {
-        self.current_save_state_history.truncate(self.current_save_state_history_position);
-        self.current_save_state_history.push(self.create_save_state_now());
+    /// Record a few keyframes from the current state on a scratch core, returning the replay data
+    /// and the length of gameplay it covers.
+    fn make_replay_compression_sample(&self) -> Result<(Vec<u8>, Duration), LocalizedError> {
+        if !self.is_game_running() {
+            return Err(LocalizedError::new("game_not_running"))
+        }
 
-        while self.current_save_state_history.len() > self.settings.emulation.max_save_state_history.get() {
-            self.current_save_state_history.remove(0);
+        let emulator_type = self.core_metadata.emulator_type.expect("make_replay_compression_sample with no emulator type");
+        let rom_data = self.loaded_rom_data.as_ref().expect("make_replay_compression_sample with no loaded rom");
+        let mut core = self.make_new_core(rom_data, None, emulator_type);
+        let state = self.core.create_save_state().ok_or_else(|| LocalizedError::new("save_state_create_failed"))?;
+        core.load_save_state(&state).map_err(|e| LocalizedError::new("current_state_load_failed").arg("error", e))?;
+
+        let frames_per_keyframe = self.settings.replay_settings.frames_per_keyframe.get();
+        let ticks_per_second = core.ticks_per_second().max(1);
+        let mut sample = Vec::new();
+        let mut elapsed_frames = 0u64;
+        let mut elapsed_ticks = 0u64;
+        let mut elapsed_millis = 0u64;
+
+        for _ in 0..COMPRESSION_SAMPLE_KEYFRAMES {
+            let keyframe = Packet::Keyframe {
+                metadata: KeyframeMetadata { input: InputBuffer::new(), speed: Speed::default(), elapsed_frames, elapsed_millis },
+                state: ByteVec::Heap(core.create_save_state())
+            };
+            let _ = sample.write_packet_data(&keyframe.write_packet_instructions());
+
+            let mut frames = 0;
+            while frames < frames_per_keyframe {
+                let time = core.run_unlocked();
+                elapsed_ticks = elapsed_ticks.saturating_add(time.ticks);
+                for _ in 0..time.frames {
+                    let timestamp = elapsed_ticks.saturating_mul(1000) / ticks_per_second;
+                    let next_frame = Packet::NextFrame { timestamp_delta: timestamp - elapsed_millis };
+                    let _ = sample.write_packet_data(&next_frame.write_packet_instructions());
+                    elapsed_millis = timestamp;
+                }
+                frames += time.frames;
+            }
+            elapsed_frames += frames;
         }
 
-        self.current_save_state_history_position = self.current_save_state_history.len();
+        Ok((sample, Duration::from_millis(elapsed_millis)))
+    }
+
+    /// Loads a replay with the given name if it exists.
+    ///
+    /// If it does, and it is successfully loaded, `Ok(true)` is returned.
+    ///
+    /// If it does not exist, `Ok(false)` is returned.
+    ///
+    /// If `override_errors` is set, all metadata mismatches are ignored, and some corruption is
+    /// tolerated.
+    pub fn load_replay_if_exists(&mut self, name: &str, override_errors: bool) -> Result<bool, LocalizedError> {
+        let acknowledged: &[ReplayMismatchKind] = if override_errors { &ReplayMismatchKind::ALL } else { &[] };
+        self.load_replay_inner(name, override_errors, acknowledged, &[], None)
+    }
 
+    /// Loads a replay with the given name if it exists, playing it back despite the given kinds of
+    /// metadata mismatches.
+    ///
+    /// Mismatches in `remembered` are also acknowledged, and they will continue to be acknowledged
+    /// for this replay as long as the same ROM, BIOS, save data, or core is loaded. Previously remembered
+    /// mismatches are always acknowledged.
+    ///
+    /// If any mismatches were not acknowledged, an error is returned and the replay is not loaded.
+    /// They can then be found with [`SuperShuckieFrontend::get_replay_mismatches`].
+    ///
+    /// If `cancel` is cancelled while the replay is being read, an error is returned and nothing
+    /// is changed.
+    pub fn load_replay_acknowledging(&mut self, name: &str, acknowledged: &[ReplayMismatchKind], remembered: &[ReplayMismatchKind], cancel: Option<&CancellationToken>) -> Result<bool, LocalizedError> {
+        self.load_replay_inner(name, false, acknowledged, remembered, cancel)
     }
 
-    fn create_save_state_now(&self) -> Vec<u8> {
-        self.core.create_save_state().expect("Failed to create a save state for an unknown reason (this is a bug!).") // TODO: handle this failing?
+    /// Get the metadata mismatches that were not acknowledged the last time a replay was loaded.
+    pub fn get_replay_mismatches(&self) -> &[ReplayMismatch] {
+        self.replay_mismatches.as_slice()
     }
 
-    /// Undo loading a save state, loading the state before loading the save state.
-    pub fn undo_load_save_state(&mut self) -> bool {
-        if self.current_save_state_history_position == 0 {
-            return false // no more to go
+    /// Stop acknowledging previously remembered metadata mismatches for the given replay of the
+    /// current ROM.
+    pub fn forget_replay_mismatches(&mut self, name: &str) {
+        let Some(rom_name) = self.get_current_rom_name_arc() else {
+            return
+        };
+        if let Some(config) = self.settings.rom_config.get_mut(rom_name.as_str()) {
+            config.acknowledged_replay_mismatches.remove(name);
         }
+    }
 
-        let backup = self.create_save_state_now();
-        self.current_save_state_history_position -= 1;
+    /// Returns `true` if the mismatch was remembered as acknowledged for the given replay.
+    fn is_replay_mismatch_remembered(&self, rom: &str, name: &str, mismatch: &ReplayMismatch) -> bool {
+        self.settings.rom_config.get(rom)
+            .and_then(|c| c.acknowledged_replay_mismatches.get(name))
+            .is_some_and(|remembered| remembered.iter().any(|m| m.kind == mismatch.kind && m.loaded == mismatch.loaded))
+    }
 
-        let history = &mut self.current_save_state_history[self.current_save_state_history_position];
-        let state_to_load = std::mem::replace(history, backup);
+    fn load_replay_inner(&mut self, name: &str, override_errors: bool, acknowledged: &[ReplayMismatchKind], remembered: &[ReplayMismatchKind], cancel: Option<&CancellationToken>) -> Result<bool, LocalizedError> {
+        self.replay_mismatches.clear();
 
-        self.core.load_save_state(state_to_load);
-        true
-    }
+        if !self.is_game_running() {
+            return Err(LocalizedError::new("game_not_running"))
+        }
 
-    /// Redo loading a save state, loading the save state before undoing loading the save state.
-    pub fn redo_load_save_state(&mut self) -> bool {
-        if self.current_save_state_history_position == self.current_save_state_history.len() {
-            return false // no more to go
+        let current_rom_name = self.get_current_rom_name_arc().expect("no rom name when game is running in load_replay_if_exists");
+        let replay_dir = self.get_replays_dir_for_rom(current_rom_name.as_str());
+        let (_, replay_file) = get_named_file_path(&replay_dir, name, REPLAY_EXTENSION)?;
+
+        if !self.files.is_file(&replay_file) {
+            return Ok(false)
         }
 
-        let backup = self.create_save_state_now();
+        let is_cancelled = || cancel.is_some_and(CancellationToken::is_cancelled);
+        let mut player = read_replay_file(self.files.as_ref(), &replay_file, name, override_errors)?;
 
-        let history = &mut self.current_save_state_history[self.current_save_state_history_position];
-        self.current_save_state_history_position += 1;
+        if self.settings.replay_settings.auto_decompress_replays_upfront {
+            player.decompress_all_blobs_until(is_cancelled);
+        }
 
-        let state_to_load = std::mem::replace(history, backup);
+        if is_cancelled() {
+            return Err(LocalizedError::new("cancelled"))
+        }
 
-        self.core.load_save_state(state_to_load);
-        true
-    }
+        // Keep any progress made before the replay replaces the SRAM
+        if !self.sram_sandboxed {
+            self.save_sram_unchecked();
+        }
 
-    pub fn on_user_input(&mut self, input: UserInput, value: f64) {
-        let Some(control) = (match input {
-            UserInput::Keyboard { keycode } => self.settings.controls.keyboard_controls.get(&keycode).copied(),
-            UserInput::Button { button, controller } => {
-                self.connected_controllers.get(&controller)
-                    .and_then(|i| self.settings.controls.controller_controls.get(i.as_str()))
-                    .and_then(|i| i.buttons.get(&button))
-                    .copied()
-            }
-            UserInput::Axis { axis, controller } => {
-                self.connected_controllers.get(&controller)
-                    .and_then(|i| self.settings.controls.controller_controls.get(i.as_str()))
-                    .and_then(|i| i.axis.get(&axis))
-                    .copied()
-            }
-        })
-        else {
-            return
-        };
+        let current_emulator_type = self.core_metadata.emulator_type.expect("???? no emulator type when reloading a replay?");
+        let expected_type = self.emulator_type_for_replay(&player);
 
-        let pressed = value > 0.5;
+        if current_emulator_type != expected_type {
+            self.instantiate_and_load_core(expected_type);
+        }
 
-        if control.control.is_button() {
-            if pressed && self.settings.replay_settings.auto_stop_playback_on_input && self.get_replay_playback_stats().is_some() {
-                self.stop_replay_playback();
+        let mut unacknowledged = Vec::new();
+        let mut newly_remembered = Vec::new();
+        for issue in self.core.get_replay_metadata_mismatches(player.get_replay_metadata()) {
+            let mismatch = ReplayMismatch::from(issue);
+            if remembered.contains(&mismatch.kind) {
+                newly_remembered.push(AcknowledgedReplayMismatch { kind: mismatch.kind, loaded: mismatch.loaded.clone() });
             }
-
-            if pressed && self.settings.replay_settings.auto_unpause_on_input && self.is_paused() {
-                self.set_paused(false);
+            else if !acknowledged.contains(&mismatch.kind) && !self.is_replay_mismatch_remembered(current_rom_name.as_str(), name, &mismatch) {
+                unacknowledged.push(mismatch);
+            }
+        }
+
+        if !unacknowledged.is_empty() {
+            let error = describe_replay_mismatches(unacknowledged.iter().map(|m| m.description.as_str()));
+            self.replay_mismatches = unacknowledged;
+            return Err(error)
+        }
+
+        // Mismatches were all checked above
+        if let Err(e) = self.core.attach_replay_player(player, true) {
+            return Err(describe_replay_attach_error(e))
+        }
+
+        if !newly_remembered.is_empty() {
+            let all_remembered = self.settings.get_rom_config_or_default(current_rom_name.as_str())
+                .acknowledged_replay_mismatches
+                .entry(name.to_owned())
+                .or_default();
+            all_remembered.retain(|m| !newly_remembered.iter().any(|n| n.kind == m.kind));
+            all_remembered.extend(newly_remembered);
+        }
+
+        self.save_file = Some(Arc::new("replay".into()));
+        self.sram_sandboxed = self.settings.replay_settings.sandbox_playback_sram;
+        self.push_event(FrontendEvent::ReplayStarted { name: name.to_owned(), recording: false });
+
+        Ok(true)
+    }
+
+    /// Check that the replay with the given name plays back to its last frame with the loaded ROM.
+    ///
+    /// The replay is played back as fast as possible on a scratch core, so the running game is not
+    /// disturbed. Unlike [`SuperShuckieFrontend::load_replay_if_exists`], problems such as a ROM
+    /// checksum mismatch are never overridden.
+    ///
+    /// Returns the number of frames played back.
+    pub fn verify_replay(&mut self, name: &str) -> Result<u32, LocalizedError> {
+        if !self.is_game_running() {
+            return Err(LocalizedError::new("game_not_running"))
+        }
+
+        let rom_name = self.get_current_rom_name_arc().expect("no rom name when game is running in verify_replay");
+        let (_, replay_file) = get_named_file_path(&self.get_replays_dir_for_rom(rom_name.as_str()), name, REPLAY_EXTENSION)?;
+        if !self.files.is_file(&replay_file) {
+            return Err(LocalizedError::new("replay_not_found").arg("name", name))
+        }
+
+        let player = read_replay_file(self.files.as_ref(), &replay_file, name, false)?;
+        let total_frames = player.get_total_frames();
+
+        let emulator_type = self.emulator_type_for_replay(&player);
+        let rom_data = self.loaded_rom_data.as_ref().expect("verify_replay with no loaded rom");
+        let mut core = SuperShuckieCore::new(self.make_new_core(rom_data, None, emulator_type), Box::new(EmulatedTimestampProvider::new()));
+
+        // The scratch core has no save data loaded, so there is nothing to compare it to
+        let issues: Vec<_> = core.get_replay_metadata_mismatches(player.get_replay_metadata())
+            .into_iter()
+            .filter(|i| !matches!(i, ReplayPlayerMetadataMismatchKind::SRAMChecksumMismatch { .. }))
+            .collect();
+        if !issues.is_empty() {
+            return Err(describe_replay_attach_error(ReplayPlayerAttachError::MismatchedMetadata { issues }))
+        }
+        core.attach_replay_player(player, true).map_err(describe_replay_attach_error)?;
+
+        while core.get_elapsed_frames() < total_frames && !core.is_replay_stalled() {
+            core.run_unlocked();
+        }
+
+        let elapsed_frames = core.get_elapsed_frames();
+        if elapsed_frames < total_frames {
+            return Err(LocalizedError::new("replay_stalled").arg("name", name).arg("frame", elapsed_frames).arg("total_frames", total_frames))
+        }
+
+        Ok(elapsed_frames.min(u32::MAX as UnsignedInteger) as u32)
+    }
+
+    /// Get the emulator type a replay was recorded with, or the current one if it is not known.
+    fn emulator_type_for_replay(&self, player: &ReplayFilePlayer) -> SuperShuckieEmulatorType {
+        let current_emulator_type = self.core_metadata.emulator_type.expect("emulator_type_for_replay with no emulator type");
+        match player.get_replay_metadata().console_type {
+            ReplayConsoleType::GameBoy => SuperShuckieEmulatorType::GameBoy,
+            ReplayConsoleType::SuperGameBoy2 => SuperShuckieEmulatorType::GameBoySGB2,
+            ReplayConsoleType::GameBoyColor => SuperShuckieEmulatorType::GameBoyColor,
+            _ => current_emulator_type
+        }
+    }
+
+    /// Render frames `first_frame` through `last_frame` (inclusive) of the replay with the given
+    /// name as a looping GIF, upscaled by `scale` (up to [`MAX_EXPORT_SCALE`]) with nearest-neighbor
+    /// scaling.
+    ///
+    /// Frames are rendered on a scratch core, so the running game is not disturbed. Clips are
+    /// limited to about 30 seconds, and frames are dropped as needed to keep the GIF playing at the
+    /// right speed. Frame blending is applied if enabled, but visualizers are not.
+    ///
+    /// Returns the filename of the clip, which is saved in the ROM's screenshots directory.
+    pub fn export_replay_clip(&mut self, name: &str, first_frame: u32, last_frame: u32, scale: NonZeroU8) -> Result<UTF8CString, LocalizedError> {
+        if scale.get() > MAX_EXPORT_SCALE {
+            return Err(LocalizedError::new("clip_scale_too_large").arg("max_scale", MAX_EXPORT_SCALE))
+        }
+
+        let mut core = self.open_replay_clip(name, first_frame, last_frame, 0)?;
+        let rom_name = self.get_current_rom_name_arc().expect("no rom name when game is running in export_replay_clip");
+
+        let frame_blending = self.settings.emulation.frame_blending;
+        let mut previous_screens = Vec::new();
+
+        let mut encoder = None;
+        let mut pending: Option<(ScreenData, u64)> = None;
+        let mut next_frame = first_frame as UnsignedInteger;
+
+        while core.get_elapsed_frames() <= last_frame as UnsignedInteger && !core.is_replay_stalled() {
+            if core.get_elapsed_frames() < next_frame {
+                core.run_unlocked();
+                continue
+            }
+            next_frame = core.get_elapsed_frames() + 1;
+
+            let mut screens = core.get_core().get_screens().to_vec();
+            if frame_blending {
+                blend_frames(&mut previous_screens, &mut screens);
+            }
+            let Some(screen) = screens.into_iter().next() else {
+                return Err(LocalizedError::new("clip_no_screen"))
+            };
+
+            let time = core.get_recording_milliseconds() / 10;
+            let encoder = match encoder.as_mut() {
+                Some(n) => n,
+                None => encoder.insert(gif::GifEncoder::new(screen.width, screen.height, scale.get() as usize).ok_or_else(|| LocalizedError::new("clip_too_large"))?)
+            };
+
+            match pending.as_ref() {
+                None => pending = Some((screen, time)),
+                Some((pending_screen, pending_time)) => {
+                    if time < pending_time + MIN_CLIP_FRAME_DELAY_CENTISECONDS || pending_screen.pixels == screen.pixels {
+                        continue
+                    }
+                    encoder.add_frame(pending_screen, (time - pending_time).min(u16::MAX as u64) as u16);
+                    pending = Some((screen, time));
+                }
+            }
+        }
+
+        let (Some(mut encoder), Some((pending_screen, _))) = (encoder, pending) else {
+            return Err(LocalizedError::new("clip_no_frames"))
+        };
+        encoder.add_frame(&pending_screen, MIN_CLIP_FRAME_DELAY_CENTISECONDS as u16);
+
+        let screenshots_dir = self.get_screenshots_dir_for_rom(rom_name.as_str());
+        let filename = self.write_file_or_make_generic(&screenshots_dir, None, Some("clip"), CLIP_EXTENSION, &encoder.finish())?;
+
+        Ok(filename.into())
+    }
+
+    /// Render the audio of frames `first_frame` through `last_frame` (inclusive) of the replay with
+    /// the given name as a WAV file at the audio output's sample rate (see
+    /// [`SuperShuckieFrontend::set_audio_sample_rate`]).
+    ///
+    /// This covers exactly the same time as [`SuperShuckieFrontend::export_replay_clip`] with the
+    /// same frames, so the two can be combined into one video. The same limits apply.
+    ///
+    /// Returns the filename of the recording, which is saved in the ROM's screenshots directory.
+    pub fn export_replay_audio(&mut self, name: &str, first_frame: u32, last_frame: u32) -> Result<UTF8CString, LocalizedError> {
+        let sample_rate = self.audio_sample_rate;
+        if sample_rate == 0 {
+            return Err(LocalizedError::new("audio_output_off"))
+        }
+
+        let mut core = self.open_replay_clip(name, first_frame, last_frame, sample_rate)?;
+        if core.get_audio_sample_rate() == 0 {
+            return Err(LocalizedError::new("audio_unsupported"))
+        }
+
+        // Skip the audio of the frames before the clip
+        let mut samples = Vec::new();
+        core.drain_audio_samples(&mut samples);
+        samples.clear();
+
+        let mut data = Vec::new();
+        while core.get_elapsed_frames() <= last_frame as UnsignedInteger && !core.is_replay_stalled() {
+            core.run_unlocked();
+            core.drain_audio_samples(&mut samples);
+            wav::append_samples(&mut data, &samples);
+            samples.clear();
+        }
+
+        let data_size = u32::try_from(data.len()).map_err(|_| LocalizedError::new("audio_recording_too_large"))?;
+        let mut wav = wav::wav_header(sample_rate, data_size).to_vec();
+        wav.extend_from_slice(&data);
+
+        let rom_name = self.get_current_rom_name_arc().expect("no rom name when game is running in export_replay_audio");
+        let screenshots_dir = self.get_screenshots_dir_for_rom(rom_name.as_str());
+        let filename = self.write_file_or_make_generic(&screenshots_dir, None, Some("audio"), AUDIO_EXTENSION, &wav)?;
+
+        Ok(filename.into())
+    }
+
+    /// Open the replay with the given name on a scratch core, positioned at `first_frame`, for
+    /// exporting a clip that ends at `last_frame`.
+    ///
+    /// The audio sample rate is set here (if nonzero) before anything is loaded, as SameBoy can't
+    /// load a save state made with audio off once audio is on.
+    fn open_replay_clip(&self, name: &str, first_frame: u32, last_frame: u32, audio_sample_rate: u32) -> Result<SuperShuckieCore, LocalizedError> {
+        if !self.is_game_running() {
+            return Err(LocalizedError::new("game_not_running"))
+        }
+
+        if last_frame < first_frame {
+            return Err(LocalizedError::new("clip_ends_before_start"))
+        }
+
+        if last_frame - first_frame >= MAX_CLIP_FRAMES {
+            return Err(LocalizedError::new("clip_too_long").arg("max_frames", MAX_CLIP_FRAMES))
+        }
+
+        let rom_name = self.get_current_rom_name_arc().expect("no rom name when game is running in open_replay_clip");
+        let (_, replay_file) = get_named_file_path(&self.get_replays_dir_for_rom(rom_name.as_str()), name, REPLAY_EXTENSION)?;
+        if !self.files.is_file(&replay_file) {
+            return Err(LocalizedError::new("replay_not_found").arg("name", name))
+        }
+
+        let player = read_replay_file(self.files.as_ref(), &replay_file, name, true)?;
+        if player.get_total_frames() <= first_frame as UnsignedInteger {
+            return Err(LocalizedError::new("replay_too_short").arg("name", name).arg("total_frames", player.get_total_frames()))
+        }
+
+        let emulator_type = self.emulator_type_for_replay(&player);
+        let rom_data = self.loaded_rom_data.as_ref().expect("open_replay_clip with no loaded rom");
+        let mut core = SuperShuckieCore::new(self.make_new_core(rom_data, None, emulator_type), Box::new(EmulatedTimestampProvider::new()));
+        if audio_sample_rate != 0 {
+            core.set_audio_sample_rate(audio_sample_rate);
+        }
+        core.attach_replay_player(player, true).map_err(describe_replay_attach_error)?;
+        core.go_to_replay_frame(first_frame as UnsignedInteger);
+        Ok(core)
+    }
+
+    /// Stop playing back any currently playing replay.
+    #[inline]
+    pub fn stop_replay_playback(&mut self) {
+        let was_playing_back = self.core.is_playing_back();
+        self.core.detach_replay_player();
+        self.seeking_forward = false;
+        self.next_seek_backward = None;
+        self.reset_speed();
+        self.current_input = Input::default();
+        self.current_tilt = [0.0; 4];
+        if was_playing_back {
+            self.push_event(FrontendEvent::ReplayEnded { recording: false });
+        }
+    }
+
+    /// Get the replay playback stats if currently playing back.
+    pub fn get_replay_playback_stats(&self) -> Option<SuperShuckieReplayTimes> {
+        if !self.core.is_playing_back() {
+            return None;
+        }
+        
+        let frames = self.core.get_playback_total_frames();
+        let ms = self.core.get_playback_total_milliseconds();
+        Some(SuperShuckieReplayTimes { total_milliseconds: ms, total_frames: frames })
+    }
+
+    /// Get the input the replay being played back is pressing on the current frame, or `None` if
+    /// not playing back.
+    ///
+    /// This is useful for displaying the replay's inputs on screen.
+    pub fn get_playback_input(&self) -> Option<Input> {
+        self.core.is_playing_back().then(|| self.core.get_applied_input())
+    }
+
+    /// Get all keyframes in the current playback, sorted by frame, or an empty slice if not playing
+    /// back.
+    #[inline]
+    pub fn get_playback_keyframes(&self) -> &[ReplayMarker] {
+        self.core.get_playback_keyframes()
+    }
+
+    /// Get all bookmarks in the current playback, sorted by frame, or an empty slice if not playing
+    /// back.
+    #[inline]
+    pub fn get_playback_bookmarks(&self) -> &[ReplayMarker] {
+        self.core.get_playback_bookmarks()
+    }
+
+    /// Get all bookmarks in the replay being played back or recorded, sorted by frame, or an empty
+    /// slice if neither.
+    pub fn get_bookmarks(&self) -> &[ReplayMarker] {
+        match self.recording_replay_file.as_ref() {
+            Some(replay_file) => replay_file.bookmarks.as_slice(),
+            None => self.core.get_playback_bookmarks()
+        }
+    }
+
+    /// Get the average time spent per frame in each part of the core thread.
+    ///
+    /// See [`ThreadedSuperShuckieCore::get_frame_timings`].
+    #[inline]
+    pub fn get_frame_timings(&self) -> FrameTimings {
+        self.core.get_frame_timings()
+    }
+
+    /// Push the state from before loading a save state so it can be undone.
+    fn push_save_state_history(&mut self, state: Vec<u8>) {
+        self.current_save_state_history.truncate(self.current_save_state_history_position);
+        self.current_save_state_history.push(state);
+
+        while self.current_save_state_history.len() > self.settings.emulation.max_save_state_history.get() {
+            self.current_save_state_history.remove(0);
+        }
+
+        self.current_save_state_history_position = self.current_save_state_history.len();
+
+    }
+
+    fn create_save_state_now(&mut self) -> Vec<u8> {
+        let callbacks = &mut self.callbacks;
+        self.core.create_save_state_with_progress(None, |e| callbacks.report_progress(e)).expect("Failed to create a save state for an unknown reason (this is a bug!).") // TODO: handle this failing?
+    }
+
+    /// Undo loading a save state, loading the state before loading the save state.
+    pub fn undo_load_save_state(&mut self) -> bool {
+        if self.current_save_state_history_position == 0 {
+            return false // no more to go
+        }
+
+        let backup = self.create_save_state_now();
+        self.current_save_state_history_position -= 1;
+
+        let history = &mut self.current_save_state_history[self.current_save_state_history_position];
+        let state_to_load = std::mem::replace(history, backup);
+
+        // These were made by this core, so they always load
+        let _ = self.core.load_save_state(state_to_load);
+        self.push_event(FrontendEvent::StateLoaded { name: None });
+        true
+    }
+
+    /// Redo loading a save state, loading the save state before undoing loading the save state.
+    pub fn redo_load_save_state(&mut self) -> bool {
+        if self.current_save_state_history_position == self.current_save_state_history.len() {
+            return false // no more to go
+        }
+
+        let backup = self.create_save_state_now();
+
+        let history = &mut self.current_save_state_history[self.current_save_state_history_position];
+        self.current_save_state_history_position += 1;
+
+        let state_to_load = std::mem::replace(history, backup);
+
+        let _ = self.core.load_save_state(state_to_load);
+        self.push_event(FrontendEvent::StateLoaded { name: None });
+        true
+    }
+
+    pub fn on_user_input(&mut self, input: UserInput, value: f64) {
+        let rom_controls = self.rom_name.as_ref()
+            .and_then(|i| self.settings.rom_config.get(i.as_str()))
+            .and_then(|i| i.controls.as_ref());
+        let controls = &self.settings.controls;
+
+        let control = match input {
+            UserInput::Keyboard { keycode } => controls.resolve_keyboard_controls(rom_controls).get(&keycode).copied(),
+            UserInput::Button { button, controller } => {
+                self.connected_controllers.get(&controller)
+                    .and_then(|i| controls.resolve_controller_controls(rom_controls, i.as_str()))
+                    .and_then(|i| i.buttons.get(&button))
+                    .copied()
+            }
+            UserInput::Axis { axis, controller } => {
+                self.connected_controllers.get(&controller)
+                    .and_then(|i| controls.resolve_controller_controls(rom_controls, i.as_str()))
+                    .and_then(|i| i.axis.get(&axis))
+                    .copied()
+            }
+        };
+
+        let pressed = value > 0.5;
+
+        if let UserInput::Button { controller, button } = input && (self.ui_navigation_captured || control.is_none()) {
+            self.callbacks.ui_navigation(controller, button, pressed);
+            return
+        }
+
+        let Some(control) = control else {
+            return
+        };
+
+        if control.control.is_button() {
+            if pressed && self.settings.replay_settings.auto_stop_playback_on_input && self.get_replay_playback_stats().is_some() {
+                self.stop_replay_playback();
+            }
+
+            if pressed && self.settings.replay_settings.auto_unpause_on_input && self.is_paused() {
+                self.set_paused(false);
             }
 
             match control.modifier {
@@ -420,11 +1380,39 @@ impl SuperShuckieFrontend {
             match control.control {
                 Control::Turbo => self.apply_turbo(value),
                 Control::Reset => if pressed {
-                    self.core.hard_reset();
+                    self.hard_reset_console();
                 }
                 Control::Pause => if pressed && self.is_game_running() {
                     self.set_paused(!self.paused);
                 }
+                Control::SeekForward => self.set_seeking_forward(pressed),
+                Control::SpeedUp => if pressed {
+                    self.cycle_speed_preset(true);
+                }
+                Control::SlowDown => if pressed {
+                    self.cycle_speed_preset(false);
+                }
+                Control::SoftReset => if pressed {
+                    self.soft_reset_console();
+                }
+                // Deletes save data without asking, so it can't be bound (see Control::is_bindable)
+                Control::PowerCycleClearSRAM => {}
+                Control::TiltUp | Control::TiltDown | Control::TiltLeft | Control::TiltRight => {
+                    self.set_tilt(control.control, value);
+                }
+                Control::SeekBackward => self.set_rewinding(pressed),
+                Control::ToggleOnionSkin => if pressed {
+                    self.toggle_onion_skin();
+                }
+                Control::NextSaveProfile => if pressed {
+                    self.cycle_save_profile(true);
+                }
+                Control::PreviousSaveProfile => if pressed {
+                    self.cycle_save_profile(false);
+                }
+                Control::FrameAdvance => if pressed {
+                    self.frame_advance();
+                }
 
                 Control::A => unreachable!(),
                 Control::B => unreachable!(),
@@ -442,36 +1430,130 @@ impl SuperShuckieFrontend {
         }
     }
 
-    pub fn load_rom<P: AsRef<Path>>(&mut self, path: P) -> Result<(), UTF8CString> {
+    /// Set the tilt for one direction.
+    ///
+    /// `value` can be negative to tilt the opposite way (e.g. if an analog stick axis is bound to
+    /// just one direction).
+    fn set_tilt(&mut self, control: Control, value: f64) {
+        let Some(direction) = control.tilt_direction() else {
+            return
+        };
+        self.current_tilt[direction] = value.clamp(-1.0, 1.0);
+
+        let [up, down, left, right] = self.current_tilt;
+        let to_axis = |positive: f64, negative: f64| ((positive - negative).clamp(-1.0, 1.0) * i16::MAX as f64) as i16;
+        let (x, y) = (to_axis(right, left), to_axis(down, up));
+
+        self.current_input.tilt = if x == 0 && y == 0 { None } else { Some((x, y)) };
+        self.core.enqueue_input(self.current_input);
+    }
+
+    pub fn load_rom<P: AsRef<Path>>(&mut self, path: P) -> Result<(), LocalizedError> {
         let path = path.as_ref();
 
         let Some(filename) = path.file_name().and_then(|i| i.to_str()) else {
-            return Err(format!(
-                "{} does not appear to be a valid ROM file (missing filename)",
-                path.display()
-            ).into())
+            return Err(LocalizedError::new("rom_missing_filename").arg("path", path.display()))
         };
 
         let Some(extension) = path.extension().and_then(|i| i.to_str()) else {
-            return Err(format!("{filename} does not appear to be a valid ROM file (missing extension)").into())
+            return Err(LocalizedError::new("rom_missing_extension").arg("filename", filename))
         };
 
-        let data = std::fs::read(path).map_err(|e| {
-            format!("Failed to read ROM at {filename}: {e}")
+        let data = self.files.read(path).map_err(|e| {
+            LocalizedError::new("rom_read_failed").arg("filename", filename).arg("error", e)
         })?;
 
+        if extension.eq_ignore_ascii_case(ARCHIVE_EXTENSION) {
+            let archive = parse_rom_archive(filename, &data)?;
+            let entry = match archive_rom_entries(&archive).as_slice() {
+                [] => return Err(LocalizedError::new("archive_has_no_roms").arg("filename", filename)),
+                [entry] => *entry,
+                entries => {
+                    let names: Vec<&str> = entries.iter().map(|e| e.name.as_str()).collect();
+                    return Err(LocalizedError::new("archive_has_multiple_roms").arg("filename", filename).arg("roms", names.join(", ")))
+                }
+            };
+            return self.load_rom_from_archive_entry(filename, &archive, entry)
+        }
+
+        self.load_rom_data(filename, extension, data)
+    }
+
+    /// List the ROMs in the ZIP archive at `path` that can be loaded with
+    /// [`SuperShuckieFrontend::load_rom_from_archive`], in the order they are stored.
+    pub fn get_roms_in_archive<P: AsRef<Path>>(&self, path: P) -> Result<Vec<UTF8CString>, LocalizedError> {
+        let (filename, data) = self.read_rom_archive(path.as_ref())?;
+        let archive = parse_rom_archive(&filename, &data)?;
+        Ok(archive_rom_entries(&archive).into_iter().map(|e| UTF8CString::from_str(&e.name)).collect())
+    }
+
+    /// Load the ROM `entry` (as listed by [`SuperShuckieFrontend::get_roms_in_archive`]) from the
+    /// ZIP archive at `path`.
+    ///
+    /// The ROM is named after its own filename rather than the archive's, so its save data and
+    /// other user data are shared with the same ROM loaded outside of an archive.
+    pub fn load_rom_from_archive<P: AsRef<Path>>(&mut self, path: P, entry: &str) -> Result<(), LocalizedError> {
+        let (filename, data) = self.read_rom_archive(path.as_ref())?;
+        let archive = parse_rom_archive(&filename, &data)?;
+        let entry = archive_rom_entries(&archive)
+            .into_iter()
+            .find(|e| e.name == entry)
+            .ok_or_else(|| LocalizedError::new("archive_rom_not_found").arg("filename", &filename).arg("entry", entry))?;
+        self.load_rom_from_archive_entry(&filename, &archive, entry)
+    }
+
+    fn read_rom_archive(&self, path: &Path) -> Result<(String, Vec<u8>), LocalizedError> {
+        let filename = path.file_name().map(|f| f.to_string_lossy().into_owned()).unwrap_or_else(|| path.display().to_string());
+        let data = self.files.read(path).map_err(|e| LocalizedError::new("archive_read_failed").arg("filename", &filename).arg("error", e))?;
+        Ok((filename, data))
+    }
+
+    fn load_rom_from_archive_entry(&mut self, archive_filename: &str, archive: &ZipArchive, entry: &ZipEntry) -> Result<(), LocalizedError> {
+        let data = archive.extract(entry).map_err(|e| LocalizedError::new("archive_extract_failed").arg("filename", archive_filename).arg("error", e))?;
+        let filename = entry.filename();
+        let extension = Path::new(filename).extension().and_then(|e| e.to_str()).unwrap_or_default();
+        self.load_rom_data(filename, extension, data)
+    }
+
+    fn load_rom_data(&mut self, filename: &str, extension: &str, data: Vec<u8>) -> Result<(), LocalizedError> {
         let emulator_to_use = match extension.to_lowercase().as_str() {
             "gb" | "gbc" => self.choose_for_game_boy(data.as_slice()),
-            unknown => return Err(format!("Unknown or unsupported ROM file type .{unknown}").into())
+            unknown => return Err(LocalizedError::new("rom_type_unsupported").arg("extension", unknown))
         };
 
+        // Prepare the ROM's folders before closing the running game, so a failure leaves it running.
+        // If they can't be moved to the current naming scheme, the ROM isn't loaded at all, since it
+        // would otherwise start without the save data left under the old name.
+        let checksum: UTF8CString = blake3_hash_to_ascii(blake3_hash(&data)).into();
+        let previous_config = self.settings.rom_config.get(filename).cloned();
+        self.record_rom_checksum(filename, &checksum);
+        if let Err(e) = self.relocate_rom_dirs(filename) {
+            self.restore_rom_config(filename, previous_config);
+            return Err(LocalizedError::new("rom_dirs_not_moved").arg("error", e.format(&self.messages)))
+        }
         self.create_userdata_for_rom(filename)?;
-        self.close_rom();
+
+        // The running game's SRAM is saved into its folder, which depends on the checksum recorded
+        // for its filename. If it has the same filename, that is the one recorded before this ROM's.
+        if self.get_current_rom_name() == Some(filename) {
+            let config = self.settings.rom_config.get(filename).cloned();
+            self.restore_rom_config(filename, previous_config);
+            self.close_rom();
+            self.restore_rom_config(filename, config);
+        }
+        else {
+            self.close_rom();
+        }
+
+        self.rom_checksum = Some(checksum);
         self.loaded_rom_data = Some(data);
         self.rom_name = Some(Arc::new(UTF8CString::from_str(filename)));
         self.core_metadata.emulator_type = Some(emulator_to_use);
         self.save_file = Some(Arc::new(self.get_current_save_file_name_for_rom(filename)));
         self.reload_rom_in_place();
+        self.load_game_profile();
+        self.begin_rom_stats_session();
+        self.push_event(FrontendEvent::RomLoaded { rom_name: filename.to_owned() });
         Ok(())
     }
 
@@ -485,55 +1567,433 @@ impl SuperShuckieFrontend {
         self.settings.controls = controls
     }
 
-    /// Hard reset the console.
-    pub fn hard_reset_console(&mut self) {
-        self.core.hard_reset()
+    /// Replace the keyboard controls with the default keyboard controls.
+    pub fn reset_keyboard_controls_to_defaults(&mut self) {
+        let callbacks = &mut self.callbacks;
+        self.settings.controls.keyboard_controls = Controls::default_keyboard_controls(|key| callbacks.keycode_for_physical_key(key));
     }
 
-    fn create_userdata_for_rom(&mut self, rom: &str) -> Result<(), UTF8CString> {
-        fn create_if_not_dir(what: &Path) -> Result<(), UTF8CString> {
-            if !what.is_dir() && let Err(e) = std::fs::create_dir(what) {
-                return Err(format!("Failed to create userdata dir for {}: {e}", what.display()).into());
+    /// Replace the keycode of every keyboard control, including per-ROM ones, with `remap(keycode)`.
+    ///
+    /// This is for migrating keyboard controls when the frontend changes what its keycodes mean. If two
+    /// keycodes are remapped to the same one, the control bound to the greater keycode is kept, and the
+    /// user is told that the others were removed.
+    ///
+    /// Returns the original keycode and setting of each removed control.
+    pub fn remap_keyboard_controls<F: FnMut(i32) -> i32>(&mut self, mut remap: F) -> Vec<(i32, ControlSetting)> {
+        let mut dropped = Vec::new();
+        let global = std::iter::once(&mut self.settings.controls);
+        let rom = self.settings.rom_config.values_mut().filter_map(|c| c.controls.as_mut());
+        for controls in global.chain(rom) {
+            // Keep the original keycodes around until every control is remapped to report collisions
+            let mut remapped = BTreeMap::new();
+            for (keycode, setting) in std::mem::take(&mut controls.keyboard_controls) {
+                if let Some(previous) = remapped.insert(remap(keycode), (keycode, setting)) {
+                    dropped.push(previous);
+                }
             }
-            Ok(())
+            controls.keyboard_controls = remapped.into_iter().map(|(keycode, (_, setting))| (keycode, setting)).collect();
         }
 
-        create_if_not_dir(&self.get_userdir_for_rom(rom))?;
-        create_if_not_dir(&self.get_save_states_dir_for_rom(rom))?;
-        create_if_not_dir(&self.get_save_data_dir_for_rom(rom))?;
-        create_if_not_dir(&self.get_replays_dir_for_rom(rom))?;
-
-        Ok(())
+        if !dropped.is_empty() {
+            self.show_message("keyboard_controls_dropped", &[("count", &dropped.len().to_string())]);
+        }
+        dropped
     }
 
-    fn get_save_states_dir_for_rom(&self, rom: &str) -> PathBuf {
-        self.get_userdir_for_rom(rom).join("save states")
+    /// Get the per-ROM control settings for the given ROM, if any.
+    pub fn get_rom_control_settings(&self, rom: &str) -> Option<&Controls> {
+        self.settings.rom_config.get(rom).and_then(|i| i.controls.as_ref())
     }
 
-    fn get_save_data_dir_for_rom(&self, rom: &str) -> PathBuf {
-        self.get_userdir_for_rom(rom).join("save data")
+    /// Overwrite the per-ROM control settings for the given ROM.
+    ///
+    /// If `None`, the global control settings will be used for the ROM.
+    pub fn set_rom_control_settings(&mut self, rom: &str, controls: Option<Controls>) {
+        self.settings.get_rom_config_or_default(rom).controls = controls
     }
 
-    fn get_replays_dir_for_rom(&self, rom: &str) -> PathBuf {
-        self.get_userdir_for_rom(rom).join("replays")
+    /// Get the number of frames input is delayed by for the given ROM.
+    pub fn get_rom_input_delay(&self, rom: &str) -> u8 {
+        self.settings.rom_config.get(rom).map(|i| i.input_delay_frames).unwrap_or(0)
     }
 
-    fn get_userdir_for_rom(&self, filename: &str) -> PathBuf {
-        self.user_dir.join(format!("{filename}-data"))
+    /// Set the number of frames input is delayed by for the given ROM.
+    ///
+    /// If the ROM is currently loaded, this takes effect immediately.
+    pub fn set_rom_input_delay(&mut self, rom: &str, frames: u8) {
+        self.settings.get_rom_config_or_default(rom).input_delay_frames = frames;
+        if self.get_current_rom_name() == Some(rom) {
+            self.core.set_input_delay(frames as usize);
+        }
     }
 
-    fn reload_rom_in_place(&mut self) {
-        let emulator_type = self.core_metadata.emulator_type.expect("reload_rom_in_place with no emulator type");
-        self.instantiate_and_load_core(emulator_type);
+    /// Get the cheats saved for the given ROM.
+    pub fn get_rom_cheats(&self, rom: &str) -> &[CheatSetting] {
+        self.settings.rom_config.get(rom).map(|i| i.cheats.as_slice()).unwrap_or_default()
     }
 
-    fn instantiate_and_load_core(&mut self, emulator_type: SuperShuckieEmulatorType) {
-        let rom_name = self.get_current_rom_name().expect("reload_rom_in_place with no loaded ROM");
-        let save_file = self.get_current_save_name().expect("reload_rom_in_place with no save file");
-        let save_file_data = self.get_save_file_data(rom_name, save_file);
+    /// Save a GameShark or Game Genie code for the given ROM, enabled.
+    ///
+    /// If the ROM is currently loaded, this takes effect immediately, and the code is only saved if
+    /// it applies to the ROM.
+    pub fn add_rom_cheat(&mut self, rom: &str, code: &str, description: &str) -> Result<(), LocalizedError> {
+        let code = code.trim().to_uppercase();
+        CheatCode::parse(&code).map_err(|e| LocalizedError::new("cheat_invalid").arg("code", &code).arg("error", e))?;
+        if self.get_rom_cheats(rom).iter().any(|c| c.code.as_str() == code) {
+            return Err(LocalizedError::new("cheat_already_added").arg("code", code))
+        }
+
+        self.settings.get_rom_config_or_default(rom).cheats.push(CheatSetting {
+            code: code.into(),
+            description: description.into(),
+            enabled: true
+        });
+
+        if let Err(e) = self.apply_rom_cheats(rom) {
+            self.settings.get_rom_config_or_default(rom).cheats.pop();
+            return Err(e)
+        }
+        Ok(())
+    }
+
+    /// Remove the cheat at `index` in [`SuperShuckieFrontend::get_rom_cheats`] from the given ROM.
+    ///
+    /// Returns `false` if there is no cheat at `index`.
+    pub fn remove_rom_cheat(&mut self, rom: &str, index: usize) -> bool {
+        let Some(cheats) = self.settings.rom_config.get_mut(rom).map(|c| &mut c.cheats).filter(|c| index < c.len()) else {
+            return false
+        };
+        cheats.remove(index);
+
+        // Removing a cheat can't make the others fail to apply
+        let _ = self.apply_rom_cheats(rom);
+        true
+    }
+
+    /// Enable or disable the cheat at `index` in [`SuperShuckieFrontend::get_rom_cheats`] for the
+    /// given ROM.
+    ///
+    /// If the ROM is currently loaded, this takes effect immediately.
+    pub fn set_rom_cheat_enabled(&mut self, rom: &str, index: usize, enabled: bool) -> Result<(), LocalizedError> {
+        let Some(cheat) = self.settings.rom_config.get_mut(rom).and_then(|c| c.cheats.get_mut(index)) else {
+            return Err(LocalizedError::new("cheat_not_found").arg("rom", rom).arg("index", index))
+        };
+        let previous = std::mem::replace(&mut cheat.enabled, enabled);
+
+        if let Err(e) = self.apply_rom_cheats(rom) {
+            self.settings.get_rom_config_or_default(rom).cheats[index].enabled = previous;
+            return Err(e)
+        }
+        Ok(())
+    }
+
+    /// Apply the given ROM's enabled cheats if it is currently loaded.
+    fn apply_rom_cheats(&self, rom: &str) -> Result<(), LocalizedError> {
+        if self.get_current_rom_name() != Some(rom) {
+            return Ok(())
+        }
+
+        let codes = self.get_rom_cheats(rom)
+            .iter()
+            .filter(|c| c.enabled)
+            .map(|c| c.code.as_str().to_owned())
+            .collect();
+        self.core.set_cheats(codes).map_err(|e| LocalizedError::new("cheats_rejected").arg("error", e))
+    }
+
+    /// Copy the global control settings into the per-ROM control settings for the given ROM.
+    pub fn copy_control_settings_to_rom(&mut self, rom: &str) {
+        let controls = self.settings.controls.clone();
+        self.set_rom_control_settings(rom, Some(controls));
+    }
+
+    /// Hard reset the console.
+    pub fn hard_reset_console(&mut self) {
+        if !self.core.is_playing_back() {
+            self.count_hard_reset();
+        }
+        self.core.hard_reset();
+        self.push_event(FrontendEvent::Reset { hard: true });
+    }
+
+    /// Soft reset the console.
+    ///
+    /// Returns `false` if the console does not support soft resetting.
+    pub fn soft_reset_console(&mut self) -> bool {
+        if !self.is_game_running() {
+            return false
+        }
+
+        let supported = self.core.soft_reset();
+        if supported {
+            self.push_event(FrontendEvent::Reset { hard: false });
+        }
+        else {
+            self.show_message("soft_reset_unsupported", &[]);
+        }
+        supported
+    }
+
+    /// Delete the current save file's data and reload the game, simulating power cycling with a
+    /// fresh cartridge.
+    ///
+    /// The current replay (if any) will be stopped. If the save data can't be backed up or deleted,
+    /// the game keeps running as-is.
+    pub fn power_cycle_with_fresh_sram(&mut self) -> Result<(), LocalizedError> {
+        if !self.is_game_running() {
+            return Err(LocalizedError::new("game_not_running"))
+        }
+
+        let rom_name = self.get_current_rom_name_arc().expect("power cycle when not running");
+        let save_file = self.get_current_save_name().expect("power cycle with no save file").to_owned();
+        self.delete_save_file_data(rom_name.as_str(), save_file.as_str())?;
+        self.reload_rom_in_place();
+        self.count_hard_reset();
+        self.push_event(FrontendEvent::Reset { hard: true });
+        Ok(())
+    }
+
+    /// Get the play stats for the given ROM.
+    ///
+    /// If the ROM is currently running, this includes the current session.
+    pub fn get_rom_stats(&self, rom: &str) -> ROMStats {
+        if let Some(stats) = self.rom_stats && self.get_current_rom_name() == Some(rom) {
+            return stats
+        }
+        ROMStats::read_or_default(self.files.as_ref(), &self.get_stats_path_for_rom(rom))
+    }
+
+    fn begin_rom_stats_session(&mut self) {
+        let Some(rom_name) = self.get_current_rom_name_arc() else {
+            return
+        };
+
+        let path = self.get_stats_path_for_rom(rom_name.as_str());
+        let mut stats = ROMStats::read_or_default(self.files.as_ref(), &path);
+        stats.begin_session();
+        stats.write(self.files.as_ref(), &path);
+
+        let mut save_profiles = SaveProfiles::read_or_default(self.files.as_ref(), &self.get_save_profiles_path_for_rom(rom_name.as_str()));
+        if let Some(save_file) = self.get_current_save_name() {
+            save_profiles.touch(save_file);
+        }
+
+        self.rom_stats = Some(stats);
+        self.save_profiles = Some(save_profiles);
+        self.last_playtime_update = Instant::now();
+        self.last_stats_write = self.last_playtime_update;
+        self.autosnap_playtime = Duration::ZERO;
+    }
+
+    fn end_rom_stats_session(&mut self) {
+        self.update_playtime();
+        self.write_rom_stats();
+        self.rom_stats = None;
+        self.save_profiles = None;
+    }
+
+    fn update_playtime(&mut self) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_playtime_update);
+        self.last_playtime_update = now;
+
+        if self.paused {
+            return
+        }
+
+        let Some(stats) = self.rom_stats.as_mut() else {
+            return
+        };
+        self.autosnap_playtime = self.autosnap_playtime.saturating_add(elapsed);
+        stats.playtime_milliseconds = stats.playtime_milliseconds.saturating_add(elapsed.as_millis() as u64);
+
+        // Replays don't count towards whatever save file is loaded
+        if !self.sram_sandboxed && let (Some(profiles), Some(save_file)) = (self.save_profiles.as_mut(), self.save_file.as_ref()) {
+            let profile = profiles.get_or_default(save_file.as_str());
+            profile.playtime_milliseconds = profile.playtime_milliseconds.saturating_add(elapsed.as_millis() as u64);
+        }
+    }
+
+    fn write_rom_stats(&mut self) {
+        self.last_stats_write = Instant::now();
+        let (Some(stats), Some(rom_name)) = (self.rom_stats, self.get_current_rom_name()) else {
+            return
+        };
+        stats.write(self.files.as_ref(), &self.get_stats_path_for_rom(rom_name));
+        if let Some(save_profiles) = self.save_profiles.as_ref() {
+            save_profiles.write(self.files.as_ref(), &self.get_save_profiles_path_for_rom(rom_name));
+        }
+    }
+
+    fn count_hard_reset(&mut self) {
+        if let Some(stats) = self.rom_stats.as_mut() {
+            stats.hard_resets = stats.hard_resets.saturating_add(1);
+        }
+    }
+
+    fn create_userdata_for_rom(&mut self, rom: &str) -> Result<(), LocalizedError> {
+        let create_if_not_dir = |what: &Path| -> Result<(), LocalizedError> {
+            if !self.files.is_dir(what) && let Err(e) = self.files.create_dir_all(what) {
+                return Err(LocalizedError::new("user_dir_create_failed").arg("path", what.display()).arg("error", e));
+            }
+            Ok(())
+        };
+
+        create_if_not_dir(&self.get_userdir_for_rom(rom))?;
+        create_if_not_dir(&self.get_save_states_dir_for_rom(rom))?;
+        create_if_not_dir(&self.get_save_data_dir_for_rom(rom))?;
+        create_if_not_dir(&self.get_replays_dir_for_rom(rom))?;
+        create_if_not_dir(&self.get_printer_dir_for_rom(rom))?;
+        create_if_not_dir(&self.get_screenshots_dir_for_rom(rom))?;
+
+        Ok(())
+    }
+
+    fn get_save_states_dir_for_rom(&self, rom: &str) -> PathBuf {
+        self.get_storage_dir_for_rom(rom, StorageCategory::SaveStates)
+    }
+
+    fn get_quarantine_dir_for_rom(&self, rom: &str) -> PathBuf {
+        self.get_save_states_dir_for_rom(rom).with_file_name(QUARANTINE_FOLDER)
+    }
+
+    fn get_save_data_dir_for_rom(&self, rom: &str) -> PathBuf {
+        self.get_storage_dir_for_rom(rom, StorageCategory::SaveData)
+    }
+
+    fn get_replays_dir_for_rom(&self, rom: &str) -> PathBuf {
+        self.get_storage_dir_for_rom(rom, StorageCategory::Replays)
+    }
+
+    fn get_printer_dir_for_rom(&self, rom: &str) -> PathBuf {
+        self.get_storage_dir_for_rom(rom, StorageCategory::Printer)
+    }
+
+    fn get_screenshots_dir_for_rom(&self, rom: &str) -> PathBuf {
+        self.get_storage_dir_for_rom(rom, StorageCategory::Screenshots)
+    }
+
+    /// Resolve the directory for the category, using the base directory from the storage settings
+    /// if one is set (see [`StorageSettings`]).
+    fn get_storage_dir_for_rom(&self, rom: &str, category: StorageCategory) -> PathBuf {
+        let data_dir = match self.settings.storage.get_base_dir(category) {
+            Some(base) => Path::new(base.as_str()).join(format!("{}-data", self.rom_dir_name(rom))),
+            None => self.get_userdir_for_rom(rom)
+        };
+        data_dir.join(category.folder_name())
+    }
+
+    fn get_stats_path_for_rom(&self, rom: &str) -> PathBuf {
+        self.get_userdir_for_rom(rom).join(STATS_FILE)
+    }
+
+    fn get_save_profiles_path_for_rom(&self, rom: &str) -> PathBuf {
+        self.get_save_data_dir_for_rom(rom).join(SAVE_PROFILES_FILE)
+    }
+
+    fn get_userdir_for_rom(&self, filename: &str) -> PathBuf {
+        self.user_dir.join(format!("{}-data", self.rom_dir_name(filename)))
+    }
+
+    /// Get what the ROM's folders are named after, which is its checksum if keying by checksum and
+    /// the checksum is known, or its filename otherwise.
+    fn rom_dir_name<'a>(&'a self, rom: &'a str) -> &'a str {
+        if self.settings.storage.key_by_checksum && let Some(checksum) = self.get_recorded_rom_checksum(rom) {
+            checksum.as_str()
+        }
+        else {
+            rom
+        }
+    }
+
+    fn get_recorded_rom_checksum(&self, rom: &str) -> Option<&UTF8CString> {
+        self.settings.rom_config.get(rom).and_then(|c| c.checksum.as_ref())
+    }
+
+    /// Remember the checksum of the ROM with the given filename.
+    ///
+    /// If the ROM has no config yet but another ROM with the same checksum does, it is copied over,
+    /// since the ROM was most likely renamed.
+    fn record_rom_checksum(&mut self, rom: &str, checksum: &UTF8CString) {
+        if !self.settings.rom_config.contains_key(rom)
+            && let Some(config) = self.settings.rom_config.values().find(|c| c.checksum.as_ref() == Some(checksum)) {
+            self.settings.rom_config.insert(rom.to_owned(), config.clone());
+        }
+        self.settings.get_rom_config_or_default(rom).checksum = Some(checksum.clone());
+    }
+
+    /// Put back a ROM's config as it was before, removing it if it had none.
+    fn restore_rom_config(&mut self, rom: &str, config: Option<ROMConfig>) {
+        match config {
+            Some(config) => self.settings.rom_config.insert(rom.to_owned(), config),
+            None => self.settings.rom_config.remove(rom)
+        };
+    }
+
+    /// Get every directory that can hold ROM folders: the user directory and each base directory
+    /// from the storage settings.
+    fn get_rom_data_base_dirs(&self) -> Vec<PathBuf> {
+        let mut dirs = vec![self.user_dir.clone()];
+        for category in [StorageCategory::SaveStates, StorageCategory::SaveData, StorageCategory::Replays, StorageCategory::Printer, StorageCategory::Screenshots] {
+            if let Some(base) = self.settings.storage.get_base_dir(category) && !dirs.iter().any(|d| d == Path::new(base.as_str())) {
+                dirs.push(PathBuf::from(base.as_str()));
+            }
+        }
+        dirs
+    }
+
+    /// Move the ROM's folders from the naming scheme not in use to the one that is, returning
+    /// `true` if anything was moved.
+    ///
+    /// Nothing is moved if the ROM's checksum is not known. Folders are never merged, so if any
+    /// exist under both names, nothing is moved and an error is returned.
+    fn relocate_rom_dirs(&self, rom: &str) -> Result<bool, LocalizedError> {
+        let Some(checksum) = self.get_recorded_rom_checksum(rom) else {
+            return Ok(false)
+        };
+
+        let (from, to) = if self.settings.storage.key_by_checksum {
+            (rom, checksum.as_str())
+        }
+        else {
+            (checksum.as_str(), rom)
+        };
+
+        let mut moves = Vec::new();
+        for base in self.get_rom_data_base_dirs() {
+            let from_dir = base.join(format!("{from}-data"));
+            if !self.files.is_dir(&from_dir) {
+                continue
+            }
+
+            let to_dir = base.join(format!("{to}-data"));
+            if self.files.exists(&to_dir) {
+                return Err(LocalizedError::new("user_dir_exists").arg("from", from_dir.display()).arg("to", to_dir.display()))
+            }
+            moves.push((from_dir, to_dir));
+        }
+
+        for (from_dir, to_dir) in &moves {
+            self.files.rename(from_dir, to_dir).map_err(|e| LocalizedError::new("user_dir_move_failed").arg("from", from_dir.display()).arg("to", to_dir.display()).arg("error", e))?;
+        }
+
+        Ok(!moves.is_empty())
+    }
+
+    fn reload_rom_in_place(&mut self) {
+        let emulator_type = self.core_metadata.emulator_type.expect("reload_rom_in_place with no emulator type");
+        self.instantiate_and_load_core(emulator_type);
+    }
+
+    fn instantiate_and_load_core(&mut self, emulator_type: SuperShuckieEmulatorType) {
+        let rom_name = self.get_current_rom_name().expect("reload_rom_in_place with no loaded ROM");
+        let save_file = self.get_current_save_name().expect("reload_rom_in_place with no save file");
+        let save_file_data = self.get_save_file_data(rom_name, save_file);
         let rom_data = self.loaded_rom_data.as_ref().expect("reload_rom_in_place with no loaded rom");
         let core = self.make_new_core(rom_data, save_file_data, emulator_type);
         self.switch_core(ThreadedSuperShuckieCore::new(core));
+        self.sram_sandboxed = false;
     }
 
     fn switch_core(&mut self, core: ThreadedSuperShuckieCore) {
@@ -552,11 +2012,16 @@ impl SuperShuckieFrontend {
         let bios = self.get_bios_for_core(emulator_type);
 
         let mut core: Box<dyn EmulatorCore> = match emulator_type {
-            SuperShuckieEmulatorType::GameBoy => Box::new(GameBoyColor::new_from_rom(rom_data, bios.as_slice(), Model::DmgB)),
-            SuperShuckieEmulatorType::GameBoySGB2 => Box::new(GameBoyColor::new_from_rom(rom_data, bios.as_slice(), Model::Sgb2)),
-            SuperShuckieEmulatorType::GameBoyColor => Box::new(GameBoyColor::new_from_rom(rom_data, bios.as_slice(), Model::Cgb0))
+            SuperShuckieEmulatorType::GameBoy => Box::new(GameBoyColor::new_from_rom(rom_data, bios.as_slice(), Model::DmgB, self.core_color_correction())),
+            SuperShuckieEmulatorType::GameBoySGB2 => Box::new(GameBoyColor::new_from_rom(rom_data, bios.as_slice(), Model::Sgb2, self.core_color_correction())),
+            SuperShuckieEmulatorType::GameBoyColor => Box::new(GameBoyColor::new_from_rom(rom_data, bios.as_slice(), Model::Cgb0, self.core_color_correction()))
         };
 
+        for (key, value) in &self.settings.emulation.core_options {
+            // Options are shared between cores, so not every core will know every option.
+            let _ = core.set_option(key, value);
+        }
+
         if let Some(sram) = save_file {
             let _ = core.load_sram(sram.as_slice()); // TODO: handle this?
         }
@@ -569,11 +2034,97 @@ impl SuperShuckieFrontend {
     }
 
     fn get_save_file_data(&self, rom: &str, save_file: &str) -> Option<Vec<u8>> {
-        std::fs::read(self.get_save_path(rom, save_file)).ok()
+        self.files.read(&self.get_save_path(rom, save_file)).ok()
+    }
+
+    /// Back up and delete the save data on disk, if any.
+    fn delete_save_file_data(&self, rom: &str, save_file: &str) -> Result<(), LocalizedError> {
+        self.back_up_save_file(rom, save_file, None)
+            .map_err(|e| LocalizedError::new("save_backup_failed").arg("error", e.format(&self.messages)))?;
+        match self.files.remove_file(&self.get_save_path(rom, save_file)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(LocalizedError::new("save_delete_failed").arg("error", e)),
+            _ => Ok(())
+        }
+    }
+
+    fn get_save_backup_dir(&self, rom: &str, save_file: &str) -> Result<PathBuf, LocalizedError> {
+        let save_file = sanitize_user_name(save_file)?;
+        let backups_dir = self.get_save_data_dir_for_rom(rom).join(SAVE_BACKUP_FOLDER);
+        Ok(save_file.split('/').fold(backups_dir, |path, component| path.join(component)))
+    }
+
+    /// Copy the save data on disk to the save's backups before it is replaced with `new_data` (or
+    /// deleted if `None`), deleting the oldest backups if there are too many.
+    ///
+    /// Nothing is copied if backups are disabled, there is no save data yet, or it wouldn't change.
+    fn back_up_save_file(&self, rom: &str, save_file: &str, new_data: Option<&[u8]>) -> Result<(), LocalizedError> {
+        if !self.settings.save_backups.enabled {
+            return Ok(())
+        }
+
+        let Some(old_data) = self.get_save_file_data(rom, save_file) else {
+            return Ok(())
+        };
+
+        if old_data.is_empty() || new_data == Some(old_data.as_slice()) {
+            return Ok(())
+        }
+
+        let backup_dir = self.get_save_backup_dir(rom, save_file)?;
+        self.files.create_dir_all(&backup_dir).map_err(|e| LocalizedError::new("folder_create_failed").arg("path", backup_dir.display()).arg("error", e))?;
+
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map(|i| i.as_secs()).unwrap_or_default();
+        let (_, path) = get_unused_named_file_path(self.files.as_ref(), &backup_dir, &format_filename_timestamp(timestamp), SAVE_DATA_EXTENSION)?;
+        self.files.write(&path, &old_data).map_err(|e| LocalizedError::new("save_backup_write_failed").arg("error", e))?;
+
+        let mut backups = list_files_in_dir_with_extension(self.files.as_ref(), &backup_dir, SAVE_DATA_EXTENSION);
+        backups.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+
+        let excess = backups.len().saturating_sub(self.settings.save_backups.max_backups.get());
+        for old in &backups[..excess] {
+            self.files.remove_file(&backup_dir.join(format!("{old}.{SAVE_DATA_EXTENSION}")))
+                .map_err(|e| LocalizedError::new("save_backup_delete_failed").arg("backup", old).arg("error", e))?;
+        }
+
+        Ok(())
+    }
+
+    /// Get the backups of a ROM's save data, newest first (see [`SaveBackupSettings`]).
+    pub fn get_save_backups_for_rom(&self, rom: &str, save_file: &str) -> Vec<UTF8CString> {
+        let Ok(backup_dir) = self.get_save_backup_dir(rom, save_file) else {
+            return Vec::new()
+        };
+
+        let mut backups = list_files_in_dir_with_extension(self.files.as_ref(), &backup_dir, SAVE_DATA_EXTENSION);
+        backups.sort_by(|a, b| b.as_str().cmp(a.as_str()));
+        backups
     }
 
-    fn delete_save_file_data(&mut self, rom: &str, save_file: &str) {
-        let _ = std::fs::remove_file(self.get_save_path(rom, save_file)).ok();
+    /// Replace the current save data with one of its backups and reload the game.
+    ///
+    /// The current save data is backed up first, so this can be undone.
+    pub fn restore_save_backup(&mut self, backup: &str) -> Result<(), LocalizedError> {
+        if !self.is_game_running() {
+            return Err(LocalizedError::new("game_not_running"))
+        }
+
+        let rom_name = self.get_current_rom_name_arc().expect("restore_save_backup when not running");
+        let save_file = self.get_current_save_name().expect("restore_save_backup with no save file").to_owned();
+
+        let backup_dir = self.get_save_backup_dir(rom_name.as_str(), &save_file)?;
+        let (_, backup_path) = get_named_file_path(&backup_dir, backup, SAVE_DATA_EXTENSION)?;
+        let data = self.files.read(&backup_path).map_err(|e| LocalizedError::new("save_backup_read_failed").arg("backup", backup).arg("error", e))?;
+
+        if !self.sram_sandboxed {
+            self.save_sram()?;
+        }
+        self.back_up_save_file(rom_name.as_str(), &save_file, Some(&data))?;
+        self.files.write(&self.get_save_path(rom_name.as_str(), &save_file), &data)
+            .map_err(|e| LocalizedError::new("sram_write_failed").arg("error", e))?;
+
+        self.reload_rom_in_place();
+        self.push_event(FrontendEvent::Reset { hard: true });
+        Ok(())
     }
 
     fn get_save_path(&self, rom: &str, save_file: &str) -> PathBuf {
@@ -597,17 +2148,25 @@ impl SuperShuckieFrontend {
 
     /// Unload the ROM without saving.
     pub fn unload_rom(&mut self) {
+        self.end_rom_stats_session();
         self.before_unload_or_reload_rom();
         self.core = ThreadedSuperShuckieCore::new(Box::new(NullEmulatorCore));
         self.save_file = None;
+        self.sram_sandboxed = false;
         self.rom_name = None;
+        self.rom_checksum = None;
+        self.game_profile = None;
         self.core_metadata.emulator_type = None;
         self.current_input = Input::default();
+        self.current_tilt = [0.0; 4];
         self.after_switch_core();
     }
 
     /// Set whether or not the game is paused.
     pub fn set_paused(&mut self, paused: bool) {
+        self.update_playtime();
+        self.paused_by_focus_loss = false;
+
         // we still want to do this for config reasons
         self.paused = paused;
 
@@ -621,6 +2180,43 @@ impl SuperShuckieFrontend {
         }
     }
 
+    /// Tell the frontend whether or not the window is focused.
+    ///
+    /// If pausing on focus loss is enabled, the game is paused when focus is lost (unless recording
+    /// a replay or in a netplay session), then unpaused when focus is regained if it wasn't paused
+    /// or unpaused some other way in the meantime.
+    pub fn set_focused(&mut self, focused: bool) {
+        if focused {
+            if self.paused_by_focus_loss {
+                self.set_paused(false);
+            }
+            return
+        }
+
+        if !self.settings.emulation.pause_on_focus_loss
+            || !self.is_game_running()
+            || self.paused
+            || self.recording_replay_file.is_some()
+            || self.core.get_netplay_status() != NetplayStatus::Inactive {
+            return
+        }
+
+        self.set_paused(true);
+        self.paused_by_focus_loss = true;
+    }
+
+    /// Tell the frontend whether or not the window is minimized.
+    ///
+    /// If throttling when minimized is enabled, screens aren't drawn until the window is restored.
+    pub fn set_minimized(&mut self, minimized: bool) {
+        self.minimized = minimized;
+        self.core.set_background(self.is_throttled());
+    }
+
+    fn is_throttled(&self) -> bool {
+        self.minimized && self.settings.emulation.throttle_when_minimized
+    }
+
     /// Set whether or not the game is paused temporarily.
     pub fn set_playback_frozen(&mut self, paused: bool) {
         self.core.set_playback_frozen(paused);
@@ -632,9 +2228,13 @@ impl SuperShuckieFrontend {
     }
 
     /// Save the SRAM.
-    pub fn save_sram(&mut self) -> Result<(), UTF8CString> {
+    pub fn save_sram(&mut self) -> Result<(), LocalizedError> {
         if !self.is_game_running() {
-            return Err("Game not running".into())
+            return Err(LocalizedError::new("game_not_running"))
+        }
+
+        if self.sram_sandboxed {
+            return Err(LocalizedError::new("sram_sandboxed"))
         }
 
         let current_rom = self.get_current_rom_name().expect("save_sram with no current ROM");
@@ -643,7 +2243,13 @@ impl SuperShuckieFrontend {
         let sram = self.core.get_sram().expect("save_sram failed to get sram (BUG!)");
         let save_file = self.get_save_path(current_rom, current_save);
 
-        std::fs::write(&save_file, sram).map_err(|e| format!("Failed to write SRAM to disk: {e}").into())
+        if let Err(e) = self.back_up_save_file(current_rom, current_save, Some(&sram)) {
+            self.show_error("save_backup_failed", &[("error", &e.format(&self.messages))]);
+        }
+
+        self.files.write(&save_file, &sram).map_err(|e| LocalizedError::new("sram_write_failed").arg("error", e))?;
+        self.push_event(FrontendEvent::SramSaved);
+        Ok(())
     }
 
     fn save_sram_unchecked(&mut self) {
@@ -656,6 +2262,29 @@ impl SuperShuckieFrontend {
         self.core_metadata.emulator_type.is_some()
     }
 
+    /// Choose the encoding of the screens passed to the `refresh_screens` and `change_video_mode`
+    /// callbacks, returning the first supported encoding in `preferred` (or
+    /// [`ScreenDataEncoding::A8R8G8B8`] if there are none).
+    ///
+    /// Screens are emulated and post-processed as [`ScreenDataEncoding::A8R8G8B8`], so any other
+    /// encoding is converted once per displayed frame before being passed to the callbacks. Screens
+    /// read in any other way (e.g. [`SuperShuckieFrontend::read_screens`]) are not converted.
+    pub fn set_preferred_screen_encodings(&mut self, preferred: &[ScreenDataEncoding]) -> ScreenDataEncoding {
+        let encoding = preferred.iter().copied().find(|e| ScreenDataEncoding::ALL.contains(e)).unwrap_or_default();
+        if encoding != self.screen_encoding {
+            self.screen_encoding = encoding;
+            self.encoded_screens.clear();
+            self.update_video_mode();
+        }
+        encoding
+    }
+
+    /// Get the encoding of the screens passed to the callbacks.
+    #[inline]
+    pub fn get_screen_encoding(&self) -> ScreenDataEncoding {
+        self.screen_encoding
+    }
+
     /// Calls the `refresh_screens` callback regardless of if there's a new frame.
     #[inline]
     pub fn force_refresh_screens(&mut self) {
@@ -680,14 +2309,47 @@ impl SuperShuckieFrontend {
     }
 
     /// Set the game speed.
-    pub fn set_speed_settings(&mut self, mut base: f64, mut turbo: f64) {
-        base = Speed::from_multiplier_float(base).into_multiplier_float();
-        turbo = Speed::from_multiplier_float(turbo).into_multiplier_float();
+    ///
+    /// Both multipliers are rounded to speeds that can actually be used (see [`Speed::quantize`]),
+    /// clamping them if out of range. NaN is rejected.
+    pub fn set_speed_settings(&mut self, base: f64, turbo: f64) -> Result<(), LocalizedError> {
+        let base = Speed::from_multiplier_checked(base, SpeedBounds::Clamp).map_err(|_| LocalizedError::new("speed_not_a_number"))?;
+        let turbo = Speed::from_multiplier_checked(turbo, SpeedBounds::Clamp).map_err(|_| LocalizedError::new("speed_not_a_number"))?;
 
-        self.settings.emulation.base_speed_multiplier = base;
-        self.settings.emulation.turbo_speed_multiplier = turbo;
+        self.settings.emulation.base_speed_multiplier = base.into_multiplier_float();
+        self.settings.emulation.turbo_speed_multiplier = turbo.into_multiplier_float();
 
         self.reset_speed();
+        Ok(())
+    }
+
+    /// Get the speed the game is actually running at, including turbo, seeking, and power saving.
+    #[inline]
+    pub fn get_effective_speed(&self) -> f64 {
+        self.effective_speed.into_multiplier_float()
+    }
+
+    /// Switch to the next faster (or slower) speed preset, if any.
+    pub fn cycle_speed_preset(&mut self, faster: bool) {
+        let current = self.settings.emulation.base_speed_multiplier;
+        let presets = self.settings.emulation.speed_presets.iter().copied();
+
+        // Speeds are fixed point, so compare with some tolerance
+        let next = if faster {
+            presets.filter(|i| *i > current + 0.001).min_by(f64::total_cmp)
+        }
+        else {
+            presets.filter(|i| *i < current - 0.001).max_by(f64::total_cmp)
+        };
+
+        let Some(next) = next else {
+            return
+        };
+
+        let turbo = self.settings.emulation.turbo_speed_multiplier;
+        if self.set_speed_settings(next, turbo).is_ok() {
+            self.show_message("speed_changed", &[("speed", &self.settings.emulation.base_speed_multiplier.to_string())]);
+        }
     }
 
     /// Set a custom setting.
@@ -703,21 +2365,194 @@ impl SuperShuckieFrontend {
         self.settings.custom.get(setting)
     }
 
-    /// Set the current save file, optionally initializing (clearing) the old one.
+    /// Get the save files of a ROM with their metadata, sorted by name.
     ///
-    /// The game will be reloaded.
-    pub fn load_or_create_save_file(&mut self, save_file: &str, initialize: bool) {
-        if !self.is_game_running() {
-            return;
-        }
+    /// This includes save files created with [`SuperShuckieFrontend::create_save_profile`] that
+    /// have not been written to yet.
+    pub fn get_save_profiles_for_rom(&self, rom: &str) -> Vec<SaveProfile> {
+        let read_profiles;
+        let profiles = match self.save_profiles.as_ref() {
+            Some(profiles) if self.get_current_rom_name() == Some(rom) => profiles,
+            _ => {
+                read_profiles = SaveProfiles::read_or_default(self.files.as_ref(), &self.get_save_profiles_path_for_rom(rom));
+                &read_profiles
+            }
+        };
 
-        self.set_current_save_file(save_file);
+        let mut names: BTreeSet<String> = self.get_all_saves_for_rom(rom).iter().map(|i| i.as_str().to_owned()).collect();
+        names.extend(profiles.names().map(|i| i.to_owned()));
 
-        if initialize {
+        names.into_iter().map(|name| SaveProfile {
+            metadata: profiles.get(&name).copied().unwrap_or_default(),
+            name: name.into()
+        }).collect()
+    }
+
+    /// Create an empty save file for the current ROM without switching to it.
+    pub fn create_save_profile(&mut self, name: &str) -> Result<(), LocalizedError> {
+        let rom_name = self.get_current_rom_name_arc().ok_or_else(|| LocalizedError::new("game_not_running"))?;
+        let name = sanitize_save_profile_name(name)?;
+        if self.save_profile_exists(rom_name.as_str(), &name) {
+            return Err(LocalizedError::new("save_profile_exists").arg("name", name))
+        }
+
+        self.update_save_profiles(|profiles| profiles.insert(&name, SaveProfileMetadata { created: save_profile::now(), ..Default::default() }));
+        Ok(())
+    }
+
+    /// Copy a save file of the current ROM (and its metadata) to a new save file.
+    ///
+    /// If it is the current save file, the SRAM is saved first so the copy is up to date.
+    pub fn duplicate_save_profile(&mut self, name: &str, new_name: &str) -> Result<(), LocalizedError> {
+        let rom_name = self.get_current_rom_name_arc().ok_or_else(|| LocalizedError::new("game_not_running"))?;
+        let new_name = sanitize_save_profile_name(new_name)?;
+        if !self.save_profile_exists(rom_name.as_str(), name) {
+            return Err(LocalizedError::new("save_profile_not_found").arg("name", name))
+        }
+        if self.save_profile_exists(rom_name.as_str(), &new_name) {
+            return Err(LocalizedError::new("save_profile_exists").arg("name", new_name))
+        }
+
+        if self.get_current_save_name() == Some(name) {
+            self.save_sram_unchecked();
+        }
+
+        let path = self.get_save_path(rom_name.as_str(), name);
+        if self.files.is_file(&path) {
+            self.files.copy(&path, &self.get_save_path(rom_name.as_str(), &new_name))
+                .map_err(|e| LocalizedError::new("save_profile_copy_failed").arg("name", name).arg("error", e))?;
+        }
+
+        self.update_save_profiles(|profiles| {
+            let metadata = profiles.get(name).copied().unwrap_or_default();
+            profiles.insert(&new_name, SaveProfileMetadata { created: save_profile::now(), last_used: None, ..metadata });
+        });
+        Ok(())
+    }
+
+    /// Rename a save file of the current ROM, along with its metadata and backups.
+    pub fn rename_save_profile(&mut self, name: &str, new_name: &str) -> Result<(), LocalizedError> {
+        let rom_name = self.get_current_rom_name_arc().ok_or_else(|| LocalizedError::new("game_not_running"))?;
+        let new_name = sanitize_save_profile_name(new_name)?;
+        if !self.save_profile_exists(rom_name.as_str(), name) {
+            return Err(LocalizedError::new("save_profile_not_found").arg("name", name))
+        }
+        if self.save_profile_exists(rom_name.as_str(), &new_name) {
+            return Err(LocalizedError::new("save_profile_exists").arg("name", new_name))
+        }
+
+        let is_current = self.get_current_save_name() == Some(name);
+        if is_current {
+            self.save_sram_unchecked();
+            self.update_playtime();
+        }
+
+        let path = self.get_save_path(rom_name.as_str(), name);
+        if self.files.is_file(&path) {
+            self.files.rename(&path, &self.get_save_path(rom_name.as_str(), &new_name))
+                .map_err(|e| LocalizedError::new("save_profile_rename_failed").arg("name", name).arg("error", e))?;
+        }
+
+        if let (Ok(backups), Ok(new_backups)) = (self.get_save_backup_dir(rom_name.as_str(), name), self.get_save_backup_dir(rom_name.as_str(), &new_name)) && self.files.is_dir(&backups) {
+            let _ = self.files.rename(&backups, &new_backups);
+        }
+
+        self.update_save_profiles(|profiles| {
+            let metadata = profiles.remove(name).unwrap_or_default();
+            profiles.insert(&new_name, metadata);
+        });
+
+        if is_current {
+            self.settings.get_rom_config_or_default(rom_name.as_str()).save_name = new_name.as_str().into();
+            self.save_file = Some(Arc::new(new_name.as_str().into()));
+        }
+        Ok(())
+    }
+
+    /// Delete a save file of the current ROM, backing it up first (see [`SaveBackupSettings`]).
+    ///
+    /// The current save file can't be deleted.
+    pub fn delete_save_profile(&mut self, name: &str) -> Result<(), LocalizedError> {
+        let rom_name = self.get_current_rom_name_arc().ok_or_else(|| LocalizedError::new("game_not_running"))?;
+        if self.get_current_save_name() == Some(name) {
+            return Err(LocalizedError::new("save_profile_is_current"))
+        }
+        if !self.save_profile_exists(rom_name.as_str(), name) {
+            return Err(LocalizedError::new("save_profile_not_found").arg("name", name))
+        }
+
+        self.back_up_save_file(rom_name.as_str(), name, None)?;
+        let path = self.get_save_path(rom_name.as_str(), name);
+        if self.files.is_file(&path) {
+            self.files.remove_file(&path).map_err(|e| LocalizedError::new("save_profile_delete_failed").arg("name", name).arg("error", e))?;
+        }
+
+        self.update_save_profiles(|profiles| { profiles.remove(name); });
+        Ok(())
+    }
+
+    /// Switch to the next (or previous) save file of the current ROM by name, reloading the game.
+    pub fn cycle_save_profile(&mut self, forward: bool) {
+        let Some(rom_name) = self.get_current_rom_name_arc() else {
+            return
+        };
+
+        let profiles = self.get_save_profiles_for_rom(rom_name.as_str());
+        if profiles.is_empty() {
+            return
+        }
+
+        let current = self.get_current_save_name().and_then(|c| profiles.iter().position(|p| p.name.as_str() == c));
+        let next = match (current, forward) {
+            (Some(i), true) => (i + 1) % profiles.len(),
+            (Some(i), false) => (i + profiles.len() - 1) % profiles.len(),
+            (None, true) => 0,
+            (None, false) => profiles.len() - 1
+        };
+
+        if current == Some(next) {
+            return
+        }
+
+        let name = profiles[next].name.clone();
+        self.load_or_create_save_file(name.as_str(), false);
+        self.show_message("save_profile_switched", &[("name", name.as_str())]);
+    }
+
+    fn save_profile_exists(&self, rom: &str, name: &str) -> bool {
+        self.files.is_file(&self.get_save_path(rom, name))
+            || self.save_profiles.as_ref().is_some_and(|p| p.get(name).is_some())
+            || self.get_current_save_name() == Some(name)
+    }
+
+    /// Change the current ROM's save file metadata and write it to disk.
+    fn update_save_profiles<F: FnOnce(&mut SaveProfiles)>(&mut self, f: F) {
+        let Some(rom_name) = self.get_current_rom_name_arc() else {
+            return
+        };
+        let path = self.get_save_profiles_path_for_rom(rom_name.as_str());
+        let profiles = self.save_profiles.get_or_insert_with(|| SaveProfiles::read_or_default(self.files.as_ref(), &path));
+        f(profiles);
+        profiles.write(self.files.as_ref(), &path);
+    }
+
+    /// Set the current save file, optionally initializing (clearing) the old one.
+    ///
+    /// The game will be reloaded.
+    pub fn load_or_create_save_file(&mut self, save_file: &str, initialize: bool) {
+        if !self.is_game_running() {
+            return;
+        }
+
+        if initialize {
             let rom_name = self.get_current_rom_name_arc().expect("save file when not running");
-            self.delete_save_file_data(rom_name.as_str(), save_file);
+            if let Err(e) = self.delete_save_file_data(rom_name.as_str(), save_file) {
+                self.show_error(e.key(), &e.args().iter().map(|(name, value)| (*name, value.as_str())).collect::<Vec<_>>());
+                return
+            }
         }
 
+        self.set_current_save_file(save_file);
         self.reload_rom_in_place();
     }
 
@@ -730,292 +2565,1994 @@ impl SuperShuckieFrontend {
         }
 
         self.save_sram_unchecked();
+        self.update_playtime();
 
         let rom_name = self.get_current_rom_name_arc().expect("save file when not running");
         self.settings.get_rom_config_or_default(rom_name.as_str()).save_name = save_file.into();
         self.save_file = Some(Arc::new(save_file.into()));
+        self.sram_sandboxed = false;
+
+        if let Some(save_profiles) = self.save_profiles.as_mut() {
+            save_profiles.touch(save_file);
+        }
+        self.write_rom_stats();
     }
 
     /// Handle any logic that needs to be done regularly.
     pub fn tick(&mut self) {
+        if self.seeking_forward && !self.core.is_playing_back() {
+            self.set_seeking_forward(false);
+        }
+        self.tick_seek_backward();
         self.refresh_screen(false);
+        self.save_printed_pages();
+        self.update_peripheral_output();
+        self.push_audio();
+        self.check_core_hang();
+        self.check_ram_access_error();
+        self.check_determinism_mismatch();
+        self.dispatch_progress_events();
+        self.check_playback_finished();
+
+        self.update_playtime();
+        if self.last_stats_write.elapsed() >= STATS_WRITE_INTERVAL {
+            self.write_rom_stats();
+        }
+        self.tick_autosnap();
+        self.tick_instance_listener();
+        self.tick_power_saving();
+        self.tick_storage_watch();
     }
 
-    fn refresh_screen(&mut self, force: bool) {
-        let current_frame_count = self.core.get_elapsed_frames();
-        if force || current_frame_count == self.frame_count {
+    fn tick_storage_watch(&mut self) {
+        if self.storage_snapshot.is_none() || self.last_storage_check.elapsed() < STORAGE_CHECK_INTERVAL {
             return
         }
+        self.last_storage_check = Instant::now();
 
-        self.frame_count = current_frame_count;
-        self.core.read_screens(|screens| {
-            self.callbacks.refresh_screens(screens);
-        })
+        let Some(snapshot) = self.take_storage_snapshot() else {
+            self.storage_snapshot = None;
+            return
+        };
+        let previous = self.storage_snapshot.replace(snapshot).expect("no previous storage snapshot");
+        let current = self.storage_snapshot.as_ref().expect("no current storage snapshot");
+
+        let mut events = Vec::new();
+        for ((category, previous), current) in WATCHED_STORAGE_CATEGORIES.into_iter().zip(previous.iter()).zip(current.iter()) {
+            events.extend(current.difference(previous).map(|name| FrontendEvent::StorageChanged { category, name: name.clone(), added: true }));
+            events.extend(previous.difference(current).map(|name| FrontendEvent::StorageChanged { category, name: name.clone(), added: false }));
+        }
+        for event in events {
+            self.push_event(event);
+        }
     }
 
-    fn get_current_rom_name_arc(&self) -> Option<Arc<UTF8CString>> {
-        self.rom_name.clone()
+    /// List the files in each of [`WATCHED_STORAGE_CATEGORIES`] for the current ROM, or `None` if no
+    /// ROM is loaded.
+    fn take_storage_snapshot(&self) -> Option<Vec<BTreeSet<String>>> {
+        let rom = self.get_current_rom_name()?;
+        Some(WATCHED_STORAGE_CATEGORIES.into_iter().map(|category| {
+            let files = match category {
+                StorageCategory::SaveStates => self.get_all_save_states_for_rom(rom),
+                StorageCategory::SaveData => self.get_all_saves_for_rom(rom),
+                StorageCategory::Replays => self.get_all_replays_for_rom(rom),
+                _ => unreachable!("{category:?} is not watched")
+            };
+            files.into_iter().map(|f| f.as_str().to_owned()).collect()
+        }).collect())
     }
 
-    pub fn get_current_rom_name(&self) -> Option<&str> {
-        self.rom_name.as_ref().map(|i| i.as_str())
+    /// Start comparing against the files that exist now, if watching for changes.
+    fn reset_storage_watch(&mut self) {
+        self.storage_snapshot = if self.settings.storage.watch_for_external_changes {
+            self.take_storage_snapshot()
+        }
+        else {
+            None
+        };
+        self.last_storage_check = Instant::now();
     }
 
-    pub fn get_current_rom_name_c_str(&self) -> Option<&CStr> {
-        self.rom_name.as_ref().map(|i| i.as_c_str())
+    /// Set whether to check the current ROM's save states, save data, and replays for files added
+    /// or removed by other programs (e.g. cloud sync), emitting [`FrontendEvent::StorageChanged`].
+    ///
+    /// Folders are checked every couple of seconds. Files added or removed by this frontend are
+    /// reported, too.
+    pub fn set_watch_storage_setting(&mut self, watch: bool) {
+        self.settings.storage.watch_for_external_changes = watch;
+        self.reset_storage_watch();
     }
 
-    pub fn get_current_save_name(&self) -> Option<&str> {
-        self.save_file.as_ref().map(|i| i.as_str())
+    /// Get whether to check for files added or removed by other programs.
+    #[inline]
+    pub fn get_watch_storage_setting(&self) -> bool {
+        self.settings.storage.watch_for_external_changes
     }
 
-    pub fn get_current_save_name_c_str(&self) -> Option<&CStr> {
-        self.save_file.as_ref().map(|i| i.as_c_str())
+    /// Set whether each ROM's folders are named after the ROM's checksum rather than its filename,
+    /// so they are still found if the ROM is renamed or moved.
+    ///
+    /// The running game's folders are moved right away. Other ROMs' folders are moved when they
+    /// are next loaded, or by [`SuperShuckieFrontend::migrate_user_dirs`].
+    pub fn set_key_user_dirs_by_checksum_setting(&mut self, key_by_checksum: bool) -> Result<(), LocalizedError> {
+        if self.settings.storage.key_by_checksum == key_by_checksum {
+            return Ok(())
+        }
+
+        if self.recording_replay_file.is_some() {
+            return Err(LocalizedError::new("user_dirs_move_while_recording"))
+        }
+
+        self.settings.storage.key_by_checksum = key_by_checksum;
+
+        if let Some(rom) = self.get_current_rom_name_arc() {
+            let result = self.relocate_rom_dirs(rom.as_str()).and_then(|_| self.create_userdata_for_rom(rom.as_str()));
+            if let Err(e) = result {
+                self.settings.storage.key_by_checksum = !key_by_checksum;
+                return Err(e)
+            }
+        }
+
+        self.reset_storage_watch();
+        Ok(())
     }
 
+    /// Get whether each ROM's folders are named after the ROM's checksum.
     #[inline]
-    pub fn set_auto_stop_playback_on_input_setting(&mut self, new_setting: bool) {
-        self.settings.replay_settings.auto_stop_playback_on_input = new_setting
+    pub fn get_key_user_dirs_by_checksum_setting(&self) -> bool {
+        self.settings.storage.key_by_checksum
     }
 
-    #[inline]
-    pub fn get_auto_stop_playback_on_input_setting(&self) -> bool {
-        self.settings.replay_settings.auto_stop_playback_on_input
+    /// Move the folders of every ROM with a known checksum to the naming scheme in use (see
+    /// [`SuperShuckieFrontend::set_key_user_dirs_by_checksum_setting`]).
+    ///
+    /// Checksums are only known for ROMs that were loaded before, so ROMs in `rom_dirs` (including
+    /// ones in ZIP archives) that have folders are checksummed first. The running game's folders
+    /// are already up to date and are left alone.
+    pub fn migrate_user_dirs<P: AsRef<Path>>(&mut self, rom_dirs: &[P]) -> UserDirMigration {
+        let mut migration = UserDirMigration::default();
+
+        for dir in rom_dirs {
+            let dir = dir.as_ref();
+            let paths = match self.files.read_dir(dir) {
+                Ok(paths) => paths,
+                Err(e) => {
+                    migration.skipped.push(LocalizedError::new("file_read_failed").arg("path", dir.display()).arg("error", e).format(&self.messages).into());
+                    continue
+                }
+            };
+
+            for path in paths {
+                if let Err(e) = self.record_rom_checksums_in_file(&path) {
+                    migration.skipped.push(e.format(&self.messages).into());
+                }
+            }
+        }
+
+        let current_rom = self.get_current_rom_name_arc();
+        let roms: Vec<String> = self.settings.rom_config.iter()
+            .filter(|(rom, config)| config.checksum.is_some() && current_rom.as_ref().is_none_or(|c| c.as_str() != rom.as_str()))
+            .map(|(rom, _)| rom.clone())
+            .collect();
+
+        for rom in roms {
+            match self.relocate_rom_dirs(&rom) {
+                Ok(true) => migration.moved.push(rom.into()),
+                Ok(false) => (),
+                Err(e) => migration.skipped.push(e.format(&self.messages).into())
+            }
+        }
+
+        migration
     }
 
-    #[inline]
-    pub fn set_auto_unpause_on_input_setting(&mut self, new_setting: bool) {
-        self.settings.replay_settings.auto_unpause_on_input = new_setting
+    /// Record the checksum of the ROM at `path`, or of every ROM in it if it is a ZIP archive, if
+    /// the ROM has folders that are named after its filename.
+    ///
+    /// Files that are not ROMs are ignored.
+    fn record_rom_checksums_in_file(&mut self, path: &Path) -> Result<(), LocalizedError> {
+        let (Some(filename), Some(extension)) = (path.file_name().and_then(|f| f.to_str()), path.extension().and_then(|e| e.to_str())) else {
+            return Ok(())
+        };
+
+        let is_archive = extension.eq_ignore_ascii_case(ARCHIVE_EXTENSION);
+        if !self.files.is_file(path) || !(is_archive || is_supported_rom_extension(extension)) {
+            return Ok(())
+        }
+
+        let has_dirs = |s: &Self, rom: &str| s.get_rom_data_base_dirs().iter().any(|base| s.files.is_dir(&base.join(format!("{rom}-data"))));
+        if !is_archive && !has_dirs(self, filename) {
+            return Ok(())
+        }
+
+        let data = self.files.read(path).map_err(|e| LocalizedError::new("rom_read_failed").arg("filename", filename).arg("error", e))?;
+
+        if !is_archive {
+            self.record_rom_checksum(filename, &blake3_hash_to_ascii(blake3_hash(&data)).into());
+            return Ok(())
+        }
+
+        let archive = parse_rom_archive(filename, &data)?;
+        for entry in archive_rom_entries(&archive) {
+            if !has_dirs(self, entry.filename()) {
+                continue
+            }
+            let rom = archive.extract(entry).map_err(|e| LocalizedError::new("archive_extract_failed").arg("filename", filename).arg("error", e))?;
+            self.record_rom_checksum(entry.filename(), &blake3_hash_to_ascii(blake3_hash(&rom)).into());
+        }
+
+        Ok(())
+    }
+
+    fn tick_power_saving(&mut self) {
+        if self.settings.emulation.power_saving != PowerSaving::OnBattery
+            || self.last_power_check.is_some_and(|t| t.elapsed() < POWER_CHECK_INTERVAL) {
+            return
+        }
+        self.last_power_check = Some(Instant::now());
+        self.update_power_saving();
+    }
+
+    /// Apply power saving if it was turned on or off, such as by the host switching to battery.
+    fn update_power_saving(&mut self) {
+        let active = match self.settings.emulation.power_saving {
+            PowerSaving::Off => false,
+            PowerSaving::OnBattery => power::is_on_battery(self.files.as_ref()).unwrap_or(false),
+            PowerSaving::Always => true
+        };
+
+        if active == self.power_saving_active {
+            return
+        }
+
+        self.power_saving_active = active;
+        self.core.set_low_priority(active);
+        if self.seeking_forward {
+            self.set_core_speed(self.seek_speed());
+        }
+        else {
+            self.reset_speed();
+        }
     }
 
+    /// Set the power saving mode.
+    pub fn set_power_saving_setting(&mut self, new_setting: PowerSaving) {
+        self.settings.emulation.power_saving = new_setting;
+        self.last_power_check = None;
+        self.update_power_saving();
+    }
+
+    /// Get the power saving mode.
     #[inline]
-    pub fn get_auto_unpause_on_input_setting(&self) -> bool {
-        self.settings.replay_settings.auto_unpause_on_input
+    pub fn get_power_saving_setting(&self) -> PowerSaving {
+        self.settings.emulation.power_saving
     }
 
+    /// Get whether or not power is currently being saved.
     #[inline]
-    pub fn set_auto_pause_on_record_setting(&mut self, new_setting: bool) {
-        self.settings.replay_settings.auto_pause_on_record = new_setting
+    pub fn is_power_saving_active(&self) -> bool {
+        self.power_saving_active
+    }
+
+    /// Set the speed of the core, capped at 1x when saving power.
+    fn set_core_speed(&mut self, multiplier: f64) {
+        let multiplier = if self.power_saving_active { multiplier.min(1.0) } else { multiplier };
+        self.effective_speed = Speed::from_multiplier_float(multiplier);
+        self.core.set_speed(self.effective_speed);
+    }
+
+    fn begin_instance_listener(&mut self) {
+        self.end_instance_listener();
+        match InstanceListener::bind(self.files.as_ref(), &self.user_dir) {
+            Ok(listener) => self.instance_listener = Some(listener),
+            Err(e) => self.show_error("instance_listen_failed", &[("error", &e)])
+        }
+    }
+
+    fn end_instance_listener(&mut self) {
+        if let Some(listener) = self.instance_listener.take() {
+            listener.close(self.files.as_ref());
+        }
+    }
+
+    fn tick_instance_listener(&mut self) {
+        let Some(listener) = self.instance_listener.as_ref() else {
+            return
+        };
+
+        for path in listener.accept_forwarded_paths() {
+            if path.as_os_str().is_empty() {
+                continue
+            }
+            if let Err(e) = self.load_rom(&path) {
+                self.show_error("forwarded_rom_failed", &[("path", &path.display().to_string()), ("error", &e.format(&self.messages))]);
+            }
+        }
+    }
+
+    fn tick_autosnap(&mut self) {
+        let settings = &self.settings.autosnap;
+        if !settings.enabled || !self.is_game_running() || self.core.is_playing_back() {
+            return
+        }
+
+        let interval = Duration::from_secs(settings.interval_minutes.get() as u64 * 60);
+        if self.autosnap_playtime < interval {
+            return
+        }
+        self.autosnap_playtime = Duration::ZERO;
+
+        if let Err(e) = self.create_autosnap() {
+            self.show_error("autosnap_failed", &[("error", &e.format(&self.messages))]);
+        }
     }
 
+    /// Save what is currently shown on screen as a PNG, upscaled by `scale` (up to
+    /// [`MAX_EXPORT_SCALE`]) with nearest-neighbor scaling.
+    ///
+    /// Unlike [`SuperShuckieFrontend::read_screens`], this includes frame blending and visualizers.
+    /// Each screen is saved to its own file in the ROM's screenshots directory.
+    ///
+    /// Returns the name of the (last) screenshot if created.
+    pub fn save_screenshot(&mut self, scale: NonZeroU8) -> Result<UTF8CString, LocalizedError> {
+        if !self.is_game_running() {
+            return Err(LocalizedError::new("game_not_running"))
+        }
+
+        if scale.get() > MAX_EXPORT_SCALE {
+            return Err(LocalizedError::new("screenshot_scale_too_large").arg("max_scale", MAX_EXPORT_SCALE))
+        }
+
+        let rom_name = self.get_current_rom_name_arc().expect("no rom name when game is running in save_screenshot");
+        let screenshots_dir = self.get_screenshots_dir_for_rom(rom_name.as_str());
+
+        let screens = if self.displayed_screens.is_empty() {
+            self.core.read_screens(|screens| screens.to_vec())
+        }
+        else {
+            self.displayed_screens.clone()
+        };
+
+        let mut last_filename = None;
+        for screen in &screens {
+            let Some(png) = png::encode_png(screen, scale.get() as usize) else {
+                continue
+            };
+            let filename = self.write_file_or_make_generic(&screenshots_dir, None, Some("screenshot"), SCREENSHOT_EXTENSION, &png)?;
+            last_filename = Some(filename);
+        }
+
+        last_filename.map(UTF8CString::from).ok_or_else(|| LocalizedError::new("no_screens"))
+    }
+
+    /// Create an autosnap now, deleting the oldest ones if there are too many.
+    ///
+    /// Returns the name of the save state if created (see [`AutosnapSettings`]).
+    pub fn create_autosnap(&mut self) -> Result<UTF8CString, LocalizedError> {
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map(|i| i.as_secs()).unwrap_or_default();
+        let name = self.create_save_state(Some(&format!("{AUTOSNAP_FOLDER}/{}", format_filename_timestamp(timestamp))), None)?;
+
+        let rom_name = self.get_current_rom_name().expect("no rom name when game is running in create_autosnap");
+        let autosnap_dir = self.get_save_states_dir_for_rom(rom_name).join(AUTOSNAP_FOLDER);
+        let mut autosnaps = list_files_in_dir_with_extension(self.files.as_ref(), &autosnap_dir, SAVE_STATE_EXTENSION);
+        autosnaps.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+
+        let excess = autosnaps.len().saturating_sub(self.settings.autosnap.max_snapshots.get());
+        for old in &autosnaps[..excess] {
+            self.files.remove_file(&autosnap_dir.join(format!("{old}.{SAVE_STATE_EXTENSION}")))
+                .map_err(|e| LocalizedError::new("autosnap_delete_failed").arg("name", old).arg("error", e))?;
+        }
+
+        Ok(name)
+    }
+
+
+    fn set_seeking_forward(&mut self, seeking: bool) {
+        let seeking = seeking && self.core.is_playing_back();
+        if seeking == self.seeking_forward {
+            return
+        }
+
+        self.seeking_forward = seeking;
+        if seeking {
+            self.set_core_speed(self.seek_speed());
+        }
+        else {
+            self.reset_speed();
+        }
+    }
+
+    fn seek_speed(&self) -> f64 {
+        self.settings.emulation.base_speed_multiplier * self.settings.replay_settings.seek_speed_multiplier
+    }
+
+    /// Set whether or not rewind is being held.
+    ///
+    /// While playing back a replay, this steps backward by one keyframe interval at a time until
+    /// released, like [`Control::SeekBackward`]. Otherwise, this rewinds through the states captured
+    /// by the core if rewinding is enabled (see [`SuperShuckieFrontend::set_rewind_enabled`]).
+    pub fn set_rewinding(&mut self, rewinding: bool) {
+        if !rewinding {
+            self.next_seek_backward = None;
+            self.core.set_rewinding(false);
+        }
+        else if !self.core.is_playing_back() {
+            if self.settings.emulation.rewind_enabled {
+                self.core.set_rewinding(true);
+            }
+        }
+        else if self.next_seek_backward.is_none() {
+            self.next_seek_backward = Some(Instant::now());
+            self.tick_seek_backward();
+        }
+    }
+
+    /// Get whether states are captured for rewinding outside of replay playback.
     #[inline]
-    pub fn get_auto_pause_on_record_setting(&self) -> bool {
-        self.settings.replay_settings.auto_pause_on_record
+    pub fn is_rewind_enabled(&self) -> bool {
+        self.settings.emulation.rewind_enabled
+    }
+
+    /// Set whether states are captured for rewinding outside of replay playback.
+    ///
+    /// Disabling this discards all captured states.
+    pub fn set_rewind_enabled(&mut self, enabled: bool) {
+        self.settings.emulation.rewind_enabled = enabled;
+        self.core.set_rewind_settings(self.rewind_settings());
     }
 
+    /// Get the number of frames between states captured for rewinding.
     #[inline]
-    pub fn set_auto_decompress_replays_upfront_setting(&mut self, new_setting: bool) {
-        self.settings.replay_settings.auto_decompress_replays_upfront = new_setting;
+    pub fn get_rewind_interval_frames(&self) -> NonZeroU64 {
+        self.settings.emulation.rewind_interval_frames
+    }
+
+    /// Set the number of frames between states captured for rewinding.
+    ///
+    /// This discards all captured states.
+    pub fn set_rewind_interval_frames(&mut self, frames: NonZeroU64) {
+        self.settings.emulation.rewind_interval_frames = frames;
+        self.core.set_rewind_settings(self.rewind_settings());
     }
 
+    /// Get the memory used by states captured for rewinding, in MiB.
     #[inline]
-    pub fn get_auto_decompress_replays_upfront_setting(&self) -> bool {
-        self.settings.replay_settings.auto_decompress_replays_upfront
+    pub fn get_rewind_memory_budget_mib(&self) -> NonZeroU32 {
+        self.settings.emulation.rewind_memory_budget_mib
+    }
+
+    /// Set the memory used by states captured for rewinding, in MiB.
+    ///
+    /// This discards all captured states.
+    pub fn set_rewind_memory_budget_mib(&mut self, mib: NonZeroU32) {
+        self.settings.emulation.rewind_memory_budget_mib = mib;
+        self.core.set_rewind_settings(self.rewind_settings());
+    }
+
+    fn rewind_settings(&self) -> Option<RewindSettings> {
+        let emulation = &self.settings.emulation;
+        emulation.rewind_enabled.then(|| RewindSettings {
+            interval: emulation.rewind_interval_frames,
+            memory_budget: (emulation.rewind_memory_budget_mib.get() as usize).saturating_mul(1024 * 1024)
+        })
     }
 
-    /// Get the number of milliseconds elapsed.
-    #[inline]
-    pub fn get_elapsed_milliseconds(&self) -> u32 {
-        self.core.get_elapsed_milliseconds()
+    fn tick_seek_backward(&mut self) {
+        let Some(next) = self.next_seek_backward else {
+            return
+        };
+
+        if !self.core.is_playing_back() {
+            self.next_seek_backward = None;
+            return
+        }
+
+        let now = Instant::now();
+        if now < next {
+            return
+        }
+
+        // Step back by one keyframe interval at a time while held
+        let frames = self.settings.replay_settings.frames_per_keyframe.get().min(i32::MAX as u64) as i32;
+        self.core.advance_playback_frames(-frames);
+        self.next_seek_backward = Some(now + Duration::from_millis(self.settings.replay_settings.seek_backward_interval_ms.get()));
+    }
+
+    fn save_printed_pages(&mut self) {
+        let pages = self.core.take_printed_pages();
+        if pages.is_empty() {
+            return
+        }
+
+        let Some(rom_name) = self.get_current_rom_name_arc() else {
+            return
+        };
+        let printer_dir = self.get_printer_dir_for_rom(rom_name.as_str());
+
+        for page in pages {
+            let Some(png) = png::encode_png(&page, 1) else {
+                continue
+            };
+            let result = self.write_file_or_make_generic(&printer_dir, None, Some("page"), PRINTED_PAGE_EXTENSION, &png);
+
+            match result {
+                Ok(filename) => self.show_message("printed_page_saved", &[("filename", filename.as_str())]),
+                Err(e) => self.show_error("printed_page_failed", &[("error", &e.format(&self.messages))])
+            }
+        }
+    }
+
+    fn check_core_hang(&mut self) {
+        let Some(report) = self.core.take_hang_report() else {
+            return
+        };
+
+        // The core thread already paused itself
+        self.set_paused(true);
+        self.show_error("core_hang", &[
+            ("core", report.core_name),
+            ("frame", &report.frame.to_string()),
+            ("ticks", &report.elapsed_ticks.to_string()),
+            ("seconds", &format!("{:.1}", report.elapsed_time.as_secs_f64()))
+        ]);
+    }
+
+    fn check_ram_access_error(&mut self) {
+        let Some(error) = self.core.take_ram_access_error() else {
+            return
+        };
+
+        // The core thread already paused itself
+        self.set_paused(true);
+        self.show_error("ram_access_failed", &[("error", &error.to_string())]);
+    }
+
+    fn check_determinism_mismatch(&mut self) {
+        let Some(mismatch) = self.core.take_determinism_mismatch() else {
+            return
+        };
+
+        self.show_error("replay_desync_detected", &[
+            ("error", &mismatch.to_string()),
+            ("frame", &mismatch.start_frame.to_string())
+        ]);
+    }
+
+    fn dispatch_progress_events(&mut self) {
+        for event in self.core.take_progress_events() {
+            self.callbacks.report_progress(event);
+        }
+    }
+
+    fn update_peripheral_output(&mut self) {
+        let mut output = self.core.get_peripheral_output();
+
+        // don't leave the controller rumbling while paused
+        if self.paused {
+            output.rumble_amplitude = 0.0;
+        }
+
+        if output.rumble_amplitude != self.peripheral_output.rumble_amplitude {
+            self.callbacks.set_rumble(output.rumble_amplitude);
+        }
+        if output.infrared_led != self.peripheral_output.infrared_led {
+            self.callbacks.set_infrared_led(output.infrared_led);
+        }
+
+        self.peripheral_output = output;
+    }
+
+    fn push_audio(&mut self) {
+        if self.audio_sample_rate == 0 {
+            return
+        }
+
+        self.audio_samples.clear();
+        self.core.drain_audio_samples(&mut self.audio_samples);
+        if self.audio_samples.is_empty() {
+            return
+        }
+
+        self.callbacks.push_audio(self.audio_samples.as_slice(), self.core.get_audio_sample_rate());
+
+        if let Some(recording) = self.audio_recording.as_mut() {
+            wav::append_samples(&mut recording.buffer, &self.audio_samples);
+            if recording.buffer.len() >= AUDIO_RECORDING_BUFFER_SIZE && let Err(e) = recording.write_buffer(self.files.as_ref()) {
+                self.audio_recording = None;
+                self.show_error("audio_recording_failed", &[("error", &e.format(&self.messages))]);
+            }
+        }
+    }
+
+    fn refresh_screen(&mut self, force: bool) {
+        if self.is_throttled() {
+            return
+        }
+
+        let current_frame_count = self.core.get_elapsed_frames();
+        if force || current_frame_count == self.frame_count {
+            return
+        }
+
+        self.frame_count = current_frame_count;
+
+        if self.power_saving_active {
+            let skip = self.skip_next_render;
+            self.skip_next_render = !skip;
+            if skip {
+                return
+            }
+        }
+
+        if self.visualizers.is_empty() && !self.settings.emulation.frame_blending && !self.settings.emulation.onion_skin {
+            self.displayed_screens.clear();
+            self.core.read_screens(|screens| {
+                self.callbacks.refresh_screens(encode_screens(screens, self.screen_encoding, &mut self.encoded_screens));
+            });
+        }
+        else {
+            let mut screens = self.core.read_screens(|screens| screens.to_vec());
+            if self.settings.emulation.frame_blending {
+                self.blend_with_previous_frame(&mut screens);
+            }
+            if self.settings.emulation.onion_skin {
+                let frames = self.settings.emulation.onion_skin_frames.get() as usize;
+                self.onion_skin.apply(&mut screens, frames, self.settings.emulation.onion_skin_opacity);
+            }
+            if !self.visualizers.is_empty() {
+                self.visualizers.apply(&mut screens, &self.core.get_watched_memory());
+            }
+            self.callbacks.refresh_screens(encode_screens(&screens, self.screen_encoding, &mut self.encoded_screens));
+            self.displayed_screens = screens;
+        }
+        self.push_event(FrontendEvent::FrameReady { frame: current_frame_count });
+    }
+
+    /// Mix each screen with the previous frame to simulate LCD ghosting.
+    ///
+    /// This also makes games that flicker sprites every other frame look transparent like they did
+    /// on real hardware.
+    fn blend_with_previous_frame(&mut self, screens: &mut [ScreenData]) {
+        blend_frames(&mut self.previous_screens, screens);
+    }
+
+    /// Get whether frame blending is enabled.
+    #[inline]
+    pub fn is_frame_blending_enabled(&self) -> bool {
+        self.settings.emulation.frame_blending
+    }
+
+    /// Set whether each frame is mixed with the previous one to simulate LCD ghosting.
+    pub fn set_frame_blending_enabled(&mut self, enabled: bool) {
+        self.settings.emulation.frame_blending = enabled;
+        self.previous_screens.clear();
+    }
+
+    /// Get whether onion skinning is enabled.
+    #[inline]
+    pub fn is_onion_skin_enabled(&self) -> bool {
+        self.settings.emulation.onion_skin
+    }
+
+    /// Set whether the previous frames are overlaid onto each frame, which helps line up
+    /// frame-perfect tricks.
+    pub fn set_onion_skin_enabled(&mut self, enabled: bool) {
+        self.settings.emulation.onion_skin = enabled;
+        self.onion_skin.clear();
+    }
+
+    /// Get the number of previous frames overlaid by onion skinning.
+    #[inline]
+    pub fn get_onion_skin_frames(&self) -> NonZeroU8 {
+        self.settings.emulation.onion_skin_frames
+    }
+
+    /// Set the number of previous frames overlaid by onion skinning, up to 16.
+    pub fn set_onion_skin_frames(&mut self, frames: NonZeroU8) {
+        self.settings.emulation.onion_skin_frames = frames.min(NonZeroU8::new(MAX_ONION_SKIN_FRAMES).expect("MAX_ONION_SKIN_FRAMES is 0"));
+    }
+
+    /// Get the opacity of the most recent frame overlaid by onion skinning.
+    #[inline]
+    pub fn get_onion_skin_opacity(&self) -> f64 {
+        self.settings.emulation.onion_skin_opacity
+    }
+
+    /// Set the opacity of the most recent frame overlaid by onion skinning, from 0.0 to 1.0.
+    ///
+    /// Older frames are overlaid with less opacity the older they are.
+    pub fn set_onion_skin_opacity(&mut self, opacity: f64) {
+        self.settings.emulation.onion_skin_opacity = if opacity.is_nan() { EmulationSettings::DEFAULT_ONION_SKIN_OPACITY() } else { opacity.clamp(0.0, 1.0) };
+    }
+
+    fn toggle_onion_skin(&mut self) {
+        let enabled = !self.settings.emulation.onion_skin;
+        self.set_onion_skin_enabled(enabled);
+        if enabled {
+            let frames = self.settings.emulation.onion_skin_frames.to_string();
+            self.show_message("onion_skin_enabled", &[("frames", &frames)]);
+        }
+        else {
+            self.show_message("onion_skin_disabled", &[]);
+        }
+    }
+
+    /// Read the screens.
+    ///
+    /// This is useful for getting the pixels of a [`FrontendEvent::FrameReady`] event.
+    pub fn read_screens<T, F: FnOnce(&[ScreenData]) -> T>(&self, reader: F) -> T {
+        self.core.read_screens(reader)
+    }
+
+    /// Add a visualizer, which draws over the screens every frame.
+    pub fn add_visualizer(&mut self, visualizer: Box<dyn Visualizer>) -> VisualizerId {
+        let id = self.visualizers.add(visualizer);
+        self.update_watched_memory();
+        id
+    }
+
+    /// Remove a visualizer, returning `true` if it was removed.
+    pub fn remove_visualizer(&mut self, id: VisualizerId) -> bool {
+        let removed = self.visualizers.remove(id);
+        if removed {
+            self.update_watched_memory();
+        }
+        removed
+    }
+
+    /// Watch the memory needed by visualizers, followed by the game profile's watches.
+    fn update_watched_memory(&self) {
+        let mut watched_memory = self.visualizers.watched_memory();
+        if let Some(profile) = self.game_profile.as_ref() {
+            watched_memory.extend(profile.watched_memory());
+        }
+        self.core.set_watched_memory(watched_memory);
+    }
+
+    /// Get the BLAKE3 checksum of the loaded ROM in hex, as used by game profiles.
+    #[inline]
+    pub fn get_rom_checksum(&self) -> Option<&UTF8CString> {
+        self.rom_checksum.as_ref()
+    }
+
+    /// Add a game profile (e.g. one embedded in the frontend) from JSON.
+    ///
+    /// Profiles in the user directory's [`GAME_PROFILES_FOLDER`](game_profile::GAME_PROFILES_FOLDER)
+    /// take precedence over ones added here. If a game is running without a profile, this one is
+    /// loaded right away if it matches.
+    pub fn add_game_profile(&mut self, json: &str) -> Result<(), LocalizedError> {
+        let profile = GameProfile::from_json(json).map_err(|e| LocalizedError::new("game_profile_rejected").arg("error", e))?;
+        self.embedded_game_profiles.push(profile);
+        if self.is_game_running() && self.game_profile.is_none() {
+            self.load_game_profile();
+        }
+        Ok(())
+    }
+
+    /// Get the game profile for the loaded ROM, if any.
+    #[inline]
+    pub fn get_game_profile(&self) -> Option<&GameProfile> {
+        self.game_profile.as_ref()
+    }
+
+    /// Get the current value of each watch in the game profile, in order.
+    pub fn get_game_profile_watches(&self) -> Vec<GameProfileWatchValue> {
+        let Some(profile) = self.game_profile.as_ref() else {
+            return Vec::new()
+        };
+
+        let memory = self.core.get_watched_memory();
+        let first = self.visualizers.watched_memory().len();
+
+        profile.watches.iter().enumerate().map(|(index, watch)| GameProfileWatchValue {
+            name: watch.name.clone(),
+            value: memory.get(first + index)
+                .filter(|data| data.len() == watch.length.get())
+                .map(|data| watch.format_value(data).into())
+        }).collect()
+    }
+
+    /// Find the game profile for the loaded ROM and start watching its addresses.
+    fn load_game_profile(&mut self) {
+        let Some(checksum) = self.rom_checksum.as_ref() else {
+            return
+        };
+
+        let (profile, errors) = find_game_profile(self.files.as_ref(), &self.user_dir, &self.embedded_game_profiles, checksum.as_str());
+        for error in errors {
+            self.show_error("game_profile_invalid", &[("error", &error)]);
+        }
+
+        self.game_profile = profile;
+        if let Some(profile) = self.game_profile.as_ref() {
+            let name = profile.name.to_string();
+            self.show_message("game_profile_loaded", &[("name", &name)]);
+        }
+        self.update_watched_memory();
+    }
+
+    /// Show a message from the catalog to the user.
+    fn show_message(&mut self, key: &str, args: &[(&str, &str)]) {
+        let message = self.messages.format(key, args);
+        self.callbacks.show_message(&message);
+        self.push_event(FrontendEvent::Message { key: key.to_owned(), message });
+    }
+
+    /// Show a message from the catalog to the user about something that failed.
+    fn show_error(&mut self, key: &str, args: &[(&str, &str)]) {
+        let message = self.messages.format(key, args);
+        self.callbacks.show_message(&message);
+        self.push_event(FrontendEvent::Error { key: key.to_owned(), message });
+    }
+
+    /// Set the language of messages shown to the user.
+    ///
+    /// Languages other than the default one are loaded from the user directory (see
+    /// [`locale::LOCALE_FOLDER`]).
+    pub fn set_language(&mut self, language: &str) -> Result<(), LocalizedError> {
+        self.messages = MessageCatalog::load(self.files.as_ref(), &self.user_dir, language)?;
+        self.settings.locale.language = language.into();
+        Ok(())
+    }
+
+    /// Get the language of messages shown to the user.
+    #[inline]
+    pub fn get_language(&self) -> &UTF8CString {
+        self.messages.language()
+    }
+
+    /// Get all languages that can be set with [`SuperShuckieFrontend::set_language`].
+    pub fn get_available_languages(&self) -> Vec<UTF8CString> {
+        MessageCatalog::available_languages(self.files.as_ref(), &self.user_dir)
+    }
+
+    /// Get the message catalog for the current language.
+    ///
+    /// Frontends can use this to localize their own strings, too.
+    #[inline]
+    pub fn get_messages(&self) -> &MessageCatalog {
+        &self.messages
+    }
+
+    /// Set whether or not events are queued for [`SuperShuckieFrontend::poll_event`].
+    ///
+    /// This is off by default. Turning it off discards any queued events. Callbacks are called
+    /// either way.
+    pub fn set_event_polling_enabled(&mut self, enabled: bool) {
+        if enabled != self.events.is_some() {
+            self.events = enabled.then(VecDeque::new);
+        }
+    }
+
+    /// Take the oldest queued event, if any.
+    ///
+    /// See [`SuperShuckieFrontend::set_event_polling_enabled`].
+    pub fn poll_event(&mut self) -> Option<FrontendEvent> {
+        self.events.as_mut()?.pop_front()
+    }
+
+    /// Add a hook which is called with every event as it happens, returning its ID.
+    ///
+    /// This is for integrations (e.g. achievements, rich presence, or scripts) that need to know
+    /// when things like loading a ROM, resetting, or loading a save state happen. Unlike
+    /// [`SuperShuckieFrontend::poll_event`], this works even if event polling is disabled.
+    pub fn add_event_hook(&mut self, hook: EventHook) -> EventHookId {
+        // 0 is never used so that it can mean "no hook" in the C API
+        self.next_event_hook_id = self.next_event_hook_id.wrapping_add(1).max(1);
+        let id = self.next_event_hook_id;
+        self.event_hooks.push((id, hook));
+        id
+    }
+
+    /// Remove a hook, returning `true` if it was removed.
+    pub fn remove_event_hook(&mut self, id: EventHookId) -> bool {
+        let Some(index) = self.event_hooks.iter().position(|(i, _)| *i == id) else {
+            return false
+        };
+        let _ = self.event_hooks.remove(index);
+        true
+    }
+
+    fn push_event(&mut self, event: FrontendEvent) {
+        for (_, hook) in &mut self.event_hooks {
+            hook(&event);
+        }
+
+        let Some(events) = self.events.as_mut() else {
+            return
+        };
+
+        // Drop the oldest events if nobody is polling
+        if events.len() >= MAX_QUEUED_EVENTS {
+            events.pop_front();
+        }
+        events.push_back(event);
+    }
+
+    fn check_playback_finished(&mut self) {
+        let finished = self.core.is_playing_back() && self.core.get_elapsed_frames() >= self.core.get_playback_total_frames();
+        if finished && !self.playback_finished {
+            self.push_event(FrontendEvent::PlaybackFinished);
+        }
+        self.playback_finished = finished;
+    }
+
+    fn get_current_rom_name_arc(&self) -> Option<Arc<UTF8CString>> {
+        self.rom_name.clone()
+    }
+
+    pub fn get_current_rom_name(&self) -> Option<&str> {
+        self.rom_name.as_ref().map(|i| i.as_str())
+    }
+
+    pub fn get_current_rom_name_c_str(&self) -> Option<&CStr> {
+        self.rom_name.as_ref().map(|i| i.as_c_str())
+    }
+
+    pub fn get_current_save_name(&self) -> Option<&str> {
+        self.save_file.as_ref().map(|i| i.as_str())
+    }
+
+    pub fn get_current_save_name_c_str(&self) -> Option<&CStr> {
+        self.save_file.as_ref().map(|i| i.as_c_str())
+    }
+
+    #[inline]
+    pub fn set_auto_stop_playback_on_input_setting(&mut self, new_setting: bool) {
+        self.settings.replay_settings.auto_stop_playback_on_input = new_setting
+    }
+
+    #[inline]
+    pub fn get_auto_stop_playback_on_input_setting(&self) -> bool {
+        self.settings.replay_settings.auto_stop_playback_on_input
+    }
+
+    #[inline]
+    pub fn set_auto_unpause_on_input_setting(&mut self, new_setting: bool) {
+        self.settings.replay_settings.auto_unpause_on_input = new_setting
+    }
+
+    #[inline]
+    pub fn get_auto_unpause_on_input_setting(&self) -> bool {
+        self.settings.replay_settings.auto_unpause_on_input
+    }
+
+    #[inline]
+    pub fn set_auto_pause_on_record_setting(&mut self, new_setting: bool) {
+        self.settings.replay_settings.auto_pause_on_record = new_setting
+    }
+
+    #[inline]
+    pub fn get_auto_pause_on_record_setting(&self) -> bool {
+        self.settings.replay_settings.auto_pause_on_record
+    }
+
+    /// Set whether save data written during replay playback is discarded (see
+    /// [`ReplaySettings::sandbox_playback_sram`]).
+    ///
+    /// This takes effect the next time a replay is played back.
+    #[inline]
+    pub fn set_sandbox_playback_sram_setting(&mut self, new_setting: bool) {
+        self.settings.replay_settings.sandbox_playback_sram = new_setting
+    }
+
+    #[inline]
+    pub fn get_sandbox_playback_sram_setting(&self) -> bool {
+        self.settings.replay_settings.sandbox_playback_sram
+    }
+
+    /// Set how often replays are checked for determinism while recording, in keyframes, or `None` to
+    /// not check (see [`ReplaySettings::determinism_check_keyframes`]).
+    ///
+    /// This takes effect the next time a replay is recorded.
+    #[inline]
+    pub fn set_determinism_check_keyframes_setting(&mut self, new_setting: Option<NonZeroU64>) {
+        self.settings.replay_settings.determinism_check_keyframes = new_setting
+    }
+
+    #[inline]
+    pub fn get_determinism_check_keyframes_setting(&self) -> Option<NonZeroU64> {
+        self.settings.replay_settings.determinism_check_keyframes
+    }
+
+    /// Make a shadow core for checking the replay about to be recorded, if enabled.
+    fn determinism_check_settings(&self) -> Option<DeterminismCheckSettings> {
+        let keyframes_per_check = self.settings.replay_settings.determinism_check_keyframes?;
+        let emulator_type = self.core_metadata.emulator_type.expect("determinism_check_settings with no emulator type");
+        let rom_data = self.loaded_rom_data.as_ref().expect("determinism_check_settings with no loaded rom");
+
+        let mut shadow_core = self.make_new_core(rom_data, None, emulator_type);
+        shadow_core.set_rendering_enabled(false);
+        if self.settings.game_boy_settings.printer_connected {
+            shadow_core.set_printer_connected(true);
+        }
+
+        Some(DeterminismCheckSettings { shadow_core, keyframes_per_check })
+    }
+
+    /// Return `true` if the SRAM came from a replay and is not being saved.
+    ///
+    /// This stays set after playback stops, until another save file is chosen (e.g. with
+    /// [`SuperShuckieFrontend::set_current_save_file`]) or the ROM is reloaded.
+    #[inline]
+    pub fn is_sram_sandboxed(&self) -> bool {
+        self.sram_sandboxed
+    }
+
+    /// Set what to record when a save state is loaded while recording.
+    ///
+    /// This takes effect the next time a recording is started.
+    #[inline]
+    pub fn set_load_state_record_mode_setting(&mut self, mode: LoadStateRecordMode) {
+        self.settings.replay_settings.load_state_record_mode = mode;
+    }
+
+    #[inline]
+    pub fn get_load_state_record_mode_setting(&self) -> LoadStateRecordMode {
+        self.settings.replay_settings.load_state_record_mode
+    }
+
+    #[inline]
+    pub fn set_auto_decompress_replays_upfront_setting(&mut self, new_setting: bool) {
+        self.settings.replay_settings.auto_decompress_replays_upfront = new_setting;
+    }
+
+    #[inline]
+    pub fn get_auto_decompress_replays_upfront_setting(&self) -> bool {
+        self.settings.replay_settings.auto_decompress_replays_upfront
+    }
+
+    #[inline]
+    pub fn set_autosnap_enabled_setting(&mut self, new_setting: bool) {
+        self.settings.autosnap.enabled = new_setting;
+        self.autosnap_playtime = Duration::ZERO;
+    }
+
+    #[inline]
+    pub fn set_pause_on_focus_loss_setting(&mut self, new_setting: bool) {
+        self.settings.emulation.pause_on_focus_loss = new_setting;
+    }
+
+    #[inline]
+    pub fn get_pause_on_focus_loss_setting(&self) -> bool {
+        self.settings.emulation.pause_on_focus_loss
+    }
+
+    pub fn set_throttle_when_minimized_setting(&mut self, new_setting: bool) {
+        self.settings.emulation.throttle_when_minimized = new_setting;
+        self.core.set_background(self.is_throttled());
+    }
+
+    #[inline]
+    pub fn get_throttle_when_minimized_setting(&self) -> bool {
+        self.settings.emulation.throttle_when_minimized
+    }
+
+    #[inline]
+    pub fn get_autosnap_enabled_setting(&self) -> bool {
+        self.settings.autosnap.enabled
+    }
+
+    #[inline]
+    pub fn set_save_backups_enabled_setting(&mut self, new_setting: bool) {
+        self.settings.save_backups.enabled = new_setting;
+    }
+
+    #[inline]
+    pub fn get_save_backups_enabled_setting(&self) -> bool {
+        self.settings.save_backups.enabled
+    }
+
+    /// Set whether or not new instances forward their ROM to this one (see
+    /// [`instance::forward_to_running_instance`]).
+    pub fn set_single_instance_setting(&mut self, new_setting: bool) {
+        self.settings.instance.single_instance = new_setting;
+        if !new_setting {
+            self.end_instance_listener();
+        }
+        else if self.instance_listener.is_none() {
+            self.begin_instance_listener();
+        }
+    }
+
+    #[inline]
+    pub fn get_single_instance_setting(&self) -> bool {
+        self.settings.instance.single_instance
+    }
+
+    /// Get the number of milliseconds elapsed.
+    #[inline]
+    pub fn get_elapsed_milliseconds(&self) -> u32 {
+        self.core.get_elapsed_milliseconds()
+    }
+
+    /// Get the number of milliseconds elapsed.
+    #[inline]
+    pub fn get_elapsed_frames(&self) -> u32 {
+        self.core.get_elapsed_frames()
+    }
+
+    /// Skip to the desired frame.
+    #[inline]
+    pub fn go_to_replay_frame(&mut self, frame: u32) {
+        self.core.go_to_replay_frame(frame);
+    }
+
+    /// Skip to the desired frame, stopping early (wherever the seek got to) if `cancel` is cancelled.
+    #[inline]
+    pub fn go_to_replay_frame_cancellable(&mut self, frame: u32, cancel: CancellationToken) {
+        self.core.go_to_replay_frame_cancellable(frame, cancel);
+    }
+
+    #[inline]
+    pub fn advance_playback_frames(&mut self, delta: i32) {
+        self.core.advance_playback_frames(delta)
+    }
+
+    /// Advance one frame.
+    ///
+    /// While playing back a replay, this steps forward one frame of the replay. Otherwise, this
+    /// pauses the game if it is running, or runs exactly one frame if it is already paused. This
+    /// does nothing in a netplay session.
+    pub fn frame_advance(&mut self) {
+        if !self.is_game_running() || self.core.get_netplay_status() != NetplayStatus::Inactive {
+            return
+        }
+
+        if self.core.is_playing_back() {
+            self.core.advance_playback_frames(1);
+        }
+        else if !self.paused {
+            self.set_paused(true);
+        }
+        else {
+            self.core.run_single_frame();
+        }
+    }
+
+    /// Save the settings to disk.
+    #[inline]
+    pub fn write_settings(&self) {
+        // TODO: handle errors here?
+        let _ = self.files.write(&self.user_dir.join(SETTINGS_FILE), serde_json::to_string_pretty(&self.settings).expect("failed to serialize").as_bytes());
+    }
+
+    fn before_unload_or_reload_rom(&mut self) {
+        self.reset_save_state_history();
+        self.stop_recording_replay();
+        self.stop_recording_audio();
+        self.pokeabyte_error = None;
+    }
+
+    /// Start recording a replay.
+    ///
+    /// If `name` is set, that name will be used.
+    ///
+    /// Returns the name of the replay if started.
+    pub fn start_recording_replay(&mut self, name: Option<&str>) -> Result<UTF8CString, LocalizedError> {
+        if !self.is_game_running() {
+            return Err(LocalizedError::new("game_not_running"))
+        }
+
+        let current_rom_name = self.get_current_rom_name_arc().expect("no rom name when game is running in start_recording_replay");
+        let save_states_dir = self.get_replays_dir_for_rom(current_rom_name.as_str());
+
+        let final_kind = ReplaySinkKind::Final {
+            flush_threshold: (self.settings.replay_settings.write_buffer_size_kb as usize).saturating_mul(1024)
+        };
+        let (final_file, final_replay, final_replay_path) = self.create_replay_sink_or_make_generic(&save_states_dir, name, None, REPLAY_EXTENSION, final_kind)?;
+        let (temp_file, _, temp_replay) = self.create_replay_sink_or_make_generic(&save_states_dir, None, Some("temp"), REPLAY_EXTENSION, ReplaySinkKind::Temporary)?;
+
+        if self.settings.replay_settings.auto_pause_on_record {
+            self.set_paused(true);
+        }
+
+        self.core.set_determinism_check(self.determinism_check_settings());
+        self.core.start_recording_replay(PartialReplayRecordMetadata {
+            rom_name: current_rom_name.to_string(),
+            rom_filename: current_rom_name.to_string(),
+
+            settings: ReplayFileRecorderSettings {
+                minimum_uncompressed_bytes_per_blob: (self.settings.replay_settings.max_recording_blob_size_mb.get() as usize)
+                    .saturating_mul(1024)
+                    .saturating_mul(1024),
+                compression_level: self.settings.replay_settings.zstd_compression_level,
+                load_save_state_mode: self.settings.replay_settings.load_state_record_mode.into()
+            },
+
+            // TODO: patches
+            patch_format: ReplayPatchFormat::Unpatched,
+            patch_target_checksum: ReplayHeaderBlake3Hash::default(),
+            patch_data: ByteVec::default(),
+
+            frames_per_keyframe: self.settings.replay_settings.frames_per_keyframe,
+            keyframe_on_events: self.settings.replay_settings.keyframe_on_events,
+
+            final_file,
+            temp_file,
+        });
+
+        self.recording_replay_file = Some(ReplayFileInfo {
+            final_replay_name: final_replay.clone().into(),
+            temp_replay_path: temp_replay,
+            final_replay_path,
+            bookmarks: Vec::new()
+        });
+
+        self.push_event(FrontendEvent::ReplayStarted { name: final_replay.clone(), recording: true });
+        Ok(final_replay.into())
+    }
+
+    /// Add a bookmark to the replay being recorded, if any.
+    pub fn add_replay_bookmark(&mut self, name: &str) {
+        let Some(replay_file) = self.recording_replay_file.as_mut() else {
+            return
+        };
+        replay_file.bookmarks.push(ReplayMarker {
+            elapsed_frames: self.core.get_elapsed_frames() as u64,
+            elapsed_millis: self.core.get_elapsed_milliseconds() as u64,
+            name: name.to_owned()
+        });
+        self.core.add_replay_bookmark(name.to_owned());
+    }
+
+    /// Stop recording replay.
+    pub fn stop_recording_replay(&mut self) {
+        let Some(replay_file) = self.recording_replay_file.take() else {
+            return
+        };
+
+        // FIXME: We should make sure that it actually finalized here before deleting the temp file.
+        let zero_frames = self.core.get_elapsed_frames() == 0;
+
+        let callbacks = &mut self.callbacks;
+        self.core.stop_recording_replay_with_progress(|e| callbacks.report_progress(e));
+        let _ = self.files.remove_file(&replay_file.temp_replay_path);
+
+        if zero_frames {
+            let _ = self.files.remove_file(&replay_file.final_replay_path);
+        }
+
+        self.push_event(FrontendEvent::ReplayEnded { recording: true });
+    }
+
+    /// Get all saves for the given ROM.
+    #[inline]
+    pub fn get_all_saves_for_rom(&self, rom: &str) -> Vec<UTF8CString> {
+        list_files_in_dir_with_extension(self.files.as_ref(), &self.get_save_data_dir_for_rom(rom), SAVE_DATA_EXTENSION)
+    }
+
+    /// Get all save states for the given ROM.
+    ///
+    /// Save states in subfolders are included, named with their subfolder (e.g. `practice/boss3`).
+    #[inline]
+    pub fn get_all_save_states_for_rom(&self, rom: &str) -> Vec<UTF8CString> {
+        list_named_files_in_dir_with_extension(self.files.as_ref(), &self.get_save_states_dir_for_rom(rom), SAVE_STATE_EXTENSION)
+    }
+
+    /// Get all replays for the given ROM.
+    ///
+    /// Replays in subfolders are included, named with their subfolder (e.g. `practice/boss3`).
+    #[inline]
+    pub fn get_all_replays_for_rom(&self, rom: &str) -> Vec<UTF8CString> {
+        list_named_files_in_dir_with_extension(self.files.as_ref(), &self.get_replays_dir_for_rom(rom), REPLAY_EXTENSION)
+    }
+
+    /// Scrub identifying info from the header of the given ROM's replay with the given name, so it can
+    /// be shared publicly.
+    ///
+    /// The internal ROM name is replaced with `rom_name` and the ROM filename is cleared. Nothing
+    /// else is changed, so it still plays back the same.
+    pub fn scrub_replay(&self, rom: &str, name: &str, rom_name: &str) -> Result<(), LocalizedError> {
+        let (_, replay_file) = get_named_file_path(&self.get_replays_dir_for_rom(rom), name, REPLAY_EXTENSION)?;
+        if !self.files.is_file(&replay_file) {
+            return Err(LocalizedError::new("replay_not_found").arg("name", name))
+        }
+        if self.recording_replay_file.as_ref().is_some_and(|r| r.final_replay_path == replay_file) {
+            return Err(LocalizedError::new("replay_still_recording").arg("name", name))
+        }
+
+        let mut data = self.files.read(&replay_file).map_err(|e| LocalizedError::new("replay_read_failed").arg("name", name).arg("error", e))?;
+        scrub_replay_header(&mut data, rom_name).map_err(|e| LocalizedError::new("replay_scrub_failed").arg("name", name).arg("error", e))?;
+        self.files.write(&replay_file, &data).map_err(|e| LocalizedError::new("file_write_failed").arg("path", name).arg("error", e))?;
+        Ok(())
+    }
+
+    /// Get all replays for a ROM along with their details, filtered and sorted by `query`.
+    ///
+    /// Compatibility is checked against the loaded ROM, so it is [`ReplayCompatibility::Unknown`]
+    /// unless `rom` is loaded. Files are only re-read if they changed since the last call.
+    pub fn browse_replays(&mut self, rom: &str, query: &ReplayBrowserQuery) -> Vec<ReplayBrowserEntry> {
+        let replay_dir = self.get_replays_dir_for_rom(rom);
+        let rom_loaded = self.is_game_running() && self.get_current_rom_name() == Some(rom);
+        let name_filter = query.name_filter.to_lowercase();
+
+        let mut entries = Vec::new();
+        let mut paths = Vec::new();
+
+        for name in list_named_files_in_dir_with_extension(self.files.as_ref(), &replay_dir, REPLAY_EXTENSION) {
+            let Ok((_, path)) = get_named_file_path(&replay_dir, name.as_str(), REPLAY_EXTENSION) else {
+                continue
+            };
+            paths.push(path);
+
+            // Filtered out replays stay cached in case the filter changes
+            if !name.as_str().to_lowercase().contains(&name_filter) {
+                continue
+            }
+
+            let Some(details) = self.replay_browser_cache.read(self.files.as_ref(), paths.last().expect("we just added the path")) else {
+                continue
+            };
+
+            let mut entry = ReplayBrowserEntry {
+                name,
+                total_frames: 0,
+                total_milliseconds: 0,
+                size: details.size,
+                created: details.created,
+                console_type: ReplayConsoleType::Unknown,
+                compatibility: ReplayCompatibility::Unreadable,
+                mismatches: Vec::new()
+            };
+
+            if let Some(summary) = details.summary {
+                entry.total_frames = summary.total_frames;
+                entry.total_milliseconds = summary.total_milliseconds;
+                entry.console_type = summary.metadata.console_type;
+
+                let playable = matches!(summary.metadata.console_type, ReplayConsoleType::GameBoy | ReplayConsoleType::SuperGameBoy2 | ReplayConsoleType::GameBoyColor);
+                entry.compatibility = if !playable {
+                    ReplayCompatibility::Incompatible
+                }
+                else if !rom_loaded {
+                    ReplayCompatibility::Unknown
+                }
+                else {
+                    entry.mismatches = self.core.get_replay_metadata_mismatches(&summary.metadata)
+                        .into_iter()
+                        .map(ReplayMismatch::from)
+                        .filter(|m| !self.is_replay_mismatch_remembered(rom, entry.name.as_str(), m))
+                        .map(|m| m.kind)
+                        .collect();
+                    if entry.mismatches.is_empty() { ReplayCompatibility::Compatible } else { ReplayCompatibility::Mismatched }
+                };
+            }
+
+            entries.push(entry);
+        }
+
+        self.replay_browser_cache.retain(&paths);
+        replay_browser::apply_query(&mut entries, query);
+        entries
+    }
+
+    fn after_switch_core(&mut self) {
+        self.update_video_mode();
+    }
+
+    fn update_video_mode(&mut self) {
+        self.core.read_screens(|screens| {
+            self.callbacks.change_video_mode(encode_screens(screens, self.screen_encoding, &mut self.encoded_screens), self.settings.emulation.video_scale);
+        });
+    }
+
+    fn after_load_rom(&mut self) {
+        self.force_refresh_screens();
+        self.previous_screens.clear();
+        self.onion_skin.clear();
+        self.displayed_screens.clear();
+        self.current_input = Input::default();
+        self.current_tilt = [0.0; 4];
+        self.set_core_speed(self.settings.emulation.base_speed_multiplier);
+        self.core.set_low_priority(self.power_saving_active);
+        self.reset_storage_watch();
+        self.core.set_crash_dump_directory(Some(self.user_dir.join("crash dumps")));
+        self.core.set_watchdog_timeout(match self.settings.emulation.watchdog_timeout_seconds {
+            0 => None,
+            n => Some(Duration::from_secs(n))
+        });
+        self.core.set_pokeabyte_write_policy(self.pokeabyte_write_policy());
+        self.core.set_pokeabyte_frame_skip_policy(self.pokeabyte_frame_skip_policy());
+        self.core.set_strict_ram_access(self.settings.pokeabyte.strict_ram_access);
+        self.core.set_rewind_settings(self.rewind_settings());
+        self.update_watched_memory();
+        self.core.set_background(self.is_throttled());
+        if let Some(rom) = self.get_current_rom_name_arc() {
+            self.core.set_input_delay(self.get_rom_input_delay(rom.as_str()) as usize);
+            if let Err(e) = self.apply_rom_cheats(rom.as_str()) {
+                self.show_error("cheats_failed", &[("error", &e.format(&self.messages))]);
+            }
+        }
+        if self.settings.game_boy_settings.printer_connected {
+            self.core.set_printer_connected(true);
+        }
+        if self.audio_sample_rate != 0 {
+            self.core.set_audio_sample_rate(self.audio_sample_rate);
+        }
+        if self.serial_capture {
+            self.core.set_serial_capture_enabled(true);
+        }
+        if self.settings.pokeabyte.enabled {
+            let _ = self.set_pokeabyte_enabled(true);
+        }
+        if !self.paused {
+            self.core.start();
+        }
+    }
+
+    #[inline]
+    fn reset_speed(&mut self) {
+        self.apply_turbo(0.0);
+    }
+
+    fn apply_turbo(&mut self, turbo: f64) {
+        let base_speed = self.settings.emulation.base_speed_multiplier;
+        let max_speed = self.settings.emulation.turbo_speed_multiplier * base_speed;
+        let total_speed = base_speed + (max_speed - base_speed) * turbo;
+        self.set_core_speed(total_speed);
+    }
+
+    #[inline]
+    /// Get the replay file info, or `None` if not recording.
+    pub fn get_replay_file_info(&self) -> Option<&ReplayFileInfo> {
+        self.recording_replay_file.as_ref()
+    }
+
+    /// Host a netplay session, listening for the other instance on the given UDP port.
+    ///
+    /// Both instances control the same console, and the current state is sent to the other instance
+    /// once it connects. The ROM's input delay (see [`SuperShuckieFrontend::get_rom_input_delay`]) is
+    /// applied to local input.
+    pub fn host_netplay(&mut self, port: u16) -> Result<(), LocalizedError> {
+        let input_delay = self.netplay_input_delay()?;
+        let session = NetplaySession::host(port, input_delay).map_err(|e| LocalizedError::new("netplay_listen_failed").arg("port", port).arg("error", e))?;
+        self.core.start_netplay(session);
+        Ok(())
+    }
+
+    /// Join a netplay session hosted at the given address (e.g. `192.168.1.2:7845`).
+    ///
+    /// The same ROM must be loaded by both instances.
+    pub fn join_netplay(&mut self, address: &str) -> Result<(), LocalizedError> {
+        let input_delay = self.netplay_input_delay()?;
+        let session = NetplaySession::join(address, input_delay).map_err(|e| LocalizedError::new("netplay_connect_failed").arg("address", address).arg("error", e))?;
+        self.core.start_netplay(session);
+        Ok(())
+    }
+
+    /// Watch a netplay session hosted at the given address (e.g. `192.168.1.2:7845`).
+    ///
+    /// The session is played back slightly delayed, and local input is ignored. The same ROM must be
+    /// loaded as the host.
+    pub fn spectate_netplay(&mut self, address: &str) -> Result<(), LocalizedError> {
+        self.netplay_input_delay()?;
+        let session = NetplaySession::spectate(address).map_err(|e| LocalizedError::new("netplay_connect_failed").arg("address", address).arg("error", e))?;
+        self.core.start_netplay(session);
+        Ok(())
+    }
+
+    /// End the current netplay session, if any.
+    pub fn stop_netplay(&mut self) {
+        self.core.stop_netplay();
+    }
+
+    /// Get the status of the current netplay session.
+    pub fn get_netplay_status(&self) -> NetplayStatus {
+        self.core.get_netplay_status()
+    }
+
+    /// Get the input delay to use for a new netplay session, or an error if one can't be started.
+    fn netplay_input_delay(&self) -> Result<u8, LocalizedError> {
+        let Some(rom) = self.get_current_rom_name() else {
+            return Err(LocalizedError::new("game_not_running"))
+        };
+        if self.core.is_playing_back() || self.recording_replay_file.is_some() {
+            return Err(LocalizedError::new("netplay_during_replay"))
+        }
+        Ok(self.get_rom_input_delay(rom))
+    }
+
+    /// Returns true if PokeAByte is enabled, false if not, or an error if there was an error starting it.
+    pub fn is_pokeabyte_enabled(&self) -> Result<bool, &LocalizedError> {
+        match self.pokeabyte_error.as_ref() {
+            Some(e) => Err(e),
+            None => Ok(self.settings.pokeabyte.enabled)
+        }
+    }
+
+    /// Set whether or not the Poke-A-Byte integration server is enabled.
+    pub fn set_pokeabyte_enabled(&mut self, enabled: bool) -> Result<(), &LocalizedError> {
+        self.settings.pokeabyte.enabled = enabled;
+        self.pokeabyte_error = None;
+
+        let result = match self.pokeabyte_transport() {
+            Ok(transport) => {
+                self.core.set_pokeabyte_transport(transport);
+                self.core.set_pokeabyte_enabled(enabled).map_err(|e| LocalizedError::new("pokeabyte_start_failed").arg("error", e))
+            },
+            Err(e) if enabled => Err(e),
+            Err(_) => self.core.set_pokeabyte_enabled(false).map_err(|e| LocalizedError::new("pokeabyte_start_failed").arg("error", e))
+        };
+        self.pokeabyte_address = result.as_ref().ok().cloned().flatten().map(|t| t.to_string().into());
+        self.push_event(FrontendEvent::PokeAByteStatus {
+            enabled: enabled && result.is_ok(),
+            address: self.pokeabyte_address.as_ref().map(|a| a.as_str().to_owned()),
+            error: result.as_ref().err().map(|e| e.format(&self.messages))
+        });
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(e) => {
+                self.pokeabyte_error = Some(e);
+                Err(self.pokeabyte_error.as_ref().expect("pokeabyte_error was just set earlier..."))
+            }
+        }
+    }
+
+    /// Get the address the Poke-A-Byte integration server is listening on, if it is enabled.
+    ///
+    /// If the configured UDP port was in use (e.g. by another instance), this is the fallback port
+    /// that was used, instead, so the client can be pointed at this instance.
+    pub fn get_pokeabyte_listen_address(&self) -> Option<&UTF8CString> {
+        self.pokeabyte_address.as_ref()
+    }
+
+    /// Get the transport used to listen for Poke-A-Byte and its address (empty for the default).
+    pub fn get_pokeabyte_transport(&self) -> (PokeAByteTransportKind, &UTF8CString) {
+        (self.settings.pokeabyte.transport, &self.settings.pokeabyte.address)
+    }
+
+    /// Set the transport used to listen for Poke-A-Byte and its address (empty for the default).
+    ///
+    /// If the integration server is enabled, it is restarted with the new transport.
+    pub fn set_pokeabyte_transport(&mut self, transport: PokeAByteTransportKind, address: &str) -> Result<(), &LocalizedError> {
+        self.settings.pokeabyte.transport = transport;
+        self.settings.pokeabyte.address = address.into();
+        if !self.settings.pokeabyte.enabled {
+            return Ok(())
+        }
+        self.set_pokeabyte_enabled(true)
+    }
+
+    fn pokeabyte_transport(&self) -> Result<PokeAByteTransport, LocalizedError> {
+        let address = self.settings.pokeabyte.address.as_str();
+        match self.settings.pokeabyte.transport {
+            PokeAByteTransportKind::Udp if address.is_empty() => Ok(PokeAByteTransport::default()),
+            PokeAByteTransportKind::Udp => address.parse()
+                .map(PokeAByteTransport::Udp)
+                .map_err(|_| LocalizedError::new("pokeabyte_invalid_address").arg("address", address)),
+            PokeAByteTransportKind::UnixSocket => Ok(PokeAByteTransport::UnixSocket(match address {
+                "" => self.user_dir.join("pokeabyte.sock"),
+                path => PathBuf::from(path)
+            })),
+            PokeAByteTransportKind::AbstractSocket => Ok(PokeAByteTransport::AbstractSocket(match address {
+                "" => "supershuckie-pokeabyte".to_owned(),
+                name => name.to_owned()
+            }))
+        }
+    }
+
+    /// Disconnect the current Poke-A-Byte client, if any.
+    ///
+    /// Only one client can be connected at a time, so this lets another client connect.
+    pub fn disconnect_pokeabyte_client(&mut self) {
+        self.core.disconnect_pokeabyte_client();
+    }
+
+    /// Returns true if Poke-A-Byte writes are rejected during replay playback, or false if they are
+    /// applied once playback ends.
+    pub fn is_rejecting_pokeabyte_writes_during_playback(&self) -> bool {
+        self.settings.pokeabyte.reject_writes_during_playback
+    }
+
+    /// Set whether Poke-A-Byte writes are rejected during replay playback (`true`) or applied once
+    /// playback ends (`false`).
+    pub fn set_rejecting_pokeabyte_writes_during_playback(&mut self, reject: bool) {
+        self.settings.pokeabyte.reject_writes_during_playback = reject;
+        self.core.set_pokeabyte_write_policy(self.pokeabyte_write_policy());
+    }
+
+    /// Returns true if failed RAM reads and writes pause emulation with an error instead of being
+    /// ignored.
+    #[inline]
+    pub fn is_strict_ram_access(&self) -> bool {
+        self.settings.pokeabyte.strict_ram_access
+    }
+
+    /// Set whether failed RAM reads and writes (e.g. from a bad Poke-A-Byte mapper or during replay
+    /// playback) pause emulation with an error instead of being ignored.
+    pub fn set_strict_ram_access(&mut self, strict: bool) {
+        self.settings.pokeabyte.strict_ram_access = strict;
+        self.core.set_strict_ram_access(strict);
+    }
+
+    /// Get how the number of frames Poke-A-Byte suggests skipping between reads is handled.
+    #[inline]
+    pub fn get_pokeabyte_frame_skip(&self) -> PokeAByteFrameSkip {
+        self.settings.pokeabyte.frame_skip
+    }
+
+    /// Get the number of frames skipped between Poke-A-Byte reads when using [`PokeAByteFrameSkip::Custom`].
+    #[inline]
+    pub fn get_pokeabyte_custom_frame_skip(&self) -> u32 {
+        self.settings.pokeabyte.custom_frame_skip
+    }
+
+    /// Set how the number of frames Poke-A-Byte suggests skipping between reads is handled.
+    ///
+    /// `custom_frame_skip` is only used with [`PokeAByteFrameSkip::Custom`].
+    pub fn set_pokeabyte_frame_skip(&mut self, frame_skip: PokeAByteFrameSkip, custom_frame_skip: u32) {
+        self.settings.pokeabyte.frame_skip = frame_skip;
+        self.settings.pokeabyte.custom_frame_skip = custom_frame_skip;
+        self.core.set_pokeabyte_frame_skip_policy(self.pokeabyte_frame_skip_policy());
+    }
+
+    fn pokeabyte_frame_skip_policy(&self) -> PokeAByteFrameSkipPolicy {
+        match self.settings.pokeabyte.frame_skip {
+            PokeAByteFrameSkip::Ignore => PokeAByteFrameSkipPolicy::Ignore,
+            PokeAByteFrameSkip::Honor => PokeAByteFrameSkipPolicy::Honor,
+            PokeAByteFrameSkip::Custom => PokeAByteFrameSkipPolicy::Custom(self.settings.pokeabyte.custom_frame_skip)
+        }
+    }
+
+    fn pokeabyte_write_policy(&self) -> PokeAByteWritePolicy {
+        if self.settings.pokeabyte.reject_writes_during_playback {
+            PokeAByteWritePolicy::Reject
+        }
+        else {
+            PokeAByteWritePolicy::Buffer
+        }
+    }
+
+    #[inline]
+    pub fn get_gbc_mode(&self) -> GameBoyMode {
+        self.settings.game_boy_settings.gbc_mode
+    }
+
+    #[inline]
+    pub fn set_gbc_mode(&mut self, mode: GameBoyMode) {
+        self.settings.game_boy_settings.gbc_mode = mode;
+        self.reload_game_boy_if_needed();
+    }
+
+    #[inline]
+    pub fn is_sgb_enabled(&self) -> bool {
+        self.settings.game_boy_settings.sgb
+    }
+
+    #[inline]
+    pub fn set_sgb_enabled(&mut self, enabled: bool) {
+        self.settings.game_boy_settings.sgb = enabled;
+        self.reload_game_boy_if_needed();
+    }
+
+    /// Describe which parts of the input the current core uses.
+    #[inline]
+    pub fn get_input_descriptor(&self) -> InputDescriptor {
+        self.core.get_input_descriptor()
+    }
+
+    /// Returns true if the control does anything for the running game.
+    ///
+    /// If no game is running, all controls are considered supported.
+    pub fn is_control_supported(&self, control: Control) -> bool {
+        if !self.is_game_running() {
+            return true
+        }
+        control.input_kind().is_none_or(|i| self.get_input_descriptor().contains(i))
+    }
+
+    /// Get the base directory used for the category, or `None` if the user directory is used.
+    #[inline]
+    pub fn get_storage_dir(&self, category: StorageCategory) -> Option<&UTF8CString> {
+        self.settings.storage.get_base_dir(category)
+    }
+
+    /// Set the base directory used for the category, or `None` to use the user directory.
+    ///
+    /// If a game is running, its folders are created in the new location right away.
+    pub fn set_storage_dir(&mut self, category: StorageCategory, dir: Option<UTF8CString>) -> Result<(), LocalizedError> {
+        if let Some(dir) = dir.as_ref() && !Path::new(dir.as_str()).is_absolute() {
+            return Err(LocalizedError::new("storage_dir_not_absolute").arg("path", dir))
+        }
+
+        let old_dir = self.settings.storage.get_base_dir(category).cloned();
+        self.settings.storage.set_base_dir(category, dir);
+
+        if let Some(rom) = self.get_current_rom_name_arc() && let Err(e) = self.create_userdata_for_rom(rom.as_str()) {
+            self.settings.storage.set_base_dir(category, old_dir);
+            return Err(e)
+        }
+
+        self.reset_storage_watch();
+        Ok(())
     }
 
-    /// Get the number of milliseconds elapsed.
+    /// Returns true if a Game Boy Printer is connected.
     #[inline]
-    pub fn get_elapsed_frames(&self) -> u32 {
-        self.core.get_elapsed_frames()
+    pub fn is_printer_connected(&self) -> bool {
+        self.settings.game_boy_settings.printer_connected
     }
 
-    /// Skip to the desired frame.
-    #[inline]
-    pub fn go_to_replay_frame(&mut self, frame: u32) {
-        self.core.go_to_replay_frame(frame);
+    /// Connect or disconnect a Game Boy Printer.
+    ///
+    /// Printed pages are saved as PNGs in the ROM's printer directory.
+    pub fn set_printer_connected(&mut self, connected: bool) {
+        self.settings.game_boy_settings.printer_connected = connected;
+        if self.is_game_running() {
+            self.core.set_printer_connected(connected);
+        }
     }
 
-    #[inline]
-    pub fn advance_playback_frames(&mut self, delta: i32) {
-        self.core.advance_playback_frames(delta)
+    /// Start or stop capturing bytes the running game sends over the serial port (e.g. test ROM
+    /// results), which can then be taken with [`SuperShuckieFrontend::take_serial_output`].
+    ///
+    /// This is not saved, but it stays set when another ROM is loaded. Returns `false` if the
+    /// running console does not support it or a printer is connected.
+    pub fn set_serial_capture_enabled(&mut self, enabled: bool) -> bool {
+        self.serial_capture = enabled;
+        if !enabled {
+            self.serial_output.clear();
+        }
+        !self.is_game_running() || self.core.set_serial_capture_enabled(enabled)
     }
 
-    /// Save the settings to disk.
-    #[inline]
-    pub fn write_settings(&self) {
-        // TODO: handle errors here?
-        let _ = std::fs::write(self.user_dir.join(SETTINGS_FILE), serde_json::to_string_pretty(&self.settings).expect("failed to serialize"));
+    /// Set the rate audio samples are generated at, in Hz, or 0 to stop generating them.
+    ///
+    /// Samples are passed to [`SuperShuckieFrontendCallbacks::push_audio`] every tick. This is not
+    /// saved, but it stays set when another ROM is loaded, so it should be set once the audio
+    /// output is ready. Returns `false` if the running console does not support audio.
+    ///
+    /// Any audio recording is stopped first, since its sample rate can't change.
+    pub fn set_audio_sample_rate(&mut self, sample_rate: u32) -> bool {
+        self.stop_recording_audio();
+        self.audio_sample_rate = sample_rate;
+        !self.is_game_running() || self.core.set_audio_sample_rate(sample_rate)
     }
 
-    fn before_unload_or_reload_rom(&mut self) {
-        self.reset_save_state_history();
-        self.stop_recording_replay();
-        self.pokeabyte_error = None;
+    /// Get the rate audio samples are generated at, in Hz, or 0 if disabled.
+    #[inline]
+    pub fn get_audio_sample_rate(&self) -> u32 {
+        self.audio_sample_rate
     }
 
-    /// Start recording a replay.
+    /// Start recording the game's audio to a WAV file in the ROM's screenshots directory.
     ///
-    /// If `name` is set, that name will be used.
+    /// If `name` is set, that name will be used. This records what is sent to the audio output, so
+    /// one has to be set up first (see [`SuperShuckieFrontend::set_audio_sample_rate`]). Recording
+    /// starts on the next frame, so starting a replay recording at the same time lines the two up.
     ///
-    /// Returns the name of the replay if started.
-    pub fn start_recording_replay(&mut self, name: Option<&str>) -> Result<UTF8CString, UTF8CString> {
+    /// Recording stops when the ROM is closed or the sample rate is changed.
+    ///
+    /// Returns the filename of the recording.
+    pub fn start_recording_audio(&mut self, name: Option<&str>) -> Result<UTF8CString, LocalizedError> {
         if !self.is_game_running() {
-            return Err("Game not running".into())
+            return Err(LocalizedError::new("game_not_running"))
         }
+        self.stop_recording_audio();
 
-        let current_rom_name = self.get_current_rom_name_arc().expect("no rom name when game is running in start_recording_replay");
-        let save_states_dir = self.get_replays_dir_for_rom(current_rom_name.as_str());
-
-        let (final_file, final_replay, final_replay_path) = self.load_file_or_make_generic(&save_states_dir, name, None, REPLAY_EXTENSION)?;
-        let (temp_file, _, temp_replay) = self.load_file_or_make_generic(&save_states_dir, name, Some("temp"), REPLAY_EXTENSION)?;
-
-        if self.settings.replay_settings.auto_pause_on_record {
-            self.set_paused(true);
+        if self.audio_sample_rate == 0 {
+            return Err(LocalizedError::new("audio_output_off"))
+        }
+        let sample_rate = self.core.get_audio_sample_rate();
+        if sample_rate == 0 {
+            return Err(LocalizedError::new("audio_unsupported"))
         }
 
-        self.core.start_recording_replay(PartialReplayRecordMetadata {
-            rom_name: current_rom_name.to_string(),
-            rom_filename: current_rom_name.to_string(),
-
-            settings: ReplayFileRecorderSettings {
-                minimum_uncompressed_bytes_per_blob: (self.settings.replay_settings.max_recording_blob_size_mb.get() as usize)
-                    .saturating_mul(1024)
-                    .saturating_mul(1024),
-                compression_level: self.settings.replay_settings.zstd_compression_level
-            },
-
-            // TODO: patches
-            patch_format: ReplayPatchFormat::Unpatched,
-            patch_target_checksum: ReplayHeaderBlake3Hash::default(),
-            patch_data: ByteVec::default(),
-
-            frames_per_keyframe: self.settings.replay_settings.frames_per_keyframe,
-
-            final_file,
-            temp_file,
-        });
+        let rom_name = self.get_current_rom_name_arc().expect("no rom name when game is running in start_recording_audio");
+        let screenshots_dir = self.get_screenshots_dir_for_rom(rom_name.as_str());
+        let filename = self.write_file_or_make_generic(&screenshots_dir, name, Some("audio"), AUDIO_EXTENSION, &wav::wav_header(sample_rate, 0))?;
 
-        self.recording_replay_file = Some(ReplayFileInfo {
-            final_replay_name: final_replay.clone().into(),
-            temp_replay_path: temp_replay,
-            final_replay_path
-        });
+        // Anything generated before now isn't part of the recording
+        self.core.drain_audio_samples(&mut Vec::new());
 
-        Ok(final_replay.into())
+        let path = filename.split('/').fold(screenshots_dir, |path, component| path.join(component));
+        self.audio_recording = Some(AudioRecording { path, data_size: 0, buffer: Vec::new() });
+        Ok(filename.into())
     }
 
-    /// Stop recording replay.
-    pub fn stop_recording_replay(&mut self) {
-        let Some(replay_file) = self.recording_replay_file.take() else {
+    /// Stop recording audio, if recording.
+    pub fn stop_recording_audio(&mut self) {
+        let Some(mut recording) = self.audio_recording.take() else {
             return
         };
 
-        // FIXME: We should make sure that it actually finalized here before deleting the temp file.
-        let zero_frames = self.core.get_elapsed_frames() == 0;
-
-        self.core.stop_recording_replay();
-        let _ = std::fs::remove_file(&replay_file.temp_replay_path);
+        // Pick up whatever was generated since the last tick
+        self.core.drain_audio_samples(&mut self.audio_samples);
+        wav::append_samples(&mut recording.buffer, &self.audio_samples);
+        self.audio_samples.clear();
 
-        if zero_frames {
-            let _ = std::fs::remove_file(&replay_file.final_replay_path);
+        if let Err(e) = recording.finish(self.files.as_ref()) {
+            self.show_error("audio_recording_failed", &[("error", &e.format(&self.messages))]);
         }
     }
 
-    /// Get all saves for the given ROM.
+    /// Returns true if audio is being recorded (see [`SuperShuckieFrontend::start_recording_audio`]).
     #[inline]
-    pub fn get_all_saves_for_rom(&self, rom: &str) -> Vec<UTF8CString> {
-        list_files_in_dir_with_extension(&self.get_save_data_dir_for_rom(rom), SAVE_DATA_EXTENSION)
+    pub fn is_recording_audio(&self) -> bool {
+        self.audio_recording.is_some()
     }
 
-    /// Get all save states for the given ROM.
-    #[inline]
-    pub fn get_all_save_states_for_rom(&self, rom: &str) -> Vec<UTF8CString> {
-        list_files_in_dir_with_extension(&self.get_save_states_dir_for_rom(rom), SAVE_STATE_EXTENSION)
+    /// Take up to `max_len` bytes sent over the serial port while capture was enabled.
+    ///
+    /// Any bytes past `max_len` are kept for the next call.
+    pub fn take_serial_output(&mut self, max_len: usize) -> Vec<u8> {
+        self.serial_output.extend(self.core.take_serial_output());
+        let len = max_len.min(self.serial_output.len());
+        self.serial_output.drain(..len).collect()
     }
 
-    /// Get all replays for the given ROM.
-    #[inline]
-    pub fn get_all_replays_for_rom(&self, rom: &str) -> Vec<UTF8CString> {
-        list_files_in_dir_with_extension(&self.get_replays_dir_for_rom(rom), REPLAY_EXTENSION)
-    }
+    /// Run the loaded ROM from power-on until it reports a test result over the serial port, or
+    /// until `max_frames` frames have run.
+    ///
+    /// The ROM is run as fast as possible on a scratch core, so the running game is not disturbed.
+    /// See [`grade_serial_output`](crate::serial_test::grade_serial_output) for which test ROMs
+    /// are understood.
+    pub fn run_serial_test(&mut self, max_frames: u32) -> Result<SerialTestResult, LocalizedError> {
+        if !self.is_game_running() {
+            return Err(LocalizedError::new("game_not_running"))
+        }
 
-    fn after_switch_core(&mut self) {
-        self.update_video_mode();
+        let emulator_type = self.core_metadata.emulator_type.expect("run_serial_test with no emulator type");
+        let rom_data = self.loaded_rom_data.as_ref().expect("run_serial_test with no loaded rom");
+        self.run_serial_test_rom(rom_data, emulator_type, max_frames).map(|(result, _)| result)
     }
 
-    fn update_video_mode(&mut self) {
-        self.core.read_screens(|screens| {
-            self.callbacks.change_video_mode(screens, self.settings.emulation.video_scale);
-        });
+    /// Run every test ROM in `directory` from power-on like [`SuperShuckieFrontend::run_serial_test`],
+    /// without loading any of them.
+    ///
+    /// If a test ROM has a file next to it with the [`EXPECTED_OUTPUT_EXTENSION`], its output is
+    /// compared against that instead of being graded by its verdict. ROMs that can't be run are
+    /// reported as failing rather than stopping the suite.
+    pub fn run_test_suite<P: AsRef<Path>>(&self, directory: P, max_frames: u32) -> Result<TestSuiteReport, LocalizedError> {
+        let directory = directory.as_ref();
+        let mut roms: Vec<PathBuf> = self.files.read_dir(directory)
+            .map_err(|e| LocalizedError::new("test_suite_read_failed").arg("path", directory.display()).arg("error", e))?
+            .into_iter()
+            .filter(|p| p.extension().and_then(|e| e.to_str()).is_some_and(is_supported_rom_extension))
+            .collect();
+        roms.sort();
+
+        let mut report = TestSuiteReport {
+            max_frames,
+            ..Default::default()
+        };
+
+        for rom in roms {
+            let expected_path = rom.with_extension(EXPECTED_OUTPUT_EXTENSION);
+            let expected_output = self.files.is_file(&expected_path).then(|| self.files.read(&expected_path))
+                .transpose()
+                .map(|e| e.map(|e| String::from_utf8_lossy(&e).into_owned()));
+
+            let result = self.files.read(&rom)
+                .map_err(|e| LocalizedError::new("test_rom_read_failed").arg("error", e))
+                .and_then(|data| {
+                    let emulator_type = self.choose_for_game_boy(&data);
+                    self.run_serial_test_rom(&data, emulator_type, max_frames)
+                });
+
+            let mut entry = TestSuiteEntry {
+                rom: rom.file_name().unwrap_or_default().to_string_lossy().into_owned(),
+                passed: false,
+                verdict: None,
+                frames: 0,
+                output: String::new(),
+                expected_output: None,
+                error: None
+            };
+
+            match (result, expected_output) {
+                (Ok((result, core_name)), Ok(expected_output)) => {
+                    let output = String::from_utf8_lossy(&result.output).into_owned();
+                    entry.passed = match expected_output.as_ref() {
+                        Some(expected) => output.trim_end() == expected.trim_end(),
+                        None => result.verdict == SerialTestVerdict::Passed
+                    };
+                    entry.verdict = Some(result.verdict);
+                    entry.frames = result.frames;
+                    entry.output = output;
+                    entry.expected_output = expected_output;
+                    report.core_name = core_name.to_owned();
+                },
+                (Err(e), _) => entry.error = Some(e.format(&self.messages)),
+                (_, Err(e)) => entry.error = Some(LocalizedError::new("expected_output_read_failed").arg("error", e).format(&self.messages))
+            }
+
+            report.entries.push(entry);
+        }
+
+        Ok(report)
     }
 
-    fn after_load_rom(&mut self) {
-        self.force_refresh_screens();
-        self.current_input = Input::default();
-        self.core.set_speed(Speed::from_multiplier_float(self.settings.emulation.base_speed_multiplier));
-        if self.settings.pokeabyte.enabled {
-            let _ = self.set_pokeabyte_enabled(true);
+    /// Run `rom_data` from power-on on a scratch core, returning the result and the core's name.
+    fn run_serial_test_rom(&self, rom_data: &[u8], emulator_type: SuperShuckieEmulatorType, max_frames: u32) -> Result<(SerialTestResult, &'static str), LocalizedError> {
+        let mut core = SuperShuckieCore::new(self.make_new_core(rom_data, None, emulator_type), Box::new(EmulatedTimestampProvider::new()));
+        if !core.set_serial_capture_enabled(true) {
+            return Err(LocalizedError::new("serial_capture_unsupported"))
         }
-        if !self.paused {
-            self.core.start();
+
+        let mut output = Vec::new();
+        let mut verdict = SerialTestVerdict::TimedOut;
+        while core.get_elapsed_frames() < max_frames as UnsignedInteger {
+            core.run_unlocked();
+
+            let new_output = core.take_serial_output();
+            if new_output.is_empty() {
+                continue
+            }
+            output.extend(new_output);
+            if let Some(v) = grade_serial_output(&output) {
+                verdict = v;
+                break
+            }
         }
+
+        let result = SerialTestResult {
+            verdict,
+            frames: core.get_elapsed_frames().min(u32::MAX as UnsignedInteger) as u32,
+            output
+        };
+        Ok((result, core.get_core().core_name()))
     }
 
+    /// Get the color correction mode.
     #[inline]
-    fn reset_speed(&mut self) {
-        self.apply_turbo(0.0);
+    pub fn get_color_correction(&self) -> ColorCorrection {
+        self.settings.emulation.color_correction
     }
 
-    fn apply_turbo(&mut self, turbo: f64) {
-        let base_speed = self.settings.emulation.base_speed_multiplier;
-        let max_speed = self.settings.emulation.turbo_speed_multiplier * base_speed;
-        let total_speed = base_speed + (max_speed - base_speed) * turbo;
-        self.core.set_speed(Speed::from_multiplier_float(total_speed));
+    /// Set the color correction mode, applying it to the running game right away.
+    pub fn set_color_correction(&mut self, color_correction: ColorCorrection) {
+        self.settings.emulation.color_correction = color_correction;
+        self.apply_color_correction();
     }
 
+    /// Get the ambient light temperature, from -1.0 (warm red) to 1.0 (cool blue).
     #[inline]
-    /// Get the replay file info, or `None` if not recording.
-    pub fn get_replay_file_info(&self) -> Option<&ReplayFileInfo> {
-        self.recording_replay_file.as_ref()
+    pub fn get_light_temperature(&self) -> f64 {
+        self.settings.emulation.light_temperature
     }
 
-    /// Returns true if PokeAByte is enabled, false if not, or an error if there was an error starting it.
-    pub fn is_pokeabyte_enabled(&self) -> Result<bool, &UTF8CString> {
-        match self.pokeabyte_error.as_ref() {
-            Some(e) => Err(e),
-            None => Ok(self.settings.pokeabyte.enabled)
-        }
+    /// Set the ambient light temperature, from -1.0 (warm red) to 1.0 (cool blue).
+    ///
+    /// Values outside this range are clamped.
+    pub fn set_light_temperature(&mut self, light_temperature: f64) {
+        self.settings.emulation.light_temperature = if light_temperature.is_nan() { 0.0 } else { light_temperature.clamp(-1.0, 1.0) };
+        self.apply_color_correction();
     }
 
-    /// Set whether or not the Poke-A-Byte integration server is enabled.
-    pub fn set_pokeabyte_enabled(&mut self, enabled: bool) -> Result<(), &UTF8CString> {
-        self.settings.pokeabyte.enabled = enabled;
-        self.pokeabyte_error = None;
-        match self.core.set_pokeabyte_enabled(enabled) {
-            Ok(_) => Ok(()),
-            Err(e) => {
-                self.pokeabyte_error = Some(e.into());
-                Err(self.pokeabyte_error.as_ref().expect("pokeabyte_error was just set earlier..."))
-            }
+    /// Get all options of the running core and their current values.
+    ///
+    /// Returns an empty list if no game is running.
+    pub fn get_core_options(&self) -> Vec<CoreOption> {
+        if !self.is_game_running() {
+            return Vec::new()
         }
+        self.core.list_core_options()
     }
 
-    #[inline]
-    pub fn get_gbc_mode(&self) -> GameBoyMode {
-        self.settings.game_boy_settings.gbc_mode
-    }
-
-    #[inline]
-    pub fn set_gbc_mode(&mut self, mode: GameBoyMode) {
-        self.settings.game_boy_settings.gbc_mode = mode;
-        self.reload_game_boy_if_needed();
+    /// Set an option of the running core, saving it so it is applied to future games.
+    pub fn set_core_option(&mut self, key: &str, value: &str) -> Result<(), LocalizedError> {
+        if !self.is_game_running() {
+            return Err(LocalizedError::new("game_not_running"))
+        }
+        self.core.set_core_option(key.to_owned(), value.to_owned()).map_err(|e| LocalizedError::new("core_option_failed").arg("key", key).arg("error", e))?;
+        self.settings.emulation.core_options.insert(key.to_owned(), value.to_owned());
+        Ok(())
     }
 
-    #[inline]
-    pub fn is_sgb_enabled(&self) -> bool {
-        self.settings.game_boy_settings.sgb
+    fn core_color_correction(&self) -> supershuckie_core::emulator::ColorCorrection {
+        supershuckie_core::emulator::ColorCorrection {
+            mode: ColorCorrectionMode::from(self.settings.emulation.color_correction),
+            light_temperature: self.settings.emulation.light_temperature
+        }
     }
 
-    #[inline]
-    pub fn set_sgb_enabled(&mut self, enabled: bool) {
-        self.settings.game_boy_settings.sgb = enabled;
-        self.reload_game_boy_if_needed();
+    fn apply_color_correction(&mut self) {
+        if self.is_game_running() {
+            self.core.set_color_correction(self.core_color_correction());
+        }
     }
 
     fn reload_game_boy_if_needed(&mut self) {
@@ -1057,19 +4594,145 @@ impl SuperShuckieFrontend {
     }
 }
 
-fn list_files_in_dir_with_extension(dir: &Path, extension: &str) -> Vec<UTF8CString> {
-    let Ok(n) = std::fs::read_dir(dir) else {
-        return Vec::new()
-    };
+/// Audio being recorded with [`SuperShuckieFrontend::start_recording_audio`].
+struct AudioRecording {
+    path: PathBuf,
+
+    /// Bytes of samples written so far
+    data_size: u32,
+
+    /// Samples not written yet
+    buffer: Vec<u8>
+}
+
+impl AudioRecording {
+    /// Write out the buffered samples.
+    fn write_buffer(&mut self, files: &dyn FileStore) -> Result<(), LocalizedError> {
+        let data_size = u32::try_from(self.buffer.len()).ok()
+            .and_then(|n| self.data_size.checked_add(n))
+            .filter(|n| wav::riff_size(*n) < u32::MAX)
+            .ok_or_else(|| LocalizedError::new("audio_recording_too_large"))?;
+
+        files.write_at(&self.path, wav::WAV_HEADER_SIZE as u64 + self.data_size as u64, &self.buffer)
+            .map_err(|e| LocalizedError::new("file_write_failed").arg("path", self.path.display()).arg("error", e))?;
+        self.data_size = data_size;
+        self.buffer.clear();
+        Ok(())
+    }
+
+    /// Write out the buffered samples and fill in the header.
+    fn finish(mut self, files: &dyn FileStore) -> Result<(), LocalizedError> {
+        self.write_buffer(files)?;
+        files.write_at(&self.path, wav::WAV_RIFF_SIZE_OFFSET, &wav::riff_size(self.data_size).to_le_bytes())
+            .and_then(|_| files.write_at(&self.path, wav::WAV_DATA_SIZE_OFFSET, &self.data_size.to_le_bytes()))
+            .map_err(|e| LocalizedError::new("file_write_failed").arg("path", self.path.display()).arg("error", e))
+    }
+}
+
+impl Drop for SuperShuckieFrontend {
+    fn drop(&mut self) {
+        self.end_instance_listener();
+        self.stop_recording_audio();
+    }
+}
+
+/// Mix each screen with the previous one, then replace the previous screens with the unmixed ones.
+fn blend_frames(previous_screens: &mut Vec<ScreenData>, screens: &mut [ScreenData]) {
+    let same_dimensions = previous_screens.len() == screens.len()
+        && previous_screens.iter().zip(screens.iter()).all(|(previous, screen)| previous.pixels.len() == screen.pixels.len());
+
+    if !same_dimensions {
+        *previous_screens = screens.to_vec();
+        return
+    }
+
+    for (screen, previous) in screens.iter_mut().zip(previous_screens.iter_mut()) {
+        for (pixel, previous_pixel) in screen.pixels.iter_mut().zip(previous.pixels.iter_mut()) {
+            let current_pixel = *pixel;
+            // Average each 8-bit channel without carrying into the next one
+            *pixel = (((current_pixel ^ *previous_pixel) & 0xFEFEFEFE) >> 1) + (current_pixel & *previous_pixel);
+            *previous_pixel = current_pixel;
+        }
+    }
+}
+
+/// Returns true if `extension` (without the dot) is a type of ROM that can be loaded.
+fn is_supported_rom_extension(extension: &str) -> bool {
+    matches!(extension.to_lowercase().as_str(), "gb" | "gbc")
+}
+
+fn parse_rom_archive<'a>(filename: &str, data: &'a [u8]) -> Result<ZipArchive<'a>, LocalizedError> {
+    ZipArchive::parse(data).map_err(|e| LocalizedError::new("archive_unreadable").arg("filename", filename).arg("error", e))
+}
+
+/// Get all files in `archive` that look like supported ROMs.
+fn archive_rom_entries<'a>(archive: &'a ZipArchive) -> Vec<&'a ZipEntry> {
+    archive.entries()
+        .iter()
+        .filter(|e| !e.is_directory())
+        .filter(|e| Path::new(e.filename()).extension().and_then(|e| e.to_str()).is_some_and(is_supported_rom_extension))
+        .collect()
+}
+
+fn read_replay_file(files: &dyn FileStore, path: &Path, name: &str, override_errors: bool) -> Result<ReplayFilePlayer, LocalizedError> {
+    let file = files.read(path).map_err(|e| LocalizedError::new("replay_read_failed").arg("name", name).arg("error", e))?;
+    ReplayFilePlayer::new(file, override_errors).map_err(|e| LocalizedError::new("replay_parse_failed").arg("name", name).arg("error", format!("{e:?}")))
+}
+
+/// Get the error for an operation that stopped early, which is "Cancelled" if `cancel` was cancelled.
+fn cancelled_or(cancel: Option<&CancellationToken>, error: LocalizedError) -> LocalizedError {
+    if cancel.is_some_and(CancellationToken::is_cancelled) { LocalizedError::new("cancelled") } else { error }
+}
 
+fn describe_replay_attach_error(error: ReplayPlayerAttachError) -> LocalizedError {
+    match error {
+        ReplayPlayerAttachError::Incompatible { description } => {
+            LocalizedError::new("replay_incompatible").arg("description", description)
+        }
+        ReplayPlayerAttachError::MismatchedMetadata { issues } => {
+            describe_replay_mismatches(issues.iter().map(|i| i.to_string()))
+        }
+    }
+}
+
+fn describe_replay_mismatches<S: AsRef<str>>(issues: impl Iterator<Item = S>) -> LocalizedError {
+    let issues: Vec<S> = issues.collect();
+    let issues: Vec<&str> = issues.iter().map(|i| i.as_ref()).collect();
+    LocalizedError::new("replay_mismatched").arg("issues", issues.join("\n\n"))
+}
+
+fn list_files_in_dir_with_extension(files: &dyn FileStore, dir: &Path, extension: &str) -> Vec<UTF8CString> {
+    let mut options = Vec::new();
+    collect_files_in_dir_with_extension(files, dir, extension, None, &mut options);
+    options
+}
+
+/// Like [`list_files_in_dir_with_extension`], but also lists files in subfolders, prefixed with the
+/// subfolder (see [`sanitize_user_name`]).
+fn list_named_files_in_dir_with_extension(files: &dyn FileStore, dir: &Path, extension: &str) -> Vec<UTF8CString> {
     let mut options = Vec::new();
-    for item in n {
-        let Ok(item) = item else { continue };
-        let path = item.path();
+    collect_files_in_dir_with_extension(files, dir, extension, Some(""), &mut options);
+    options
+}
+
+/// If `prefix` is set, subfolders are also searched, and their names are prepended to `prefix`.
+fn collect_files_in_dir_with_extension(files: &dyn FileStore, dir: &Path, extension: &str, prefix: Option<&str>, options: &mut Vec<UTF8CString>) {
+    let Ok(n) = files.read_dir(dir) else {
+        return
+    };
+
+    for path in n {
+        if let Some(prefix) = prefix && files.is_dir(&path) {
+            let Some(folder_name) = path.file_name().and_then(|i| i.to_str()) else {
+                continue
+            };
+            collect_files_in_dir_with_extension(files, &path, extension, Some(&format!("{prefix}{folder_name}/")), options);
+            continue
+        }
         if path.extension() != Some(extension.as_ref()) {
             continue
         }
-        if !path.is_file() {
+        if !files.is_file(&path) {
             continue
         }
         let Some(stem) = path.file_stem() else {
@@ -1078,10 +4741,73 @@ fn list_files_in_dir_with_extension(dir: &Path, extension: &str) -> Vec<UTF8CStr
         let Some(stem_utf8) = stem.to_str() else {
             continue
         };
-        options.push(stem_utf8.into());
+        options.push(format!("{}{stem_utf8}", prefix.unwrap_or_default()).into());
     }
+}
 
-    options
+/// Get the filename and path of a file with a user-provided name, validating the name first.
+///
+/// See [`sanitize_user_name`].
+fn get_named_file_path(dir: &Path, name: &str, extension: &str) -> Result<(String, PathBuf), LocalizedError> {
+    let filename = format!("{}.{extension}", sanitize_user_name(name)?);
+    let path = filename.split('/').fold(dir.to_owned(), |path, component| path.join(component));
+    Ok((filename, path))
+}
+
+/// Like [`get_named_file_path`], but if the file already exists, a timestamp is appended to the
+/// name so nothing is overwritten.
+///
+/// Returns the name that was used and its path.
+fn get_unused_named_file_path(files: &dyn FileStore, dir: &Path, name: &str, extension: &str) -> Result<(String, PathBuf), LocalizedError> {
+    let (_, path) = get_named_file_path(dir, name, extension)?;
+    if !files.exists(&path) {
+        return Ok((name.to_owned(), path))
+    }
+
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map(|i| i.as_secs()).unwrap_or_default();
+    let name = format!("{name}_{}", format_filename_timestamp(timestamp));
+    let (_, path) = get_named_file_path(dir, &name, extension)?;
+    Ok((name, path))
+}
+
+/// Convert screens to `encoding` if they aren't already, using `buffer` to hold the converted screens.
+fn encode_screens<'a>(screens: &'a [ScreenData], encoding: ScreenDataEncoding, buffer: &'a mut Vec<ScreenData>) -> &'a [ScreenData] {
+    if screens.iter().all(|s| s.encoding == encoding) {
+        return screens
+    }
+
+    buffer.resize_with(screens.len(), ScreenData::default);
+    for (screen, encoded) in screens.iter().zip(buffer.iter_mut()) {
+        screen.convert_into(encoding, encoded);
+    }
+    buffer
+}
+
+/// Validate the name of a save file, which can't be namespaced into subfolders.
+fn sanitize_save_profile_name(name: &str) -> Result<String, LocalizedError> {
+    let name = sanitize_user_name(name)?;
+    if name.contains('/') {
+        return Err(LocalizedError::new("save_profile_name_has_slash").arg("name", name))
+    }
+    Ok(name)
+}
+
+/// Result of [`SuperShuckieFrontend::migrate_user_dirs`].
+#[derive(Default)]
+pub struct UserDirMigration {
+    /// ROMs whose folders were moved
+    pub moved: Vec<UTF8CString>,
+
+    /// Why each ROM or file that could not be checksummed or moved was skipped
+    pub skipped: Vec<UTF8CString>
+}
+
+/// A save state that was moved to quarantine because it failed to load.
+pub struct QuarantinedSaveState {
+    pub name: UTF8CString,
+
+    /// Why it failed to load
+    pub reason: UTF8CString
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -1094,6 +4820,31 @@ pub struct CoreMetadata {
     pub emulator_type: Option<SuperShuckieEmulatorType>
 }
 
+/// A metadata mismatch between a replay and what is loaded.
+pub struct ReplayMismatch {
+    /// Kind of mismatch
+    pub kind: ReplayMismatchKind,
+
+    /// Checksum or core name that is loaded
+    pub loaded: UTF8CString,
+
+    /// Human-readable description, including what the replay expected
+    pub description: UTF8CString
+}
+
+impl From<ReplayPlayerMetadataMismatchKind> for ReplayMismatch {
+    fn from(value: ReplayPlayerMetadataMismatchKind) -> Self {
+        let description = value.to_string().into();
+        let (kind, loaded) = match value {
+            ReplayPlayerMetadataMismatchKind::ROMChecksumMismatch { loaded, .. } => (ReplayMismatchKind::ROMChecksum, blake3_hash_to_ascii(loaded)),
+            ReplayPlayerMetadataMismatchKind::BIOSChecksumMismatch { loaded, .. } => (ReplayMismatchKind::BIOSChecksum, blake3_hash_to_ascii(loaded)),
+            ReplayPlayerMetadataMismatchKind::CoreMismatch { loaded, .. } => (ReplayMismatchKind::Core, loaded),
+            ReplayPlayerMetadataMismatchKind::SRAMChecksumMismatch { loaded, .. } => (ReplayMismatchKind::SRAMChecksum, blake3_hash_to_ascii(loaded))
+        };
+        Self { kind, loaded: loaded.into(), description }
+    }
+}
+
 /// Info of the replay file.
 pub struct ReplayFileInfo {
     /// Name of the replay file being made
@@ -1103,12 +4854,82 @@ pub struct ReplayFileInfo {
     pub final_replay_path: PathBuf,
 
     /// Path to the temp file being recorded
-    pub temp_replay_path: PathBuf
+    pub temp_replay_path: PathBuf,
+
+    /// Bookmarks added so far
+    pub bookmarks: Vec<ReplayMarker>
 }
 
 pub trait SuperShuckieFrontendCallbacks {
     fn refresh_screens(&mut self, screens: &[ScreenData]);
     fn change_video_mode(&mut self, screens: &[ScreenData], screen_scaling: NonZeroU8);
+    fn keycode_for_physical_key(&mut self, key: PhysicalKey) -> Option<i32>;
+    fn show_message(&mut self, message: &str);
+    fn set_rumble(&mut self, amplitude: f64);
+    fn set_infrared_led(&mut self, on: bool);
+    fn report_progress(&mut self, event: CoreProgressEvent);
+
+    /// Play audio samples, which are signed 16-bit stereo interleaved left then right (see
+    /// [`SuperShuckieFrontend::set_audio_sample_rate`]).
+    fn push_audio(&mut self, samples: &[i16], sample_rate: u32);
+
+    /// A controller button meant for navigating the UI was pressed or released (see
+    /// [`SuperShuckieFrontend::set_ui_navigation_captured`]).
+    fn ui_navigation(&mut self, controller: ConnectedControllerIndex, button: i32, pressed: bool);
+}
+
+/// Something that happened in the frontend.
+///
+/// This is for frontends that would rather poll for events than handle callbacks, as well as for
+/// integrations that want to know when things happen. See [`SuperShuckieFrontend::poll_event`] and
+/// [`SuperShuckieFrontend::add_event_hook`].
+#[derive(Clone, PartialEq, Debug)]
+pub enum FrontendEvent {
+    /// A new frame is ready to be read with [`SuperShuckieFrontend::read_screens`]
+    FrameReady { frame: u32 },
+
+    /// Something failed
+    ///
+    /// `key` identifies the message in the [`locale::MessageCatalog`], and `message` is it formatted
+    /// in the current language.
+    Error { key: String, message: String },
+
+    /// The replay being played back reached its last frame
+    PlaybackFinished,
+
+    /// The Poke-A-Byte integration server was enabled or disabled, failing if `error` is set
+    ///
+    /// If enabled, `address` is what it is listening on (which may be a fallback port).
+    PokeAByteStatus { enabled: bool, address: Option<String>, error: Option<String> },
+
+    /// A message to briefly show to the user
+    ///
+    /// `key` identifies the message in the [`locale::MessageCatalog`], and `message` is it formatted
+    /// in the current language.
+    Message { key: String, message: String },
+
+    /// A ROM was loaded
+    RomLoaded { rom_name: String },
+
+    /// The console was reset
+    Reset { hard: bool },
+
+    /// A save state was loaded, or `name` is `None` if loading a save state was undone or redone
+    StateLoaded { name: Option<String> },
+
+    /// A replay started recording or playing back
+    ReplayStarted { name: String, recording: bool },
+
+    /// A replay stopped recording or playing back
+    ReplayEnded { recording: bool },
+
+    /// The save data was written to disk
+    SramSaved,
+
+    /// A file was added to or removed from one of the current ROM's folders
+    ///
+    /// Only emitted when enabled with [`SuperShuckieFrontend::set_watch_storage_setting`].
+    StorageChanged { category: StorageCategory, name: String, added: bool }
 }
 
 fn _ensure_callbacks_are_object_safe(_: Box<dyn SuperShuckieFrontendCallbacks>) {}
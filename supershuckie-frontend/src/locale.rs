@@ -0,0 +1,299 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use crate::file_store::FileStore;
+use crate::util::UTF8CString;
+
+/// Language built into the frontend, which every other language falls back to for missing messages.
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+/// Folder in the user directory with a `<language>.json` file per language, each an object mapping
+/// message keys to templates.
+pub const LOCALE_FOLDER: &str = "locale";
+
+/// Templates for every message key in the default language.
+///
+/// Templates can refer to arguments by name in braces (e.g. `{error}`).
+const DEFAULT_MESSAGES: &[(&str, &str)] = &[
+    ("archive_extract_failed", "Failed to extract a ROM from {filename}: {error}"),
+    ("archive_has_multiple_roms", "{filename} contains multiple ROMs, so one has to be chosen: {roms}"),
+    ("archive_has_no_roms", "{filename} does not contain any supported ROMs"),
+    ("archive_read_failed", "Failed to read archive at {filename}: {error}"),
+    ("archive_rom_not_found", "{filename} does not contain a ROM named {entry}"),
+    ("archive_unreadable", "Can't read {filename}: {error}"),
+    ("audio_output_off", "Audio output is off"),
+    ("audio_recording_failed", "Audio recording stopped: {error}"),
+    ("audio_recording_too_large", "The recording is too large for a WAV file"),
+    ("audio_unsupported", "The running console does not support audio"),
+    ("autosnap_delete_failed", "Can't delete old autosnap {name}: {error}"),
+    ("autosnap_failed", "Failed to create autosnap: {error}"),
+    ("cancelled", "Cancelled"),
+    ("cheat_already_added", "{code} was already added"),
+    ("cheat_invalid", "{code}: {error}"),
+    ("cheat_not_found", "{rom} has no cheat #{index}"),
+    ("cheats_failed", "Can't apply cheats: {error}\n\nNo cheats are active."),
+    ("cheats_rejected", "{error}"),
+    ("clip_ends_before_start", "The clip must end after it starts"),
+    ("clip_no_frames", "No frames were rendered"),
+    ("clip_no_screen", "There is no screen to record"),
+    ("clip_scale_too_large", "Clips can't be scaled more than {max_scale}x"),
+    ("clip_too_large", "The clip is too large"),
+    ("clip_too_long", "Clips can't be longer than {max_frames} frames"),
+    ("compression_benchmark_failed", "Failed to benchmark compression: {error}"),
+    ("control_profile_export_failed", "Can't export the control profile: {error}"),
+    ("control_profile_invalid", "Can't read the control profile: {error}"),
+    ("core_hang", "{core} stopped responding on frame {frame} ({ticks} ticks over {seconds} seconds without finishing a frame), so emulation was paused"),
+    ("core_option_failed", "Can't set {key}: {error}"),
+    ("current_state_load_failed", "Failed to load the current state: {error}"),
+    ("expected_output_read_failed", "Failed to read expected output: {error}"),
+    ("file_read_failed", "Can't read {path}: {error}"),
+    ("file_write_failed", "Can't write to {path}: {error}"),
+    ("folder_create_failed", "Can't create {path}: {error}"),
+    ("forwarded_rom_failed", "\"{path}\" failed to load:\n\n{error}"),
+    ("game_bundle_invalid", "Can't read the game bundle: {error}"),
+    ("game_bundle_wrong_named_rom", "This game bundle is for a different ROM ({rom})"),
+    ("game_bundle_wrong_rom", "This game bundle is for a different ROM"),
+    ("game_not_running", "Game not running"),
+    ("game_profile_invalid", "Can't load game profile {error}"),
+    ("game_profile_loaded", "Loaded game profile {name}"),
+    ("game_profile_rejected", "Can't add game profile: {error}"),
+    ("instance_listen_failed", "Can't open ROMs from other instances: {error}"),
+    ("keyboard_controls_dropped", "{count} keyboard control(s) were removed because they were bound to what is now the same key. Check your controls."),
+    ("language_invalid", "{language} is not a valid language name"),
+    ("locale_parse_failed", "Can't parse {path}: {error}"),
+    ("name_dot_folder", "Name \"{name}\" cannot contain \".\" or \"..\" as a folder name."),
+    ("name_empty", "Name cannot be empty."),
+    ("name_empty_folder", "Name \"{name}\" has an empty folder name (leading, trailing, or doubled slash)."),
+    ("name_invalid_character", "Name \"{name}\" contains a character that is not allowed ({character})."),
+    ("name_invalid_spacing", "Name \"{name}\" cannot have a part that starts with a space or ends with a space or period."),
+    ("name_reserved", "Name \"{name}\" uses a reserved name ({reserved})."),
+    ("named_file_open_failed", "Can't open {name} for writing: {error}"),
+    ("named_folder_create_failed", "Can't create the folder for {name}: {error}"),
+    ("netplay_connect_failed", "Can't connect to {address}: {error}"),
+    ("netplay_during_replay", "Netplay can't be used while a replay is being recorded or played back"),
+    ("netplay_listen_failed", "Can't listen on port {port}: {error}"),
+    ("no_compression_levels", "No compression levels to benchmark"),
+    ("no_screens", "No screens to save"),
+    ("onion_skin_disabled", "Onion skin disabled"),
+    ("onion_skin_enabled", "Onion skin enabled ({frames} frames)"),
+    ("pokeabyte_invalid_address", "Invalid address {address} (IPv6 addresses must be in brackets, e.g. [::1]:55356)"),
+    ("pokeabyte_start_failed", "Can't start Poke-A-Byte: {error}"),
+    ("pokeabyte_transport_unknown", "Unknown transport"),
+    ("printed_page_failed", "Failed to save printed page: {error}"),
+    ("printed_page_saved", "Printed page saved as {filename}"),
+    ("quarantined_save_state_not_found", "Quarantined save state {name} does not exist"),
+    ("ram_access_failed", "{error}\n\nEmulation was paused because strict RAM access is enabled."),
+    ("replay_desync_detected", "{error}\n\nThe replay being recorded may not play back correctly from frame {frame} onward."),
+    ("replay_incompatible", "This replay file is incompatible:\n\n{description}"),
+    ("replay_mismatched", "This replay file has mismatched data which may prevent playback:\n\n{issues}"),
+    ("replay_not_found", "Replay {name} does not exist"),
+    ("replay_parse_failed", "Failed to parse replay {name}:\n\n{error}"),
+    ("replay_read_failed", "Failed to read replay {name}:\n\n{error}"),
+    ("replay_scrub_failed", "Can't scrub replay {name}: {error}"),
+    ("replay_stalled", "Replay {name} stalled at frame {frame} of {total_frames}"),
+    ("replay_still_recording", "Replay {name} is still being recorded"),
+    ("replay_too_short", "Replay {name} only has {total_frames} frames"),
+    ("rom_dirs_not_moved", "{error}\n\nThe game was not loaded, since its existing save data would not be found."),
+    ("rom_has_no_controls", "{rom} does not have its own controls"),
+    ("rom_missing_extension", "{filename} does not appear to be a valid ROM file (missing extension)"),
+    ("rom_missing_filename", "{path} does not appear to be a valid ROM file (missing filename)"),
+    ("rom_read_failed", "Failed to read ROM at {filename}: {error}"),
+    ("rom_type_unsupported", "Unknown or unsupported ROM file type .{extension}"),
+    ("save_backup_delete_failed", "Can't delete old save data backup {backup}: {error}"),
+    ("save_backup_failed", "Failed to back up save data: {error}"),
+    ("save_delete_failed", "Failed to delete save data: {error}"),
+    ("save_backup_read_failed", "Can't read save data backup {backup}: {error}"),
+    ("save_backup_write_failed", "Can't back up save data: {error}"),
+    ("save_profile_copy_failed", "Can't copy save file {name}: {error}"),
+    ("save_profile_delete_failed", "Can't delete save file {name}: {error}"),
+    ("save_profile_exists", "Save file {name} already exists"),
+    ("save_profile_is_current", "The current save file can't be deleted"),
+    ("save_profile_name_has_slash", "Save file name \"{name}\" can't contain slashes."),
+    ("save_profile_not_found", "Save file {name} does not exist"),
+    ("save_profile_rename_failed", "Can't rename save file {name}: {error}"),
+    ("save_profile_switched", "Switched to save file {name}"),
+    ("save_state_create_failed", "Failed to create a save state"),
+    ("save_state_load_failed", "Failed to load save state {name}: {error}"),
+    ("save_state_move_failed", "Can't move it to {path}: {error}"),
+    ("save_state_quarantine_failed", "Failed to load save state {name}: {error}\n\nIt could not be quarantined: {quarantine_error}"),
+    ("save_state_quarantined", "Save state {name} failed to load, so it was moved to quarantine as {quarantined_name}:\n\n{error}"),
+    ("save_state_restore_failed", "Save state {name} loaded, but it can't be restored to {path}: {error}"),
+    ("save_state_restore_move_failed", "Save state {name} loaded, but it can't be moved to {path}: {error}"),
+    ("save_state_still_fails", "Save state {name} still failed to load:\n\n{error}"),
+    ("screenshot_scale_too_large", "Screenshots can't be scaled more than {max_scale}x"),
+    ("serial_capture_unsupported", "This console does not support serial output capture"),
+    ("soft_reset_unsupported", "Soft reset is not supported for this console"),
+    ("speed_changed", "Speed: {speed}x"),
+    ("speed_not_a_number", "Speed is not a number"),
+    ("sram_sandboxed", "Save data from replay playback is not saved. Save as a new game to keep it."),
+    ("sram_write_failed", "Failed to write SRAM to disk: {error}"),
+    ("storage_category_unknown", "Unknown storage category {category}"),
+    ("storage_dir_not_absolute", "{path} is not an absolute path"),
+    ("test_report_invalid", "{error}"),
+    ("test_report_read_failed", "Failed to read previous report {path}: {error}"),
+    ("test_report_write_failed", "Failed to write report {path}: {error}"),
+    ("test_rom_read_failed", "Failed to read test ROM: {error}"),
+    ("test_suite_read_failed", "Failed to read test suite directory {path}: {error}"),
+    ("too_many_generic_names", "Maximum number of generics reached."),
+    ("user_dir_create_failed", "Failed to create userdata dir for {path}: {error}"),
+    ("user_dir_exists", "Can't move {from} to {to} because it already exists"),
+    ("user_dir_move_failed", "Failed to move {from} to {to}: {error}"),
+    ("user_dirs_move_while_recording", "Can't move the game's folders while recording a replay")
+];
+
+/// Looks up user-facing messages by key in the current language.
+pub struct MessageCatalog {
+    language: UTF8CString,
+    messages: BTreeMap<String, UTF8CString>
+}
+
+impl Default for MessageCatalog {
+    fn default() -> Self {
+        Self {
+            language: DEFAULT_LANGUAGE.into(),
+            messages: BTreeMap::new()
+        }
+    }
+}
+
+impl MessageCatalog {
+    /// Load the given language from the user directory.
+    pub fn load(files: &dyn FileStore, user_dir: &Path, language: &str) -> Result<Self, LocalizedError> {
+        if language == DEFAULT_LANGUAGE {
+            return Ok(Self::default())
+        }
+
+        if language.is_empty() || !language.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+            return Err(LocalizedError::new("language_invalid").arg("language", language))
+        }
+
+        let path = user_dir.join(LOCALE_FOLDER).join(format!("{language}.json"));
+        let data = files.read_to_string(&path)
+            .map_err(|e| LocalizedError::new("file_read_failed").arg("path", path.display()).arg("error", e))?;
+        let messages: BTreeMap<String, String> = serde_json::from_str(&data)
+            .map_err(|e| LocalizedError::new("locale_parse_failed").arg("path", path.display()).arg("error", e))?;
+
+        Ok(Self {
+            language: language.into(),
+            messages: messages.into_iter().map(|(k, v)| (k, v.into())).collect()
+        })
+    }
+
+    /// Get all languages that can be loaded from the user directory, including the default one.
+    pub fn available_languages(files: &dyn FileStore, user_dir: &Path) -> Vec<UTF8CString> {
+        let mut languages = vec![UTF8CString::from(DEFAULT_LANGUAGE)];
+
+        let Ok(dir) = files.read_dir(&user_dir.join(LOCALE_FOLDER)) else {
+            return languages
+        };
+
+        let mut found: Vec<String> = dir
+            .into_iter()
+            .filter_map(|path| {
+                if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                    return None
+                }
+                path.file_stem().and_then(|s| s.to_str()).map(|s| s.to_owned())
+            })
+            .filter(|l| l != DEFAULT_LANGUAGE)
+            .collect();
+        found.sort();
+
+        languages.extend(found.into_iter().map(UTF8CString::from));
+        languages
+    }
+
+    /// Get the current language.
+    #[inline]
+    pub fn language(&self) -> &UTF8CString {
+        &self.language
+    }
+
+    /// Get the template for a message key, falling back to the default language, or `None` if no
+    /// such message exists.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.messages.get(key)
+            .map(|m| m.as_str())
+            .or_else(|| DEFAULT_MESSAGES.iter().find(|(k, _)| *k == key).map(|(_, m)| *m))
+    }
+
+    /// Format the message for a key, replacing each `{name}` with the argument of that name.
+    ///
+    /// Unknown keys format as the key itself, and unknown arguments are left as-is.
+    pub fn format(&self, key: &str, args: &[(&str, &str)]) -> String {
+        let mut template = self.get(key).unwrap_or(key);
+        let mut result = String::with_capacity(template.len());
+
+        while let Some(start) = template.find('{') {
+            result.push_str(&template[..start]);
+            template = &template[start..];
+
+            let value = template.find('}')
+                .and_then(|end| args.iter().find(|(name, _)| *name == &template[1..end]).map(|(_, value)| (end, *value)));
+
+            match value {
+                Some((end, value)) => {
+                    result.push_str(value);
+                    template = &template[end + 1..];
+                },
+                None => {
+                    result.push('{');
+                    template = &template[1..];
+                }
+            }
+        }
+
+        result.push_str(template);
+        result
+    }
+}
+
+/// An error that can be shown to the user in any language.
+///
+/// `key` identifies the message in the [`MessageCatalog`], and the arguments fill in its template.
+/// Arguments are not translated, so any that come from the OS or another crate stay in English.
+#[derive(Clone, PartialEq, Debug)]
+pub struct LocalizedError {
+    key: &'static str,
+    args: Vec<(&'static str, String)>
+}
+
+impl LocalizedError {
+    /// Make an error for the given message key with no arguments.
+    pub fn new(key: &'static str) -> Self {
+        Self { key, args: Vec::new() }
+    }
+
+    /// Add an argument.
+    pub fn arg(mut self, name: &'static str, value: impl Display) -> Self {
+        self.args.push((name, value.to_string()));
+        self
+    }
+
+    /// Get the message key.
+    #[inline]
+    pub fn key(&self) -> &'static str {
+        self.key
+    }
+
+    /// Get the arguments in the order they were added.
+    #[inline]
+    pub fn args(&self) -> &[(&'static str, String)] {
+        &self.args
+    }
+
+    /// Format the error in the catalog's language.
+    pub fn format(&self, messages: &MessageCatalog) -> String {
+        let args: Vec<(&str, &str)> = self.args.iter().map(|(name, value)| (*name, value.as_str())).collect();
+        messages.format(self.key, &args)
+    }
+}
+
+/// Formats the error in the default language.
+impl Display for LocalizedError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.format(&MessageCatalog::default()))
+    }
+}
+
+impl std::error::Error for LocalizedError {}
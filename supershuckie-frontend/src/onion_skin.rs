@@ -0,0 +1,70 @@
+use std::collections::VecDeque;
+use supershuckie_core::emulator::ScreenData;
+
+/// Most previous frames that can be overlaid at once.
+pub(crate) const MAX_ONION_SKIN_FRAMES: u8 = 16;
+
+/// Remembers the last few displayed frames so they can be overlaid onto the current one.
+#[derive(Default)]
+pub(crate) struct OnionSkin {
+    /// Previous frames, most recent first
+    history: VecDeque<Vec<ScreenData>>
+}
+
+impl OnionSkin {
+    /// Overlay up to `frames` previous frames onto the screens, then remember the screens as they
+    /// were before being overlaid.
+    ///
+    /// The most recent frame is overlaid at `opacity`, and older frames fade out linearly.
+    pub(crate) fn apply(&mut self, screens: &mut [ScreenData], frames: usize, opacity: f64) {
+        let same_dimensions = self.history.front().is_none_or(|previous_screens| {
+            previous_screens.len() == screens.len()
+                && previous_screens.iter().zip(screens.iter()).all(|(previous, screen)| previous.pixels.len() == screen.pixels.len())
+        });
+
+        if !same_dimensions {
+            self.history.clear();
+        }
+
+        self.history.truncate(frames);
+        let current = screens.to_vec();
+
+        // Overlay the oldest frames first so more recent ones end up on top
+        for (index, previous_screens) in self.history.iter().enumerate().rev() {
+            let fade = (frames - index) as f64 / frames as f64;
+            let alpha = (opacity * fade * 256.0).clamp(0.0, 256.0) as u32;
+            if alpha == 0 {
+                continue
+            }
+
+            for (screen, previous) in screens.iter_mut().zip(previous_screens.iter()) {
+                for (pixel, previous_pixel) in screen.pixels.iter_mut().zip(previous.pixels.iter()) {
+                    *pixel = mix_a8r8g8b8(*pixel, *previous_pixel, alpha);
+                }
+            }
+        }
+
+        if frames > 0 {
+            if self.history.len() == frames {
+                self.history.pop_back();
+            }
+            self.history.push_front(current);
+        }
+    }
+
+    /// Forget all previous frames.
+    pub(crate) fn clear(&mut self) {
+        self.history.clear();
+    }
+}
+
+/// Mix `top` onto `bottom` with an alpha of 0-256, keeping `bottom`'s alpha.
+fn mix_a8r8g8b8(bottom: u32, top: u32, alpha: u32) -> u32 {
+    let channel = |shift: u32| {
+        let b = (bottom >> shift) & 0xFF;
+        let t = (top >> shift) & 0xFF;
+        ((t * alpha + b * (256 - alpha)) >> 8) << shift
+    };
+
+    (bottom & 0xFF000000) | channel(16) | channel(8) | channel(0)
+}
@@ -0,0 +1,74 @@
+use miniz_oxide::deflate::compress_to_vec_zlib;
+use supershuckie_core::emulator::ScreenData;
+
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+
+/// zlib compression level for image data (balanced between speed and size).
+const COMPRESSION_LEVEL: u8 = 6;
+
+/// Encode the screen data as an RGB PNG, upscaled by `scale` with nearest-neighbor scaling.
+///
+/// Returns `None` if the image would be empty or too large for a PNG.
+pub(crate) fn encode_png(screen: &ScreenData, scale: usize) -> Option<Vec<u8>> {
+    if screen.width == 0 || screen.height == 0 || scale == 0 {
+        return None
+    }
+
+    let width = screen.width.checked_mul(scale)?;
+    let height = screen.height.checked_mul(scale)?;
+    let (png_width, png_height) = (u32::try_from(width).ok()?, u32::try_from(height).ok()?);
+
+    let mut scanlines = Vec::with_capacity(height * (1 + width * 3));
+    let mut scanline = Vec::with_capacity(1 + width * 3);
+    let pixels: Vec<[u8; 3]> = screen.rgb_pixels().collect();
+    for row in pixels.chunks_exact(screen.width).take(screen.height) {
+        scanline.clear();
+        scanline.push(0); // no filter
+        for rgb in row {
+            for _ in 0..scale {
+                scanline.extend_from_slice(rgb);
+            }
+        }
+        for _ in 0..scale {
+            scanlines.extend_from_slice(&scanline);
+        }
+    }
+
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&png_width.to_be_bytes());
+    header.extend_from_slice(&png_height.to_be_bytes());
+    header.extend_from_slice(&[
+        8, // bit depth
+        2, // color type (RGB)
+        0, // compression method (deflate)
+        0, // filter method
+        0  // interlace method (none)
+    ]);
+
+    let mut png = Vec::new();
+    png.extend_from_slice(&PNG_SIGNATURE);
+    write_chunk(&mut png, b"IHDR", &header);
+    write_chunk(&mut png, b"IDAT", &compress_to_vec_zlib(&scanlines, COMPRESSION_LEVEL));
+    write_chunk(&mut png, b"IEND", &[]);
+    Some(png)
+}
+
+fn write_chunk(png: &mut Vec<u8>, chunk_type: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let crc_start = png.len();
+    png.extend_from_slice(chunk_type);
+    png.extend_from_slice(data);
+    let crc = crc32(&png[crc_start..]);
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+pub(crate) fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFFFFFFu32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB88320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
@@ -0,0 +1,51 @@
+use std::path::Path;
+use crate::file_store::FileStore;
+
+/// Returns `Some(true)` if the host is running on battery, `Some(false)` if it is on external
+/// power, or `None` if it can't be determined (e.g. a desktop or an unsupported platform).
+#[cfg(target_os = "linux")]
+pub fn is_on_battery(files: &dyn FileStore) -> Option<bool> {
+    let read = |path: &Path, what: &str| files.read_to_string(&path.join(what)).ok().map(|s| s.trim().to_owned());
+
+    let mut external_power_present = false;
+    let mut battery_present = false;
+
+    for path in files.read_dir(Path::new("/sys/class/power_supply")).ok()? {
+        match read(&path, "type").as_deref() {
+            Some("Mains") | Some("USB") => {
+                external_power_present = true;
+                if read(&path, "online").as_deref() == Some("1") {
+                    return Some(false)
+                }
+            },
+            Some("Battery") => {
+                // Peripherals (e.g. mice) report their batteries here, too
+                if read(&path, "scope").as_deref() == Some("Device") {
+                    continue
+                }
+                battery_present = true;
+                if read(&path, "status").as_deref() == Some("Discharging") {
+                    return Some(true)
+                }
+            },
+            _ => ()
+        }
+    }
+
+    match (battery_present, external_power_present) {
+        (false, _) => None,
+
+        // Everything external is offline
+        (true, true) => Some(true),
+
+        // Charging or full with no way to tell where the power is coming from
+        (true, false) => Some(false)
+    }
+}
+
+/// Returns `Some(true)` if the host is running on battery, `Some(false)` if it is on external
+/// power, or `None` if it can't be determined (e.g. a desktop or an unsupported platform).
+#[cfg(not(target_os = "linux"))]
+pub fn is_on_battery(_files: &dyn FileStore) -> Option<bool> {
+    None
+}
@@ -0,0 +1,161 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use num_enum::TryFromPrimitive;
+use supershuckie_replay_recorder::replay_file::playback::ReplayFilePlayer;
+use supershuckie_replay_recorder::replay_file::{ReplayConsoleType, ReplayFileMetadata};
+use crate::file_store::FileStore;
+use crate::settings::ReplayMismatchKind;
+use crate::util::UTF8CString;
+
+/// What to sort replays by in [`SuperShuckieFrontend::browse_replays`](crate::SuperShuckieFrontend::browse_replays).
+#[derive(Copy, Clone, PartialEq, Debug, Default, TryFromPrimitive)]
+#[repr(u32)]
+pub enum ReplaySort {
+    #[default]
+    Name = 0,
+    Duration = 1,
+    Size = 2,
+    Created = 3
+}
+
+/// Whether a replay can be played back with the loaded ROM.
+#[derive(Copy, Clone, PartialEq, Debug, TryFromPrimitive)]
+#[repr(u32)]
+pub enum ReplayCompatibility {
+    /// The replay's ROM is not loaded, so it can't be checked
+    Unknown = 0,
+
+    /// The replay can be played back
+    Compatible = 1,
+
+    /// The replay can be played back once its mismatches are acknowledged, but it may desync
+    Mismatched = 2,
+
+    /// The replay is for a console that can't be emulated
+    Incompatible = 3,
+
+    /// The replay could not be read
+    Unreadable = 4
+}
+
+/// Sort and filter parameters for [`SuperShuckieFrontend::browse_replays`](crate::SuperShuckieFrontend::browse_replays).
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct ReplayBrowserQuery<'a> {
+    pub sort: ReplaySort,
+    pub descending: bool,
+
+    /// Only include replays with names containing this, ignoring case
+    pub name_filter: &'a str,
+
+    /// Only include replays that can be played back without acknowledging any mismatches
+    pub compatible_only: bool
+}
+
+/// A replay listed by [`SuperShuckieFrontend::browse_replays`](crate::SuperShuckieFrontend::browse_replays).
+#[derive(Clone, Debug)]
+pub struct ReplayBrowserEntry {
+    pub name: UTF8CString,
+    pub total_frames: u32,
+    pub total_milliseconds: u32,
+
+    /// File size in bytes
+    pub size: u64,
+
+    /// Time the file was created (or last modified, if unsupported), in seconds since the Unix epoch
+    pub created: Option<u64>,
+
+    /// Console the replay was recorded on, or [`ReplayConsoleType::Unknown`] if unreadable
+    pub console_type: ReplayConsoleType,
+
+    pub compatibility: ReplayCompatibility,
+
+    /// Mismatches that have not been remembered as acknowledged for this replay
+    pub mismatches: Vec<ReplayMismatchKind>
+}
+
+/// Parts of a replay file that are expensive to read.
+#[derive(Clone)]
+pub(crate) struct ReplaySummary {
+    pub total_frames: u32,
+    pub total_milliseconds: u32,
+    pub metadata: ReplayFileMetadata
+}
+
+/// A replay file as of when it was last read.
+#[derive(Clone)]
+pub(crate) struct ReplayFileDetails {
+    pub size: u64,
+    pub created: Option<u64>,
+
+    /// `None` if the file could not be parsed
+    pub summary: Option<ReplaySummary>
+}
+
+struct CachedReplayFile {
+    modified: Option<SystemTime>,
+    details: ReplayFileDetails
+}
+
+/// Remembers parsed replays so browsing doesn't re-read files that haven't changed.
+#[derive(Default)]
+pub(crate) struct ReplayBrowserCache {
+    files: BTreeMap<PathBuf, CachedReplayFile>
+}
+
+impl ReplayBrowserCache {
+    /// Get the details of a replay file, reading it only if it changed since it was last read.
+    ///
+    /// Returns `None` if the file does not exist.
+    pub(crate) fn read(&mut self, files: &dyn FileStore, path: &Path) -> Option<ReplayFileDetails> {
+        let file_metadata = files.metadata(path).ok()?;
+        let size = file_metadata.len;
+        let modified = file_metadata.modified;
+
+        if let Some(cached) = self.files.get(path) && cached.details.size == size && cached.modified == modified && modified.is_some() {
+            return Some(cached.details.clone())
+        }
+
+        let created = file_metadata.created
+            .or(modified)
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|t| t.as_secs());
+
+        let summary = files.read(path).ok()
+            .and_then(|data| ReplayFilePlayer::new(data, false).ok())
+            .map(|player| ReplaySummary {
+                total_frames: player.get_total_frames().min(u32::MAX as _) as u32,
+                total_milliseconds: player.get_total_milliseconds().min(u32::MAX as _) as u32,
+                metadata: player.get_replay_metadata().clone()
+            });
+
+        let details = ReplayFileDetails { size, created, summary };
+        self.files.insert(path.to_owned(), CachedReplayFile { modified, details: details.clone() });
+        Some(details)
+    }
+
+    /// Forget every file not in `paths`.
+    pub(crate) fn retain(&mut self, paths: &[PathBuf]) {
+        self.files.retain(|path, _| paths.contains(path));
+    }
+}
+
+/// Filter and sort entries according to the query.
+pub(crate) fn apply_query(entries: &mut Vec<ReplayBrowserEntry>, query: &ReplayBrowserQuery) {
+    if query.compatible_only {
+        entries.retain(|e| e.compatibility == ReplayCompatibility::Compatible);
+    }
+
+    // Sort by name first so ties are broken consistently
+    entries.sort_by_key(|e| e.name.as_str().to_lowercase());
+    match query.sort {
+        ReplaySort::Name => (),
+        ReplaySort::Duration => entries.sort_by_key(|e| e.total_milliseconds),
+        ReplaySort::Size => entries.sort_by_key(|e| e.size),
+        ReplaySort::Created => entries.sort_by_key(|e| e.created)
+    }
+
+    if query.descending {
+        entries.reverse();
+    }
+}
@@ -0,0 +1,83 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+use serde::{Deserialize, Serialize};
+use crate::file_store::FileStore;
+use crate::util::UTF8CString;
+
+/// File in each ROM's save data folder that holds the metadata of its save files.
+pub(crate) const SAVE_PROFILES_FILE: &str = "profiles.json";
+
+/// Metadata for a single save file of a ROM.
+#[derive(Copy, Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct SaveProfileMetadata {
+    /// Time the save file was created, in seconds since the Unix epoch, if known.
+    #[serde(default = "Option::default")]
+    pub created: Option<u64>,
+
+    /// Time the save file was last switched to or loaded, in seconds since the Unix epoch.
+    #[serde(default = "Option::default")]
+    pub last_used: Option<u64>,
+
+    /// Total time spent playing with this save file, in milliseconds.
+    #[serde(default = "u64::default")]
+    pub playtime_milliseconds: u64
+}
+
+/// A save file listed by [`SuperShuckieFrontend::get_save_profiles_for_rom`](crate::SuperShuckieFrontend::get_save_profiles_for_rom).
+#[derive(Clone, Debug)]
+pub struct SaveProfile {
+    pub name: UTF8CString,
+    pub metadata: SaveProfileMetadata
+}
+
+/// Metadata of every save file of a ROM, by name.
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub(crate) struct SaveProfiles {
+    profiles: BTreeMap<String, SaveProfileMetadata>
+}
+
+impl SaveProfiles {
+    /// Read metadata from the given path, returning empty metadata if it doesn't exist or is
+    /// unreadable.
+    pub(crate) fn read_or_default(files: &dyn FileStore, path: &Path) -> Self {
+        files.read_to_string(path)
+            .ok()
+            .and_then(|i| serde_json::from_str(&i).ok())
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn write(&self, files: &dyn FileStore, path: &Path) {
+        let _ = files.write(path, serde_json::to_string_pretty(self).expect("failed to serialize").as_bytes());
+    }
+
+    pub(crate) fn get(&self, name: &str) -> Option<&SaveProfileMetadata> {
+        self.profiles.get(name)
+    }
+
+    pub(crate) fn get_or_default(&mut self, name: &str) -> &mut SaveProfileMetadata {
+        self.profiles.entry(name.to_owned()).or_default()
+    }
+
+    pub(crate) fn names(&self) -> impl Iterator<Item = &str> {
+        self.profiles.keys().map(|i| i.as_str())
+    }
+
+    pub(crate) fn remove(&mut self, name: &str) -> Option<SaveProfileMetadata> {
+        self.profiles.remove(name)
+    }
+
+    pub(crate) fn insert(&mut self, name: &str, metadata: SaveProfileMetadata) {
+        self.profiles.insert(name.to_owned(), metadata);
+    }
+
+    /// Record the save file being used now.
+    pub(crate) fn touch(&mut self, name: &str) {
+        self.get_or_default(name).last_used = now();
+    }
+}
+
+pub(crate) fn now() -> Option<u64> {
+    SystemTime::now().duration_since(UNIX_EPOCH).ok().map(|i| i.as_secs())
+}
@@ -0,0 +1,139 @@
+use serde::{Deserialize, Serialize};
+
+/// Result of running a test ROM with [`SuperShuckieFrontend::run_serial_test`](crate::SuperShuckieFrontend::run_serial_test).
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[repr(u32)]
+#[serde(rename_all = "snake_case")]
+pub enum SerialTestVerdict {
+    /// The ROM reported that it passed
+    Passed = 0,
+
+    /// The ROM reported that it failed
+    Failed = 1,
+
+    /// The ROM did not report a result before the frame limit
+    TimedOut = 2
+}
+
+/// A test ROM run by [`SuperShuckieFrontend::run_serial_test`](crate::SuperShuckieFrontend::run_serial_test).
+#[derive(Clone, Debug)]
+pub struct SerialTestResult {
+    pub verdict: SerialTestVerdict,
+
+    /// Number of frames run before the verdict was reached
+    pub frames: u32,
+
+    /// Everything the ROM sent over the serial port
+    pub output: Vec<u8>
+}
+
+/// Sent by mooneye test ROMs when they pass (the Fibonacci numbers left in the registers).
+const MOONEYE_PASSED: [u8; 6] = [3, 5, 8, 13, 21, 34];
+
+/// Sent by mooneye test ROMs when they fail.
+const MOONEYE_FAILED: [u8; 6] = [0x42; 6];
+
+/// Grade what a test ROM has sent over the serial port so far, returning `None` if it hasn't
+/// reported a result yet.
+///
+/// This understands blargg's test ROMs, which print "Passed" or "Failed" as text, and mooneye's,
+/// which send a fixed sequence of bytes.
+pub fn grade_serial_output(output: &[u8]) -> Option<SerialTestVerdict> {
+    if output.ends_with(&MOONEYE_PASSED) {
+        return Some(SerialTestVerdict::Passed)
+    }
+    if output.ends_with(&MOONEYE_FAILED) {
+        return Some(SerialTestVerdict::Failed)
+    }
+
+    // Multi-test ROMs print a line per test before the summary, so a failure anywhere is final
+    let text = String::from_utf8_lossy(output);
+    if text.contains("Failed") {
+        Some(SerialTestVerdict::Failed)
+    }
+    else if text.contains("Passed") {
+        Some(SerialTestVerdict::Passed)
+    }
+    else {
+        None
+    }
+}
+
+/// Extension of the file next to a test ROM holding the serial output it is expected to send.
+///
+/// For example, `cpu_instrs.gb` is compared against `cpu_instrs.expected`.
+pub const EXPECTED_OUTPUT_EXTENSION: &str = "expected";
+
+/// A test ROM run by [`SuperShuckieFrontend::run_test_suite`](crate::SuperShuckieFrontend::run_test_suite).
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct TestSuiteEntry {
+    /// Filename of the test ROM
+    pub rom: String,
+
+    /// Whether the test ROM passed
+    ///
+    /// If the ROM has expected output, this is whether its output matched (ignoring trailing
+    /// whitespace) regardless of the verdict. Otherwise, it is whether the verdict is `Passed`.
+    pub passed: bool,
+
+    /// The verdict the ROM reported, or `None` if it could not be run
+    pub verdict: Option<SerialTestVerdict>,
+
+    /// Number of frames run before the verdict was reached
+    pub frames: u32,
+
+    /// Everything the ROM sent over the serial port, converted to text
+    pub output: String,
+
+    /// The output the ROM was expected to send, if it has an expected output file
+    pub expected_output: Option<String>,
+
+    /// Why the ROM could not be run, if it couldn't
+    pub error: Option<String>
+}
+
+/// Report made by [`SuperShuckieFrontend::run_test_suite`](crate::SuperShuckieFrontend::run_test_suite).
+///
+/// Reports can be saved as JSON and compared against later runs (e.g. after updating the core) with
+/// [`TestSuiteReport::regressions`].
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct TestSuiteReport {
+    /// Name of the core the test ROMs were run on
+    pub core_name: String,
+
+    /// Maximum number of frames each test ROM was run for
+    pub max_frames: u32,
+
+    /// Test ROMs in filename order
+    pub entries: Vec<TestSuiteEntry>
+}
+
+impl TestSuiteReport {
+    /// Get the number of test ROMs that passed.
+    pub fn passed(&self) -> usize {
+        self.entries.iter().filter(|e| e.passed).count()
+    }
+
+    /// Get the number of test ROMs that did not pass.
+    pub fn failed(&self) -> usize {
+        self.entries.len() - self.passed()
+    }
+
+    /// Get the test ROMs that passed in `previous` but do not pass in this report.
+    pub fn regressions(&self, previous: &TestSuiteReport) -> Vec<&TestSuiteEntry> {
+        self.entries
+            .iter()
+            .filter(|e| !e.passed && previous.entries.iter().any(|p| p.passed && p.rom == e.rom))
+            .collect()
+    }
+
+    /// Serialize the report as pretty-printed JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("failed to serialize a test suite report")
+    }
+
+    /// Parse a report saved with [`TestSuiteReport::to_json`].
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        serde_json::from_str(json).map_err(|e| format!("Invalid test suite report: {e}"))
+    }
+}
@@ -1,42 +1,42 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::ffi::CStr;
-use std::fs;
+use std::fmt::{Display, Formatter};
 use std::fs::File;
 use std::hint::unreachable_unchecked;
-use std::io::{Read, Seek, SeekFrom};
+use std::io::ErrorKind;
 use std::num::{NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize};
 use std::path::Path;
 use num_enum::TryFromPrimitive;
 use serde::{Deserialize, Serialize};
-use supershuckie_core::emulator::Input;
-use supershuckie_replay_recorder::replay_file::record::ReplayFileRecorderSettings;
+use supershuckie_core::emulator::{ColorCorrectionMode, Input, InputKind};
+use supershuckie_replay_recorder::replay_file::record::{BufferedReplayFileSink, LoadSaveStateMode, ReplayFileRecorderSettings};
+use crate::file_store::FileStore;
 use crate::SETTINGS_FILE;
 use crate::util::UTF8CString;
 
-pub(crate) fn try_to_init_user_dir_and_get_settings(user_dir: &Path) -> Result<Settings, String> {
-    if !user_dir.exists() {
-        fs::create_dir(&user_dir).map_err(|e| format!("Failed to create the user_dir: {e}"))?;
+/// Returns the settings and whether or not the settings file was newly created (i.e. first run).
+pub(crate) fn try_to_init_user_dir_and_get_settings(files: &dyn FileStore, user_dir: &Path) -> Result<(Settings, bool), String> {
+    if !files.exists(user_dir) {
+        files.create_dir_all(user_dir).map_err(|e| format!("Failed to create the user_dir: {e}"))?;
     }
 
-    let settings_toml = user_dir.join(SETTINGS_FILE);
-    let mut settings_file = File::options()
-        .write(true)
-        .read(true)
-        .create(true)
-        .open(settings_toml)
-        .map_err(|e| format!("Failed to open the settings file for write access: {e}"))?;
+    let settings_json = user_dir.join(SETTINGS_FILE);
+    let mut settings_str = match files.read_to_string(&settings_json) {
+        Ok(s) => s,
+        Err(e) if e.kind() == ErrorKind::NotFound => {
+            files.write(&settings_json, &[]).map_err(|e| format!("Failed to create the settings file: {e}"))?;
+            String::new()
+        },
+        Err(e) => return Err(format!("Failed to read the settings file: {e}"))
+    };
 
-    settings_file.seek(SeekFrom::Start(0)).map_err(|e| format!("Failed to seek the settings file: {e}"))?;
-
-    let mut settings_str = String::new();
-    settings_file.read_to_string(&mut settings_str).map_err(|e| format!("Failed to read the settings file: {e}"))?;
-
-    if settings_str.trim().is_empty() {
+    let first_run = settings_str.trim().is_empty();
+    if first_run {
         settings_str = "{}".to_owned();
     }
 
     let settings: Settings = serde_json::from_str::<Settings>(&settings_str).map_err(|e| format!("Failed to parse the settings file: {e}"))?;
-    Ok(settings)
+    Ok((settings, first_run))
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -60,6 +60,21 @@ pub struct Settings {
     #[serde(default = "PokeAByteSettings::default")]
     pub pokeabyte: PokeAByteSettings,
 
+    #[serde(default = "StorageSettings::default")]
+    pub storage: StorageSettings,
+
+    #[serde(default = "AutosnapSettings::default")]
+    pub autosnap: AutosnapSettings,
+
+    #[serde(default = "SaveBackupSettings::default")]
+    pub save_backups: SaveBackupSettings,
+
+    #[serde(default = "InstanceSettings::default")]
+    pub instance: InstanceSettings,
+
+    #[serde(default = "LocaleSettings::default")]
+    pub locale: LocaleSettings,
+
     #[serde(default = "BTreeMap::default")]
     #[serde(skip_serializing_if = "BTreeMap::is_empty")]
     pub custom: BTreeMap<String, UTF8CString>
@@ -88,6 +103,9 @@ pub struct ReplaySettings {
     #[serde(default = "ReplaySettings::DEFAULT_FRAMES_PER_KEYFRAME")]
     pub frames_per_keyframe: NonZeroU64,
 
+    #[serde(default = "ReplaySettings::KEYFRAME_ON_EVENTS")]
+    pub keyframe_on_events: bool,
+
     #[serde(default = "ReplaySettings::AUTO_STOP_PLAYBACK_ON_INPUT")]
     pub auto_stop_playback_on_input: bool,
 
@@ -96,6 +114,30 @@ pub struct ReplaySettings {
 
     #[serde(default = "ReplaySettings::AUTO_PAUSE_ON_RECORD")]
     pub auto_pause_on_record: bool,
+
+    #[serde(default = "ReplaySettings::DEFAULT_SEEK_SPEED_MULTIPLIER")]
+    pub seek_speed_multiplier: f64,
+
+    #[serde(default = "ReplaySettings::DEFAULT_SEEK_BACKWARD_INTERVAL_MS")]
+    pub seek_backward_interval_ms: NonZeroU64,
+
+    #[serde(default = "ReplaySettings::DEFAULT_WRITE_BUFFER_SIZE_KB")]
+    pub write_buffer_size_kb: u32,
+
+    #[serde(default = "LoadStateRecordMode::default")]
+    pub load_state_record_mode: LoadStateRecordMode,
+
+    /// Discard save data written during replay playback instead of saving it under the "replay"
+    /// save file, until another save file is chosen or the ROM is reloaded
+    #[serde(default = "ReplaySettings::SANDBOX_PLAYBACK_SRAM")]
+    pub sandbox_playback_sram: bool,
+
+    /// Re-simulate one out of this many keyframe intervals while recording on a shadow core and
+    /// compare it with the live game, or `None` to not check (see
+    /// [`SuperShuckieCore::set_determinism_check`](supershuckie_core::SuperShuckieCore::set_determinism_check))
+    #[serde(default = "Option::default")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub determinism_check_keyframes: Option<NonZeroU64>,
 }
 
 impl Default for ReplaySettings {
@@ -105,9 +147,16 @@ impl Default for ReplaySettings {
             auto_decompress_replays_upfront: Self::AUTO_DECOMPRESS_REPLAYS_UPFRONT(),
             zstd_compression_level: Self::DEFAULT_MAX_ZSTD_COMPRESSION_LEVEL(),
             frames_per_keyframe: Self::DEFAULT_FRAMES_PER_KEYFRAME(),
+            keyframe_on_events: Self::KEYFRAME_ON_EVENTS(),
             auto_stop_playback_on_input: Self::AUTO_STOP_PLAYBACK_ON_INPUT(),
             auto_unpause_on_input: Self::AUTO_UNPAUSE_ON_INPUT(),
             auto_pause_on_record: Self::AUTO_PAUSE_ON_RECORD(),
+            seek_speed_multiplier: Self::DEFAULT_SEEK_SPEED_MULTIPLIER(),
+            seek_backward_interval_ms: Self::DEFAULT_SEEK_BACKWARD_INTERVAL_MS(),
+            write_buffer_size_kb: Self::DEFAULT_WRITE_BUFFER_SIZE_KB(),
+            load_state_record_mode: LoadStateRecordMode::default(),
+            sandbox_playback_sram: Self::SANDBOX_PLAYBACK_SRAM(),
+            determinism_check_keyframes: None,
         }
     }
 }
@@ -119,30 +168,408 @@ impl ReplaySettings {
     const AUTO_DECOMPRESS_REPLAYS_UPFRONT: fn() -> bool = || true;
     const DEFAULT_MAX_ZSTD_COMPRESSION_LEVEL: fn() -> i32 = || ReplayFileRecorderSettings::default().compression_level;
     const DEFAULT_FRAMES_PER_KEYFRAME: fn() -> NonZeroU64 = || unsafe { NonZeroU64::new_unchecked(60) };
+    const KEYFRAME_ON_EVENTS: fn() -> bool = || true;
     const AUTO_STOP_PLAYBACK_ON_INPUT: fn() -> bool = || false;
     const AUTO_UNPAUSE_ON_INPUT: fn() -> bool = || false;
     const AUTO_PAUSE_ON_RECORD: fn() -> bool = || false;
+    const DEFAULT_SEEK_SPEED_MULTIPLIER: fn() -> f64 = || 16.0;
+    const DEFAULT_SEEK_BACKWARD_INTERVAL_MS: fn() -> NonZeroU64 = || unsafe { NonZeroU64::new_unchecked(100) };
+    const DEFAULT_WRITE_BUFFER_SIZE_KB: fn() -> u32 = || (BufferedReplayFileSink::<File>::DEFAULT_FLUSH_THRESHOLD / 1024) as u32;
+    const SANDBOX_PLAYBACK_SRAM: fn() -> bool = || true;
+}
+
+/// What to record when a save state is loaded while recording a replay.
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize, Default, TryFromPrimitive)]
+#[repr(u32)]
+pub enum LoadStateRecordMode {
+    /// Embed the entire save state every time
+    #[serde(rename = "embed")]
+    #[default]
+    Embed = 0,
+
+    /// Reference an earlier keyframe if the save state matches one
+    #[serde(rename = "restore")]
+    RestoreKeyframe = 1,
+
+    /// Discard everything after an earlier keyframe if the save state matches one
+    #[serde(rename = "branch")]
+    Branch = 2
+}
+
+impl From<LoadStateRecordMode> for LoadSaveStateMode {
+    fn from(value: LoadStateRecordMode) -> Self {
+        match value {
+            LoadStateRecordMode::Embed => LoadSaveStateMode::Embed,
+            LoadStateRecordMode::RestoreKeyframe => LoadSaveStateMode::RestoreKeyframe,
+            LoadStateRecordMode::Branch => LoadSaveStateMode::Branch
+        }
+    }
 }
 
 #[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct ROMConfig {
-    pub save_name: UTF8CString
+    pub save_name: UTF8CString,
+
+    /// Controls that take priority over the global controls for this ROM.
+    #[serde(default = "Option::default")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub controls: Option<Controls>,
+
+    /// Number of frames to delay input by for this ROM.
+    #[serde(default = "u8::default")]
+    pub input_delay_frames: u8,
+
+    /// Metadata mismatches to play back anyway, by replay name.
+    #[serde(default = "BTreeMap::default")]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub acknowledged_replay_mismatches: BTreeMap<String, Vec<AcknowledgedReplayMismatch>>,
+
+    /// BLAKE3 checksum of the ROM last loaded with this name, used to find its folders when they
+    /// are named after the checksum.
+    #[serde(default = "Option::default")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checksum: Option<UTF8CString>,
+
+    /// Cheats saved for this ROM, in the order they were added.
+    #[serde(default = "Vec::default")]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub cheats: Vec<CheatSetting>
 }
 
 impl Default for ROMConfig {
     fn default() -> Self {
         Self {
-            save_name: "default".into()
+            save_name: "default".into(),
+            controls: None,
+            input_delay_frames: 0,
+            acknowledged_replay_mismatches: BTreeMap::new(),
+            checksum: None,
+            cheats: Vec::new()
         }
     }
 }
 
-#[derive(Clone, PartialEq, Default, Serialize, Deserialize)]
-pub struct PokeAByteSettings {
+/// Kind of metadata mismatch between a replay and what is loaded.
+///
+/// Each kind is a separate bit, so they can be combined into a mask.
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize, TryFromPrimitive)]
+#[repr(u32)]
+pub enum ReplayMismatchKind {
+    /// A different ROM (or a modified one) is loaded
+    #[serde(rename = "rom_checksum")]
+    ROMChecksum = 1,
+
+    /// A different BIOS (or a modified one) is loaded
+    #[serde(rename = "bios_checksum")]
+    BIOSChecksum = 2,
+
+    /// A different core (or a different version of it) is loaded
+    #[serde(rename = "core")]
+    Core = 4,
+
+    /// Different save data is loaded than when the replay was recorded
+    #[serde(rename = "sram_checksum")]
+    SRAMChecksum = 8
+}
+
+impl ReplayMismatchKind {
+    /// All kinds of mismatches.
+    pub const ALL: [ReplayMismatchKind; 4] = [ReplayMismatchKind::ROMChecksum, ReplayMismatchKind::BIOSChecksum, ReplayMismatchKind::Core, ReplayMismatchKind::SRAMChecksum];
+}
+
+/// A GameShark or Game Genie code saved for a ROM.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct CheatSetting {
+    pub code: UTF8CString,
+
+    /// Name shown to the user, which can be empty
+    #[serde(default = "UTF8CString::default")]
+    pub description: UTF8CString,
+
+    /// Apply the cheat whenever the ROM is running
     #[serde(default = "bool::default")]
     pub enabled: bool
 }
 
+/// A replay metadata mismatch that was acknowledged for future playback.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct AcknowledgedReplayMismatch {
+    pub kind: ReplayMismatchKind,
+
+    /// Checksum or core name that was loaded when this was acknowledged; if something else is
+    /// loaded, the mismatch is reported again.
+    pub loaded: UTF8CString
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct PokeAByteSettings {
+    #[serde(default = "bool::default")]
+    pub enabled: bool,
+
+    /// If true, writes are rejected during replay playback; otherwise they are applied once
+    /// playback ends.
+    #[serde(default = "PokeAByteSettings::REJECT_WRITES_DURING_PLAYBACK")]
+    pub reject_writes_during_playback: bool,
+
+    /// How the number of frames Poke-A-Byte suggests skipping between reads is handled
+    #[serde(default = "PokeAByteFrameSkip::default")]
+    pub frame_skip: PokeAByteFrameSkip,
+
+    /// Number of frames to skip between reads if frame_skip is custom
+    #[serde(default = "u32::default")]
+    pub custom_frame_skip: u32,
+
+    /// Transport used to listen for Poke-A-Byte
+    #[serde(default = "PokeAByteTransportKind::default")]
+    pub transport: PokeAByteTransportKind,
+
+    /// Address to listen on (an IP address and port, a socket path, or an abstract socket name,
+    /// depending on the transport); empty for the transport's default
+    #[serde(default = "UTF8CString::default")]
+    pub address: UTF8CString,
+
+    /// If true, failed RAM reads and writes (e.g. from a bad mapper) pause emulation with an error
+    /// instead of being ignored, stalling any replay being played back
+    #[serde(default = "bool::default")]
+    pub strict_ram_access: bool
+}
+
+impl Default for PokeAByteSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            reject_writes_during_playback: Self::REJECT_WRITES_DURING_PLAYBACK(),
+            frame_skip: PokeAByteFrameSkip::default(),
+            custom_frame_skip: 0,
+            transport: PokeAByteTransportKind::default(),
+            address: UTF8CString::default(),
+            strict_ram_access: false
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize, Default, TryFromPrimitive)]
+#[repr(u32)]
+#[serde(rename_all = "snake_case")]
+pub enum PokeAByteTransportKind {
+    /// UDP over IPv4 or IPv6 (default address 127.0.0.1:55356)
+    #[default]
+    Udp = 0,
+
+    /// Unix domain socket at a path (Unix only; default path is pokeabyte.sock in the user dir)
+    UnixSocket = 1,
+
+    /// Unix domain socket in the abstract namespace (Linux only; default name is supershuckie-pokeabyte)
+    AbstractSocket = 2
+}
+
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize, Default, TryFromPrimitive)]
+#[repr(u32)]
+#[serde(rename_all = "snake_case")]
+pub enum PokeAByteFrameSkip {
+    /// Reads are updated every frame, regardless of the suggestion
+    Ignore = 0,
+
+    /// The number of frames suggested by Poke-A-Byte is skipped
+    #[default]
+    Honor = 1,
+
+    /// custom_frame_skip frames are skipped, regardless of the suggestion
+    Custom = 2
+}
+
+impl PokeAByteSettings {
+    const REJECT_WRITES_DURING_PLAYBACK: fn() -> bool = || true;
+}
+
+/// Automatic save states ("autosnaps") taken periodically while playing.
+///
+/// These are stored in the `autosnap` subfolder of the ROM's save states folder, named by the time
+/// they were taken, and the oldest ones are deleted once there are more than `max_snapshots`.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct AutosnapSettings {
+    #[serde(default = "AutosnapSettings::ENABLED")]
+    pub enabled: bool,
+
+    /// Minutes of play (not counting time spent paused) between snapshots
+    #[serde(default = "AutosnapSettings::DEFAULT_INTERVAL_MINUTES")]
+    pub interval_minutes: NonZeroU32,
+
+    /// Number of snapshots to keep per ROM
+    #[serde(default = "AutosnapSettings::DEFAULT_MAX_SNAPSHOTS")]
+    pub max_snapshots: NonZeroUsize
+}
+
+impl Default for AutosnapSettings {
+    fn default() -> Self {
+        Self {
+            enabled: Self::ENABLED(),
+            interval_minutes: Self::DEFAULT_INTERVAL_MINUTES(),
+            max_snapshots: Self::DEFAULT_MAX_SNAPSHOTS()
+        }
+    }
+}
+
+impl AutosnapSettings {
+    const ENABLED: fn() -> bool = || true;
+    const DEFAULT_INTERVAL_MINUTES: fn() -> NonZeroU32 = || unsafe { NonZeroU32::new_unchecked(5) };
+    const DEFAULT_MAX_SNAPSHOTS: fn() -> NonZeroUsize = || unsafe { NonZeroUsize::new_unchecked(10) };
+}
+
+/// Copies of save data kept whenever it is overwritten or deleted.
+///
+/// These are stored in the `backups/<save name>` subfolder of the ROM's save data folder, named by
+/// the time they were made, and the oldest ones are deleted once there are more than
+/// `max_backups` for a save.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct SaveBackupSettings {
+    #[serde(default = "SaveBackupSettings::ENABLED")]
+    pub enabled: bool,
+
+    /// Number of backups to keep per save
+    #[serde(default = "SaveBackupSettings::DEFAULT_MAX_BACKUPS")]
+    pub max_backups: NonZeroUsize
+}
+
+impl Default for SaveBackupSettings {
+    fn default() -> Self {
+        Self {
+            enabled: Self::ENABLED(),
+            max_backups: Self::DEFAULT_MAX_BACKUPS()
+        }
+    }
+}
+
+impl SaveBackupSettings {
+    const ENABLED: fn() -> bool = || true;
+    const DEFAULT_MAX_BACKUPS: fn() -> NonZeroUsize = || unsafe { NonZeroUsize::new_unchecked(10) };
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct InstanceSettings {
+    /// If set, starting another instance with the same user directory forwards its ROM to this
+    /// instance instead of running separately
+    #[serde(default = "InstanceSettings::SINGLE_INSTANCE")]
+    pub single_instance: bool
+}
+
+impl Default for InstanceSettings {
+    fn default() -> Self {
+        Self {
+            single_instance: Self::SINGLE_INSTANCE()
+        }
+    }
+}
+
+impl InstanceSettings {
+    const SINGLE_INSTANCE: fn() -> bool = || false;
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct LocaleSettings {
+    /// Language of messages shown to the user (see [`crate::locale`])
+    #[serde(default = "LocaleSettings::DEFAULT_LANGUAGE")]
+    pub language: UTF8CString
+}
+
+impl Default for LocaleSettings {
+    fn default() -> Self {
+        Self {
+            language: Self::DEFAULT_LANGUAGE()
+        }
+    }
+}
+
+impl LocaleSettings {
+    const DEFAULT_LANGUAGE: fn() -> UTF8CString = || crate::locale::DEFAULT_LANGUAGE.into();
+}
+
+/// Category of per-ROM user data, each of which can be stored in a different location.
+#[derive(Copy, Clone, PartialEq, Debug, TryFromPrimitive)]
+#[repr(u32)]
+pub enum StorageCategory {
+    SaveStates = 0,
+    SaveData = 1,
+    Replays = 2,
+    Printer = 3,
+    Screenshots = 4
+}
+
+impl StorageCategory {
+    /// Name of the folder for this category within a ROM's data folder.
+    pub const fn folder_name(self) -> &'static str {
+        match self {
+            Self::SaveStates => "save states",
+            Self::SaveData => "save data",
+            Self::Replays => "replays",
+            Self::Printer => "printer",
+            Self::Screenshots => "screenshots"
+        }
+    }
+}
+
+/// Base directories to use instead of the user directory for each [`StorageCategory`].
+///
+/// Data for each ROM is stored in `{base}/{rom}-data/{category}`, the same layout as the user
+/// directory, so existing folders can be moved over as-is. If keying by checksum, `{rom}` is the
+/// ROM's checksum instead of its filename.
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct StorageSettings {
+    #[serde(default = "Option::default")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub save_states_dir: Option<UTF8CString>,
+
+    #[serde(default = "Option::default")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub save_data_dir: Option<UTF8CString>,
+
+    #[serde(default = "Option::default")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replays_dir: Option<UTF8CString>,
+
+    #[serde(default = "Option::default")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub printer_dir: Option<UTF8CString>,
+
+    #[serde(default = "Option::default")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub screenshots_dir: Option<UTF8CString>,
+
+    /// Report save states, save data, and replays added or removed by other programs
+    #[serde(default = "bool::default")]
+    pub watch_for_external_changes: bool,
+
+    /// Name each ROM's folders after the ROM's checksum rather than its filename, so they are
+    /// still found if the ROM is renamed or moved
+    #[serde(default = "bool::default")]
+    pub key_by_checksum: bool
+}
+
+impl StorageSettings {
+    /// Get the base directory for the category, or `None` if the user directory is used.
+    pub fn get_base_dir(&self, category: StorageCategory) -> Option<&UTF8CString> {
+        match category {
+            StorageCategory::SaveStates => self.save_states_dir.as_ref(),
+            StorageCategory::SaveData => self.save_data_dir.as_ref(),
+            StorageCategory::Replays => self.replays_dir.as_ref(),
+            StorageCategory::Printer => self.printer_dir.as_ref(),
+            StorageCategory::Screenshots => self.screenshots_dir.as_ref()
+        }
+    }
+
+    /// Set the base directory for the category, or `None` to use the user directory.
+    pub fn set_base_dir(&mut self, category: StorageCategory, dir: Option<UTF8CString>) {
+        let setting = match category {
+            StorageCategory::SaveStates => &mut self.save_states_dir,
+            StorageCategory::SaveData => &mut self.save_data_dir,
+            StorageCategory::Replays => &mut self.replays_dir,
+            StorageCategory::Printer => &mut self.printer_dir,
+            StorageCategory::Screenshots => &mut self.screenshots_dir
+        };
+        *setting = dir;
+    }
+}
+
 #[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct EmulationSettings {
     #[serde(default = "EmulationSettings::DEFAULT_BASE_SPEED_MULTIPLIER")]
@@ -155,7 +582,68 @@ pub struct EmulationSettings {
     pub video_scale: NonZeroU8,
 
     #[serde(default = "EmulationSettings::DEFAULT_MAX_SAVE_STATE_HISTORY")]
-    pub max_save_state_history: NonZeroUsize
+    pub max_save_state_history: NonZeroUsize,
+
+    #[serde(default = "EmulationSettings::DEFAULT_SPEED_PRESETS")]
+    pub speed_presets: Vec<f64>,
+
+    /// Seconds the core can go without finishing a frame before emulation is paused (0 = never)
+    #[serde(default = "EmulationSettings::DEFAULT_WATCHDOG_TIMEOUT_SECONDS")]
+    pub watchdog_timeout_seconds: u64,
+
+    #[serde(default = "ColorCorrection::default")]
+    pub color_correction: ColorCorrection,
+
+    /// Ambient light temperature, from -1.0 (warm red) to 1.0 (cool blue)
+    #[serde(default = "EmulationSettings::DEFAULT_LIGHT_TEMPERATURE")]
+    pub light_temperature: f64,
+
+    /// Mix each frame with the previous one to simulate LCD ghosting
+    #[serde(default = "bool::default")]
+    pub frame_blending: bool,
+
+    /// Overlay the previous frames onto each frame to help line up frame-perfect tricks
+    #[serde(default = "bool::default")]
+    pub onion_skin: bool,
+
+    /// Number of previous frames overlaid by onion skinning
+    #[serde(default = "EmulationSettings::DEFAULT_ONION_SKIN_FRAMES")]
+    pub onion_skin_frames: NonZeroU8,
+
+    /// Opacity of the most recent frame overlaid by onion skinning, from 0.0 to 1.0; older frames
+    /// fade out
+    #[serde(default = "EmulationSettings::DEFAULT_ONION_SKIN_OPACITY")]
+    pub onion_skin_opacity: f64,
+
+    /// Pause while the window is unfocused, except while recording a replay or in a netplay session
+    #[serde(default = "bool::default")]
+    pub pause_on_focus_loss: bool,
+
+    /// Skip drawing and idle less eagerly while the window is minimized; emulation and Poke-A-Byte
+    /// keep running
+    #[serde(default = "EmulationSettings::THROTTLE_WHEN_MINIMIZED")]
+    pub throttle_when_minimized: bool,
+
+    #[serde(default = "PowerSaving::default")]
+    pub power_saving: PowerSaving,
+
+    /// Capture states while playing so the rewind control can go back in time; does nothing while
+    /// playing back a replay, where rewind seeks instead
+    #[serde(default = "bool::default")]
+    pub rewind_enabled: bool,
+
+    /// Frames between states captured for rewinding
+    #[serde(default = "EmulationSettings::DEFAULT_REWIND_INTERVAL_FRAMES")]
+    pub rewind_interval_frames: NonZeroU64,
+
+    /// Memory used by states captured for rewinding, in MiB; the oldest are discarded first
+    #[serde(default = "EmulationSettings::DEFAULT_REWIND_MEMORY_BUDGET_MIB")]
+    pub rewind_memory_budget_mib: NonZeroU32,
+
+    /// Core-specific options by key; options a core doesn't know are ignored by it
+    #[serde(default = "BTreeMap::default")]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub core_options: BTreeMap<String, String>
 }
 
 impl EmulationSettings {
@@ -163,6 +651,14 @@ impl EmulationSettings {
     const DEFAULT_TURBO_SPEED_MULTIPLIER: fn() -> f64 = || 2.0;
     const DEFAULT_VIDEO_SCALE: fn() -> NonZeroU8 = || unsafe { NonZeroU8::new_unchecked(4) };
     const DEFAULT_MAX_SAVE_STATE_HISTORY: fn() -> NonZeroUsize = || unsafe { NonZeroUsize::new_unchecked(100) };
+    const DEFAULT_SPEED_PRESETS: fn() -> Vec<f64> = || vec![0.25, 0.5, 1.0, 2.0, 4.0, 8.0];
+    const DEFAULT_WATCHDOG_TIMEOUT_SECONDS: fn() -> u64 = || 10;
+    const DEFAULT_LIGHT_TEMPERATURE: fn() -> f64 = || 0.0;
+    const DEFAULT_ONION_SKIN_FRAMES: fn() -> NonZeroU8 = || unsafe { NonZeroU8::new_unchecked(3) };
+    pub(crate) const DEFAULT_ONION_SKIN_OPACITY: fn() -> f64 = || 0.5;
+    const THROTTLE_WHEN_MINIMIZED: fn() -> bool = || true;
+    const DEFAULT_REWIND_INTERVAL_FRAMES: fn() -> NonZeroU64 = || unsafe { NonZeroU64::new_unchecked(4) };
+    const DEFAULT_REWIND_MEMORY_BUDGET_MIB: fn() -> NonZeroU32 = || unsafe { NonZeroU32::new_unchecked(64) };
 }
 
 impl Default for EmulationSettings {
@@ -171,7 +667,79 @@ impl Default for EmulationSettings {
             base_speed_multiplier: EmulationSettings::DEFAULT_BASE_SPEED_MULTIPLIER(),
             turbo_speed_multiplier: EmulationSettings::DEFAULT_TURBO_SPEED_MULTIPLIER(),
             video_scale: EmulationSettings::DEFAULT_VIDEO_SCALE(),
-            max_save_state_history: EmulationSettings::DEFAULT_MAX_SAVE_STATE_HISTORY()
+            max_save_state_history: EmulationSettings::DEFAULT_MAX_SAVE_STATE_HISTORY(),
+            speed_presets: EmulationSettings::DEFAULT_SPEED_PRESETS(),
+            watchdog_timeout_seconds: EmulationSettings::DEFAULT_WATCHDOG_TIMEOUT_SECONDS(),
+            color_correction: ColorCorrection::default(),
+            light_temperature: EmulationSettings::DEFAULT_LIGHT_TEMPERATURE(),
+            frame_blending: false,
+            onion_skin: false,
+            onion_skin_frames: EmulationSettings::DEFAULT_ONION_SKIN_FRAMES(),
+            onion_skin_opacity: EmulationSettings::DEFAULT_ONION_SKIN_OPACITY(),
+            pause_on_focus_loss: false,
+            throttle_when_minimized: EmulationSettings::THROTTLE_WHEN_MINIMIZED(),
+            power_saving: PowerSaving::default(),
+            rewind_enabled: false,
+            rewind_interval_frames: EmulationSettings::DEFAULT_REWIND_INTERVAL_FRAMES(),
+            rewind_memory_budget_mib: EmulationSettings::DEFAULT_REWIND_MEMORY_BUDGET_MIB(),
+            core_options: BTreeMap::new()
+        }
+    }
+}
+
+/// When to cap the speed at 1x, skip every other frame, and lower the core thread's priority.
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize, Default, TryFromPrimitive)]
+#[repr(u32)]
+#[serde(rename_all = "snake_case")]
+pub enum PowerSaving {
+    /// Never save power
+    #[default]
+    Off = 0,
+
+    /// Save power while the host is running on battery
+    OnBattery = 1,
+
+    /// Always save power
+    Always = 2
+}
+
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize, Default, TryFromPrimitive)]
+#[repr(u32)]
+#[serde(rename_all = "snake_case")]
+pub enum ColorCorrection {
+    /// Colors are mapped directly to sRGB
+    #[default]
+    Disabled = 0,
+
+    /// Brightness is corrected, but hues are not
+    CorrectCurves = 1,
+
+    /// Brightness and hues are corrected for a modern display
+    ModernAccurate = 2,
+
+    /// Brightness and hues are corrected for a modern display, and blue contrast is boosted
+    ModernBalanced = 3,
+
+    /// Contrast is boosted beyond ModernBalanced
+    ModernBoostContrast = 4,
+
+    /// Contrast is reduced to better match the original display
+    ReduceContrast = 5,
+
+    /// Contrast is heavily reduced
+    LowContrast = 6
+}
+
+impl From<ColorCorrection> for ColorCorrectionMode {
+    fn from(value: ColorCorrection) -> Self {
+        match value {
+            ColorCorrection::Disabled => ColorCorrectionMode::Disabled,
+            ColorCorrection::CorrectCurves => ColorCorrectionMode::CorrectCurves,
+            ColorCorrection::ModernAccurate => ColorCorrectionMode::ModernAccurate,
+            ColorCorrection::ModernBalanced => ColorCorrectionMode::ModernBalanced,
+            ColorCorrection::ModernBoostContrast => ColorCorrectionMode::ModernBoostContrast,
+            ColorCorrection::ReduceContrast => ColorCorrectionMode::ReduceContrast,
+            ColorCorrection::LowContrast => ColorCorrectionMode::LowContrast
         }
     }
 }
@@ -182,7 +750,10 @@ pub struct GameBoySettings {
     pub gbc_mode: GameBoyMode,
 
     #[serde(default = "bool::default")]
-    pub sgb: bool
+    pub sgb: bool,
+
+    #[serde(default = "bool::default")]
+    pub printer_connected: bool
 }
 
 #[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize, Default, TryFromPrimitive)]
@@ -204,7 +775,7 @@ pub enum GameBoyMode {
 
 pub type ControlMap = BTreeMap<i32, ControlSetting>;
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct Controls {
     #[serde(default = "BTreeMap::default")]
     pub keyboard_controls: ControlMap,
@@ -222,13 +793,327 @@ impl Default for Controls {
     }
 }
 
-#[derive(Clone, Serialize, Deserialize, Default)]
+impl Controls {
+    /// Default keyboard bindings, by physical key.
+    pub const DEFAULT_KEYBOARD_CONTROLS: [(PhysicalKey, ControlSetting); 12] = [
+        (PhysicalKey::ArrowUp, ControlSetting { control: Control::Up, modifier: ControlModifier::Normal }),
+        (PhysicalKey::ArrowDown, ControlSetting { control: Control::Down, modifier: ControlModifier::Normal }),
+        (PhysicalKey::ArrowLeft, ControlSetting { control: Control::Left, modifier: ControlModifier::Normal }),
+        (PhysicalKey::ArrowRight, ControlSetting { control: Control::Right, modifier: ControlModifier::Normal }),
+        (PhysicalKey::KeyX, ControlSetting { control: Control::A, modifier: ControlModifier::Normal }),
+        (PhysicalKey::KeyZ, ControlSetting { control: Control::B, modifier: ControlModifier::Normal }),
+        (PhysicalKey::KeyS, ControlSetting { control: Control::A, modifier: ControlModifier::Rapid }),
+        (PhysicalKey::KeyA, ControlSetting { control: Control::B, modifier: ControlModifier::Rapid }),
+        (PhysicalKey::Enter, ControlSetting { control: Control::Start, modifier: ControlModifier::Normal }),
+        (PhysicalKey::Backspace, ControlSetting { control: Control::Select, modifier: ControlModifier::Normal }),
+        (PhysicalKey::Tab, ControlSetting { control: Control::Turbo, modifier: ControlModifier::Normal }),
+        (PhysicalKey::KeyP, ControlSetting { control: Control::Pause, modifier: ControlModifier::Normal }),
+    ];
+
+    /// Generate the default keyboard bindings.
+    ///
+    /// `keycode_for` returns the keycode for the physical key on the current keyboard layout, or
+    /// `None` if there is no such key (in which case it will not be bound).
+    pub fn default_keyboard_controls<F: FnMut(PhysicalKey) -> Option<i32>>(mut keycode_for: F) -> ControlMap {
+        Self::DEFAULT_KEYBOARD_CONTROLS
+            .iter()
+            .filter_map(|(key, setting)| Some((keycode_for(*key)?, *setting)))
+            .collect()
+    }
+
+    /// Get the keyboard controls, preferring `rom_controls` (if set and non-empty) over these.
+    pub fn resolve_keyboard_controls<'a>(&'a self, rom_controls: Option<&'a Controls>) -> &'a ControlMap {
+        rom_controls
+            .map(|i| &i.keyboard_controls)
+            .filter(|i| !i.is_empty())
+            .unwrap_or(&self.keyboard_controls)
+    }
+
+    /// Check the controls for common mistakes.
+    ///
+    /// `rom_controls` are the per-ROM controls used over these, if any. Each keyboard keycode is
+    /// passed through `remap_keycode` (see [`SuperShuckieFrontend::remap_keyboard_controls`](crate::SuperShuckieFrontend::remap_keyboard_controls))
+    /// to find keycodes that end up on the same key; pass the keycode through unchanged if no remap
+    /// is pending.
+    pub fn validate<F: FnMut(i32) -> i32>(&self, rom_controls: Option<&Controls>, mut remap_keycode: F) -> Vec<ControlsWarning> {
+        let mut warnings = Vec::new();
+
+        // Bindings that are actually used, with per-ROM controls taking precedence
+        let controller_names: BTreeSet<&String> = self.controller_controls
+            .keys()
+            .chain(rom_controls.iter().flat_map(|i| i.controller_controls.keys()))
+            .collect();
+        let mut maps = vec![(None, self.resolve_keyboard_controls(rom_controls))];
+        for name in controller_names {
+            if let Some(settings) = self.resolve_controller_controls(rom_controls, name) {
+                maps.extend([(Some(name), &settings.buttons), (Some(name), &settings.axis)]);
+            }
+        }
+        let all_maps = || maps.iter().copied();
+
+        // Per-ROM bindings replace global ones on the same input
+        if let Some(rom_controls) = rom_controls {
+            let mut shadowed = |device: Option<&String>, rom: &ControlMap, global: &ControlMap| {
+                for (code, setting) in rom {
+                    if let Some(global_setting) = global.get(code) && global_setting != setting {
+                        warnings.push(ControlsWarning::DuplicateBinding {
+                            device: device.cloned(),
+                            code: *code,
+                            kept: *setting,
+                            dropped: *global_setting
+                        });
+                    }
+                }
+            };
+            shadowed(None, &rom_controls.keyboard_controls, &self.keyboard_controls);
+            for (name, rom) in &rom_controls.controller_controls {
+                if let Some(global) = self.controller_controls.get(name) {
+                    shadowed(Some(name), &rom.buttons, &global.buttons);
+                    shadowed(Some(name), &rom.axis, &global.axis);
+                }
+            }
+        }
+
+        // Keycodes remapped to the same key keep the control bound to the greater keycode
+        for map in std::iter::once(&self.keyboard_controls).chain(rom_controls.map(|i| &i.keyboard_controls)) {
+            let mut remapped = ControlMap::new();
+            for (keycode, setting) in map {
+                let code = remap_keycode(*keycode);
+                if let Some(dropped) = remapped.insert(code, *setting) && dropped != *setting {
+                    warnings.push(ControlsWarning::DuplicateBinding { device: None, code, kept: *setting, dropped });
+                }
+            }
+        }
+
+        for (device, map) in all_maps() {
+            for (code, setting) in map {
+                if !setting.control.is_button() && setting.modifier != ControlModifier::Normal {
+                    warnings.push(ControlsWarning::IgnoredModifier {
+                        device: device.cloned(),
+                        code: *code,
+                        setting: *setting
+                    });
+                }
+            }
+        }
+
+        // A toggled button stays held, so holding it with another binding does nothing
+        let buttons: Vec<ControlSetting> = all_maps().flat_map(|(_, map)| map.values().copied()).filter(|i| i.control.is_button()).collect();
+        let mut conflicts = Vec::new();
+        for setting in buttons.iter().filter(|i| i.modifier == ControlModifier::Toggle) {
+            if !conflicts.contains(&setting.control) && buttons.iter().any(|i| i.control == setting.control && i.modifier != ControlModifier::Toggle) {
+                conflicts.push(setting.control);
+            }
+        }
+        warnings.extend(conflicts.into_iter().map(|control| ControlsWarning::ModifierConflict { control }));
+
+        for control in Control::ESSENTIAL {
+            let bound = all_maps().any(|(_, map)| map.values().any(|i| i.control == control));
+            if !bound {
+                warnings.push(ControlsWarning::MissingBinding { control });
+            }
+        }
+
+        warnings
+    }
+
+    /// Get the controls for a controller, preferring `rom_controls` (if set) over these.
+    pub fn resolve_controller_controls<'a>(&'a self, rom_controls: Option<&'a Controls>, controller: &str) -> Option<&'a ControllerSettings> {
+        rom_controls
+            .and_then(|i| i.controller_controls.get(controller))
+            .or_else(|| self.controller_controls.get(controller))
+    }
+}
+
+/// Describes a potential problem with a set of controls.
+///
+/// `device` is `None` for the keyboard.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ControlsWarning {
+    /// An essential control is not bound on any device.
+    MissingBinding { control: Control },
+
+    /// Two bindings end up on the same input, so only `kept` works.
+    ///
+    /// This happens when a per-ROM binding replaces a global one, or when two keycodes are remapped
+    /// to the same key.
+    DuplicateBinding { device: Option<String>, code: i32, kept: ControlSetting, dropped: ControlSetting },
+
+    /// A button is bound as a toggle and also with another modifier, which does nothing while the
+    /// button is toggled on.
+    ModifierConflict { control: Control },
+
+    /// A non-button control has a modifier, which will be ignored.
+    IgnoredModifier { device: Option<String>, code: i32, setting: ControlSetting }
+}
+
+impl Display for ControlsWarning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let device_name = |device: &Option<String>| device.as_ref().map(|i| i.as_str()).unwrap_or("keyboard").to_owned();
+        match self {
+            ControlsWarning::MissingBinding { control } => write!(f, "{} is not bound to anything", control.as_str()),
+            ControlsWarning::DuplicateBinding { device, code, kept, dropped } => write!(
+                f,
+                "{} (input {code} on {}) replaces {}",
+                kept.control.as_str(),
+                device_name(device),
+                dropped.control.as_str()
+            ),
+            ControlsWarning::ModifierConflict { control } => write!(
+                f,
+                "{} is bound as a toggle, so its other bindings do nothing while it is toggled on",
+                control.as_str()
+            ),
+            ControlsWarning::IgnoredModifier { device, code, setting } => write!(
+                f,
+                "{} (input {code} on {}) cannot use the {} modifier",
+                setting.control.as_str(),
+                device_name(device),
+                setting.modifier.as_str()
+            )
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Default)]
 pub struct ControllerSettings {
     #[serde(default = "BTreeMap::default")]
     pub buttons: ControlMap,
 
     #[serde(default = "BTreeMap::default")]
     pub axis: ControlMap,
+
+    /// GUID of the controller (e.g. from SDL), if known, so control profiles can be matched to the
+    /// same model even if it reports a different name
+    #[serde(default = "Option::default")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub guid: Option<String>
+}
+
+/// Controls saved to a standalone file so they can be shared (e.g. mappings for a popular
+/// controller).
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct ControlProfile {
+    /// Version of the profile format (see [`ControlProfile::VERSION`])
+    #[serde(default = "ControlProfile::VERSION")]
+    pub version: u32,
+
+    /// Name to show for the profile
+    #[serde(default = "String::default")]
+    pub name: String,
+
+    /// Keyboard bindings, if any; these replace the keyboard controls when installed
+    #[serde(default = "BTreeMap::default")]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub keyboard_controls: ControlMap,
+
+    #[serde(default = "Vec::default")]
+    pub controllers: Vec<ControlProfileController>
+}
+
+/// Bindings for one controller in a [`ControlProfile`].
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct ControlProfileController {
+    /// Name the controller reports
+    pub name: String,
+
+    /// GUID of the controller, if known
+    #[serde(default = "Option::default")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub guid: Option<String>,
+
+    #[serde(default = "BTreeMap::default")]
+    pub buttons: ControlMap,
+
+    #[serde(default = "BTreeMap::default")]
+    pub axis: ControlMap
+}
+
+impl ControlProfile {
+    /// Current version of the profile format.
+    pub const VERSION: fn() -> u32 = || 1;
+
+    /// Make a profile out of `controls`.
+    ///
+    /// If `controller` is set, only that controller is included (and not the keyboard).
+    /// `guid_for` returns the GUID for controllers that don't have one saved, if known.
+    pub fn from_controls<F: Fn(&str) -> Option<String>>(controls: &Controls, name: &str, controller: Option<&str>, guid_for: F) -> Result<Self, String> {
+        let controllers: Vec<ControlProfileController> = controls.controller_controls
+            .iter()
+            .filter(|(controller_name, _)| controller.is_none_or(|c| c == controller_name.as_str()))
+            .map(|(controller_name, settings)| ControlProfileController {
+                name: controller_name.clone(),
+                guid: settings.guid.clone().or_else(|| guid_for(controller_name)),
+                buttons: settings.buttons.clone(),
+                axis: settings.axis.clone()
+            })
+            .collect();
+
+        if let Some(controller) = controller && controllers.is_empty() {
+            return Err(format!("{controller} has no controls to export"))
+        }
+
+        Ok(Self {
+            version: Self::VERSION(),
+            name: name.to_owned(),
+            keyboard_controls: if controller.is_none() { controls.keyboard_controls.clone() } else { ControlMap::new() },
+            controllers
+        })
+    }
+
+    /// Parse a profile, rejecting ones from newer versions.
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let profile: Self = serde_json::from_str(json).map_err(|e| format!("Not a valid control profile: {e}"))?;
+        if profile.version > Self::VERSION() {
+            return Err(format!("This control profile is for a newer version (format {}, expected {})", profile.version, Self::VERSION()))
+        }
+        Ok(profile)
+    }
+
+    /// Install the profile into `controls`, replacing the bindings of each controller in it.
+    ///
+    /// Controllers are installed under the name of a connected controller with the same GUID, if
+    /// any, since the same model may report a different name on another platform.
+    /// `connected` is the name and GUID (if known) of each connected controller.
+    pub fn install_into(&self, controls: &mut Controls, connected: &[(&str, Option<&str>)]) {
+        if !self.keyboard_controls.is_empty() {
+            controls.keyboard_controls = self.keyboard_controls.clone();
+        }
+
+        for controller in &self.controllers {
+            let connected_name = controller.guid.as_deref().and_then(|guid| {
+                connected.iter().find(|(_, g)| *g == Some(guid)).map(|(name, _)| *name)
+            });
+            let name = connected_name.unwrap_or(controller.name.as_str());
+            controls.controller_controls.insert(name.to_owned(), ControllerSettings {
+                buttons: controller.buttons.clone(),
+                axis: controller.axis.clone(),
+                guid: controller.guid.clone()
+            });
+        }
+    }
+}
+
+/// A key on a keyboard by its physical location (i.e. scancode), regardless of keyboard layout.
+///
+/// Keys are named after the key in that location on a US QWERTY keyboard.
+#[derive(Copy, Clone, Debug, PartialEq, TryFromPrimitive)]
+#[repr(u32)]
+pub enum PhysicalKey {
+    ArrowUp,
+    ArrowDown,
+    ArrowLeft,
+    ArrowRight,
+
+    KeyA,
+    KeyP,
+    KeyS,
+    KeyX,
+    KeyZ,
+
+    Enter,
+    Backspace,
+    Tab
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -313,9 +1198,42 @@ pub enum Control {
 
     Turbo,
     Reset,
-    Pause
+    Pause,
+
+    SeekForward,
+    SeekBackward,
+
+    SpeedUp,
+    SlowDown,
+
+    SoftReset,
+    PowerCycleClearSRAM,
+
+    TiltUp,
+    TiltDown,
+    TiltLeft,
+    TiltRight,
+
+    ToggleOnionSkin,
+
+    NextSaveProfile,
+    PreviousSaveProfile,
+
+    FrameAdvance
 }
 impl Control {
+    /// Controls that are needed to play most games.
+    pub const ESSENTIAL: [Control; 8] = [
+        Control::Up,
+        Control::Down,
+        Control::Left,
+        Control::Right,
+        Control::A,
+        Control::B,
+        Control::Start,
+        Control::Select
+    ];
+
     pub const fn is_button(self) -> bool {
         match self {
             Control::A => true,
@@ -332,10 +1250,32 @@ impl Control {
             Control::Y => true,
             Control::Turbo => false,
             Control::Reset => false,
-            Control::Pause => false
+            Control::Pause => false,
+            Control::SeekForward => false,
+            Control::SeekBackward => false,
+            Control::SpeedUp => false,
+            Control::SlowDown => false,
+            Control::SoftReset => false,
+            Control::PowerCycleClearSRAM => false,
+            Control::TiltUp => false,
+            Control::TiltDown => false,
+            Control::TiltLeft => false,
+            Control::TiltRight => false,
+            Control::ToggleOnionSkin => false,
+            Control::NextSaveProfile => false,
+            Control::PreviousSaveProfile => false,
+            Control::FrameAdvance => false
         }
     }
 
+    /// Return true if this control can be bound to an input.
+    ///
+    /// [`Control::PowerCycleClearSRAM`] deletes save data with no way to confirm it first, so it is
+    /// only available from the frontend's menus.
+    pub const fn is_bindable(self) -> bool {
+        !matches!(self, Control::PowerCycleClearSRAM)
+    }
+
     /// Return true if this button spoils something.
     pub const fn is_spoiler(self) -> bool {
         match self {
@@ -364,6 +1304,66 @@ impl Control {
             Control::Turbo => {}
             Control::Reset => {}
             Control::Pause => {}
+            Control::SeekForward => {}
+            Control::SeekBackward => {}
+            Control::SpeedUp => {}
+            Control::SlowDown => {}
+            Control::SoftReset => {}
+            Control::PowerCycleClearSRAM => {}
+            Control::TiltUp => {}
+            Control::TiltDown => {}
+            Control::TiltLeft => {}
+            Control::TiltRight => {}
+            Control::ToggleOnionSkin => {}
+            Control::NextSaveProfile => {}
+            Control::PreviousSaveProfile => {}
+            Control::FrameAdvance => {}
+        }
+    }
+
+    /// Get the part of the input this control sets, if any.
+    pub const fn input_kind(self) -> Option<InputKind> {
+        match self {
+            Control::A => Some(InputKind::A),
+            Control::B => Some(InputKind::B),
+            Control::Start => Some(InputKind::Start),
+            Control::Select => Some(InputKind::Select),
+            Control::Up => Some(InputKind::DUp),
+            Control::Down => Some(InputKind::DDown),
+            Control::Left => Some(InputKind::DLeft),
+            Control::Right => Some(InputKind::DRight),
+            Control::L => Some(InputKind::L),
+            Control::R => Some(InputKind::R),
+            Control::X => Some(InputKind::X),
+            Control::Y => Some(InputKind::Y),
+            Control::TiltUp => Some(InputKind::Tilt),
+            Control::TiltDown => Some(InputKind::Tilt),
+            Control::TiltLeft => Some(InputKind::Tilt),
+            Control::TiltRight => Some(InputKind::Tilt),
+            Control::Turbo => None,
+            Control::Reset => None,
+            Control::Pause => None,
+            Control::SeekForward => None,
+            Control::SeekBackward => None,
+            Control::SpeedUp => None,
+            Control::SlowDown => None,
+            Control::SoftReset => None,
+            Control::PowerCycleClearSRAM => None,
+            Control::ToggleOnionSkin => None,
+            Control::NextSaveProfile => None,
+            Control::PreviousSaveProfile => None,
+            Control::FrameAdvance => None
+        }
+    }
+
+    /// Get the tilt direction index (up, down, left, right), if this is a tilt control.
+    pub(crate) const fn tilt_direction(self) -> Option<usize> {
+        match self {
+            Control::TiltUp => Some(0),
+            Control::TiltDown => Some(1),
+            Control::TiltLeft => Some(2),
+            Control::TiltRight => Some(3),
+            _ => None
         }
     }
 
@@ -384,6 +1384,20 @@ impl Control {
             Control::Turbo => {}
             Control::Reset => {}
             Control::Pause => {}
+            Control::SeekForward => {}
+            Control::SeekBackward => {}
+            Control::SpeedUp => {}
+            Control::SlowDown => {}
+            Control::SoftReset => {}
+            Control::PowerCycleClearSRAM => {}
+            Control::TiltUp => {}
+            Control::TiltDown => {}
+            Control::TiltLeft => {}
+            Control::TiltRight => {}
+            Control::ToggleOnionSkin => {}
+            Control::NextSaveProfile => {}
+            Control::PreviousSaveProfile => {}
+            Control::FrameAdvance => {}
         }
     }
 
@@ -413,7 +1427,21 @@ impl Control {
             Control::Y => c"Y",
             Control::Turbo => c"Turbo",
             Control::Reset => c"Reset console",
-            Control::Pause => c"Pause"
+            Control::Pause => c"Pause",
+            Control::SeekForward => c"Seek forward (replay)",
+            Control::SeekBackward => c"Seek backward (replay)",
+            Control::SpeedUp => c"Speed up",
+            Control::SlowDown => c"Slow down",
+            Control::SoftReset => c"Soft reset console",
+            Control::PowerCycleClearSRAM => c"Power cycle (clear save data)",
+            Control::TiltUp => c"Tilt up",
+            Control::TiltDown => c"Tilt down",
+            Control::TiltLeft => c"Tilt left",
+            Control::TiltRight => c"Tilt right",
+            Control::ToggleOnionSkin => c"Toggle onion skin",
+            Control::NextSaveProfile => c"Next save file",
+            Control::PreviousSaveProfile => c"Previous save file",
+            Control::FrameAdvance => c"Frame advance"
         }
     }
 }
@@ -0,0 +1,44 @@
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+use serde::{Deserialize, Serialize};
+use crate::file_store::FileStore;
+
+/// Play statistics for a single ROM.
+#[derive(Copy, Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct ROMStats {
+    /// Total time spent playing (i.e. with the game loaded and not paused), in milliseconds.
+    #[serde(default = "u64::default")]
+    pub playtime_milliseconds: u64,
+
+    /// Number of times the ROM was loaded.
+    #[serde(default = "u64::default")]
+    pub sessions: u64,
+
+    /// Time the ROM was last loaded, in seconds since the Unix epoch.
+    #[serde(default = "Option::default")]
+    pub last_played: Option<u64>,
+
+    /// Number of times the console was hard reset or power cycled.
+    #[serde(default = "u64::default")]
+    pub hard_resets: u64
+}
+
+impl ROMStats {
+    /// Read stats from the given path, returning default stats if it doesn't exist or is unreadable.
+    pub(crate) fn read_or_default(files: &dyn FileStore, path: &Path) -> Self {
+        files.read_to_string(path)
+            .ok()
+            .and_then(|i| serde_json::from_str(&i).ok())
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn write(&self, files: &dyn FileStore, path: &Path) {
+        let _ = files.write(path, serde_json::to_string_pretty(self).expect("failed to serialize").as_bytes());
+    }
+
+    /// Record a new session starting now.
+    pub(crate) fn begin_session(&mut self) {
+        self.sessions = self.sessions.saturating_add(1);
+        self.last_played = SystemTime::now().duration_since(UNIX_EPOCH).ok().map(|i| i.as_secs());
+    }
+}
@@ -1,6 +1,7 @@
 use std::ffi::{CStr, CString};
 use std::fmt::Formatter;
 use std::str::FromStr;
+use crate::locale::LocalizedError;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde::de::{Error, Visitor};
 
@@ -113,3 +114,76 @@ impl From<String> for UTF8CString {
         Self { inner }
     }
 }
+
+/// Characters that can't be used in a name on at least one supported platform.
+const RESERVED_NAME_CHARACTERS: &[char] = &['<', '>', ':', '"', '|', '?', '*'];
+
+/// Names reserved by Windows, regardless of extension.
+const RESERVED_WINDOWS_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL",
+    "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8", "COM9",
+    "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9"
+];
+
+/// Validate a user-provided name for a save state or replay, returning it normalized.
+///
+/// Names may be namespaced into subfolders with `/` or `\`, e.g. `practice/boss3`. Each part of the
+/// name must be non-empty, must not be `.` or `..`, and must be a valid filename on all supported
+/// platforms. Returns an error describing the problem otherwise.
+pub fn sanitize_user_name(name: &str) -> Result<String, LocalizedError> {
+    if name.is_empty() {
+        return Err(LocalizedError::new("name_empty"))
+    }
+
+    let mut components = Vec::new();
+    for component in name.split(['/', '\\']) {
+        if component.is_empty() {
+            return Err(LocalizedError::new("name_empty_folder").arg("name", name))
+        }
+        if component == "." || component == ".." {
+            return Err(LocalizedError::new("name_dot_folder").arg("name", name))
+        }
+        if let Some(c) = component.chars().find(|c| c.is_control() || RESERVED_NAME_CHARACTERS.contains(c)) {
+            return Err(LocalizedError::new("name_invalid_character").arg("name", name).arg("character", format!("{c:?}")))
+        }
+        if component.ends_with(['.', ' ']) || component.starts_with(' ') {
+            return Err(LocalizedError::new("name_invalid_spacing").arg("name", name))
+        }
+
+        let stem = component.split('.').next().unwrap_or(component);
+        if RESERVED_WINDOWS_NAMES.iter().any(|r| r.eq_ignore_ascii_case(stem)) {
+            return Err(LocalizedError::new("name_reserved").arg("name", name).arg("reserved", stem))
+        }
+
+        components.push(component);
+    }
+
+    Ok(components.join("/"))
+}
+
+/// Format seconds since the Unix epoch as a UTC timestamp usable in filenames, e.g.
+/// `2024-03-09_17-05-42`.
+///
+/// These sort chronologically when sorted as strings.
+pub fn format_filename_timestamp(seconds_since_epoch: u64) -> String {
+    let days = (seconds_since_epoch / 86400) as i64;
+    let seconds_of_day = seconds_since_epoch % 86400;
+
+    // Convert days to a civil date (Howard Hinnant's days_from_civil, reversed)
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z.rem_euclid(146097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{year:04}-{month:02}-{day:02}_{:02}-{:02}-{:02}",
+        seconds_of_day / 3600,
+        seconds_of_day / 60 % 60,
+        seconds_of_day % 60
+    )
+}
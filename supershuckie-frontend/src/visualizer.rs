@@ -0,0 +1,119 @@
+use supershuckie_core::emulator::ScreenData;
+use supershuckie_core::WatchedMemoryRange;
+
+/// Identifies a visualizer added with [`SuperShuckieFrontend::add_visualizer`](crate::SuperShuckieFrontend::add_visualizer).
+pub type VisualizerId = u32;
+
+/// Draws an overlay on top of the game's screens, such as a map viewer, hitboxes, or a stat HUD.
+///
+/// Visualizers run on the frontend's thread whenever a new frame is shown.
+pub trait Visualizer {
+    /// Get the memory to read for each frame.
+    ///
+    /// This is only called once, when the visualizer is added.
+    fn watched_memory(&self) -> Vec<WatchedMemoryRange>;
+
+    /// Draw an overlay for a finished frame.
+    ///
+    /// `memory` holds one buffer per range returned by [`Visualizer::watched_memory`], in order.
+    /// `overlays` holds one cleared (fully transparent) overlay per screen, with the same size and
+    /// encoding, which is alpha blended onto the screen.
+    ///
+    /// Return `false` if nothing was drawn.
+    fn visualize(&mut self, screens: &[ScreenData], memory: &[Vec<u8>], overlays: &mut [ScreenData]) -> bool;
+}
+
+struct RegisteredVisualizer {
+    id: VisualizerId,
+    visualizer: Box<dyn Visualizer>,
+    watched_memory: Vec<WatchedMemoryRange>
+}
+
+/// All visualizers added to a frontend.
+#[derive(Default)]
+pub(crate) struct Visualizers {
+    visualizers: Vec<RegisteredVisualizer>,
+    next_id: VisualizerId,
+    overlays: Vec<ScreenData>
+}
+
+impl Visualizers {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.visualizers.is_empty()
+    }
+
+    pub(crate) fn add(&mut self, visualizer: Box<dyn Visualizer>) -> VisualizerId {
+        // 0 is never used so that it can mean "no visualizer" in the C API
+        self.next_id = self.next_id.wrapping_add(1).max(1);
+        let id = self.next_id;
+
+        let watched_memory = visualizer.watched_memory();
+        self.visualizers.push(RegisteredVisualizer { id, visualizer, watched_memory });
+        id
+    }
+
+    pub(crate) fn remove(&mut self, id: VisualizerId) -> bool {
+        let Some(index) = self.visualizers.iter().position(|i| i.id == id) else {
+            return false
+        };
+        self.visualizers.remove(index);
+        true
+    }
+
+    /// Get the memory every visualizer needs, in order.
+    pub(crate) fn watched_memory(&self) -> Vec<WatchedMemoryRange> {
+        self.visualizers.iter().flat_map(|i| i.watched_memory.iter().copied()).collect()
+    }
+
+    /// Run each visualizer, compositing its overlays onto `screens`.
+    ///
+    /// `memory` is what was read for [`Visualizers::watched_memory`]; it may be empty if it has
+    /// not been read yet, in which case visualizers get zeroed memory.
+    pub(crate) fn apply(&mut self, screens: &mut [ScreenData], memory: &[Vec<u8>]) {
+        let mut memory = memory.iter();
+
+        for registered in &mut self.visualizers {
+            let visualizer_memory: Vec<Vec<u8>> = registered.watched_memory
+                .iter()
+                .map(|range| memory.next().filter(|i| i.len() == range.length).cloned().unwrap_or_else(|| vec![0; range.length]))
+                .collect();
+
+            self.overlays.resize_with(screens.len(), || ScreenData { pixels: Vec::new(), width: 0, height: 0, encoding: screens[0].encoding });
+            for (overlay, screen) in self.overlays.iter_mut().zip(screens.iter()) {
+                overlay.width = screen.width;
+                overlay.height = screen.height;
+                overlay.encoding = screen.encoding;
+                overlay.pixels.clear();
+                overlay.pixels.resize(screen.pixels.len(), 0);
+            }
+
+            if !registered.visualizer.visualize(screens, &visualizer_memory, &mut self.overlays) {
+                continue
+            }
+
+            for (screen, overlay) in screens.iter_mut().zip(self.overlays.iter()) {
+                for (pixel, overlay_pixel) in screen.pixels.iter_mut().zip(overlay.pixels.iter()) {
+                    *pixel = blend_a8r8g8b8(*pixel, *overlay_pixel);
+                }
+            }
+        }
+    }
+}
+
+/// Alpha blend `top` onto `bottom`, keeping `bottom`'s alpha.
+fn blend_a8r8g8b8(bottom: u32, top: u32) -> u32 {
+    let alpha = top >> 24;
+    match alpha {
+        0 => return bottom,
+        0xFF => return (bottom & 0xFF000000) | (top & 0xFFFFFF),
+        _ => ()
+    }
+
+    let channel = |shift: u32| {
+        let b = (bottom >> shift) & 0xFF;
+        let t = (top >> shift) & 0xFF;
+        ((t * alpha + b * (0xFF - alpha)) / 0xFF) << shift
+    };
+
+    (bottom & 0xFF000000) | channel(16) | channel(8) | channel(0)
+}
@@ -0,0 +1,50 @@
+/// Size of the header written by [`wav_header`].
+pub(crate) const WAV_HEADER_SIZE: usize = 44;
+
+/// Offset of the RIFF chunk size, which is the file size minus 8.
+pub(crate) const WAV_RIFF_SIZE_OFFSET: u64 = 4;
+
+/// Offset of the data chunk size.
+pub(crate) const WAV_DATA_SIZE_OFFSET: u64 = 40;
+
+const CHANNELS: u16 = 2;
+const BYTES_PER_SAMPLE: u16 = 2;
+
+/// Make the header of a 16-bit stereo PCM WAV file with `data_size` bytes of samples.
+///
+/// Streams written before their length is known can write a size of 0 and patch the sizes at
+/// [`WAV_RIFF_SIZE_OFFSET`] and [`WAV_DATA_SIZE_OFFSET`] when finished.
+pub(crate) fn wav_header(sample_rate: u32, data_size: u32) -> [u8; WAV_HEADER_SIZE] {
+    let block_align = CHANNELS * BYTES_PER_SAMPLE;
+
+    let mut header = [0u8; WAV_HEADER_SIZE];
+    header[0..4].copy_from_slice(b"RIFF");
+    header[4..8].copy_from_slice(&riff_size(data_size).to_le_bytes());
+    header[8..12].copy_from_slice(b"WAVE");
+    header[12..16].copy_from_slice(b"fmt ");
+    header[16..20].copy_from_slice(&16u32.to_le_bytes());
+    header[20..22].copy_from_slice(&1u16.to_le_bytes()); // PCM
+    header[22..24].copy_from_slice(&CHANNELS.to_le_bytes());
+    header[24..28].copy_from_slice(&sample_rate.to_le_bytes());
+    header[28..32].copy_from_slice(&(sample_rate * block_align as u32).to_le_bytes());
+    header[32..34].copy_from_slice(&block_align.to_le_bytes());
+    header[34..36].copy_from_slice(&(BYTES_PER_SAMPLE * 8).to_le_bytes());
+    header[36..40].copy_from_slice(b"data");
+    header[40..44].copy_from_slice(&data_size.to_le_bytes());
+    header
+}
+
+/// Get the RIFF chunk size of a file with `data_size` bytes of samples.
+pub(crate) const fn riff_size(data_size: u32) -> u32 {
+    data_size.saturating_add(WAV_HEADER_SIZE as u32 - 8)
+}
+
+/// Append interleaved samples (as from
+/// [`drain_audio_samples`](supershuckie_core::ThreadedSuperShuckieCore::drain_audio_samples)) as
+/// WAV sample data.
+pub(crate) fn append_samples(data: &mut Vec<u8>, samples: &[i16]) {
+    data.reserve(samples.len() * BYTES_PER_SAMPLE as usize);
+    for sample in samples {
+        data.extend_from_slice(&sample.to_le_bytes());
+    }
+}
@@ -0,0 +1,168 @@
+use crate::png::crc32;
+use miniz_oxide::inflate::decompress_to_vec_with_limit;
+
+const LOCAL_HEADER_SIGNATURE: u32 = 0x04034B50;
+const CENTRAL_HEADER_SIGNATURE: u32 = 0x02014B50;
+const END_OF_CENTRAL_DIRECTORY_SIGNATURE: u32 = 0x06054B50;
+
+const LOCAL_HEADER_SIZE: usize = 30;
+const CENTRAL_HEADER_SIZE: usize = 46;
+const END_OF_CENTRAL_DIRECTORY_SIZE: usize = 22;
+
+const METHOD_STORED: u16 = 0;
+const METHOD_DEFLATE: u16 = 8;
+
+const FLAG_ENCRYPTED: u16 = 1 << 0;
+const FLAG_UTF8: u16 = 1 << 11;
+
+/// Largest file that can be extracted, which is the size of the largest Game Boy ROM (MBC5).
+const MAX_EXTRACTED_SIZE: usize = 8 * 1024 * 1024;
+
+/// A file in a [`ZipArchive`].
+pub(crate) struct ZipEntry {
+    /// Path of the file in the archive, with `/` as the separator
+    pub name: String,
+    method: u16,
+    flags: u16,
+    crc32: u32,
+    compressed_size: usize,
+    uncompressed_size: usize,
+    local_header_offset: usize
+}
+
+impl ZipEntry {
+    /// Returns true if this is a directory rather than a file.
+    pub fn is_directory(&self) -> bool {
+        self.name.ends_with('/')
+    }
+
+    /// Get the filename without any directories.
+    pub fn filename(&self) -> &str {
+        self.name.rsplit('/').next().unwrap_or(&self.name)
+    }
+}
+
+/// Minimal ZIP archive reader.
+///
+/// Only stored and deflated files are supported, which covers just about every ROM archive. ZIP64
+/// and encrypted archives are rejected.
+pub(crate) struct ZipArchive<'a> {
+    data: &'a [u8],
+    entries: Vec<ZipEntry>
+}
+
+impl<'a> ZipArchive<'a> {
+    /// Read the central directory of `data`.
+    pub fn parse(data: &'a [u8]) -> Result<Self, String> {
+        let eocd = find_end_of_central_directory(data).ok_or("Not a ZIP archive (no end of central directory)")?;
+        let entry_count = read_u16(data, eocd + 10) as usize;
+        let directory_size = read_u32(data, eocd + 12) as usize;
+        let directory_offset = read_u32(data, eocd + 16) as usize;
+
+        if entry_count == u16::MAX as usize || directory_offset == u32::MAX as usize {
+            return Err("ZIP64 archives are not supported".to_owned())
+        }
+
+        let directory = directory_offset.checked_add(directory_size)
+            .and_then(|end| data.get(directory_offset..end))
+            .ok_or("ZIP central directory is out of bounds")?;
+
+        let mut entries = Vec::with_capacity(entry_count);
+        let mut offset = 0;
+        for _ in 0..entry_count {
+            let header = directory.get(offset..offset + CENTRAL_HEADER_SIZE).ok_or("ZIP central directory is truncated")?;
+            if read_u32(header, 0) != CENTRAL_HEADER_SIGNATURE {
+                return Err("ZIP central directory is corrupt".to_owned())
+            }
+
+            let flags = read_u16(header, 8);
+            let name_length = read_u16(header, 28) as usize;
+            let extra_length = read_u16(header, 30) as usize;
+            let comment_length = read_u16(header, 32) as usize;
+
+            let name_start = offset + CENTRAL_HEADER_SIZE;
+            let name = directory.get(name_start..name_start + name_length).ok_or("ZIP central directory is truncated")?;
+            let name = if flags & FLAG_UTF8 != 0 {
+                String::from_utf8_lossy(name).into_owned()
+            }
+            else {
+                // Technically code page 437, but anything outside ASCII is rare enough in ROM names
+                name.iter().map(|&c| if c.is_ascii() { c as char } else { char::REPLACEMENT_CHARACTER }).collect()
+            };
+
+            entries.push(ZipEntry {
+                name,
+                method: read_u16(header, 10),
+                flags,
+                crc32: read_u32(header, 16),
+                compressed_size: read_u32(header, 20) as usize,
+                uncompressed_size: read_u32(header, 24) as usize,
+                local_header_offset: read_u32(header, 42) as usize
+            });
+
+            offset = name_start + name_length + extra_length + comment_length;
+        }
+
+        Ok(Self { data, entries })
+    }
+
+    /// Get all entries, including directories, in the order they are stored.
+    pub fn entries(&self) -> &[ZipEntry] {
+        &self.entries
+    }
+
+    /// Decompress an entry and verify its checksum.
+    ///
+    /// Entries larger than the largest Game Boy ROM are rejected without being decompressed.
+    pub fn extract(&self, entry: &ZipEntry) -> Result<Vec<u8>, String> {
+        if entry.flags & FLAG_ENCRYPTED != 0 {
+            return Err(format!("{} is encrypted", entry.name))
+        }
+        if entry.compressed_size == u32::MAX as usize || entry.uncompressed_size == u32::MAX as usize {
+            return Err("ZIP64 archives are not supported".to_owned())
+        }
+        if entry.uncompressed_size > MAX_EXTRACTED_SIZE {
+            return Err(format!("{} is too large to be a ROM", entry.name))
+        }
+
+        let header = self.data
+            .get(entry.local_header_offset..entry.local_header_offset + LOCAL_HEADER_SIZE)
+            .filter(|h| read_u32(h, 0) == LOCAL_HEADER_SIGNATURE)
+            .ok_or_else(|| format!("{} has a corrupt header", entry.name))?;
+
+        // The local header's sizes may be zeroed (with the real ones after the data), so only the
+        // name and extra field lengths are used from it
+        let start = entry.local_header_offset + LOCAL_HEADER_SIZE + read_u16(header, 26) as usize + read_u16(header, 28) as usize;
+        let compressed = start.checked_add(entry.compressed_size)
+            .and_then(|end| self.data.get(start..end))
+            .ok_or_else(|| format!("{} is truncated", entry.name))?;
+
+        let data = match entry.method {
+            METHOD_STORED => compressed.to_vec(),
+            METHOD_DEFLATE => decompress_to_vec_with_limit(compressed, entry.uncompressed_size).map_err(|e| format!("{} is corrupt: {e}", entry.name))?,
+            method => return Err(format!("{} uses an unsupported compression method ({method})", entry.name))
+        };
+
+        if data.len() != entry.uncompressed_size || crc32(&data) != entry.crc32 {
+            return Err(format!("{} is corrupt (checksum mismatch)", entry.name))
+        }
+
+        Ok(data)
+    }
+}
+
+fn find_end_of_central_directory(data: &[u8]) -> Option<usize> {
+    let last = data.len().checked_sub(END_OF_CENTRAL_DIRECTORY_SIZE)?;
+
+    // The comment at the end can be up to 65535 bytes long
+    let first = last.saturating_sub(u16::MAX as usize);
+    (first..=last).rev().find(|&i| read_u32(data, i) == END_OF_CENTRAL_DIRECTORY_SIGNATURE)
+}
+
+fn read_u16(data: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([data[offset], data[offset + 1]])
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]])
+}
@@ -0,0 +1,121 @@
+mod common;
+
+use common::*;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use supershuckie_frontend::file_store::{FileStore, MemoryFileStore};
+use supershuckie_frontend::SuperShuckieFrontend;
+
+const SAMPLE_RATE: u32 = 48000;
+
+/// Tick until at least `frames` more frames have run, failing after a few seconds.
+fn run_frames(frontend: &mut SuperShuckieFrontend, frames: u32) {
+    let target = frontend.get_elapsed_frames() + frames;
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while frontend.get_elapsed_frames() < target {
+        assert!(Instant::now() < deadline, "the game never ran");
+        frontend.tick();
+        std::thread::sleep(Duration::from_millis(5));
+    }
+}
+
+/// Read the only WAV file in the store, checking its header and returning its sample rate and
+/// sample data.
+fn read_wav(store: &MemoryFileStore) -> (u32, Vec<u8>) {
+    let files = files_with_extension(store, "wav");
+    let [file] = files.as_slice() else {
+        panic!("expected one WAV file, got {files:?}")
+    };
+    let wav = store.read(file).expect("failed to read WAV file");
+    assert!(wav.len() >= 44, "WAV file is truncated");
+
+    let u16_at = |offset: usize| u16::from_le_bytes(wav[offset..offset + 2].try_into().unwrap());
+    let u32_at = |offset: usize| u32::from_le_bytes(wav[offset..offset + 4].try_into().unwrap());
+    assert_eq!(&wav[0..4], b"RIFF");
+    assert_eq!(u32_at(4) as usize, wav.len() - 8, "RIFF size doesn't match the file");
+    assert_eq!(&wav[8..16], b"WAVEfmt ");
+    assert_eq!(u16_at(20), 1, "not PCM");
+    assert_eq!(u16_at(22), 2, "not stereo");
+    assert_eq!(u16_at(34), 16, "not 16-bit");
+    assert_eq!(&wav[36..40], b"data");
+    assert_eq!(u32_at(40) as usize, wav.len() - 44, "data size doesn't match the file");
+
+    (u32_at(24), wav[44..].to_vec())
+}
+
+/// Load the test ROM with audio output on and unpause it.
+fn start_game(store: &Arc<MemoryFileStore>) -> SuperShuckieFrontend {
+    let mut frontend = new_frontend(store);
+    let rom = add_rom(&frontend, "game.gbc", &test_rom("GAME"));
+    assert!(frontend.set_audio_sample_rate(SAMPLE_RATE));
+    frontend.load_rom(&rom).expect("failed to load ROM");
+    frontend.set_paused(false);
+    frontend
+}
+
+#[test]
+fn recording_audio_writes_wav() {
+    let store = Arc::new(MemoryFileStore::new());
+    let mut frontend = start_game(&store);
+    run_frames(&mut frontend, 3);
+    frontend.create_save_state(Some("slot"), None).expect("failed to create save state");
+
+    let name = frontend.start_recording_audio(Some("music")).expect("failed to start recording audio");
+    assert_eq!(name.as_str(), "music.wav");
+    assert!(frontend.is_recording_audio());
+
+    run_frames(&mut frontend, 10);
+    assert_eq!(frontend.load_save_state_if_exists("slot"), Ok(true));
+    run_frames(&mut frontend, 10);
+    frontend.stop_recording_audio();
+    assert!(!frontend.is_recording_audio());
+
+    let (sample_rate, data) = read_wav(&store);
+    assert_eq!(sample_rate, SAMPLE_RATE);
+    assert!(!data.is_empty(), "no audio was recorded");
+    assert_eq!(data.len() % 4, 0, "partial stereo sample");
+}
+
+#[test]
+fn unloading_rom_stops_recording_audio() {
+    let store = Arc::new(MemoryFileStore::new());
+    let mut frontend = start_game(&store);
+
+    frontend.start_recording_audio(None).expect("failed to start recording audio");
+    run_frames(&mut frontend, 5);
+    frontend.close_rom();
+    assert!(!frontend.is_recording_audio());
+
+    // The sizes were patched when it was stopped
+    read_wav(&store);
+}
+
+#[test]
+fn recording_audio_needs_audio_output() {
+    let store = Arc::new(MemoryFileStore::new());
+    let mut frontend = start_game(&store);
+    frontend.set_audio_sample_rate(0);
+    assert!(frontend.start_recording_audio(None).is_err());
+    assert!(files_with_extension(&store, "wav").is_empty());
+}
+
+#[test]
+fn exported_replay_audio_matches_clip_length() {
+    let store = Arc::new(MemoryFileStore::new());
+    let mut frontend = start_game(&store);
+
+    frontend.start_recording_replay(Some("run")).expect("failed to start recording");
+    run_frames(&mut frontend, 70);
+    frontend.stop_recording_replay();
+
+    let (first_frame, last_frame) = (5, 64);
+    frontend.export_replay_audio("run", first_frame, last_frame).expect("failed to export replay audio");
+    let (sample_rate, data) = read_wav(&store);
+    assert_eq!(sample_rate, SAMPLE_RATE);
+
+    // A Game Boy frame is 70224 cycles of a 4 MiHz clock
+    let frames = (last_frame - first_frame + 1) as f64;
+    let expected = frames * 70224.0 / 4194304.0 * sample_rate as f64;
+    let actual = (data.len() / 4) as f64;
+    assert!((actual - expected).abs() / expected < 0.02, "expected about {expected} samples, got {actual}");
+}
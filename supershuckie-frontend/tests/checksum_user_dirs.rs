@@ -0,0 +1,58 @@
+mod common;
+
+use common::*;
+use std::path::Path;
+use std::sync::Arc;
+use supershuckie_frontend::file_store::{FileStore, MemoryFileStore};
+
+#[test]
+fn replacing_rom_keeps_old_sram_out_of_new_folder() {
+    let store = Arc::new(MemoryFileStore::new());
+    let mut frontend = new_frontend(&store);
+    frontend.set_key_user_dirs_by_checksum_setting(true).expect("failed to key by checksum");
+
+    // Give the second game some save data
+    let rom = add_rom(&frontend, "game.gbc", &test_rom("SECOND"));
+    frontend.load_rom(&rom).expect("failed to load second ROM");
+    frontend.save_sram().expect("failed to save second ROM's SRAM");
+    frontend.unload_rom();
+    let saves = files_with_extension(&store, "sav");
+    let [second_save] = saves.as_slice() else {
+        panic!("expected one save file, got {saves:?}")
+    };
+    let second_sram: Vec<u8> = (0..0x2000).map(|i| (i * 7) as u8).collect();
+    store.write(second_save, &second_sram).expect("failed to write SRAM");
+
+    // Play the first game under the same filename, then replace it with the second game
+    add_rom(&frontend, "game.gbc", &test_rom("FIRST"));
+    frontend.load_rom(&rom).expect("failed to load first ROM");
+    add_rom(&frontend, "game.gbc", &test_rom("SECOND"));
+    frontend.load_rom(&rom).expect("failed to reload second ROM");
+
+    assert_eq!(files_with_extension(&store, "sav").len(), 2, "the first game's SRAM was not saved separately");
+    assert!(store.read(second_save).expect("second ROM's SRAM is gone") == second_sram, "the first game's SRAM overwrote the second game's");
+
+    frontend.close_rom();
+    assert!(store.read(second_save).expect("second ROM's SRAM is gone") == second_sram, "the second game's SRAM was not loaded");
+}
+
+#[test]
+fn failing_to_move_folders_keeps_running_game() {
+    let store = Arc::new(MemoryFileStore::new());
+    let mut frontend = new_frontend(&store);
+    frontend.set_key_user_dirs_by_checksum_setting(true).expect("failed to key by checksum");
+
+    // Record the other game's checksum, then make folders under both names so they can't be moved
+    let other = add_rom(&frontend, "other.gbc", &test_rom("OTHER"));
+    frontend.load_rom(&other).expect("failed to load other ROM");
+    frontend.close_rom();
+    store.create_dir_all(&Path::new(USER_DIR).join("other.gbc-data")).expect("failed to make folder");
+
+    let game = add_rom(&frontend, "game.gbc", &test_rom("GAME"));
+    frontend.load_rom(&game).expect("failed to load ROM");
+
+    let error = frontend.load_rom(&other).expect_err("loaded a ROM whose folders couldn't be moved");
+    assert_eq!(error.key(), "rom_dirs_not_moved");
+    assert!(frontend.is_game_running(), "the running game was closed");
+    assert_eq!(frontend.get_current_rom_name(), Some("game.gbc"));
+}
@@ -0,0 +1,77 @@
+#![allow(dead_code)]
+
+use std::num::NonZeroU8;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use supershuckie_core::emulator::ScreenData;
+use supershuckie_core::CoreProgressEvent;
+use supershuckie_frontend::file_store::MemoryFileStore;
+use supershuckie_frontend::settings::PhysicalKey;
+use supershuckie_frontend::{ConnectedControllerIndex, SuperShuckieFrontend, SuperShuckieFrontendCallbacks};
+
+pub const USER_DIR: &str = "/supershuckie-memory-store/user";
+pub const ROM_DIR: &str = "/supershuckie-memory-store/roms";
+
+const NINTENDO_LOGO: [u8; 0x30] = [
+    0xCE, 0xED, 0x66, 0x66, 0xCC, 0x0D, 0x00, 0x0B, 0x03, 0x73, 0x00, 0x83, 0x00, 0x0C, 0x00, 0x0D,
+    0x00, 0x08, 0x11, 0x1F, 0x88, 0x89, 0x00, 0x0E, 0xDC, 0xCC, 0x6E, 0xE6, 0xDD, 0xDD, 0xD9, 0x99,
+    0xBB, 0xBB, 0x67, 0x63, 0x6E, 0x0E, 0xEC, 0xCC, 0xDD, 0xDC, 0x99, 0x9F, 0xBB, 0xB9, 0x33, 0x3E
+];
+
+/// Callbacks that do nothing.
+pub struct NullCallbacks;
+
+impl SuperShuckieFrontendCallbacks for NullCallbacks {
+    fn refresh_screens(&mut self, _: &[ScreenData]) {}
+    fn change_video_mode(&mut self, _: &[ScreenData], _: NonZeroU8) {}
+    fn keycode_for_physical_key(&mut self, _: PhysicalKey) -> Option<i32> { None }
+    fn show_message(&mut self, _: &str) {}
+    fn set_rumble(&mut self, _: f64) {}
+    fn set_infrared_led(&mut self, _: bool) {}
+    fn report_progress(&mut self, _: CoreProgressEvent) {}
+    fn push_audio(&mut self, _: &[i16], _: u32) {}
+    fn ui_navigation(&mut self, _: ConnectedControllerIndex, _: i32, _: bool) {}
+}
+
+/// Make a 32 KiB Game Boy Color ROM with 8 KiB of battery-backed SRAM that spins forever without
+/// touching SRAM.
+///
+/// ROMs with different titles have different checksums.
+pub fn test_rom(title: &str) -> Vec<u8> {
+    let mut rom = vec![0u8; 0x8000];
+
+    // nop; jp $0150
+    rom[0x100..0x104].copy_from_slice(&[0x00, 0xC3, 0x50, 0x01]);
+    rom[0x104..0x134].copy_from_slice(&NINTENDO_LOGO);
+
+    let title = &title.as_bytes()[..title.len().min(15)];
+    rom[0x134..0x134 + title.len()].copy_from_slice(title);
+
+    rom[0x143] = 0x80; // CGB
+    rom[0x147] = 0x03; // MBC1+RAM+BATTERY
+    rom[0x148] = 0x00; // 32 KiB ROM
+    rom[0x149] = 0x02; // 8 KiB SRAM
+    rom[0x14D] = rom[0x134..0x14D].iter().fold(0u8, |checksum, b| checksum.wrapping_sub(*b).wrapping_sub(1));
+
+    // jr -2
+    rom[0x150..0x152].copy_from_slice(&[0x18, 0xFE]);
+
+    rom
+}
+
+/// Make a frontend whose user directory is in `store`.
+pub fn new_frontend(store: &Arc<MemoryFileStore>) -> SuperShuckieFrontend {
+    SuperShuckieFrontend::new_with_file_store(USER_DIR, Box::new(NullCallbacks), Box::new(store.clone()))
+}
+
+/// Add a ROM to the store, returning its path.
+pub fn add_rom(frontend: &SuperShuckieFrontend, filename: &str, data: &[u8]) -> PathBuf {
+    let path = Path::new(ROM_DIR).join(filename);
+    frontend.write_file(&path, data).expect("failed to add ROM");
+    path
+}
+
+/// Get the paths of every file in the store with the given extension.
+pub fn files_with_extension(store: &MemoryFileStore, extension: &str) -> Vec<PathBuf> {
+    store.files().into_iter().filter(|p| p.extension().is_some_and(|e| e == extension)).collect()
+}
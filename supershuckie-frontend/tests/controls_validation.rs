@@ -0,0 +1,129 @@
+use supershuckie_frontend::settings::{Control, ControlMap, ControlModifier, ControlSetting, ControllerSettings, Controls, ControlsWarning};
+
+fn setting(control: Control, modifier: ControlModifier) -> ControlSetting {
+    ControlSetting { control, modifier }
+}
+
+fn map(bindings: &[(i32, Control, ControlModifier)]) -> ControlMap {
+    bindings.iter().map(|(code, control, modifier)| (*code, setting(*control, *modifier))).collect()
+}
+
+/// Every essential control bound once on the keyboard, at keycodes 0 through 7.
+fn essential_controls() -> Controls {
+    Controls {
+        keyboard_controls: Control::ESSENTIAL
+            .iter()
+            .enumerate()
+            .map(|(code, control)| (code as i32, setting(*control, ControlModifier::Normal)))
+            .collect(),
+        ..Controls::default()
+    }
+}
+
+fn controller(buttons: ControlMap, axis: ControlMap) -> ControllerSettings {
+    ControllerSettings { buttons, axis, guid: None }
+}
+
+#[test]
+fn default_controls_are_valid() {
+    let controls = Controls {
+        keyboard_controls: Controls::default_keyboard_controls(|key| Some(key as i32)),
+        ..Controls::default()
+    };
+    assert_eq!(controls.validate(None, |keycode| keycode), []);
+    assert_eq!(essential_controls().validate(None, |keycode| keycode), []);
+}
+
+#[test]
+fn unbound_essential_controls_are_reported() {
+    let mut controls = essential_controls();
+    controls.keyboard_controls.remove(&4);
+    assert_eq!(controls.validate(None, |keycode| keycode), [ControlsWarning::MissingBinding { control: Control::A }]);
+
+    // Controller bindings count
+    controls.controller_controls.insert("pad".to_owned(), controller(map(&[(0, Control::A, ControlModifier::Normal)]), ControlMap::new()));
+    assert_eq!(controls.validate(None, |keycode| keycode), []);
+}
+
+#[test]
+fn rom_bindings_replacing_global_ones_are_reported() {
+    let mut controls = essential_controls();
+    controls.controller_controls.insert("pad".to_owned(), controller(
+        map(&[(0, Control::A, ControlModifier::Normal), (1, Control::B, ControlModifier::Normal)]),
+        map(&[(0, Control::Left, ControlModifier::Normal)])
+    ));
+
+    let mut rom_controls = essential_controls();
+    rom_controls.keyboard_controls.insert(100, setting(Control::Start, ControlModifier::Normal));
+    rom_controls.keyboard_controls.insert(4, setting(Control::A, ControlModifier::Rapid));
+    rom_controls.controller_controls.insert("pad".to_owned(), controller(
+        map(&[(0, Control::A, ControlModifier::Normal), (1, Control::Select, ControlModifier::Normal)]),
+        map(&[(0, Control::Right, ControlModifier::Normal)])
+    ));
+
+    assert_eq!(controls.validate(Some(&rom_controls), |keycode| keycode), [
+        ControlsWarning::DuplicateBinding {
+            device: None,
+            code: 4,
+            kept: setting(Control::A, ControlModifier::Rapid),
+            dropped: setting(Control::A, ControlModifier::Normal)
+        },
+        ControlsWarning::DuplicateBinding {
+            device: Some("pad".to_owned()),
+            code: 1,
+            kept: setting(Control::Select, ControlModifier::Normal),
+            dropped: setting(Control::B, ControlModifier::Normal)
+        },
+        ControlsWarning::DuplicateBinding {
+            device: Some("pad".to_owned()),
+            code: 0,
+            kept: setting(Control::Right, ControlModifier::Normal),
+            dropped: setting(Control::Left, ControlModifier::Normal)
+        }
+    ]);
+}
+
+#[test]
+fn keycodes_remapped_to_the_same_key_are_reported() {
+    let mut controls = essential_controls();
+    controls.keyboard_controls.insert(100, setting(Control::Turbo, ControlModifier::Normal));
+    controls.keyboard_controls.insert(101, setting(Control::Pause, ControlModifier::Normal));
+
+    // Bindings for the same control collapsing into one is harmless
+    controls.keyboard_controls.insert(102, setting(Control::SpeedUp, ControlModifier::Normal));
+    controls.keyboard_controls.insert(103, setting(Control::SpeedUp, ControlModifier::Normal));
+
+    let remap = |keycode| match keycode {
+        100 => 101,
+        103 => 102,
+        keycode => keycode
+    };
+    assert_eq!(controls.validate(None, remap), [ControlsWarning::DuplicateBinding {
+        device: None,
+        code: 101,
+        kept: setting(Control::Pause, ControlModifier::Normal),
+        dropped: setting(Control::Turbo, ControlModifier::Normal)
+    }]);
+}
+
+#[test]
+fn toggled_buttons_with_other_bindings_are_reported() {
+    let mut controls = essential_controls();
+    controls.keyboard_controls.insert(100, setting(Control::A, ControlModifier::Rapid));
+    assert_eq!(controls.validate(None, |keycode| keycode), []);
+
+    controls.keyboard_controls.insert(101, setting(Control::A, ControlModifier::Toggle));
+    controls.keyboard_controls.insert(102, setting(Control::L, ControlModifier::Toggle));
+    assert_eq!(controls.validate(None, |keycode| keycode), [ControlsWarning::ModifierConflict { control: Control::A }]);
+}
+
+#[test]
+fn modifiers_on_non_buttons_are_reported() {
+    let mut controls = essential_controls();
+    controls.keyboard_controls.insert(100, setting(Control::Turbo, ControlModifier::Toggle));
+    assert_eq!(controls.validate(None, |keycode| keycode), [ControlsWarning::IgnoredModifier {
+        device: None,
+        code: 100,
+        setting: setting(Control::Turbo, ControlModifier::Toggle)
+    }]);
+}
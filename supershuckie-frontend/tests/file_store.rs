@@ -0,0 +1,98 @@
+mod common;
+
+use common::*;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use supershuckie_frontend::file_store::{FileStore, MemoryFileStore};
+
+#[test]
+fn rom_loads_from_store() {
+    let store = Arc::new(MemoryFileStore::new());
+    let mut frontend = new_frontend(&store);
+
+    assert!(frontend.load_rom(Path::new(ROM_DIR).join("missing.gbc")).is_err());
+
+    let rom = add_rom(&frontend, "game.gbc", &test_rom("GAME"));
+    frontend.load_rom(&rom).expect("failed to load ROM");
+    assert!(frontend.is_game_running());
+    assert_eq!(frontend.get_current_rom_name(), Some("game.gbc"));
+
+    frontend.close_rom();
+    assert!(!frontend.is_game_running());
+    assert!(!Path::new(USER_DIR).exists(), "the user directory was created on disk");
+}
+
+#[test]
+fn sram_round_trips_through_store() {
+    let store = Arc::new(MemoryFileStore::new());
+    let mut frontend = new_frontend(&store);
+    let rom = add_rom(&frontend, "game.gbc", &test_rom("GAME"));
+
+    frontend.load_rom(&rom).expect("failed to load ROM");
+    frontend.save_sram().expect("failed to save SRAM");
+    let saves = files_with_extension(&store, "sav");
+    let [save] = saves.as_slice() else {
+        panic!("expected one save file, got {saves:?}")
+    };
+    assert_eq!(store.read(save).expect("failed to read SRAM").len(), 0x2000);
+    assert_eq!(frontend.get_all_saves_for_rom("game.gbc").len(), 1);
+
+    // Replace the save while the game is closed, then make sure the game loaded it
+    frontend.unload_rom();
+    let sram: Vec<u8> = (0..0x2000).map(|i| (i * 7) as u8).collect();
+    store.write(save, &sram).expect("failed to write SRAM");
+
+    frontend.load_rom(&rom).expect("failed to reload ROM");
+    store.remove_file(save).expect("failed to remove SRAM");
+    frontend.close_rom();
+    assert_eq!(store.read(save).expect("SRAM was not saved on close"), sram);
+}
+
+#[test]
+fn save_states_round_trip_through_store() {
+    let store = Arc::new(MemoryFileStore::new());
+    let mut frontend = new_frontend(&store);
+    let rom = add_rom(&frontend, "game.gbc", &test_rom("GAME"));
+    frontend.load_rom(&rom).expect("failed to load ROM");
+
+    let name = frontend.create_save_state(Some("slot"), None).expect("failed to create save state");
+    assert_eq!(name.as_str(), "slot.save_state");
+    assert_eq!(files_with_extension(&store, "save_state").len(), 1);
+    assert!(frontend.get_all_save_states_for_rom("game.gbc").iter().any(|s| s.as_str() == "slot"));
+
+    assert_eq!(frontend.load_save_state_if_exists("slot"), Ok(true));
+    assert_eq!(frontend.load_save_state_if_exists("missing"), Ok(false));
+
+    let generic = frontend.create_save_state(None, None).expect("failed to create generic save state");
+    assert_ne!(generic, name);
+    assert_eq!(files_with_extension(&store, "save_state").len(), 2);
+}
+
+#[test]
+fn replays_record_into_store() {
+    let store = Arc::new(MemoryFileStore::new());
+    let mut frontend = new_frontend(&store);
+    let rom = add_rom(&frontend, "game.gbc", &test_rom("GAME"));
+    frontend.load_rom(&rom).expect("failed to load ROM");
+    frontend.set_paused(false);
+
+    let name = frontend.start_recording_replay(Some("run")).expect("failed to start recording");
+    assert_eq!(files_with_extension(&store, "replay").len(), 2, "expected the replay and its temp file");
+
+    let started = Instant::now();
+    while frontend.get_elapsed_frames() < 10 {
+        assert!(started.elapsed() < Duration::from_secs(10), "the game never ran");
+        frontend.tick();
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    frontend.stop_recording_replay();
+
+    let replays = files_with_extension(&store, "replay");
+    let [replay] = replays.as_slice() else {
+        panic!("expected only the finished replay, got {replays:?}")
+    };
+    assert_eq!(replay.file_name().and_then(|s| s.to_str()), Some(name.as_str()));
+    assert!(!store.read(replay).expect("failed to read replay").is_empty());
+    assert!(!Path::new(USER_DIR).exists(), "the user directory was created on disk");
+}
@@ -0,0 +1,53 @@
+mod common;
+
+use common::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Arc;
+use supershuckie_frontend::file_store::MemoryFileStore;
+use supershuckie_frontend::FrontendEvent;
+use supershuckie_frontend::settings::{Control, ControlModifier, ControlSetting, Controls};
+
+fn keyboard_controls(bindings: &[(i32, Control)]) -> Controls {
+    let mut controls = Controls::default();
+    for (keycode, control) in bindings {
+        controls.keyboard_controls.insert(*keycode, ControlSetting { control: *control, modifier: ControlModifier::Normal });
+    }
+    controls
+}
+
+#[test]
+fn remapping_keyboard_controls_includes_rom_controls() {
+    let store = Arc::new(MemoryFileStore::new());
+    let mut frontend = new_frontend(&store);
+    frontend.set_control_settings(keyboard_controls(&[(1, Control::A), (2, Control::B)]));
+    frontend.set_rom_control_settings("game", Some(keyboard_controls(&[(3, Control::Start)])));
+
+    frontend.remap_keyboard_controls(|keycode| keycode + 100);
+
+    assert_eq!(frontend.get_control_settings().keyboard_controls, keyboard_controls(&[(101, Control::A), (102, Control::B)]).keyboard_controls);
+    assert_eq!(frontend.get_rom_control_settings("game").map(|c| &c.keyboard_controls), Some(&keyboard_controls(&[(103, Control::Start)]).keyboard_controls));
+}
+
+#[test]
+fn remapping_keyboard_controls_reports_collisions() {
+    let store = Arc::new(MemoryFileStore::new());
+    let mut frontend = new_frontend(&store);
+    frontend.set_control_settings(keyboard_controls(&[(1, Control::A), (2, Control::B), (3, Control::Start)]));
+    frontend.set_rom_control_settings("game", Some(keyboard_controls(&[(3, Control::Select), (4, Control::Up)])));
+
+    let messages = Rc::new(RefCell::new(Vec::new()));
+    let hook_messages = messages.clone();
+    frontend.add_event_hook(Box::new(move |event| if let FrontendEvent::Message { key, .. } = event {
+        hook_messages.borrow_mut().push(key.clone());
+    }));
+
+    // 1 and 2 become the same key, as do 3 and 4
+    let dropped = frontend.remap_keyboard_controls(|keycode| (keycode + 1) / 2);
+
+    let setting = |control| ControlSetting { control, modifier: ControlModifier::Normal };
+    assert_eq!(dropped, [(1, setting(Control::A)), (3, setting(Control::Select))]);
+    assert_eq!(frontend.get_control_settings().keyboard_controls, keyboard_controls(&[(1, Control::B), (2, Control::Start)]).keyboard_controls);
+    assert_eq!(frontend.get_rom_control_settings("game").map(|c| &c.keyboard_controls), Some(&keyboard_controls(&[(2, Control::Up)]).keyboard_controls));
+    assert_eq!(*messages.borrow(), ["keyboard_controls_dropped"]);
+}
@@ -0,0 +1,38 @@
+mod common;
+
+use common::*;
+use std::path::Path;
+use std::sync::Arc;
+use supershuckie_frontend::file_store::{FileStore, MemoryFileStore};
+use supershuckie_frontend::locale::LOCALE_FOLDER;
+
+#[test]
+fn errors_have_keys_and_arguments() {
+    let store = Arc::new(MemoryFileStore::new());
+    let mut frontend = new_frontend(&store);
+    let rom = add_rom(&frontend, "game.gbc", &test_rom("GAME"));
+    frontend.load_rom(&rom).expect("failed to load ROM");
+
+    let error = frontend.scrub_replay("game", "missing", "").expect_err("scrubbed a missing replay");
+    assert_eq!(error.key(), "replay_not_found");
+    assert_eq!(error.args(), &[("name", "missing".to_owned())]);
+    assert_eq!(error.to_string(), "Replay missing does not exist");
+}
+
+#[test]
+fn errors_are_formatted_in_the_current_language() {
+    let store = Arc::new(MemoryFileStore::new());
+    let mut frontend = new_frontend(&store);
+    let locale_dir = Path::new(USER_DIR).join(LOCALE_FOLDER);
+    store.create_dir_all(&locale_dir).expect("failed to create locale folder");
+    store.write(&locale_dir.join("xx.json"), br#"{"game_not_running": "No game"}"#).expect("failed to write locale");
+    frontend.set_language("xx").expect("failed to set language");
+
+    let error = frontend.set_core_option("rtc_mode", "sync").expect_err("set a core option with no game running");
+    assert_eq!(error.key(), "game_not_running");
+    assert_eq!(error.format(frontend.get_messages()), "No game");
+
+    // Errors from loading a language have keys, too
+    let error = frontend.set_language("../xx").expect_err("loaded an invalid language name");
+    assert_eq!(error.key(), "language_invalid");
+}
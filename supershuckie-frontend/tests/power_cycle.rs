@@ -0,0 +1,37 @@
+mod common;
+
+use common::*;
+use std::sync::Arc;
+use supershuckie_frontend::file_store::{FileStore, MemoryFileStore};
+
+#[test]
+fn power_cycle_keeps_save_data_it_cannot_back_up() {
+    let store = Arc::new(MemoryFileStore::new());
+    let mut frontend = new_frontend(&store);
+    let rom = add_rom(&frontend, "game.gbc", &test_rom("GAME"));
+    frontend.load_rom(&rom).expect("failed to load ROM");
+    frontend.save_sram().expect("failed to save SRAM");
+
+    let saves = files_with_extension(&store, "sav");
+    let [save] = saves.as_slice() else {
+        panic!("expected one save file, got {saves:?}")
+    };
+    let sram: Vec<u8> = (0..0x2000).map(|i| (i * 7) as u8).collect();
+    store.write(save, &sram).expect("failed to write SRAM");
+
+    // A file where the backups go keeps them from being made
+    let backups = save.parent().expect("save has no folder").join("backups");
+    let blocker = backups.join(save.file_stem().expect("save has no name"));
+    store.create_dir_all(&backups).expect("failed to create backups folder");
+    store.write(&blocker, b"").expect("failed to block backups");
+
+    let error = frontend.power_cycle_with_fresh_sram().expect_err("power cycled without backing up save data");
+    assert_eq!(error.key(), "save_backup_failed");
+    assert!(frontend.is_game_running());
+    assert!(store.read(save).expect("save data was deleted") == sram, "save data was changed");
+
+    store.remove_file(&blocker).expect("failed to unblock backups");
+    frontend.power_cycle_with_fresh_sram().expect("failed to power cycle");
+    assert!(store.read(save).ok() != Some(sram.clone()), "save data was not deleted");
+    assert!(store.read_dir(&blocker).expect("no backups were made").iter().any(|p| store.read(p).ok() == Some(sram.clone())));
+}
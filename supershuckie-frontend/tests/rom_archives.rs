@@ -0,0 +1,179 @@
+mod common;
+
+use common::*;
+use miniz_oxide::deflate::core::deflate_flags::{TDEFL_FORCE_ALL_RAW_BLOCKS, TDEFL_FORCE_ALL_STATIC_BLOCKS};
+use miniz_oxide::deflate::core::{compress, create_comp_flags_from_zip_params, CompressorOxide, TDEFLFlush, TDEFLStatus};
+use std::sync::Arc;
+use supershuckie_frontend::file_store::MemoryFileStore;
+use supershuckie_frontend::SuperShuckieFrontend;
+
+const METHOD_STORED: u16 = 0;
+const METHOD_DEFLATE: u16 = 8;
+
+/// A file to put in a test archive.
+struct Entry<'a> {
+    name: &'a str,
+    method: u16,
+    data: Vec<u8>,
+    crc32: u32,
+    uncompressed_size: u32
+}
+
+impl<'a> Entry<'a> {
+    fn stored(name: &'a str, data: &[u8]) -> Self {
+        Self { name, method: METHOD_STORED, data: data.to_vec(), crc32: crc32(data), uncompressed_size: data.len() as u32 }
+    }
+
+    fn deflated(name: &'a str, data: &[u8], flags: u32) -> Self {
+        Self { name, method: METHOD_DEFLATE, data: deflate(data, flags), crc32: crc32(data), uncompressed_size: data.len() as u32 }
+    }
+}
+
+fn deflate(data: &[u8], flags: u32) -> Vec<u8> {
+    let mut compressor = CompressorOxide::new(flags);
+    let mut output = vec![0; data.len() * 2 + 1024];
+    let (status, _, length) = compress(&mut compressor, data, &mut output, TDEFLFlush::Finish);
+    assert_eq!(status, TDEFLStatus::Done);
+    output.truncate(length);
+    output
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0u32, |crc, &byte| {
+        (0..8).fold(crc ^ byte as u32, |crc, _| if crc & 1 != 0 { (crc >> 1) ^ 0xEDB88320 } else { crc >> 1 })
+    })
+}
+
+fn make_archive(entries: &[Entry]) -> Vec<u8> {
+    let mut archive = Vec::new();
+    let mut directory = Vec::new();
+
+    for entry in entries {
+        let offset = archive.len() as u32;
+        let mut common = Vec::new();
+        common.extend_from_slice(&20u16.to_le_bytes()); // version needed
+        common.extend_from_slice(&0u16.to_le_bytes()); // flags
+        common.extend_from_slice(&entry.method.to_le_bytes());
+        common.extend_from_slice(&[0; 4]); // modification time and date
+        common.extend_from_slice(&entry.crc32.to_le_bytes());
+        common.extend_from_slice(&(entry.data.len() as u32).to_le_bytes());
+        common.extend_from_slice(&entry.uncompressed_size.to_le_bytes());
+        common.extend_from_slice(&(entry.name.len() as u16).to_le_bytes());
+        common.extend_from_slice(&0u16.to_le_bytes()); // extra field length
+
+        archive.extend_from_slice(&0x04034B50u32.to_le_bytes());
+        archive.extend_from_slice(&common);
+        archive.extend_from_slice(entry.name.as_bytes());
+        archive.extend_from_slice(&entry.data);
+
+        directory.extend_from_slice(&0x02014B50u32.to_le_bytes());
+        directory.extend_from_slice(&20u16.to_le_bytes()); // version made by
+        directory.extend_from_slice(&common);
+        directory.extend_from_slice(&[0; 10]); // comment length, disk, attributes
+        directory.extend_from_slice(&offset.to_le_bytes());
+        directory.extend_from_slice(entry.name.as_bytes());
+    }
+
+    let directory_offset = archive.len() as u32;
+    archive.extend_from_slice(&directory);
+    archive.extend_from_slice(&0x06054B50u32.to_le_bytes());
+    archive.extend_from_slice(&[0; 4]); // disk numbers
+    archive.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+    archive.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+    archive.extend_from_slice(&(directory.len() as u32).to_le_bytes());
+    archive.extend_from_slice(&directory_offset.to_le_bytes());
+    archive.extend_from_slice(&0u16.to_le_bytes()); // comment length
+    archive
+}
+
+/// Add an archive containing `entry` and load it.
+fn load_archive(frontend: &mut SuperShuckieFrontend, entry: Entry) -> Result<(), String> {
+    let name = entry.name.to_owned();
+    let path = add_rom(frontend, "game.zip", &make_archive(&[entry]));
+    frontend.load_rom_from_archive(&path, &name).map_err(|e| e.to_string())
+}
+
+fn assert_loads(entry: Entry) {
+    let store = Arc::new(MemoryFileStore::new());
+    let mut frontend = new_frontend(&store);
+    load_archive(&mut frontend, entry).expect("failed to load ROM from archive");
+    assert_eq!(frontend.get_current_rom_name(), Some("game.gbc"));
+}
+
+fn assert_rejected(entry: Entry, error: &str) {
+    let store = Arc::new(MemoryFileStore::new());
+    let mut frontend = new_frontend(&store);
+    let result = load_archive(&mut frontend, entry).expect_err("loaded a bad archive");
+    assert!(result.contains(error), "unexpected error: {result}");
+    assert!(!frontend.is_game_running());
+}
+
+/// A ROM with enough variety in it to be worth compressing with dynamic Huffman codes.
+fn varied_rom() -> Vec<u8> {
+    let mut rom = test_rom("GAME");
+    for (i, byte) in rom[0x200..].iter_mut().enumerate() {
+        *byte = (i * i / 7 % 61) as u8;
+    }
+    rom
+}
+
+#[test]
+fn stored_rom_loads() {
+    assert_loads(Entry::stored("game.gbc", &varied_rom()));
+}
+
+#[test]
+fn deflated_rom_with_stored_blocks_loads() {
+    assert_loads(Entry::deflated("game.gbc", &varied_rom(), create_comp_flags_from_zip_params(0, 0, 0) | TDEFL_FORCE_ALL_RAW_BLOCKS));
+}
+
+#[test]
+fn deflated_rom_with_fixed_blocks_loads() {
+    assert_loads(Entry::deflated("game.gbc", &varied_rom(), create_comp_flags_from_zip_params(6, 0, 0) | TDEFL_FORCE_ALL_STATIC_BLOCKS));
+}
+
+#[test]
+fn deflated_rom_with_dynamic_blocks_loads() {
+    let rom = varied_rom();
+    let entry = Entry::deflated("game.gbc", &rom, create_comp_flags_from_zip_params(9, 0, 0));
+
+    // The first block's type is in bits 1-2
+    assert_eq!((entry.data[0] >> 1) & 3, 2, "expected a dynamic block");
+    assert_loads(entry);
+}
+
+#[test]
+fn truncated_deflate_data_is_rejected() {
+    let mut entry = Entry::deflated("game.gbc", &varied_rom(), create_comp_flags_from_zip_params(9, 0, 0));
+    entry.data.truncate(entry.data.len() / 2);
+    assert_rejected(entry, "game.gbc is corrupt");
+}
+
+#[test]
+fn corrupt_deflate_data_is_rejected() {
+    let mut entry = Entry::deflated("game.gbc", &varied_rom(), create_comp_flags_from_zip_params(9, 0, 0));
+    let middle = entry.data.len() / 2;
+    entry.data[middle] ^= 0xFF;
+    assert_rejected(entry, "game.gbc is corrupt");
+}
+
+#[test]
+fn checksum_mismatch_is_rejected() {
+    let mut entry = Entry::stored("game.gbc", &varied_rom());
+    entry.crc32 ^= 1;
+    assert_rejected(entry, "checksum mismatch");
+}
+
+#[test]
+fn data_larger_than_declared_is_rejected() {
+    let mut entry = Entry::deflated("game.gbc", &vec![0; 0x100000], create_comp_flags_from_zip_params(9, 0, 0));
+    entry.uncompressed_size = 0x8000;
+    assert_rejected(entry, "game.gbc is corrupt");
+}
+
+#[test]
+fn oversized_rom_is_rejected() {
+    let mut entry = Entry::deflated("game.gbc", &varied_rom(), create_comp_flags_from_zip_params(9, 0, 0));
+    entry.uncompressed_size = u32::MAX - 1;
+    assert_rejected(entry, "too large");
+}
@@ -0,0 +1,90 @@
+mod common;
+
+use common::*;
+use std::io::{ErrorKind, Read, Write};
+use std::net::{Ipv4Addr, TcpStream};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use supershuckie_frontend::file_store::{FileStore, MemoryFileStore};
+use supershuckie_frontend::instance::forward_to_running_instance;
+use supershuckie_frontend::SuperShuckieFrontend;
+
+const INSTANCE_FILE: &str = "instance.port";
+
+/// Tick until `done` returns true, failing after a few seconds.
+fn tick_until(frontend: &mut SuperShuckieFrontend, mut done: impl FnMut(&SuperShuckieFrontend) -> bool) {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while !done(frontend) {
+        assert!(Instant::now() < deadline, "timed out");
+        frontend.tick();
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
+
+#[test]
+fn forwarded_rom_is_loaded() {
+    let store = Arc::new(MemoryFileStore::new());
+    let mut frontend = new_frontend(&store);
+    let rom = add_rom(&frontend, "game.gbc", &test_rom("GAME"));
+
+    assert!(!forward_to_running_instance(store.as_ref(), Path::new(USER_DIR), Some(&rom)));
+
+    frontend.set_single_instance_setting(true);
+    assert!(forward_to_running_instance(store.as_ref(), Path::new(USER_DIR), Some(&rom)));
+    tick_until(&mut frontend, |f| f.is_game_running());
+    assert_eq!(frontend.get_current_rom_name(), Some("game.gbc"));
+
+    frontend.set_single_instance_setting(false);
+    assert!(!store.exists(&Path::new(USER_DIR).join(INSTANCE_FILE)));
+    assert!(!forward_to_running_instance(store.as_ref(), Path::new(USER_DIR), Some(&rom)));
+}
+
+#[test]
+fn wrong_token_is_refused() {
+    let store = Arc::new(MemoryFileStore::new());
+    let mut frontend = new_frontend(&store);
+    let rom = add_rom(&frontend, "game.gbc", &test_rom("GAME"));
+    frontend.set_single_instance_setting(true);
+
+    let instance_file = Path::new(USER_DIR).join(INSTANCE_FILE);
+    let instance = store.read_to_string(&instance_file).expect("instance file was not written");
+    let port = instance.lines().next().expect("no port in instance file");
+    store.write(&instance_file, format!("{port}\n{}\n", "0".repeat(32)).as_bytes()).expect("failed to replace instance file");
+
+    assert!(!forward_to_running_instance(store.as_ref(), Path::new(USER_DIR), Some(&rom)));
+    frontend.tick();
+    assert!(!frontend.is_game_running());
+
+    // The file is no longer ours, so it's left alone
+    frontend.set_single_instance_setting(false);
+    assert!(store.exists(&instance_file));
+}
+
+#[test]
+fn slow_connections_are_closed() {
+    let store = Arc::new(MemoryFileStore::new());
+    let mut frontend = new_frontend(&store);
+    frontend.set_single_instance_setting(true);
+
+    let instance = store.read_to_string(&Path::new(USER_DIR).join(INSTANCE_FILE)).expect("instance file was not written");
+    let port: u16 = instance.lines().next().and_then(|p| p.parse().ok()).expect("no port in instance file");
+    let stream = TcpStream::connect((Ipv4Addr::LOCALHOST, port)).expect("failed to connect");
+    stream.set_read_timeout(Some(Duration::from_millis(50))).expect("failed to set timeout");
+
+    // Trickle in a line that never ends, never waiting long enough for a single read to time out
+    let start = Instant::now();
+    loop {
+        assert!(start.elapsed() < Duration::from_secs(5), "connection was held open");
+        if (&stream).write_all(b"s").is_err() {
+            break
+        }
+        match (&stream).read(&mut [0u8; 1]) {
+            Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => (),
+            Ok(0) | Err(_) => break,
+            Ok(_) => panic!("received a response")
+        }
+    }
+
+    frontend.set_single_instance_setting(false);
+}
@@ -1,18 +1,19 @@
 use std::borrow::Cow;
-use std::net::UdpSocket;
+use std::ops::Range;
 use std::sync::{Arc, Mutex, MutexGuard, Weak};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::{channel, Receiver, Sender};
-use std::time::Duration;
 use tinyvec::{ArrayVec, TinyVec};
-use crate::protocol::{Instruction, MetadataHeader, PokeAByteProtocolRequestPacket, PokeAByteProtocolRequestReadBlock, MAX_NUMBER_OF_READ_BLOCKS};
+use crate::protocol::{Instruction, MetadataHeader, CAPABILITY_DOUBLE_BUFFERED_MEMORY, PokeAByteProtocolRequestPacket, PokeAByteProtocolRequestReadBlock, MAX_NUMBER_OF_READ_BLOCKS, METADATA_HEADER_SIZE, PING_RESPONSE_SIZE, SETUP_RESPONSE_SIZE};
 use crate::shared_memory::PokeAByteSharedMemory;
 
+#[cfg(target_os = "macos")]
+use crate::shared_memory::MACOS_MAX_MMAP_MEMORY_LENGTH;
+use crate::transport::ServerSocket;
+
 #[cfg(not(target_pointer_width = "64"))]
 compile_error!("must be compiled for 64-bit");
 
-// FIXME: this is not currently configurable
-const POKEABYTE_UDP: &str = "127.0.0.1:55356";
-
 pub struct PokeAByteWrite {
     pub address: u64,
     pub data: TinyVec<[u8; 16]>
@@ -20,11 +21,46 @@ pub struct PokeAByteWrite {
 
 pub struct PokeAByteIntegrationServer {
     session: Arc<Mutex<Option<PokeAByteSession>>>,
-    server_close_notifier: Mutex<Receiver<()>>
+    server_close_notifier: Mutex<Receiver<()>>,
+    reject_writes: Arc<AtomicBool>,
+    frame_skip_policy: Arc<Mutex<PokeAByteFrameSkipPolicy>>,
+    socket: ServerSocket,
+    transport: PokeAByteTransport
+}
+
+/// Describes how to handle the number of frames Poke-A-Byte suggests skipping between reads.
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub enum PokeAByteFrameSkipPolicy {
+    /// Update reads every frame, regardless of the suggestion.
+    Ignore,
+
+    /// Skip the number of frames suggested by the client, if any.
+    #[default]
+    Honor,
+
+    /// Always skip the given number of frames, regardless of the suggestion.
+    Custom(u32)
+}
+
+impl PokeAByteFrameSkipPolicy {
+    /// Get the number of frames to skip between reads with this policy and the given suggestion.
+    pub const fn effective_frame_skip(self, suggested: Option<u32>) -> Option<u32> {
+        match self {
+            PokeAByteFrameSkipPolicy::Ignore => None,
+            PokeAByteFrameSkipPolicy::Honor => suggested,
+            PokeAByteFrameSkipPolicy::Custom(frames) => Some(frames)
+        }
+    }
 }
 
 /// All session-related data from Poke-A-Byte.
+///
+/// A session is owned by the client that set it up. Other clients are sent error responses until
+/// the owner closes it or is disconnected with [`PokeAByteIntegrationServer::disconnect_client`].
 pub struct PokeAByteSession {
+    /// Address of the client that owns the session.
+    pub client: PokeAByteClientAddress,
+
     /// Shared memory block.
     pub shared_memory: PokeAByteSharedMemory,
 
@@ -56,14 +92,111 @@ pub struct PokeAByteSetup {
     /// shared memory buffer.
     pub blocks: ArrayVec<[PokeAByteProtocolRequestReadBlock; MAX_NUMBER_OF_READ_BLOCKS]>,
 
+    /// `blocks` with contiguous and overlapping game addresses merged, sorted by game address.
+    ///
+    /// Reading these instead of each block separately reduces the per-frame overhead when a mapper
+    /// has many small blocks.
+    pub reads: Vec<PokeAByteCoalescedRead>,
+
     /// Suggested number of frames to skip, if any.
     ///
     /// The emulator can (and ideally should) respect this configuration.
     pub frame_skip: Option<u32>,
 
+    /// Protocol version and capabilities negotiated with the client.
+    pub protocol: PokeAByteProtocol,
+
+    /// Number of frames to actually skip between reads, if any.
+    ///
+    /// This is `frame_skip` with the current [`PokeAByteFrameSkipPolicy`] applied, and it is what
+    /// was reported back to the client.
+    pub effective_frame_skip: Option<u32>,
+
     _cant_let_you_instantiate_that_stair_fax: ()
 }
 
+/// A single read of game RAM covering one or more [`PokeAByteSetup::blocks`].
+#[derive(Clone, PartialEq, Debug)]
+pub struct PokeAByteCoalescedRead {
+    /// RAM address in the game to start reading from.
+    pub game_address: u32,
+
+    /// Number of bytes to read.
+    pub length: usize,
+
+    /// Where the read data goes: an offset into the read, and the range in the shared memory buffer
+    /// to copy into from there.
+    pub targets: Vec<(usize, Range<usize>)>
+}
+
+impl PokeAByteCoalescedRead {
+    /// Get the range in the shared memory buffer that this can be read directly into, if it maps to
+    /// exactly one contiguous span of it.
+    pub fn direct_target(&self) -> Option<Range<usize>> {
+        match self.targets.as_slice() {
+            [(0, range)] if range.len() == self.length => Some(range.clone()),
+            _ => None
+        }
+    }
+
+    /// Copy data read from the game into each of its targets in the shared memory buffer.
+    pub fn scatter(&self, data: &[u8], memory: &mut [u8]) {
+        for (offset, range) in &self.targets {
+            memory[range.clone()].copy_from_slice(&data[*offset..][..range.len()]);
+        }
+    }
+}
+
+/// Merge blocks with contiguous or overlapping game addresses into as few reads as possible.
+fn coalesce_read_blocks(blocks: &[PokeAByteProtocolRequestReadBlock]) -> Vec<PokeAByteCoalescedRead> {
+    let mut sorted: Vec<&PokeAByteProtocolRequestReadBlock> = blocks.iter().filter(|b| !b.range.is_empty()).collect();
+    sorted.sort_by_key(|b| b.game_address);
+
+    let mut reads: Vec<PokeAByteCoalescedRead> = Vec::new();
+    for block in sorted {
+        let start = block.game_address as usize;
+        match reads.last_mut() {
+            Some(read) if start <= read.game_address as usize + read.length => {
+                let offset = start - read.game_address as usize;
+                read.length = read.length.max(offset + block.range.len());
+                read.targets.push((offset, block.range.clone()));
+            },
+            _ => reads.push(PokeAByteCoalescedRead {
+                game_address: block.game_address,
+                length: block.range.len(),
+                targets: vec![(0, block.range.clone())]
+            })
+        }
+    }
+
+    // Blocks that are also contiguous in shared memory can be copied in one go
+    for read in &mut reads {
+        read.targets.sort_by_key(|(offset, _)| *offset);
+        let mut merged: Vec<(usize, Range<usize>)> = Vec::with_capacity(read.targets.len());
+        for (offset, range) in read.targets.drain(..) {
+            match merged.last_mut() {
+                Some((last_offset, last_range)) if *last_offset + last_range.len() == offset && last_range.end == range.start => {
+                    last_range.end = range.end;
+                },
+                _ => merged.push((offset, range))
+            }
+        }
+        read.targets = merged;
+    }
+
+    reads
+}
+
+/// Protocol version and optional features negotiated with a Poke-A-Byte client.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct PokeAByteProtocol {
+    /// Protocol version used to talk to the client (the older of the client's and the server's).
+    pub version: u8,
+
+    /// Capability bits supported by both the client and the server.
+    pub capabilities: u32
+}
+
 impl Drop for PokeAByteIntegrationServer {
     fn drop(&mut self) {
         self.session = Arc::new(Mutex::new(None));
@@ -72,37 +205,105 @@ impl Drop for PokeAByteIntegrationServer {
 }
 
 impl PokeAByteIntegrationServer {
-    /// Begin listening.
+    /// Begin listening on the default UDP address.
     pub fn begin_listen() -> Result<Self, PokeAByteError> {
-        let socket = UdpSocket::bind(&POKEABYTE_UDP)
-            .map_err(|e| PokeAByteError::SocketFailure { explanation: Cow::Owned(format!("Failed to bind: {e:?}")) })?;
+        Self::begin_listen_on(PokeAByteTransport::default())
+    }
 
-        let _ = socket.set_read_timeout(Some(Duration::from_millis(500)));
-        let _ = socket.set_write_timeout(Some(Duration::from_millis(500)));
+    /// Begin listening with the given transport.
+    ///
+    /// See [`PokeAByteIntegrationServer::transport`] for the transport actually used.
+    pub fn begin_listen_on(transport: PokeAByteTransport) -> Result<Self, PokeAByteError> {
+        let (socket, transport) = ServerSocket::bind(&transport)?;
 
         let (sender, receiver) = channel();
 
         let session = Arc::new(Mutex::new(None));
         let session_downgraded = Arc::downgrade(&session);
+        let reject_writes = Arc::new(AtomicBool::new(false));
+        let reject_writes_thread = reject_writes.clone();
+        let frame_skip_policy = Arc::new(Mutex::new(PokeAByteFrameSkipPolicy::default()));
+        let frame_skip_policy_thread = frame_skip_policy.clone();
+        let socket_thread = socket.try_clone()
+            .map_err(|e| PokeAByteError::SocketFailure { explanation: Cow::Owned(format!("Failed to clone socket: {e:?}")) })?;
 
         let this = Self {
             session,
-            server_close_notifier: Mutex::new(receiver)
+            server_close_notifier: Mutex::new(receiver),
+            reject_writes,
+            frame_skip_policy,
+            socket,
+            transport
         };
 
         let _ = std::thread::Builder::new().name("PokeAByteIntegrationServer".to_owned()).spawn(move || {
-            PokeAByteIntegrationServer::thread(session_downgraded, socket, sender)
+            PokeAByteIntegrationServer::thread(session_downgraded, socket_thread, sender, reject_writes_thread, frame_skip_policy_thread)
         });
 
         Ok(this)
     }
 
+    /// Get the transport the server is listening with.
+    ///
+    /// This may differ from the requested transport, such as if the requested UDP port was in use
+    /// and a fallback port was used, instead.
+    pub fn transport(&self) -> &PokeAByteTransport {
+        &self.transport
+    }
+
+    /// Set whether writes should be rejected.
+    ///
+    /// If `true`, writes are not queued, and the client is sent an error response instead. Writes
+    /// queued before then can be rejected with [`PokeAByteIntegrationServer::reject_queued_writes`].
+    pub fn set_reject_writes(&self, reject: bool) {
+        self.reject_writes.store(reject, Ordering::Relaxed);
+    }
+
+    /// Discard the writes queued in `session`, sending its client an error response for each, as
+    /// if they had been rejected when they arrived.
+    pub fn reject_queued_writes(&self, session: &mut PokeAByteSession) {
+        let response = MetadataHeader::new_error_response(Instruction::Write, session.config.protocol.version).into_bytes();
+        for _ in session.writes.by_ref() {
+            let _ = self.socket.send_to(&response, &session.client);
+        }
+    }
+
+    /// Set how the frame skip suggested by the client is handled.
+    ///
+    /// This applies to the current session immediately. Clients are told the effective frame skip
+    /// when they next send a setup request.
+    pub fn set_frame_skip_policy(&self, policy: PokeAByteFrameSkipPolicy) {
+        // Lock the policy first so a concurrent setup request can't use the old policy.
+        let mut current_policy = self.frame_skip_policy.lock().expect("could not get frame skip policy???");
+        *current_policy = policy;
+        if let Some(session) = self.get_session().as_mut() {
+            session.config.effective_frame_skip = policy.effective_frame_skip(session.config.frame_skip);
+        }
+    }
+
+    /// End the current session, if any, telling its client that it was closed.
+    ///
+    /// This lets another client set up a session. Returns `false` if there was no session.
+    pub fn disconnect_client(&self) -> bool {
+        let Some(session) = self.get_session().take() else {
+            return false
+        };
+        let _ = self.socket.send_to(&MetadataHeader::new_response(Instruction::Close, session.config.protocol.version).into_bytes(), &session.client);
+        true
+    }
+
     /// Get the current session, if any.
     pub fn get_session(&self) -> MutexGuard<'_, Option<PokeAByteSession>> {
         self.session.lock().expect("could not get session???")
     }
 
-    fn thread(session: Weak<Mutex<Option<PokeAByteSession>>>, socket: UdpSocket, close_notifier: Sender<()>) {
+    fn thread(
+        session: Weak<Mutex<Option<PokeAByteSession>>>,
+        socket: ServerSocket,
+        close_notifier: Sender<()>,
+        reject_writes: Arc<AtomicBool>,
+        frame_skip_policy: Arc<Mutex<PokeAByteFrameSkipPolicy>>
+    ) {
         let mut buffer = vec![0u8; 65536];
 
         let mut writer: Option<Sender<PokeAByteWrite>> = None;
@@ -114,12 +315,13 @@ impl PokeAByteIntegrationServer {
                 return
             };
 
-            let Ok((len, addr)) = socket.recv_from(&mut buffer) else {
+            // Clients that can't be replied to can't hold a session, either.
+            let Ok((len, Some(addr))) = socket.recv_from(&mut buffer) else {
                 continue
             };
 
             let bytes_received = &buffer.as_slice()[..len];
-            let packet = match PokeAByteProtocolRequestPacket::parse_bytes(bytes_received) {
+            let (header, packet) = match PokeAByteProtocolRequestPacket::parse_bytes(bytes_received) {
                 Ok(n) => n,
                 Err(e) => {
                     // TODO: should we log this?
@@ -131,25 +333,49 @@ impl PokeAByteIntegrationServer {
             };
 
             match packet {
-                PokeAByteProtocolRequestPacket::Ping => {
-                    let _ = socket.send_to(&MetadataHeader::new_response(Instruction::Ping).into_bytes(), addr);
+                PokeAByteProtocolRequestPacket::Ping { capabilities } => {
+                    let protocol = PokeAByteProtocol { version: header.protocol_version, capabilities };
+                    let _ = socket.send_to(&ping_response(protocol), &addr);
                 },
                 PokeAByteProtocolRequestPacket::NoOp => {},
                 PokeAByteProtocolRequestPacket::Close => {
-                    // unhandled for now
+                    let mut session = promotion.lock().expect("Failed to lock: crash?");
+                    if session.as_ref().is_some_and(|s| s.client == addr) {
+                        *session = None;
+                        writer = None;
+                    }
                 },
-                PokeAByteProtocolRequestPacket::Setup { blocks, frame_skip } => {
+                PokeAByteProtocolRequestPacket::Setup { blocks, frame_skip, capabilities } => {
                     let memory_size = blocks
                         .iter()
                         .map(|i| i.range.end)
                         .max()
                         .unwrap_or(0);
 
+                    // Fall back to a single buffer if two won't fit
+                    #[cfg(target_os = "macos")]
+                    let capabilities = if PokeAByteSharedMemory::total_size(memory_size, true) > MACOS_MAX_MMAP_MEMORY_LENGTH {
+                        capabilities & !CAPABILITY_DOUBLE_BUFFERED_MEMORY
+                    }
+                    else {
+                        capabilities
+                    };
+
+                    let protocol = PokeAByteProtocol { version: header.protocol_version, capabilities };
+                    let double_buffered = capabilities & CAPABILITY_DOUBLE_BUFFERED_MEMORY != 0;
+
+                    let policy = frame_skip_policy.lock().expect("Failed to lock: crash?");
+                    let effective_frame_skip = policy.effective_frame_skip(frame_skip);
+
                     let mut session = promotion.lock().expect("Failed to lock: crash?");
+                    if session.as_ref().is_some_and(|s| s.client != addr) {
+                        let _ = socket.send_to(&MetadataHeader::new_error_response(Instruction::Setup, header.protocol_version).into_bytes(), &addr);
+                        continue
+                    }
                     *session = None; // For cleaning up the old SHM and clearing the file descriptor.
 
                     // Safety: We're going to zero-initialize this before we use it.
-                    let mut shared_memory = unsafe { PokeAByteSharedMemory::new(memory_size) }
+                    let mut shared_memory = unsafe { PokeAByteSharedMemory::new(memory_size, double_buffered) }
                         .expect("Failed to initialize shared memory");
 
                     let (writer_queue, writes_queue) = channel();
@@ -159,18 +385,21 @@ impl PokeAByteIntegrationServer {
 
                     // let Poke-A-Byte know that we're open for business, since zero initialization
                     // is not instant (though it'll probably still be quick)
-                    let _ = socket.send_to(&MetadataHeader::new_response(Instruction::Setup).into_bytes(), addr);
+                    let _ = socket.send_to(&setup_response(protocol, blocks.len(), effective_frame_skip), &addr);
 
                     // Zero-initialize
-                    unsafe { shared_memory.get_memory_mut() }.fill(0);
+                    shared_memory.zero_initialize();
 
                     *session = Some(PokeAByteSession {
+                        client: addr.clone(),
                         shared_memory,
                         writes,
                         config: PokeAByteSetup {
-                            blocks, frame_skip, _cant_let_you_instantiate_that_stair_fax: ()
+                            reads: coalesce_read_blocks(&blocks),
+                            blocks, frame_skip, protocol, effective_frame_skip, _cant_let_you_instantiate_that_stair_fax: ()
                         },
                     });
+                    drop(policy);
 
                 },
                 PokeAByteProtocolRequestPacket::Write { data, address } => {
@@ -178,6 +407,12 @@ impl PokeAByteIntegrationServer {
                         continue
                     }
 
+                    let is_owner = promotion.lock().expect("Failed to lock: crash?").as_ref().is_some_and(|s| s.client == addr);
+                    if !is_owner || reject_writes.load(Ordering::Relaxed) {
+                        let _ = socket.send_to(&MetadataHeader::new_error_response(Instruction::Write, header.protocol_version).into_bytes(), &addr);
+                        continue
+                    }
+
                     let Some(writer) = writer.as_ref() else {
                         continue
                     };
@@ -191,6 +426,29 @@ impl PokeAByteIntegrationServer {
     }
 }
 
+/// Make a ping response, which reports the negotiated protocol version and capabilities.
+fn ping_response(protocol: PokeAByteProtocol) -> [u8; PING_RESPONSE_SIZE] {
+    let mut response = [0u8; PING_RESPONSE_SIZE];
+    response[..METADATA_HEADER_SIZE].copy_from_slice(&MetadataHeader::new_response(Instruction::Ping, protocol.version).into_bytes());
+    response[8..12].copy_from_slice(&protocol.capabilities.to_le_bytes());
+    response
+}
+
+/// Make a setup response, which mirrors the setup request's block count, frame skip, and
+/// capabilities fields.
+///
+/// The frame skip is the one actually used, with -1 meaning frames are not skipped.
+fn setup_response(protocol: PokeAByteProtocol, block_count: usize, effective_frame_skip: Option<u32>) -> [u8; SETUP_RESPONSE_SIZE] {
+    let mut response = [0u8; SETUP_RESPONSE_SIZE];
+    response[..METADATA_HEADER_SIZE].copy_from_slice(&MetadataHeader::new_response(Instruction::Setup, protocol.version).into_bytes());
+    response[8..12].copy_from_slice(&(block_count as u32).to_le_bytes());
+
+    let frame_skip = effective_frame_skip.and_then(|i| i32::try_from(i).ok()).unwrap_or(-1);
+    response[12..16].copy_from_slice(&frame_skip.to_le_bytes());
+    response[16..20].copy_from_slice(&protocol.capabilities.to_le_bytes());
+    response
+}
+
 #[derive(Clone, PartialEq, Debug)]
 pub enum PokeAByteError {
     SharedMemoryFailure { explanation: Cow<'static, str> },
@@ -200,3 +458,6 @@ pub enum PokeAByteError {
 
 mod shared_memory;
 mod protocol;
+mod transport;
+
+pub use transport::*;
@@ -47,6 +47,7 @@ fn main() {
             for i in memory {
                 *i = i.wrapping_add(1);
             }
+            session.shared_memory.publish();
 
         }
     }
@@ -7,7 +7,27 @@ use crate::PokeAByteError;
 #[cfg(target_os = "macos")]
 use crate::shared_memory::MACOS_MAX_MMAP_MEMORY_LENGTH;
 
-const PROTOCOL_VERSION: u8 = 1;
+/// Oldest protocol version clients can use.
+const MIN_PROTOCOL_VERSION: u8 = 1;
+
+/// Newest protocol version supported.
+///
+/// Clients using an older version are responded to with their version, and clients using a newer
+/// version are responded to with this version so they can fall back to it.
+const PROTOCOL_VERSION: u8 = 2;
+
+/// First protocol version where Ping and Setup exchange capability bits.
+const CAPABILITIES_PROTOCOL_VERSION: u8 = 2;
+
+/// Shared memory is double buffered behind a generation counter so the client can detect torn
+/// reads (see `PokeAByteSharedMemory` for the layout).
+pub const CAPABILITY_DOUBLE_BUFFERED_MEMORY: u32 = 1 << 0;
+
+/// Capability bits supported by this server.
+///
+/// Future instructions (e.g. freezing memory or on-demand reads) each get a bit, and they are only
+/// used if the client sets that bit, too.
+const SUPPORTED_CAPABILITIES: u32 = CAPABILITY_DOUBLE_BUFFERED_MEMORY;
 
 #[derive(Copy, Clone, PartialEq, Debug, TryFromPrimitive)]
 #[repr(u8)]
@@ -23,29 +43,40 @@ pub const METADATA_HEADER_SIZE: usize = 8;
 
 #[derive(Copy, Clone, Debug)]
 pub struct MetadataHeader {
-    #[expect(unused)]
+    /// For requests, this is the version negotiated with the client rather than the client's own.
     pub protocol_version: u8,
     pub instruction: Instruction,
-    pub is_response: bool
+    pub is_response: bool,
+
+    /// Set in responses if the request was rejected.
+    pub is_error: bool
 }
 
 impl MetadataHeader {
-    pub const fn new_response(instruction: Instruction) -> Self {
+    pub const fn new_response(instruction: Instruction, protocol_version: u8) -> Self {
         Self {
-            protocol_version: PROTOCOL_VERSION,
+            protocol_version,
             instruction,
-            is_response: true
+            is_response: true,
+            is_error: false
+        }
+    }
+
+    pub const fn new_error_response(instruction: Instruction, protocol_version: u8) -> Self {
+        Self {
+            is_error: true,
+            ..Self::new_response(instruction, protocol_version)
         }
     }
 
     pub const fn into_bytes(self) -> [u8; METADATA_HEADER_SIZE] {
-        [PROTOCOL_VERSION, 0, 0, 0, self.instruction as u8, self.is_response as u8, 0, 0]
+        [self.protocol_version, 0, 0, 0, self.instruction as u8, self.is_response as u8, self.is_error as u8, 0]
     }
 
     pub fn from_client_bytes(bytes: [u8; METADATA_HEADER_SIZE]) -> Result<Self, PokeAByteError> {
         let protocol_byte = bytes[0];
-        if protocol_byte != PROTOCOL_VERSION {
-            return Err(PokeAByteError::BadPacketFromClient { explanation: Cow::Owned(format!("Unknown protocol {protocol_byte} (expected {PROTOCOL_VERSION})")) })
+        if protocol_byte < MIN_PROTOCOL_VERSION {
+            return Err(PokeAByteError::BadPacketFromClient { explanation: Cow::Owned(format!("Unknown protocol {protocol_byte} (expected {MIN_PROTOCOL_VERSION}-{PROTOCOL_VERSION})")) })
         }
 
         let is_response = bytes[5];
@@ -59,20 +90,30 @@ impl MetadataHeader {
 
         Ok(Self {
             is_response: false,
-            protocol_version: protocol_byte,
+            is_error: false,
+            protocol_version: protocol_byte.min(PROTOCOL_VERSION),
             instruction
         })
     }
 }
 
+/// Size of a ping response (header and capabilities).
+pub const PING_RESPONSE_SIZE: usize = 0xC;
+
+/// Size of a setup response (header, block count, frame skip, and capabilities).
+pub const SETUP_RESPONSE_SIZE: usize = 0x14;
+
 const READ_BLOCK_SIZE: usize = 0xC;
 pub const MAX_NUMBER_OF_READ_BLOCKS: usize = 128;
 
 pub enum PokeAByteProtocolRequestPacket<'a> {
     NoOp,
-    Ping,
+    Ping {
+        capabilities: u32
+    },
     Setup {
         frame_skip: Option<u32>,
+        capabilities: u32,
         blocks: ArrayVec<[PokeAByteProtocolRequestReadBlock; MAX_NUMBER_OF_READ_BLOCKS]>
     },
     Write {
@@ -89,16 +130,30 @@ pub struct PokeAByteProtocolRequestReadBlock {
 }
 
 impl<'a> PokeAByteProtocolRequestPacket<'a> {
-    pub fn parse_bytes(bytes: &'a [u8]) -> Result<Self, PokeAByteError> {
+    /// Parse the request, returning it along with its header.
+    pub fn parse_bytes(bytes: &'a [u8]) -> Result<(MetadataHeader, Self), PokeAByteError> {
         let Some(header) = bytes.get(..METADATA_HEADER_SIZE) else {
             return Err(PokeAByteError::BadPacketFromClient { explanation: Cow::Borrowed("too small to be header") })
         };
         let header_bytes: [u8; METADATA_HEADER_SIZE] = header.try_into().unwrap();
         let header = MetadataHeader::from_client_bytes(header_bytes)?;
+        Self::parse_body(header, bytes).map(|packet| (header, packet))
+    }
+
+    /// Get the capabilities at the given offset supported by both the client and server.
+    ///
+    /// Older clients don't send any, and newer clients may leave them out if they don't have any.
+    fn read_capabilities(header: MetadataHeader, bytes: &[u8], offset: usize) -> u32 {
+        if header.protocol_version < CAPABILITIES_PROTOCOL_VERSION {
+            return 0
+        }
+        bytes.get(offset..offset + 4).map(LittleEndian::read_u32).unwrap_or(0) & SUPPORTED_CAPABILITIES
+    }
 
+    fn parse_body(header: MetadataHeader, bytes: &'a [u8]) -> Result<Self, PokeAByteError> {
         match header.instruction {
             Instruction::NoOp => Ok(Self::NoOp),
-            Instruction::Ping => Ok(Self::Ping),
+            Instruction::Ping => Ok(Self::Ping { capabilities: Self::read_capabilities(header, bytes, 8) }),
             Instruction::Setup => {
                 let Some(_setup_data) = bytes.get(..0x20 + READ_BLOCK_SIZE * MAX_NUMBER_OF_READ_BLOCKS) else {
                     return Err(PokeAByteError::BadPacketFromClient { explanation: Cow::Borrowed("too small to be setup header") })
@@ -109,6 +164,7 @@ impl<'a> PokeAByteProtocolRequestPacket<'a> {
                 }
 
                 let frame_skip = u32::try_from(LittleEndian::read_i32(&bytes[12..])).ok();
+                let capabilities = Self::read_capabilities(header, bytes, 16);
                 let blocks = (&bytes[32..])
                     .chunks_exact(0xC)
                     .take(block_count);
@@ -143,7 +199,8 @@ impl<'a> PokeAByteProtocolRequestPacket<'a> {
 
                 Ok(Self::Setup {
                     blocks: blocks_into,
-                    frame_skip
+                    frame_skip,
+                    capabilities
                 })
             },
             Instruction::Write => {
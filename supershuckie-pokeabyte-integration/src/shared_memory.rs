@@ -1,10 +1,29 @@
 use std::borrow::Cow;
 use std::ffi::{c_char, CStr};
 use std::ptr::null_mut;
+use std::sync::atomic::{AtomicU64, Ordering};
 use crate::PokeAByteError;
 
+/// Size of the header before the buffers in double-buffered shared memory.
+///
+/// The header holds the generation (u64, little endian) at 0x0 and the size of each buffer (u32,
+/// little endian) at 0x8.
+pub const DOUBLE_BUFFER_HEADER_SIZE: usize = 0x10;
+
+/// Memory shared with the Poke-A-Byte client.
+///
+/// If `CAPABILITY_DOUBLE_BUFFERED_MEMORY` was negotiated, the shared memory is a [`DOUBLE_BUFFER_HEADER_SIZE`] header followed by two buffers.
+/// The server writes to the back buffer, then publishes it by incrementing the generation, and the
+/// front buffer is buffer `generation % 2`. Clients read the generation, copy the front buffer, and
+/// read the generation again, retrying if it changed in the meantime (a seqlock).
+///
+/// Otherwise, the shared memory is a single buffer that is written in place.
 pub struct PokeAByteSharedMemory {
-    memory: &'static mut [u8]
+    memory: &'static mut [u8],
+
+    /// Size of each buffer
+    len: usize,
+    double_buffered: bool
 }
 
 // macOS mmap is limited to 4 MiB. As such, we cannot (presently) support larger memory mapped files
@@ -18,41 +37,106 @@ unsafe extern "C" {
 }
 
 impl PokeAByteSharedMemory {
+    /// Get the total size of shared memory needed for buffers of `len` bytes.
+    pub(crate) const fn total_size(len: usize, double_buffered: bool) -> usize {
+        if double_buffered {
+            DOUBLE_BUFFER_HEADER_SIZE + len * 2
+        }
+        else {
+            len
+        }
+    }
+
     /// # Safety
     ///
-    /// The memory returned is not guaranteed to be initialized and must be zero-initialized
-    /// manually.
-    pub(crate) unsafe fn new(len: usize) -> Result<PokeAByteSharedMemory, PokeAByteError> {
+    /// The memory returned is not guaranteed to be initialized and must be initialized with
+    /// [`PokeAByteSharedMemory::zero_initialize`].
+    pub(crate) unsafe fn new(len: usize, double_buffered: bool) -> Result<PokeAByteSharedMemory, PokeAByteError> {
+        let total_len = Self::total_size(len, double_buffered);
         let mut error = null_mut();
         let memory = unsafe {
-            let ram = supershuckie_pokeabyte_try_create_shared_memory(len, &mut error);
+            let ram = supershuckie_pokeabyte_try_create_shared_memory(total_len, &mut error);
             if ram.is_null() {
                 return Err(PokeAByteError::SharedMemoryFailure { explanation: Cow::Owned(format!("Error: {}", CStr::from_ptr(error).to_str().unwrap())) })
             }
-            std::slice::from_raw_parts_mut(ram, len)
+            std::slice::from_raw_parts_mut(ram, total_len)
         };
 
         Ok(Self {
-            memory
+            memory,
+            len,
+            double_buffered
         })
     }
 
+    /// Zero the buffers and write the header, if any.
+    pub(crate) fn zero_initialize(&mut self) {
+        self.memory.fill(0);
+        if self.double_buffered {
+            self.memory[8..12].copy_from_slice(&(self.len as u32).to_le_bytes());
+        }
+    }
+
+    /// Returns true if the shared memory is double buffered.
+    #[inline]
+    pub fn is_double_buffered(&self) -> bool {
+        self.double_buffered
+    }
+
+    fn generation(&self) -> &AtomicU64 {
+        // SAFETY: The header is at the start of the mapping, which is page aligned.
+        unsafe { AtomicU64::from_ptr(self.memory.as_ptr() as *mut u64) }
+    }
+
+    fn buffer_range(&self, index: u64) -> core::ops::Range<usize> {
+        let start = DOUBLE_BUFFER_HEADER_SIZE + (index % 2) as usize * self.len;
+        start..start + self.len
+    }
+
+    /// Get the buffer the client currently sees.
+    ///
     /// # Safety
     ///
     /// There is no protection against data races from other processes. It is not recommended to use
     /// this for anything except reading bytes.
     #[inline]
     pub unsafe fn get_memory(&self) -> &[u8] {
-        self.memory
+        if !self.double_buffered {
+            return self.memory
+        }
+        let range = self.buffer_range(u64::from_le(self.generation().load(Ordering::Acquire)));
+        &self.memory[range]
     }
 
+    /// Get the buffer to write to.
+    ///
+    /// If double buffered, this is the back buffer, which the client sees once
+    /// [`PokeAByteSharedMemory::publish`] is called. It has to be written in full, as it still holds
+    /// the data from two publishes ago.
+    ///
     /// # Safety
     ///
     /// There is no protection against data races from other processes. It is not recommended to use
     /// this for anything except reading and writing bytes.
     #[inline]
     pub unsafe fn get_memory_mut(&mut self) -> &mut [u8] {
-        self.memory
+        if !self.double_buffered {
+            return self.memory
+        }
+        let range = self.buffer_range(u64::from_le(self.generation().load(Ordering::Acquire)).wrapping_add(1));
+        &mut self.memory[range]
+    }
+
+    /// Let the client see everything written with [`PokeAByteSharedMemory::get_memory_mut`].
+    ///
+    /// This does nothing if not double buffered.
+    pub fn publish(&mut self) {
+        if !self.double_buffered {
+            return
+        }
+        let generation = self.generation();
+        let next = u64::from_le(generation.load(Ordering::Acquire)).wrapping_add(1);
+        generation.store(next.to_le(), Ordering::Release);
     }
 }
 
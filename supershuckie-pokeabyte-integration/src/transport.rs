@@ -0,0 +1,260 @@
+use std::borrow::Cow;
+use std::io;
+use std::net::{SocketAddr, UdpSocket};
+use std::path::PathBuf;
+use std::time::Duration;
+use crate::PokeAByteError;
+
+#[cfg(unix)]
+use std::os::unix::net::UnixDatagram;
+
+#[cfg(unix)]
+use std::path::Path;
+
+#[cfg(unix)]
+use std::sync::Arc;
+
+#[cfg(target_os = "linux")]
+use std::os::linux::net::SocketAddrExt;
+
+/// Address Poke-A-Byte connects to by default.
+pub const DEFAULT_POKEABYTE_UDP_ADDRESS: &str = "127.0.0.1:55356";
+
+/// Number of ports after the requested UDP port to try if it is in use (e.g. by another instance).
+const UDP_PORT_FALLBACK_COUNT: u16 = 15;
+
+const SOCKET_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Describes how the integration server listens for Poke-A-Byte.
+#[derive(Clone, PartialEq, Debug)]
+pub enum PokeAByteTransport {
+    /// UDP socket bound to the given IPv4 or IPv6 address.
+    ///
+    /// If the port is in use, the next few ports are tried, instead.
+    Udp(SocketAddr),
+
+    /// Unix domain datagram socket bound to the given path (Unix only).
+    ///
+    /// A socket left at the path by a server that is no longer running is replaced, but binding
+    /// fails if anything else is there (including a socket that is still in use). The socket is
+    /// removed when the server is closed.
+    UnixSocket(PathBuf),
+
+    /// Unix domain datagram socket bound to the given name in the abstract namespace (Linux only).
+    ///
+    /// Unlike [`PokeAByteTransport::UnixSocket`], this does not need a path on the filesystem.
+    AbstractSocket(String)
+}
+
+impl Default for PokeAByteTransport {
+    fn default() -> Self {
+        Self::Udp(DEFAULT_POKEABYTE_UDP_ADDRESS.parse().expect("default address is not valid"))
+    }
+}
+
+impl core::fmt::Display for PokeAByteTransport {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Udp(address) => write!(f, "{address}"),
+            Self::UnixSocket(path) => write!(f, "{}", path.display()),
+            Self::AbstractSocket(name) => write!(f, "@{name}")
+        }
+    }
+}
+
+/// Address of a Poke-A-Byte client.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum PokeAByteClientAddress {
+    /// Client connected with UDP.
+    Udp(SocketAddr),
+
+    /// Client bound to a path with a Unix domain socket.
+    UnixSocket(PathBuf),
+
+    /// Client bound to a name in the abstract namespace with a Unix domain socket.
+    AbstractSocket(Vec<u8>)
+}
+
+/// Socket the server listens on, which is one of several transports.
+pub(crate) enum ServerSocket {
+    Udp(UdpSocket),
+
+    /// The socket file is set if the socket was bound to a path.
+    #[cfg(unix)]
+    Unix(UnixDatagram, Option<Arc<SocketFile>>)
+}
+
+impl ServerSocket {
+    /// Bind the socket, returning it along with the transport actually used.
+    pub(crate) fn bind(transport: &PokeAByteTransport) -> Result<(Self, PokeAByteTransport), PokeAByteError> {
+        let bind_error = |e: io::Error| PokeAByteError::SocketFailure { explanation: Cow::Owned(format!("Failed to bind: {e:?}")) };
+
+        let (socket, transport) = match transport {
+            PokeAByteTransport::Udp(address) => {
+                let socket = Self::bind_udp_with_fallback(*address).map_err(bind_error)?;
+                let bound_address = socket.local_addr().map_err(bind_error)?;
+                (Self::Udp(socket), PokeAByteTransport::Udp(bound_address))
+            },
+
+            #[cfg(unix)]
+            PokeAByteTransport::UnixSocket(path) => {
+                // A socket left behind by a previous instance would make binding fail.
+                remove_stale_socket(path).map_err(bind_error)?;
+                let socket = UnixDatagram::bind(path).map_err(bind_error)?;
+                let socket_file = SocketFile::identify(path).map_err(bind_error)?;
+                (Self::Unix(socket, Some(Arc::new(socket_file))), transport.clone())
+            },
+
+            #[cfg(target_os = "linux")]
+            PokeAByteTransport::AbstractSocket(name) => {
+                let address = std::os::unix::net::SocketAddr::from_abstract_name(name.as_bytes()).map_err(bind_error)?;
+                (Self::Unix(UnixDatagram::bind_addr(&address).map_err(bind_error)?, None), transport.clone())
+            },
+
+            #[allow(unreachable_patterns)]
+            _ => return Err(PokeAByteError::SocketFailure { explanation: Cow::Borrowed("This transport is not supported on this platform") })
+        };
+
+        socket.set_timeouts(SOCKET_TIMEOUT);
+        Ok((socket, transport))
+    }
+
+    fn bind_udp_with_fallback(address: SocketAddr) -> io::Result<UdpSocket> {
+        let mut result = UdpSocket::bind(address);
+        for offset in 1..=UDP_PORT_FALLBACK_COUNT {
+            let Err(e) = &result else {
+                break
+            };
+            let Some(port) = address.port().checked_add(offset) else {
+                break
+            };
+            if e.kind() != io::ErrorKind::AddrInUse {
+                break
+            }
+            result = UdpSocket::bind(SocketAddr::new(address.ip(), port));
+        }
+        result
+    }
+
+    fn set_timeouts(&self, timeout: Duration) {
+        match self {
+            Self::Udp(socket) => {
+                let _ = socket.set_read_timeout(Some(timeout));
+                let _ = socket.set_write_timeout(Some(timeout));
+            },
+            #[cfg(unix)]
+            Self::Unix(socket, _) => {
+                let _ = socket.set_read_timeout(Some(timeout));
+                let _ = socket.set_write_timeout(Some(timeout));
+            }
+        }
+    }
+
+    pub(crate) fn try_clone(&self) -> io::Result<Self> {
+        match self {
+            Self::Udp(socket) => socket.try_clone().map(Self::Udp),
+            #[cfg(unix)]
+            Self::Unix(socket, socket_file) => socket.try_clone().map(|s| Self::Unix(s, socket_file.clone()))
+        }
+    }
+
+    /// Receive a packet.
+    ///
+    /// The address is `None` if the client can't be replied to (i.e. an unbound Unix socket).
+    pub(crate) fn recv_from(&self, buffer: &mut [u8]) -> io::Result<(usize, Option<PokeAByteClientAddress>)> {
+        match self {
+            Self::Udp(socket) => socket.recv_from(buffer).map(|(len, address)| (len, Some(PokeAByteClientAddress::Udp(address)))),
+
+            #[cfg(unix)]
+            Self::Unix(socket, _) => {
+                let (len, address) = socket.recv_from(buffer)?;
+                if let Some(path) = address.as_pathname() {
+                    return Ok((len, Some(PokeAByteClientAddress::UnixSocket(path.to_owned()))))
+                }
+
+                #[cfg(target_os = "linux")]
+                if let Some(name) = address.as_abstract_name() {
+                    return Ok((len, Some(PokeAByteClientAddress::AbstractSocket(name.to_owned()))))
+                }
+
+                Ok((len, None))
+            }
+        }
+    }
+
+    pub(crate) fn send_to(&self, buffer: &[u8], address: &PokeAByteClientAddress) -> io::Result<usize> {
+        match (self, address) {
+            (Self::Udp(socket), PokeAByteClientAddress::Udp(address)) => socket.send_to(buffer, address),
+
+            #[cfg(unix)]
+            (Self::Unix(socket, _), PokeAByteClientAddress::UnixSocket(path)) => socket.send_to(buffer, path),
+
+            #[cfg(target_os = "linux")]
+            (Self::Unix(socket, _), PokeAByteClientAddress::AbstractSocket(name)) => {
+                socket.send_to_addr(buffer, &std::os::unix::net::SocketAddr::from_abstract_name(name)?)
+            },
+
+            #[allow(unreachable_patterns)]
+            _ => Err(io::ErrorKind::Unsupported.into())
+        }
+    }
+}
+
+/// Remove a socket left at `path` by a server that is no longer running.
+///
+/// Nothing is removed if `path` doesn't exist. Anything else there (including a socket something
+/// is still bound to) is an error.
+#[cfg(unix)]
+fn remove_stale_socket(path: &Path) -> io::Result<()> {
+    use std::os::unix::fs::FileTypeExt;
+
+    let metadata = match std::fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e)
+    };
+
+    if !metadata.file_type().is_socket() {
+        return Err(io::Error::new(io::ErrorKind::AlreadyExists, format!("{} exists and is not a socket", path.display())))
+    }
+
+    match UnixDatagram::unbound()?.connect(path) {
+        Ok(()) => Err(io::Error::new(io::ErrorKind::AddrInUse, format!("{} is in use by another process", path.display()))),
+        Err(e) if e.kind() == io::ErrorKind::ConnectionRefused => std::fs::remove_file(path),
+        Err(e) => Err(e)
+    }
+}
+
+/// Socket file created by binding a [`ServerSocket`] to a path.
+///
+/// It is removed when dropped, unless something else has replaced it since.
+#[cfg(unix)]
+pub(crate) struct SocketFile {
+    path: PathBuf,
+    device: u64,
+    inode: u64
+}
+
+#[cfg(unix)]
+impl SocketFile {
+    fn identify(path: &Path) -> io::Result<Self> {
+        use std::os::unix::fs::MetadataExt;
+
+        let metadata = std::fs::symlink_metadata(path)?;
+        Ok(Self { path: path.to_owned(), device: metadata.dev(), inode: metadata.ino() })
+    }
+}
+
+#[cfg(unix)]
+impl Drop for SocketFile {
+    fn drop(&mut self) {
+        use std::os::unix::fs::MetadataExt;
+
+        let Ok(metadata) = std::fs::symlink_metadata(&self.path) else {
+            return
+        };
+        if metadata.dev() == self.device && metadata.ino() == self.inode {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
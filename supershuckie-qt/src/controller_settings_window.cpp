@@ -1,5 +1,6 @@
 #include "controller_settings_window.hpp"
 #include "main_window.hpp"
+#include "keyboard.hpp"
 
 #include <SDL3/SDL.h>
 #include <QGridLayout>
@@ -39,7 +40,7 @@ ControlsSettingsWindow::ControlsSettingsWindow(MainWindow *parent, SuperShuckieC
     int control_box_y_offset = 100;
     
     for(control_types = 0; (label = supershuckie_control_settings_control_name(control_types)) != nullptr; control_types++) {
-        if(supershuckie_control_settings_control_is_spoiler(control_types)) {
+        if(!supershuckie_control_settings_control_is_bindable(control_types) || supershuckie_control_settings_control_is_spoiler(control_types) || !supershuckie_frontend_is_control_supported(this->parent->frontend, control_types)) {
             continue;
         }
         
@@ -58,7 +59,7 @@ ControlsSettingsWindow::ControlsSettingsWindow(MainWindow *parent, SuperShuckieC
             if(!supershuckie_control_settings_control_is_button(control_type) && control_modifiers != 0) {
                 continue;
             }
-            if(supershuckie_control_settings_control_is_spoiler(control_type)) {
+            if(!supershuckie_control_settings_control_is_bindable(control_type) || supershuckie_control_settings_control_is_spoiler(control_type) || !supershuckie_frontend_is_control_supported(this->parent->frontend, control_type)) {
                 continue;
             }
 
@@ -204,7 +205,8 @@ void ControlSettingsSetting::keyPressEvent(QKeyEvent *event) {
     event->ignore();
 
     auto device = this->window->ss_device_name();
-    if(device != nullptr) {
+    auto keycode = keycode_for_key_event(event);
+    if(device != nullptr || !keycode.has_value()) {
         return;
     }
 
@@ -212,7 +214,7 @@ void ControlSettingsSetting::keyPressEvent(QKeyEvent *event) {
         this->window->settings.get(),
         device,
         false,
-        event->key(),
+        *keycode,
         this->control_type,
         this->control_modifier
     );
@@ -279,7 +281,7 @@ void ControlsSettingsWindow::update_textboxes() {
 
         if(device == nullptr) {
             for(auto button : buffer_button) {
-                auto name = QKeySequence(button).toString();
+                auto name = keycode_name(button);
                 if(label.isEmpty()) {
                     label = name;
                 }
@@ -69,10 +69,13 @@ GameSpeedDialog::GameSpeedDialog(MainWindow *parent): QDialog(parent), parent(pa
 }
 
 void GameSpeedDialog::accept() {
+    char err[256];
     supershuckie_frontend_set_speed_settings(
         this->parent->frontend,
         static_cast<double>(this->base_speed_slider->value()) / 100.0,
-        static_cast<double>(this->turbo_speed_slider->value()) / 100.0
+        static_cast<double>(this->turbo_speed_slider->value()) / 100.0,
+        err,
+        sizeof(err)
     );
     QDialog::accept();
 }
@@ -80,10 +83,12 @@ void GameSpeedDialog::accept() {
 void GameSpeedDialog::do_update_speed() {
     char fmt[256];
 
-    double base_speed = 60.0 * this->base_speed_slider->value() / 100.0;
-    std::snprintf(fmt, sizeof(fmt), "= ~%d FPS", static_cast<int>(base_speed));
+    // Show the speeds that will actually be used, since they're rounded and capped
+    double base = supershuckie_speed_quantize(this->base_speed_slider->value() / 100.0);
+    double turbo = supershuckie_speed_quantize(base * supershuckie_speed_quantize(this->turbo_speed_slider->value() / 100.0));
+    std::snprintf(fmt, sizeof(fmt), "= ~%d FPS", static_cast<int>(60.0 * base));
     this->base_speed_text->setText(fmt);
-    std::snprintf(fmt, sizeof(fmt), "= ~%d FPS", static_cast<int>(base_speed * this->turbo_speed_slider->value() / 100.0));
+    std::snprintf(fmt, sizeof(fmt), "= ~%d FPS", static_cast<int>(60.0 * turbo));
     this->turbo_speed_text->setText(fmt);
 }
 int GameSpeedDialog::exec() {
@@ -0,0 +1,186 @@
+#include <QKeyEvent>
+#include <QKeySequence>
+#include <supershuckie/supershuckie.h>
+
+#include "keyboard.hpp"
+
+namespace SuperShuckie64 {
+    struct KeyLocation {
+        // Key in this location on a US QWERTY keyboard
+        Qt::Key key;
+
+        // Scancode, with 0x100 set for extended keys
+        std::uint32_t windows;
+
+        // evdev code (XKB keycodes are offset by 8)
+        std::uint32_t evdev;
+
+        // kVK_* virtual keycode
+        std::uint32_t macos;
+    };
+
+    // Qt::Key_Control is Command and Qt::Key_Meta is Control on macOS.
+    static const KeyLocation KEY_LOCATIONS[] = {
+        { Qt::Key_Escape, 0x01, 1, 0x35 },
+        { Qt::Key_1, 0x02, 2, 0x12 },
+        { Qt::Key_2, 0x03, 3, 0x13 },
+        { Qt::Key_3, 0x04, 4, 0x14 },
+        { Qt::Key_4, 0x05, 5, 0x15 },
+        { Qt::Key_5, 0x06, 6, 0x17 },
+        { Qt::Key_6, 0x07, 7, 0x16 },
+        { Qt::Key_7, 0x08, 8, 0x1A },
+        { Qt::Key_8, 0x09, 9, 0x1C },
+        { Qt::Key_9, 0x0A, 10, 0x19 },
+        { Qt::Key_0, 0x0B, 11, 0x1D },
+        { Qt::Key_Minus, 0x0C, 12, 0x1B },
+        { Qt::Key_Equal, 0x0D, 13, 0x18 },
+        { Qt::Key_Backspace, 0x0E, 14, 0x33 },
+        { Qt::Key_Tab, 0x0F, 15, 0x30 },
+        { Qt::Key_Q, 0x10, 16, 0x0C },
+        { Qt::Key_W, 0x11, 17, 0x0D },
+        { Qt::Key_E, 0x12, 18, 0x0E },
+        { Qt::Key_R, 0x13, 19, 0x0F },
+        { Qt::Key_T, 0x14, 20, 0x11 },
+        { Qt::Key_Y, 0x15, 21, 0x10 },
+        { Qt::Key_U, 0x16, 22, 0x20 },
+        { Qt::Key_I, 0x17, 23, 0x22 },
+        { Qt::Key_O, 0x18, 24, 0x1F },
+        { Qt::Key_P, 0x19, 25, 0x23 },
+        { Qt::Key_BracketLeft, 0x1A, 26, 0x21 },
+        { Qt::Key_BracketRight, 0x1B, 27, 0x1E },
+        { Qt::Key_Return, 0x1C, 28, 0x24 },
+        { Qt::Key_Control, 0x1D, 29, 0x37 },
+        { Qt::Key_A, 0x1E, 30, 0x00 },
+        { Qt::Key_S, 0x1F, 31, 0x01 },
+        { Qt::Key_D, 0x20, 32, 0x02 },
+        { Qt::Key_F, 0x21, 33, 0x03 },
+        { Qt::Key_G, 0x22, 34, 0x05 },
+        { Qt::Key_H, 0x23, 35, 0x04 },
+        { Qt::Key_J, 0x24, 36, 0x26 },
+        { Qt::Key_K, 0x25, 37, 0x28 },
+        { Qt::Key_L, 0x26, 38, 0x25 },
+        { Qt::Key_Semicolon, 0x27, 39, 0x29 },
+        { Qt::Key_Apostrophe, 0x28, 40, 0x27 },
+        { Qt::Key_QuoteLeft, 0x29, 41, 0x32 },
+        { Qt::Key_Shift, 0x2A, 42, 0x38 },
+        { Qt::Key_Backslash, 0x2B, 43, 0x2A },
+        { Qt::Key_Z, 0x2C, 44, 0x06 },
+        { Qt::Key_X, 0x2D, 45, 0x07 },
+        { Qt::Key_C, 0x2E, 46, 0x08 },
+        { Qt::Key_V, 0x2F, 47, 0x09 },
+        { Qt::Key_B, 0x30, 48, 0x0B },
+        { Qt::Key_N, 0x31, 49, 0x2D },
+        { Qt::Key_M, 0x32, 50, 0x2E },
+        { Qt::Key_Comma, 0x33, 51, 0x2B },
+        { Qt::Key_Period, 0x34, 52, 0x2F },
+        { Qt::Key_Slash, 0x35, 53, 0x2C },
+        { Qt::Key_Alt, 0x38, 56, 0x3A },
+        { Qt::Key_Space, 0x39, 57, 0x31 },
+        { Qt::Key_F1, 0x3B, 59, 0x7A },
+        { Qt::Key_F2, 0x3C, 60, 0x78 },
+        { Qt::Key_F3, 0x3D, 61, 0x63 },
+        { Qt::Key_F4, 0x3E, 62, 0x76 },
+        { Qt::Key_F5, 0x3F, 63, 0x60 },
+        { Qt::Key_F6, 0x40, 64, 0x61 },
+        { Qt::Key_F7, 0x41, 65, 0x62 },
+        { Qt::Key_F8, 0x42, 66, 0x64 },
+        { Qt::Key_F9, 0x43, 67, 0x65 },
+        { Qt::Key_F10, 0x44, 68, 0x6D },
+        { Qt::Key_F11, 0x57, 87, 0x67 },
+        { Qt::Key_F12, 0x58, 88, 0x6F },
+        { Qt::Key_Enter, 0x11C, 96, 0x4C },
+        { Qt::Key_Home, 0x147, 102, 0x73 },
+        { Qt::Key_Up, 0x148, 103, 0x7E },
+        { Qt::Key_PageUp, 0x149, 104, 0x74 },
+        { Qt::Key_Left, 0x14B, 105, 0x7B },
+        { Qt::Key_Right, 0x14D, 106, 0x7C },
+        { Qt::Key_End, 0x14F, 107, 0x77 },
+        { Qt::Key_Down, 0x150, 108, 0x7D },
+        { Qt::Key_PageDown, 0x151, 109, 0x79 },
+        { Qt::Key_Insert, 0x152, 110, 0x72 },
+        { Qt::Key_Delete, 0x153, 111, 0x75 },
+        { Qt::Key_Meta, 0x15B, 125, 0x3B }
+    };
+
+    static std::uint32_t native_code(const KeyLocation &location) {
+        #if defined(_WIN32)
+        return location.windows;
+        #elif defined(__APPLE__)
+        return location.macos;
+        #else
+        return location.evdev + 8;
+        #endif
+    }
+
+    static const KeyLocation *location_for_key(int key) {
+        for(auto &location : KEY_LOCATIONS) {
+            if(location.key == key) {
+                return &location;
+            }
+        }
+        return nullptr;
+    }
+
+    std::optional<std::int32_t> keycode_for_key_event(const QKeyEvent *event) {
+        #ifdef __APPLE__
+        // macOS doesn't report scancodes, but its virtual keycodes don't depend on the layout
+        std::uint32_t code = event->nativeVirtualKey();
+        #else
+        std::uint32_t code = event->nativeScanCode();
+        if(code == 0) {
+            // Synthesized events have no scancode
+            return std::nullopt;
+        }
+        #endif
+        return static_cast<std::int32_t>(code) | SCANCODE_KEYCODE_FLAG;
+    }
+
+    bool keycode_for_physical_key(std::uint32_t physical_key, std::int32_t *keycode) {
+        Qt::Key key;
+        switch(physical_key) {
+            case SuperShuckiePhysicalKey__ArrowUp: key = Qt::Key_Up; break;
+            case SuperShuckiePhysicalKey__ArrowDown: key = Qt::Key_Down; break;
+            case SuperShuckiePhysicalKey__ArrowLeft: key = Qt::Key_Left; break;
+            case SuperShuckiePhysicalKey__ArrowRight: key = Qt::Key_Right; break;
+            case SuperShuckiePhysicalKey__KeyA: key = Qt::Key_A; break;
+            case SuperShuckiePhysicalKey__KeyP: key = Qt::Key_P; break;
+            case SuperShuckiePhysicalKey__KeyS: key = Qt::Key_S; break;
+            case SuperShuckiePhysicalKey__KeyX: key = Qt::Key_X; break;
+            case SuperShuckiePhysicalKey__KeyZ: key = Qt::Key_Z; break;
+            case SuperShuckiePhysicalKey__Enter: key = Qt::Key_Return; break;
+            case SuperShuckiePhysicalKey__Backspace: key = Qt::Key_Backspace; break;
+            case SuperShuckiePhysicalKey__Tab: key = Qt::Key_Tab; break;
+            default: return false;
+        }
+
+        *keycode = static_cast<std::int32_t>(native_code(*location_for_key(key))) | SCANCODE_KEYCODE_FLAG;
+        return true;
+    }
+
+    std::int32_t migrate_legacy_keycode(std::int32_t keycode) {
+        if(keycode & SCANCODE_KEYCODE_FLAG) {
+            return keycode;
+        }
+
+        // There's no way to tell which layout the key was bound on, so assume US QWERTY
+        auto *location = location_for_key(keycode);
+        if(location == nullptr) {
+            return keycode;
+        }
+        return static_cast<std::int32_t>(native_code(*location)) | SCANCODE_KEYCODE_FLAG;
+    }
+
+    QString keycode_name(std::int32_t keycode) {
+        if(!(keycode & SCANCODE_KEYCODE_FLAG)) {
+            return QKeySequence(keycode).toString();
+        }
+
+        auto code = static_cast<std::uint32_t>(keycode & ~SCANCODE_KEYCODE_FLAG);
+        for(auto &location : KEY_LOCATIONS) {
+            if(native_code(location) == code) {
+                return QKeySequence(location.key).toString();
+            }
+        }
+        return QString("Key #%1").arg(code);
+    }
+}
@@ -0,0 +1,30 @@
+#ifndef __SUPERSHUCKIE_KEYBOARD_HPP__
+#define __SUPERSHUCKIE_KEYBOARD_HPP__
+
+#include <cstdint>
+#include <optional>
+#include <QString>
+
+class QKeyEvent;
+
+namespace SuperShuckie64 {
+    // Keycodes passed to the frontend are native scancodes (virtual keycodes on macOS) with this bit set, so controls
+    // stay on the same physical key regardless of keyboard layout. Keycodes without it are Qt::Key values bound by older
+    // versions, which never have this bit set.
+    constexpr std::int32_t SCANCODE_KEYCODE_FLAG = 0x40000000;
+
+    // Get the keycode for the physical key of a key event, if the platform reported one.
+    std::optional<std::int32_t> keycode_for_key_event(const QKeyEvent *event);
+
+    // Get the keycode for a SuperShuckiePhysicalKey.
+    bool keycode_for_physical_key(std::uint32_t physical_key, std::int32_t *keycode);
+
+    // Convert a Qt::Key keycode to the keycode of the key in its location on a US QWERTY keyboard. Keycodes that are
+    // already scancodes or that aren't known are returned as-is.
+    std::int32_t migrate_legacy_keycode(std::int32_t keycode);
+
+    // Get the name of a keycode to show to the user.
+    QString keycode_name(std::int32_t keycode);
+}
+
+#endif
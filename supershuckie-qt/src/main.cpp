@@ -1,7 +1,9 @@
-#include <stdio.h>
+#include <cstdio>
+#include <cstdint>
 
 #include <SDL3/SDL.h>
 #include <QApplication>
+#include <QCommandLineParser>
 
 #ifdef _WIN32
 #include <QStyleFactory>
@@ -10,21 +12,139 @@
 #include "main_window.hpp"
 #include "theme.hpp"
 
-int main(int argc, char **argv) {
-    SDL_Init(SDL_INIT_EVENTS | SDL_INIT_GAMEPAD | SDL_INIT_VIDEO);
+static int verify_replay(const QString &user_dir, const QString &rom, const QString &replay);
+static int scrub_replay(const QString &user_dir, const QString &rom, const QString &replay, const QString &rom_name);
+static int run_serial_test(const QString &user_dir, const QString &rom, std::uint32_t max_frames);
+static int run_test_suite(const QString &user_dir, const QString &directory, std::uint32_t max_frames, const QString &report, const QString &baseline);
 
+int main(int argc, char **argv) {
     QCoreApplication::setOrganizationName("SnowyMouse");
     QCoreApplication::setApplicationName("SuperShuckie");
 
+    // Parse arguments before creating the application so --headless-verify doesn't need a display.
+    QStringList arguments;
+    for(int i = 0; i < argc; i++) {
+        arguments.append(QString::fromLocal8Bit(argv[i]));
+    }
+
+    QCommandLineParser parser;
+    parser.setApplicationDescription("Game Boy emulator with replay recording");
+    parser.addPositionalArgument("rom", "ROM to load.", "[rom]");
+    QCommandLineOption help_option({"h", "help"}, "Show this help.");
+    QCommandLineOption replay_option("replay", "Play back the replay <name> after loading the ROM.", "name");
+    QCommandLineOption record_option("record", "Start recording the replay <name> after loading the ROM.", "name");
+    QCommandLineOption savestate_option("savestate", "Load the save state <name> after loading the ROM.", "name");
+    QCommandLineOption speed_option("speed", "Set the base game speed multiplier.", "multiplier");
+    QCommandLineOption fullscreen_option("fullscreen", "Start in fullscreen.");
+    QCommandLineOption user_dir_option("user-dir", "Use <path> for settings and user data.", "path");
+    QCommandLineOption verify_option("headless-verify", "Check that the replay <name> plays back to its end with the ROM without opening a window, then exit.", "name");
+    QCommandLineOption scrub_option("scrub-replay", "Replace the ROM name and clear the ROM filename in the header of the replay <name> so it can be shared publicly, then exit.", "name");
+    QCommandLineOption scrub_rom_name_option("scrub-rom-name", "ROM name to put in replays scrubbed with --scrub-replay (empty by default).", "name");
+    QCommandLineOption serial_test_option("serial-test", "Run the ROM without opening a window until it reports a test result over the serial port or <frames> frames have run, then exit (with 0 only if it passed).", "frames");
+    QCommandLineOption test_suite_option("test-suite", "Run every test ROM in <directory> like --serial-test without opening a window, then exit (with 0 only if all passed, or if none regressed with --test-baseline).", "directory");
+    QCommandLineOption test_frames_option("test-frames", "Run each ROM in --test-suite for up to <frames> frames (7200 by default).", "frames");
+    QCommandLineOption test_report_option("test-report", "Write a JSON report of --test-suite to <file>.", "file");
+    QCommandLineOption test_baseline_option("test-baseline", "Compare --test-suite against the report <file> from an earlier run.", "file");
+    parser.addOptions({help_option, replay_option, record_option, savestate_option, speed_option, fullscreen_option, user_dir_option, verify_option, scrub_option, scrub_rom_name_option, serial_test_option, test_suite_option, test_frames_option, test_report_option, test_baseline_option});
+
+    auto fail = [&parser](const QString &message) {
+        std::fprintf(stderr, "%s\n\n%s", message.toLocal8Bit().constData(), parser.helpText().toLocal8Bit().constData());
+        return 1;
+    };
+
+    if(!parser.parse(arguments)) {
+        return fail(parser.errorText());
+    }
+    if(parser.isSet(help_option)) {
+        std::printf("%s", parser.helpText().toLocal8Bit().constData());
+        return 0;
+    }
+
+    auto positional = parser.positionalArguments();
+    if(positional.size() > 1) {
+        return fail("Only one ROM can be loaded.");
+    }
+    QString rom = positional.empty() ? QString() : positional[0];
+
+    if(parser.isSet(replay_option) && parser.isSet(record_option)) {
+        return fail("--replay and --record can't be used together.");
+    }
+    bool needs_rom = parser.isSet(replay_option) || parser.isSet(record_option) || parser.isSet(savestate_option) || parser.isSet(verify_option) || parser.isSet(scrub_option) || parser.isSet(serial_test_option);
+    if(needs_rom && rom.isEmpty()) {
+        return fail("A ROM is required for --replay, --record, --savestate, --headless-verify, --scrub-replay, and --serial-test.");
+    }
+
+    double speed = 0.0;
+    if(parser.isSet(speed_option)) {
+        bool ok = false;
+        speed = parser.value(speed_option).toDouble(&ok);
+        if(!ok || speed <= 0.0) {
+            return fail("--speed must be a positive number.");
+        }
+    }
+
+    auto user_dir = parser.isSet(user_dir_option) ? parser.value(user_dir_option) : SuperShuckie64::MainWindow::user_data_dir();
+
+    if(parser.isSet(verify_option)) {
+        return verify_replay(user_dir, rom, parser.value(verify_option));
+    }
+    if(parser.isSet(scrub_option)) {
+        return scrub_replay(user_dir, rom, parser.value(scrub_option), parser.value(scrub_rom_name_option));
+    }
+    if(parser.isSet(serial_test_option)) {
+        bool ok = false;
+        auto max_frames = parser.value(serial_test_option).toUInt(&ok);
+        if(!ok || max_frames == 0) {
+            return fail("--serial-test must be a positive number of frames.");
+        }
+        return run_serial_test(user_dir, rom, max_frames);
+    }
+    if(parser.isSet(test_suite_option)) {
+        std::uint32_t max_frames = 7200;
+        if(parser.isSet(test_frames_option)) {
+            bool ok = false;
+            max_frames = parser.value(test_frames_option).toUInt(&ok);
+            if(!ok || max_frames == 0) {
+                return fail("--test-frames must be a positive number of frames.");
+            }
+        }
+        return run_test_suite(user_dir, parser.value(test_suite_option), max_frames, parser.value(test_report_option), parser.value(test_baseline_option));
+    }
+
+    // If another window is already open, let it load the ROM instead (unless we were asked to do more than that).
+    bool only_rom = !needs_rom && !parser.isSet(speed_option) && !parser.isSet(fullscreen_option);
+    if(only_rom && supershuckie_frontend_forward_to_running_instance(user_dir.toStdString().c_str(), rom.isEmpty() ? nullptr : rom.toStdString().c_str())) {
+        return 0;
+    }
+
+    SDL_Init(SDL_INIT_EVENTS | SDL_INIT_GAMEPAD | SDL_INIT_VIDEO);
+
     QApplication app(argc, argv);
 
     SixShooter::Theme theme;
 
-    SuperShuckie64::MainWindow window;
-    window.show();
+    SuperShuckie64::MainWindow window(user_dir);
+    if(parser.isSet(fullscreen_option)) {
+        window.showFullScreen();
+    }
+    else {
+        window.show();
+    }
+
+    if(parser.isSet(speed_option)) {
+        window.set_base_speed(speed);
+    }
 
-    if(argc == 2) {
-        window.load_rom(argv[1]);
+    if(!rom.isEmpty() && window.load_rom(rom.toStdString())) {
+        if(parser.isSet(savestate_option)) {
+            window.load_save_state(parser.value(savestate_option).toStdString().c_str());
+        }
+        if(parser.isSet(replay_option)) {
+            window.play_replay(parser.value(replay_option).toStdString().c_str());
+        }
+        if(parser.isSet(record_option)) {
+            window.record_replay(parser.value(record_option).toStdString().c_str());
+        }
     }
 
     int result = app.exec();
@@ -32,3 +152,137 @@ int main(int argc, char **argv) {
 
     return result;
 }
+
+static int verify_replay(const QString &user_dir, const QString &rom, const QString &replay) {
+    SuperShuckieFrontendCallbacks callbacks = {};
+    auto *frontend = supershuckie_frontend_new(user_dir.toStdString().c_str(), &callbacks);
+
+    char error[256] = "";
+    std::uint32_t frames = 0;
+    int result = 0;
+
+    auto rom_string = rom.toStdString();
+    auto replay_string = replay.toStdString();
+
+    if(!supershuckie_frontend_load_rom(frontend, rom_string.c_str(), error, sizeof(error))) {
+        std::fprintf(stderr, "\"%s\" failed to load:\n\n%s\n", rom_string.c_str(), error);
+        result = 1;
+    }
+    else if(!supershuckie_frontend_verify_replay(frontend, replay_string.c_str(), &frames, error, sizeof(error))) {
+        std::fprintf(stderr, "Replay \"%s\" failed verification:\n\n%s\n", replay_string.c_str(), error);
+        result = 1;
+    }
+    else {
+        std::printf("Replay \"%s\" played back to its end (%u frames)\n", replay_string.c_str(), frames);
+    }
+
+    supershuckie_frontend_free(frontend);
+    return result;
+}
+
+static int scrub_replay(const QString &user_dir, const QString &rom, const QString &replay, const QString &rom_name) {
+    SuperShuckieFrontendCallbacks callbacks = {};
+    auto *frontend = supershuckie_frontend_new(user_dir.toStdString().c_str(), &callbacks);
+
+    char error[256] = "";
+    int result = 0;
+
+    auto rom_string = rom.toStdString();
+    auto replay_string = replay.toStdString();
+    auto rom_name_string = rom_name.toStdString();
+
+    if(!supershuckie_frontend_load_rom(frontend, rom_string.c_str(), error, sizeof(error))) {
+        std::fprintf(stderr, "\"%s\" failed to load:\n\n%s\n", rom_string.c_str(), error);
+        result = 1;
+    }
+    else if(!supershuckie_frontend_scrub_replay(frontend, nullptr, replay_string.c_str(), rom_name_string.c_str(), error, sizeof(error))) {
+        std::fprintf(stderr, "Replay \"%s\" could not be scrubbed:\n\n%s\n", replay_string.c_str(), error);
+        result = 1;
+    }
+    else {
+        std::printf("Replay \"%s\" was scrubbed\n", replay_string.c_str());
+    }
+
+    supershuckie_frontend_free(frontend);
+    return result;
+}
+
+static int run_serial_test(const QString &user_dir, const QString &rom, std::uint32_t max_frames) {
+    SuperShuckieFrontendCallbacks callbacks = {};
+    auto *frontend = supershuckie_frontend_new(user_dir.toStdString().c_str(), &callbacks);
+
+    char output[4096] = "";
+    std::uint32_t verdict = SuperShuckieSerialTestVerdict__TimedOut;
+    std::uint32_t frames = 0;
+    int result = 1;
+
+    auto rom_string = rom.toStdString();
+
+    if(!supershuckie_frontend_load_rom(frontend, rom_string.c_str(), output, sizeof(output))) {
+        std::fprintf(stderr, "\"%s\" failed to load:\n\n%s\n", rom_string.c_str(), output);
+    }
+    else if(!supershuckie_frontend_run_serial_test(frontend, max_frames, &verdict, &frames, output, sizeof(output))) {
+        std::fprintf(stderr, "\"%s\" could not be tested:\n\n%s\n", rom_string.c_str(), output);
+    }
+    else {
+        std::printf("%s\n", output);
+        switch(verdict) {
+            case SuperShuckieSerialTestVerdict__Passed:
+                std::printf("\"%s\" passed (%u frames)\n", rom_string.c_str(), frames);
+                result = 0;
+                break;
+            case SuperShuckieSerialTestVerdict__Failed:
+                std::printf("\"%s\" failed (%u frames)\n", rom_string.c_str(), frames);
+                break;
+            default:
+                std::printf("\"%s\" did not report a result within %u frames\n", rom_string.c_str(), frames);
+                break;
+        }
+    }
+
+    supershuckie_frontend_free(frontend);
+    return result;
+}
+
+static int run_test_suite(const QString &user_dir, const QString &directory, std::uint32_t max_frames, const QString &report, const QString &baseline) {
+    SuperShuckieFrontendCallbacks callbacks = {};
+    auto *frontend = supershuckie_frontend_new(user_dir.toStdString().c_str(), &callbacks);
+
+    char error[256] = "";
+    std::uint32_t passed = 0;
+    std::uint32_t failed = 0;
+    std::uint32_t regressions = 0;
+    int result = 1;
+
+    auto directory_string = directory.toStdString();
+    auto report_string = report.toStdString();
+    auto baseline_string = baseline.toStdString();
+
+    if(!supershuckie_frontend_run_test_suite(
+        frontend,
+        directory_string.c_str(),
+        max_frames,
+        report.isEmpty() ? nullptr : report_string.c_str(),
+        baseline.isEmpty() ? nullptr : baseline_string.c_str(),
+        &passed,
+        &failed,
+        &regressions,
+        error,
+        sizeof(error)
+    )) {
+        std::fprintf(stderr, "Test suite \"%s\" could not be run:\n\n%s\n", directory_string.c_str(), error);
+    }
+    else {
+        std::printf("%u passed, %u failed\n", passed, failed);
+        if(!baseline.isEmpty()) {
+            std::printf("%u regressed since \"%s\"\n", regressions, baseline_string.c_str());
+            result = regressions == 0 ? 0 : 1;
+        }
+        else {
+            result = failed == 0 ? 0 : 1;
+        }
+    }
+
+    supershuckie_frontend_free(frontend);
+    return result;
+}
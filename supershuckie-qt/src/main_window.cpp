@@ -1,7 +1,10 @@
 // FIXME: we need this to be somewhere else
 #define SUPERSHUCKIE_VERSION "0.1.2"
 
+#include <algorithm>
+#include <cctype>
 #include <cstdio>
+#include <cstdlib>
 #include <QLayout>
 #include <SDL3/SDL.h>
 #include <QMenuBar>
@@ -13,6 +16,7 @@
 #include <QStandardPaths>
 #include <QDesktopServices>
 #include <QGridLayout>
+#include <QPushButton>
 
 #ifdef _WIN32
 #include <windows.h>
@@ -28,6 +32,7 @@
 #include "game_speed_dialog.hpp"
 #include "render_widget.hpp"
 #include "main_window.hpp"
+#include "keyboard.hpp"
 #include "controller_settings_window.hpp"
 #include "replay_playback_controls.hpp"
 
@@ -96,7 +101,7 @@ private:
     QLabel *ds;
 };
 
-MainWindow::MainWindow(): QMainWindow() {
+MainWindow::MainWindow(const QString &app_dir): QMainWindow() {
     // Remove rounded corners (Windows)
     #ifdef _WIN32
     DWORD one = 1;
@@ -153,19 +158,21 @@ MainWindow::MainWindow(): QMainWindow() {
     callbacks.user_data = this;
     callbacks.refresh_screens = MainWindow::on_refresh_screens;
     callbacks.change_video_mode = MainWindow::on_change_video_mode;
+    callbacks.keycode_for_physical_key = MainWindow::on_keycode_for_physical_key;
+    callbacks.show_message = MainWindow::on_show_message;
+    callbacks.set_rumble = MainWindow::on_set_rumble;
+    callbacks.report_progress = MainWindow::on_report_progress;
 
-    #ifdef __APPLE__
-    this->app_dir = QStandardPaths::writableLocation(QStandardPaths::AppDataLocation);
-    QDir().mkpath(app_dir);
-    #else
-    this->app_dir = QString("./UserData");
-    #endif
+    this->app_dir = app_dir;
 
     this->frontend = supershuckie_frontend_new(
         this->app_dir.toStdString().c_str(),
         &callbacks
     );
 
+    // Keyboard controls used to be bound by Qt::Key, which moves with the keyboard layout
+    supershuckie_frontend_remap_keyboard_controls(this->frontend, [](void *, std::int32_t keycode) { return migrate_legacy_keycode(keycode); }, nullptr);
+
     const char *status_bar_visible_setting = supershuckie_frontend_get_custom_setting(this->frontend, DISPLAY_STATUS_BAR);
     bool status_bar_visible = status_bar_visible_setting != nullptr && *status_bar_visible_setting == '1';
     this->status_bar->setVisible(status_bar_visible);
@@ -178,6 +185,8 @@ MainWindow::MainWindow(): QMainWindow() {
     else if(buf[0] != 0) {
         DISPLAY_ERROR_DIALOG("Failed to automatically start Poke-A-Byte integration", "An error occurred on startup when trying to enable Poke-A-Byte integration:\n\n%s", buf);
     }
+    this->reject_pokeabyte_writes_during_playback->setChecked(supershuckie_frontend_is_rejecting_pokeabyte_writes_during_playback(this->frontend));
+    this->strict_ram_access->setChecked(supershuckie_frontend_is_strict_ram_access(this->frontend));
 
     const char *quick_slots = supershuckie_frontend_get_custom_setting(this->frontend, USE_NUMBER_KEYS_FOR_QUICK_SLOTS);
     if(quick_slots != nullptr && quick_slots[0] == '1') {
@@ -207,7 +216,16 @@ MainWindow::MainWindow(): QMainWindow() {
     this->auto_stop_replay_on_input->setChecked(supershuckie_frontend_get_auto_stop_playback_on_input_setting(this->frontend));
     this->auto_unpause_on_input->setChecked(supershuckie_frontend_get_auto_unpause_on_input_setting(this->frontend));
     this->auto_pause_on_record->setChecked(supershuckie_frontend_get_auto_pause_on_record_setting(this->frontend));
+    this->sandbox_playback_sram->setChecked(supershuckie_frontend_get_sandbox_playback_sram_setting(this->frontend));
+    this->autosnap_enabled->setChecked(supershuckie_frontend_get_autosnap_enabled_setting(this->frontend));
+    this->save_backups_enabled->setChecked(supershuckie_frontend_get_save_backups_enabled_setting(this->frontend));
+    this->single_instance->setChecked(supershuckie_frontend_get_single_instance_setting(this->frontend));
+    this->pause_on_focus_loss->setChecked(supershuckie_frontend_get_pause_on_focus_loss_setting(this->frontend));
+    this->throttle_when_minimized->setChecked(supershuckie_frontend_get_throttle_when_minimized_setting(this->frontend));
     this->sgb_enabled->setChecked(supershuckie_frontend_is_sgb_enabled(this->frontend));
+    this->frame_blending->setChecked(supershuckie_frontend_is_frame_blending_enabled(this->frontend));
+    this->onion_skin->setChecked(supershuckie_frontend_is_onion_skin_enabled(this->frontend));
+    this->printer_connected->setChecked(supershuckie_frontend_is_printer_connected(this->frontend));
 
     this->sdl.frontend = this->frontend;
     this->render_widget->setFocus(Qt::OtherFocusReason);
@@ -316,6 +334,7 @@ void MainWindow::tick() {
 
     supershuckie_frontend_tick(this->frontend);
     this->pause->setChecked(supershuckie_frontend_is_paused(this->frontend));
+    this->onion_skin->setChecked(supershuckie_frontend_is_onion_skin_enabled(this->frontend));
 
     if(supershuckie_frontend_is_paused(this->frontend)) {
         this->paused_state->show();
@@ -360,6 +379,14 @@ void MainWindow::set_up_file_menu() {
     this->unload_rom->setShortcut(QKeyCombination(Qt::ControlModifier | Qt::ShiftModifier, Qt::Key_W));
     connect(this->unload_rom, SIGNAL(triggered()), this, SLOT(do_unload_rom()));
 
+    this->file_menu->addSeparator();
+    this->save_screenshot_menu = this->file_menu->addMenu("Save screenshot");
+    for(std::uint8_t scale : { 1, 2, 4, 8, 10 }) {
+        char fmt[256];
+        std::snprintf(fmt, sizeof(fmt), "%ux", static_cast<unsigned int>(scale));
+        this->save_screenshot_menu->addAction(new NumberedAction(this, fmt, scale, &MainWindow::save_screenshot));
+    }
+
     this->file_menu->addSeparator();
     auto *open_user_dir = this->file_menu->addAction("Open data directory");
     connect(open_user_dir, SIGNAL(triggered()), this, SLOT(do_open_user_dir()));
@@ -387,11 +414,30 @@ void MainWindow::set_up_gameplay_menu() {
     this->save_new_game->setShortcut(QKeyCombination(Qt::ControlModifier | Qt::ShiftModifier, Qt::Key_S));
     connect(this->save_new_game, SIGNAL(triggered()), this, SLOT(do_save_new_game()));
 
+    this->rename_game = this->gameplay_menu->addAction("Rename current game...");
+    connect(this->rename_game, SIGNAL(triggered()), this, SLOT(do_rename_game()));
+
+    this->delete_game = this->gameplay_menu->addAction("Delete game...");
+    connect(this->delete_game, SIGNAL(triggered()), this, SLOT(do_delete_game()));
+
+    this->restore_save_backup = this->gameplay_menu->addAction("Restore save backup...");
+    connect(this->restore_save_backup, SIGNAL(triggered()), this, SLOT(do_restore_save_backup()));
+
+    this->save_backups_enabled = this->gameplay_menu->addAction("Back up save data");
+    this->save_backups_enabled->setCheckable(true);
+    connect(this->save_backups_enabled, SIGNAL(triggered()), this, SLOT(do_toggle_save_backups()));
+
     this->gameplay_menu->addSeparator();
 
     this->reset_console = this->gameplay_menu->addAction("Reset console");
     connect(this->reset_console, SIGNAL(triggered()), this, SLOT(do_reset_console()));
 
+    this->soft_reset_console = this->gameplay_menu->addAction("Soft reset console");
+    connect(this->soft_reset_console, SIGNAL(triggered()), this, SLOT(do_soft_reset_console()));
+
+    this->power_cycle_clear_sram = this->gameplay_menu->addAction("Power cycle (clear save data)");
+    connect(this->power_cycle_clear_sram, SIGNAL(triggered()), this, SLOT(do_power_cycle_clear_sram()));
+
     this->pause = this->gameplay_menu->addAction("Pause");
     this->pause->setCheckable(true);
     this->pause->setShortcut(QKeyCombination(Qt::ControlModifier, Qt::Key_P));
@@ -401,6 +447,19 @@ void MainWindow::set_up_gameplay_menu() {
     this->auto_unpause_on_input = this->gameplay_menu->addAction("Unpause on input");
     this->auto_unpause_on_input->setCheckable(true);
     connect(this->auto_unpause_on_input, SIGNAL(triggered()), this, SLOT(do_toggle_auto_unpause_on_input()));
+
+    this->gameplay_menu->addSeparator();
+    auto *host_netplay = this->gameplay_menu->addAction("Host netplay session...");
+    connect(host_netplay, SIGNAL(triggered()), this, SLOT(do_host_netplay()));
+
+    auto *join_netplay = this->gameplay_menu->addAction("Join netplay session...");
+    connect(join_netplay, SIGNAL(triggered()), this, SLOT(do_join_netplay()));
+
+    auto *spectate_netplay = this->gameplay_menu->addAction("Spectate netplay session...");
+    connect(spectate_netplay, SIGNAL(triggered()), this, SLOT(do_spectate_netplay()));
+
+    auto *stop_netplay = this->gameplay_menu->addAction("End netplay session");
+    connect(stop_netplay, SIGNAL(triggered()), this, SLOT(do_stop_netplay()));
 }
 
 void MainWindow::set_up_save_states_menu() {
@@ -441,6 +500,12 @@ void MainWindow::set_up_save_states_menu() {
     this->redo_load_save_state->setShortcut(QKeyCombination(Qt::ControlModifier | Qt::ShiftModifier, Qt::Key_U));
     connect(this->redo_load_save_state, SIGNAL(triggered()), this, SLOT(do_redo_load_save_state()));
 
+    this->save_states_menu->addSeparator();
+
+    this->autosnap_enabled = this->save_states_menu->addAction("Take save states automatically");
+    this->autosnap_enabled->setCheckable(true);
+    connect(this->autosnap_enabled, SIGNAL(triggered()), this, SLOT(do_toggle_autosnap()));
+
     this->set_quick_load_shortcuts();
 }
 
@@ -469,10 +534,29 @@ void MainWindow::set_up_replays_menu() {
     connect(this->auto_pause_on_record, SIGNAL(triggered()), this, SLOT(do_toggle_auto_pause_on_record()));
     this->auto_pause_on_record->setCheckable(true);
 
+    this->sandbox_playback_sram = this->replays_menu->addAction("Discard save data from playback");
+    connect(this->sandbox_playback_sram, SIGNAL(triggered()), this, SLOT(do_toggle_sandbox_playback_sram()));
+    this->sandbox_playback_sram->setCheckable(true);
+
     this->keyboard_replay_controls = this->replays_menu->addAction("Allow keyboard to control replay playback");
     connect(this->keyboard_replay_controls, SIGNAL(triggered()), this, SLOT(do_toggle_replay_keyboard_controls()));
     this->keyboard_replay_controls->setCheckable(true);
     this->keyboard_replay_controls->setChecked(true);
+
+    auto *load_state_record_mode_items = this->replays_menu->addMenu("When loading a save state while recording");
+
+    this->load_state_record_mode[0] = new NumberedAction(this, "Store the whole save state", SuperShuckieLoadStateRecordMode::SuperShuckieLoadStateRecordMode__Embed, &MainWindow::set_load_state_record_mode);
+    this->load_state_record_mode[1] = new NumberedAction(this, "Reference an earlier keyframe if possible", SuperShuckieLoadStateRecordMode::SuperShuckieLoadStateRecordMode__RestoreKeyframe, &MainWindow::set_load_state_record_mode);
+    this->load_state_record_mode[2] = new NumberedAction(this, "Rewind the recording if possible", SuperShuckieLoadStateRecordMode::SuperShuckieLoadStateRecordMode__Branch, &MainWindow::set_load_state_record_mode);
+
+    for(auto m : this->load_state_record_mode) {
+        m->setCheckable(true);
+        load_state_record_mode_items->addAction(m);
+    }
+
+    this->replays_menu->addSeparator();
+    auto *optimize_compression = this->replays_menu->addAction("Optimize compression for this computer");
+    connect(optimize_compression, SIGNAL(triggered()), this, SLOT(do_optimize_replay_compression()));
 }
 
 NumberedAction::NumberedAction(MainWindow *parent, const char *text, std::uint8_t number, on_activated activated): QAction(text, parent), number(number), parent(parent), activated_fn(activated) {
@@ -492,7 +576,7 @@ void MainWindow::set_video_scale(std::uint8_t scale) {
 
 void MainWindow::make_save_state(const char *state) {
     char error[256];
-    auto success = supershuckie_frontend_create_save_state(this->frontend, state, error, sizeof(error));
+    auto success = supershuckie_frontend_create_save_state(this->frontend, state, nullptr, error, sizeof(error));
     if(success) {
         char title[512];
         std::snprintf(title, sizeof(title), "Created state \"%s\"", error);
@@ -503,6 +587,19 @@ void MainWindow::make_save_state(const char *state) {
     }
 }
 
+void MainWindow::save_screenshot(std::uint8_t scale) {
+    char error[256];
+    auto success = supershuckie_frontend_save_screenshot(this->frontend, scale, error, sizeof(error));
+    if(success) {
+        char title[512];
+        std::snprintf(title, sizeof(title), "Saved screenshot \"%s\"", error);
+        this->set_title(title);
+    }
+    else {
+        DISPLAY_ERROR_DIALOG("Failed to save screenshot", "%s", error);
+    }
+}
+
 void MainWindow::load_save_state(const char *state) {
     char error[256];
     auto success = supershuckie_frontend_load_save_state(this->frontend, state, error, sizeof(error));
@@ -553,6 +650,14 @@ void MainWindow::set_up_settings_menu() {
         action->setCheckable(true);
     }
 
+    this->frame_blending = this->settings_menu->addAction("Frame blending");
+    connect(this->frame_blending, SIGNAL(triggered()), this, SLOT(do_toggle_frame_blending()));
+    this->frame_blending->setCheckable(true);
+
+    this->onion_skin = this->settings_menu->addAction("Onion skin");
+    connect(this->onion_skin, SIGNAL(triggered()), this, SLOT(do_toggle_onion_skin()));
+    this->onion_skin->setCheckable(true);
+
     this->settings_menu->addSeparator();
 
     this->game_boy_settings = this->settings_menu->addMenu("Game Boy settings");
@@ -568,19 +673,71 @@ void MainWindow::set_up_settings_menu() {
         gbc_mode_items->addAction(m);
     }
 
+    auto *color_correction_items = this->game_boy_settings->addMenu("Color correction");
+
+    this->color_correction[0] = new NumberedAction(this, "Disabled", SuperShuckieColorCorrection::SuperShuckieColorCorrection__Disabled, &MainWindow::set_color_correction);
+    this->color_correction[1] = new NumberedAction(this, "Correct curves", SuperShuckieColorCorrection::SuperShuckieColorCorrection__CorrectCurves, &MainWindow::set_color_correction);
+    this->color_correction[2] = new NumberedAction(this, "Modern - Accurate", SuperShuckieColorCorrection::SuperShuckieColorCorrection__ModernAccurate, &MainWindow::set_color_correction);
+    this->color_correction[3] = new NumberedAction(this, "Modern - Balanced", SuperShuckieColorCorrection::SuperShuckieColorCorrection__ModernBalanced, &MainWindow::set_color_correction);
+    this->color_correction[4] = new NumberedAction(this, "Modern - Boost contrast", SuperShuckieColorCorrection::SuperShuckieColorCorrection__ModernBoostContrast, &MainWindow::set_color_correction);
+    this->color_correction[5] = new NumberedAction(this, "Reduce contrast", SuperShuckieColorCorrection::SuperShuckieColorCorrection__ReduceContrast, &MainWindow::set_color_correction);
+    this->color_correction[6] = new NumberedAction(this, "Harsh reality (low contrast)", SuperShuckieColorCorrection::SuperShuckieColorCorrection__LowContrast, &MainWindow::set_color_correction);
+
+    for(auto m : this->color_correction) {
+        m->setCheckable(true);
+        color_correction_items->addAction(m);
+    }
+
     this->sgb_enabled = this->game_boy_settings->addAction("Enable SGB colors (experimental)");
     connect(this->sgb_enabled, SIGNAL(triggered()), this, SLOT(do_toggle_sgb()));
     this->sgb_enabled->setCheckable(true);
 
+    this->printer_connected = this->game_boy_settings->addAction("Connect Game Boy Printer");
+    connect(this->printer_connected, SIGNAL(triggered()), this, SLOT(do_toggle_printer()));
+    this->printer_connected->setCheckable(true);
+
     this->settings_menu->addSeparator();
 
     this->enable_pokeabyte_integration = this->settings_menu->addAction("Enable Poke-A-Byte integration");
     this->enable_pokeabyte_integration->setCheckable(true);
     connect(this->enable_pokeabyte_integration, SIGNAL(triggered()), this, SLOT(do_toggle_pokeabyte()));
 
+    this->reject_pokeabyte_writes_during_playback = this->settings_menu->addAction("Reject Poke-A-Byte writes during replay playback");
+    this->reject_pokeabyte_writes_during_playback->setCheckable(true);
+    connect(this->reject_pokeabyte_writes_during_playback, SIGNAL(triggered()), this, SLOT(do_toggle_reject_pokeabyte_writes_during_playback()));
+    this->strict_ram_access = this->settings_menu->addAction("Pause on failed RAM reads/writes");
+    this->strict_ram_access->setCheckable(true);
+    connect(this->strict_ram_access, SIGNAL(triggered()), this, SLOT(do_toggle_strict_ram_access()));
+
+    auto *disconnect_pokeabyte_client = this->settings_menu->addAction("Disconnect Poke-A-Byte client");
+    connect(disconnect_pokeabyte_client, SIGNAL(triggered()), this, SLOT(do_disconnect_pokeabyte_client()));
+
     this->show_status_bar = this->settings_menu->addAction("Show status bar");
     this->show_status_bar->setCheckable(true);
     connect(this->show_status_bar, SIGNAL(triggered()), this, SLOT(do_toggle_status_bar()));
+
+    this->pause_on_focus_loss = this->settings_menu->addAction("Pause when unfocused");
+    this->pause_on_focus_loss->setCheckable(true);
+    connect(this->pause_on_focus_loss, SIGNAL(triggered()), this, SLOT(do_toggle_pause_on_focus_loss()));
+
+    this->throttle_when_minimized = this->settings_menu->addAction("Save CPU while minimized");
+    this->throttle_when_minimized->setCheckable(true);
+    connect(this->throttle_when_minimized, SIGNAL(triggered()), this, SLOT(do_toggle_throttle_when_minimized()));
+
+    auto *power_saving_items = this->settings_menu->addMenu("Power saving");
+
+    this->power_saving[0] = new NumberedAction(this, "Off", SuperShuckiePowerSaving::SuperShuckiePowerSaving__Off, &MainWindow::set_power_saving);
+    this->power_saving[1] = new NumberedAction(this, "On battery", SuperShuckiePowerSaving::SuperShuckiePowerSaving__OnBattery, &MainWindow::set_power_saving);
+    this->power_saving[2] = new NumberedAction(this, "Always", SuperShuckiePowerSaving::SuperShuckiePowerSaving__Always, &MainWindow::set_power_saving);
+
+    for(auto m : this->power_saving) {
+        m->setCheckable(true);
+        power_saving_items->addAction(m);
+    }
+
+    this->single_instance = this->settings_menu->addAction("Open ROMs in the running window");
+    this->single_instance->setCheckable(true);
+    connect(this->single_instance, SIGNAL(triggered()), this, SLOT(do_toggle_single_instance()));
 }
 
 void MainWindow::refresh_action_states() {
@@ -590,6 +747,7 @@ void MainWindow::refresh_action_states() {
     this->replays_menu->setEnabled(game_loaded);
     this->close_rom->setEnabled(game_loaded);
     this->unload_rom->setEnabled(game_loaded);
+    this->save_screenshot_menu->setEnabled(game_loaded);
 
     for(auto &state : this->quick_load_save_states) {
         state->setEnabled(game_loaded);
@@ -614,11 +772,26 @@ void MainWindow::refresh_action_states() {
         supershuckie_frontend_get_replay_state(this->frontend) : SuperShuckieReplayState::SuperShuckieReplayState__NoReplay;
 
 
+    auto power_saving = this->frontend != nullptr ? supershuckie_frontend_get_power_saving_setting(this->frontend) : 0;
+    for(auto &i : this->power_saving) {
+        i->setChecked(i->number == power_saving);
+    }
+
     auto gbc_mode = this->frontend != nullptr ? supershuckie_frontend_get_gbc_mode(this->frontend) : 0;
     for(auto &i : this->gbc_mode) {
         i->setChecked(i->number == gbc_mode);
     }
 
+    auto color_correction = this->frontend != nullptr ? supershuckie_frontend_get_color_correction(this->frontend) : 0;
+    for(auto &i : this->color_correction) {
+        i->setChecked(i->number == color_correction);
+    }
+
+    auto load_state_record_mode = this->frontend != nullptr ? supershuckie_frontend_get_load_state_record_mode_setting(this->frontend) : 0;
+    for(auto &i : this->load_state_record_mode) {
+        i->setChecked(i->number == load_state_record_mode);
+    }
+
     switch(replay_state) {
         case SuperShuckieReplayState::SuperShuckieReplayState__Recording:
             this->play_replay->setEnabled(false);
@@ -657,7 +830,7 @@ void MainWindow::refresh_action_states() {
 void MainWindow::do_open_rom() {
     QFileDialog rom_opener;
     rom_opener.setFileMode(QFileDialog::FileMode::ExistingFile);
-    rom_opener.setNameFilters(QStringList({"GB/GBC ROM dumps (*.gb *.gbc)", "Any files (*)"}));
+    rom_opener.setNameFilters(QStringList({"GB/GBC ROM dumps (*.gb *.gbc *.zip)", "Any files (*)"}));
     rom_opener.setWindowTitle("Select a ROM to open");
     rom_opener.exec();
 
@@ -669,12 +842,58 @@ void MainWindow::do_open_rom() {
     this->load_rom(files[0].toStdString());
 }
 
-void MainWindow::load_rom(const std::filesystem::path &path) {
+QString MainWindow::user_data_dir() {
+    #ifdef __APPLE__
+    auto app_dir = QStandardPaths::writableLocation(QStandardPaths::AppDataLocation);
+    QDir().mkpath(app_dir);
+    return app_dir;
+    #else
+    return QString("./UserData");
+    #endif
+}
+
+bool MainWindow::load_rom(const std::filesystem::path &path) {
     char error[256] = "";
 
     auto path_string = path.string();
-    if(!supershuckie_frontend_load_rom(this->frontend, path.string().c_str(), error, sizeof(error))) {
+
+    // Let the user pick if an archive has more than one ROM in it
+    auto extension = path.extension().string();
+    std::transform(extension.begin(), extension.end(), extension.begin(), [](unsigned char c) { return std::tolower(c); });
+    if(extension == ".zip") {
+        auto *roms_ptr = supershuckie_frontend_get_roms_in_archive(this->frontend, path_string.c_str(), error, sizeof(error));
+        if(roms_ptr == nullptr) {
+            DISPLAY_ERROR_DIALOG("Can't load ROM", "\"%s\" failed to load:\n\n%s", path_string.c_str(), error);
+            return false;
+        }
+
+        auto roms = wrap_array_std(roms_ptr);
+        if(roms.size() > 1) {
+            auto rom = SelectItemDialog::ask(this, roms, "Select a ROM", "This archive contains multiple ROMs. Select one to load.");
+            if(rom == std::nullopt) {
+                return false;
+            }
+            if(!supershuckie_frontend_load_rom_from_archive(this->frontend, path_string.c_str(), rom->c_str(), error, sizeof(error))) {
+                DISPLAY_ERROR_DIALOG("Can't load ROM", "\"%s\" failed to load:\n\n%s", rom->c_str(), error);
+                return false;
+            }
+            return true;
+        }
+    }
+
+    if(!supershuckie_frontend_load_rom(this->frontend, path_string.c_str(), error, sizeof(error))) {
         DISPLAY_ERROR_DIALOG("Can't load ROM", "\"%s\" failed to load:\n\n%s", path_string.c_str(), error);
+        return false;
+    }
+    return true;
+}
+
+void MainWindow::set_base_speed(double speed) {
+    double turbo;
+    supershuckie_frontend_get_speed_settings(this->frontend, nullptr, &turbo);
+    char err[256];
+    if(!supershuckie_frontend_set_speed_settings(this->frontend, speed, turbo, err, sizeof(err))) {
+        DISPLAY_ERROR_DIALOG("Can't change speed", "%s", err);
     }
 }
 
@@ -724,6 +943,86 @@ void MainWindow::do_reset_console() {
     supershuckie_frontend_hard_reset_console(this->frontend);
 }
 
+void MainWindow::do_soft_reset_console() {
+    supershuckie_frontend_soft_reset_console(this->frontend);
+}
+
+void MainWindow::do_power_cycle_clear_sram() {
+    auto answer = QMessageBox::question(this, "Power cycle", "This will permanently delete the current save file's data and restart the game. Continue?");
+    if(answer != QMessageBox::Yes) {
+        return;
+    }
+    char error[256];
+    if(!supershuckie_frontend_power_cycle_with_fresh_sram(this->frontend, error, sizeof(error))) {
+        DISPLAY_ERROR_DIALOG("Failed to power cycle", "%s", error);
+        return;
+    }
+    this->set_title("Power cycled with empty save data");
+}
+
+void MainWindow::do_host_netplay() {
+    auto text = AskForTextDialog::ask(this, "Host netplay session", "Enter the UDP port to listen on", "The other player must have the same ROM loaded.");
+    if(text == std::nullopt) {
+        return;
+    }
+
+    char *end = nullptr;
+    auto port = std::strtoul(text->c_str(), &end, 10);
+    if(text->empty() || *end != 0 || port == 0 || port > UINT16_MAX) {
+        DISPLAY_ERROR_DIALOG("Failed to host netplay session", "\"%s\" is not a valid port.", text->c_str());
+        return;
+    }
+
+    char err[256];
+    if(!supershuckie_frontend_host_netplay(this->frontend, static_cast<std::uint16_t>(port), err, sizeof(err))) {
+        DISPLAY_ERROR_DIALOG("Failed to host netplay session", "%s", err);
+        return;
+    }
+
+    char fmt[256];
+    std::snprintf(fmt, sizeof(fmt), "Hosting netplay session on port %lu", port);
+    this->set_title(fmt);
+}
+
+void MainWindow::do_join_netplay() {
+    auto text = AskForTextDialog::ask(this, "Join netplay session", "Enter the address of the host (e.g. 192.168.1.2:7845)", "You must have the same ROM loaded as the host.");
+    if(text == std::nullopt) {
+        return;
+    }
+
+    char err[256];
+    if(!supershuckie_frontend_join_netplay(this->frontend, text->c_str(), err, sizeof(err))) {
+        DISPLAY_ERROR_DIALOG("Failed to join netplay session", "%s", err);
+        return;
+    }
+
+    char fmt[256];
+    std::snprintf(fmt, sizeof(fmt), "Joining netplay session at %s", text->c_str());
+    this->set_title(fmt);
+}
+
+void MainWindow::do_spectate_netplay() {
+    auto text = AskForTextDialog::ask(this, "Spectate netplay session", "Enter the address of the host (e.g. 192.168.1.2:7845)", "You must have the same ROM loaded as the host.");
+    if(text == std::nullopt) {
+        return;
+    }
+
+    char err[256];
+    if(!supershuckie_frontend_spectate_netplay(this->frontend, text->c_str(), err, sizeof(err))) {
+        DISPLAY_ERROR_DIALOG("Failed to spectate netplay session", "%s", err);
+        return;
+    }
+
+    char fmt[256];
+    std::snprintf(fmt, sizeof(fmt), "Spectating netplay session at %s", text->c_str());
+    this->set_title(fmt);
+}
+
+void MainWindow::do_stop_netplay() {
+    supershuckie_frontend_stop_netplay(this->frontend);
+    this->set_title("Ended netplay session");
+}
+
 void MainWindow::do_toggle_pause() {
     supershuckie_frontend_set_paused(this->frontend, this->pause->isChecked());
 }
@@ -763,6 +1062,17 @@ void MainWindow::closeEvent(QCloseEvent *event) {
     // }
 }
 
+void MainWindow::changeEvent(QEvent *event) {
+    QMainWindow::changeEvent(event);
+
+    if(event->type() == QEvent::ActivationChange && this->frontend) {
+        supershuckie_frontend_set_focused(this->frontend, this->isActiveWindow());
+    }
+    else if(event->type() == QEvent::WindowStateChange && this->frontend) {
+        supershuckie_frontend_set_minimized(this->frontend, this->isMinimized());
+    }
+}
+
 MainWindow::~MainWindow() {
     if(this->frontend) {
         supershuckie_frontend_free(this->frontend);
@@ -779,15 +1089,20 @@ void MainWindow::do_record_replay() {
         this->set_title(saved);
     }
     else {
-        char result[256];
-        if(supershuckie_frontend_start_recording_replay(this->frontend, nullptr, result, sizeof(result))) {
-            char fmt[512];
-            std::snprintf(fmt, sizeof(fmt), "Started recording replay \"%s\"", result);
-            this->set_title(fmt);
-        }
-        else {
-            DISPLAY_ERROR_DIALOG("Failed to start recording replay", "%s", result);
-        }
+        this->record_replay(nullptr);
+    }
+    this->refresh_action_states();
+}
+
+void MainWindow::record_replay(const char *name) {
+    char result[256];
+    if(supershuckie_frontend_start_recording_replay(this->frontend, name, result, sizeof(result))) {
+        char fmt[512];
+        std::snprintf(fmt, sizeof(fmt), "Started recording replay \"%s\"", result);
+        this->set_title(fmt);
+    }
+    else {
+        DISPLAY_ERROR_DIALOG("Failed to start recording replay", "%s", result);
     }
     this->refresh_action_states();
 }
@@ -806,7 +1121,7 @@ std::vector<std::string> SuperShuckie64::wrap_array_std(SuperShuckieStringArrayR
 
 void MainWindow::do_load_game() {
     // TODO: consider pre-selecting the save that we're already on?
-    auto saves = wrap_array_std(supershuckie_frontend_get_all_saves_for_rom(this->frontend, nullptr));
+    auto saves = wrap_array_std(supershuckie_frontend_get_save_profiles(this->frontend, nullptr));
 
     auto text = SelectItemDialog::ask(this, saves, "Select a save", "Select a save file to load.");
     if(text == std::nullopt) {
@@ -820,6 +1135,80 @@ void MainWindow::do_load_game() {
     this->set_title(fmt);
 }
 
+void MainWindow::do_rename_game() {
+    const char *current = supershuckie_frontend_get_current_save_name(this->frontend);
+    if(current == nullptr) {
+        return;
+    }
+    std::string old_name = current;
+
+    auto text = AskForTextDialog::ask(this, "Rename game", "Enter the new name of the current save file");
+    if(text == std::nullopt) {
+        return;
+    }
+
+    char err[256];
+    if(!supershuckie_frontend_rename_save_profile(this->frontend, old_name.c_str(), text->c_str(), err, sizeof(err))) {
+        DISPLAY_ERROR_DIALOG("Failed to rename save file", "Save file \"%s\" could not be renamed:\n\n%s", old_name.c_str(), err);
+        return;
+    }
+
+    char fmt[256];
+    std::snprintf(fmt, sizeof(fmt), "Renamed save file \"%s\" to \"%s\"", old_name.c_str(), text->c_str());
+    this->set_title(fmt);
+}
+
+void MainWindow::do_delete_game() {
+    auto saves = wrap_array_std(supershuckie_frontend_get_save_profiles(this->frontend, nullptr));
+    const char *current = supershuckie_frontend_get_current_save_name(this->frontend);
+    if(current != nullptr) {
+        std::erase(saves, std::string(current));
+    }
+
+    if(saves.empty()) {
+        this->set_title("There are no other save files to delete");
+        return;
+    }
+
+    auto text = SelectItemDialog::ask(this, saves, "Select a save", "Select a save file to delete. It will be backed up first if save backups are enabled.");
+    if(text == std::nullopt) {
+        return;
+    }
+
+    char err[256];
+    if(!supershuckie_frontend_delete_save_profile(this->frontend, text->c_str(), err, sizeof(err))) {
+        DISPLAY_ERROR_DIALOG("Failed to delete save file", "Save file \"%s\" could not be deleted:\n\n%s", text->c_str(), err);
+        return;
+    }
+
+    char fmt[256];
+    std::snprintf(fmt, sizeof(fmt), "Deleted save file \"%s\"", text->c_str());
+    this->set_title(fmt);
+}
+
+void MainWindow::do_restore_save_backup() {
+    auto backups = wrap_array_std(supershuckie_frontend_get_save_backups(this->frontend));
+    if(backups.empty()) {
+        this->set_title("There are no backups of this save file");
+        return;
+    }
+
+    auto text = SelectItemDialog::ask(this, backups, "Select a backup", "Select a backup to restore. The game will be restarted.");
+    if(text == std::nullopt) {
+        return;
+    }
+
+    char err[256];
+    if(!supershuckie_frontend_restore_save_backup(this->frontend, text->c_str(), err, sizeof(err))) {
+        DISPLAY_ERROR_DIALOG("Failed to restore backup", "Backup \"%s\" failed to restore:\n\n%s", text->c_str(), err);
+        return;
+    }
+
+    char fmt[256];
+    std::snprintf(fmt, sizeof(fmt), "Restored save backup \"%s\"", text->c_str());
+    this->set_title(fmt);
+}
+
 void MainWindow::do_resume_replay() {
     // TODO
     auto replays = wrap_array_std(supershuckie_frontend_get_all_replays_for_rom(this->frontend, nullptr));
@@ -838,14 +1227,62 @@ void MainWindow::do_play_replay() {
         return;
     }
 
+    this->play_replay(text->c_str());
+}
+
+void MainWindow::play_replay(const char *name) {
     char err[256];
     char fmt[512];
-    
-    if(!supershuckie_frontend_load_replay(this->frontend, text->c_str(), false, err, sizeof(err))) {
+
+    bool loaded = supershuckie_frontend_load_replay_acknowledging(this->frontend, name, 0, 0, nullptr, err, sizeof(err));
+    std::uint32_t mismatches = loaded ? 0 : supershuckie_frontend_get_replay_mismatches(this->frontend);
+
+    // Ask about each mismatch separately so only the ones the user is fine with get acknowledged
+    if(mismatches != 0) {
+        std::uint32_t acknowledged = 0;
+        std::uint32_t remembered = 0;
+
+        for(std::uint32_t kind : {
+            SuperShuckieReplayMismatch__ROMChecksum,
+            SuperShuckieReplayMismatch__BIOSChecksum,
+            SuperShuckieReplayMismatch__Core,
+            SuperShuckieReplayMismatch__SRAMChecksum
+        }) {
+            if((mismatches & kind) == 0) {
+                continue;
+            }
+
+            char description[512] = "";
+            supershuckie_frontend_get_replay_mismatch_description(this->frontend, kind, description, sizeof(description));
+
+            QMessageBox qmb(this);
+            qmb.setWindowTitle("Replay file issues detected");
+            qmb.setIcon(QMessageBox::Icon::Warning);
+            qmb.setText(description);
+            auto *play_button = qmb.addButton("Play anyway", QMessageBox::AcceptRole);
+            auto *always_button = qmb.addButton("Always play this replay anyway", QMessageBox::AcceptRole);
+            qmb.addButton(QMessageBox::Cancel);
+            qmb.exec();
+
+            if(qmb.clickedButton() == always_button) {
+                remembered |= kind;
+            }
+            else if(qmb.clickedButton() == play_button) {
+                acknowledged |= kind;
+            }
+            else {
+                return;
+            }
+        }
+
+        loaded = supershuckie_frontend_load_replay_acknowledging(this->frontend, name, acknowledged, remembered, nullptr, err, sizeof(err));
+    }
+
+    if(!loaded) {
         std::snprintf(fmt, sizeof(fmt), "%s", err);
         DISPLAY_ERROR_DIALOG("Replay file issues detected", "%s", fmt);
 
-        if(!supershuckie_frontend_load_replay(this->frontend, text->c_str(), true, err, sizeof(err))) {
+        if(!supershuckie_frontend_load_replay(this->frontend, name, true, err, sizeof(err))) {
             return;
         }
     }
@@ -854,7 +1291,7 @@ void MainWindow::do_play_replay() {
         return;
     }
 
-    std::snprintf(fmt, sizeof(fmt), "Opened replay file \"%s\"", text->c_str());
+    std::snprintf(fmt, sizeof(fmt), "Opened replay file \"%s\"", name);
     this->set_title(fmt);
 
     this->playback_bar->show();
@@ -869,6 +1306,42 @@ void MainWindow::on_refresh_screens(void *user_data, std::size_t screen_count, c
     self->render_widget->refresh_screen(first_screen);
 }
 
+void MainWindow::on_show_message(void *user_data, const char *message) {
+    auto *self = reinterpret_cast<MainWindow *>(user_data);
+    self->render_widget->show_osd_message(QString(message));
+}
+
+void MainWindow::on_set_rumble(void *user_data, double amplitude) {
+    auto *self = reinterpret_cast<MainWindow *>(user_data);
+    self->sdl.set_rumble(amplitude);
+}
+
+void MainWindow::on_report_progress(void *user_data, std::uint32_t, std::uint32_t operation, std::uint32_t stage, std::uint8_t percent) {
+    auto *self = reinterpret_cast<MainWindow *>(user_data);
+
+    if(stage == SuperShuckieCoreOperationStage__Finished) {
+        self->status_bar->clearMessage();
+        return;
+    }
+
+    const char *what;
+    switch(operation) {
+        case SuperShuckieCoreOperation__CreateSaveState: what = "Creating save state"; break;
+        case SuperShuckieCoreOperation__SeekReplay: what = "Seeking"; break;
+        case SuperShuckieCoreOperation__FinishReplay: what = "Finishing replay"; break;
+        default: what = "Working"; break;
+    }
+
+    self->status_bar->showMessage(QString("%1... %2%").arg(what).arg(percent));
+
+    // Blocking operations report progress before returning to the event loop, so repaint now
+    self->status_bar->repaint();
+}
+
+bool MainWindow::on_keycode_for_physical_key(void *, std::uint32_t physical_key, std::int32_t *keycode) {
+    return keycode_for_physical_key(physical_key, keycode);
+}
+
 void MainWindow::on_change_video_mode(void *user_data, std::size_t screen_count, const SuperShuckieScreenData *screen_data, std::uint8_t video_scale) {
     auto *self = reinterpret_cast<MainWindow *>(user_data);
     
@@ -938,6 +1411,18 @@ void MainWindow::do_toggle_pokeabyte() {
     }
 }
 
+void MainWindow::do_toggle_reject_pokeabyte_writes_during_playback() {
+    supershuckie_frontend_set_rejecting_pokeabyte_writes_during_playback(this->frontend, this->reject_pokeabyte_writes_during_playback->isChecked());
+}
+
+void MainWindow::do_toggle_strict_ram_access() {
+    supershuckie_frontend_set_strict_ram_access(this->frontend, this->strict_ram_access->isChecked());
+}
+
+void MainWindow::do_disconnect_pokeabyte_client() {
+    supershuckie_frontend_disconnect_pokeabyte_client(this->frontend);
+}
+
 void MainWindow::do_toggle_stop_replay_on_input() {
     supershuckie_frontend_set_auto_stop_playback_on_input_setting(this->frontend, this->auto_stop_replay_on_input->isChecked());
 }
@@ -977,6 +1462,30 @@ void MainWindow::do_toggle_auto_pause_on_record() {
     supershuckie_frontend_set_auto_pause_on_record_setting(this->frontend, this->auto_pause_on_record->isChecked());
 }
 
+void MainWindow::do_toggle_sandbox_playback_sram() {
+    supershuckie_frontend_set_sandbox_playback_sram_setting(this->frontend, this->sandbox_playback_sram->isChecked());
+}
+
+void MainWindow::do_toggle_autosnap() {
+    supershuckie_frontend_set_autosnap_enabled_setting(this->frontend, this->autosnap_enabled->isChecked());
+}
+
+void MainWindow::do_toggle_save_backups() {
+    supershuckie_frontend_set_save_backups_enabled_setting(this->frontend, this->save_backups_enabled->isChecked());
+}
+
+void MainWindow::do_toggle_pause_on_focus_loss() {
+    supershuckie_frontend_set_pause_on_focus_loss_setting(this->frontend, this->pause_on_focus_loss->isChecked());
+}
+
+void MainWindow::do_toggle_throttle_when_minimized() {
+    supershuckie_frontend_set_throttle_when_minimized_setting(this->frontend, this->throttle_when_minimized->isChecked());
+}
+
+void MainWindow::do_toggle_single_instance() {
+    supershuckie_frontend_set_single_instance_setting(this->frontend, this->single_instance->isChecked());
+}
+
 void MainWindow::do_open_user_dir() {
     QDesktopServices::openUrl(QUrl::fromLocalFile(this->app_dir));
 }
@@ -989,11 +1498,51 @@ void MainWindow::do_toggle_replay_keyboard_controls() {
     supershuckie_frontend_set_custom_setting(this->frontend, KEYBOARD_REPLAY_CONTROLS_DISABLED, !this->keyboard_replay_controls->isChecked() ? "1" : "0");
 }
 
+void MainWindow::do_toggle_frame_blending() {
+    supershuckie_frontend_set_frame_blending_enabled(this->frontend, this->frame_blending->isChecked());
+}
+
+void MainWindow::do_toggle_onion_skin() {
+    supershuckie_frontend_set_onion_skin_enabled(this->frontend, this->onion_skin->isChecked());
+}
+
 void MainWindow::do_toggle_sgb() {
     supershuckie_frontend_set_sgb_enabled(this->frontend, this->sgb_enabled->isChecked());
 }
 
+void MainWindow::do_toggle_printer() {
+    supershuckie_frontend_set_printer_connected(this->frontend, this->printer_connected->isChecked());
+}
+
+void MainWindow::do_optimize_replay_compression() {
+    std::int32_t level = 0;
+    char error[256];
+    if(!supershuckie_frontend_optimize_replay_compression_level(this->frontend, &level, error, sizeof(error))) {
+        DISPLAY_ERROR_DIALOG("Failed to optimize compression", "%s", error);
+        return;
+    }
+
+    char message[256];
+    std::snprintf(message, sizeof(message), "Replays will now be compressed with zstd level %d.", static_cast<int>(level));
+    QMessageBox::information(this, "Optimize compression", message);
+}
+
+void MainWindow::set_power_saving(std::uint8_t mode) {
+    supershuckie_frontend_set_power_saving_setting(this->frontend, mode);
+    this->refresh_action_states();
+}
+
 void MainWindow::set_gbc_mode(std::uint8_t mode) {
     supershuckie_frontend_set_gbc_mode(this->frontend, mode);
     this->refresh_action_states();
 }
+
+void MainWindow::set_color_correction(std::uint8_t mode) {
+    supershuckie_frontend_set_color_correction(this->frontend, mode);
+    this->refresh_action_states();
+}
+
+void MainWindow::set_load_state_record_mode(std::uint8_t mode) {
+    supershuckie_frontend_set_load_state_record_mode_setting(this->frontend, mode);
+    this->refresh_action_states();
+}
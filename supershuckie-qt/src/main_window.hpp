@@ -12,6 +12,7 @@
 class QMenu;
 class QAction;
 class QCloseEvent;
+class QEvent;
 class QLabel;
 
 namespace SuperShuckie64 {
@@ -44,10 +45,16 @@ class MainWindow: public QMainWindow {
     friend ReplayPlaybackControls;
     
 public:
-    MainWindow();
+    explicit MainWindow(const QString &app_dir = MainWindow::user_data_dir());
     ~MainWindow();
 
-    void load_rom(const std::filesystem::path &path);
+    bool load_rom(const std::filesystem::path &path);
+    void load_save_state(const char *state);
+    void play_replay(const char *name);
+    void record_replay(const char *name);
+    void set_base_speed(double speed);
+
+    static QString user_data_dir();
 
 private:
     typedef std::chrono::steady_clock clock;
@@ -72,6 +79,10 @@ private:
     QMenu *quick_slots;
     QAction *undo_load_save_state;
     QAction *redo_load_save_state;
+    QAction *autosnap_enabled;
+    QAction *single_instance;
+    QAction *pause_on_focus_loss;
+    QAction *throttle_when_minimized;
 
     QStatusBar *status_bar;
     QLabel *status_bar_fps;
@@ -80,12 +91,19 @@ private:
     QAction *open_rom;
     QAction *close_rom;
     QAction *unload_rom;
+    QMenu *save_screenshot_menu;
 
     QAction *new_game;
     QAction *load_game;
     QAction *save_game;
     QAction *save_new_game;
+    QAction *rename_game;
+    QAction *delete_game;
+    QAction *restore_save_backup;
+    QAction *save_backups_enabled;
     QAction *reset_console;
+    QAction *soft_reset_console;
+    QAction *power_cycle_clear_sram;
     QAction *pause;
     QAction *quit;
 
@@ -95,11 +113,18 @@ private:
     QAction *auto_stop_replay_on_input;
     QAction *auto_unpause_on_input;
     QAction *auto_pause_on_record;
+    QAction *sandbox_playback_sram;
     QAction *keyboard_replay_controls;
 
+    QAction *frame_blending;
+    QAction *onion_skin;
     QAction *sgb_enabled;
+    QAction *printer_connected;
     QMenu *game_boy_settings;
     NumberedAction *gbc_mode[3];
+    NumberedAction *power_saving[3];
+    NumberedAction *color_correction[7];
+    NumberedAction *load_state_record_mode[3];
 
     QLabel *current_state;
     QLabel *paused_state;
@@ -109,6 +134,8 @@ private:
     QAction *use_number_row_for_quick_slots;
     QAction *show_status_bar;
     QAction *enable_pokeabyte_integration;
+    QAction *reject_pokeabyte_writes_during_playback;
+    QAction *strict_ram_access;
 
     SuperShuckieReplayState last_known_replay_state = SuperShuckieReplayState::SuperShuckieReplayState__NoReplay;
 
@@ -138,13 +165,17 @@ private:
     void quick_save(std::uint8_t index);
     void quick_load(std::uint8_t index);
     void set_gbc_mode(std::uint8_t mode);
+    void set_power_saving(std::uint8_t mode);
+    void set_color_correction(std::uint8_t mode);
+    void save_screenshot(std::uint8_t scale);
+    void set_load_state_record_mode(std::uint8_t mode);
 
     void make_save_state(const char *state);
-    void load_save_state(const char *state);
 
     void set_video_scale(std::uint8_t scale);
 
     void closeEvent(QCloseEvent *event) override;
+    void changeEvent(QEvent *event) override;
 
     bool is_game_running();
 
@@ -152,6 +183,10 @@ private:
 
     static void on_refresh_screens(void *user_data, std::size_t screen_count, const uint32_t *const *pixels);
     static void on_change_video_mode(void *user_data, std::size_t screen_count, const SuperShuckieScreenData *screen_data, std::uint8_t scaling);
+    static bool on_keycode_for_physical_key(void *user_data, std::uint32_t physical_key, std::int32_t *keycode);
+    static void on_show_message(void *user_data, const char *message);
+    static void on_set_rumble(void *user_data, double amplitude);
+    static void on_report_progress(void *user_data, std::uint32_t operation_id, std::uint32_t operation, std::uint32_t stage, std::uint8_t percent);
 
     std::uint32_t frames_in_last_second = 0;
     double current_fps = 0.0;
@@ -172,10 +207,20 @@ private slots:
     void do_unload_rom();
     void do_new_game() noexcept;
     void do_load_game();
+    void do_rename_game();
+    void do_delete_game();
+    void do_restore_save_backup();
+    void do_toggle_save_backups();
     void do_save_game();
     void do_save_new_game();
     void do_reset_console();
+    void do_soft_reset_console();
+    void do_power_cycle_clear_sram();
     void do_toggle_pause();
+    void do_host_netplay();
+    void do_join_netplay();
+    void do_spectate_netplay();
+    void do_stop_netplay();
     void do_toggle_number_row_for_save_states();
     void do_record_replay();
     void do_resume_replay();
@@ -185,14 +230,26 @@ private slots:
     void do_redo_load_save_state();
     void do_toggle_status_bar();
     void do_toggle_pokeabyte();
+    void do_toggle_reject_pokeabyte_writes_during_playback();
+    void do_toggle_strict_ram_access();
+    void do_disconnect_pokeabyte_client();
     void do_toggle_stop_replay_on_input();
     void do_open_controls_settings_dialog() noexcept;
     void do_toggle_auto_unpause_on_input();
     void do_toggle_auto_pause_on_record();
+    void do_toggle_sandbox_playback_sram();
+    void do_toggle_autosnap();
+    void do_toggle_single_instance();
+    void do_toggle_pause_on_focus_loss();
+    void do_toggle_throttle_when_minimized();
     void do_open_user_dir();
     void do_change_playback_time(int frames);
     void do_toggle_replay_keyboard_controls();
+    void do_toggle_frame_blending();
+    void do_toggle_onion_skin();
     void do_toggle_sgb();
+    void do_toggle_printer();
+    void do_optimize_replay_compression();
 };
 
 class NumberedAction: public QAction {
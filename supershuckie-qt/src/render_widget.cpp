@@ -1,8 +1,11 @@
 #include "render_widget.hpp"
 #include "main_window.hpp"
+#include "keyboard.hpp"
 #include <QGraphicsPixmapItem>
 #include <QKeyEvent>
 #include <QMimeData>
+#include <QLabel>
+#include <QTimer>
 
 using namespace SuperShuckie64;
 
@@ -12,6 +15,23 @@ GameRenderWidget::GameRenderWidget(MainWindow *window, QWidget *parent): QGraphi
     this->setVerticalScrollBarPolicy(Qt::ScrollBarPolicy::ScrollBarAlwaysOff);
     this->setSizePolicy(QSizePolicy::Policy::Fixed, QSizePolicy::Policy::Fixed);
     this->setFocusPolicy(Qt::ClickFocus);
+
+    this->osd_label = new QLabel(this);
+    this->osd_label->setStyleSheet("QLabel { background-color: rgba(0, 0, 0, 160); color: white; padding: 4px; }");
+    this->osd_label->move(8, 8);
+    this->osd_label->hide();
+
+    this->osd_timer = new QTimer(this);
+    this->osd_timer->setSingleShot(true);
+    connect(this->osd_timer, &QTimer::timeout, this->osd_label, &QLabel::hide);
+}
+
+void GameRenderWidget::show_osd_message(const QString &message) {
+    this->osd_label->setText(message);
+    this->osd_label->adjustSize();
+    this->osd_label->show();
+    this->osd_label->raise();
+    this->osd_timer->start(2000);
 }
 
 void GameRenderWidget::set_dimensions(unsigned width, unsigned height, unsigned scale) {
@@ -119,8 +139,9 @@ void GameRenderWidget::keyPressEvent(QKeyEvent *event) {
             }
         }
 
-        if(!auto_repeat) {
-            supershuckie_frontend_key_press(this->main_window->frontend, key, true);
+        auto keycode = keycode_for_key_event(event);
+        if(!auto_repeat && keycode.has_value()) {
+            supershuckie_frontend_key_press(this->main_window->frontend, *keycode, true);
         }
     }
 }
@@ -128,8 +149,9 @@ void GameRenderWidget::keyPressEvent(QKeyEvent *event) {
 void GameRenderWidget::keyReleaseEvent(QKeyEvent *event) {
     QWidget::keyReleaseEvent(event);
 
-    if(this->main_window->frontend != nullptr && !event->isAutoRepeat()) {
-        supershuckie_frontend_key_press(this->main_window->frontend, event->key(), false);
+    auto keycode = keycode_for_key_event(event);
+    if(this->main_window->frontend != nullptr && !event->isAutoRepeat() && keycode.has_value()) {
+        supershuckie_frontend_key_press(this->main_window->frontend, *keycode, false);
     }
 }
 
@@ -6,6 +6,8 @@
 #include <QPixmap>
 
 class QGraphicsScene;
+class QLabel;
+class QTimer;
 
 namespace SuperShuckie64 {
 
@@ -16,6 +18,7 @@ class GameRenderWidget: public QGraphicsView {
     friend MainWindow;
 public:
     void set_dimensions(unsigned width, unsigned height, unsigned scale);
+    void show_osd_message(const QString &message);
 
 private:
     GameRenderWidget(MainWindow *window, QWidget *parent);
@@ -28,6 +31,8 @@ private:
     QPixmap pixmap;
     QGraphicsScene *scene = nullptr;
     QGraphicsPixmapItem *pixmap_item = nullptr;
+    QLabel *osd_label = nullptr;
+    QTimer *osd_timer = nullptr;
 
     void rebuild_scene();
     void force_refresh_screen();
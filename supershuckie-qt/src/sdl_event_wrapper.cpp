@@ -1,6 +1,7 @@
 #include "sdl_event_wrapper.hpp"
 
 #include <SDL3/SDL.h>
+#include <algorithm>
 
 using namespace SuperShuckie64;
 
@@ -8,6 +9,17 @@ SDLEventWrapper::SDLEventWrapper() {
 
 }
 
+void SDLEventWrapper::set_rumble(double amplitude) {
+    auto intensity = static_cast<Uint16>(std::clamp(amplitude, 0.0, 1.0) * 0xFFFF);
+    for(auto &[id, _] : this->connected_controllers) {
+        auto *gamepad = SDL_GetGamepadFromID(id);
+        if(gamepad != nullptr) {
+            // Each call replaces the previous effect, so just rumble for as long as SDL allows until told otherwise
+            SDL_RumbleGamepad(gamepad, intensity, intensity, 0xFFFF);
+        }
+    }
+}
+
 SDLEventWrapperResult SDLEventWrapper::next() {
     SDLEventWrapperResult result = {};
     char msg[256];
@@ -29,6 +41,10 @@ SDLEventWrapperResult SDLEventWrapper::next() {
                 auto *name = SDL_GetGamepadName(gamepad);
                 auto mapping = supershuckie_frontend_connect_controller(this->frontend, name);
 
+                char guid[64];
+                SDL_GUIDToString(SDL_GetGamepadGUIDForID(id), guid, sizeof(guid));
+                supershuckie_frontend_set_controller_guid(this->frontend, mapping, guid);
+
                 std::snprintf(msg, sizeof(msg), "Connected controller \"%s\"", name);
                 this->events_to_print.emplace_back(msg);
 
@@ -47,6 +47,7 @@ class SDLEventWrapper {
 public:
     SDLEventWrapper();
     SDLEventWrapperResult next();
+    void set_rumble(double amplitude);
 private:
     SuperShuckieFrontendRaw *frontend = nullptr;
     std::unordered_map<std::uint32_t, ConnectedController> connected_controllers;
@@ -0,0 +1,106 @@
+use crate::{ByteVec, PacketIO, UnsignedInteger};
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+
+/// Runs of changed bytes separated by at most this many unchanged bytes are merged into one run,
+/// since starting a new run costs about as much.
+const STATE_DIFF_MERGE_GAP: usize = 4;
+
+/// Largest state [`apply_state_diff`] reconstructs.
+///
+/// Save states for supported consoles are at most a few MiB, so a diff claiming a larger target
+/// is corrupted (or malicious) and would otherwise allocate whatever it asked for.
+pub const MAX_STATE_DIFF_TARGET_SIZE: usize = 16 * 1024 * 1024;
+
+// State diffs are encoded as:
+//
+// - UnsignedInteger: length of the target state
+// - Any number of runs, each consisting of:
+//   - UnsignedInteger: number of unchanged bytes since the end of the previous run (or the start)
+//   - UnsignedInteger: number of changed bytes
+//   - The changed bytes
+//
+// The base state is treated as if it were truncated or zero-padded to the target state's length.
+
+/// Encode the differences between `base` and `target` (see [`Packet::LoadSaveStateDiff`](crate::Packet::LoadSaveStateDiff)).
+///
+/// The result is deterministic: runs of changed bytes are merged whenever they are separated by four
+/// or fewer unchanged bytes.
+pub fn create_state_diff(base: &[u8], target: &[u8]) -> ByteVec {
+    let changed = |i: usize| base.get(i).copied().unwrap_or(0) != target[i];
+
+    let mut diff = ByteVec::new();
+    write_unsigned(&mut diff, target.len());
+
+    let mut run_end = 0usize;
+    let mut i = 0usize;
+    while i < target.len() {
+        if !changed(i) {
+            i += 1;
+            continue
+        }
+
+        let run_start = i;
+        let mut last_changed = i;
+        while i < target.len() && i - last_changed <= STATE_DIFF_MERGE_GAP {
+            if changed(i) {
+                last_changed = i;
+            }
+            i += 1;
+        }
+        let run = &target[run_start..=last_changed];
+
+        write_unsigned(&mut diff, run_start - run_end);
+        write_unsigned(&mut diff, run.len());
+        diff.extend_from_slice(run);
+
+        run_end = last_changed + 1;
+        i = run_end;
+    }
+
+    diff
+}
+
+/// Reconstruct a state from `base` and a diff made with [`create_state_diff`].
+///
+/// Diffs with a target larger than [`MAX_STATE_DIFF_TARGET_SIZE`] are rejected.
+pub fn apply_state_diff(base: &[u8], mut diff: &[u8]) -> Result<Vec<u8>, Cow<'static, str>> {
+    let target_len = read_unsigned(&mut diff)?;
+    if target_len > MAX_STATE_DIFF_TARGET_SIZE {
+        return Err(Cow::Borrowed("state diff target is too large"))
+    }
+
+    let mut target = Vec::new();
+    target.try_reserve_exact(target_len).map_err(|_| Cow::Borrowed("state diff target is too large to allocate"))?;
+    target.extend_from_slice(&base[..base.len().min(target_len)]);
+    target.resize(target_len, 0);
+
+    let mut position = 0usize;
+    while !diff.is_empty() {
+        let skip = read_unsigned(&mut diff)?;
+        let len = read_unsigned(&mut diff)?;
+
+        let start = position.checked_add(skip).ok_or(Cow::Borrowed("state diff run offset overflowed"))?;
+        let end = start.checked_add(len).ok_or(Cow::Borrowed("state diff run length overflowed"))?;
+        let (Some(destination), Some((bytes, remaining))) = (target.get_mut(start..end), diff.split_at_checked(len)) else {
+            return Err(Cow::Borrowed("state diff run is out of bounds"))
+        };
+
+        destination.copy_from_slice(bytes);
+        diff = remaining;
+        position = end;
+    }
+
+    Ok(target)
+}
+
+fn write_unsigned(diff: &mut ByteVec, value: usize) {
+    let value = UnsignedInteger::try_from(value).expect("failed to convert usize to UnsignedInteger");
+    for i in value.write_packet_instructions() {
+        diff.extend_from_slice(i.bytes());
+    }
+}
+
+fn read_unsigned(diff: &mut &[u8]) -> Result<usize, Cow<'static, str>> {
+    usize::read_all(diff).map_err(|_| Cow::Borrowed("state diff is truncated or corrupted"))
+}
@@ -10,8 +10,10 @@ extern crate alloc;
 
 pub mod replay_file;
 
+mod diff;
 mod packet;
 mod util;
 
+pub use diff::*;
 pub use packet::*;
 pub use util::*;
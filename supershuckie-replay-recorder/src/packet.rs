@@ -33,6 +33,12 @@ pub enum Packet {
     #[allow(missing_docs)]
     WriteMemory { address: UnsignedInteger, data: ByteVec },
 
+    /// Write RAM to multiple addresses at once.
+    ///
+    /// All writes are applied before the next frame, in order.
+    #[allow(missing_docs)]
+    WriteMemoryMulti { writes: Vec<MemoryWrite> },
+
     /// Set the current input.
     #[allow(missing_docs)]
     ChangeInput { data: InputBuffer },
@@ -44,10 +50,26 @@ pub enum Packet {
     /// Hard reset the console.
     ResetConsole,
 
+    /// Soft reset the console (see [`Packet::ResetConsole`] for hard resets).
+    SoftResetConsole,
+
     /// Load a save state.
     #[allow(missing_docs)]
     LoadSaveState { state: ByteVec },
 
+    /// Load the save state of an earlier keyframe.
+    ///
+    /// `keyframe_index` counts every keyframe in the replay in order, starting at 0 for the first
+    /// keyframe.
+    #[allow(missing_docs)]
+    RestoreState { keyframe_index: UnsignedInteger },
+
+    /// Load a save state stored as a diff against an earlier keyframe's save state.
+    ///
+    /// `keyframe_index` is the same as in [`Packet::RestoreState`]. See [`apply_state_diff`](crate::apply_state_diff).
+    #[allow(missing_docs)]
+    LoadSaveStateDiff { keyframe_index: UnsignedInteger, diff: ByteVec },
+
     /// Describes a named point in the replay.
     #[allow(missing_docs)]
     Bookmark { metadata: BookmarkMetadata },
@@ -82,21 +104,89 @@ pub struct Speed {
 }
 
 impl Speed {
-    /// Get the speed value from a multiplier.
+    /// Slowest possible speed (1/256x)
+    pub const MIN: Speed = Speed { speed_over_256: NonZeroU16::MIN };
+
+    /// Fastest possible speed (65535/256x, just under 256x)
+    pub const MAX: Speed = Speed { speed_over_256: NonZeroU16::MAX };
+
+    /// Get the speed value from a multiplier, rounded to the nearest 1/256.
+    ///
+    /// Multipliers out of range are clamped to [`Speed::MIN`] and [`Speed::MAX`], and NaN is
+    /// treated as [`Speed::MIN`]. Use [`Speed::from_multiplier_checked`] to catch these instead.
     pub const fn from_multiplier_float(multiplier: f64) -> Self {
+        // Float to int casts saturate (and turn NaN into 0)
         Self {
-            speed_over_256: match NonZeroU16::new((multiplier * 256.0) as u16) {
+            speed_over_256: match NonZeroU16::new((multiplier * 256.0 + 0.5) as u16) {
                 Some(n) => n,
-                None => NonZeroU16::new(1).expect("1 is not 0")
+                None => NonZeroU16::MIN
             }
         }
     }
+
+    /// Get the speed value from a multiplier, rounded to the nearest 1/256.
+    ///
+    /// Multipliers that round to something out of range are clamped or rejected depending on
+    /// `bounds`. NaN is always rejected.
+    pub fn from_multiplier_checked(multiplier: f64, bounds: SpeedBounds) -> Result<Self, SpeedError> {
+        if multiplier.is_nan() {
+            return Err(SpeedError::NotANumber)
+        }
+
+        let speed_over_256 = (multiplier * 256.0).round();
+        if bounds == SpeedBounds::Reject && !(1.0..=u16::MAX as f64).contains(&speed_over_256) {
+            return Err(SpeedError::OutOfRange { multiplier })
+        }
+
+        Ok(Self::from_multiplier_float(multiplier))
+    }
+
+    /// Round a multiplier to the speed that will actually be used (see
+    /// [`Speed::from_multiplier_float`]).
+    pub const fn quantize(multiplier: f64) -> f64 {
+        Self::from_multiplier_float(multiplier).into_multiplier_float()
+    }
+
     /// Convert the speed value into a multiplier.
     pub const fn into_multiplier_float(self) -> f64 {
         (self.speed_over_256.get() as f64) / 256.0
     }
 }
 
+/// How [`Speed::from_multiplier_checked`] handles multipliers outside of [`Speed::MIN`] and
+/// [`Speed::MAX`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SpeedBounds {
+    /// Use the closest speed in range
+    Clamp,
+
+    /// Return [`SpeedError::OutOfRange`]
+    Reject
+}
+
+/// Describes why a multiplier can't be used as a [`Speed`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SpeedError {
+    /// The multiplier is NaN
+    NotANumber,
+
+    /// The multiplier is outside of [`Speed::MIN`] and [`Speed::MAX`]
+    #[allow(missing_docs)]
+    OutOfRange { multiplier: f64 }
+}
+
+impl core::fmt::Display for SpeedError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::NotANumber => f.write_str("Speed is not a number"),
+            Self::OutOfRange { multiplier } => f.write_fmt(format_args!(
+                "Speed {multiplier}x is out of range ({}x - {}x)",
+                Speed::MIN.into_multiplier_float(), Speed::MAX.into_multiplier_float()
+            ))
+        }
+    }
+}
+
 impl Default for Speed {
     fn default() -> Self {
         Self::from_multiplier_float(1.0)
@@ -119,6 +209,16 @@ pub struct KeyframeMetadata {
     pub elapsed_millis: TimestampMillis
 }
 
+/// A single write in a [`Packet::WriteMemoryMulti`] packet
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct MemoryWrite {
+    /// Address to write to (emulator-specific)
+    pub address: UnsignedInteger,
+
+    /// Data to write
+    pub data: ByteVec
+}
+
 /// Payload for bookmarks
 #[derive(Clone, PartialEq, Debug, Default)]
 pub struct BookmarkMetadata {
@@ -1,6 +1,6 @@
 use core::cmp::Ordering;
 
-use crate::packet::{BookmarkMetadata, ByteVec, KeyframeMetadata, Packet, Speed, UnsignedInteger};
+use crate::packet::{BookmarkMetadata, ByteVec, KeyframeMetadata, MemoryWrite, Packet, Speed, UnsignedInteger};
 use crate::{InputBuffer, TimestampMillis};
 use alloc::borrow::{Cow, ToOwned};
 use alloc::string::String;
@@ -265,6 +265,9 @@ pub enum PacketDiscriminator {
     /// Write a variable amount of data.
     WriteMemoryVar = 0x88,
 
+    /// Write to multiple addresses at once.
+    WriteMemoryMulti = 0x89,
+
     /// Describes a keyframe
     Keyframe = 0xF0,
 
@@ -280,6 +283,15 @@ pub enum PacketDiscriminator {
     /// Load the save state at the given keyframe
     LoadSaveState = 0xF4,
 
+    /// Soft reset the console
+    SoftResetConsole = 0xF5,
+
+    /// Load the save state of an earlier keyframe
+    RestoreState = 0xF6,
+
+    /// Load a save state stored as a diff against an earlier keyframe
+    LoadSaveStateDiff = 0xF7,
+
     /// Compressed blob
     CompressedBlob = 0xFE,
     
@@ -341,7 +353,10 @@ impl Packet {
         match self {
             Packet::NoOp => PacketDiscriminator::NoOp as u8,
             Packet::ResetConsole => PacketDiscriminator::ResetConsole as u8,
+            Packet::SoftResetConsole => PacketDiscriminator::SoftResetConsole as u8,
             Packet::LoadSaveState { .. } => PacketDiscriminator::LoadSaveState as u8,
+            Packet::RestoreState { .. } => PacketDiscriminator::RestoreState as u8,
+            Packet::LoadSaveStateDiff { .. } => PacketDiscriminator::LoadSaveStateDiff as u8,
             Packet::NextFrame { .. } => PacketDiscriminator::NextFrame as u8,
             Packet::WriteMemory { data, .. } => match data.len() {
                 1 => PacketDiscriminator::WriteMemory8 as u8,
@@ -349,6 +364,7 @@ impl Packet {
                 4 => PacketDiscriminator::WriteMemory32 as u8,
                 _ => PacketDiscriminator::WriteMemoryVar as u8,
             },
+            Packet::WriteMemoryMulti { .. } => PacketDiscriminator::WriteMemoryMulti as u8,
             Packet::ChangeInput { data } => match data.len() {
                 1 => PacketDiscriminator::ChangeInput8 as u8,
                 2 => PacketDiscriminator::ChangeInput16 as u8,
@@ -370,7 +386,7 @@ impl PacketIO<'_> for Packet {
 
         // we can write the payload here
         match self {
-            Packet::NoOp | Packet::ResetConsole => (),
+            Packet::NoOp | Packet::ResetConsole | Packet::SoftResetConsole => (),
             
             Packet::NextFrame { timestamp_delta } => {
                 commands.extend(timestamp_delta.write_packet_instructions());
@@ -399,6 +415,10 @@ impl PacketIO<'_> for Packet {
                 }
             }
 
+            Packet::WriteMemoryMulti { writes } => {
+                commands.extend(writes.write_packet_instructions());
+            }
+
             Packet::CompressedBlob {
                 keyframes,
                 bookmarks,
@@ -435,6 +455,15 @@ impl PacketIO<'_> for Packet {
             Packet::LoadSaveState { state } => {
                 (commands).extend(state.write_packet_instructions())
             }
+
+            Packet::RestoreState { keyframe_index } => {
+                commands.extend(keyframe_index.write_packet_instructions());
+            }
+
+            Packet::LoadSaveStateDiff { keyframe_index, diff } => {
+                commands.extend(keyframe_index.write_packet_instructions());
+                commands.extend(diff.write_packet_instructions());
+            }
         }
 
         commands
@@ -448,6 +477,9 @@ impl PacketIO<'_> for Packet {
         else if discriminator_byte == PacketDiscriminator::ResetConsole {
             return Ok(Packet::ResetConsole)
         }
+        else if discriminator_byte == PacketDiscriminator::SoftResetConsole {
+            return Ok(Packet::SoftResetConsole)
+        }
 
         let Ok(t) = PacketDiscriminator::try_from_primitive(discriminator_byte) else {
             return Err(PacketReadError::ParseFail { explanation: Cow::Owned(alloc::format!("Unknown packet discriminator 0x{discriminator_byte:08X}")) })
@@ -477,9 +509,11 @@ impl PacketIO<'_> for Packet {
         }
 
         match t {
-            PacketDiscriminator::NoOp | PacketDiscriminator::ResetConsole => unreachable!("{t:?} should have already been handled"),
+            PacketDiscriminator::NoOp | PacketDiscriminator::ResetConsole | PacketDiscriminator::SoftResetConsole => unreachable!("{t:?} should have already been handled"),
             PacketDiscriminator::NextFrame => Ok(Packet::NextFrame { timestamp_delta: TimestampMillis::read_all(from)? }),
             PacketDiscriminator::LoadSaveState => Ok(Packet::LoadSaveState { state: ByteVec::read_all(from)? }),
+            PacketDiscriminator::RestoreState => Ok(Packet::RestoreState { keyframe_index: UnsignedInteger::read_all(from)? }),
+            PacketDiscriminator::LoadSaveStateDiff => Ok(Packet::LoadSaveStateDiff { keyframe_index: UnsignedInteger::read_all(from)?, diff: ByteVec::read_all(from)? }),
             PacketDiscriminator::ChangeInput8 => change_input!(u8),
             PacketDiscriminator::ChangeInput16 => change_input!(u16),
             PacketDiscriminator::ChangeInput32 => change_input!(u32),
@@ -488,6 +522,7 @@ impl PacketIO<'_> for Packet {
             PacketDiscriminator::WriteMemory16 => write_memory!(u16),
             PacketDiscriminator::WriteMemory32 => write_memory!(u32),
             PacketDiscriminator::WriteMemoryVar => Ok(Packet::WriteMemory { address: UnsignedInteger::read_all(from)?, data: ByteVec::read_all(from)? }),
+            PacketDiscriminator::WriteMemoryMulti => Ok(Packet::WriteMemoryMulti { writes: Vec::read_all(from)? }),
             PacketDiscriminator::Keyframe => Ok(Packet::Keyframe { metadata: KeyframeMetadata::read_all(from)?, state: ByteVec::read_all(from)? }),
             PacketDiscriminator::Bookmark => Ok(Packet::Bookmark { metadata: BookmarkMetadata::read_all(from)? }),
             PacketDiscriminator::ChangeSpeed => Ok(Packet::ChangeSpeed { speed: Speed::read_all(from)? }),
@@ -560,3 +595,19 @@ impl PacketIO<'_> for BookmarkMetadata {
         })
     }
 }
+
+impl PacketIO<'_> for MemoryWrite {
+    fn write_packet_instructions(&'_ self) -> PacketInstructionsVec<'_> {
+        let mut instructions = PacketInstructionsVec::new();
+        instructions.extend(self.address.write_packet_instructions());
+        instructions.extend(self.data.write_packet_instructions());
+        instructions
+    }
+
+    fn read_all(from: &mut &[u8]) -> Result<Self, PacketReadError> {
+        Ok(Self {
+            address: UnsignedInteger::read_all(from)?,
+            data: ByteVec::read_all(from)?,
+        })
+    }
+}
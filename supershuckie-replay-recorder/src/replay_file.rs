@@ -5,3 +5,6 @@ pub use header::*;
 
 pub mod record;
 pub mod playback;
+pub mod import;
+pub mod script;
+pub mod conformance;
@@ -0,0 +1,682 @@
+//! Replay format conformance testing.
+//!
+//! This provides canonical test vectors (small replay files made from fixed inputs and fixed save
+//! states) along with a validator that checks arbitrary replay files against the format. Together,
+//! these can be used to check that third-party implementations (and future versions of the format)
+//! are compatible:
+//!
+//! - A reader is conformant if it reads each [`TestVector::data`] as [`TestVector::packets`].
+//! - A writer is conformant if, given the same packets, it produces a file that passes
+//!   [`TestVector::check`].
+//!
+//! Note that the exact bytes of each vector depend on the zstd version used to compress it, so
+//! files should be compared by their packets (as done by [`TestVector::check`]), not byte-for-byte.
+//! This crate's own tests do compare them byte-for-byte against copies checked in under
+//! `tests/conformance`, so that any change to the format is caught.
+//!
+//! See [`generate_test_vectors`] and [`validate_replay`].
+
+use crate::replay_file::playback::ReplayFilePlayer;
+use crate::replay_file::record::{LoadSaveStateMode, NullReplayFileSink, ReplayFileRecorder, ReplayFileRecorderSettings, ReplayFileWriteError};
+use crate::replay_file::{ReplayConsoleType, ReplayFileMetadata, ReplayHeaderBytes, ReplayHeaderRaw, ReplayPatchFormat};
+use crate::util::decompress_data;
+use crate::{blake3_hash, create_state_diff, BookmarkMetadata, ByteVec, InputBuffer, KeyframeMetadata, MemoryWrite, Packet, PacketIO, PacketReadError, Speed, TimestampMillis, UnsignedInteger};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// zstd compression level used for test vectors.
+pub const CONFORMANCE_COMPRESSION_LEVEL: i32 = 3;
+
+/// Frame rate test vectors are recorded at, in frames per second.
+const CONFORMANCE_FRAME_RATE: UnsignedInteger = 60;
+
+/// A canonical replay file and the packets it contains.
+///
+/// See [`generate_test_vectors`].
+#[derive(Clone, PartialEq, Debug)]
+pub struct TestVector {
+    /// Short name of the vector, suitable for a filename
+    pub name: &'static str,
+
+    /// What the vector tests
+    pub description: &'static str,
+
+    /// Header metadata of the replay
+    pub metadata: ReplayFileMetadata,
+
+    /// Patch data of the replay, if any
+    pub patch_data: Vec<u8>,
+
+    /// All packets in the replay, in order, with compressed blobs expanded
+    pub packets: Vec<Packet>,
+
+    /// The replay file
+    pub data: Vec<u8>
+}
+
+impl TestVector {
+    /// Validate `data` (e.g. written by another implementation), additionally checking that it has
+    /// the same metadata, patch data, and packets as this vector.
+    pub fn check(&self, data: &[u8]) -> ConformanceReport {
+        let mut report = validate_replay(data);
+
+        if let Some(metadata) = report.metadata.as_ref() && *metadata != self.metadata {
+            report.error(None, format!("Metadata does not match test vector {}", self.name));
+        }
+        if report.patch_data.as_deref().is_some_and(|i| i != self.patch_data.as_slice()) {
+            report.error(None, format!("Patch data does not match test vector {}", self.name));
+        }
+
+        let mismatch = report.packets.iter().zip(self.packets.iter()).position(|(a, b)| a != b);
+        if let Some(index) = mismatch {
+            report.error(Some(index), format!("Packet does not match test vector {} (expected {:?})", self.name, self.packets[index]));
+        }
+        else if report.packets.len() != self.packets.len() {
+            report.error(None, format!("Expected {} packets for test vector {}, got {}", self.packets.len(), self.name, report.packets.len()));
+        }
+
+        report
+    }
+}
+
+/// Generate all canonical test vectors.
+///
+/// These are deterministic, aside from the compressed bytes (see the [module docs](self)).
+pub fn generate_test_vectors() -> Result<Vec<TestVector>, ReplayFileWriteError> {
+    use TestStep::*;
+
+    const INPUT_SEQUENCE: [&[u8]; 12] = [&[0x01], &[0x02], &[0x04], &[0x08], &[0x10], &[0x20], &[0x40], &[0x80], &[0x81], &[0x42], &[0x24], &[0x00]];
+    let inputs: Vec<TestStep> = INPUT_SEQUENCE
+        .iter()
+        .flat_map(|i| [Input(i), Frames(10)])
+        .chain([Keyframe(2)])
+        .collect();
+
+    Ok(vec![
+        TestBuilder::new("minimal", "A single keyframe and nothing else").finish()?,
+        TestBuilder::new("inputs", "Input changes every 10 frames for 120 frames")
+            .steps(&inputs)
+            .finish()?,
+        TestBuilder::new("events", "One of every packet that can appear in a recording")
+            .steps(&[
+                Frames(5),
+                Bookmark("start"),
+                Input(&[0x09]),
+                Frames(3),
+                Speed(2.0),
+                Frames(3),
+                WriteMemory(0xC000, &[0x12, 0x34]),
+                WriteMemoryMulti(&[(0xC100, &[0x56]), (0xFF80, &[0x78, 0x9A, 0xBC])]),
+                Frames(1),
+                SoftReset,
+                Frames(2),
+                Keyframe(2),
+                Speed(0.5),
+                Input(&[0x00]),
+                Frames(4),
+                Reset,
+                Frames(1),
+                LoadSaveState(3),
+                Frames(1),
+                RestoreState(1),
+                LoadModifiedSaveState(2, 0x40, 0xFF),
+                Bookmark("end"),
+                Frames(1),
+                Keyframe(4)
+            ])
+            .finish()?,
+        TestBuilder::new("multi_blob", "Every keyframe starts a new compressed blob")
+            .with_blob_size(1)
+            .steps(&[Frames(30), Keyframe(2), Input(&[0x10]), Frames(30), Keyframe(3), Frames(30), Keyframe(4)])
+            .finish()?,
+        TestBuilder::new("patched", "A replay of a ROM with a BPS patch")
+            .with_patch(b"BPS1 (not a real patch; only its length and contents are checked)")
+            .steps(&[Frames(60), Keyframe(2)])
+            .finish()?,
+        TestBuilder::new("restore", "Loading save states that match earlier keyframes")
+            .with_load_save_state_mode(LoadSaveStateMode::RestoreKeyframe)
+            .steps(&[
+                Frames(10),
+                Keyframe(2),
+                Input(&[0x01]),
+                Frames(10),
+                LoadSaveState(2),
+                Frames(5),
+                LoadSaveState(1),
+                Frames(5),
+                LoadSaveState(3),
+                Frames(1),
+                LoadModifiedSaveState(3, 0x80, 0x00),
+                Frames(1),
+                Keyframe(4)
+            ])
+            .finish()?,
+        TestBuilder::new("branch", "Loading a save state that matches an earlier keyframe, discarding everything after it")
+            .with_load_save_state_mode(LoadSaveStateMode::Branch)
+            .steps(&[
+                Frames(10),
+                Keyframe(2),
+                Bookmark("discarded"),
+                Input(&[0x01]),
+                Frames(20),
+                LoadSaveState(2),
+                Input(&[0x02]),
+                Frames(10),
+                Keyframe(3)
+            ])
+            .finish()?
+    ])
+}
+
+/// Write all canonical test vectors to `directory` as `{name}.replay`.
+///
+/// The `std` feature is required to use this.
+#[cfg(feature = "std")]
+pub fn write_test_vectors(directory: &std::path::Path) -> Result<(), ReplayFileWriteError> {
+    for vector in generate_test_vectors()? {
+        std::fs::write(directory.join(format!("{}.replay", vector.name)), vector.data.as_slice())
+            .map_err(|e| ReplayFileWriteError::Other { explanation: format!("Can't write {}: {e}", vector.name).into() })?;
+    }
+    Ok(())
+}
+
+/// A step used to build a test vector.
+#[derive(Copy, Clone)]
+enum TestStep {
+    Frames(UnsignedInteger),
+    Input(&'static [u8]),
+    Speed(f64),
+    Keyframe(u8),
+    Bookmark(&'static str),
+    WriteMemory(UnsignedInteger, &'static [u8]),
+    WriteMemoryMulti(&'static [(UnsignedInteger, &'static [u8])]),
+    Reset,
+    SoftReset,
+    LoadSaveState(u8),
+    LoadModifiedSaveState(u8, usize, u8),
+    RestoreState(UnsignedInteger)
+}
+
+/// Records a test vector while keeping track of the packets it should contain.
+struct TestBuilder {
+    name: &'static str,
+    description: &'static str,
+    metadata: ReplayFileMetadata,
+    patch_data: Vec<u8>,
+    settings: ReplayFileRecorderSettings,
+    steps: Vec<TestStep>
+}
+
+impl TestBuilder {
+    fn new(name: &'static str, description: &'static str) -> Self {
+        Self {
+            name,
+            description,
+            metadata: ReplayFileMetadata {
+                console_type: ReplayConsoleType::GameBoyColor,
+                rom_name: "SUPERSHUCKIE".to_string(),
+                rom_filename: "conformance.gbc".to_string(),
+                rom_checksum: blake3_hash(b"conformance rom"),
+                bios_checksum: blake3_hash(b"conformance bios"),
+                emulator_core_name: "conformance".to_string(),
+                patch_format: ReplayPatchFormat::Unpatched,
+                patch_target_checksum: [0u8; 32],
+                sram_checksum: [0u8; 32],
+                core_config: ByteVec::new()
+            },
+            patch_data: Vec::new(),
+            settings: ReplayFileRecorderSettings {
+                minimum_uncompressed_bytes_per_blob: 1024 * 1024,
+                compression_level: CONFORMANCE_COMPRESSION_LEVEL,
+                load_save_state_mode: LoadSaveStateMode::Embed
+            },
+            steps: Vec::new()
+        }
+    }
+
+    fn with_blob_size(mut self, minimum_uncompressed_bytes_per_blob: usize) -> Self {
+        self.settings.minimum_uncompressed_bytes_per_blob = minimum_uncompressed_bytes_per_blob;
+        self
+    }
+
+    fn with_load_save_state_mode(mut self, mode: LoadSaveStateMode) -> Self {
+        self.settings.load_save_state_mode = mode;
+        self
+    }
+
+    fn with_patch(mut self, patch_data: &[u8]) -> Self {
+        self.metadata.patch_format = ReplayPatchFormat::BPS;
+        self.metadata.patch_target_checksum = blake3_hash(b"conformance unpatched rom");
+        self.patch_data = patch_data.to_vec();
+        self
+    }
+
+    fn steps(mut self, steps: &[TestStep]) -> Self {
+        self.steps.extend_from_slice(steps);
+        self
+    }
+
+    fn finish(self) -> Result<TestVector, ReplayFileWriteError> {
+        let mut input = InputBuffer::from([0u8].as_slice());
+        let mut speed = Speed::default();
+        let mut frames: UnsignedInteger = 0;
+        let mut millis: TimestampMillis = 0;
+
+        let mut recorder = ReplayFileRecorder::new_with_metadata(
+            self.metadata.clone(),
+            ByteVec::from(self.patch_data.as_slice()),
+            self.settings.clone(),
+            millis,
+            input.clone(),
+            speed,
+            canonical_state(1),
+            Vec::new(),
+            NullReplayFileSink
+        )?;
+
+        let first_keyframe = KeyframeMetadata { input: input.clone(), speed, elapsed_frames: frames, elapsed_millis: millis };
+        let mut packets = vec![Packet::Keyframe { metadata: first_keyframe.clone(), state: canonical_state(1) }];
+
+        // State, packet index, and metadata of each keyframe, for predicting what loading a save state records
+        let mut keyframes = vec![(canonical_state(1), 0usize, first_keyframe)];
+
+        // Frames passed to the recorder; unlike `frames`, this does not go back when branching
+        let mut clock_frames: UnsignedInteger = 0;
+        let mut timestamp_offset: TimestampMillis = 0;
+
+        for step in self.steps.iter().copied() {
+            match step {
+                TestStep::Frames(count) => for _ in 0..count {
+                    clock_frames += 1;
+                    frames += 1;
+                    let timestamp = clock_frames * 1000 / CONFORMANCE_FRAME_RATE;
+                    recorder.next_frame(timestamp)?;
+                    packets.push(Packet::NextFrame { timestamp_delta: timestamp - timestamp_offset - millis });
+                    millis = timestamp - timestamp_offset;
+                },
+                TestStep::Input(data) => {
+                    input = InputBuffer::from(data);
+                    recorder.set_input(input.clone())?;
+                    packets.push(Packet::ChangeInput { data: input.clone() });
+                },
+                TestStep::Speed(multiplier) => {
+                    speed = Speed::from_multiplier_float(multiplier);
+                    recorder.set_speed(speed)?;
+                    packets.push(Packet::ChangeSpeed { speed });
+                },
+                TestStep::Keyframe(seed) => {
+                    recorder.insert_keyframe(canonical_state(seed), millis + timestamp_offset)?;
+                    let metadata = KeyframeMetadata { input: input.clone(), speed, elapsed_frames: frames, elapsed_millis: millis };
+                    keyframes.push((canonical_state(seed), packets.len(), metadata.clone()));
+                    packets.push(Packet::Keyframe { metadata, state: canonical_state(seed) });
+                },
+                TestStep::Bookmark(name) => {
+                    recorder.add_bookmark(name)?;
+                    packets.push(Packet::Bookmark {
+                        metadata: BookmarkMetadata { name: name.to_string(), elapsed_frames: frames, elapsed_millis: millis }
+                    });
+                },
+                TestStep::WriteMemory(address, data) => {
+                    recorder.write_memory(address, ByteVec::from(data))?;
+                    packets.push(Packet::WriteMemory { address, data: ByteVec::from(data) });
+                },
+                TestStep::WriteMemoryMulti(writes) => {
+                    let writes: Vec<MemoryWrite> = writes.iter().map(|(address, data)| MemoryWrite { address: *address, data: ByteVec::from(*data) }).collect();
+                    recorder.write_memory_multi(writes.clone())?;
+                    packets.push(Packet::WriteMemoryMulti { writes });
+                },
+                TestStep::Reset => {
+                    recorder.reset_console()?;
+                    packets.push(Packet::ResetConsole);
+                },
+                TestStep::SoftReset => {
+                    recorder.soft_reset_console()?;
+                    packets.push(Packet::SoftResetConsole);
+                },
+                TestStep::LoadSaveState(_) | TestStep::LoadModifiedSaveState(..) => {
+                    let state = match step {
+                        TestStep::LoadSaveState(seed) => canonical_state(seed),
+                        TestStep::LoadModifiedSaveState(seed, offset, byte) => {
+                            let mut state = canonical_state(seed);
+                            state[offset] = byte;
+                            state
+                        },
+                        _ => unreachable!()
+                    };
+                    recorder.load_save_state(state.clone())?;
+
+                    let mode = self.settings.load_save_state_mode;
+                    let matching_keyframe = match mode {
+                        LoadSaveStateMode::Embed => None,
+                        _ => keyframes.iter().rposition(|(s, _, _)| *s == state)
+                    };
+
+                    match matching_keyframe {
+                        None => {
+                            let (last_state, _, _) = keyframes.last().expect("there is always an initial keyframe");
+                            let diff = create_state_diff(last_state.as_slice(), state.as_slice());
+                            if diff.len() < state.len() {
+                                packets.push(Packet::LoadSaveStateDiff { keyframe_index: (keyframes.len() - 1) as UnsignedInteger, diff });
+                            }
+                            else {
+                                packets.push(Packet::LoadSaveState { state: state.clone() });
+                            }
+
+                            if mode != LoadSaveStateMode::Embed {
+                                let metadata = KeyframeMetadata { input: input.clone(), speed, elapsed_frames: frames, elapsed_millis: millis };
+                                keyframes.push((state.clone(), packets.len(), metadata.clone()));
+                                packets.push(Packet::Keyframe { metadata, state });
+                            }
+                        },
+
+                        // Test vectors that branch are expected to fit in one blob
+                        Some(index) if mode == LoadSaveStateMode::Branch => {
+                            keyframes.truncate(index + 1);
+                            let (_, packet_index, metadata) = &keyframes[index];
+                            packets.truncate(packet_index + 1);
+                            timestamp_offset += millis - metadata.elapsed_millis;
+                            millis = metadata.elapsed_millis;
+                            frames = metadata.elapsed_frames;
+                            input = metadata.input.clone();
+                            speed = metadata.speed;
+                        },
+                        Some(index) => packets.push(Packet::RestoreState { keyframe_index: index as UnsignedInteger })
+                    }
+                },
+                TestStep::RestoreState(keyframe_index) => {
+                    recorder.restore_state(keyframe_index)?;
+                    packets.push(Packet::RestoreState { keyframe_index });
+                }
+            }
+        }
+
+        let (data, _) = recorder.close().map_err(|(_, _, e)| e)?;
+
+        Ok(TestVector {
+            name: self.name,
+            description: self.description,
+            metadata: self.metadata,
+            patch_data: self.patch_data,
+            packets,
+            data
+        })
+    }
+}
+
+/// Generate a fixed save state for test vectors.
+///
+/// Save states are opaque to the replay format, so any bytes will do.
+fn canonical_state(seed: u8) -> ByteVec {
+    (0..=255u8).map(|i| i.wrapping_mul(seed).wrapping_add(seed)).collect()
+}
+
+/// How serious a [`ConformanceIssue`] is.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum ConformanceSeverity {
+    /// The file does not follow the format and may not be playable.
+    Error,
+
+    /// The file follows the format but has something unusual in it (e.g. it was never finished).
+    Warning
+}
+
+/// A problem found by [`validate_replay`].
+#[derive(Clone, PartialEq, Debug)]
+pub struct ConformanceIssue {
+    /// How serious the issue is
+    pub severity: ConformanceSeverity,
+
+    /// Index of the packet the issue was found at (with compressed blobs expanded), if any
+    pub packet_index: Option<usize>,
+
+    /// Description of the issue
+    pub description: String
+}
+
+/// Result of [`validate_replay`].
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct ConformanceReport {
+    /// Header metadata, if it could be parsed
+    pub metadata: Option<ReplayFileMetadata>,
+
+    /// Patch data, if it could be read
+    pub patch_data: Option<Vec<u8>>,
+
+    /// All packets that could be read, with compressed blobs expanded
+    pub packets: Vec<Packet>,
+
+    /// All issues found
+    pub issues: Vec<ConformanceIssue>
+}
+
+impl ConformanceReport {
+    /// Return `true` if no errors were found (warnings are allowed).
+    pub fn is_conformant(&self) -> bool {
+        !self.issues.iter().any(|i| i.severity == ConformanceSeverity::Error)
+    }
+
+    fn error(&mut self, packet_index: Option<usize>, description: String) {
+        self.issues.push(ConformanceIssue { severity: ConformanceSeverity::Error, packet_index, description });
+    }
+
+    fn warning(&mut self, packet_index: Option<usize>, description: String) {
+        self.issues.push(ConformanceIssue { severity: ConformanceSeverity::Warning, packet_index, description });
+    }
+}
+
+/// Check that `data` is a valid replay file.
+///
+/// Unlike [`ReplayFilePlayer::new`], this does not stop at the first problem, and it also checks
+/// that compressed blobs, keyframes, and bookmarks agree with the packets around them.
+pub fn validate_replay(data: &[u8]) -> ConformanceReport {
+    let mut report = ConformanceReport::default();
+    validate_into(data, &mut report);
+
+    if report.is_conformant() && let Err(e) = ReplayFilePlayer::new(data, false) {
+        report.error(None, format!("Replay passed validation but could not be played back: {e:?}"));
+    }
+
+    report
+}
+
+fn validate_into(data: &[u8], report: &mut ConformanceReport) {
+    // Header
+    let Some(header_bytes) = data.get(..size_of::<ReplayHeaderBytes>()) else {
+        report.error(None, format!("File is too small for a header ({} < {} bytes)", data.len(), size_of::<ReplayHeaderBytes>()));
+        return
+    };
+    let header_bytes: &ReplayHeaderBytes = header_bytes.try_into().expect("should be able to convert array");
+    let header = ReplayHeaderRaw::from_bytes(header_bytes);
+    let metadata = match header.parse() {
+        Ok(n) => n,
+        Err(e) => {
+            report.error(None, format!("Invalid header: {e}"));
+            return
+        }
+    };
+
+    if header._padding_0.iter().chain(header._padding_1.iter()).chain(header._padding_2.iter()).any(|i| *i != 0) {
+        report.warning(None, "Header padding is not zeroed".to_string());
+    }
+
+    // Patch
+    let patch_length = header.patch_data_length;
+    let patch_end = usize::try_from(patch_length).ok().and_then(|i| i.checked_add(header_bytes.len()));
+    let Some(patch_data) = patch_end.and_then(|end| data.get(header_bytes.len()..end)) else {
+        report.error(None, format!("Patch data length {patch_length} is out of bounds"));
+        return
+    };
+    match metadata.patch_format {
+        ReplayPatchFormat::Unpatched if !patch_data.is_empty() => report.error(None, "Replay is unpatched but has patch data".to_string()),
+        ReplayPatchFormat::BPS if patch_data.is_empty() => report.error(None, "Replay has a patch format but no patch data".to_string()),
+        _ => ()
+    }
+
+    report.metadata = Some(metadata);
+    report.patch_data = Some(patch_data.to_vec());
+
+    // Packets
+    let mut stream = StreamValidator::default();
+    let mut remaining = &data[header_bytes.len() + patch_data.len()..];
+    let mut warned_uncompressed = false;
+
+    while !remaining.is_empty() {
+        let packet = match Packet::read_all(&mut remaining) {
+            Ok(n) => n,
+            Err(e) => {
+                let explanation = match e {
+                    PacketReadError::NotEnoughData => "not enough data".into(),
+                    PacketReadError::ParseFail { explanation } => explanation
+                };
+                report.error(Some(report.packets.len()), format!("Can't read packet ({explanation}); {} bytes are left over", remaining.len()));
+                return
+            }
+        };
+
+        let Packet::CompressedBlob { keyframes, bookmarks, compressed_data, uncompressed_size, timestamp_start, timestamp_end, elapsed_frames_start, elapsed_frames_end } = packet else {
+            if !warned_uncompressed {
+                warned_uncompressed = true;
+                report.warning(Some(report.packets.len()), "Replay has uncompressed packets outside of a compressed blob (it may not have been finished)".to_string());
+            }
+            stream.next(packet, report);
+            continue
+        };
+
+        let blob_index = report.packets.len();
+        let decompressed = usize::try_from(uncompressed_size)
+            .map_err(|_| "uncompressed size exceeds usize".into())
+            .and_then(|size| decompress_data(compressed_data.as_slice(), size));
+        let decompressed = match decompressed {
+            Ok(n) => n,
+            Err(e) => {
+                report.error(Some(blob_index), format!("Can't decompress blob: {e}"));
+                continue
+            }
+        };
+
+        match keyframes.first() {
+            None => report.error(Some(blob_index), "Compressed blob has no keyframes".to_string()),
+            Some(first) => {
+                if first.elapsed_frames != elapsed_frames_start || first.elapsed_millis != timestamp_start {
+                    report.error(Some(blob_index), format!(
+                        "Compressed blob starts at frame {elapsed_frames_start} ({timestamp_start} ms), but its first keyframe is at frame {} ({} ms)",
+                        first.elapsed_frames,
+                        first.elapsed_millis
+                    ));
+                }
+            }
+        }
+
+        let mut blob_keyframes = Vec::new();
+        let mut blob_bookmarks = Vec::new();
+        let mut inner = decompressed.as_slice();
+        while !inner.is_empty() {
+            let packet = match Packet::read_all(&mut inner) {
+                Ok(n) => n,
+                Err(e) => {
+                    report.error(Some(report.packets.len()), format!("Can't read packet in compressed blob at packet {blob_index}: {e:?}"));
+                    break
+                }
+            };
+            match &packet {
+                Packet::CompressedBlob { .. } => report.error(Some(report.packets.len()), "Compressed blobs cannot be nested".to_string()),
+                Packet::Keyframe { metadata, .. } => blob_keyframes.push(metadata.clone()),
+                Packet::Bookmark { metadata } => blob_bookmarks.push(metadata.clone()),
+                _ => ()
+            }
+            stream.next(packet, report);
+        }
+
+        if blob_keyframes != keyframes {
+            report.error(Some(blob_index), "Compressed blob's keyframe list does not match the keyframes inside of it".to_string());
+        }
+        if blob_bookmarks != bookmarks {
+            report.error(Some(blob_index), "Compressed blob's bookmark list does not match the bookmarks inside of it".to_string());
+        }
+        if stream.frames != elapsed_frames_end || stream.millis != timestamp_end {
+            report.error(Some(blob_index), format!(
+                "Compressed blob ends at frame {elapsed_frames_end} ({timestamp_end} ms), but its packets end at frame {} ({} ms)",
+                stream.frames,
+                stream.millis
+            ));
+        }
+    }
+
+    if report.packets.is_empty() {
+        report.error(None, "Replay has no packets".to_string());
+    }
+}
+
+/// Tracks the state of the packet stream to check that packets agree with it.
+#[derive(Default)]
+struct StreamValidator {
+    keyframes: UnsignedInteger,
+    frames: UnsignedInteger,
+    millis: TimestampMillis,
+    input: Option<InputBuffer>,
+    speed: Speed
+}
+
+impl StreamValidator {
+    fn next(&mut self, packet: Packet, report: &mut ConformanceReport) {
+        let index = report.packets.len();
+
+        if index == 0 && !matches!(&packet, Packet::Keyframe { metadata, .. } if metadata.elapsed_frames == 0) {
+            report.error(Some(index), "Replay does not start with a keyframe at frame 0".to_string());
+        }
+
+        match &packet {
+            Packet::NextFrame { timestamp_delta } => {
+                self.frames = self.frames.wrapping_add(1);
+                match self.millis.checked_add(*timestamp_delta) {
+                    Some(n) => self.millis = n,
+                    None => report.error(Some(index), "Timestamp overflowed".to_string())
+                }
+            },
+            Packet::ChangeInput { data } => {
+                self.input = Some(data.clone());
+            },
+            Packet::ChangeSpeed { speed } => {
+                self.speed = *speed;
+            },
+            Packet::Keyframe { metadata, .. } => {
+                if metadata.elapsed_frames != self.frames {
+                    report.error(Some(index), format!("Keyframe is at frame {}, but it appears on frame {}", metadata.elapsed_frames, self.frames));
+                }
+                if metadata.elapsed_millis < self.millis {
+                    report.error(Some(index), format!("Keyframe goes back in time ({} ms < {} ms)", metadata.elapsed_millis, self.millis));
+                }
+                if self.input.as_ref().is_some_and(|i| *i != metadata.input) {
+                    report.error(Some(index), "Keyframe input does not match the current input".to_string());
+                }
+                if index != 0 && metadata.speed != self.speed {
+                    report.error(Some(index), "Keyframe speed does not match the current speed".to_string());
+                }
+                self.millis = metadata.elapsed_millis;
+                self.input = Some(metadata.input.clone());
+                self.speed = metadata.speed;
+                self.keyframes += 1;
+            },
+            Packet::RestoreState { keyframe_index } if *keyframe_index >= self.keyframes => {
+                report.error(Some(index), format!("Restores keyframe #{keyframe_index}, but only {} keyframes came before it", self.keyframes));
+            },
+            Packet::LoadSaveStateDiff { keyframe_index, .. } if *keyframe_index >= self.keyframes => {
+                report.error(Some(index), format!("Save state is diffed against keyframe #{keyframe_index}, but only {} keyframes came before it", self.keyframes));
+            },
+            Packet::Bookmark { metadata } if metadata.elapsed_frames != self.frames || metadata.elapsed_millis != self.millis => {
+                report.error(Some(index), format!(
+                    "Bookmark is at frame {} ({} ms), but it appears on frame {} ({} ms)",
+                    metadata.elapsed_frames,
+                    metadata.elapsed_millis,
+                    self.frames,
+                    self.millis
+                ));
+            },
+            _ => ()
+        }
+
+        report.packets.push(packet);
+    }
+}
@@ -1,4 +1,5 @@
 use crate::util::{reinterpret_ref, MaybeEnum};
+use crate::ByteVec;
 use alloc::borrow::ToOwned;
 use alloc::format;
 use alloc::string::String;
@@ -44,6 +45,9 @@ pub fn blake3_hash_to_ascii(hash: ReplayHeaderBlake3Hash) -> String {
 /// UTF-8 null-terminated 255 byte length string
 pub type ReplayHeaderString = [u8; 256];
 
+/// Core-specific configuration blob (see [`ReplayFileMetadata::core_config`])
+pub type ReplayHeaderCoreConfig = [u8; 0x400];
+
 /// Raw replay header, mapping directly to the actual file.
 #[derive(Copy, Clone, PartialEq, Debug)]
 #[repr(C, packed(1))]
@@ -87,8 +91,21 @@ pub struct ReplayHeaderRaw {
     /// 0x360 - blake3 hash of the BIOS
     pub bios_checksum: ReplayHeaderBlake3Hash,
 
-    /// 0x380 - padding
-    pub _padding_2: [u8; 0x480 - 4],
+    /// 0x380 - blake3 hash of the SRAM when recording started (all zeroes if unknown or empty)
+    pub sram_checksum: ReplayHeaderBlake3Hash,
+
+    /// 0x3A0 - length of the core config blob
+    pub core_config_length: u32,
+
+    /// 0x3A4 - padding
+    pub _padding_2: [u8; 4],
+
+    /// 0x3A8 - core-specific configuration that affects determinism (only the first
+    /// `core_config_length` bytes are used)
+    pub core_config: ReplayHeaderCoreConfig,
+
+    /// 0x7A8 - padding
+    pub _padding_3: [u8; 0x54],
 
     /// 0x7FC - signature (must equal [`SIGNATURE_END`])
     pub signature_end: [u8; 4],
@@ -127,7 +144,19 @@ pub struct ReplayFileMetadata {
     pub patch_format: ReplayPatchFormat,
 
     /// blake3 hash of the target ROM (before patch)
-    pub patch_target_checksum: ReplayHeaderBlake3Hash
+    pub patch_target_checksum: ReplayHeaderBlake3Hash,
+
+    /// blake3 hash of the SRAM when recording started
+    ///
+    /// This is all zeroes if the game has no SRAM or the replay predates this field, in which case
+    /// it should not be checked.
+    pub sram_checksum: ReplayHeaderBlake3Hash,
+
+    /// Core-specific configuration that affects determinism, to be restored before playback (max
+    /// length is 1024 bytes)
+    ///
+    /// This is empty if the core has no such configuration or the replay predates this field.
+    pub core_config: ByteVec
 }
 
 impl ReplayHeaderRaw {
@@ -177,6 +206,11 @@ impl ReplayHeaderRaw {
             bios_checksum: self.bios_checksum,
             rom_checksum: self.rom_checksum,
             patch_target_checksum: self.patch_target_checksum,
+            sram_checksum: self.sram_checksum,
+            core_config: self.core_config
+                .get(..self.core_config_length as usize)
+                .ok_or_else(|| format!("core_config_length {} exceeds {} bytes", { self.core_config_length }, self.core_config.len()))
+                .map(ByteVec::from)?,
 
             rom_name: parse_string_buffer(&self.rom_name, "rom_name")?,
             rom_filename: parse_string_buffer(&self.rom_filename, "rom_filename")?,
@@ -188,18 +222,10 @@ impl ReplayHeaderRaw {
 impl ReplayFileMetadata {
     /// Convert the parsed header into a raw header.
     pub fn as_raw_header(&self) -> Result<ReplayHeaderRaw, String> {
-        fn into_str_bytes(what: &str, name: &'static str) -> Result<ReplayHeaderString, String> {
-            let mut result = [0u8; 256];
-            let limit = result.len() - 1;
-            let result_minus_null_termination = &mut result[0..limit];
-            let what_bytes = what.as_bytes();
-            
-            result_minus_null_termination.get_mut(0..what_bytes.len())
-                .ok_or_else(|| format!("{name} exceeds {limit} bytes"))?
-                .copy_from_slice(what_bytes);
-
-            Ok(result)
-        }
+        let mut core_config = [0u8; size_of::<ReplayHeaderCoreConfig>()];
+        core_config.get_mut(0..self.core_config.len())
+            .ok_or_else(|| format!("core_config exceeds {} bytes", size_of::<ReplayHeaderCoreConfig>()))?
+            .copy_from_slice(&self.core_config);
 
         Ok(ReplayHeaderRaw {
             signature_start: SIGNATURE_START,
@@ -213,15 +239,55 @@ impl ReplayFileMetadata {
             patch_format: MaybeEnum::new(self.patch_format),
             patch_data_length: 0,
             patch_target_checksum: self.patch_target_checksum,
+            sram_checksum: self.sram_checksum,
+            core_config_length: self.core_config.len() as u32,
+            core_config,
             signature_end: SIGNATURE_END,
 
             _padding_0: [0u8; _],
             _padding_1: [0u8; _],
-            _padding_2: [0u8; _]
+            _padding_2: [0u8; _],
+            _padding_3: [0u8; _]
         })
     }
 }
 
+fn into_str_bytes(what: &str, name: &'static str) -> Result<ReplayHeaderString, String> {
+    let mut result = [0u8; 256];
+    let limit = result.len() - 1;
+    let result_minus_null_termination = &mut result[0..limit];
+    let what_bytes = what.as_bytes();
+
+    result_minus_null_termination.get_mut(0..what_bytes.len())
+        .ok_or_else(|| format!("{name} exceeds {limit} bytes"))?
+        .copy_from_slice(what_bytes);
+
+    Ok(result)
+}
+
+/// Rewrite the header of a replay file in place so it can be shared without leaking local info.
+///
+/// The internal ROM name is replaced with `rom_name` and the ROM filename is cleared. Nothing else
+/// in the file is touched (including the core name, which is needed to check for mismatches), so
+/// the replay plays back exactly the same.
+///
+/// Returns an error if the file does not start with a valid header.
+pub fn scrub_replay_header(file: &mut [u8], rom_name: &str) -> Result<(), String> {
+    let header_bytes: &mut ReplayHeaderBytes = file
+        .get_mut(..size_of::<ReplayHeaderBytes>())
+        .ok_or_else(|| "Replay is too small to have a header".to_owned())?
+        .try_into()
+        .expect("we just got exactly this many bytes");
+
+    let mut header = *ReplayHeaderRaw::from_bytes(header_bytes);
+    header.parse()?;
+    header.rom_name = into_str_bytes(rom_name, "rom_name")?;
+    header.rom_filename = [0u8; _];
+
+    header_bytes.copy_from_slice(header.as_bytes());
+    Ok(())
+}
+
 /// Console type to use for replays.
 #[derive(Copy, Clone, PartialEq, Debug, TryFromPrimitive, Default, IntoPrimitive)]
 #[repr(u32)]
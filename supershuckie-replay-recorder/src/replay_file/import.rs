@@ -0,0 +1,329 @@
+//! Third-party movie importing functionality.
+//!
+//! See [`import_vbm`] and [`import_bk2_input_log`].
+
+use crate::replay_file::record::{ReplayFileRecorder, ReplayFileRecorderSettings, ReplayFileSink, ReplayFileWriteError};
+use crate::replay_file::{ReplayConsoleType, ReplayFileMetadata};
+use crate::{ByteVec, InputBuffer, Speed, TimestampMillis};
+use alloc::borrow::{Cow, ToOwned};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Frame rate of the Game Boy (and Game Boy Color), in frames per second.
+pub const GAME_BOY_FRAME_RATE: f64 = 4194304.0 / 70224.0;
+
+/// Buttons held on a single frame of an imported movie.
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+#[allow(missing_docs)]
+pub struct MovieFrameInput {
+    pub a: bool,
+    pub b: bool,
+    pub start: bool,
+    pub select: bool,
+
+    pub d_up: bool,
+    pub d_down: bool,
+    pub d_left: bool,
+    pub d_right: bool,
+
+    /// The console is reset (power cycled) before this frame.
+    pub reset: bool
+}
+
+/// A movie that was converted from a third-party format.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ImportedMovie {
+    /// Console the movie was recorded on, if known.
+    pub console_type: ReplayConsoleType,
+
+    /// Internal ROM name, if known.
+    pub rom_name: String,
+
+    /// Number of frames per second the movie was recorded at.
+    pub frame_rate: f64,
+
+    /// Input for each frame.
+    pub frames: Vec<MovieFrameInput>
+}
+
+/// Describes an error that occurred when importing a movie.
+#[derive(Clone, PartialEq, Debug)]
+#[allow(missing_docs)]
+pub enum MovieImportError {
+    /// The movie could not be parsed.
+    InvalidMovie { explanation: Cow<'static, str> },
+
+    /// The movie is valid but uses a feature that cannot be imported.
+    Unsupported { explanation: Cow<'static, str> }
+}
+
+const VBM_SIGNATURE: [u8; 4] = *b"VBM\x1A";
+const VBM_HEADER_SIZE: usize = 0x100;
+
+const VBM_START_FROM_SAVE_STATE: u8 = 1 << 0;
+const VBM_START_FROM_SRAM: u8 = 1 << 1;
+
+const VBM_SYSTEM_GBA: u8 = 1 << 0;
+const VBM_SYSTEM_GBC: u8 = 1 << 1;
+const VBM_SYSTEM_SGB: u8 = 1 << 2;
+
+const VBM_BUTTON_A: u16 = 1 << 0;
+const VBM_BUTTON_B: u16 = 1 << 1;
+const VBM_BUTTON_SELECT: u16 = 1 << 2;
+const VBM_BUTTON_START: u16 = 1 << 3;
+const VBM_BUTTON_RIGHT: u16 = 1 << 4;
+const VBM_BUTTON_LEFT: u16 = 1 << 5;
+const VBM_BUTTON_UP: u16 = 1 << 6;
+const VBM_BUTTON_DOWN: u16 = 1 << 7;
+const VBM_BUTTON_RESET: u16 = 1 << 11;
+
+/// Import a VisualBoyAdvance (.vbm) movie.
+///
+/// Only movies that start from power-on are supported. If more than one controller was recorded,
+/// only the first controller is used.
+pub fn import_vbm(data: &[u8]) -> Result<ImportedMovie, MovieImportError> {
+    let Some(header) = data.get(..VBM_HEADER_SIZE) else {
+        return Err(MovieImportError::InvalidMovie { explanation: Cow::Borrowed("too small to be a VBM movie") })
+    };
+
+    if header[0x00..0x04] != VBM_SIGNATURE {
+        return Err(MovieImportError::InvalidMovie { explanation: Cow::Borrowed("not a VBM movie (bad signature)") })
+    }
+
+    let read_u32 = |offset: usize| u32::from_le_bytes(header[offset..offset + 4].try_into().expect("should be 4 bytes"));
+
+    let frame_count = read_u32(0x0C) as usize;
+    let start_flags = header[0x14];
+    let controller_flags = header[0x15];
+    let system_flags = header[0x16];
+    let controller_data_offset = read_u32(0x3C) as usize;
+
+    if start_flags & (VBM_START_FROM_SAVE_STATE | VBM_START_FROM_SRAM) != 0 {
+        return Err(MovieImportError::Unsupported { explanation: Cow::Borrowed("movies that start from a save state or SRAM are not supported") })
+    }
+
+    let console_type = if system_flags & VBM_SYSTEM_GBA != 0 {
+        return Err(MovieImportError::Unsupported { explanation: Cow::Borrowed("Game Boy Advance movies are not supported") })
+    }
+    else if system_flags & VBM_SYSTEM_GBC != 0 {
+        ReplayConsoleType::GameBoyColor
+    }
+    else if system_flags & VBM_SYSTEM_SGB != 0 {
+        ReplayConsoleType::SuperGameBoy2
+    }
+    else {
+        ReplayConsoleType::GameBoy
+    };
+
+    let controller_count = (controller_flags & 0xF).count_ones() as usize;
+    if controller_count == 0 {
+        return Err(MovieImportError::InvalidMovie { explanation: Cow::Borrowed("movie has no controllers") })
+    }
+
+    let bytes_per_frame = controller_count * size_of::<u16>();
+    let controller_data = frame_count
+        .checked_mul(bytes_per_frame)
+        .and_then(|len| data.get(controller_data_offset..)?.get(..len))
+        .ok_or(MovieImportError::InvalidMovie { explanation: Cow::Borrowed("controller data is out-of-bounds") })?;
+
+    let frames = controller_data
+        .chunks_exact(bytes_per_frame)
+        .map(|frame| {
+            let buttons = u16::from_le_bytes([frame[0], frame[1]]);
+            MovieFrameInput {
+                a: buttons & VBM_BUTTON_A != 0,
+                b: buttons & VBM_BUTTON_B != 0,
+                start: buttons & VBM_BUTTON_START != 0,
+                select: buttons & VBM_BUTTON_SELECT != 0,
+                d_up: buttons & VBM_BUTTON_UP != 0,
+                d_down: buttons & VBM_BUTTON_DOWN != 0,
+                d_left: buttons & VBM_BUTTON_LEFT != 0,
+                d_right: buttons & VBM_BUTTON_RIGHT != 0,
+                reset: buttons & VBM_BUTTON_RESET != 0
+            }
+        })
+        .collect();
+
+    let rom_name = header[0x24..0x30]
+        .split(|b| *b == 0)
+        .next()
+        .and_then(|name| str::from_utf8(name).ok())
+        .unwrap_or_default()
+        .to_owned();
+
+    Ok(ImportedMovie {
+        console_type,
+        rom_name,
+        frame_rate: GAME_BOY_FRAME_RATE,
+        frames
+    })
+}
+
+/// Import the `Input Log.txt` of a BizHawk (.bk2) movie.
+///
+/// BK2 movies are zip archives, so the input log must be extracted first. Since the console type is
+/// stored separately (in `Header.txt`), the returned console type is
+/// [`ReplayConsoleType::Unknown`].
+pub fn import_bk2_input_log(input_log: &str) -> Result<ImportedMovie, MovieImportError> {
+    let mut buttons: Option<Vec<Option<Bk2Button>>> = None;
+    let mut in_input = false;
+    let mut frames = Vec::new();
+
+    for line in input_log.lines() {
+        let line = line.trim_end_matches('\r');
+
+        if line == "[Input]" {
+            in_input = true;
+            continue
+        }
+        if line == "[/Input]" {
+            break
+        }
+        if !in_input {
+            continue
+        }
+
+        if let Some(log_key) = line.strip_prefix("LogKey:") {
+            let log_key = log_key.trim_start_matches('#');
+            buttons = Some(log_key.split(['|', '#']).filter(|i| !i.is_empty()).map(Bk2Button::from_name).collect());
+            continue
+        }
+
+        let Some(buttons) = buttons.as_ref() else {
+            return Err(MovieImportError::InvalidMovie { explanation: Cow::Borrowed("input log has no LogKey") })
+        };
+
+        let Some(frame_line) = line.strip_prefix('|') else {
+            continue
+        };
+
+        let mut frame = MovieFrameInput::default();
+        let mut states = frame_line.chars().filter(|c| *c != '|');
+        for button in buttons {
+            let Some(state) = states.next() else {
+                return Err(MovieImportError::InvalidMovie { explanation: Cow::Owned(format!("input log frame #{} is too short", frames.len())) })
+            };
+            if state == ',' || state.is_ascii_digit() || state == ' ' || state == '-' {
+                return Err(MovieImportError::Unsupported { explanation: Cow::Borrowed("analog inputs are not supported") })
+            }
+            if let Some(button) = button && state != '.' {
+                button.set_for_frame(&mut frame);
+            }
+        }
+
+        frames.push(frame);
+    }
+
+    if buttons.is_none() {
+        return Err(MovieImportError::InvalidMovie { explanation: Cow::Borrowed("input log has no [Input] section") })
+    }
+
+    Ok(ImportedMovie {
+        console_type: ReplayConsoleType::Unknown,
+        rom_name: String::new(),
+        frame_rate: GAME_BOY_FRAME_RATE,
+        frames
+    })
+}
+
+#[derive(Copy, Clone)]
+enum Bk2Button {
+    A,
+    B,
+    Start,
+    Select,
+    Up,
+    Down,
+    Left,
+    Right,
+    Power
+}
+
+impl Bk2Button {
+    fn from_name(name: &str) -> Option<Self> {
+        // Multi-controller logs prefix each button with the player, e.g. "P1 Up"
+        let name = match name.split_once(' ') {
+            Some(("P1", name)) => name,
+            Some(_) => return None,
+            None => name
+        };
+
+        match name {
+            "A" => Some(Self::A),
+            "B" => Some(Self::B),
+            "Start" => Some(Self::Start),
+            "Select" => Some(Self::Select),
+            "Up" => Some(Self::Up),
+            "Down" => Some(Self::Down),
+            "Left" => Some(Self::Left),
+            "Right" => Some(Self::Right),
+            "Power" => Some(Self::Power),
+            _ => None
+        }
+    }
+
+    fn set_for_frame(self, frame: &mut MovieFrameInput) {
+        match self {
+            Self::A => frame.a = true,
+            Self::B => frame.b = true,
+            Self::Start => frame.start = true,
+            Self::Select => frame.select = true,
+            Self::Up => frame.d_up = true,
+            Self::Down => frame.d_down = true,
+            Self::Left => frame.d_left = true,
+            Self::Right => frame.d_right = true,
+            Self::Power => frame.reset = true
+        }
+    }
+}
+
+impl ImportedMovie {
+    /// Write the movie as a replay file.
+    ///
+    /// `initial_keyframe_state` can be any save state of the correct ROM, as the console is reset
+    /// immediately after it is loaded. `encode_input` converts each frame's input into the format
+    /// expected by the emulator core.
+    pub fn write_replay<Final: ReplayFileSink, Temp: ReplayFileSink, E: FnMut(&MovieFrameInput) -> InputBuffer>(
+        &self,
+        replay_file_metadata: ReplayFileMetadata,
+        settings: ReplayFileRecorderSettings,
+        initial_keyframe_state: ByteVec,
+        mut encode_input: E,
+        final_sink: Final,
+        temp_sink: Temp
+    ) -> Result<(Final, Temp), ReplayFileWriteError> {
+        let mut current_input = encode_input(&MovieFrameInput::default());
+
+        let mut recorder = ReplayFileRecorder::new_with_metadata(
+            replay_file_metadata,
+            ByteVec::new(),
+            settings,
+            0,
+            current_input.clone(),
+            Speed::default(),
+            initial_keyframe_state,
+            final_sink,
+            temp_sink
+        )?;
+
+        recorder.reset_console()?;
+
+        for (frame_index, frame) in self.frames.iter().enumerate() {
+            if frame.reset {
+                recorder.reset_console()?;
+            }
+
+            let input = encode_input(frame);
+            if input != current_input {
+                recorder.set_input(input.clone())?;
+                current_input = input;
+            }
+
+            let elapsed_millis = ((frame_index + 1) as f64 * 1000.0 / self.frame_rate) as TimestampMillis;
+            recorder.next_frame(elapsed_millis)?;
+        }
+
+        recorder.close().map_err(|(_, _, e)| e)
+    }
+}
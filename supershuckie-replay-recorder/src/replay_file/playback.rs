@@ -28,7 +28,7 @@ use alloc::sync::Arc;
 use alloc::collections::BTreeMap;
 use alloc::vec;
 use crate::replay_file::{ReplayFileMetadata, ReplayHeaderBytes, ReplayHeaderRaw};
-use crate::{BookmarkMetadata, KeyframeMetadata, Packet, PacketIO, PacketReadError, TimestampMillis, UnsignedInteger};
+use crate::{BookmarkMetadata, ByteVec, KeyframeMetadata, Packet, PacketIO, PacketReadError, TimestampMillis, UnsignedInteger};
 use crate::util::{decompress_data, launder_reference};
 
 type KeyframeMap<'a> = BTreeMap<UnsignedInteger, Vec<&'a KeyframeMetadata>>;
@@ -159,6 +159,7 @@ impl ReplayFilePlayer {
                     bookmarks,
                     uncompressed_size,
                     timestamp_end,
+                    elapsed_frames_end,
                     ..
                 } => {
                     // Vec works with up to isize maximum elements
@@ -180,6 +181,8 @@ impl ReplayFilePlayer {
                         add_bookmark!(i)
                     }
 
+                    // Blobs may end well after their last keyframe (e.g. imported movies)
+                    total_frame_count = total_frame_count.max(*elapsed_frames_end);
                     total_millis = *timestamp_end;
                 },
                 Packet::Keyframe { metadata, .. } => {
@@ -330,6 +333,67 @@ impl ReplayFilePlayer {
         unreachable!("failed to find keyframe somehow even though we somehow had it in self.keyframes...");
     }
 
+    /// Get the save state of the keyframe referenced by a [`Packet::RestoreState`] packet.
+    ///
+    /// This does not change the current position.
+    pub fn get_keyframe_state(&mut self, keyframe_index: UnsignedInteger) -> Result<ByteVec, ReplayFileReadError> {
+        let no_such_keyframe = || ReplayFileReadError::InvalidReplayFile { explanation: Cow::Owned(format!("Replay references keyframe #{keyframe_index} which does not exist")) };
+        let mut remaining = usize::try_from(keyframe_index).map_err(|_| no_such_keyframe())?;
+
+        for packet_index in 0..self.all_uncompressed_packets.len() {
+            match &self.all_uncompressed_packets[packet_index] {
+                Packet::Keyframe { state, .. } => {
+                    if remaining == 0 {
+                        return Ok(state.clone())
+                    }
+                    remaining -= 1;
+                },
+                Packet::CompressedBlob { keyframes, .. } => {
+                    if remaining >= keyframes.len() {
+                        remaining -= keyframes.len();
+                        continue
+                    }
+
+                    self.decompress_immediately(packet_index)?;
+                    let packets = self.compressed_blobs_finished
+                        .get(&packet_index)
+                        .expect("compressed blob not found in finished cache")
+                        .as_ref()
+                        .expect("should be decompressed but wasn't for some reason???");
+
+                    return packets
+                        .iter()
+                        .filter_map(|p| match p {
+                            Packet::Keyframe { state, .. } => Some(state),
+                            _ => None
+                        })
+                        .nth(remaining)
+                        .cloned()
+                        .ok_or_else(no_such_keyframe)
+                },
+                _ => continue
+            }
+        }
+
+        Err(no_such_keyframe())
+    }
+
+    /// Get the current position in the packet stream.
+    pub fn get_position(&self) -> ReplayPlayerPosition {
+        ReplayPlayerPosition {
+            uncompressed_packet_index: self.next_uncompressed_packet_index,
+            compressed_packet_index: self.next_compressed_packet_index
+        }
+    }
+
+    /// Return to a position previously returned by [`ReplayFilePlayer::get_position`].
+    ///
+    /// Unlike [`ReplayFilePlayer::go_to_keyframe`], this does not need to start at a keyframe.
+    pub fn set_position(&mut self, position: ReplayPlayerPosition) {
+        self.next_uncompressed_packet_index = position.uncompressed_packet_index;
+        self.next_compressed_packet_index = position.compressed_packet_index;
+    }
+
     fn decompress_immediately(&mut self, blob_packet_index: usize) -> Result<(), ReplayFileReadError> {
         let Some(Packet::CompressedBlob { compressed_data, uncompressed_size, .. }) = self.all_uncompressed_packets.get(blob_packet_index) else {
             panic!("decompress_immediately on {blob_packet_index} failed because it's not a compressed blob packet...")
@@ -438,13 +502,26 @@ impl ReplayFilePlayer {
 
     /// Decompress all blobs.
     pub fn decompress_all_blobs(&mut self) {
+        self.decompress_all_blobs_until(|| false);
+    }
+
+    /// Decompress all blobs, checking `should_stop` before each one.
+    ///
+    /// Returns `false` if it stopped early. Blobs that were not decompressed will still be
+    /// decompressed as needed during playback.
+    pub fn decompress_all_blobs_until(&mut self, mut should_stop: impl FnMut() -> bool) -> bool {
         self.cleanup_enabled = false;
 
         for (index, packet) in self.all_uncompressed_packets.clone().iter().enumerate() {
             if let Packet::CompressedBlob { .. } = packet {
+                if should_stop() {
+                    return false
+                }
                 let _ = self.decompress_immediately(index);
             }
         }
+
+        true
     }
 
     fn hint_decompress_next_blob_and_cleanup(&mut self) {
@@ -568,6 +645,13 @@ impl ReplayFilePlayer {
     }
 }
 
+/// A position in the packet stream of a [`ReplayFilePlayer`].
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct ReplayPlayerPosition {
+    uncompressed_packet_index: usize,
+    compressed_packet_index: Option<usize>
+}
+
 /// An error when seeking to a given a keyframe.
 #[derive(Clone, PartialEq, Debug)]
 pub enum ReplaySeekError {
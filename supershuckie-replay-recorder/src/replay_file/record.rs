@@ -2,10 +2,11 @@
 //!
 //! See [`ReplayFileRecorder`] and [`NonBlockingReplayFileRecorder`].
 
-use crate::replay_file::ReplayFileMetadata;
-use crate::{BookmarkMetadata, ByteVec, InputBuffer, KeyframeMetadata, Packet, PacketIO, PacketWriteCommand, Speed, TimestampMillis, UnsignedInteger};
+use crate::replay_file::{ReplayFileMetadata, ReplayHeaderBlake3Hash};
+use crate::{blake3_hash, create_state_diff, BookmarkMetadata, ByteVec, InputBuffer, KeyframeMetadata, MemoryWrite, Packet, PacketIO, PacketWriteCommand, Speed, TimestampMillis, UnsignedInteger};
 use alloc::string::String;
 use alloc::borrow::Cow;
+use alloc::boxed::Box;
 use alloc::vec::Vec;
 use alloc::format;
 use zstd_sys::ZSTD_defaultCLevel;
@@ -16,12 +17,27 @@ use spin::Lazy as LazyLock;
 #[cfg(feature = "std")]
 use std::sync::LazyLock;
 
+mod buffered;
+pub use buffered::*;
+
 #[cfg(feature = "std")]
 mod thread;
 
 #[cfg(feature = "std")]
 pub use thread::*;
 
+#[cfg(feature = "std")]
+mod mmap;
+
+#[cfg(feature = "std")]
+pub use mmap::*;
+
+#[cfg(feature = "std")]
+mod benchmark;
+
+#[cfg(feature = "std")]
+pub use benchmark::*;
+
 #[cfg(feature = "std")]
 use std::{
     io::{Seek, SeekFrom, Write},
@@ -44,10 +60,25 @@ pub struct ReplayFileRecorder<Final: ReplayFileSink, Temp: ReplayFileSink> {
     elapsed_frames: UnsignedInteger,
     elapsed_millis: TimestampMillis,
     last_keyframe_frames: UnsignedInteger,
+    total_keyframes: UnsignedInteger,
 
     current_speed: Speed,
     current_input: InputBuffer,
 
+    /// Save state of the most recent keyframe, which loaded save states are diffed against
+    last_keyframe_state: ByteVec,
+
+    /// Every keyframe recorded so far, used to find save states that match one.
+    ///
+    /// This is left empty when using [`LoadSaveStateMode::Embed`].
+    recorded_keyframes: Vec<RecordedKeyframe>,
+
+    /// Index in `recorded_keyframes` of the first keyframe in the current blob.
+    current_blob_first_keyframe: usize,
+
+    /// Amount subtracted from given timestamps, which goes up whenever the replay is branched.
+    timestamp_offset: TimestampMillis,
+
     sink: Option<SinkTuple<Final, Temp>>,
 
     poisoned: bool
@@ -58,6 +89,16 @@ struct SinkTuple<Final: ReplayFileSink, Temp: ReplayFileSink> {
     temp_sink: Temp
 }
 
+struct RecordedKeyframe {
+    state_hash: ReplayHeaderBlake3Hash,
+
+    /// Length of the current blob right after this keyframe (if it is still in the current blob)
+    blob_end: usize,
+
+    /// Number of bookmarks in the current blob right before this keyframe
+    blob_bookmarks: usize
+}
+
 /// Settings for [`ReplayFileRecorder`]
 #[derive(Clone)]
 pub struct ReplayFileRecorderSettings {
@@ -69,7 +110,38 @@ pub struct ReplayFileRecorderSettings {
     /// zstd compression level
     ///
     /// Default is [`DEFAULT_ZSTD_COMPRESSION_LEVEL`]
-    pub compression_level: i32
+    pub compression_level: i32,
+
+    /// What to record when a save state is loaded
+    ///
+    /// Default is [`LoadSaveStateMode::Embed`]
+    pub load_save_state_mode: LoadSaveStateMode
+}
+
+/// Determines what [`ReplayFileRecorder::load_save_state`] records.
+///
+/// Save states are matched against earlier keyframes by their contents. When not embedding, a save
+/// state that matches nothing is embedded and also recorded as a keyframe, so only the first load of
+/// each save state is stored in full.
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub enum LoadSaveStateMode {
+    /// Always embed the entire save state in a [`Packet::LoadSaveState`] packet.
+    #[default]
+    Embed,
+
+    /// If the save state matches an earlier keyframe, record a [`Packet::RestoreState`] packet
+    /// referencing it instead of embedding the save state again.
+    RestoreKeyframe,
+
+    /// If the save state matches an earlier keyframe, discard everything recorded after that
+    /// keyframe and continue recording from it, as if the discarded part was never played.
+    ///
+    /// Only keyframes in the blob currently being recorded can be branched from. Older keyframes
+    /// are referenced as with [`LoadSaveStateMode::RestoreKeyframe`].
+    ///
+    /// Timestamps given afterwards are offset so that the recording continues from the keyframe's
+    /// timestamp.
+    Branch
 }
 
 /// Default minimum uncompressed bytes per blob
@@ -118,12 +190,17 @@ impl<Final: ReplayFileSink, Temp: ReplayFileSink> ReplayFileRecorder<Final, Temp
             elapsed_frames: 0,
             elapsed_millis: 0,
             last_keyframe_frames: 0,
+            total_keyframes: 0,
+            last_keyframe_state: ByteVec::new(),
             current_speed: starting_speed,
             current_input: starting_input,
             current_blob: Vec::new(),
             current_blob_keyframes: Vec::new(),
             current_blob_bookmarks: Vec::new(),
             current_blob_offset: u64::try_from(current_blob_offset).expect("failed to read"),
+            recorded_keyframes: Vec::new(),
+            current_blob_first_keyframe: 0,
+            timestamp_offset: 0,
             poisoned: false,
             sink: Some(SinkTuple {
                 final_sink, temp_sink
@@ -154,18 +231,17 @@ impl<Final: ReplayFileSink, Temp: ReplayFileSink> ReplayFileRecorder<Final, Temp
     pub fn close(&mut self) -> Result<(Final, Temp), (Final, Temp, ReplayFileWriteError)> {
         assert!(!self.is_closed(), "Already closed...");
 
-        let _ = self.next_blob();
+        let result = self.next_blob().and_then(|_| self.flush());
 
         let Some(SinkTuple { final_sink, temp_sink }) = self.sink.take() else {
             unreachable!();
         };
 
-        if let Err(e) = self.next_blob() {
-            self.poisoned = true;
-            return Err((final_sink, temp_sink, e))
-        }
         self.poisoned = true;
-        Ok((final_sink, temp_sink))
+        match result {
+            Ok(()) => Ok((final_sink, temp_sink)),
+            Err(e) => Err((final_sink, temp_sink, e))
+        }
     }
 
     /// Returns true if an unrecoverable error occurred.
@@ -175,6 +251,7 @@ impl<Final: ReplayFileSink, Temp: ReplayFileSink> ReplayFileRecorder<Final, Temp
 
     /// Advance a new frame.
     pub fn next_frame(&mut self, timestamp: TimestampMillis) -> Result<(), ReplayFileWriteError> {
+        let timestamp = timestamp.saturating_sub(self.timestamp_offset);
         let elapsed_old = self.elapsed_millis;
         let timestamp_delta = timestamp.checked_sub(self.elapsed_millis)
             .ok_or_else(|| ReplayFileWriteError::BadInput { explanation: Cow::Owned(format!("Timestamp overflowed ({elapsed_old} -> {timestamp}")) })?;
@@ -202,8 +279,15 @@ impl<Final: ReplayFileSink, Temp: ReplayFileSink> ReplayFileRecorder<Final, Temp
 
     /// Add a new keyframe.
     ///
+    /// This also flushes both sinks (see [`ReplayFileRecorder::flush`]).
+    ///
     /// Returns the frame index the keyframe is on.
     pub fn insert_keyframe(&mut self, state: ByteVec, elapsed_millis: TimestampMillis) -> Result<u64, ReplayFileWriteError> {
+        self.insert_keyframe_at(state, elapsed_millis.saturating_sub(self.timestamp_offset))
+    }
+
+    /// Add a new keyframe at a recorded timestamp (i.e. with `timestamp_offset` already subtracted).
+    fn insert_keyframe_at(&mut self, state: ByteVec, elapsed_millis: TimestampMillis) -> Result<u64, ReplayFileWriteError> {
         assert!(self.elapsed_millis <= elapsed_millis, "Bad timestamp given (time went backwards!!!); expected {} (current) <= {elapsed_millis} (last)", self.elapsed_millis);
         self.assert_not_closed()?;
 
@@ -223,14 +307,41 @@ impl<Final: ReplayFileSink, Temp: ReplayFileSink> ReplayFileRecorder<Final, Temp
 
         self.current_blob_keyframes.push(metadata.clone());
 
+        let state_hash = match self.settings.load_save_state_mode {
+            LoadSaveStateMode::Embed => None,
+            _ => Some(blake3_hash(state.as_slice()))
+        };
+        let blob_bookmarks = self.current_blob_bookmarks.len();
+
         self.write_packet_data(&Packet::Keyframe {
             metadata,
-            state
+            state: state.clone()
         })?;
+        self.total_keyframes += 1;
+        self.last_keyframe_state = state;
+
+        if let Some(state_hash) = state_hash {
+            self.recorded_keyframes.push(RecordedKeyframe {
+                state_hash,
+                blob_end: self.current_blob.len(),
+                blob_bookmarks
+            });
+        }
+
+        self.flush()?;
 
         Ok(self.elapsed_frames)
     }
 
+    /// Flush any data buffered by the sinks.
+    pub fn flush(&mut self) -> Result<(), ReplayFileWriteError> {
+        self.do_with_poison(|this| {
+            let (final_sink, temp_sink) = this.get_sinks();
+            final_sink.flush()?;
+            temp_sink.flush()
+        })
+    }
+
     fn next_blob(&mut self) -> Result<(), ReplayFileWriteError> {
         self.do_with_poison(|this| {
             let uncompressed_size = this.current_blob.len();
@@ -238,6 +349,7 @@ impl<Final: ReplayFileSink, Temp: ReplayFileSink> ReplayFileRecorder<Final, Temp
                 .map_err(|e| ReplayFileWriteError::Other { explanation: Cow::Owned(format!("next_blob failed to compress: {e}")) })?;
 
             this.current_blob.clear();
+            this.current_blob_first_keyframe = this.recorded_keyframes.len();
 
             let keyframes_len = this.current_blob_keyframes.len();
 
@@ -285,23 +397,121 @@ impl<Final: ReplayFileSink, Temp: ReplayFileSink> ReplayFileRecorder<Final, Temp
         self.write_packet_data(&Packet::ResetConsole)
     }
 
+    /// Soft-reset the console.
+    pub fn soft_reset_console(&mut self) -> Result<(), ReplayFileWriteError> {
+        self.write_packet_data(&Packet::SoftResetConsole)
+    }
+
     /// Write RAM to an address.
     pub fn write_memory(&mut self, address: UnsignedInteger, data: ByteVec) -> Result<(), ReplayFileWriteError> {
         self.write_packet_data(&Packet::WriteMemory { address, data })
     }
 
+    /// Write RAM to multiple addresses at once.
+    pub fn write_memory_multi(&mut self, writes: Vec<MemoryWrite>) -> Result<(), ReplayFileWriteError> {
+        self.write_packet_data(&Packet::WriteMemoryMulti { writes })
+    }
+
     /// Set the current speed.
     pub fn set_speed(&mut self, speed: Speed) -> Result<(), ReplayFileWriteError> {
         if self.current_speed == speed {
             return Ok(())
         }
 
+        self.current_speed = speed;
         self.write_packet_data(&Packet::ChangeSpeed { speed })
     }
 
     /// Load a given save state immediately.
+    ///
+    /// How this is recorded depends on [`ReplayFileRecorderSettings::load_save_state_mode`]. Save
+    /// states that are recorded in full are stored as a [`Packet::LoadSaveStateDiff`] against the
+    /// most recent keyframe if that is smaller.
     pub fn load_save_state(&mut self, state: ByteVec) -> Result<(), ReplayFileWriteError> {
-        self.write_packet_data(&Packet::LoadSaveState { state })
+        let mode = self.settings.load_save_state_mode;
+        let matching_keyframe = match mode {
+            LoadSaveStateMode::Embed => None,
+            _ => {
+                let state_hash = blake3_hash(state.as_slice());
+                self.recorded_keyframes.iter().rposition(|k| k.state_hash == state_hash)
+            }
+        };
+
+        let Some(keyframe_index) = matching_keyframe else {
+            self.write_save_state(&state)?;
+            if mode != LoadSaveStateMode::Embed {
+                self.insert_keyframe_at(state, self.elapsed_millis)?;
+            }
+            return Ok(())
+        };
+
+        if mode == LoadSaveStateMode::Branch && keyframe_index >= self.current_blob_first_keyframe {
+            self.branch_from_keyframe(keyframe_index, state)
+        }
+        else {
+            let keyframe_index = UnsignedInteger::try_from(keyframe_index).expect("failed to convert keyframe index from usize to UnsignedInteger");
+            self.write_packet_data(&Packet::RestoreState { keyframe_index })
+        }
+    }
+
+    /// Load the save state of an earlier keyframe immediately.
+    ///
+    /// `keyframe_index` counts every keyframe recorded so far, starting at 0 for the initial
+    /// keyframe. Unlike [`ReplayFileRecorder::load_save_state`], this always records a
+    /// [`Packet::RestoreState`] packet.
+    pub fn restore_state(&mut self, keyframe_index: UnsignedInteger) -> Result<(), ReplayFileWriteError> {
+        if keyframe_index >= self.total_keyframes {
+            return Err(ReplayFileWriteError::BadInput { explanation: Cow::Owned(format!("Can't restore keyframe #{keyframe_index}; only {} keyframes were recorded", self.total_keyframes)) })
+        }
+        self.write_packet_data(&Packet::RestoreState { keyframe_index })
+    }
+
+    /// Get the number of keyframes recorded so far.
+    pub const fn total_keyframes(&self) -> UnsignedInteger {
+        self.total_keyframes
+    }
+
+    /// Record loading `state`, diffing it against the most recent keyframe if that is smaller.
+    fn write_save_state(&mut self, state: &ByteVec) -> Result<(), ReplayFileWriteError> {
+        let diff = create_state_diff(self.last_keyframe_state.as_slice(), state.as_slice());
+        if diff.len() >= state.len() {
+            return self.write_packet_data(&Packet::LoadSaveState { state: state.clone() })
+        }
+
+        let keyframe_index = self.total_keyframes.checked_sub(1).expect("there is always an initial keyframe");
+        self.write_packet_data(&Packet::LoadSaveStateDiff { keyframe_index, diff })
+    }
+
+    /// Discard everything after the given keyframe, which must be in the current blob and have the
+    /// save state `state`.
+    fn branch_from_keyframe(&mut self, keyframe_index: usize, state: ByteVec) -> Result<(), ReplayFileWriteError> {
+        self.do_with_poison(|this| {
+            let RecordedKeyframe { blob_end, blob_bookmarks, .. } = this.recorded_keyframes[keyframe_index];
+            let blob_keyframes = keyframe_index - this.current_blob_first_keyframe + 1;
+
+            this.recorded_keyframes.truncate(keyframe_index + 1);
+            this.total_keyframes = UnsignedInteger::try_from(keyframe_index + 1).expect("failed to convert keyframe count from usize to UnsignedInteger");
+            this.current_blob.truncate(blob_end);
+            this.current_blob_keyframes.truncate(blob_keyframes);
+            this.current_blob_bookmarks.truncate(blob_bookmarks);
+
+            let keyframe = this.current_blob_keyframes.last().expect("branched from a keyframe not in the current blob");
+
+            // Timestamps given after this continue from where they were, so offset them to continue
+            // from the keyframe instead.
+            this.timestamp_offset += this.elapsed_millis - keyframe.elapsed_millis;
+            this.elapsed_millis = keyframe.elapsed_millis;
+            this.elapsed_frames = keyframe.elapsed_frames;
+            this.last_keyframe_frames = keyframe.elapsed_frames;
+            this.current_input = keyframe.input.clone();
+            this.current_speed = keyframe.speed;
+            this.last_keyframe_state = state;
+
+            let temp_sink_len = this.current_blob_offset
+                .checked_add(u64::try_from(blob_end).expect("failed to convert blob_end from usize to u64"))
+                .expect("overflowed getting the temporary sink length");
+            this.get_sinks().1.truncate(temp_sink_len)
+        })
     }
 
     fn write_packet_data<'a, P: PacketIO<'a>>(&mut self, what: &'a P) -> Result<(), ReplayFileWriteError> {
@@ -350,6 +560,7 @@ impl Default for ReplayFileRecorderSettings {
         Self {
             minimum_uncompressed_bytes_per_blob: DEFAULT_MINIMUM_UNCOMPRESSED_BYTES_PER_BLOB,
             compression_level: *DEFAULT_ZSTD_COMPRESSION_LEVEL,
+            load_save_state_mode: LoadSaveStateMode::default()
         }
     }
 }
@@ -372,6 +583,13 @@ pub trait ReplayFileSink {
         }
         Ok(written)
     }
+
+    /// Writes any buffered data through.
+    ///
+    /// By default, this does nothing.
+    fn flush(&mut self) -> Result<(), ReplayFileWriteError> {
+        Ok(())
+    }
 }
 
 impl ReplayFileSink for Vec<u8> {
@@ -400,6 +618,28 @@ impl ReplayFileSink for Vec<u8> {
     }
 }
 
+impl<S: ReplayFileSink + ?Sized> ReplayFileSink for Box<S> {
+    #[inline]
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), ReplayFileWriteError> {
+        self.as_mut().write_bytes(bytes)
+    }
+
+    #[inline]
+    fn truncate(&mut self, size: u64) -> Result<(), ReplayFileWriteError> {
+        self.as_mut().truncate(size)
+    }
+
+    #[inline]
+    fn write_packet_data(&mut self, instructions: &[PacketWriteCommand<'_>]) -> Result<usize, ReplayFileWriteError> {
+        self.as_mut().write_packet_data(instructions)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> Result<(), ReplayFileWriteError> {
+        self.as_mut().flush()
+    }
+}
+
 #[cfg(feature = "std")]
 impl ReplayFileSink for File {
     fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), ReplayFileWriteError> {
@@ -412,6 +652,11 @@ impl ReplayFileSink for File {
         self.seek(SeekFrom::End(0))?;
         Ok(())
     }
+
+    fn flush(&mut self) -> Result<(), ReplayFileWriteError> {
+        Write::flush(self)?;
+        Ok(())
+    }
 }
 
 #[cfg(feature = "std")]
@@ -473,9 +718,13 @@ pub trait ReplayFileRecorderFns: core::any::Any + 'static + Send {
     fn insert_keyframe(&mut self, state: ByteVec, timestamp_millis: TimestampMillis) -> Result<(), ReplayFileWriteError>;
     fn set_input(&mut self, input_buffer: InputBuffer) -> Result<(), ReplayFileWriteError>;
     fn reset_console(&mut self) -> Result<(), ReplayFileWriteError>;
+    fn soft_reset_console(&mut self) -> Result<(), ReplayFileWriteError>;
     fn write_memory(&mut self, address: UnsignedInteger, data: ByteVec) -> Result<(), ReplayFileWriteError>;
+    fn write_memory_multi(&mut self, writes: Vec<MemoryWrite>) -> Result<(), ReplayFileWriteError>;
     fn set_speed(&mut self, speed: Speed) -> Result<(), ReplayFileWriteError>;
     fn load_save_state(&mut self, state: ByteVec) -> Result<(), ReplayFileWriteError>;
+    fn restore_state(&mut self, keyframe_index: UnsignedInteger) -> Result<(), ReplayFileWriteError>;
+    fn flush(&mut self) -> Result<(), ReplayFileWriteError>;
 }
 
 impl<Final: ReplayFileSink + 'static + Send, Temp: ReplayFileSink + 'static + Send> ReplayFileRecorderFns for ReplayFileRecorder<Final, Temp> {
@@ -516,11 +765,21 @@ impl<Final: ReplayFileSink + 'static + Send, Temp: ReplayFileSink + 'static + Se
         self.reset_console()
     }
 
+    #[inline]
+    fn soft_reset_console(&mut self) -> Result<(), ReplayFileWriteError> {
+        self.soft_reset_console()
+    }
+
     #[inline]
     fn write_memory(&mut self, address: UnsignedInteger, data: ByteVec) -> Result<(), ReplayFileWriteError> {
         self.write_memory(address, data)
     }
 
+    #[inline]
+    fn write_memory_multi(&mut self, writes: Vec<MemoryWrite>) -> Result<(), ReplayFileWriteError> {
+        self.write_memory_multi(writes)
+    }
+
     #[inline]
     fn set_speed(&mut self, speed: Speed) -> Result<(), ReplayFileWriteError> {
         self.set_speed(speed)
@@ -530,6 +789,16 @@ impl<Final: ReplayFileSink + 'static + Send, Temp: ReplayFileSink + 'static + Se
     fn load_save_state(&mut self, state: ByteVec) -> Result<(), ReplayFileWriteError> {
         self.load_save_state(state)
     }
+
+    #[inline]
+    fn restore_state(&mut self, keyframe_index: UnsignedInteger) -> Result<(), ReplayFileWriteError> {
+        self.restore_state(keyframe_index)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> Result<(), ReplayFileWriteError> {
+        self.flush()
+    }
 }
 
 fn _ensure_replay_file_recorder_fns_is_dyn_compatible(_fns: &dyn ReplayFileRecorderFns) {}
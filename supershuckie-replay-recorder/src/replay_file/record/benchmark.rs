@@ -0,0 +1,86 @@
+use super::ReplayFileWriteError;
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+use core::ops::RangeInclusive;
+use std::time::{Duration, Instant};
+use zstd_sys::{ZSTD_maxCLevel, ZSTD_minCLevel};
+
+/// Result of compressing a sample at a single compression level.
+///
+/// See [`benchmark_compression_levels`].
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct CompressionBenchmarkResult {
+    /// zstd compression level used
+    pub compression_level: i32,
+
+    /// Size of the sample, in bytes
+    pub uncompressed_size: usize,
+
+    /// Size of the sample after compression, in bytes
+    pub compressed_size: usize,
+
+    /// Time taken to compress the sample
+    pub duration: Duration
+}
+
+impl CompressionBenchmarkResult {
+    /// Get the compressed size as a fraction of the uncompressed size.
+    pub fn ratio(&self) -> f64 {
+        self.compressed_size as f64 / self.uncompressed_size.max(1) as f64
+    }
+
+    /// Get the number of uncompressed bytes compressed per second.
+    pub fn bytes_per_second(&self) -> f64 {
+        self.uncompressed_size as f64 / self.duration.as_secs_f64().max(f64::MIN_POSITIVE)
+    }
+}
+
+/// Get the range of compression levels supported by zstd.
+///
+/// Negative levels trade compression for speed and are rarely useful for replays.
+pub fn supported_compression_levels() -> RangeInclusive<i32> {
+    // SAFETY: These are safe.
+    unsafe { (ZSTD_minCLevel() as i32)..=(ZSTD_maxCLevel() as i32) }
+}
+
+/// Compress `sample` at each compression level in `levels`, measuring the size and time taken.
+///
+/// `sample` should look like real replay data (e.g. the uncompressed contents of a blob), since
+/// results vary wildly based on what is being compressed. Levels outside of
+/// [`supported_compression_levels`] are skipped.
+///
+/// The `std` feature is required to use this.
+pub fn benchmark_compression_levels(sample: &[u8], levels: RangeInclusive<i32>) -> Result<Vec<CompressionBenchmarkResult>, ReplayFileWriteError> {
+    let supported = supported_compression_levels();
+    let mut results = Vec::new();
+
+    for compression_level in levels.filter(|i| supported.contains(i)) {
+        let start = Instant::now();
+        let compressed = crate::compress_data(sample, compression_level)
+            .map_err(|e| ReplayFileWriteError::Other { explanation: Cow::Owned(alloc::format!("benchmark failed to compress: {e}")) })?;
+        let duration = start.elapsed();
+
+        results.push(CompressionBenchmarkResult {
+            compression_level,
+            uncompressed_size: sample.len(),
+            compressed_size: compressed.len(),
+            duration
+        });
+    }
+
+    Ok(results)
+}
+
+/// Pick the compression level that compresses the best while still compressing at least
+/// `min_bytes_per_second`.
+///
+/// If multiple levels produce the same size, the fastest one is picked. If no level is fast enough,
+/// the fastest level is picked instead. Returns `None` if `results` is empty.
+pub fn pick_compression_level(results: &[CompressionBenchmarkResult], min_bytes_per_second: f64) -> Option<i32> {
+    results
+        .iter()
+        .filter(|i| i.bytes_per_second() >= min_bytes_per_second)
+        .min_by(|a, b| a.compressed_size.cmp(&b.compressed_size).then(a.duration.cmp(&b.duration)))
+        .or_else(|| results.iter().min_by_key(|i| i.duration))
+        .map(|i| i.compression_level)
+}
@@ -0,0 +1,137 @@
+use super::{ReplayFileSink, ReplayFileWriteError};
+use crate::PacketWriteCommand;
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+use core::mem::ManuallyDrop;
+
+/// A [`ReplayFileSink`] that buffers writes before passing them to another sink.
+///
+/// Packets are often only a few bytes long, so writing each one directly to a file would mean a
+/// syscall for every input change. Instead, writes are collected in memory and passed to the inner
+/// sink once the buffer would exceed the flush threshold. Writes at least as large as the threshold
+/// bypass the buffer entirely.
+///
+/// The buffer is also flushed when truncating, when [`flush`](ReplayFileSink::flush) is called, and
+/// when dropped (though errors are ignored there; use [`into_inner`](Self::into_inner) to catch
+/// them). [`ReplayFileRecorder`](super::ReplayFileRecorder) flushes its sinks on every keyframe.
+pub struct BufferedReplayFileSink<S: ReplayFileSink> {
+    inner: S,
+    buffer: Vec<u8>,
+    flush_threshold: usize
+}
+
+impl<S: ReplayFileSink> BufferedReplayFileSink<S> {
+    /// Default number of bytes to buffer before flushing.
+    pub const DEFAULT_FLUSH_THRESHOLD: usize = 64 * 1024;
+
+    /// Wrap the sink, buffering up to [`DEFAULT_FLUSH_THRESHOLD`](Self::DEFAULT_FLUSH_THRESHOLD) bytes.
+    #[inline]
+    pub fn new(inner: S) -> Self {
+        Self::with_flush_threshold(inner, Self::DEFAULT_FLUSH_THRESHOLD)
+    }
+
+    /// Wrap the sink, buffering up to `flush_threshold` bytes.
+    ///
+    /// A threshold of `0` disables buffering.
+    pub fn with_flush_threshold(inner: S, flush_threshold: usize) -> Self {
+        Self {
+            inner,
+            buffer: Vec::with_capacity(flush_threshold),
+            flush_threshold
+        }
+    }
+
+    /// Get the number of bytes to buffer before flushing.
+    #[inline]
+    pub const fn flush_threshold(&self) -> usize {
+        self.flush_threshold
+    }
+
+    /// Get the number of bytes currently buffered.
+    #[inline]
+    pub fn buffered_len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Get a reference to the inner sink.
+    ///
+    /// Note that buffered data will not have been written to it yet.
+    #[inline]
+    pub const fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    /// Flush the buffer and return the inner sink.
+    pub fn into_inner(mut self) -> Result<S, ReplayFileWriteError> {
+        self.flush()?;
+        let mut this = ManuallyDrop::new(self);
+
+        // SAFETY: `this` is never used or dropped again, so each field is moved out exactly once.
+        unsafe {
+            core::ptr::drop_in_place(&mut this.buffer);
+            Ok(core::ptr::read(&this.inner))
+        }
+    }
+
+    fn flush_buffer(&mut self) -> Result<(), ReplayFileWriteError> {
+        if !self.buffer.is_empty() {
+            self.inner.write_bytes(self.buffer.as_slice())?;
+            self.buffer.clear();
+        }
+        Ok(())
+    }
+
+    /// Make room for `incoming` more bytes, returning `true` if they should go into the buffer.
+    fn prepare_for(&mut self, incoming: usize) -> Result<bool, ReplayFileWriteError> {
+        if self.buffer.len().saturating_add(incoming) > self.flush_threshold {
+            self.flush_buffer()?;
+        }
+        if incoming >= self.flush_threshold {
+            return Ok(false)
+        }
+        self.buffer.try_reserve(incoming).map_err(|_| ReplayFileWriteError::Other { explanation: Cow::Borrowed("BufferedReplayFileSink failed to reserve memory") })?;
+        Ok(true)
+    }
+}
+
+impl<S: ReplayFileSink> ReplayFileSink for BufferedReplayFileSink<S> {
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), ReplayFileWriteError> {
+        if self.prepare_for(bytes.len())? {
+            self.buffer.extend_from_slice(bytes);
+            Ok(())
+        }
+        else {
+            self.inner.write_bytes(bytes)
+        }
+    }
+
+    fn truncate(&mut self, size: u64) -> Result<(), ReplayFileWriteError> {
+        self.flush_buffer()?;
+        self.inner.truncate(size)
+    }
+
+    fn write_packet_data(&mut self, instructions: &[PacketWriteCommand<'_>]) -> Result<usize, ReplayFileWriteError> {
+        let mut total_len = 0usize;
+        for i in instructions {
+            total_len = total_len.saturating_add(i.bytes().len());
+        }
+        if !self.prepare_for(total_len)? {
+            return self.inner.write_packet_data(instructions)
+        }
+        for i in instructions {
+            self.buffer.extend_from_slice(i.bytes());
+        }
+        Ok(total_len)
+    }
+
+    fn flush(&mut self) -> Result<(), ReplayFileWriteError> {
+        self.flush_buffer()?;
+        self.inner.flush()
+    }
+}
+
+impl<S: ReplayFileSink> Drop for BufferedReplayFileSink<S> {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
@@ -0,0 +1,137 @@
+use super::{ReplayFileSink, ReplayFileWriteError};
+use crate::PacketWriteCommand;
+use alloc::borrow::Cow;
+use memmap2::MmapMut;
+use std::fs::File;
+
+/// A [`ReplayFileSink`] backed by a memory-mapped file.
+///
+/// The file is preallocated and grown in large increments, so most writes are a copy into memory
+/// rather than a syscall, and truncating only moves the end of the sink. This makes it well-suited
+/// for temporary sinks, which are truncated often.
+///
+/// The file must be opened for both reading and writing. Any existing contents of the file are
+/// overwritten. The file is trimmed to the length of the sink
+/// when dropped. If the process dies before then, the file may have trailing zero bytes, which are
+/// read back as [`Packet::NoOp`](crate::Packet::NoOp).
+///
+/// The `std` feature is required to use this.
+pub struct MmapReplayFileSink {
+    file: File,
+    map: Option<MmapMut>,
+    len: u64,
+    capacity: u64,
+    growth: u64
+}
+
+impl MmapReplayFileSink {
+    /// Default number of bytes to preallocate and grow by.
+    pub const DEFAULT_GROWTH: u64 = 16 * 1024 * 1024;
+
+    /// Instantiate a sink, preallocating and growing by [`DEFAULT_GROWTH`](Self::DEFAULT_GROWTH) bytes.
+    #[inline]
+    pub fn new(file: File) -> Result<Self, ReplayFileWriteError> {
+        Self::with_growth(file, Self::DEFAULT_GROWTH)
+    }
+
+    /// Instantiate a sink, preallocating and growing by `growth` bytes.
+    pub fn with_growth(file: File, growth: u64) -> Result<Self, ReplayFileWriteError> {
+        // Discard the existing contents so the preallocated space is all zeroes.
+        file.set_len(0)?;
+
+        let mut sink = Self {
+            file,
+            map: None,
+            len: 0,
+            capacity: 0,
+            growth: growth.max(1)
+        };
+        sink.reserve(0)?;
+        Ok(sink)
+    }
+
+    /// Ensure there is room for `additional` more bytes, growing the file if needed.
+    fn reserve(&mut self, additional: usize) -> Result<(), ReplayFileWriteError> {
+        let required = self.len
+            .checked_add(additional as u64)
+            .ok_or(ReplayFileWriteError::Other { explanation: Cow::Borrowed("MmapReplayFileSink length overflowed") })?;
+
+        if required <= self.capacity && self.map.is_some() {
+            return Ok(())
+        }
+
+        let new_capacity = required.max(self.capacity.saturating_add(self.growth));
+        if usize::try_from(new_capacity).is_err() {
+            return Err(ReplayFileWriteError::Other { explanation: Cow::Borrowed("MmapReplayFileSink can't map more than usize::MAX bytes") })
+        }
+
+        // Unmap first, since some platforms won't resize a file that is mapped.
+        if let Some(map) = self.map.take() {
+            map.flush()?;
+        }
+
+        self.file.set_len(new_capacity)?;
+
+        // SAFETY: We own the file, and nothing else in this process can access it while it's mapped.
+        //         Another process could modify it, but that's a risk with any file-backed sink.
+        let map = unsafe { MmapMut::map_mut(&self.file) }?;
+
+        self.map = Some(map);
+        self.capacity = new_capacity;
+        Ok(())
+    }
+
+    fn map_mut(&mut self) -> &mut MmapMut {
+        self.map.as_mut().expect("MmapReplayFileSink map was not initialized")
+    }
+}
+
+impl ReplayFileSink for MmapReplayFileSink {
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), ReplayFileWriteError> {
+        self.reserve(bytes.len())?;
+        let start = self.len as usize;
+        self.map_mut()[start..start + bytes.len()].copy_from_slice(bytes);
+        self.len += bytes.len() as u64;
+        Ok(())
+    }
+
+    fn truncate(&mut self, size: u64) -> Result<(), ReplayFileWriteError> {
+        // Like File::set_len, growing fills with zeroes.
+        if size > self.len {
+            self.reserve((size - self.len) as usize)?;
+            let (start, end) = (self.len as usize, size as usize);
+            self.map_mut()[start..end].fill(0);
+        }
+        else {
+            // Zero what was cut off so it isn't read back if the file is never trimmed.
+            let (start, end) = (size as usize, self.len as usize);
+            self.map_mut()[start..end].fill(0);
+        }
+        self.len = size;
+        Ok(())
+    }
+
+    fn write_packet_data(&mut self, instructions: &[PacketWriteCommand<'_>]) -> Result<usize, ReplayFileWriteError> {
+        let mut total_len = 0usize;
+        for i in instructions {
+            total_len = total_len.saturating_add(i.bytes().len());
+        }
+        self.reserve(total_len)?;
+        for i in instructions {
+            let bytes = i.bytes();
+            let start = self.len as usize;
+            self.map_mut()[start..start + bytes.len()].copy_from_slice(bytes);
+            self.len += bytes.len() as u64;
+        }
+        Ok(total_len)
+    }
+}
+
+impl Drop for MmapReplayFileSink {
+    fn drop(&mut self) {
+        if let Some(map) = self.map.take() {
+            let _ = map.flush();
+        }
+        let _ = self.file.set_len(self.len);
+    }
+}
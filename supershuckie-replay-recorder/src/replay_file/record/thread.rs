@@ -1,7 +1,8 @@
 use super::{ReplayFileWriteError, ReplayFileRecorder, ReplayFileSink, ReplayFileRecorderFns};
-use crate::{ByteVec, InputBuffer, Speed, TimestampMillis, UnsignedInteger};
+use crate::{ByteVec, InputBuffer, MemoryWrite, Speed, TimestampMillis, UnsignedInteger};
 use alloc::borrow::ToOwned;
 use alloc::string::String;
+use alloc::vec::Vec;
 use std::sync::mpsc::{channel, Receiver, Sender};
 use std::sync::Mutex;
 use std::sync::{Arc, Weak};
@@ -105,11 +106,21 @@ impl<Final: ReplayFileSink + Send + 'static, Temp: ReplayFileSink + Send + 'stat
         let _ = self.sender.send(ThreadedReplayFileRecorderCommand::ResetConsole);
     }
 
+    /// Soft-reset the console.
+    pub fn soft_reset_console(&mut self) {
+        let _ = self.sender.send(ThreadedReplayFileRecorderCommand::SoftResetConsole);
+    }
+
     /// Write RAM to an address.
     pub fn write_memory(&mut self, address: UnsignedInteger, data: ByteVec) {
         let _ = self.sender.send(ThreadedReplayFileRecorderCommand::WriteMemory { address, data });
     }
 
+    /// Write RAM to multiple addresses at once.
+    pub fn write_memory_multi(&mut self, writes: Vec<MemoryWrite>) {
+        let _ = self.sender.send(ThreadedReplayFileRecorderCommand::WriteMemoryMulti { writes });
+    }
+
     /// Set the current speed.
     pub fn set_speed(&mut self, speed: Speed) {
         let _ = self.sender.send(ThreadedReplayFileRecorderCommand::SetSpeed { speed });
@@ -120,6 +131,18 @@ impl<Final: ReplayFileSink + Send + 'static, Temp: ReplayFileSink + Send + 'stat
         let _ = self.sender.send(ThreadedReplayFileRecorderCommand::LoadSaveState { state });
     }
 
+    /// Load the save state of an earlier keyframe.
+    ///
+    /// See [`ReplayFileRecorder::restore_state`].
+    pub fn restore_state(&mut self, keyframe_index: UnsignedInteger) {
+        let _ = self.sender.send(ThreadedReplayFileRecorderCommand::RestoreState { keyframe_index });
+    }
+
+    /// Flush any data buffered by the sinks.
+    pub fn flush(&mut self) {
+        let _ = self.sender.send(ThreadedReplayFileRecorderCommand::Flush);
+    }
+
     /// Check for errors, if any.
     pub fn poll_errors(&mut self) -> Option<ReplayFileWriteError> {
         self.receiver.recv().ok().map(|i| match i {
@@ -169,6 +192,9 @@ impl<Final: ReplayFileSink, Temp: ReplayFileSink> ThreadedReplayFileRecorderThre
             ThreadedReplayFileRecorderCommand::WriteMemory { address, data } => {
                 recorder.write_memory(address, data)
             },
+            ThreadedReplayFileRecorderCommand::WriteMemoryMulti { writes } => {
+                recorder.write_memory_multi(writes)
+            },
             ThreadedReplayFileRecorderCommand::NewKeyframe { timestamp, state } => {
                 let _ = recorder.insert_keyframe(state, timestamp)?;
                 Ok(())
@@ -185,11 +211,20 @@ impl<Final: ReplayFileSink, Temp: ReplayFileSink> ThreadedReplayFileRecorderThre
             ThreadedReplayFileRecorderCommand::ResetConsole => {
                 recorder.reset_console()
             },
+            ThreadedReplayFileRecorderCommand::SoftResetConsole => {
+                recorder.soft_reset_console()
+            },
             ThreadedReplayFileRecorderCommand::NextFrame { timestamp } => {
                 recorder.next_frame(timestamp)
             },
             ThreadedReplayFileRecorderCommand::LoadSaveState { state } => {
                 recorder.load_save_state(state)
+            },
+            ThreadedReplayFileRecorderCommand::RestoreState { keyframe_index } => {
+                recorder.restore_state(keyframe_index)
+            },
+            ThreadedReplayFileRecorderCommand::Flush => {
+                recorder.flush()
             }
         }
     }
@@ -202,8 +237,12 @@ enum ThreadedReplayFileRecorderCommand {
     SetInput { input: InputBuffer },
     SetSpeed { speed: Speed },
     WriteMemory { address: UnsignedInteger, data: ByteVec },
+    WriteMemoryMulti { writes: Vec<MemoryWrite> },
     LoadSaveState { state: ByteVec },
+    RestoreState { keyframe_index: UnsignedInteger },
     ResetConsole,
+    SoftResetConsole,
+    Flush,
     Close
 }
 
@@ -254,12 +293,24 @@ impl<Final: ReplayFileSink + Sync + Send + 'static, Temp: ReplayFileSink + Sync
         Ok(())
     }
 
+    #[inline]
+    fn soft_reset_console(&mut self) -> Result<(), ReplayFileWriteError> {
+        self.soft_reset_console();
+        Ok(())
+    }
+
     #[inline]
     fn write_memory(&mut self, address: UnsignedInteger, data: ByteVec) -> Result<(), ReplayFileWriteError> {
         self.write_memory(address, data);
         Ok(())
     }
 
+    #[inline]
+    fn write_memory_multi(&mut self, writes: Vec<MemoryWrite>) -> Result<(), ReplayFileWriteError> {
+        self.write_memory_multi(writes);
+        Ok(())
+    }
+
     #[inline]
     fn set_speed(&mut self, speed: Speed) -> Result<(), ReplayFileWriteError> {
         self.set_speed(speed);
@@ -271,6 +322,18 @@ impl<Final: ReplayFileSink + Sync + Send + 'static, Temp: ReplayFileSink + Sync
         self.load_save_state(state);
         Ok(())
     }
+
+    #[inline]
+    fn restore_state(&mut self, keyframe_index: UnsignedInteger) -> Result<(), ReplayFileWriteError> {
+        self.restore_state(keyframe_index);
+        Ok(())
+    }
+
+    #[inline]
+    fn flush(&mut self) -> Result<(), ReplayFileWriteError> {
+        self.flush();
+        Ok(())
+    }
 }
 
 // TODO: write unit tests
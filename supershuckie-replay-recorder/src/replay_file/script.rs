@@ -0,0 +1,218 @@
+//! Plain-text input script functionality.
+//!
+//! Input scripts are a human-readable listing of a replay's input track, intended for hand-editing
+//! and diffing. Each line is a frame index followed by the buttons held starting on that frame; the
+//! input is held until the next line. For example:
+//!
+//! ```text
+//! # Super Shuckie input script
+//! 0 -
+//! 120 Start
+//! 122 -
+//! 300 Reset A
+//! end 500
+//! ```
+//!
+//! `-` releases all buttons, `Reset` resets the console before the frame runs, and `end` is the
+//! total number of frames. Lines starting with `#` are comments.
+//!
+//! See [`export_input_script`] and [`import_input_script`].
+
+use crate::replay_file::import::{GAME_BOY_FRAME_RATE, ImportedMovie, MovieFrameInput, MovieImportError};
+use crate::replay_file::playback::{ReplayFilePlayer, ReplayFileReadError, ReplaySeekError};
+use crate::replay_file::ReplayConsoleType;
+use crate::{Packet, UnsignedInteger};
+use alloc::borrow::Cow;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+const SCRIPT_HEADER: &str = "# Super Shuckie input script";
+
+/// Longest script that can be imported (24 hours), so a typo in a frame index can't exhaust memory.
+const MAX_SCRIPT_FRAMES: usize = (GAME_BOY_FRAME_RATE * 60.0 * 60.0 * 24.0) as usize;
+
+type ButtonAccessor = fn(&mut MovieFrameInput) -> &mut bool;
+
+const BUTTON_NAMES: [(&str, ButtonAccessor); 9] = [
+    ("Reset", |i| &mut i.reset),
+    ("A", |i| &mut i.a),
+    ("B", |i| &mut i.b),
+    ("Start", |i| &mut i.start),
+    ("Select", |i| &mut i.select),
+    ("Up", |i| &mut i.d_up),
+    ("Down", |i| &mut i.d_down),
+    ("Left", |i| &mut i.d_left),
+    ("Right", |i| &mut i.d_right)
+];
+
+/// An input script exported with [`export_input_script`].
+#[derive(Clone, PartialEq, Debug)]
+pub struct ExportedInputScript {
+    /// The input script
+    pub script: String,
+
+    /// Frames a save state was loaded before (including keyframes restored by
+    /// [`Packet::RestoreState`]).
+    ///
+    /// Input scripts can't load save states, so playing the script back diverges from the replay
+    /// starting at the first of these. They are also noted in the script as comments.
+    pub state_loaded_frames: Vec<UnsignedInteger>
+}
+
+/// Export the input track of a replay as an input script.
+///
+/// `decode_input` converts the emulator core's encoded input into buttons.
+pub fn export_input_script<D: FnMut(&[u8]) -> MovieFrameInput>(
+    player: &mut ReplayFilePlayer,
+    mut decode_input: D
+) -> Result<ExportedInputScript, ReplayFileReadError> {
+    player.go_to_keyframe(0).map_err(|e| match e {
+        ReplaySeekError::ReadError { error } => error,
+        ReplaySeekError::NoSuchKeyframe { .. } => unreachable!("there is always a keyframe at frame index 0")
+    })?;
+
+    let mut script = String::new();
+    script.push_str(SCRIPT_HEADER);
+    script.push('\n');
+
+    let mut current_input: Option<MovieFrameInput> = None;
+    let mut written_input: Option<MovieFrameInput> = None;
+    let mut reset_pending = false;
+    let mut state_loaded_frames = Vec::new();
+    let mut frame: UnsignedInteger = 0;
+
+    while let Some(packet) = player.next_packet()? {
+        match packet {
+            Packet::Keyframe { metadata, .. } if current_input.is_none() => {
+                current_input = Some(decode_input(metadata.input.as_slice()));
+            },
+            Packet::ChangeInput { data } => {
+                current_input = Some(decode_input(data.as_slice()));
+            },
+            Packet::ResetConsole => {
+                reset_pending = true;
+            },
+            Packet::LoadSaveState { .. } | Packet::LoadSaveStateDiff { .. } | Packet::RestoreState { .. } if state_loaded_frames.last() != Some(&frame) => {
+                let _ = writeln!(script, "# save state loaded before frame {frame}, which this script does not reproduce");
+                state_loaded_frames.push(frame);
+            },
+            Packet::NextFrame { .. } => {
+                let mut input = current_input.unwrap_or_default();
+                input.reset = reset_pending;
+
+                if written_input != Some(input) || reset_pending {
+                    write_line(&mut script, frame, &input);
+                    written_input = Some(MovieFrameInput { reset: false, ..input });
+                }
+
+                reset_pending = false;
+                frame += 1;
+            },
+            _ => {}
+        }
+    }
+
+    let _ = writeln!(script, "end {frame}");
+    Ok(ExportedInputScript { script, state_loaded_frames })
+}
+
+fn write_line(script: &mut String, frame: UnsignedInteger, input: &MovieFrameInput) {
+    let _ = write!(script, "{frame}");
+
+    let mut input = *input;
+    let mut any = false;
+    for (name, button) in BUTTON_NAMES {
+        if *button(&mut input) {
+            script.push(' ');
+            script.push_str(name);
+            any = true;
+        }
+    }
+
+    if !any {
+        script.push_str(" -");
+    }
+    script.push('\n');
+}
+
+/// Import an input script.
+///
+/// Scripts can be at most 24 hours long.
+///
+/// The result can then be written as a replay with [`ImportedMovie::write_replay`].
+pub fn import_input_script(script: &str) -> Result<ImportedMovie, MovieImportError> {
+    let mut frames: Vec<MovieFrameInput> = Vec::new();
+    let mut current_input = MovieFrameInput::default();
+    let mut end = None;
+
+    for (line_index, line) in script.lines().enumerate() {
+        let line_number = line_index + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue
+        }
+
+        if end.is_some() {
+            return Err(MovieImportError::InvalidMovie { explanation: Cow::Owned(format!("line {line_number}: unexpected line after `end`")) })
+        }
+
+        let mut tokens = line.split_whitespace();
+        let frame_token = tokens.next().expect("line is not empty");
+
+        if frame_token == "end" {
+            let frame_count = tokens.next()
+                .and_then(|i| i.parse::<usize>().ok())
+                .ok_or_else(|| MovieImportError::InvalidMovie { explanation: Cow::Owned(format!("line {line_number}: expected a frame count after `end`")) })?;
+            if frame_count > MAX_SCRIPT_FRAMES {
+                return Err(MovieImportError::InvalidMovie { explanation: Cow::Owned(format!("line {line_number}: frame count {frame_count} is more than the maximum of {MAX_SCRIPT_FRAMES}")) })
+            }
+            end = Some(frame_count);
+            continue
+        }
+
+        let frame = frame_token.parse::<usize>()
+            .map_err(|_| MovieImportError::InvalidMovie { explanation: Cow::Owned(format!("line {line_number}: invalid frame index `{frame_token}`")) })?;
+
+        if frame >= MAX_SCRIPT_FRAMES {
+            return Err(MovieImportError::InvalidMovie { explanation: Cow::Owned(format!("line {line_number}: frame {frame} is past the maximum of {MAX_SCRIPT_FRAMES} frames")) })
+        }
+
+        if frame < frames.len() {
+            return Err(MovieImportError::InvalidMovie { explanation: Cow::Owned(format!("line {line_number}: frame {frame} is out of order")) })
+        }
+
+        frames.resize(frame, MovieFrameInput { reset: false, ..current_input });
+
+        let mut input = MovieFrameInput::default();
+        for token in tokens {
+            if token == "-" {
+                continue
+            }
+            let Some((_, button)) = BUTTON_NAMES.iter().find(|(name, _)| name.eq_ignore_ascii_case(token)) else {
+                return Err(MovieImportError::InvalidMovie { explanation: Cow::Owned(format!("line {line_number}: unknown button `{token}`")) })
+            };
+            *button(&mut input) = true;
+        }
+
+        frames.push(input);
+        current_input = input;
+    }
+
+    let Some(end) = end else {
+        return Err(MovieImportError::InvalidMovie { explanation: Cow::Borrowed("script has no `end` line") })
+    };
+
+    if end < frames.len() {
+        return Err(MovieImportError::InvalidMovie { explanation: Cow::Owned(format!("`end {end}` is before the last input")) })
+    }
+    frames.resize(end, MovieFrameInput { reset: false, ..current_input });
+
+    Ok(ImportedMovie {
+        console_type: ReplayConsoleType::Unknown,
+        rom_name: String::new(),
+        frame_rate: GAME_BOY_FRAME_RATE,
+        frames
+    })
+}
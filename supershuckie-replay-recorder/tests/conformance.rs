@@ -0,0 +1,45 @@
+//! Checks the canonical test vectors against themselves and against the golden copies in
+//! `tests/conformance`, so any change to the replay format fails here.
+//!
+//! If the format is changed on purpose (or zstd is updated, which changes the compressed bytes),
+//! regenerate the golden copies by running these tests with `SUPERSHUCKIE_BLESS_CONFORMANCE=1`.
+
+use std::path::{Path, PathBuf};
+use supershuckie_replay_recorder::replay_file::conformance::{generate_test_vectors, write_test_vectors, ConformanceReport, TestVector};
+
+fn golden_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("conformance")
+}
+
+fn vectors() -> Vec<TestVector> {
+    generate_test_vectors().expect("failed to generate test vectors")
+}
+
+fn assert_conformant(name: &str, report: &ConformanceReport) {
+    assert!(report.is_conformant(), "{name} is not conformant: {:#?}", report.issues);
+}
+
+#[test]
+fn test_vectors_are_conformant() {
+    for vector in vectors() {
+        assert_conformant(vector.name, &vector.check(&vector.data));
+    }
+}
+
+#[test]
+fn test_vectors_match_golden_files() {
+    let directory = golden_dir();
+    if std::env::var_os("SUPERSHUCKIE_BLESS_CONFORMANCE").is_some() {
+        std::fs::create_dir_all(&directory).expect("failed to create golden directory");
+        write_test_vectors(&directory).expect("failed to write golden files");
+    }
+
+    for vector in vectors() {
+        let path = directory.join(format!("{}.replay", vector.name));
+        let golden = std::fs::read(&path).unwrap_or_else(|e| panic!("failed to read {}: {e}", path.display()));
+
+        // Check the packets first, since that's a more useful failure than differing bytes
+        assert_conformant(vector.name, &vector.check(&golden));
+        assert!(golden == vector.data, "{} no longer matches its golden file byte-for-byte", vector.name);
+    }
+}
@@ -0,0 +1,116 @@
+use supershuckie_replay_recorder::replay_file::import::{MovieFrameInput, MovieImportError};
+use supershuckie_replay_recorder::replay_file::playback::ReplayFilePlayer;
+use supershuckie_replay_recorder::replay_file::record::{LoadSaveStateMode, NullReplayFileSink, ReplayFileRecorder, ReplayFileRecorderSettings};
+use supershuckie_replay_recorder::replay_file::script::{export_input_script, import_input_script};
+use supershuckie_replay_recorder::replay_file::{ReplayConsoleType, ReplayFileMetadata, ReplayPatchFormat};
+use supershuckie_replay_recorder::{blake3_hash, ByteVec, InputBuffer, Speed, UnsignedInteger};
+
+type Recorder = ReplayFileRecorder<Vec<u8>, NullReplayFileSink>;
+
+fn state(seed: u8) -> ByteVec {
+    (0..256u32).map(|i| (i as u8).wrapping_mul(seed).wrapping_add(seed)).collect::<Vec<u8>>().as_slice().into()
+}
+
+fn start_recording() -> Recorder {
+    let metadata = ReplayFileMetadata {
+        console_type: ReplayConsoleType::GameBoyColor,
+        rom_name: "SCRIPT".to_owned(),
+        rom_filename: "script.gbc".to_owned(),
+        rom_checksum: blake3_hash(b"script rom"),
+        bios_checksum: blake3_hash(b"script bios"),
+        emulator_core_name: "test".to_owned(),
+        patch_format: ReplayPatchFormat::Unpatched,
+        patch_target_checksum: [0u8; 32],
+        sram_checksum: [0u8; 32],
+        core_config: ByteVec::new()
+    };
+    let settings = ReplayFileRecorderSettings {
+        minimum_uncompressed_bytes_per_blob: 1024 * 1024,
+        compression_level: 3,
+        load_save_state_mode: LoadSaveStateMode::Embed
+    };
+    ReplayFileRecorder::new_with_metadata(
+        metadata,
+        ByteVec::new(),
+        settings,
+        0,
+        InputBuffer::from([0u8].as_slice()),
+        Speed::default(),
+        state(1),
+        Vec::new(),
+        NullReplayFileSink
+    ).expect("failed to start recording")
+}
+
+/// Advance `count` frames at 60 FPS.
+fn frames(recorder: &mut Recorder, elapsed_frames: &mut UnsignedInteger, count: UnsignedInteger) {
+    for _ in 0..count {
+        *elapsed_frames += 1;
+        recorder.next_frame(*elapsed_frames * 1000 / 60).expect("failed to record frame");
+    }
+}
+
+/// Input is a single byte where bit 0 is A.
+fn decode_input(input: &[u8]) -> MovieFrameInput {
+    MovieFrameInput { a: input.first().is_some_and(|i| i & 1 != 0), ..MovieFrameInput::default() }
+}
+
+fn import_error(script: &str) -> String {
+    match import_input_script(script) {
+        Ok(_) => panic!("imported a bad script"),
+        Err(MovieImportError::InvalidMovie { explanation }) => explanation.into_owned(),
+        Err(MovieImportError::Unsupported { explanation }) => panic!("unexpected unsupported error: {explanation}")
+    }
+}
+
+#[test]
+fn script_imports() {
+    let movie = import_input_script("# Super Shuckie input script\n0 -\n2 Start\n3 Reset A\nend 5\n").expect("failed to import script");
+    assert_eq!(movie.frames.len(), 5);
+    assert!(!movie.frames[1].start);
+    assert!(movie.frames[2].start);
+    assert!(movie.frames[3].reset && movie.frames[3].a && !movie.frames[3].start);
+    assert!(!movie.frames[4].reset && movie.frames[4].a);
+}
+
+#[test]
+fn huge_frame_index_is_rejected() {
+    let error = import_error("0 -\n4000000000 A\nend 4000000001\n");
+    assert!(error.starts_with("line 2:"), "unexpected error: {error}");
+}
+
+#[test]
+fn huge_frame_count_is_rejected() {
+    let error = import_error("0 -\n\n60 A\nend 18446744073709551615\n");
+    assert!(error.starts_with("line 4:"), "unexpected error: {error}");
+}
+
+#[test]
+fn exported_script_notes_loaded_states() {
+    let mut recorder = start_recording();
+    let mut elapsed_frames = 0;
+
+    frames(&mut recorder, &mut elapsed_frames, 2);
+    recorder.set_input(InputBuffer::from([1u8].as_slice())).expect("failed to set input");
+    frames(&mut recorder, &mut elapsed_frames, 2);
+    recorder.load_save_state(state(2)).expect("failed to load save state");
+    frames(&mut recorder, &mut elapsed_frames, 2);
+    recorder.restore_state(0).expect("failed to restore keyframe 0");
+    recorder.load_save_state(state(3)).expect("failed to load save state");
+    frames(&mut recorder, &mut elapsed_frames, 2);
+
+    let (data, _) = recorder.close().unwrap_or_else(|(_, _, e)| panic!("failed to close recording: {e:?}"));
+    let mut player = ReplayFilePlayer::new(data, false).expect("failed to read recording");
+    let exported = export_input_script(&mut player, decode_input).expect("failed to export script");
+
+    assert_eq!(exported.state_loaded_frames, [4, 6]);
+    assert_eq!(exported.script, "# Super Shuckie input script\n\
+        0 -\n\
+        2 A\n\
+        # save state loaded before frame 4, which this script does not reproduce\n\
+        # save state loaded before frame 6, which this script does not reproduce\n\
+        end 8\n");
+
+    // The notes don't get in the way of importing the script
+    assert_eq!(import_input_script(&exported.script).expect("failed to import script").frames.len(), 8);
+}
@@ -0,0 +1,44 @@
+use std::fs::{File, OpenOptions};
+use std::path::PathBuf;
+use supershuckie_replay_recorder::replay_file::record::{MmapReplayFileSink, ReplayFileSink};
+
+/// Open a new file in the temporary directory for reading and writing.
+fn open_temp_file(name: &str) -> (PathBuf, File) {
+    let path = std::env::temp_dir().join(format!("supershuckie-mmap-sink-{}-{name}", std::process::id()));
+    let file = OpenOptions::new().read(true).write(true).create(true).truncate(true).open(&path).expect("failed to open temp file");
+    (path, file)
+}
+
+/// Read the file as if the process died without dropping the sink.
+fn read_without_drop(path: &PathBuf, sink: MmapReplayFileSink) -> Vec<u8> {
+    std::mem::forget(sink);
+    let data = std::fs::read(path).expect("failed to read temp file");
+    let _ = std::fs::remove_file(path);
+    data
+}
+
+#[test]
+fn truncated_bytes_are_zeroed() {
+    let (path, file) = open_temp_file("truncate");
+    let mut sink = MmapReplayFileSink::with_growth(file, 64).expect("failed to make sink");
+    sink.write_bytes(&[0xAA; 48]).expect("failed to write");
+    sink.truncate(16).expect("failed to truncate");
+    sink.write_bytes(&[0xBB; 8]).expect("failed to write");
+
+    let data = read_without_drop(&path, sink);
+    assert_eq!(&data[..16], &[0xAA; 16]);
+    assert_eq!(&data[16..24], &[0xBB; 8]);
+    assert!(data[24..].iter().all(|b| *b == 0), "old bytes were left after the end of the sink");
+}
+
+#[test]
+fn existing_contents_are_zeroed() {
+    let (path, file) = open_temp_file("existing");
+    std::fs::write(&path, [0xCC; 32]).expect("failed to write temp file");
+    let mut sink = MmapReplayFileSink::with_growth(file, 64).expect("failed to make sink");
+    sink.write_bytes(&[0xAA; 4]).expect("failed to write");
+
+    let data = read_without_drop(&path, sink);
+    assert_eq!(&data[..4], &[0xAA; 4]);
+    assert!(data[4..].iter().all(|b| *b == 0), "old contents were left in the preallocated space");
+}
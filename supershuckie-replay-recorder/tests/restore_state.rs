@@ -0,0 +1,127 @@
+use supershuckie_replay_recorder::replay_file::playback::ReplayFilePlayer;
+use supershuckie_replay_recorder::replay_file::record::{LoadSaveStateMode, NullReplayFileSink, ReplayFileRecorder, ReplayFileRecorderSettings};
+use supershuckie_replay_recorder::replay_file::{ReplayConsoleType, ReplayFileMetadata, ReplayPatchFormat};
+use supershuckie_replay_recorder::{blake3_hash, ByteVec, InputBuffer, Packet, Speed, UnsignedInteger};
+
+type Recorder = ReplayFileRecorder<Vec<u8>, NullReplayFileSink>;
+
+fn state(seed: u8) -> ByteVec {
+    (0..256u32).map(|i| (i as u8).wrapping_mul(seed).wrapping_add(seed)).collect::<Vec<u8>>().as_slice().into()
+}
+
+fn start_recording(load_save_state_mode: LoadSaveStateMode, minimum_uncompressed_bytes_per_blob: usize) -> Recorder {
+    let metadata = ReplayFileMetadata {
+        console_type: ReplayConsoleType::GameBoyColor,
+        rom_name: "RESTORE".to_owned(),
+        rom_filename: "restore.gbc".to_owned(),
+        rom_checksum: blake3_hash(b"restore rom"),
+        bios_checksum: blake3_hash(b"restore bios"),
+        emulator_core_name: "test".to_owned(),
+        patch_format: ReplayPatchFormat::Unpatched,
+        patch_target_checksum: [0u8; 32],
+        sram_checksum: [0u8; 32],
+        core_config: ByteVec::new()
+    };
+    let settings = ReplayFileRecorderSettings {
+        minimum_uncompressed_bytes_per_blob,
+        compression_level: 3,
+        load_save_state_mode
+    };
+
+    ReplayFileRecorder::new_with_metadata(
+        metadata,
+        ByteVec::new(),
+        settings,
+        0,
+        InputBuffer::from([0u8].as_slice()),
+        Speed::default(),
+        state(1),
+        Vec::new(),
+        NullReplayFileSink
+    ).expect("failed to start recording")
+}
+
+/// Advance `count` frames at 60 FPS.
+fn frames(recorder: &mut Recorder, elapsed_frames: &mut UnsignedInteger, count: UnsignedInteger) {
+    for _ in 0..count {
+        *elapsed_frames += 1;
+        recorder.next_frame(*elapsed_frames * 1000 / 60).expect("failed to record frame");
+    }
+}
+
+fn finish(mut recorder: Recorder) -> ReplayFilePlayer {
+    let (data, _) = recorder.close().unwrap_or_else(|(_, _, e)| panic!("failed to close recording: {e:?}"));
+    ReplayFilePlayer::new(data, false).expect("failed to read recording")
+}
+
+/// Play back the replay, returning the keyframe index and state of each RestoreState packet along
+/// with the frame it happened on.
+fn play_restores(player: &mut ReplayFilePlayer) -> (Vec<(UnsignedInteger, UnsignedInteger, ByteVec)>, UnsignedInteger) {
+    let mut restores = Vec::new();
+    let mut elapsed_frames = 0;
+    loop {
+        let packet = player.next_packet().expect("failed to read packet").cloned();
+        match packet {
+            Some(Packet::NextFrame { .. }) => elapsed_frames += 1,
+            Some(Packet::RestoreState { keyframe_index }) => {
+                // Looking up the state must not move playback
+                let position = player.get_position();
+                let restored = player.get_keyframe_state(keyframe_index).expect("failed to get keyframe state");
+                assert_eq!(player.get_position(), position, "getting the keyframe state moved playback");
+                restores.push((elapsed_frames, keyframe_index, restored));
+            },
+            Some(_) => {},
+            None => return (restores, elapsed_frames)
+        }
+    }
+}
+
+#[test]
+fn restore_state_plays_back() {
+    let mut recorder = start_recording(LoadSaveStateMode::Embed, 1024 * 1024);
+    let mut elapsed_frames = 0;
+
+    frames(&mut recorder, &mut elapsed_frames, 10);
+    recorder.insert_keyframe(state(2), elapsed_frames * 1000 / 60).expect("failed to insert keyframe");
+    frames(&mut recorder, &mut elapsed_frames, 10);
+    recorder.restore_state(0).expect("failed to restore keyframe 0");
+    frames(&mut recorder, &mut elapsed_frames, 5);
+    recorder.restore_state(1).expect("failed to restore keyframe 1");
+    frames(&mut recorder, &mut elapsed_frames, 5);
+    assert_eq!(recorder.total_keyframes(), 2);
+
+    let mut player = finish(recorder);
+    let (restores, total_frames) = play_restores(&mut player);
+    assert_eq!(restores, vec![(20, 0, state(1)), (25, 1, state(2))]);
+    assert_eq!(total_frames, 30);
+    assert_eq!(player.get_total_frames(), 30);
+}
+
+#[test]
+fn restore_state_plays_back_across_blobs() {
+    // Every keyframe starts a new blob, so restoring has to decompress an earlier one
+    let mut recorder = start_recording(LoadSaveStateMode::RestoreKeyframe, 1);
+    let mut elapsed_frames = 0;
+
+    frames(&mut recorder, &mut elapsed_frames, 10);
+    recorder.insert_keyframe(state(2), elapsed_frames * 1000 / 60).expect("failed to insert keyframe");
+    frames(&mut recorder, &mut elapsed_frames, 10);
+    recorder.insert_keyframe(state(3), elapsed_frames * 1000 / 60).expect("failed to insert keyframe");
+    frames(&mut recorder, &mut elapsed_frames, 10);
+
+    // Loading a save state matching an earlier keyframe records a RestoreState packet for it
+    recorder.load_save_state(state(2)).expect("failed to load save state");
+    frames(&mut recorder, &mut elapsed_frames, 3);
+
+    let mut player = finish(recorder);
+    let (restores, total_frames) = play_restores(&mut player);
+    assert_eq!(restores, vec![(30, 1, state(2))]);
+    assert_eq!(total_frames, 33);
+}
+
+#[test]
+fn restore_state_rejects_unrecorded_keyframes() {
+    let mut recorder = start_recording(LoadSaveStateMode::Embed, 1024 * 1024);
+    assert!(recorder.restore_state(1).is_err());
+    assert!(recorder.restore_state(0).is_ok());
+}
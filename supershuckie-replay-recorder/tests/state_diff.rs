@@ -0,0 +1,23 @@
+use supershuckie_replay_recorder::{apply_state_diff, create_state_diff, MAX_STATE_DIFF_TARGET_SIZE};
+
+#[test]
+fn diffs_reconstruct_the_target() {
+    let base: Vec<u8> = (0..64).collect();
+    let mut target = base.clone();
+    target[3] = 0xFF;
+    target[40..48].fill(0xAA);
+    target.extend_from_slice(&[1, 2, 3]);
+
+    let diff = create_state_diff(&base, &target);
+    assert_eq!(apply_state_diff(&base, &diff).expect("failed to apply diff"), target);
+    assert_eq!(apply_state_diff(&base, &create_state_diff(&base, &base[..16])).expect("failed to apply diff"), &base[..16]);
+}
+
+#[test]
+fn oversized_targets_are_rejected() {
+    let diff = create_state_diff(&[], &vec![0u8; MAX_STATE_DIFF_TARGET_SIZE + 1]);
+    assert!(apply_state_diff(&[], &diff).is_err());
+
+    let diff = create_state_diff(&[], &vec![0u8; MAX_STATE_DIFF_TARGET_SIZE]);
+    assert_eq!(apply_state_diff(&[], &diff).expect("failed to apply diff").len(), MAX_STATE_DIFF_TARGET_SIZE);
+}